@@ -0,0 +1,413 @@
+use crate::change_analyzer::{AnalyzedChange, ChangeAnalyzer};
+use crate::config::Config;
+use crate::llm;
+use crate::llm_providers::{get_provider_metadata, LLMProviderType};
+use crate::token_optimizer::TokenOptimizer;
+use anyhow::{anyhow, Context, Result};
+use git2::{Commit, DiffFindOptions, DiffOptions, Patch, Repository};
+use std::path::Path;
+
+/// Default number of commits to follow back through a line's history
+pub const DEFAULT_MAX_COMMITS: usize = 10;
+
+/// A commit that touched the tracked lines, with the hunks that touched them
+pub struct LineChange {
+    pub change: AnalyzedChange,
+    /// Path of the file in this commit, which differs from the current path after a rename
+    pub path: String,
+    pub hunks: String,
+}
+
+/// Parse a `<file>:<line>` or `<file>:<start>-<end>` target into a path and a 1-based line range
+pub fn parse_line_target(target: &str) -> Result<(String, usize, usize)> {
+    let (path, range) = target.rsplit_once(':').ok_or_else(|| {
+        anyhow!(
+            "Expected <file>:<line> or <file>:<start>-<end>, got '{}'",
+            target
+        )
+    })?;
+    if path.is_empty() {
+        return Err(anyhow!("Missing file in '{}'", target));
+    }
+
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|line| *line > 0)
+            .ok_or_else(|| anyhow!("Invalid line number '{}' in '{}'", value, target))
+    };
+    let (start, end) = match range.split_once('-') {
+        Some((start, end)) => (parse(start)?, parse(end)?),
+        None => {
+            let line = parse(range)?;
+            (line, line)
+        }
+    };
+    if start > end {
+        return Err(anyhow!(
+            "Invalid line range in '{}': {} comes after {}",
+            target,
+            start,
+            end
+        ));
+    }
+
+    Ok((path.trim_start_matches("./").to_string(), start, end))
+}
+
+/// Read the given 1-based line range of a file as of HEAD
+pub fn read_lines_at_head(
+    repo_path: &Path,
+    path: &str,
+    start: usize,
+    end: usize,
+) -> Result<String> {
+    let repo = Repository::open(repo_path)?;
+    let tree = repo.head()?.peel_to_tree()?;
+    let entry = tree
+        .get_path(Path::new(path))
+        .map_err(|_| anyhow!("'{}' is not tracked at HEAD", path))?;
+    let blob = repo.find_blob(entry.id())?;
+    if blob.is_binary() {
+        return Err(anyhow!("'{}' is a binary file", path));
+    }
+
+    let content = String::from_utf8_lossy(blob.content());
+    let line_count = content.lines().count();
+    if end > line_count {
+        return Err(anyhow!(
+            "Line {} is past the end of '{}', which has {} lines at HEAD",
+            end,
+            path,
+            line_count
+        ));
+    }
+
+    Ok(content
+        .lines()
+        .skip(start - 1)
+        .take(end - start + 1)
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Follow a range of lines back through the first-parent history of HEAD, like `git log -L`
+///
+/// Returns the commits that touched the lines, newest first, following renames and stopping
+/// at the commit that introduced the lines or after `max_commits` commits.
+pub fn get_line_history(
+    repo_path: &Path,
+    path: &str,
+    start: usize,
+    end: usize,
+    max_commits: usize,
+) -> Result<Vec<LineChange>> {
+    let repo = Repository::open(repo_path)?;
+    let analyzer = ChangeAnalyzer::new(&repo);
+
+    let mut commit = repo.head()?.peel_to_commit()?;
+    let mut path = path.to_string();
+    let (mut start, mut end) = (start as i64, end as i64);
+    let mut history = Vec::new();
+
+    while history.len() < max_commits {
+        let parent = commit.parent(0).ok();
+        if !changes_path(&commit, parent.as_ref(), &path)? {
+            match parent {
+                Some(parent) => {
+                    commit = parent;
+                    continue;
+                }
+                None => break,
+            }
+        }
+
+        let Some(file) = diff_file(&repo, &commit, parent.as_ref(), &path)? else {
+            break;
+        };
+
+        let mut hunks = String::new();
+        for hunk in &file.hunks {
+            if hunk.touches(start, end) {
+                hunks.push_str(&hunk.text);
+            }
+        }
+        let old_start = map_line(&file.hunks, start, true);
+        let old_end = map_line(&file.hunks, end, false);
+
+        if !hunks.is_empty() {
+            history.push(LineChange {
+                change: analyzer.analyze_commit(&commit)?,
+                path: path.clone(),
+                hunks,
+            });
+        }
+
+        // The lines did not exist before this commit, so their history starts here
+        let Some(old_path) = file.old_path else {
+            break;
+        };
+        if old_start > old_end {
+            break;
+        }
+        let Some(parent) = parent else {
+            break;
+        };
+
+        path = old_path;
+        start = old_start;
+        end = old_end;
+        commit = parent;
+    }
+
+    Ok(history)
+}
+
+/// A file's changes in one commit, as hunks with their line ranges
+struct FileDiff {
+    /// Path before the commit, or `None` when the commit added the file
+    old_path: Option<String>,
+    hunks: Vec<Hunk>,
+}
+
+struct Hunk {
+    old_begin: i64,
+    old_end: i64,
+    new_begin: i64,
+    new_end: i64,
+    text: String,
+}
+
+impl Hunk {
+    fn new(old_start: u32, old_lines: u32, new_start: u32, new_lines: u32, text: String) -> Self {
+        // Git reports the line before an empty range, so move it to where the lines would be
+        let old_begin = i64::from(old_start) + i64::from(old_lines == 0);
+        let new_begin = i64::from(new_start) + i64::from(new_lines == 0);
+        Self {
+            old_begin,
+            old_end: old_begin + i64::from(old_lines),
+            new_begin,
+            new_end: new_begin + i64::from(new_lines),
+            text,
+        }
+    }
+
+    /// Whether the hunk changes any line in the inclusive range, or removes lines from inside it
+    fn touches(&self, start: i64, end: i64) -> bool {
+        if self.new_begin == self.new_end {
+            start < self.new_begin && self.new_begin <= end
+        } else {
+            self.new_begin <= end && start < self.new_end
+        }
+    }
+}
+
+/// Map a line number after the hunks were applied to the line number before them
+///
+/// Lines inside a hunk map to its first old line when `is_start` is set, or to its last otherwise,
+/// so a range of added lines maps to an empty range.
+fn map_line(hunks: &[Hunk], line: i64, is_start: bool) -> i64 {
+    let mut offset = 0;
+    for hunk in hunks {
+        if line < hunk.new_begin {
+            break;
+        }
+        if line >= hunk.new_end {
+            offset = hunk.old_end - hunk.new_end;
+            continue;
+        }
+        return if is_start {
+            hunk.old_begin
+        } else {
+            hunk.old_end - 1
+        };
+    }
+    line + offset
+}
+
+/// Check whether a commit changes the file at `path`, without computing a diff
+fn changes_path(commit: &Commit, parent: Option<&Commit>, path: &str) -> Result<bool> {
+    let blob_id = |commit: &Commit| -> Result<Option<git2::Oid>> {
+        Ok(commit.tree()?.get_path(Path::new(path)).ok().map(|entry| entry.id()))
+    };
+    let parent_id = match parent {
+        Some(parent) => blob_id(parent)?,
+        None => None,
+    };
+    Ok(blob_id(commit)? != parent_id)
+}
+
+/// Diff the file at `path` against the commit's parent, detecting renames
+fn diff_file(
+    repo: &Repository,
+    commit: &Commit,
+    parent: Option<&Commit>,
+    path: &str,
+) -> Result<Option<FileDiff>> {
+    let parent_tree = parent.map(|parent| parent.tree()).transpose()?;
+    // Without context lines, hunks only cover the lines that actually changed
+    let mut options = DiffOptions::new();
+    options.context_lines(0);
+    let mut diff =
+        repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), Some(&mut options))?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+    for (index, delta) in diff.deltas().enumerate() {
+        if delta.new_file().path() != Some(Path::new(path)) {
+            continue;
+        }
+        let old_path = match delta.status() {
+            git2::Delta::Added => None,
+            _ => delta
+                .old_file()
+                .path()
+                .map(|path| path.to_string_lossy().to_string()),
+        };
+
+        let mut hunks = Vec::new();
+        if let Some(patch) = Patch::from_diff(&diff, index)? {
+            for hunk_index in 0..patch.num_hunks() {
+                let (hunk, line_count) = patch.hunk(hunk_index)?;
+                let mut text = String::from_utf8_lossy(hunk.header()).to_string();
+                for line_index in 0..line_count {
+                    let line = patch.line_in_hunk(hunk_index, line_index)?;
+                    if matches!(line.origin(), '+' | '-' | ' ') {
+                        text.push(line.origin());
+                    }
+                    text.push_str(&String::from_utf8_lossy(line.content()));
+                }
+                hunks.push(Hunk::new(
+                    hunk.old_start(),
+                    hunk.old_lines(),
+                    hunk.new_start(),
+                    hunk.new_lines(),
+                    text,
+                ));
+            }
+        }
+        return Ok(Some(FileDiff { old_path, hunks }));
+    }
+
+    Ok(None)
+}
+
+pub struct BlameExplainer;
+
+impl BlameExplainer {
+    /// Explain why a range of lines looks the way it does and how it evolved
+    pub async fn explain(
+        repo_path: &Path,
+        path: &str,
+        start: usize,
+        end: usize,
+        max_commits: usize,
+        config: &Config,
+    ) -> Result<String> {
+        let code = read_lines_at_head(repo_path, path, start, end)?;
+        let history = get_line_history(repo_path, path, start, end, max_commits)?;
+        if history.is_empty() {
+            return Err(anyhow!("No commits found for {}:{}-{}", path, start, end));
+        }
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let mut system_prompt = create_blame_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        // Keep long histories within the provider's context window
+        let token_limit = config
+            .providers
+            .get(&config.default_provider)
+            .and_then(|provider| provider.token_limit)
+            .unwrap_or_else(|| get_provider_metadata(&provider_type).default_token_limit);
+        let optimizer = TokenOptimizer::new(token_limit);
+        let per_commit_limit = (token_limit / 2) / history.len();
+        let history: Vec<LineChange> = history
+            .into_iter()
+            .map(|mut entry| {
+                entry.hunks = optimizer.truncate_string(&entry.hunks, per_commit_limit.max(1));
+                entry
+            })
+            .collect();
+
+        let user_prompt = create_blame_user_prompt(path, start, end, &code, &history);
+
+        llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+            .await
+            .context("Failed to generate line history explanation")
+    }
+}
+
+pub fn create_blame_system_prompt() -> String {
+    String::from(
+        "You are an AI assistant specialized in explaining the history of code to developers who are unfamiliar with it. \
+        Your task is to explain why a piece of code looks the way it does, based on the commits that shaped it. \
+        Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Write the explanation in Markdown with exactly these sections: '## Why it looks this way', '## How it evolved', and '## Things to keep in mind'.
+        2. In 'Why it looks this way', explain the purpose of the current code and the decisions behind it in a short paragraph.
+        3. In 'How it evolved', list the commits oldest first as bullet points, each with its short hash and one sentence on what it changed in these lines and why.
+        4. In 'Things to keep in mind', point out constraints, workarounds, or past bugs a developer should know before changing the code. Leave the section out if there are none.
+        5. Base the reasoning on the commit messages and diffs, and clearly mark any reasoning about intent as likely, never as certain.
+        6. Focus on the tracked lines; mention other parts of a commit only when they explain the change to these lines.
+        7. Do not restate the diffs line by line.
+        8. Avoid common cliché words (like 'enhance', 'streamline', 'leverage', etc) and phrases.
+        9. NO YAPPING!
+
+        Generate only the explanation, without any introduction or closing remarks."
+    )
+}
+
+pub fn create_blame_user_prompt(
+    path: &str,
+    start: usize,
+    end: usize,
+    code: &str,
+    history: &[LineChange],
+) -> String {
+    let mut prompt = format!(
+        "Based on the following history, explain why lines {}-{} of {} look the way they do and how they evolved:\n\n\
+        Current code:\n{}\n\n\
+        Commits that touched these lines, oldest first:\n\n",
+        start, end, path, code
+    );
+
+    for entry in history.iter().rev() {
+        let change = &entry.change;
+        prompt.push_str(&format!(
+            "Commit: {}\nAuthor: {}\nFile: {}\nMessage: {}\n",
+            &change.commit_hash[..change.commit_hash.len().min(7)],
+            change.author,
+            entry.path,
+            change.commit_message.trim()
+        ));
+        let analysis: Vec<&String> = change
+            .file_changes
+            .iter()
+            .filter(|file| file.new_path == entry.path)
+            .flat_map(|file| file.analysis.iter())
+            .collect();
+        if !analysis.is_empty() {
+            prompt.push_str("Analysis:\n");
+            for line in analysis {
+                prompt.push_str(&format!("- {}\n", line));
+            }
+        }
+        prompt.push_str(&format!("Changes to these lines:\n{}\n---\n\n", entry.hunks));
+    }
+
+    prompt
+}
@@ -0,0 +1,155 @@
+use crate::config::Config;
+use crate::context::CommitContext;
+use crate::git;
+use crate::llm;
+use crate::llm_providers::LLMProviderType;
+use anyhow::{anyhow, Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::Path;
+
+/// Number of branch names to suggest
+pub const SUGGESTION_COUNT: usize = 3;
+
+lazy_static! {
+    // Numbering or bullets the model may put in front of each suggestion
+    static ref LIST_MARKER: Regex = Regex::new(r"^(\d+[.)]|[-*])\s+").unwrap();
+}
+
+pub struct BranchNameGenerator;
+
+impl BranchNameGenerator {
+    /// Suggest branch names for the pending changes and an optional issue description
+    pub async fn generate(
+        repo_path: &Path,
+        issue: Option<&str>,
+        config: &Config,
+    ) -> Result<Vec<String>> {
+        let context = git::get_git_info(repo_path, config)?;
+        if context.staged_files.is_empty() && context.unstaged_files.is_empty() && issue.is_none() {
+            return Err(anyhow!(
+                "No pending changes found. Make some changes or describe the work with --issue"
+            ));
+        }
+
+        let mut system_prompt = create_branch_name_system_prompt(&config.branch_name_pattern);
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let user_prompt = create_branch_name_user_prompt(&context, issue);
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let response =
+            llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+                .await
+                .context("Failed to generate branch names")?;
+
+        let names = parse_branch_names(&response);
+        if names.is_empty() {
+            return Err(anyhow!("The model did not suggest any valid branch names"));
+        }
+        Ok(names)
+    }
+}
+
+pub fn create_branch_name_system_prompt(pattern: &str) -> String {
+    format!(
+        "You are an AI assistant specialized in naming Git branches. \
+        Your task is to suggest branch names that describe the pending work, based on the provided changes and issue description.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Suggest exactly {} branch names, one per line, best first.
+        2. Follow this pattern exactly: {}
+        3. Replace {{type}} with one of: feat, fix, docs, style, refactor, perf, test, build, ci, chore.
+        4. Replace {{description}} with two to five lowercase words in kebab-case describing the work.
+        5. Replace {{issue}} with the issue number from the issue description; drop it, and the separator next to it, if there is no number.
+        6. Use only lowercase letters, digits, '-', and '/'.
+        7. Keep each name under 50 characters.
+        8. Make the suggestions meaningfully different from each other.
+        9. NO YAPPING!
+
+        Generate only the branch names, without numbering, explanations, quotes, or code fences.",
+        SUGGESTION_COUNT, pattern
+    )
+}
+
+pub fn create_branch_name_user_prompt(context: &CommitContext, issue: Option<&str>) -> String {
+    let mut prompt = String::from("Based on the following pending work, suggest branch names:\n\n");
+
+    if let Some(issue) = issue {
+        prompt.push_str(&format!("Issue description:\n{}\n\n", issue.trim()));
+    }
+
+    if !context.staged_files.is_empty() {
+        prompt.push_str("Staged changes:\n");
+        for file in &context.staged_files {
+            prompt.push_str(&format!("- {} ({})\n", file.path, file.change_type));
+            for analysis in &file.analysis {
+                prompt.push_str(&format!("  - {}\n", analysis));
+            }
+        }
+        prompt.push('\n');
+    }
+
+    if !context.unstaged_files.is_empty() {
+        prompt.push_str(&format!(
+            "Unstaged files:\n{}\n",
+            context.unstaged_files.join(", ")
+        ));
+    }
+
+    prompt
+}
+
+/// Turn the model response into a list of valid, kebab-case branch names
+pub fn parse_branch_names(response: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for line in response.lines() {
+        let line = LIST_MARKER
+            .replace(line.trim(), "")
+            .trim()
+            .trim_matches(['`', '"', '\''])
+            .to_string();
+        if line.is_empty() || line.starts_with("```") {
+            continue;
+        }
+
+        let name = to_branch_name(&line);
+        if !name.is_empty()
+            && git2::Branch::name_is_valid(&name).unwrap_or(false)
+            && !names.contains(&name)
+        {
+            names.push(name);
+        }
+    }
+    names.truncate(SUGGESTION_COUNT);
+    names
+}
+
+/// Lowercase a name and replace anything other than letters, digits, and '/' with single dashes
+fn to_branch_name(name: &str) -> String {
+    let mut result = String::new();
+    for c in name.to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() || c == '/' {
+            result.push(c);
+        } else if !result.ends_with('-') && !result.ends_with('/') && !result.is_empty() {
+            result.push('-');
+        }
+    }
+    result
+        .split('/')
+        .map(|part| part.trim_matches('-'))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
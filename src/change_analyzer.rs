@@ -1,15 +1,27 @@
-use crate::context::{ChangeType, StagedFile};
-use crate::file_analyzers::get_analyzer;
+use crate::context::{ChangeType, StageStatus, StagedFile};
+use crate::exclusions::PromptFilter;
+use crate::file_analyzers::{analyze_contents, analyzes_contents, get_analyzer, is_lockfile};
+use crate::risk::{detect_risks, RiskLabel};
 use anyhow::Result;
 use git2::{Commit, DiffDelta, Repository};
 
 pub struct ChangeAnalyzer<'a> {
     repo: &'a Repository,
+    filter: PromptFilter,
 }
 
 impl<'a> ChangeAnalyzer<'a> {
     pub fn new(repo: &'a Repository) -> Self {
-        Self { repo }
+        Self {
+            repo,
+            filter: PromptFilter::default(),
+        }
+    }
+
+    /// Leave out the analysis of files the filter excludes from prompts
+    pub fn with_filter(mut self, filter: &PromptFilter) -> Self {
+        self.filter = filter.clone();
+        self
     }
 
     pub fn analyze_commit(&self, commit: &Commit) -> Result<AnalyzedChange> {
@@ -57,24 +69,52 @@ impl<'a> ChangeAnalyzer<'a> {
         };
 
         let analyzer = get_analyzer(new_file.to_str()?);
+        let content_excluded = self.filter.is_excluded(new_file.to_str()?);
         let staged_file = StagedFile {
             path: new_file.to_str()?.to_string(),
             change_type: change_type.clone(),
-            diff: self.get_file_diff(delta).unwrap_or_default(),
+            diff: if content_excluded {
+                String::new()
+            } else {
+                self.get_file_diff(delta).unwrap_or_default()
+            },
             analysis: Vec::new(),
-            content_excluded: false,
+            content_excluded,
+            stage_status: StageStatus::Staged,
         };
 
-        let analysis = analyzer.analyze(new_file.to_str()?, &staged_file);
+        // Lockfiles are still summarized, as they are in staged changes
+        let analyzed = !content_excluded || is_lockfile(&staged_file.path);
+        let analysis = if !analyzed {
+            vec!["[Analysis excluded]".to_string()]
+        } else if analyzes_contents(&staged_file.path) {
+            let old = self.blob_text(delta.old_file().id());
+            let new = self.blob_text(delta.new_file().id());
+            analyze_contents(&staged_file, old.as_deref(), new.as_deref())
+        } else {
+            analyzer.analyze(new_file.to_str()?, &staged_file)
+        };
+        // Excluded files don't flag risks either, so they can't surface in changelogs or bumps
+        let risk_labels = if analyzed {
+            detect_risks(&staged_file.path, &staged_file.diff, &analysis)
+        } else {
+            Vec::new()
+        };
 
         Some(FileChange {
             old_path: old_file.to_str()?.to_string(),
             new_path: new_file.to_str()?.to_string(),
             change_type,
             analysis,
+            risk_labels,
         })
     }
 
+    fn blob_text(&self, id: git2::Oid) -> Option<String> {
+        let blob = self.repo.find_blob(id).ok()?;
+        String::from_utf8(blob.content().to_vec()).ok()
+    }
+
     fn get_file_diff(&self, delta: &DiffDelta) -> Result<String> {
         let mut diff_content = String::new();
         let old_file = delta.old_file();
@@ -152,4 +192,5 @@ pub struct FileChange {
     pub new_path: String,
     pub change_type: ChangeType,
     pub analysis: Vec<String>,
+    pub risk_labels: Vec<RiskLabel>,
 }
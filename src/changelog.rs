@@ -1,12 +1,19 @@
+use crate::change_analyzer::AnalyzedChange;
 use crate::changelog_prompts;
 use crate::changelog_prompts::create_release_notes_user_prompt;
 use crate::config::Config;
+use crate::exclusions::PromptFilter;
 use crate::git;
 use crate::llm;
 use crate::llm_providers::LLMProviderType;
 use crate::readme_reader::{find_and_read_readme, summarize_readme};
+use crate::risk::enabled_risk_labels;
+use crate::session::{session_key, ReleaseNotesSession};
+use crate::ui;
+use crate::workspace::{format_workspace_impact, WorkspaceGraph};
 use anyhow::{Context, Result};
 use std::path::Path;
+use std::str::FromStr;
 
 pub struct ChangelogGenerator;
 
@@ -18,16 +25,29 @@ impl ChangelogGenerator {
         config: &Config,
         detail_level: DetailLevel,
     ) -> Result<String> {
-        let analyzed_changes = git::get_commits_between(repo_path, from, to)?;
+        let readme_summary = generate_readme_summary(repo_path, config).await?;
+        Self::generate_with_summary(
+            repo_path,
+            from,
+            to,
+            config,
+            detail_level,
+            readme_summary.as_deref(),
+        )
+        .await
+    }
 
-        // Find and summarize README
-        let readme_content = find_and_read_readme(repo_path)?;
-        let readme_summary = if let Some(content) = readme_content {
-            let provider_type: LLMProviderType = config.default_provider.parse()?;
-            Some(summarize_readme(config, &provider_type, &content).await?)
-        } else {
-            None
-        };
+    /// Generate a changelog using an already summarized README
+    pub async fn generate_with_summary(
+        repo_path: &Path,
+        from: &str,
+        to: &str,
+        config: &Config,
+        detail_level: DetailLevel,
+        readme_summary: Option<&str>,
+    ) -> Result<String> {
+        let mut analyzed_changes = analyze_commits(repo_path, from, to, config)?;
+        retain_enabled_risks(repo_path, config, &mut analyzed_changes);
 
         let mut system_prompt = changelog_prompts::create_changelog_system_prompt(config);
         let effective_instructions = config.get_effective_instructions();
@@ -38,23 +58,27 @@ impl ChangelogGenerator {
             ));
         }
 
-        let user_prompt = changelog_prompts::create_changelog_user_prompt(
+        let mut user_prompt = changelog_prompts::create_changelog_user_prompt(
             &analyzed_changes,
             detail_level,
             from,
             to,
-            readme_summary.as_deref(),
-        );
+            readme_summary,
+        )?;
+        append_workspace_impact(&mut user_prompt, repo_path, &analyzed_changes);
 
         let provider_type: LLMProviderType = config
             .default_provider
             .parse()
             .context("Failed to parse default provider")?;
 
+        let spinner = ui::create_spinner("Writing the changelog...");
         let changelog =
             llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
                 .await
-                .context("Failed to generate changelog")?;
+                .context("Failed to generate changelog");
+        spinner.finish_and_clear();
+        let changelog = changelog?;
 
         Ok(changelog)
     }
@@ -70,16 +94,44 @@ impl ReleaseNotesGenerator {
         config: &Config,
         detail_level: DetailLevel,
     ) -> Result<String> {
-        let changelog =
-            ChangelogGenerator::generate(repo_path, from, to, config, detail_level).await?;
-
-        // Find and summarize README
-        let readme_content = find_and_read_readme(repo_path)?;
-        let readme_summary = if let Some(content) = readme_content {
-            let provider_type: LLMProviderType = config.default_provider.parse()?;
-            Some(summarize_readme(config, &provider_type, &content).await?)
-        } else {
-            None
+        // Save intermediate results so an interrupted run can be resumed
+        let from_id = git::resolve_commit_id(repo_path, from)?;
+        let to_id = git::resolve_commit_id(repo_path, to)?;
+        let detail = format!("{:?}", detail_level);
+        let instructions = config.get_effective_instructions();
+        let use_gitmoji = config.use_gitmoji.to_string();
+        let key = session_key(&[
+            &from_id,
+            &to_id,
+            &detail,
+            &config.default_provider,
+            &instructions,
+            &use_gitmoji,
+        ]);
+        let mut session = ReleaseNotesSession::load_or_new(repo_path, &key);
+
+        if session.readme_summary.is_none() {
+            session.readme_summary = generate_readme_summary(repo_path, config).await?;
+            session.save(repo_path)?;
+        }
+        let readme_summary = session.readme_summary.clone();
+
+        let changelog = match session.changelog.clone() {
+            Some(changelog) => changelog,
+            None => {
+                let changelog = ChangelogGenerator::generate_with_summary(
+                    repo_path,
+                    from,
+                    to,
+                    config,
+                    detail_level,
+                    readme_summary.as_deref(),
+                )
+                .await?;
+                session.changelog = Some(changelog.clone());
+                session.save(repo_path)?;
+                changelog
+            }
         };
 
         let mut system_prompt = changelog_prompts::create_release_notes_system_prompt(config);
@@ -91,28 +143,157 @@ impl ReleaseNotesGenerator {
             ));
         }
 
-        let user_prompt = create_release_notes_user_prompt(
+        let mut user_prompt = create_release_notes_user_prompt(
             &changelog,
             detail_level,
             from,
             to,
             readme_summary.as_deref(),
-        );
+        )?;
+        let analyzed_changes = analyze_commits(repo_path, from, to, config)?;
+        append_workspace_impact(&mut user_prompt, repo_path, &analyzed_changes);
 
         let provider_type: LLMProviderType = config
             .default_provider
             .parse()
             .context("Failed to parse default provider")?;
 
+        let spinner = ui::create_spinner("Writing the release notes...");
         let release_notes =
             llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
                 .await
-                .context("Failed to generate release notes summary")?;
+                .context("Failed to generate release notes summary");
+        spinner.finish_and_clear();
+        let release_notes = release_notes?;
+
+        session.clear(repo_path)?;
 
         Ok(release_notes)
     }
 }
 
+pub struct TagMessageGenerator;
+
+impl TagMessageGenerator {
+    /// Generate an annotated tag message summarizing the changes from `from` to `to`
+    pub async fn generate(
+        repo_path: &Path,
+        tag_name: &str,
+        from: &str,
+        to: &str,
+        config: &Config,
+    ) -> Result<String> {
+        let analyzed_changes = analyze_commits(repo_path, from, to, config)?;
+
+        let mut system_prompt = changelog_prompts::create_tag_message_system_prompt(config);
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        // Reuse the changelog prompt at minimal detail to keep the tag message condensed
+        let mut user_prompt = changelog_prompts::create_changelog_user_prompt(
+            &analyzed_changes,
+            DetailLevel::Minimal,
+            from,
+            to,
+            None,
+        )?;
+        user_prompt.push_str(&format!(
+            "\n\nWrite the annotated tag message for tag '{}' instead of a full changelog.",
+            tag_name
+        ));
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let spinner = ui::create_spinner(&format!("Writing the tag message for {}...", tag_name));
+        let tag_message =
+            llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+                .await
+                .context("Failed to generate tag message");
+        spinner.finish_and_clear();
+        let tag_message = tag_message?;
+
+        Ok(tag_message.trim().to_string())
+    }
+}
+
+/// Analyze each commit in the range, showing progress since long ranges take a while
+fn analyze_commits(
+    repo_path: &Path,
+    from: &str,
+    to: &str,
+    config: &Config,
+) -> Result<Vec<AnalyzedChange>> {
+    let progress = ui::create_progress_bar(0, "Analyzing commits");
+    let filter = PromptFilter::from_config(config);
+    let analyzed_changes =
+        git::get_commits_between_with_progress(repo_path, from, to, &filter, |done, total| {
+            progress.set_length(total as u64);
+            progress.set_position(done as u64);
+        });
+    progress.finish_and_clear();
+    analyzed_changes
+}
+
+/// Find and summarize the README, if the repository has one
+async fn generate_readme_summary(repo_path: &Path, config: &Config) -> Result<Option<String>> {
+    match find_and_read_readme(repo_path)? {
+        Some(content) => {
+            let provider_type: LLMProviderType = config.default_provider.parse()?;
+            let spinner = ui::create_spinner("Summarizing the README...");
+            let summary = summarize_readme(config, &provider_type, &content).await;
+            spinner.finish_and_clear();
+            Ok(Some(summary?))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Only surface the risk labels enabled for this repository
+pub(crate) fn retain_enabled_risks(
+    repo_path: &Path,
+    config: &Config,
+    changes: &mut [AnalyzedChange],
+) {
+    let enabled_risks = enabled_risk_labels(repo_path, config);
+    for change in changes {
+        for file_change in &mut change.file_changes {
+            file_change
+                .risk_labels
+                .retain(|label| enabled_risks.contains(label));
+        }
+    }
+}
+
+/// Append the reverse-dependency impact of the changed workspace members to a prompt
+pub(crate) fn append_workspace_impact(prompt: &mut String, repo_path: &Path, changes: &[AnalyzedChange]) {
+    let Some(graph) = WorkspaceGraph::load(repo_path) else {
+        return;
+    };
+
+    let impact = graph.impact_of(changes.iter().flat_map(|change| {
+        change
+            .file_changes
+            .iter()
+            .map(|file_change| file_change.new_path.as_str())
+    }));
+
+    if !impact.is_empty() {
+        prompt.push_str(&format!(
+            "\n\nWorkspace impact (changed members and the members that depend on them):\n{}\n\
+            Describe changes to members with dependents together with the members they affect.",
+            format_workspace_impact(&impact)
+        ));
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum DetailLevel {
     Minimal,
@@ -131,6 +312,39 @@ impl DetailLevel {
     }
 }
 
+/// How a generated changelog or release notes are written out
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum OutputFormat {
+    Markdown,
+    /// An object with the range and the Markdown text, for scripts and CI pipelines
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(anyhow::anyhow!("Invalid output format: {}", s)),
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Render generated text covering `from..to` in this format
+    pub fn render(self, text: &str, from: &str, to: &str) -> Result<String> {
+        match self {
+            OutputFormat::Markdown => Ok(format!("{}\n", text.trim_end())),
+            OutputFormat::Json => {
+                let value = serde_json::json!({ "from": from, "to": to, "content": text });
+                Ok(format!("{}\n", serde_json::to_string_pretty(&value)?))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,6 +468,26 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_tag_message_generation() -> Result<()> {
+        let (temp_dir, _repo) = setup_test_repo()?;
+        let mut config = Config::default();
+        config.default_provider = "test".to_string();
+
+        let previous_tag = git::get_latest_tag(temp_dir.path(), "v1.1.0~1")?;
+        assert_eq!(previous_tag.as_deref(), Some("v1.0.0"));
+
+        let tag_message =
+            TagMessageGenerator::generate(temp_dir.path(), "v1.1.0", "v1.0.0", "v1.1.0", &config)
+                .await?;
+
+        assert!(tag_message.contains("annotated Git tag messages"));
+        assert!(tag_message.contains("tag 'v1.1.0'"));
+        assert!(tag_message.contains("Add file1.txt"));
+
+        Ok(())
+    }
+
     #[test]
     fn test_detail_level_from_str() {
         assert_eq!(
@@ -2,12 +2,22 @@ use crate::change_analyzer::{AnalyzedChange, ChangeMetrics};
 use crate::changelog::DetailLevel;
 use crate::config::Config;
 use crate::gitmoji::get_gitmoji_list;
+use crate::templates;
+use anyhow::Result;
+use serde_json::{json, Value};
 
 pub fn create_changelog_system_prompt(config: &Config) -> String {
+    if let Some(prompt) = templates::render_override_or_warn(
+        templates::CHANGELOG_SYSTEM,
+        &system_template_variables(config),
+    ) {
+        return prompt;
+    }
+
     let use_emoji = config.use_gitmoji;
     let instructions = &config.instructions;
 
-    let mut prompt = String::from(
+    let mut prompt = format!(
         "You are an AI assistant specialized in generating clear, concise, and informative changelogs for software projects. \
         Your task is to create a well-structured changelog based on the provided commit information and analysis. \
         Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
@@ -15,7 +25,7 @@ pub fn create_changelog_system_prompt(config: &Config) -> String {
         Work step-by-step and follow these guidelines exactly:
 
         1. Focus on the impact and significance of the changes in addition to technical details.
-        2. Use the present tense and imperative mood.
+        2. {}
         3. Group changes by type (e.g., 'Features', 'Bug Fixes', 'Performance Improvements', 'Refactoring').
         4. For each entry, include the commit hash at the end in parentheses.
         5. Ensure the changelog is well-structured and easy to read.
@@ -30,8 +40,10 @@ pub fn create_changelog_system_prompt(config: &Config) -> String {
         14. Mention any changes to project dependencies or build configurations.
         15. Highlight changes that affect multiple parts of the codebase or have cross-cutting concerns.
         16. Include a summary of the overall metrics (total commits, files changed, lines added/deleted) at the beginning of the changelog.
-        17. Never include a conclusion or final summary statement.
-        18. NO YAPPING!"
+        17. If any changes carry risk labels, add a 'Risk Notes' section after the grouped changes that lists each risky change and what upgraders should watch for.
+        18. Never include a conclusion or final summary statement.
+        19. NO YAPPING!",
+        config.language().entry_rule()
     );
 
     if use_emoji {
@@ -63,13 +75,48 @@ pub fn create_changelog_user_prompt(
     from: &str,
     to: &str,
     readme_summary: Option<&str>,
-) -> String {
+) -> Result<String> {
+    let total_metrics = calculate_total_metrics(changes);
+    let variables = json!({
+        "from": from,
+        "to": to,
+        "detail_level": detail_level_name(detail_level),
+        "readme_summary": readme_summary,
+        "total_commits": changes.len(),
+        "files_changed": total_metrics.files_changed,
+        "total_lines_changed": total_metrics.total_lines_changed,
+        "insertions": total_metrics.insertions,
+        "deletions": total_metrics.deletions,
+        "has_risks": changes.iter().any(|change| !format_risk_labels(change).is_empty()),
+        "changes": changes
+            .iter()
+            .map(|change| {
+                let mut details = String::new();
+                push_change_details(&mut details, change, detail_level);
+                details
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        "commits": changes.iter().map(|change| json!({
+            "hash": change.commit_hash,
+            "author": change.author,
+            "message": change.commit_message,
+            "impact_score": change.impact_score,
+            "files_changed": change.metrics.files_changed,
+            "insertions": change.metrics.insertions,
+            "deletions": change.metrics.deletions,
+            "risk_labels": format_risk_labels(change),
+        })).collect::<Vec<_>>(),
+    });
+    if let Some(prompt) = templates::render_override(templates::CHANGELOG_USER, &variables)? {
+        return Ok(prompt);
+    }
+
     let mut prompt = String::from(format!(
         "Based on the following changes from {} to {}, generate a changelog:\n\n",
         from, to
     ));
 
-    let total_metrics = calculate_total_metrics(changes);
     prompt.push_str(&format!("Overall Changes:\n"));
     prompt.push_str(&format!("Total commits: {}\n", changes.len()));
     prompt.push_str(&format!("Files changed: {}\n", total_metrics.files_changed));
@@ -82,48 +129,7 @@ pub fn create_changelog_user_prompt(
     prompt.push_str("\n");
 
     for change in changes {
-        prompt.push_str(&format!("Commit: {}\n", change.commit_hash));
-        prompt.push_str(&format!("Author: {}\n", change.author));
-        prompt.push_str(&format!("Message: {}\n", change.commit_message));
-        prompt.push_str(&format!(
-            "Files changed: {}\n",
-            change.metrics.files_changed
-        ));
-        prompt.push_str(&format!(
-            "Lines changed: {}\n",
-            change.metrics.total_lines_changed
-        ));
-        prompt.push_str(&format!("Insertions: {}\n", change.metrics.insertions));
-        prompt.push_str(&format!("Deletions: {}\n", change.metrics.deletions));
-        prompt.push_str(&format!("Impact score: {:.2}\n", change.impact_score));
-
-        match detail_level {
-            DetailLevel::Minimal => {
-                // For minimal detail, we don't include file-level changes
-            }
-            DetailLevel::Standard => {
-                prompt.push_str("File changes summary:\n");
-                for file_change in &change.file_changes {
-                    prompt.push_str(&format!(
-                        "  - {} ({})\n",
-                        file_change.new_path, file_change.change_type
-                    ));
-                }
-            }
-            DetailLevel::Detailed => {
-                prompt.push_str("Detailed file changes:\n");
-                for file_change in &change.file_changes {
-                    prompt.push_str(&format!(
-                        "  - {} ({})\n",
-                        file_change.new_path, file_change.change_type
-                    ));
-                    for analysis in &file_change.analysis {
-                        prompt.push_str(&format!("    * {}\n", analysis));
-                    }
-                }
-            }
-        }
-
+        push_change_details(&mut prompt, change, detail_level);
         prompt.push_str("\n");
     }
 
@@ -147,14 +153,56 @@ pub fn create_changelog_user_prompt(
     prompt.push_str("For each change, provide a clear description of what was changed and, where possible, why it matters to users or developers. ");
     prompt.push_str("Include the overall metrics at the beginning of the changelog to give context about the scope of changes in this release.");
 
+    if changes.iter().any(|change| !format_risk_labels(change).is_empty()) {
+        prompt.push_str(" Some changes carry risk labels, so include a 'Risk Notes' section describing them.");
+    }
+
     if readme_summary.is_some() {
         prompt.push_str(" Use the README summary to provide context about the project and ensure the changelog reflects the project's goals and main features.");
     }
 
+    Ok(prompt)
+}
+
+pub fn create_tag_message_system_prompt(config: &Config) -> String {
+    let mut prompt = String::from(
+        "You are an AI assistant specialized in writing annotated Git tag messages for software releases. \
+        Your task is to write a condensed summary of the changes included in a release, based on the provided commit information. \
+        Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Start with a single subject line of the form '<tag name>: <one-line summary of the release>'.
+        2. Follow the subject with a blank line and a short bulleted list of the most important changes.
+        3. List at most ten bullets, ordered by impact score, merging related commits into one bullet.
+        4. Mention breaking changes first, prefixed with 'BREAKING:'.
+        5. Do not use Markdown headings, links, or commit hashes; tag messages are read as plain text in the terminal.
+        6. Wrap lines at 72 characters.
+        7. Avoid common cliché words (like 'enhance', 'streamline', 'leverage', etc) and phrases.
+        8. Do not speculate about the purpose of a change or add any information not directly supported by the context.
+        9. NO YAPPING!
+
+        Generate only the tag message, without any explanations or code fences."
+    );
+
+    if config.use_gitmoji {
+        prompt.push_str(
+            "\n\nYou may start each bullet with a single relevant emoji from the following list:\n",
+        );
+        prompt.push_str(&get_gitmoji_list());
+    }
+
     prompt
 }
 
 pub fn create_release_notes_system_prompt(config: &Config) -> String {
+    if let Some(prompt) = templates::render_override_or_warn(
+        templates::RELEASE_NOTES_SYSTEM,
+        &system_template_variables(config),
+    ) {
+        return prompt;
+    }
+
     let use_emoji = config.use_gitmoji;
     let instructions = &config.instructions;
 
@@ -204,7 +252,18 @@ pub fn create_release_notes_user_prompt(
     from: &str,
     to: &str,
     readme_summary: Option<&str>,
-) -> String {
+) -> Result<String> {
+    let variables = json!({
+        "changelog": changelog,
+        "from": from,
+        "to": to,
+        "detail_level": detail_level_name(detail_level),
+        "readme_summary": readme_summary,
+    });
+    if let Some(prompt) = templates::render_override(templates::RELEASE_NOTES_USER, &variables)? {
+        return Ok(prompt);
+    }
+
     let mut prompt = String::from(format!(
         "Based on the following changelog for changes from {} to {}, generate release notes:\n\n",
         from, to
@@ -250,10 +309,96 @@ pub fn create_release_notes_user_prompt(
         prompt.push_str(" Ensure the release notes align with the project's overall goals and main features as described in the README summary.");
     }
 
-    prompt
+    Ok(prompt)
+}
+
+/// Variables available to the changelog and release notes system prompt templates
+fn system_template_variables(config: &Config) -> Value {
+    json!({
+        "use_gitmoji": config.use_gitmoji,
+        "gitmoji_list": get_gitmoji_list(),
+        "instructions": config.instructions,
+        "language": config.language().name,
+    })
+}
+
+fn detail_level_name(detail_level: DetailLevel) -> &'static str {
+    match detail_level {
+        DetailLevel::Minimal => "minimal",
+        DetailLevel::Standard => "standard",
+        DetailLevel::Detailed => "detailed",
+    }
+}
+
+/// Append a commit's message, metrics, risk labels, and file changes at the given detail level
+pub fn push_change_details(
+    prompt: &mut String,
+    change: &AnalyzedChange,
+    detail_level: DetailLevel,
+) {
+    prompt.push_str(&format!("Commit: {}\n", change.commit_hash));
+    prompt.push_str(&format!("Author: {}\n", change.author));
+    prompt.push_str(&format!("Message: {}\n", change.commit_message));
+    prompt.push_str(&format!(
+        "Files changed: {}\n",
+        change.metrics.files_changed
+    ));
+    prompt.push_str(&format!(
+        "Lines changed: {}\n",
+        change.metrics.total_lines_changed
+    ));
+    prompt.push_str(&format!("Insertions: {}\n", change.metrics.insertions));
+    prompt.push_str(&format!("Deletions: {}\n", change.metrics.deletions));
+    prompt.push_str(&format!("Impact score: {:.2}\n", change.impact_score));
+
+    let risks = format_risk_labels(change);
+    if !risks.is_empty() {
+        prompt.push_str(&format!("Risk labels: {}\n", risks));
+    }
+
+    match detail_level {
+        DetailLevel::Minimal => {
+            // For minimal detail, we don't include file-level changes
+        }
+        DetailLevel::Standard => {
+            prompt.push_str("File changes summary:\n");
+            for file_change in &change.file_changes {
+                prompt.push_str(&format!(
+                    "  - {} ({})\n",
+                    file_change.new_path, file_change.change_type
+                ));
+            }
+        }
+        DetailLevel::Detailed => {
+            prompt.push_str("Detailed file changes:\n");
+            for file_change in &change.file_changes {
+                prompt.push_str(&format!(
+                    "  - {} ({})\n",
+                    file_change.new_path, file_change.change_type
+                ));
+                for analysis in &file_change.analysis {
+                    prompt.push_str(&format!("    * {}\n", analysis));
+                }
+            }
+        }
+    }
+}
+
+fn format_risk_labels(change: &AnalyzedChange) -> String {
+    change
+        .file_changes
+        .iter()
+        .flat_map(|file_change| {
+            file_change
+                .risk_labels
+                .iter()
+                .map(move |label| format!("{} ({})", label, file_change.new_path))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
-fn calculate_total_metrics(changes: &[AnalyzedChange]) -> ChangeMetrics {
+pub fn calculate_total_metrics(changes: &[AnalyzedChange]) -> ChangeMetrics {
     changes.iter().fold(
         ChangeMetrics {
             files_changed: 0,
@@ -0,0 +1,265 @@
+use crate::log_debug;
+use crate::ui;
+use anyhow::Result;
+use colored::*;
+use console::{Key, Term};
+use std::io::Write;
+use std::process::Command;
+
+/// A changelog split into sections and entries so it can be reviewed piece by piece
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ChangelogDocument {
+    /// Lines before the first heading, such as a metrics summary
+    pub intro: Vec<String>,
+    pub sections: Vec<ChangelogSection>,
+}
+
+/// A heading with the text and list entries under it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangelogSection {
+    pub heading: String,
+    /// Lines between the heading and the first entry
+    pub body: Vec<String>,
+    /// List entries, each with its continuation lines
+    pub entries: Vec<String>,
+}
+
+impl ChangelogDocument {
+    /// Split a changelog into sections at each Markdown heading and into entries at each
+    /// top-level list item
+    pub fn parse(changelog: &str) -> Self {
+        let mut document = ChangelogDocument::default();
+        for line in changelog.trim().lines() {
+            if is_heading(line) {
+                document.sections.push(ChangelogSection {
+                    heading: line.to_string(),
+                    body: Vec::new(),
+                    entries: Vec::new(),
+                });
+                continue;
+            }
+            let Some(section) = document.sections.last_mut() else {
+                document.intro.push(line.to_string());
+                continue;
+            };
+            if is_entry_start(line) {
+                section.entries.push(line.to_string());
+            } else if line.trim().is_empty() {
+                // Blank lines are restored when rendering
+            } else if let Some(entry) = section.entries.last_mut() {
+                entry.push('\n');
+                entry.push_str(line);
+            } else {
+                section.body.push(line.to_string());
+            }
+        }
+        while document.intro.last().is_some_and(|line| line.trim().is_empty()) {
+            document.intro.pop();
+        }
+        document
+    }
+
+    /// Write the changelog back out as Markdown
+    pub fn render(&self) -> String {
+        let mut blocks = Vec::new();
+        let intro = self.intro.join("\n");
+        if !intro.trim().is_empty() {
+            blocks.push(intro.trim().to_string());
+        }
+        for section in &self.sections {
+            blocks.push(section.heading.clone());
+            if !section.body.is_empty() {
+                blocks.push(section.body.join("\n"));
+            }
+            if !section.entries.is_empty() {
+                blocks.push(section.entries.join("\n"));
+            }
+        }
+        blocks.join("\n\n")
+    }
+
+    /// Remove an entry, and its section once the section has nothing left to say
+    pub fn drop_entry(&mut self, section: usize, entry: usize) {
+        let Some(current) = self.sections.get_mut(section) else {
+            return;
+        };
+        if entry < current.entries.len() {
+            current.entries.remove(entry);
+        }
+        if current.entries.is_empty() && current.body.is_empty() {
+            self.sections.remove(section);
+        }
+    }
+
+    /// Swap an entry with its neighbor above or below, returning its new position
+    pub fn move_entry(&mut self, section: usize, entry: usize, up: bool) -> usize {
+        let Some(current) = self.sections.get_mut(section) else {
+            return entry;
+        };
+        let target = if up {
+            entry.checked_sub(1)
+        } else {
+            Some(entry + 1).filter(|target| *target < current.entries.len())
+        };
+        match target {
+            Some(target) if entry < current.entries.len() => {
+                current.entries.swap(entry, target);
+                target
+            }
+            _ => entry,
+        }
+    }
+
+    /// Replace the text of an entry; empty text drops it
+    pub fn replace_entry(&mut self, section: usize, entry: usize, text: &str) {
+        let text = text.trim_end();
+        if text.trim().is_empty() {
+            self.drop_entry(section, entry);
+        } else if let Some(current) = self
+            .sections
+            .get_mut(section)
+            .and_then(|current| current.entries.get_mut(entry))
+        {
+            *current = text.to_string();
+        }
+    }
+
+    /// Sections that have entries to review
+    fn reviewable_sections(&self) -> Vec<usize> {
+        (0..self.sections.len())
+            .filter(|index| !self.sections[*index].entries.is_empty())
+            .collect()
+    }
+}
+
+/// Review a generated changelog section by section, editing, reordering, or dropping entries
+///
+/// Returns the reviewed changelog, or `None` if the review was cancelled.
+pub fn review_changelog(changelog: &str) -> Result<Option<String>> {
+    let mut document = ChangelogDocument::parse(changelog);
+    let mut term = Term::stdout();
+    let mut section_position = 0;
+    let mut selected = 0;
+
+    loop {
+        let sections = document.reviewable_sections();
+        if sections.is_empty() {
+            return Ok(Some(document.render()));
+        }
+        section_position = section_position.min(sections.len() - 1);
+        let section = sections[section_position];
+        selected = selected.min(document.sections[section].entries.len() - 1);
+
+        term.clear_screen()?;
+        draw_section(
+            &mut term,
+            &document.sections[section],
+            (section_position, sections.len()),
+            selected,
+        )?;
+
+        match term.read_key()? {
+            Key::ArrowUp | Key::Char('k') => selected = selected.saturating_sub(1),
+            Key::ArrowDown | Key::Char('j') => selected += 1,
+            Key::ArrowLeft | Key::Char('h') => {
+                section_position = section_position.saturating_sub(1);
+                selected = 0;
+            }
+            Key::ArrowRight | Key::Char('l') => {
+                section_position += 1;
+                selected = 0;
+            }
+            Key::Char('K') => selected = document.move_entry(section, selected, true),
+            Key::Char('J') => selected = document.move_entry(section, selected, false),
+            Key::Char('d') | Key::Del => document.drop_entry(section, selected),
+            Key::Char('e') => {
+                let entry = document.sections[section].entries[selected].clone();
+                if let Some(edited) = edit_in_editor(&entry)? {
+                    document.replace_entry(section, selected, &edited);
+                }
+            }
+            Key::Char('E') => {
+                if let Some(edited) = edit_in_editor(&document.render())? {
+                    document = ChangelogDocument::parse(&edited);
+                }
+            }
+            Key::Enter => {
+                term.clear_screen()?;
+                return Ok(Some(document.render()));
+            }
+            Key::Escape | Key::Char('q') => {
+                term.clear_screen()?;
+                return Ok(None);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Draw a section with its entries; `position` is the section's place among those reviewed
+fn draw_section(
+    out: &mut Term,
+    section: &ChangelogSection,
+    (position, total): (usize, usize),
+    selected: usize,
+) -> Result<()> {
+    let title = format!(
+        "Review changelog · section {}/{}: {}",
+        position + 1,
+        total,
+        section.heading.trim_start_matches('#').trim()
+    );
+    writeln!(out, "{}", ui::create_gradient_text(&title))?;
+    writeln!(out, "{}", "━".repeat(50).bright_purple())?;
+    for line in &section.body {
+        writeln!(out, "{}", line.dimmed())?;
+    }
+    for (index, entry) in section.entries.iter().enumerate() {
+        for (line_index, line) in entry.lines().enumerate() {
+            let cursor = if index == selected && line_index == 0 {
+                "❯".bright_purple().bold().to_string()
+            } else {
+                " ".to_string()
+            };
+            if index == selected {
+                writeln!(out, "{} {}", cursor, line.bold())?;
+            } else {
+                writeln!(out, "{} {}", cursor, line)?;
+            }
+        }
+    }
+    writeln!(out, "{}", "━".repeat(50).bright_purple())?;
+    writeln!(
+        out,
+        "{}",
+        "↑↓ select  ←→ section  e edit  E edit all  d drop  K/J move up/down  Enter accept  Esc cancel"
+            .dimmed()
+    )?;
+    Ok(())
+}
+
+fn is_heading(line: &str) -> bool {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    level > 0 && line[level..].starts_with(' ')
+}
+
+fn is_entry_start(line: &str) -> bool {
+    line.starts_with("- ") || line.starts_with("* ") || line.starts_with("+ ")
+}
+
+fn edit_in_editor(text: &str) -> Result<Option<String>> {
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(text.as_bytes())?;
+
+    let path = file.into_temp_path();
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vim".to_string());
+    let status = Command::new(editor).arg(&path).status()?;
+
+    if status.success() {
+        let edited = std::fs::read_to_string(&path)?;
+        log_debug!("✏️ Changelog edited during review: {}", edited);
+        Ok(Some(edited))
+    } else {
+        Ok(None)
+    }
+}
@@ -0,0 +1,260 @@
+use crate::config::{Config, RecentCommitsConfig};
+use crate::context::CommitContext;
+use crate::exclusions::PromptFilter;
+use crate::git;
+use crate::llm;
+use crate::llm_providers::{get_provider_metadata, LLMProviderType};
+use crate::token_optimizer::TokenOptimizer;
+use anyhow::{anyhow, Context, Result};
+use git2::{BranchType, Repository, RepositoryState};
+use std::fs;
+use std::path::Path;
+
+/// Number of commits on the target branch shown to the model as a style reference
+const TARGET_HISTORY_COUNT: usize = 5;
+
+/// A commit being cherry-picked, and the branch it lands on
+pub struct CherryPickContext {
+    pub commit_id: String,
+    pub original_message: String,
+    /// Local branches other than the target that already contain the commit
+    pub source_branches: Vec<String>,
+    pub target: String,
+    /// Subjects of the most recent commits on the target branch
+    pub target_history: Vec<String>,
+    /// The changes the original commit made
+    pub changes: CommitContext,
+}
+
+impl CherryPickContext {
+    /// Collect the context for cherry-picking `reference` onto the current branch.
+    /// Without a reference, the cherry-pick in progress (`CHERRY_PICK_HEAD`) is used.
+    pub fn load(
+        repo_path: &Path,
+        reference: Option<&str>,
+        filter: &PromptFilter,
+    ) -> Result<Self> {
+        let repo = Repository::open(repo_path)?;
+        let reference = match reference {
+            Some(reference) => reference.to_string(),
+            None => read_cherry_pick_head(&repo).ok_or_else(|| {
+                anyhow!("No cherry-pick in progress. Specify the commit to cherry-pick")
+            })?,
+        };
+
+        let commit_id = git::resolve_commit_id(repo_path, &reference)
+            .map_err(|_| anyhow!("Commit '{}' not found", reference))?;
+        let original_message = git::get_commit_message(repo_path, &commit_id)?;
+        let target = git::get_current_branch_name(repo_path)?;
+        let source_branches = branches_containing(&repo, &commit_id, &target)?;
+        let target_history = recent_subjects(&repo, TARGET_HISTORY_COUNT)?;
+        let changes = git::get_commit_info(
+            repo_path,
+            &commit_id,
+            &RecentCommitsConfig::default(),
+            filter,
+        )?;
+
+        Ok(CherryPickContext {
+            commit_id,
+            original_message,
+            source_branches,
+            target,
+            target_history,
+            changes,
+        })
+    }
+}
+
+pub struct CherryPickMessageGenerator;
+
+impl CherryPickMessageGenerator {
+    /// Adapt the original commit message to the branch the commit is picked onto
+    pub async fn generate(context: &CherryPickContext, config: &Config) -> Result<String> {
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let mut changes = context.changes.clone();
+        let optimizer = TokenOptimizer::new(get_provider_metadata(&provider_type).default_token_limit);
+        optimizer.optimize_context(&mut changes);
+
+        let mut system_prompt = create_cherry_pick_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let user_prompt = create_cherry_pick_user_prompt(context, &changes);
+
+        let message =
+            llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+                .await
+                .context("Failed to generate cherry-pick message")?;
+
+        Ok(ensure_cherry_pick_trailer(&message, &context.commit_id))
+    }
+}
+
+pub fn create_cherry_pick_system_prompt() -> String {
+    String::from(
+        "You are an AI assistant specialized in writing Git commit messages for cherry-picked commits. \
+        Your task is to adapt the message of a commit that is copied onto another branch, such as a backport to a release branch, so it reads correctly on that branch.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Keep the original subject line unless it refers to something that does not exist on the target branch; keep it under 72 characters.
+        2. Separate subject from body with a blank line, and wrap the body at 72 characters.
+        3. Keep the parts of the original body that explain the change.
+        4. Drop issue-closing keywords and trailers such as 'Fixes #123' or 'Closes #45'; mention the issue as a plain reference instead, since the fix was already recorded on the source branch.
+        5. Drop references to earlier commits, pull requests, or follow-up work that only exist on the source branch.
+        6. Add one sentence noting that this is a backport or cherry-pick onto the target branch, naming the source branch when it is known.
+        7. Match the style of the recent commits on the target branch.
+        8. Do not add a '(cherry picked from commit ...)' line; it is added for you.
+        9. NO YAPPING!
+
+        Generate only the commit message, without any explanations, quotes, or code fences.",
+    )
+}
+
+pub fn create_cherry_pick_user_prompt(
+    context: &CherryPickContext,
+    changes: &CommitContext,
+) -> String {
+    let mut prompt = String::from(
+        "Based on the following information, adapt the commit message for the target branch:\n\n",
+    );
+
+    prompt.push_str(&format!("Commit: {}\n", context.commit_id));
+    if context.source_branches.is_empty() {
+        prompt.push_str("Source branch: unknown\n");
+    } else {
+        prompt.push_str(&format!(
+            "Source branch: {}\n",
+            context.source_branches.join(", ")
+        ));
+    }
+    prompt.push_str(&format!("Target branch: {}\n\n", context.target));
+
+    prompt.push_str(&format!(
+        "Original message:\n{}\n\n",
+        context.original_message.trim()
+    ));
+
+    if !context.target_history.is_empty() {
+        prompt.push_str("Recent commits on the target branch:\n");
+        for subject in &context.target_history {
+            prompt.push_str(&format!("- {}\n", subject));
+        }
+        prompt.push('\n');
+    }
+
+    prompt.push_str("Changes:\n");
+    for file in &changes.staged_files {
+        prompt.push_str(&format!("- {} ({})\n", file.path, file.change_type));
+        for analysis in &file.analysis {
+            prompt.push_str(&format!("  - {}\n", analysis));
+        }
+    }
+
+    prompt.push_str("\nDiffs:\n");
+    for file in &changes.staged_files {
+        prompt.push_str(&format!("File: {}\n{}\n", file.path, file.diff));
+    }
+
+    prompt
+}
+
+/// Make sure the message ends with the "(cherry picked from commit <hash>)" line `git cherry-pick -x` adds
+pub fn ensure_cherry_pick_trailer(message: &str, commit_id: &str) -> String {
+    let trailer = format!("(cherry picked from commit {})", commit_id);
+    let kept: Vec<&str> = message
+        .trim()
+        .lines()
+        .filter(|line| !line.trim().starts_with("(cherry picked from commit"))
+        .collect();
+    format!("{}\n\n{}", kept.join("\n").trim_end(), trailer)
+}
+
+/// Whether a cherry-pick is in progress, either stopped on conflicts or committing
+pub fn is_cherry_pick_in_progress(repo_path: &Path) -> bool {
+    Repository::open(repo_path)
+        .map(|repo| {
+            matches!(
+                repo.state(),
+                RepositoryState::CherryPick | RepositoryState::CherryPickSequence
+            )
+        })
+        .unwrap_or(false)
+}
+
+/// Whether a commit message file still holds the picked commit's own message, as Git prepared it
+///
+/// Comment lines are ignored, and so is the trailer `git cherry-pick -x` adds. A message the user
+/// wrote themselves, with `git commit -m` for example, is not the default.
+pub fn has_default_cherry_pick_message(
+    repo_path: &Path,
+    message_file: &Path,
+    comment_char: char,
+) -> bool {
+    let Ok(repo) = Repository::open(repo_path) else {
+        return false;
+    };
+    let Some(commit_id) = read_cherry_pick_head(&repo) else {
+        return false;
+    };
+    let Ok(original) = git::get_commit_message(repo_path, &commit_id) else {
+        return false;
+    };
+    let Ok(content) = fs::read_to_string(message_file) else {
+        return false;
+    };
+
+    let prepared: String = content
+        .lines()
+        .filter(|line| !line.starts_with(comment_char))
+        .collect::<Vec<_>>()
+        .join("\n");
+    prepared.trim().starts_with(original.trim())
+}
+
+fn read_cherry_pick_head(repo: &Repository) -> Option<String> {
+    let content = fs::read_to_string(repo.path().join("CHERRY_PICK_HEAD")).ok()?;
+    content.lines().next().map(|line| line.trim().to_string())
+}
+
+fn branches_containing(repo: &Repository, commit_id: &str, target: &str) -> Result<Vec<String>> {
+    let commit = git2::Oid::from_str(commit_id)?;
+    let mut branches = Vec::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let Some(name) = branch.name()?.map(str::to_string) else {
+            continue;
+        };
+        let Some(tip) = branch.get().target() else {
+            continue;
+        };
+        if name != target && (tip == commit || repo.graph_descendant_of(tip, commit)?) {
+            branches.push(name);
+        }
+    }
+    Ok(branches)
+}
+
+fn recent_subjects(repo: &Repository, count: usize) -> Result<Vec<String>> {
+    let Ok(head) = repo.head().and_then(|head| head.peel_to_commit()) else {
+        return Ok(Vec::new());
+    };
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head.id())?;
+    let mut subjects = Vec::new();
+    for oid in revwalk.take(count) {
+        let commit = repo.find_commit(oid?)?;
+        subjects.push(commit.summary().unwrap_or_default().to_string());
+    }
+    Ok(subjects)
+}
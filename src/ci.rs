@@ -0,0 +1,118 @@
+use serde_json::json;
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit code for errors without a more specific failure kind
+pub const EXIT_ERROR: i32 = 1;
+
+/// Flag to control whether Git-Iris runs without spinners, colors, or prompts
+static CI_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Run without spinners, colors, or prompts, and report errors as JSON
+pub fn enable_ci_mode() {
+    CI_MODE.store(true, Ordering::SeqCst);
+    colored::control::set_override(false);
+}
+
+/// Check whether CI mode is enabled
+pub fn is_ci() -> bool {
+    CI_MODE.load(Ordering::SeqCst)
+}
+
+/// Check whether the environment marks this as a CI run, as most CI services do with `CI=true`
+pub fn detect_ci_env() -> bool {
+    env::var("CI")
+        .map(|value| matches!(value.trim().to_lowercase().as_str(), "true" | "1"))
+        .unwrap_or(false)
+}
+
+/// Failures that pipelines can branch on, each with its own exit code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    NoStagedChanges,
+    ConfigMissing,
+    ProviderFailure,
+}
+
+impl FailureKind {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            FailureKind::NoStagedChanges => 2,
+            FailureKind::ConfigMissing => 3,
+            FailureKind::ProviderFailure => 4,
+        }
+    }
+
+    /// Short key used in JSON error output
+    pub fn key(&self) -> &'static str {
+        match self {
+            FailureKind::NoStagedChanges => "no_staged_changes",
+            FailureKind::ConfigMissing => "config_missing",
+            FailureKind::ProviderFailure => "provider_failure",
+        }
+    }
+}
+
+/// An error tagged with its failure kind, displayed as the underlying error
+#[derive(Debug)]
+pub struct Failure {
+    pub kind: FailureKind,
+    error: anyhow::Error,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl Error for Failure {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
+/// Tag an error with a failure kind, so it exits with that kind's code
+pub fn failure(kind: FailureKind, error: impl Into<anyhow::Error>) -> anyhow::Error {
+    Failure {
+        kind,
+        error: error.into(),
+    }
+    .into()
+}
+
+/// Find the failure kind an error was tagged with, looking through any added context
+pub fn failure_kind(error: &anyhow::Error) -> Option<FailureKind> {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<Failure>())
+        .map(|failure| failure.kind)
+}
+
+/// Exit code for an error
+pub fn exit_code(error: &anyhow::Error) -> i32 {
+    failure_kind(error).map_or(EXIT_ERROR, |kind| kind.exit_code())
+}
+
+/// Format an error as a single line of JSON for CI logs
+pub fn format_error_json(error: &anyhow::Error) -> String {
+    json!({
+        "error": {
+            "kind": failure_kind(error).map_or("error", |kind| kind.key()),
+            "message": format!("{:#}", error),
+            "exit_code": exit_code(error),
+        }
+    })
+    .to_string()
+}
+
+/// Print an error to stderr: as JSON in CI mode, otherwise with its causes
+pub fn report_error(error: &anyhow::Error) {
+    if is_ci() {
+        eprintln!("{}", format_error_json(error));
+    } else {
+        eprintln!("Error: {:?}", error);
+    }
+}
@@ -0,0 +1,319 @@
+use crate::file_analyzers::is_lockfile;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use std::fmt;
+
+lazy_static! {
+    // Test functions as each language declares them, on lines a diff adds
+    static ref RUST_TEST: Regex = Regex::new(r"(?m)^\+\s*#\[(?:\w+::)*test\b").unwrap();
+    static ref PYTHON_TEST: Regex =
+        Regex::new(r"(?m)^\+\s*(?:async\s+)?def\s+test(?:_|[A-Z])\w*\s*\(").unwrap();
+    static ref GO_TEST: Regex = Regex::new(r"(?m)^\+\s*func\s+Test[A-Z_0-9]\w*\s*\(").unwrap();
+    static ref JS_TEST: Regex = Regex::new(r"(?m)^\+\s*(?:it|test)(?:\.\w+)?\s*\(").unwrap();
+    static ref JVM_TEST: Regex = Regex::new(r"(?m)^\+\s*@(?:Test|ParameterizedTest)\b").unwrap();
+    static ref CSHARP_TEST: Regex =
+        Regex::new(r"(?m)^\+\s*\[(?:Test|TestMethod|Fact|Theory)\b").unwrap();
+    static ref RUBY_TEST: Regex =
+        Regex::new(r#"(?m)^\+\s*(?:(?:it|test)\s+['"]|def\s+test_)"#).unwrap();
+    static ref FUNCTION_TEST: Regex =
+        Regex::new(r"(?m)^\+\s*(?:public\s+)?func(?:tion)?\s+test\w*\s*\(").unwrap();
+}
+
+/// Markers that tools write at the top of files they generate
+const GENERATED_MARKERS: &[&str] = &[
+    "@generated",
+    "Code generated",
+    "DO NOT EDIT",
+    "auto-generated",
+    "autogenerated",
+];
+
+/// Lockfiles beyond those the dependency analyzers summarize
+const GENERATED_NAMES: &[&str] = &[
+    "go.sum",
+    "Gemfile.lock",
+    "poetry.lock",
+    "Pipfile.lock",
+    "composer.lock",
+];
+
+/// Minified bundles, source maps, snapshots, and code generated from schemas
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".min.js",
+    ".min.css",
+    ".js.map",
+    ".css.map",
+    ".pb.go",
+    "_pb2.py",
+    "_pb2_grpc.py",
+    ".g.dart",
+    ".freezed.dart",
+    ".designer.cs",
+    ".snap",
+];
+
+const DOCS_SUFFIXES: &[&str] = &[".md", ".mdx", ".rst", ".adoc", ".txt"];
+
+/// Prefixes of the names of documents kept at the top of a project, in upper case
+const DOCS_NAMES: &[&str] = &[
+    "README",
+    "CHANGELOG",
+    "LICENSE",
+    "CONTRIBUTING",
+    "AUTHORS",
+    "NOTICE",
+];
+
+const CONFIG_SUFFIXES: &[&str] = &[
+    ".toml",
+    ".yaml",
+    ".yml",
+    ".json",
+    ".ini",
+    ".cfg",
+    ".conf",
+    ".properties",
+    ".env",
+    ".gradle",
+    ".csproj",
+    ".config.js",
+    ".config.ts",
+    ".config.mjs",
+    ".config.cjs",
+];
+
+const CONFIG_NAMES: &[&str] = &[
+    "Dockerfile",
+    "Makefile",
+    "CMakeLists.txt",
+    "go.mod",
+    "pom.xml",
+    "Gemfile",
+];
+
+/// Number of paths listed in a coverage signal before the rest are counted
+const MAX_LISTED: usize = 5;
+
+/// What a changed file is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileRole {
+    Production,
+    Test,
+    Config,
+    Docs,
+    Generated,
+}
+
+impl fmt::Display for FileRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileRole::Production => write!(f, "production"),
+            FileRole::Test => write!(f, "test"),
+            FileRole::Config => write!(f, "config"),
+            FileRole::Docs => write!(f, "docs"),
+            FileRole::Generated => write!(f, "generated"),
+        }
+    }
+}
+
+/// Classify a changed file from its path, and from its diff for files that say they are
+/// generated
+pub fn classify_file(path: &str, diff: &str) -> FileRole {
+    if is_generated(path, diff) {
+        FileRole::Generated
+    } else if is_test_file(path) {
+        FileRole::Test
+    } else if is_docs(path) {
+        FileRole::Docs
+    } else if is_config(path) {
+        FileRole::Config
+    } else {
+        FileRole::Production
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn has_directory(path: &str, names: &[&str]) -> bool {
+    let mut directories = path.split('/').rev().skip(1);
+    directories.any(|directory| names.contains(&directory))
+}
+
+fn is_generated(path: &str, diff: &str) -> bool {
+    let name = file_name(path);
+    is_lockfile(path)
+        || GENERATED_NAMES.contains(&name)
+        || GENERATED_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+        || name.contains(".generated.")
+        || name.contains("_generated.")
+        || has_directory(path, &["generated", "__generated__", "dist"])
+        // The marker sits in a header comment, so only the start of the diff is searched
+        || diff
+            .lines()
+            .take(10)
+            .any(|line| GENERATED_MARKERS.iter().any(|marker| line.contains(marker)))
+}
+
+/// Whether a file holds tests, by the directory it is in or the naming conventions of test
+/// frameworks
+pub fn is_test_file(path: &str) -> bool {
+    let name = file_name(path);
+    let stem = name.split('.').next().unwrap_or(name);
+    let extension = name.rsplit('.').next().unwrap_or_default();
+    has_directory(path, &["tests", "test", "__tests__", "spec", "specs"])
+        || name == "conftest.py"
+        || (name.ends_with(".py") && (stem.starts_with("test_") || stem.ends_with("_test")))
+        || name.ends_with("_test.go")
+        || name.ends_with("_spec.rb")
+        || name.ends_with("_test.rb")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        // JUnit, xUnit, and XCTest name test classes after the class under test
+        || (["java", "kt", "scala", "cs", "swift", "php"].contains(&extension)
+            && ["Test", "Tests"]
+                .iter()
+                .any(|suffix| stem.len() > suffix.len() && stem.ends_with(suffix)))
+}
+
+fn is_docs(path: &str) -> bool {
+    let name = file_name(path);
+    let upper = name.to_uppercase();
+    DOCS_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+        || DOCS_NAMES.iter().any(|prefix| upper.starts_with(prefix))
+        || has_directory(path, &["docs", "doc"])
+}
+
+fn is_config(path: &str) -> bool {
+    let name = file_name(path);
+    CONFIG_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+        || CONFIG_NAMES.contains(&name)
+        || name.starts_with('.')
+        || has_directory(path, &[".github", ".circleci", ".gitlab"])
+}
+
+/// The number of test functions a diff adds, in dedicated test files and inline test modules
+/// alike
+pub fn count_added_tests(path: &str, diff: &str) -> usize {
+    let extension = file_name(path).rsplit('.').next().unwrap_or_default();
+    let pattern: &Regex = match extension {
+        "rs" => &RUST_TEST,
+        "py" => &PYTHON_TEST,
+        "go" => &GO_TEST,
+        // `it(` and `test(` are too common to count outside test files
+        "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" if is_test_file(path) => &JS_TEST,
+        "java" | "kt" => &JVM_TEST,
+        "cs" => &CSHARP_TEST,
+        "rb" => &RUBY_TEST,
+        "php" | "swift" => &FUNCTION_TEST,
+        _ => return 0,
+    };
+    pattern.find_iter(diff).count()
+}
+
+/// The code a test file is named after, such as `parser` for `tests/parser_tests.rs`,
+/// `test_parser.py`, or `Parser.test.ts`
+fn test_subject(path: &str) -> String {
+    let name = file_name(path);
+    let stem = name.split('.').next().unwrap_or(name);
+    let stem = stem.strip_prefix("test_").unwrap_or(stem);
+    let stem = ["_tests", "_test", "_spec", "Tests", "Test"]
+        .iter()
+        .find_map(|suffix| stem.strip_suffix(suffix).filter(|rest| !rest.is_empty()))
+        .unwrap_or(stem);
+    stem.to_lowercase()
+}
+
+/// The name tests for a source file would use, such as `parser` for `src/parser.rs`, or the
+/// directory for module files like `src/parser/mod.rs`
+fn code_subject(path: &str) -> String {
+    let name = file_name(path);
+    let stem = name.split('.').next().unwrap_or(name);
+    if ["mod", "index", "__init__"].contains(&stem) {
+        if let Some(directory) = path.rsplit('/').nth(1) {
+            return directory.to_lowercase();
+        }
+    }
+    stem.to_lowercase()
+}
+
+fn list_paths(paths: &[&str]) -> String {
+    let listed = paths
+        .iter()
+        .take(MAX_LISTED)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(", ");
+    match paths.len().saturating_sub(MAX_LISTED) {
+        0 => listed,
+        rest => format!("{} and {} more", listed, rest),
+    }
+}
+
+/// Describe how the tests in a change relate to the code it changes, from each file's path
+/// and diff, such as "Adds 3 tests for parser in tests/parser_tests.rs" or "Modifies code in
+/// src/lexer.rs without matching test changes"
+///
+/// Diffs may be empty when only paths are known; tests are then noted as modified rather
+/// than counted.
+pub fn test_coverage_signals(files: &[(&str, &str)]) -> Vec<String> {
+    let mut signals = Vec::new();
+    let mut tested_subjects = Vec::new();
+    let mut modified_tests = Vec::new();
+    let mut production = Vec::new();
+
+    for &(path, diff) in files {
+        let role = classify_file(path, diff);
+        let added = count_added_tests(path, diff);
+        if added > 0 {
+            let subject = match role {
+                FileRole::Test => test_subject(path),
+                _ => code_subject(path),
+            };
+            signals.push(format!(
+                "Adds {} {} for {} in {}",
+                added,
+                if added == 1 { "test" } else { "tests" },
+                subject,
+                path
+            ));
+            tested_subjects.push(subject);
+        } else if role == FileRole::Test {
+            tested_subjects.push(test_subject(path));
+            modified_tests.push(path);
+        }
+        if role == FileRole::Production {
+            production.push(path);
+        }
+    }
+
+    if !modified_tests.is_empty() {
+        signals.push(format!(
+            "Modifies tests in {}",
+            list_paths(&modified_tests)
+        ));
+    }
+
+    let untested: Vec<&str> = production
+        .into_iter()
+        .filter(|path| !tested_subjects.contains(&code_subject(path)))
+        .collect();
+    if !untested.is_empty() {
+        signals.push(if tested_subjects.is_empty() {
+            format!(
+                "Modifies code in {} without any test changes",
+                list_paths(&untested)
+            )
+        } else {
+            format!(
+                "Modifies code in {} without matching test changes",
+                list_paths(&untested)
+            )
+        });
+    }
+
+    signals
+}
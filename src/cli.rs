@@ -1,9 +1,16 @@
+use crate::blame::DEFAULT_MAX_COMMITS;
+use crate::fixup::DEFAULT_MAX_COMMITS as FIXUP_MAX_COMMITS;
+use crate::stats::DEFAULT_TOP as STATS_TOP;
 use crate::commands;
-use crate::llm::get_available_provider_names;
+use crate::context::ChangeScope;
+use crate::language::Language;
+use crate::llm::{get_available_provider_names, DryRunComplete};
 use crate::log_debug;
 use crate::ui;
 use clap::builder::{styling::AnsiColor, Styles};
-use clap::{crate_version, Parser, Subcommand};
+use clap::{crate_version, ArgGroup, Parser, Subcommand};
+use std::path::PathBuf;
+use std::str::FromStr;
 
 /// CLI structure defining the available commands and global arguments
 #[derive(Parser)]
@@ -30,6 +37,48 @@ pub struct Cli {
     )]
     pub log: bool,
 
+    /// Print the prompts that would be sent to the LLM and exit without calling it
+    #[arg(
+        long = "dry-run",
+        global = true,
+        help = "Print the prompts that would be sent to the LLM, with an estimated token count, and exit without calling it"
+    )]
+    pub dry_run: bool,
+
+    /// Show what would be redacted from the prompts and exit without calling the LLM
+    #[arg(
+        long = "show-redactions",
+        global = true,
+        help = "Show the text the configured redaction patterns would replace in the prompts, and exit without calling the LLM"
+    )]
+    pub show_redactions: bool,
+
+    /// Run non-interactively for CI pipelines
+    #[arg(
+        long = "ci",
+        global = true,
+        help = "Run non-interactively: no spinners, colors, or prompts, JSON errors, and distinct exit codes (enabled automatically when CI=true)"
+    )]
+    pub ci: bool,
+
+    /// Use a named configuration profile
+    #[arg(
+        long = "profile",
+        global = true,
+        value_name = "NAME",
+        help = "Use the settings of a named configuration profile instead of the default one"
+    )]
+    pub profile: Option<String>,
+
+    /// Use a different configuration file
+    #[arg(
+        long = "config",
+        global = true,
+        value_name = "PATH",
+        help = "Read and write the configuration at this path instead of the default location"
+    )]
+    pub config: Option<PathBuf>,
+
     /// Display the version
     #[arg(
         short = 'v',
@@ -41,6 +90,8 @@ pub struct Cli {
 }
 
 /// Enumeration of available subcommands
+// Parsed once per run, so the size of the largest variant doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
     /// Generate a commit message using AI
@@ -54,6 +105,15 @@ pub enum Commands {
         #[arg(short, long, help = "Automatically commit with the generated message")]
         auto_commit: bool,
 
+        /// Commit without asking for confirmation
+        #[arg(
+            short,
+            long,
+            requires = "auto_commit",
+            help = "With --auto-commit, commit without asking for confirmation"
+        )]
+        yes: bool,
+
         /// Custom instructions for this commit
         #[arg(short, long, help = "Custom instructions for this commit")]
         instructions: Option<String>,
@@ -73,10 +133,167 @@ pub enum Commands {
         /// Print the generated message to stdout and exit
         #[arg(short, long, help = "Print the generated message to stdout and exit")]
         print: bool,
+
+        /// Record the context summary as a git note on the new commit
+        #[arg(
+            long,
+            requires = "auto_commit",
+            help = "Record the context summary as a git note under refs/notes/iris (with --auto-commit)"
+        )]
+        note: bool,
+
+        /// List TODO, FIXME, and HACK comments added by the staged changes in the commit body
+        #[arg(
+            long,
+            help = "List TODO, FIXME, and HACK comments added by the staged changes in the commit body"
+        )]
+        todos: bool,
+
+        /// Write the generated message to a file instead of stdout
+        #[arg(
+            short = 'o',
+            long = "output",
+            value_name = "PATH",
+            conflicts_with_all = ["print", "auto_commit"],
+            help = "Write the generated message to a file, such as the one Git passes to prepare-commit-msg, keeping its comment lines"
+        )]
+        output: Option<String>,
+
+        /// Revise the last generated message with feedback instead of starting from scratch
+        #[arg(
+            long,
+            value_name = "FEEDBACK",
+            help = "Revise the last generated message with feedback, such as \"make it mention the API change\""
+        )]
+        refine: Option<String>,
+
+        /// Include working-tree changes to tracked files alongside the staged ones
+        #[arg(
+            long,
+            conflicts_with = "unstaged",
+            help = "Include working-tree changes to tracked files alongside the staged ones, and commit them all like 'git commit -a'"
+        )]
+        all: bool,
+
+        /// Describe only working-tree changes to tracked files that are not staged
+        #[arg(
+            long,
+            help = "Describe only working-tree changes to tracked files that are not staged, and stage them when committing"
+        )]
+        unstaged: bool,
+
+        /// Number of candidate messages to generate and page through
+        #[arg(
+            long,
+            default_value_t = 1,
+            value_parser = clap::value_parser!(u8).range(1..=5),
+            conflicts_with_all = ["print", "auto_commit", "output", "tui"],
+            help = "Generate several candidate messages (up to 5) to page through with the arrow keys"
+        )]
+        candidates: u8,
+
+        /// Review the diff and the message in a full-screen terminal UI
+        #[arg(
+            long,
+            conflicts_with_all = ["print", "auto_commit", "output"],
+            help = "Review the diff and the message side by side in a full-screen terminal UI, with keys to regenerate, edit, toggle Gitmoji, switch presets, and commit"
+        )]
+        tui: bool,
+
+        /// Choose which staged files to describe before generating
+        #[arg(
+            long,
+            conflicts_with_all = ["print", "auto_commit", "output"],
+            help = "Pick which staged files the message describes; unchecked files stay staged but their contents are left out of the prompt"
+        )]
+        select_files: bool,
+
+        /// Leave the contents of matching files out of the prompt for this run
+        #[arg(
+            long,
+            value_name = "PATTERN",
+            help = "Leave the contents of files matching this glob pattern out of the prompt (repeatable)"
+        )]
+        exclude: Vec<String>,
+
+        /// Keep the contents of matching files in the prompt for this run
+        #[arg(
+            long,
+            value_name = "PATTERN",
+            help = "Keep the contents of files matching this glob pattern in the prompt, even if they are excluded by default or in the configuration (repeatable)"
+        )]
+        include: Vec<String>,
+
+        /// Generate a Conventional Commits message with a scope inferred from the changed paths
+        #[arg(
+            long,
+            help = "Write the message in the Conventional Commits format, 'type(scope): subject', with the scope inferred from the changed paths"
+        )]
+        conventional: bool,
+
+        /// Write the message in another language for this commit
+        #[arg(long, value_name = "CODE", value_parser = language_parser, help = "Write the message in this language, such as de or fr, instead of the configured one")]
+        language: Option<String>,
+    },
+    /// Regenerate the message of the last commit and amend it
+    #[command(
+        about = "Regenerate the message of the last commit and amend it",
+        long_about = "Analyze the HEAD commit, including its diff and existing message, generate an improved message, and amend HEAD with it. Only the message changes; the commit's contents stay the same."
+    )]
+    Amend {
+        /// Custom instructions for the new message
+        #[arg(short, long, help = "Custom instructions for the new message")]
+        instructions: Option<String>,
+
+        /// Override default LLM provider
+        #[arg(long, help = "Override default LLM provider", value_parser = available_providers_parser)]
+        provider: Option<String>,
+
+        /// Disable Gitmoji for the new message
+        #[arg(long, help = "Disable Gitmoji for the new message")]
+        no_gitmoji: bool,
+
+        /// Select an instruction preset
+        #[arg(long, help = "Select an instruction preset")]
+        preset: Option<String>,
+
+        /// Print the generated message to stdout and exit without amending
+        #[arg(short, long, help = "Print the generated message to stdout and exit without amending")]
+        print: bool,
+    },
+    /// Review staged changes using AI
+    #[command(
+        about = "Review staged changes using AI",
+        long_about = "Review the staged changes before committing, reporting bugs, security issues, missing tests, and style problems with file and line references."
+    )]
+    Review {
+        /// Custom instructions for this review
+        #[arg(short, long, help = "Custom instructions for this review")]
+        instructions: Option<String>,
+
+        /// Override default LLM provider
+        #[arg(long, help = "Override default LLM provider", value_parser = available_providers_parser)]
+        provider: Option<String>,
+
+        /// Select an instruction preset
+        #[arg(long, help = "Select an instruction preset")]
+        preset: Option<String>,
     },
+    /// Set up Git-Iris with a guided wizard
+    #[command(
+        about = "Set up Git-Iris with a guided wizard",
+        long_about = "Walk through choosing a provider, entering its API key (optionally kept in the system keyring), picking a model, the Gitmoji preference, and installing the commit hook, then write the configuration."
+    )]
+    Init,
     /// Configure the AI-assisted Git commit message generator
-    #[command(about = "Configure the AI-assisted Git commit message generator")]
+    #[command(
+        about = "Configure the AI-assisted Git commit message generator",
+        args_conflicts_with_subcommands = true
+    )]
     Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+
         /// Set default LLM provider
         #[arg(long, help = "Set default LLM provider", value_parser = available_providers_parser)]
         provider: Option<String>,
@@ -100,10 +317,38 @@ pub enum Commands {
         )]
         param: Option<Vec<String>>,
 
+        /// Set the request timeout for the specified provider
+        #[arg(long, value_name = "SECONDS", help = "Give up on requests to the specified provider after this many seconds (0 to wait indefinitely)")]
+        timeout: Option<u64>,
+
+        /// Set how often failed requests to the specified provider are retried
+        #[arg(long, value_name = "COUNT", help = "Retry requests to the specified provider this many times after timeouts, connection errors, rate limits, and server errors")]
+        max_retries: Option<u32>,
+
+        /// Set the proxy for the specified provider
+        #[arg(long, value_name = "URL", help = "Send requests to the specified provider through this proxy (an empty URL removes it)")]
+        proxy: Option<String>,
+
+        /// Add a header to requests to the specified provider
+        #[arg(long, value_name = "NAME=VALUE", help = "Add a header to every request to the specified provider, such as an API gateway's authentication header; an empty VALUE removes it (repeatable)")]
+        header: Vec<String>,
+
         /// Set Gitmoji usage preference
         #[arg(long, help = "Enable or disable Gitmoji")]
         gitmoji: Option<bool>,
 
+        /// Set Gitmoji placement preference
+        #[arg(long, help = "Place the Gitmoji at the start of the body instead of the subject line")]
+        gitmoji_in_body: Option<bool>,
+
+        /// Set the file mapping change types to custom gitmojis
+        #[arg(long, value_name = "PATH", help = "Use the gitmojis from this mapping file instead of the built-in ones (empty to go back to them)")]
+        gitmoji_file: Option<String>,
+
+        /// Set how gitmojis are written
+        #[arg(long, value_name = "STYLE", help = "Write gitmojis as emoji (unicode) or as :shortcode:s (shortcode)")]
+        gitmoji_style: Option<String>,
+
         /// Set instructions for the commit message generation
         #[arg(
             short,
@@ -115,10 +360,89 @@ pub enum Commands {
         /// Set default instruction preset
         #[arg(long, help = "Set default instruction preset")]
         preset: Option<String>,
+
+        /// Add a glob pattern for files whose contents are left out of prompts
+        #[arg(
+            long,
+            help = "Leave the contents of files matching this glob pattern out of prompts (repeatable)"
+        )]
+        prompt_exclude: Vec<String>,
+
+        /// Add a glob pattern for files whose contents are kept in prompts
+        #[arg(
+            long,
+            help = "Keep the contents of files matching this glob pattern in prompts, even if they are excluded by default (repeatable)"
+        )]
+        prompt_include: Vec<String>,
+
+        /// Add a pattern for text replaced in prompts before they are sent
+        #[arg(
+            long,
+            value_name = "PATTERN",
+            help = "Replace text matching this regular expression, or glob written as glob:PATTERN, in prompts before they are sent (repeatable)"
+        )]
+        redact: Vec<String>,
+
+        /// Add a project term and what it means, for prompts to use
+        #[arg(
+            long,
+            value_name = "TERM=MEANING",
+            help = "Have generated text use TERM for MEANING, such as ACL=access control list or \"the scheduler=src/sched/\"; an empty MEANING removes the term (repeatable)"
+        )]
+        glossary: Vec<String>,
+
+        /// Set the language generated text is written in
+        #[arg(long, value_name = "CODE", value_parser = language_parser, help = "Write generated commit messages, changelogs, and release notes in this language, such as de or fr")]
+        language: Option<String>,
+
+        /// Set how many recent commits prompts include
+        #[arg(long, value_name = "COUNT", help = "Set how many recent commits are shown to the model as examples of the repository's style (0 to leave them out)")]
+        recent_commits: Option<usize>,
+
+        /// Set whether recent commits include their full messages
+        #[arg(long, value_name = "BOOL", help = "Include the full messages of recent commits (true) or only their subjects (false)")]
+        recent_commit_bodies: Option<bool>,
+
+        /// Set Conventional Commits mode for generated commit messages
+        #[arg(long, help = "Enable or disable Conventional Commits mode for generated commit messages")]
+        conventional: Option<bool>,
+
+        /// Map a glob pattern to a Conventional Commits scope
+        #[arg(
+            long,
+            value_name = "PATTERN=SCOPE",
+            help = "Use SCOPE as the Conventional Commits scope for changed files matching PATTERN (repeatable)"
+        )]
+        conventional_scope: Vec<String>,
+
+        /// Set the subject width the model is asked to stay within
+        #[arg(long, value_name = "COLUMNS", help = "Set the subject width the model is asked to stay within")]
+        subject_length: Option<usize>,
+
+        /// Set the subject width generated subjects are shortened to
+        #[arg(long, value_name = "COLUMNS", help = "Shorten generated subjects wider than this at a word boundary (0 for no limit)")]
+        max_subject_length: Option<usize>,
+
+        /// Set the column generated bodies are wrapped at
+        #[arg(long, value_name = "COLUMN", help = "Re-wrap the body of generated messages at this column (0 to leave it as generated)")]
+        wrap_body_at: Option<usize>,
+
+        /// Set whether a blank line separates the subject from the body
+        #[arg(long, help = "Always separate the subject from the body with a blank line")]
+        blank_line_after_subject: Option<bool>,
     },
     /// List available instruction presets
     #[command(about = "List available instruction presets")]
     ListPresets,
+    /// Manage user-defined instruction presets
+    #[command(
+        about = "Manage user-defined instruction presets",
+        long_about = "Add, edit, and remove your own instruction presets. Custom presets are stored in the configuration file, listed by 'list-presets', and usable with --preset on every command."
+    )]
+    Preset {
+        #[command(subcommand)]
+        action: PresetAction,
+    },
     /// Generate a changelog
     #[command(
         about = "Generate a changelog",
@@ -142,12 +466,28 @@ pub enum Commands {
         preset: Option<String>,
 
         /// Set the detail level for the changelog
-        #[arg(long, help = "Set the detail level (minimal, standard, detailed)", default_value = "standard")]
-        detail_level: String,
+        #[arg(long, help = "Set the detail level (minimal, standard, detailed); defaults to the configured one")]
+        detail_level: Option<String>,
+
+        /// Output format
+        #[arg(long, value_parser = ["markdown", "json"], help = "Output format (markdown or json); defaults to the configured one")]
+        format: Option<String>,
+
+        /// Write the changelog to a file instead of stdout
+        #[arg(short, long, value_name = "PATH", help = "Write the changelog to a file instead of stdout")]
+        output: Option<String>,
 
         /// Enable or disable Gitmoji in the changelog
         #[arg(long, help = "Enable or disable Gitmoji in the changelog")]
         gitmoji: Option<bool>,
+
+        /// Review the changelog section by section before printing it
+        #[arg(long, help = "Review the changelog section by section, editing, reordering, or dropping entries before it is printed")]
+        review: bool,
+
+        /// Write the changelog in another language
+        #[arg(long, value_name = "CODE", value_parser = language_parser, help = "Write the changelog in this language, such as de or fr, instead of the configured one")]
+        language: Option<String>,
     },
     /// Generate release notes
     #[command(
@@ -172,151 +512,1494 @@ pub enum Commands {
         preset: Option<String>,
 
         /// Set the detail level for the release notes
-        #[arg(long, help = "Set the detail level (minimal, standard, detailed)", default_value = "standard")]
-        detail_level: String,
+        #[arg(long, help = "Set the detail level (minimal, standard, detailed); defaults to the configured one")]
+        detail_level: Option<String>,
+
+        /// Output format
+        #[arg(long, value_parser = ["markdown", "json"], help = "Output format (markdown or json); defaults to the configured one")]
+        format: Option<String>,
+
+        /// Write the release notes to a file instead of stdout
+        #[arg(short, long, value_name = "PATH", help = "Write the release notes to a file instead of stdout")]
+        output: Option<String>,
 
         /// Enable or disable Gitmoji in the release notes
         #[arg(long, help = "Enable or disable Gitmoji in the release notes")]
         gitmoji: Option<bool>,
+
+        /// Discard any saved progress from an interrupted run
+        #[arg(long, help = "Discard saved progress from an interrupted run and start over")]
+        fresh: bool,
+
+        /// Write the release notes in another language
+        #[arg(long, value_name = "CODE", value_parser = language_parser, help = "Write the release notes in this language, such as de or fr, instead of the configured one")]
+        language: Option<String>,
     },
-}
+    /// Generate a migration guide between two releases
+    #[command(
+        about = "Generate a migration guide between two releases",
+        long_about = "Generate an upgrade guide between two Git references, focused on breaking changes, removed, changed, and moved public APIs, and configuration changes."
+    )]
+    MigrationGuide {
+        /// Release being upgraded from (commit hash, tag, or branch name)
+        #[arg(long, required = true)]
+        from: String,
 
-/// Define custom styles for Clap
-fn get_styles() -> Styles {
-    Styles::styled()
-        .header(AnsiColor::Magenta.on_default().bold())
-        .usage(AnsiColor::Cyan.on_default().bold())
-        .literal(AnsiColor::Green.on_default().bold())
-        .placeholder(AnsiColor::Yellow.on_default())
-        .valid(AnsiColor::Blue.on_default().bold())
-        .invalid(AnsiColor::Red.on_default().bold())
-        .error(AnsiColor::Red.on_default().bold())
-}
+        /// Release being upgraded to (commit hash, tag, or branch name). Defaults to HEAD if not specified.
+        #[arg(long)]
+        to: Option<String>,
 
-/// Parse the command-line arguments
-pub fn parse_args() -> Cli {
-    Cli::parse()
-}
+        /// Custom instructions for the migration guide
+        #[arg(short, long, help = "Custom instructions for the migration guide")]
+        instructions: Option<String>,
 
-/// Generate dynamic help including available LLM providers
-fn get_dynamic_help() -> String {
-    let providers = get_available_provider_names().join(", ");
-    format!("Available providers: {}", providers)
-}
+        /// Select an instruction preset for the migration guide
+        #[arg(long, help = "Select an instruction preset for the migration guide")]
+        preset: Option<String>,
+    },
+    /// Generate an annotated tag message
+    #[command(
+        about = "Generate an annotated tag message",
+        long_about = "Generate an annotated tag message summarizing the changes since the previous tag, and optionally create the tag."
+    )]
+    Tag {
+        /// Name of the tag to generate a message for
+        name: String,
 
-/// Validate provider input against available providers
-fn available_providers_parser(s: &str) -> Result<String, String> {
-    let available_providers = get_available_provider_names();
-    if available_providers.contains(&s.to_lowercase()) && s.to_lowercase() != "test" {
-        Ok(s.to_lowercase())
-    } else {
-        Err(format!(
-            "Invalid provider. Available providers are: {}",
-            available_providers.join(", ")
-        ))
-    }
-}
+        /// Starting Git reference. Defaults to the most recent tag.
+        #[arg(long)]
+        from: Option<String>,
 
-/// Main function to parse arguments and handle the command
-pub async fn main() -> anyhow::Result<()> {
-    let cli = parse_args();
+        /// Commit to tag. Defaults to HEAD if not specified.
+        #[arg(long)]
+        to: Option<String>,
 
-    if cli.version {
-        ui::print_version(crate_version!());
-        return Ok(());
-    }
+        /// Custom instructions for tag message generation
+        #[arg(short, long, help = "Custom instructions for tag message generation")]
+        instructions: Option<String>,
 
-    if cli.log {
-        crate::logger::enable_logging();
-    } else {
-        crate::logger::disable_logging();
-    }
+        /// Select an instruction preset for tag message generation
+        #[arg(long, help = "Select an instruction preset for tag message generation")]
+        preset: Option<String>,
 
-    match cli.command {
-        Some(command) => handle_command(command).await?,
-        None => {
-            // If no subcommand is provided, print the help
-            let _ = Cli::parse_from(&["git-iris", "--help"]);
-        }
-    }
+        /// Enable or disable Gitmoji in the tag message
+        #[arg(long, help = "Enable or disable Gitmoji in the tag message")]
+        gitmoji: Option<bool>,
 
-    Ok(())
-}
+        /// Create the annotated tag with the generated message
+        #[arg(long, help = "Create the annotated tag with the generated message")]
+        create: bool,
+    },
+    /// Generate a squash-merge title for the current branch
+    #[command(
+        about = "Generate a squash-merge title for the current branch",
+        long_about = "Generate a Conventional Commits squash-merge title (and optionally a body) from the commits on the current branch, ready to pass to 'gh pr merge --subject/--body'."
+    )]
+    PrTitle {
+        /// Base branch the pull request will be merged into
+        #[arg(long, default_value = "main", help = "Base branch the pull request will be merged into")]
+        base: String,
 
-/// Handle the command based on parsed arguments
-pub async fn handle_command(command: Commands) -> anyhow::Result<()> {
-    match command {
-        Commands::Gen {
-            auto_commit,
-            instructions,
-            provider,
-            no_gitmoji,
-            preset,
-            print,
-        } => {
-            log_debug!(
-                "Handling 'gen' command with auto_commit: {}, instructions: {:?}, provider: {:?}, no_gitmoji: {}, preset: {:?}, print: {}",
-                auto_commit,
-                instructions,
-                provider,
-                no_gitmoji,
-                preset,
-                print
-            );
+        /// Also generate a commit body
+        #[arg(long, help = "Also generate a commit body, separated from the title by a blank line")]
+        body: bool,
 
-            ui::print_version(crate_version!());
-            println!();
+        /// Custom instructions for title generation
+        #[arg(short, long, help = "Custom instructions for title generation")]
+        instructions: Option<String>,
 
-            commands::handle_gen_command(
-                !no_gitmoji,
-                provider,
-                auto_commit,
-                instructions,
-                preset,
-                print,
-            )
-            .await?;
-        }
-        Commands::Config {
-            provider,
-            api_key,
-            model,
-            param,
+        /// Select an instruction preset for title generation
+        #[arg(long, help = "Select an instruction preset for title generation")]
+        preset: Option<String>,
+    },
+    /// Generate a pull request title and description
+    #[command(
+        about = "Generate a pull request title and description",
+        long_about = "Generate a pull request title and a structured description (summary, changes, testing notes) from the commits on the current branch, and optionally open the pull request on GitHub or GitLab."
+    )]
+    Pr {
+        /// Base branch the pull request will be merged into
+        #[arg(long, default_value = "main", help = "Base branch the pull request will be merged into")]
+        base: String,
+
+        /// Custom instructions for pull request generation
+        #[arg(short, long, help = "Custom instructions for pull request generation")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for pull request generation
+        #[arg(long, help = "Select an instruction preset for pull request generation")]
+        preset: Option<String>,
+
+        /// Open the pull request on GitHub or GitLab
+        #[arg(
+            long,
+            help = "Open the pull request on GitHub or GitLab (requires GITHUB_TOKEN or GITLAB_TOKEN)"
+        )]
+        publish: bool,
+    },
+    /// Generate one commit message for a range of commits
+    #[command(
+        about = "Generate one commit message for a range of commits",
+        long_about = "Analyze all commits in a range and generate a single consolidated commit message suitable for squashing them, optionally writing it to .git/SQUASH_MSG."
+    )]
+    SquashMsg {
+        /// Starting Git reference (exclusive)
+        #[arg(long, conflicts_with = "count", help = "Starting Git reference (exclusive)")]
+        from: Option<String>,
+
+        /// Ending Git reference. Defaults to HEAD if not specified.
+        #[arg(long, help = "Ending Git reference (defaults to HEAD)")]
+        to: Option<String>,
+
+        /// Squash the last N commits before the ending reference
+        #[arg(short = 'n', long, help = "Squash the last N commits before the ending reference")]
+        count: Option<usize>,
+
+        /// Custom instructions for the message
+        #[arg(short, long, help = "Custom instructions for the message")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the message
+        #[arg(long, help = "Select an instruction preset for the message")]
+        preset: Option<String>,
+
+        /// Write the message to .git/SQUASH_MSG
+        #[arg(long, help = "Write the message to .git/SQUASH_MSG")]
+        write: bool,
+    },
+    /// Generate a merge commit message
+    #[command(
+        about = "Generate a merge commit message",
+        long_about = "Inspect the merge base, the incoming commits, and any conflicts, and generate a descriptive merge commit message. Without a branch, the merge in progress is used, which lets the command run as a prepare-commit-msg hook."
+    )]
+    MergeMsg {
+        /// Branch to merge into the current branch. Defaults to the merge in progress.
+        #[arg(help = "Branch to merge into the current branch (defaults to the merge in progress)")]
+        branch: Option<String>,
+
+        /// Custom instructions for the merge message
+        #[arg(short, long, help = "Custom instructions for the merge message")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the merge message
+        #[arg(long, help = "Select an instruction preset for the merge message")]
+        preset: Option<String>,
+
+        /// Write the message to this commit message file, as passed to prepare-commit-msg
+        #[arg(long, help = "Write the message to this commit message file (for prepare-commit-msg hooks)")]
+        message_file: Option<String>,
+
+        /// Commit message source, as passed to prepare-commit-msg. Only 'merge' generates a message.
+        #[arg(long, help = "Commit message source passed to prepare-commit-msg; only 'merge' generates a message")]
+        source: Option<String>,
+    },
+    /// Adapt a cherry-picked commit's message to the current branch
+    #[command(
+        about = "Adapt a cherry-picked commit's message to the current branch",
+        long_about = "Take the original commit's message and the current branch's context, and generate a message suited to the destination: stale issue references are dropped and the backport is noted. Without a commit, the cherry-pick in progress is used, and the prepare-commit-msg hook does this automatically."
+    )]
+    CherryPickMsg {
+        /// Commit being cherry-picked. Defaults to the cherry-pick in progress.
+        #[arg(help = "Commit being cherry-picked (defaults to the cherry-pick in progress)")]
+        commit: Option<String>,
+
+        /// Custom instructions for the message
+        #[arg(short, long, help = "Custom instructions for the message")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the message
+        #[arg(long, help = "Select an instruction preset for the message")]
+        preset: Option<String>,
+
+        /// Write the message to this commit message file
+        #[arg(long, help = "Write the message to this commit message file (for prepare-commit-msg hooks)")]
+        message_file: Option<String>,
+    },
+    /// Suggest branch names for pending changes
+    #[command(
+        about = "Suggest branch names for pending changes",
+        long_about = "Analyze staged and unstaged changes, and an optional issue description, and suggest kebab-case branch names following the configured pattern."
+    )]
+    BranchName {
+        /// Description of the issue being worked on
+        #[arg(long, help = "Description of the issue being worked on")]
+        issue: Option<String>,
+
+        /// Create and check out the chosen branch
+        #[arg(long, help = "Create and check out the chosen branch")]
+        create: bool,
+
+        /// Custom instructions for the suggestions
+        #[arg(short, long, help = "Custom instructions for the suggestions")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the suggestions
+        #[arg(long, help = "Select an instruction preset for the suggestions")]
+        preset: Option<String>,
+    },
+    /// Suggest the next semantic version
+    #[command(
+        about = "Suggest the next semantic version",
+        long_about = "Analyze the commits since the last tag, including conventional commit types, breaking-change markers, and file analysis, and suggest whether the next release is a major, minor, or patch bump."
+    )]
+    Semver {
+        /// Ending Git reference. Defaults to HEAD if not specified.
+        #[arg(long, help = "Ending Git reference (defaults to HEAD)")]
+        to: Option<String>,
+
+        /// Output format
+        #[arg(long, default_value = "text", value_parser = ["text", "json"], help = "Output format (text or json)")]
+        format: String,
+    },
+    /// List TODO, FIXME, and HACK comments added by the staged changes
+    #[command(
+        about = "List TODO, FIXME, and HACK comments added by the staged changes",
+        long_about = "Scan the staged diff for added TODO, FIXME, and HACK comments and report them with their file and line, so they don't land unnoticed."
+    )]
+    Todo {
+        /// Output format
+        #[arg(long, default_value = "text", value_parser = ["text", "json"], help = "Output format (text or json)")]
+        format: String,
+    },
+    /// Manage the prepare-commit-msg hook
+    #[command(
+        about = "Manage the prepare-commit-msg hook",
+        long_about = "Install or uninstall a prepare-commit-msg hook that fills in AI-generated commit messages whenever you run `git commit`, and optionally a commit-msg hook that lints them."
+    )]
+    Hooks {
+        #[command(subcommand)]
+        action: HooksAction,
+    },
+    /// Check commit messages against the lint rules
+    #[command(
+        about = "Check commit messages against the lint rules",
+        long_about = "Validate commit messages against the configured rules: Conventional Commits format, subject length, imperative mood, and body wrapping. Checks HEAD by default, a commit or range such as main..HEAD when given, or a message file as a commit-msg hook does."
+    )]
+    Lint {
+        /// Commit or range of commits to check. Defaults to HEAD.
+        #[arg(help = "Commit or range of commits to check, such as main..HEAD (defaults to HEAD)", conflicts_with = "message_file")]
+        range: Option<String>,
+
+        /// Check the message in a file instead of commits, as a commit-msg hook does
+        #[arg(long, help = "Check the message in a file instead of commits, as a commit-msg hook does")]
+        message_file: Option<String>,
+    },
+    /// Generate a progress report for a period
+    #[command(
+        about = "Generate a progress report for a period",
+        long_about = "Analyze the commits since a point in time, like the changelog does, and write a polished progress report with a summary, themes of work, notable changes, and metrics, as Markdown or a standalone HTML page."
+    )]
+    Report {
+        /// Start of the period, e.g. '1 week ago' or '2024-05-01'
+        #[arg(long, default_value = "1 week ago", help = "Start of the period, e.g. '1 week ago' or '2024-05-01'")]
+        since: String,
+
+        /// Only include commits by this author name or email
+        #[arg(long, help = "Only include commits by this author name or email")]
+        author: Option<String>,
+
+        /// Output format
+        #[arg(long, default_value = "md", value_parser = ["md", "html"], help = "Output format (md or html)")]
+        format: String,
+
+        /// Custom instructions for the report
+        #[arg(short, long, help = "Custom instructions for the report")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the report
+        #[arg(long, help = "Select an instruction preset for the report")]
+        preset: Option<String>,
+    },
+    /// Report statistics for a range of commits
+    #[command(
+        about = "Report statistics for a range of commits",
+        long_about = "Compute hot files, churn by directory, contributors, the distribution of commit types, and the average commit size for a range of commits, locally and without an LLM. With --narrative, an LLM-written interpretation is added."
+    )]
+    Stats {
+        /// Starting Git reference (exclusive). Defaults to the first commit.
+        #[arg(long, help = "Starting Git reference, exclusive (defaults to the whole history)")]
+        from: Option<String>,
+
+        /// Ending Git reference. Defaults to HEAD if not specified.
+        #[arg(long, help = "Ending Git reference (defaults to HEAD)")]
+        to: Option<String>,
+
+        /// Number of entries shown per section
+        #[arg(long, default_value_t = STATS_TOP, help = "Number of entries shown per section")]
+        top: usize,
+
+        /// Add an LLM-written narrative that interprets the statistics
+        #[arg(long, help = "Add an LLM-written narrative that interprets the statistics")]
+        narrative: bool,
+
+        /// Output format
+        #[arg(long, default_value = "text", value_parser = ["text", "json"], help = "Output format (text or json)")]
+        format: String,
+
+        /// Custom instructions for the narrative
+        #[arg(short, long, help = "Custom instructions for the narrative")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the narrative
+        #[arg(long, help = "Select an instruction preset for the narrative")]
+        preset: Option<String>,
+    },
+    /// Summarize your recent work
+    #[command(
+        about = "Summarize your recent work",
+        long_about = "Summarize your commits over a time window, grouped by area of the repository, as a short narrative for standups or weekly reports."
+    )]
+    Summary {
+        /// Start of the time window, e.g. 'yesterday', '1 week ago', or '2024-05-01'
+        #[arg(long, default_value = "yesterday", help = "Start of the time window, e.g. 'yesterday', '1 week ago', or '2024-05-01'")]
+        since: String,
+
+        /// Author name or email to summarize. Defaults to the configured Git user.
+        #[arg(long, help = "Author name or email to summarize (defaults to the configured Git user)")]
+        author: Option<String>,
+
+        /// Custom instructions for the summary
+        #[arg(short, long, help = "Custom instructions for the summary")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the summary
+        #[arg(long, help = "Select an instruction preset for the summary")]
+        preset: Option<String>,
+    },
+    /// Rewrite the messages of a range of commits
+    #[command(
+        about = "Rewrite the messages of a range of commits",
+        long_about = "Generate improved messages for every commit in a range of unpushed commits, preview them, and apply them with an automated rebase that leaves the code unchanged."
+    )]
+    Rewrite {
+        /// Starting Git reference (exclusive)
+        #[arg(long, required = true, help = "Starting Git reference (exclusive)")]
+        from: String,
+
+        /// Ending Git reference. Defaults to HEAD if not specified.
+        #[arg(long, help = "Ending Git reference (defaults to HEAD)")]
+        to: Option<String>,
+
+        /// Enforce the Conventional Commits format
+        #[arg(long, help = "Enforce the Conventional Commits format")]
+        conventional: bool,
+
+        /// Custom instructions for the new messages
+        #[arg(short, long, help = "Custom instructions for the new messages")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the new messages
+        #[arg(long, help = "Select an instruction preset for the new messages")]
+        preset: Option<String>,
+
+        /// Apply the new messages without asking for confirmation
+        #[arg(short, long, help = "Apply the new messages without asking for confirmation")]
+        yes: bool,
+
+        /// Rewrite commits even if they were already pushed
+        #[arg(long, help = "Rewrite commits even if they were already pushed")]
+        force: bool,
+    },
+    /// Generate a descriptive stash message
+    #[command(
+        about = "Generate a descriptive stash message",
+        long_about = "Generate a meaningful stash message from the uncommitted changes in the working tree, and optionally stash them with it, so `git stash list` shows more than \"WIP on main\"."
+    )]
+    StashMsg {
+        /// Stash the changes with the generated message
+        #[arg(long, help = "Stash the changes with the generated message (git stash push -m)")]
+        push: bool,
+
+        /// Include untracked files in the message and the stash
+        #[arg(short = 'u', long, help = "Include untracked files in the message and the stash")]
+        include_untracked: bool,
+
+        /// Custom instructions for the stash message
+        #[arg(short, long, help = "Custom instructions for the stash message")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the stash message
+        #[arg(long, help = "Select an instruction preset for the stash message")]
+        preset: Option<String>,
+    },
+    /// Generate a message for reverting a commit
+    #[command(
+        about = "Generate a message for reverting a commit",
+        long_about = "Analyze the commit being reverted and generate a revert message that explains what is undone, carrying over the context of the original message. With --apply, Git-Iris also performs the revert and commits it."
+    )]
+    Revert {
+        /// Commit to revert
+        #[arg(help = "Commit to revert")]
+        commit: String,
+
+        /// Revert the commit and commit the result with the generated message
+        #[arg(long, help = "Revert the commit and commit the result with the generated message")]
+        apply: bool,
+
+        /// Parent number to keep when reverting a merge commit
+        #[arg(short, long, help = "Parent number to keep when reverting a merge commit, starting at 1")]
+        mainline: Option<u32>,
+
+        /// Custom instructions for the revert message, such as the reason for reverting
+        #[arg(short, long, help = "Custom instructions for the revert message, such as the reason for reverting")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the revert message
+        #[arg(long, help = "Select an instruction preset for the revert message")]
+        preset: Option<String>,
+    },
+    /// Undo the last commit made by gen --auto-commit
+    #[command(
+        about = "Undo the last commit made by gen --auto-commit",
+        long_about = "Soft-reset the last commit if 'git-iris gen --auto-commit' created it, restoring the index exactly as it was before the commit. Commits made any other way are left alone."
+    )]
+    Undo {
+        /// Undo the commit even if it was already pushed
+        #[arg(long, help = "Undo the commit even if it is already on a remote branch")]
+        force: bool,
+    },
+    /// Browse and reuse previously generated commit messages
+    #[command(
+        about = "Browse and reuse previously generated commit messages",
+        long_about = "List the commit messages generated in this repository, newest first, marking those generated for the current changes. Show, compare, or commit with an earlier message instead of generating a new one."
+    )]
+    History {
+        #[command(subcommand)]
+        action: Option<HistoryAction>,
+
+        /// Number of messages to list
+        #[arg(long, default_value_t = 20, help = "Number of messages to list")]
+        limit: usize,
+    },
+    /// Suggest how to split the staged changes into several commits
+    #[command(
+        about = "Suggest how to split the staged changes into several commits",
+        long_about = "Analyze the staged diff and propose how to divide it into logically cohesive commits, grouping hunks by concern. Each group is printed with its rationale and the `git add` steps that stage it. With --apply, Git-Iris stages each group, generates its message, and creates the commits in order."
+    )]
+    Split {
+        /// Create the proposed commits one group at a time
+        #[arg(
+            long,
+            help = "Create the proposed commits one group at a time, generating a message for each and asking before every commit"
+        )]
+        apply: bool,
+
+        /// Commit every group without asking for confirmation
+        #[arg(
+            short,
+            long,
+            requires = "apply",
+            help = "Commit every group without asking for confirmation (with --apply)"
+        )]
+        yes: bool,
+
+        /// Custom instructions for the split suggestions
+        #[arg(short, long, help = "Custom instructions for the split suggestions")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the split suggestions
+        #[arg(long, help = "Select an instruction preset for the split suggestions")]
+        preset: Option<String>,
+    },
+    /// Suggest the commit the staged changes should be folded into
+    #[command(
+        about = "Suggest the commit the staged changes should be folded into",
+        long_about = "Compare the staged changes against recent commits, rank the commits they most likely belong to, and offer to create a `fixup!` commit targeting one of them for a later `git rebase --autosquash`."
+    )]
+    Fixup {
+        /// Create a fixup! commit for the top suggestion without asking
+        #[arg(long, help = "Create a fixup! commit for the top suggestion without asking")]
+        commit: bool,
+
+        /// Number of recent commits to consider
+        #[arg(
+            long,
+            default_value_t = FIXUP_MAX_COMMITS,
+            help = "Number of recent commits to consider"
+        )]
+        max_commits: usize,
+    },
+    /// Describe the current work in progress
+    #[command(
+        about = "Describe the current work in progress",
+        long_about = "Summarize every uncommitted change, staged, unstaged, or untracked, into a short narrative for end-of-day checkpoints and handoffs, and optionally commit it all as a `wip:` commit."
+    )]
+    Wip {
+        /// Commit all changes, including untracked files, with a wip: message
+        #[arg(long, help = "Commit all changes, including untracked files, with a `wip:` message")]
+        commit: bool,
+
+        /// Custom instructions for the summary
+        #[arg(short, long, help = "Custom instructions for the summary")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the summary
+        #[arg(long, help = "Select an instruction preset for the summary")]
+        preset: Option<String>,
+    },
+    /// Attach AI explanations to commits as git notes
+    #[command(
+        about = "Attach AI explanations to commits as git notes",
+        long_about = "Generate an explanation for each commit in a range and store it as a git note under refs/notes/iris, so the reasoning stays with the history. View the notes with `git log --notes=iris`."
+    )]
+    Notes {
+        /// Commit or range of commits, such as HEAD~5..HEAD
+        #[arg(help = "Commit or range of commits, such as HEAD~5..HEAD")]
+        range: String,
+
+        /// Custom instructions for the explanations
+        #[arg(short, long, help = "Custom instructions for the explanations")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the explanations
+        #[arg(long, help = "Select an instruction preset for the explanations")]
+        preset: Option<String>,
+
+        /// Regenerate notes for commits that already have one
+        #[arg(long, help = "Regenerate notes for commits that already have one")]
+        force: bool,
+    },
+    /// Serve Git-Iris tools to editors and AI agents
+    #[command(
+        about = "Serve Git-Iris tools to editors and AI agents",
+        long_about = "Expose commit message generation, changelogs, release notes, and code review as Model Context Protocol tools over stdio, so editors and AI agents can use the local repository context directly."
+    )]
+    Serve {
+        /// Speak the Model Context Protocol over stdin and stdout
+        #[arg(long, required = true, help = "Speak the Model Context Protocol over stdin and stdout")]
+        mcp: bool,
+    },
+    /// Cut a release: version bump, changelog, tag, and release notes
+    #[command(
+        about = "Cut a release: version bump, changelog, tag, and release notes",
+        long_about = "Run the full release workflow: find the last tag, compute the version bump, generate a changelog, update CHANGELOG.md, create an annotated tag, generate release notes, and optionally publish a GitHub or GitLab release. Use --dry-run to preview every step without changing anything."
+    )]
+    Release {
+        /// Version to release. Defaults to the suggested next version.
+        #[arg(
+            id = "release_version",
+            value_name = "VERSION",
+            help = "Version to release (defaults to the suggested next version)"
+        )]
+        release_version: Option<String>,
+
+        /// Override the suggested version bump
+        #[arg(long, conflicts_with = "release_version", value_parser = ["major", "minor", "patch"], help = "Override the suggested version bump")]
+        bump: Option<String>,
+
+        /// Starting Git reference. Defaults to the most recent tag.
+        #[arg(long, help = "Starting Git reference (defaults to the most recent tag)")]
+        from: Option<String>,
+
+        /// Changelog file to update
+        #[arg(long, default_value = "CHANGELOG.md", help = "Changelog file to update")]
+        changelog_file: String,
+
+        /// Set the detail level for the changelog and release notes
+        #[arg(long, help = "Set the detail level (minimal, standard, detailed); defaults to the configured ones")]
+        detail_level: Option<String>,
+
+        /// Custom instructions for the generated text
+        #[arg(short, long, help = "Custom instructions for the generated text")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the generated text
+        #[arg(long, help = "Select an instruction preset for the generated text")]
+        preset: Option<String>,
+
+        /// Enable or disable Gitmoji in the generated text
+        #[arg(long, help = "Enable or disable Gitmoji in the generated text")]
+        gitmoji: Option<bool>,
+
+        /// Push the tag and publish a GitHub or GitLab release
+        #[arg(long, help = "Push the tag and publish a GitHub or GitLab release (requires GITHUB_TOKEN or GITLAB_TOKEN)")]
+        publish: bool,
+
+        /// Review the changelog section by section before it is written
+        #[arg(long, help = "Review the changelog section by section, editing, reordering, or dropping entries before it is written")]
+        review: bool,
+
+        /// Write the generated text in another language
+        #[arg(long, value_name = "CODE", value_parser = language_parser, help = "Write the changelog, tag message, and release notes in this language, such as de or fr, instead of the configured one")]
+        language: Option<String>,
+    },
+    /// Diagnose the Git-Iris setup
+    #[command(
+        about = "Diagnose the Git-Iris setup",
+        long_about = "Check that Git is available, that the current directory is a Git repository, that the configuration is valid, that the default provider has an API key and model, whether the commit hook is installed, and that the debug log file is writable. Then check that configured LLM providers are reachable, that API keys are accepted, and that the configured models are available. Failed checks come with a suggested fix."
+    )]
+    Doctor {
+        /// Only run the provider connectivity checks
+        #[arg(long, help = "Only run the provider connectivity checks")]
+        providers: bool,
+    },
+    /// Explain an existing commit
+    #[command(
+        about = "Explain an existing commit",
+        long_about = "Analyze a single commit and explain what it does, why it likely matters, and which areas of the codebase it touches."
+    )]
+    Explain {
+        /// Commit to explain (hash, tag, or branch name)
+        #[arg(default_value = "HEAD", help = "Commit to explain (hash, tag, or branch name)")]
+        commit: String,
+
+        /// Custom instructions for the explanation
+        #[arg(short, long, help = "Custom instructions for the explanation")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the explanation
+        #[arg(long, help = "Select an instruction preset for the explanation")]
+        preset: Option<String>,
+    },
+    /// Explain a unified diff
+    #[command(
+        about = "Explain a unified diff",
+        long_about = "Read a unified diff from stdin or a file, analyze it, and explain what it does. Works with any diff, e.g. `git diff main... | git-iris explain-diff`."
+    )]
+    ExplainDiff {
+        /// Read the diff from this file instead of stdin
+        #[arg(short, long, help = "Read the diff from this file instead of stdin")]
+        file: Option<String>,
+
+        /// Custom instructions for the explanation
+        #[arg(short, long, help = "Custom instructions for the explanation")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the explanation
+        #[arg(long, help = "Select an instruction preset for the explanation")]
+        preset: Option<String>,
+    },
+    /// Explain the conflicts in the working tree
+    #[command(
+        about = "Explain the conflicts in the working tree",
+        long_about = "Find files with conflict markers, trace both sides back to the commits that changed each file, and explain what each side was trying to do, with a suggested resolution strategy per file. Works during merges, rebases, cherry-picks, and reverts."
+    )]
+    Conflicts {
+        /// Custom instructions for the explanation
+        #[arg(short, long, help = "Custom instructions for the explanation")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the explanation
+        #[arg(long, help = "Select an instruction preset for the explanation")]
+        preset: Option<String>,
+    },
+    /// Explain the history of a line or range of lines
+    #[command(
+        about = "Explain the history of a line or range of lines",
+        long_about = "Follow a line or range of lines back through the commits that touched it, like `git log -L`, and explain why the code looks the way it does and how it evolved. Lines are numbered as of HEAD."
+    )]
+    BlameExplain {
+        /// Lines to explain, as <file>:<line> or <file>:<start>-<end>
+        #[arg(help = "Lines to explain, as <file>:<line> or <file>:<start>-<end>")]
+        target: String,
+
+        /// Maximum number of commits to follow back
+        #[arg(long, default_value_t = DEFAULT_MAX_COMMITS, help = "Maximum number of commits to follow back")]
+        max_commits: usize,
+
+        /// Custom instructions for the explanation
+        #[arg(short, long, help = "Custom instructions for the explanation")]
+        instructions: Option<String>,
+
+        /// Select an instruction preset for the explanation
+        #[arg(long, help = "Select an instruction preset for the explanation")]
+        preset: Option<String>,
+    },
+}
+
+/// Define custom styles for Clap
+fn get_styles() -> Styles {
+    Styles::styled()
+        .header(AnsiColor::Magenta.on_default().bold())
+        .usage(AnsiColor::Cyan.on_default().bold())
+        .literal(AnsiColor::Green.on_default().bold())
+        .placeholder(AnsiColor::Yellow.on_default())
+        .valid(AnsiColor::Blue.on_default().bold())
+        .invalid(AnsiColor::Red.on_default().bold())
+        .error(AnsiColor::Red.on_default().bold())
+}
+
+/// Parse the command-line arguments
+pub fn parse_args() -> Cli {
+    Cli::parse()
+}
+
+/// Generate dynamic help including available LLM providers
+fn get_dynamic_help() -> String {
+    let providers = get_available_provider_names().join(", ");
+    format!("Available providers: {}", providers)
+}
+
+/// Validate provider input against available providers
+fn available_providers_parser(s: &str) -> Result<String, String> {
+    let available_providers = get_available_provider_names();
+    if available_providers.contains(&s.to_lowercase()) && s.to_lowercase() != "test" {
+        Ok(s.to_lowercase())
+    } else {
+        Err(format!(
+            "Invalid provider. Available providers are: {}",
+            available_providers.join(", ")
+        ))
+    }
+}
+
+fn language_parser(s: &str) -> Result<String, String> {
+    Language::from_str(s)
+        .map(|language| language.code.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Main function to parse arguments and handle the command
+pub async fn main() -> anyhow::Result<()> {
+    let cli = parse_args();
+
+    if cli.version {
+        ui::print_version(crate_version!());
+        return Ok(());
+    }
+
+    if cli.log {
+        crate::logger::enable_logging();
+    } else {
+        crate::logger::disable_logging();
+    }
+
+    if cli.ci || crate::ci::detect_ci_env() {
+        crate::ci::enable_ci_mode();
+    }
+
+    if let Some(profile) = cli.profile {
+        crate::config::set_profile(profile);
+    }
+    if let Some(config_path) = cli.config {
+        crate::config::set_config_path(config_path);
+    }
+
+    // For `release`, --dry-run previews each step of the release instead
+    if cli.dry_run && !matches!(cli.command, Some(Commands::Release { .. })) {
+        crate::llm::enable_dry_run();
+    }
+    if cli.show_redactions {
+        crate::llm::enable_show_redactions();
+    }
+
+    match cli.command {
+        Some(command) => match handle_command(command, cli.dry_run).await {
+            // The prompts were printed in place of a request to the provider
+            Err(e) if e.is::<DryRunComplete>() => {}
+            result => result?,
+        },
+        None => {
+            // If no subcommand is provided, print the help
+            let _ = Cli::parse_from(&["git-iris", "--help"]);
+        }
+    }
+
+    Ok(())
+}
+
+/// Actions for the 'config' command
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Show the full configuration with secrets masked
+    #[command(about = "Show the full configuration with secrets masked")]
+    Show,
+    /// Print a single setting
+    #[command(
+        about = "Print a single setting",
+        long_about = "Print a single setting by its dotted key, such as 'use_gitmoji' or 'providers.openai.model'. The value is printed unmasked, for use in scripts."
+    )]
+    Get {
+        /// Dotted key of the setting
+        key: String,
+    },
+    /// Remove a setting
+    #[command(
+        about = "Remove a setting",
+        long_about = "Remove a setting by its dotted key, such as 'instructions' or 'providers.openai.additional_params.temperature'. Required settings are reset to their default value."
+    )]
+    Unset {
+        /// Dotted key of the setting
+        key: String,
+    },
+    /// Check the configuration for problems
+    #[command(
+        about = "Check the configuration for problems",
+        long_about = "Check every setting, such as provider names, models, provider parameters, presets, the gitmoji file, and prompt templates, and report all problems at once with a suggested fix for each. Exits with an error if any are found."
+    )]
+    Validate,
+    /// Upgrade the configuration file to the current layout
+    #[command(
+        about = "Upgrade the configuration file to the current layout",
+        long_about = "Upgrade a configuration file written by an older version of Git-Iris, renaming and moving settings to where this version expects them. The changes are listed and only written after confirmation; the previous file is kept next to it with a .bak extension."
+    )]
+    Migrate {
+        /// Write the upgraded file without asking for confirmation
+        #[arg(short, long, help = "Write the upgraded file without asking for confirmation")]
+        yes: bool,
+    },
+    /// Export the settings to move them to another machine
+    #[command(
+        about = "Export the settings to move them to another machine",
+        long_about = "Write the global settings, together with your prompt templates and gitmoji mapping file, as one TOML or JSON bundle that 'config import' can apply on another machine. API keys, including those in the system keyring, are part of the bundle unless --no-secrets is given."
+    )]
+    Export {
+        /// Leave API keys and other secrets out of the bundle
+        #[arg(long, help = "Leave API keys and other secrets out, e.g. to share team defaults")]
+        no_secrets: bool,
+
+        /// Bundle format
+        #[arg(long, default_value = "toml", value_parser = ["toml", "json"], help = "Bundle format (toml or json)")]
+        format: String,
+
+        /// Write the bundle to a file instead of stdout
+        #[arg(short, long, value_name = "PATH", help = "Write the bundle to a file instead of stdout")]
+        output: Option<String>,
+    },
+    /// Apply settings exported with 'config export'
+    #[command(
+        about = "Apply settings exported with 'config export'",
+        long_about = "Apply a bundle written by 'config export', merging its settings into the current ones and writing its prompt templates and gitmoji mapping next to the configuration file. API keys already set on this machine are kept unless the bundle has its own."
+    )]
+    Import {
+        /// Bundle to import, or '-' to read it from stdin
+        #[arg(value_name = "FILE")]
+        file: String,
+
+        /// Replace all settings instead of merging
+        #[arg(long, help = "Replace all settings with the bundle's instead of merging them")]
+        replace: bool,
+    },
+}
+
+/// Actions for the 'preset' command
+#[derive(Subcommand)]
+pub enum PresetAction {
+    /// Add a custom instruction preset
+    #[command(
+        about = "Add a custom instruction preset",
+        group(ArgGroup::new("source").required(true).args(["text", "from_file"]))
+    )]
+    Add {
+        /// Name of the preset
+        name: String,
+
+        /// Instructions for the preset
+        #[arg(long, help = "Instructions for the preset")]
+        text: Option<String>,
+
+        /// Read the instructions for the preset from a file
+        #[arg(long, help = "Read the instructions for the preset from a file")]
+        from_file: Option<String>,
+
+        /// Short description shown by list-presets
+        #[arg(long, help = "Short description shown by list-presets")]
+        description: Option<String>,
+    },
+    /// Edit a custom instruction preset
+    #[command(
+        about = "Edit a custom instruction preset",
+        long_about = "Replace the instructions or description of a custom preset. Without --text, --from-file, or --description, the instructions open in $EDITOR.",
+        group(ArgGroup::new("source").args(["text", "from_file"]))
+    )]
+    Edit {
+        /// Name of the preset
+        name: String,
+
+        /// New instructions for the preset
+        #[arg(long, help = "New instructions for the preset")]
+        text: Option<String>,
+
+        /// Read the new instructions for the preset from a file
+        #[arg(long, help = "Read the new instructions for the preset from a file")]
+        from_file: Option<String>,
+
+        /// New description shown by list-presets
+        #[arg(long, help = "New description shown by list-presets")]
+        description: Option<String>,
+    },
+    /// Remove a custom instruction preset
+    #[command(about = "Remove a custom instruction preset")]
+    Remove {
+        /// Name of the preset
+        name: String,
+    },
+}
+
+/// Actions for the 'history' command
+#[derive(Subcommand)]
+pub enum HistoryAction {
+    /// Print a message from the history
+    #[command(about = "Print a message from the history")]
+    Show {
+        /// Number of the message, as listed by 'history' (1 is the newest)
+        number: usize,
+    },
+    /// Commit the staged changes with a message from the history
+    #[command(about = "Commit the staged changes with a message from the history")]
+    Use {
+        /// Number of the message, as listed by 'history' (1 is the newest)
+        number: usize,
+    },
+    /// Show how two messages from the history differ
+    #[command(about = "Show how two messages from the history differ")]
+    Diff {
+        /// Number of the older message
+        from: usize,
+
+        /// Number of the newer message
+        #[arg(default_value_t = 1)]
+        to: usize,
+    },
+    /// Forget every message in the history
+    #[command(about = "Forget every message in the history")]
+    Clear,
+}
+
+/// Actions for the 'hooks' command
+#[derive(Subcommand)]
+pub enum HooksAction {
+    /// Install the prepare-commit-msg hook
+    #[command(about = "Install the prepare-commit-msg hook")]
+    Install {
+        /// Replace an existing hook that was not installed by Git-Iris, keeping a backup
+        #[arg(long, help = "Replace an existing hook that was not installed by Git-Iris, keeping a backup")]
+        force: bool,
+
+        /// Also install a commit-msg hook that lints commit messages
+        #[arg(long, help = "Also install a commit-msg hook that lints commit messages with 'git-iris lint'")]
+        lint: bool,
+    },
+    /// Uninstall the hooks installed by Git-Iris
+    #[command(about = "Uninstall the hooks installed by Git-Iris")]
+    Uninstall,
+    /// Run the hook; called by Git with the prepare-commit-msg arguments
+    #[command(hide = true)]
+    Run {
+        /// File holding the commit message
+        message_file: String,
+
+        /// Source of the commit message
+        source: Option<String>,
+
+        /// Commit object name, for amended or reused commits
+        sha: Option<String>,
+    },
+}
+
+/// Handle the command based on parsed arguments
+pub async fn handle_command(command: Commands, dry_run: bool) -> anyhow::Result<()> {
+    match command {
+        Commands::Gen {
+            auto_commit,
+            yes,
+            instructions,
+            provider,
+            no_gitmoji,
+            preset,
+            print,
+            note,
+            todos,
+            output,
+            refine,
+            all,
+            unstaged,
+            tui,
+            candidates,
+            select_files,
+            exclude,
+            include,
+            conventional,
+            language,
+        } => {
+            log_debug!(
+                "Handling 'gen' command with auto_commit: {}, yes: {}, instructions: {:?}, provider: {:?}, no_gitmoji: {}, preset: {:?}, print: {}, note: {}, todos: {}, output: {:?}, refine: {:?}, all: {}, unstaged: {}, tui: {}, candidates: {}, select_files: {}, exclude: {:?}, include: {:?}, conventional: {}, language: {:?}",
+                auto_commit,
+                yes,
+                instructions,
+                provider,
+                no_gitmoji,
+                preset,
+                print,
+                note,
+                todos,
+                output,
+                refine,
+                all,
+                unstaged,
+                tui,
+                candidates,
+                select_files,
+                exclude,
+                include,
+                conventional,
+                language
+            );
+
+            if !crate::ci::is_ci() {
+                ui::print_version(crate_version!());
+                println!();
+            }
+
+            commands::handle_gen_command(commands::GenOptions {
+                use_gitmoji: !no_gitmoji,
+                provider,
+                auto_commit,
+                yes,
+                custom_instructions: instructions,
+                preset,
+                print,
+                note,
+                todos,
+                output,
+                refine,
+                scope: ChangeScope::from_flags(all, unstaged),
+                tui,
+                candidates: usize::from(candidates),
+                select_files,
+                exclude,
+                include,
+                conventional,
+                language,
+            })
+            .await?;
+        }
+        Commands::Review {
+            instructions,
+            provider,
+            preset,
+        } => {
+            log_debug!(
+                "Handling 'review' command with instructions: {:?}, provider: {:?}, preset: {:?}",
+                instructions,
+                provider,
+                preset
+            );
+
+            if !crate::ci::is_ci() {
+                ui::print_version(crate_version!());
+                println!();
+            }
+
+            commands::handle_review_command(provider, instructions, preset).await?;
+        }
+        Commands::Init => {
+            log_debug!("Handling 'init' command");
+            commands::handle_init_command()?;
+        }
+        Commands::Config {
+            action: Some(action),
+            ..
+        } => match action {
+            ConfigAction::Show => {
+                log_debug!("Handling 'config show' command");
+                commands::handle_config_show_command()?;
+            }
+            ConfigAction::Get { key } => {
+                log_debug!("Handling 'config get' command with key: {}", key);
+                commands::handle_config_get_command(&key)?;
+            }
+            ConfigAction::Unset { key } => {
+                log_debug!("Handling 'config unset' command with key: {}", key);
+                commands::handle_config_unset_command(&key)?;
+            }
+            ConfigAction::Validate => {
+                log_debug!("Handling 'config validate' command");
+                commands::handle_config_validate_command()?;
+            }
+            ConfigAction::Migrate { yes } => {
+                log_debug!("Handling 'config migrate' command with yes: {}", yes);
+                commands::handle_config_migrate_command(yes)?;
+            }
+            ConfigAction::Export {
+                no_secrets,
+                format,
+                output,
+            } => {
+                log_debug!(
+                    "Handling 'config export' command with no_secrets: {}, format: {}, output: {:?}",
+                    no_secrets,
+                    format,
+                    output
+                );
+                commands::handle_config_export_command(no_secrets, &format, output)?;
+            }
+            ConfigAction::Import { file, replace } => {
+                log_debug!(
+                    "Handling 'config import' command with file: {}, replace: {}",
+                    file,
+                    replace
+                );
+                commands::handle_config_import_command(&file, replace)?;
+            }
+        },
+        Commands::Config {
+            action: None,
+            provider,
+            api_key,
+            model,
+            param,
+            timeout,
+            max_retries,
+            proxy,
+            header,
             gitmoji,
+            gitmoji_in_body,
+            gitmoji_file,
+            gitmoji_style,
             instructions,
             token_limit,
             preset,
+            prompt_exclude,
+            prompt_include,
+            redact,
+            glossary,
+            language,
+            recent_commits,
+            recent_commit_bodies,
+            conventional,
+            conventional_scope,
+            subject_length,
+            max_subject_length,
+            wrap_body_at,
+            blank_line_after_subject,
         } => {
-            log_debug!("Handling 'config' command with provider: {:?}, api_key: {:?}, model: {:?}, param: {:?}, gitmoji: {:?}, instructions: {:?}, token_limit: {:?}, preset: {:?}",
-                       provider, api_key, model, param, gitmoji, instructions, token_limit, preset);
-            commands::handle_config_command(
+            log_debug!("Handling 'config' command with provider: {:?}, api_key: {:?}, model: {:?}, param: {:?}, timeout: {:?}, max_retries: {:?}, proxy: {:?}, header: {:?}, gitmoji: {:?}, gitmoji_in_body: {:?}, gitmoji_file: {:?}, gitmoji_style: {:?}, instructions: {:?}, token_limit: {:?}, preset: {:?}, prompt_exclude: {:?}, prompt_include: {:?}, redact: {:?}, glossary: {:?}, language: {:?}, recent_commits: {:?}, recent_commit_bodies: {:?}, conventional: {:?}, conventional_scope: {:?}, subject_length: {:?}, max_subject_length: {:?}, wrap_body_at: {:?}, blank_line_after_subject: {:?}",
+                       provider, api_key, model, param, timeout, max_retries, proxy, header, gitmoji, gitmoji_in_body, gitmoji_file, gitmoji_style, instructions, token_limit, preset, prompt_exclude, prompt_include, redact, glossary, language, recent_commits, recent_commit_bodies, conventional, conventional_scope, subject_length, max_subject_length, wrap_body_at, blank_line_after_subject);
+            commands::handle_config_command(commands::ConfigUpdate {
                 provider,
                 api_key,
                 model,
                 param,
+                timeout,
+                max_retries,
+                proxy,
+                header,
                 gitmoji,
+                gitmoji_in_body,
+                gitmoji_file,
+                gitmoji_style,
                 instructions,
                 token_limit,
                 preset,
-            )?;
+                prompt_exclude,
+                prompt_include,
+                redact,
+                glossary,
+                language,
+                recent_commits,
+                recent_commit_bodies,
+                conventional,
+                conventional_scope,
+                subject_length,
+                max_subject_length,
+                wrap_body_at,
+                blank_line_after_subject,
+            })?;
         }
         Commands::ListPresets => {
             log_debug!("Handling 'list_presets' command");
             commands::handle_list_presets_command()?;
         }
-        Commands::Changelog { from, to, instructions, preset, detail_level, gitmoji } => {
+        Commands::Preset { action } => match action {
+            PresetAction::Add { name, text, from_file, description } => {
+                log_debug!(
+                    "Handling 'preset add' command with name: {}, from_file: {:?}, description: {:?}",
+                    name, from_file, description
+                );
+                commands::handle_preset_add_command(name, text, from_file, description)?;
+            }
+            PresetAction::Edit { name, text, from_file, description } => {
+                log_debug!(
+                    "Handling 'preset edit' command with name: {}, from_file: {:?}, description: {:?}",
+                    name, from_file, description
+                );
+                commands::handle_preset_edit_command(name, text, from_file, description)?;
+            }
+            PresetAction::Remove { name } => {
+                log_debug!("Handling 'preset remove' command with name: {}", name);
+                commands::handle_preset_remove_command(name)?;
+            }
+        },
+        Commands::Changelog { from, to, instructions, preset, detail_level, format, output, gitmoji, review, language } => {
+            log_debug!(
+                "Handling 'changelog' command with from: {}, to: {:?}, instructions: {:?}, preset: {:?}, detail_level: {:?}, format: {:?}, output: {:?}, gitmoji: {:?}, review: {}, language: {:?}",
+                from, to, instructions, preset, detail_level, format, output, gitmoji, review, language
+            );
+            commands::handle_changelog_command(from, to, instructions, preset, detail_level, format, output, gitmoji, review, language).await?;
+        }
+        Commands::ReleaseNotes { from, to, instructions, preset, detail_level, format, output, gitmoji, fresh, language } => {
+            log_debug!(
+                "Handling 'release-notes' command with from: {}, to: {:?}, instructions: {:?}, preset: {:?}, detail_level: {:?}, format: {:?}, output: {:?}, gitmoji: {:?}, fresh: {}, language: {:?}",
+                from, to, instructions, preset, detail_level, format, output, gitmoji, fresh, language
+            );
+            commands::handle_release_notes_command(from, to, instructions, preset, detail_level, format, output, gitmoji, fresh, language).await?;
+        }
+        Commands::MigrationGuide { from, to, instructions, preset } => {
+            log_debug!(
+                "Handling 'migration-guide' command with from: {}, to: {:?}, instructions: {:?}, preset: {:?}",
+                from, to, instructions, preset
+            );
+            commands::handle_migration_guide_command(from, to, instructions, preset).await?;
+        }
+        Commands::Tag { name, from, to, instructions, preset, gitmoji, create } => {
+            log_debug!(
+                "Handling 'tag' command with name: {}, from: {:?}, to: {:?}, instructions: {:?}, preset: {:?}, gitmoji: {:?}, create: {}",
+                name, from, to, instructions, preset, gitmoji, create
+            );
+            commands::handle_tag_command(name, from, to, instructions, preset, gitmoji, create).await?;
+        }
+        Commands::PrTitle { base, body, instructions, preset } => {
+            log_debug!(
+                "Handling 'pr-title' command with base: {}, body: {}, instructions: {:?}, preset: {:?}",
+                base, body, instructions, preset
+            );
+            commands::handle_pr_title_command(base, body, instructions, preset).await?;
+        }
+        Commands::Pr { base, instructions, preset, publish } => {
+            log_debug!(
+                "Handling 'pr' command with base: {}, instructions: {:?}, preset: {:?}, publish: {}",
+                base, instructions, preset, publish
+            );
+            commands::handle_pr_command(base, instructions, preset, publish).await?;
+        }
+        Commands::SquashMsg { from, to, count, instructions, preset, write } => {
+            log_debug!(
+                "Handling 'squash-msg' command with from: {:?}, to: {:?}, count: {:?}, instructions: {:?}, preset: {:?}, write: {}",
+                from, to, count, instructions, preset, write
+            );
+            commands::handle_squash_msg_command(from, to, count, instructions, preset, write).await?;
+        }
+        Commands::MergeMsg { branch, instructions, preset, message_file, source } => {
+            log_debug!(
+                "Handling 'merge-msg' command with branch: {:?}, instructions: {:?}, preset: {:?}, message_file: {:?}, source: {:?}",
+                branch, instructions, preset, message_file, source
+            );
+            commands::handle_merge_msg_command(branch, instructions, preset, message_file, source).await?;
+        }
+        Commands::CherryPickMsg { commit, instructions, preset, message_file } => {
+            log_debug!(
+                "Handling 'cherry-pick-msg' command with commit: {:?}, instructions: {:?}, preset: {:?}, message_file: {:?}",
+                commit, instructions, preset, message_file
+            );
+            commands::handle_cherry_pick_msg_command(commit, instructions, preset, message_file).await?;
+        }
+        Commands::BranchName { issue, create, instructions, preset } => {
+            log_debug!(
+                "Handling 'branch-name' command with issue: {:?}, create: {}, instructions: {:?}, preset: {:?}",
+                issue, create, instructions, preset
+            );
+            commands::handle_branch_name_command(issue, create, instructions, preset).await?;
+        }
+        Commands::Semver { to, format } => {
+            log_debug!("Handling 'semver' command with to: {:?}, format: {}", to, format);
+            commands::handle_semver_command(to, format)?;
+        }
+        Commands::Todo { format } => {
+            log_debug!("Handling 'todo' command with format: {}", format);
+            commands::handle_todo_command(format)?;
+        }
+        Commands::Hooks { action } => match action {
+            HooksAction::Install { force, lint } => {
+                log_debug!("Handling 'hooks install' command with force: {}, lint: {}", force, lint);
+                commands::handle_hooks_install_command(force, lint)?;
+            }
+            HooksAction::Uninstall => {
+                log_debug!("Handling 'hooks uninstall' command");
+                commands::handle_hooks_uninstall_command()?;
+            }
+            HooksAction::Run { message_file, source, sha } => {
+                log_debug!(
+                    "Handling 'hooks run' command with message_file: {}, source: {:?}, sha: {:?}",
+                    message_file, source, sha
+                );
+                commands::handle_hooks_run_command(message_file, source).await?;
+            }
+        },
+        Commands::Lint { range, message_file } => {
             log_debug!(
-                "Handling 'changelog' command with from: {}, to: {:?}, instructions: {:?}, preset: {:?}, detail_level: {}, gitmoji: {:?}",
-                from, to, instructions, preset, detail_level, gitmoji
+                "Handling 'lint' command with range: {:?}, message_file: {:?}",
+                range, message_file
             );
-            commands::handle_changelog_command(from, to, instructions, preset, detail_level, gitmoji).await?;
+            commands::handle_lint_command(range, message_file)?;
         }
-        Commands::ReleaseNotes { from, to, instructions, preset, detail_level, gitmoji } => {
+        Commands::Report { since, author, format, instructions, preset } => {
+            log_debug!(
+                "Handling 'report' command with since: {}, author: {:?}, format: {}, instructions: {:?}, preset: {:?}",
+                since, author, format, instructions, preset
+            );
+            commands::handle_report_command(since, author, format, instructions, preset).await?;
+        }
+        Commands::Stats { from, to, top, narrative, format, instructions, preset } => {
+            log_debug!(
+                "Handling 'stats' command with from: {:?}, to: {:?}, top: {}, narrative: {}, format: {}, instructions: {:?}, preset: {:?}",
+                from, to, top, narrative, format, instructions, preset
+            );
+            commands::handle_stats_command(from, to, top, narrative, format, instructions, preset).await?;
+        }
+        Commands::Summary { since, author, instructions, preset } => {
+            log_debug!(
+                "Handling 'summary' command with since: {}, author: {:?}, instructions: {:?}, preset: {:?}",
+                since, author, instructions, preset
+            );
+            commands::handle_summary_command(since, author, instructions, preset).await?;
+        }
+        Commands::Rewrite { from, to, conventional, instructions, preset, yes, force } => {
+            log_debug!(
+                "Handling 'rewrite' command with from: {}, to: {:?}, conventional: {}, instructions: {:?}, preset: {:?}, yes: {}, force: {}",
+                from, to, conventional, instructions, preset, yes, force
+            );
+            commands::handle_rewrite_command(from, to, conventional, instructions, preset, yes, force).await?;
+        }
+        Commands::StashMsg { push, include_untracked, instructions, preset } => {
+            log_debug!(
+                "Handling 'stash-msg' command with push: {}, include_untracked: {}, instructions: {:?}, preset: {:?}",
+                push, include_untracked, instructions, preset
+            );
+            commands::handle_stash_msg_command(push, include_untracked, instructions, preset).await?;
+        }
+        Commands::Revert { commit, apply, mainline, instructions, preset } => {
+            log_debug!(
+                "Handling 'revert' command with commit: {}, apply: {}, mainline: {:?}, instructions: {:?}, preset: {:?}",
+                commit, apply, mainline, instructions, preset
+            );
+            commands::handle_revert_command(commit, apply, mainline, instructions, preset).await?;
+        }
+        Commands::Undo { force } => {
+            log_debug!("Handling 'undo' command with force: {}", force);
+            commands::handle_undo_command(force)?;
+        }
+        Commands::History { action, limit } => match action {
+            None => {
+                log_debug!("Handling 'history' command with limit: {}", limit);
+                commands::handle_history_list_command(limit)?;
+            }
+            Some(HistoryAction::Show { number }) => {
+                log_debug!("Handling 'history show' command with number: {}", number);
+                commands::handle_history_show_command(number)?;
+            }
+            Some(HistoryAction::Use { number }) => {
+                log_debug!("Handling 'history use' command with number: {}", number);
+                commands::handle_history_use_command(number)?;
+            }
+            Some(HistoryAction::Diff { from, to }) => {
+                log_debug!("Handling 'history diff' command with from: {}, to: {}", from, to);
+                commands::handle_history_diff_command(from, to)?;
+            }
+            Some(HistoryAction::Clear) => {
+                log_debug!("Handling 'history clear' command");
+                commands::handle_history_clear_command()?;
+            }
+        },
+        Commands::Split { apply, yes, instructions, preset } => {
+            log_debug!(
+                "Handling 'split' command with apply: {}, yes: {}, instructions: {:?}, preset: {:?}",
+                apply, yes, instructions, preset
+            );
+            commands::handle_split_command(apply, yes, instructions, preset).await?;
+        }
+        Commands::Fixup { commit, max_commits } => {
+            log_debug!(
+                "Handling 'fixup' command with commit: {}, max_commits: {}",
+                commit, max_commits
+            );
+            commands::handle_fixup_command(commit, max_commits)?;
+        }
+        Commands::Wip { commit, instructions, preset } => {
+            log_debug!(
+                "Handling 'wip' command with commit: {}, instructions: {:?}, preset: {:?}",
+                commit, instructions, preset
+            );
+            commands::handle_wip_command(commit, instructions, preset).await?;
+        }
+        Commands::Notes { range, instructions, preset, force } => {
+            log_debug!(
+                "Handling 'notes' command with range: {}, instructions: {:?}, preset: {:?}, force: {}",
+                range, instructions, preset, force
+            );
+            commands::handle_notes_command(range, instructions, preset, force).await?;
+        }
+        Commands::Serve { mcp } => {
+            log_debug!("Handling 'serve' command with mcp: {}", mcp);
+            commands::handle_serve_command().await?;
+        }
+        Commands::Release {
+            release_version,
+            bump,
+            from,
+            changelog_file,
+            detail_level,
+            instructions,
+            preset,
+            gitmoji,
+            publish,
+            review,
+            language,
+        } => {
+            log_debug!(
+                "Handling 'release' command with version: {:?}, bump: {:?}, from: {:?}, changelog_file: {}, detail_level: {:?}, instructions: {:?}, preset: {:?}, gitmoji: {:?}, publish: {}, dry_run: {}, review: {}, language: {:?}",
+                release_version, bump, from, changelog_file, detail_level, instructions, preset, gitmoji, publish, dry_run, review, language
+            );
+            commands::handle_release_command(
+                release_version,
+                bump,
+                from,
+                changelog_file,
+                detail_level,
+                instructions,
+                preset,
+                gitmoji,
+                publish,
+                dry_run,
+                review,
+                language,
+            )
+            .await?;
+        }
+        Commands::Doctor { providers } => {
+            log_debug!("Handling 'doctor' command with providers: {}", providers);
+            commands::handle_doctor_command(providers).await?;
+        }
+        Commands::Amend { instructions, provider, no_gitmoji, preset, print } => {
+            log_debug!(
+                "Handling 'amend' command with instructions: {:?}, provider: {:?}, no_gitmoji: {}, preset: {:?}, print: {}",
+                instructions, provider, no_gitmoji, preset, print
+            );
+            commands::handle_amend_command(provider, instructions, preset, !no_gitmoji, print).await?;
+        }
+        Commands::Explain { commit, instructions, preset } => {
+            log_debug!(
+                "Handling 'explain' command with commit: {}, instructions: {:?}, preset: {:?}",
+                commit, instructions, preset
+            );
+            commands::handle_explain_command(commit, instructions, preset).await?;
+        }
+        Commands::ExplainDiff { file, instructions, preset } => {
+            log_debug!(
+                "Handling 'explain-diff' command with file: {:?}, instructions: {:?}, preset: {:?}",
+                file, instructions, preset
+            );
+            commands::handle_explain_diff_command(file, instructions, preset).await?;
+        }
+        Commands::Conflicts { instructions, preset } => {
+            log_debug!(
+                "Handling 'conflicts' command with instructions: {:?}, preset: {:?}",
+                instructions, preset
+            );
+            commands::handle_conflicts_command(instructions, preset).await?;
+        }
+        Commands::BlameExplain {
+            target,
+            max_commits,
+            instructions,
+            preset,
+        } => {
             log_debug!(
-                "Handling 'release-notes' command with from: {}, to: {:?}, instructions: {:?}, preset: {:?}, detail_level: {}, gitmoji: {:?}",
-                from, to, instructions, preset, detail_level, gitmoji
+                "Handling 'blame-explain' command with target: {}, max_commits: {}, instructions: {:?}, preset: {:?}",
+                target, max_commits, instructions, preset
             );
-            commands::handle_release_notes_command(from, to, instructions, preset, detail_level, gitmoji).await?;
+            commands::handle_blame_explain_command(target, max_commits, instructions, preset)
+                .await?;
         }
     }
 
@@ -1,47 +1,141 @@
-use crate::changelog::{ChangelogGenerator, DetailLevel, ReleaseNotesGenerator};
-use crate::config::Config;
-use crate::git::get_git_info;
-use crate::instruction_presets::get_instruction_preset_library;
-use crate::interactive::InteractiveCommit;
-use crate::llm::get_refined_message;
+use crate::blame::{parse_line_target, BlameExplainer};
+use crate::branch::BranchNameGenerator;
+use crate::changelog::{
+    ChangelogGenerator, DetailLevel, OutputFormat, ReleaseNotesGenerator, TagMessageGenerator,
+};
+use crate::changelog_review::review_changelog;
+use crate::cherry_pick::{CherryPickContext, CherryPickMessageGenerator};
+use crate::commit_message::{candidate_diff, enforce_format, move_gitmoji_to_body, subject_line};
+use crate::ci::{self, FailureKind};
+use crate::config::{
+    self, Config, LintConfig, MessageFormatConfig, ProviderConfig, RecentCommitsConfig,
+};
+use crate::config_bundle::{BundleFormat, ConfigBundle};
+use crate::config_migration::{self, CONFIG_VERSION};
+use crate::config_validation::validate_all;
+use crate::conflicts::{ConflictContext, ConflictExplainer};
+use crate::context::ChangeScope;
+use crate::conventional::{self, ConventionalMode};
+use crate::doctor::{run_setup_checks, CheckStatus};
+use crate::exclusions;
+use crate::explain::{CommitExplainer, DiffExplainer};
+use crate::fixup::suggest_fixup_targets;
+use crate::git::{self, get_git_info, get_git_info_with_scope};
+use crate::gitmoji::{self, render_gitmoji, GitmojiStyle};
+use crate::glossary;
+use crate::history::{self, GenerationHistory};
+use crate::hooks::{self, HookAction};
+use crate::instruction_presets::InstructionPreset;
+use crate::interactive::{InteractiveCommit, Refinement};
+use crate::keyring;
+use crate::language::Language;
+use crate::lint::{lint_message, strip_comments};
+use crate::llm::{self, get_refined_message, ping_provider, validate_provider_config};
 use crate::llm_providers::{get_available_providers, get_provider_metadata, LLMProviderType};
 use crate::log_debug;
+use crate::mcp::McpServer;
+use crate::merge::{write_message_file, MergeContext, MergeMessageGenerator};
 use crate::messages;
+use crate::migration::{MigrationContext, MigrationGuideGenerator};
+use crate::notes::{commits_in_range, format_context_note, read_note, write_note, NOTES_REF};
+use crate::pr::{publish_pull_request, PullRequestGenerator, SquashTitleGenerator};
 use crate::prompt;
+use crate::redaction::compile_pattern;
+use crate::release::{publish_release, release_tag_name, update_changelog_file};
+use crate::report::{ReportFormat, ReportGenerator};
+use crate::revert::{revert_commit, RevertMessageGenerator, RevertOutcome};
+use crate::rewrite::{apply_rewrites, check_not_pushed, commits_to_rewrite, generate_rewrites};
+use crate::risk::{detect_risks, enabled_risk_labels, RiskLabel};
+use crate::secrets::{find_added_secrets, format_secret_warning};
+use crate::semver::{next_version, suggest_next_version, Bump};
+use crate::session::{LastGeneration, ReleaseNotesSession};
+use crate::split::{
+    format_split_plan, group_steps, unstage_all, SplitGroup, SplitPlanner, StagedHunk, StagedPatch,
+};
+use crate::stash::StashMessageGenerator;
+use crate::stats::{compute_stats, format_stats_report, StatsNarrativeGenerator};
+use crate::summary::{parse_since, WorkSummaryGenerator};
+use crate::todo::{append_todo_section, find_added_todos, TodoItem, TodoKind};
 use crate::token_optimizer::TokenOptimizer;
 use crate::ui;
+use crate::tui::{GenerationSettings, TuiCommit};
+use crate::undo::{record_auto_commit, undo_auto_commit};
+use crate::wip::{WipSummaryGenerator, WIP_PREFIX};
 use anyhow::{anyhow, Result};
 use clap::{crate_name, crate_version};
 use colored::*;
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, Password, Select};
 use std::collections::HashMap;
 use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Read};
 use std::str::FromStr;
 use std::sync::Arc; // Add this line
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Options for the 'gen' command
+#[derive(Debug)]
+pub struct GenOptions {
+    pub use_gitmoji: bool,
+    pub provider: Option<String>,
+    pub auto_commit: bool,
+    pub yes: bool,
+    pub custom_instructions: Option<String>,
+    pub preset: Option<String>,
+    pub print: bool,
+    pub note: bool,
+    pub todos: bool,
+    pub output: Option<String>,
+    pub refine: Option<String>,
+    pub scope: ChangeScope,
+    pub tui: bool,
+    pub candidates: usize,
+    pub select_files: bool,
+    pub exclude: Vec<String>,
+    pub include: Vec<String>,
+    pub conventional: bool,
+    pub language: Option<String>,
+}
 
 /// Handle the 'gen' command
-pub async fn handle_gen_command(
-    use_gitmoji: bool,
-    provider: Option<String>,
-    auto_commit: bool,
-    custom_instructions: Option<String>,
-    preset: Option<String>,
-    print: bool,
-) -> Result<()> {
-    log_debug!(
-        "Starting 'gen' command with use_gitmoji: {}, provider: {:?}, auto_commit: {}, custom_instructions: {:?}, preset: {:?}, print: {}",
+pub async fn handle_gen_command(options: GenOptions) -> Result<()> {
+    log_debug!("Starting 'gen' command with {:?}", options);
+    let GenOptions {
         use_gitmoji,
         provider,
         auto_commit,
+        yes,
         custom_instructions,
         preset,
-        print
-    );
+        print,
+        note,
+        todos,
+        output,
+        refine,
+        scope,
+        tui,
+        candidates,
+        select_files,
+        exclude,
+        include,
+        conventional,
+        language,
+    } = options;
 
-    let config = Config::load()?;
+    let mut config = Config::load()?;
+    config.recent_commits = config.recent_commits.for_command("gen");
+    config.prompt_exclude.extend(exclude);
+    config.prompt_include.extend(include);
+    config.conventional.enabled |= conventional;
+    if let Some(language) = language {
+        config.language = language;
+    }
 
     // Check environment prerequisites
     if let Err(e) = Config::check_environment() {
+        if ci::is_ci() {
+            return Err(e);
+        }
         ui::print_error(&format!("Error: {}", e));
         ui::print_info("\nPlease ensure the following:");
         ui::print_info("1. Git is installed and accessible from the command line.");
@@ -50,6 +144,13 @@ pub async fn handle_gen_command(
         return Ok(());
     }
 
+    // Refuse before spending an LLM request on a commit that can't be confirmed
+    if auto_commit && !yes && ci::is_ci() && !llm::is_dry_run() {
+        return Err(anyhow!(
+            "Refusing to commit without confirmation in CI mode. Pass --yes to commit"
+        ));
+    }
+
     let provider_type = if let Some(p) = provider {
         LLMProviderType::from_str(&p)?
     } else {
@@ -58,12 +159,24 @@ pub async fn handle_gen_command(
 
     let provider_metadata = get_provider_metadata(&provider_type);
 
-    if provider_metadata.requires_api_key {
+    // A dry run never calls the provider, so it works without an API key
+    if provider_metadata.requires_api_key && !llm::is_dry_run() {
         let provider_config = config
             .get_provider_config(&provider_type.to_string())
-            .ok_or_else(|| anyhow!("Provider '{}' not found in configuration", provider_type))?;
+            .ok_or_else(|| {
+                ci::failure(
+                    FailureKind::ConfigMissing,
+                    anyhow!("Provider '{}' not found in configuration", provider_type),
+                )
+            })?;
 
         if provider_config.api_key.is_empty() {
+            if ci::is_ci() {
+                return Err(ci::failure(
+                    FailureKind::ConfigMissing,
+                    anyhow!("API key for provider '{}' is not set", provider_type),
+                ));
+            }
             ui::print_error(&format!("API key for provider '{}' is not set. Please run 'git-iris config --provider {} --api-key YOUR_API_KEY' to set it.", provider_type, provider_type));
             return Ok(());
         }
@@ -71,24 +184,66 @@ pub async fn handle_gen_command(
 
     let current_dir = Arc::new(std::env::current_dir()?);
 
-    let message = messages::get_random_message();
-    let spinner = ui::create_spinner(&message);
+    // Revise the cached message instead of starting from scratch
+    let refinement = match refine {
+        Some(feedback) => {
+            let previous = LastGeneration::load(current_dir.as_path()).ok_or_else(|| {
+                anyhow!("No previous message to refine. Run 'git-iris gen' first")
+            })?;
+            Some(Refinement {
+                previous_message: previous.message,
+                feedback,
+            })
+        }
+        None => None,
+    };
 
-    let mut git_info = get_git_info(current_dir.as_path(), &config)?;
+    let spinner = ui::create_spinner("Scanning the repository...");
+
+    let mut git_info = get_git_info_with_scope(current_dir.as_path(), &config, scope)?;
 
     if git_info.staged_files.is_empty() {
         spinner.finish_and_clear();
+        if scope != ChangeScope::Staged {
+            if ci::is_ci() {
+                return Err(ci::failure(
+                    FailureKind::NoStagedChanges,
+                    anyhow!("No changes to tracked files"),
+                ));
+            }
+            ui::print_warning("No changes to tracked files to generate a commit message for.");
+            return Ok(());
+        }
+        if ci::is_ci() {
+            return Err(ci::failure(
+                FailureKind::NoStagedChanges,
+                anyhow!("No staged changes"),
+            ));
+        }
         ui::print_warning(
             "No staged changes. Please stage your changes before generating a commit message.",
         );
         ui::print_info("You can stage changes using 'git add <file>' or 'git add .'");
+        ui::print_info("Or include working-tree changes with 'git-iris gen --all'");
         return Ok(());
     }
+    let commit_all = scope != ChangeScope::Staged;
+    let diff_hash = history::changes_hash(&git_info.staged_files);
+
+    // In Conventional Commits mode, the lint rule reports anything the model still gets wrong
+    let conventional = config
+        .conventional
+        .enabled
+        .then(|| ConventionalMode::for_changes(&git_info.staged_files, &config.conventional));
+    if conventional.is_some() {
+        config.lint.conventional = true;
+    }
 
     let use_gitmoji = use_gitmoji && config.use_gitmoji;
+    let gitmoji_in_body = use_gitmoji && config.gitmoji_in_body;
 
     // Get instructions from preset and/or custom instructions
-    let preset_library = get_instruction_preset_library();
+    let preset_library = config.preset_library();
     let preset_key = preset.unwrap_or(config.instruction_preset.clone());
     let preset_instructions = preset_library
         .get_preset(&preset_key)
@@ -108,32 +263,275 @@ pub async fn handle_gen_command(
     // Update spinner message before generating the initial message
     spinner.set_message(messages::get_random_message());
 
+    // Warn about secrets before anything is committed, while the diffs are whole
+    let secrets = find_added_secrets(&git_info.staged_files);
+    if !secrets.is_empty() {
+        spinner.suspend(|| {
+            ui::print_warning(&format_secret_warning(
+                &secrets,
+                config.redaction.detect_secrets,
+            ));
+        });
+    }
+
+    // Scan for TODOs before token optimization truncates the diffs
+    let added_todos = if todos {
+        find_added_todos(&git_info.staged_files)
+    } else {
+        Vec::new()
+    };
+
+    // The TUI shows the full diff, before token optimization truncates it
+    let tui_diff = if tui {
+        git_info
+            .staged_files
+            .iter()
+            .map(|file| format!("File: {}\n{}", file.path, file.diff))
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        String::new()
+    };
+
+    // Unchecked files stay staged, but the prompt leaves out their contents
+    let mut excluded_files = Vec::new();
+    if select_files && !ci::is_ci() {
+        excluded_files = spinner.suspend(|| exclusions::select_prompt_files(&git_info))?;
+        if !excluded_files.is_empty() {
+            exclusions::exclude_files(&mut git_info, &excluded_files);
+            suggest_prompt_exclusions(
+                current_dir.as_path(),
+                &excluded_files,
+                &config.prompt_exclude,
+            );
+        }
+    }
+
     // Token optimization
     let token_limit = provider_metadata.default_token_limit;
     let optimizer = TokenOptimizer::new(token_limit);
     optimizer.optimize_context(&mut git_info);
 
-    let system_prompt = prompt::create_system_prompt(use_gitmoji, &combined_instructions);
+    let system_prompt = commit_system_prompt(
+        use_gitmoji,
+        &combined_instructions,
+        config.language(),
+        &config.format,
+        conventional.as_ref(),
+    );
     let user_prompt = prompt::create_user_prompt(&git_info)?;
+    let (initial_prompt, feedback) =
+        commit_message_prompt(current_dir.as_path(), &user_prompt, refinement.as_ref());
 
     // Generate the initial message
-    let initial_message = get_refined_message(
+    let initial_message = generate_commit_message(
         &config,
         &provider_type,
         &system_prompt,
-        &user_prompt,
+        &initial_prompt,
         Some(&combined_instructions),
+        conventional.as_ref(),
     )
     .await?;
+    let initial_message = if gitmoji_in_body {
+        move_gitmoji_to_body(&initial_message)
+    } else {
+        initial_message
+    };
+    let initial_message = append_todo_section(&initial_message, &added_todos);
+    save_last_generation(current_dir.as_path(), &initial_message, feedback, &diff_hash);
+
+    // Further candidates to page through in the interactive process
+    let mut extra_candidates = Vec::new();
+    for _ in 1..candidates {
+        let message = generate_commit_message(
+            &config,
+            &provider_type,
+            &system_prompt,
+            &initial_prompt,
+            Some(&combined_instructions),
+            conventional.as_ref(),
+        )
+        .await?;
+        let message = if gitmoji_in_body {
+            move_gitmoji_to_body(&message)
+        } else {
+            message
+        };
+        let message = append_todo_section(&message, &added_todos);
+        history::record_generation(current_dir.as_path(), &message, &diff_hash);
+        extra_candidates.push(message);
+    }
 
     spinner.finish_and_clear();
 
-    if print {
+    if print || output.is_some() || auto_commit {
+        warn_lint_violations(&initial_message, &config.lint);
+    }
+
+    if let Some(path) = output {
+        let comment_char = git::get_comment_char(current_dir.as_path());
+        write_message_file(std::path::Path::new(&path), &initial_message, comment_char)?;
+        ui::print_success(&format!("Wrote the generated message to {}", path));
+        return Ok(());
+    }
+
+    // CI mode never waits for input, so print the message instead of starting the interactive process
+    if print || (ci::is_ci() && !auto_commit) {
         // Print the generated message to stdout and exit
         println!("{}", initial_message);
         return Ok(());
     }
 
+    if auto_commit {
+        let mut message = initial_message;
+        if !yes {
+            loop {
+                println!("{}", "━".repeat(50).bright_purple());
+                println!("{}", message);
+                println!("{}", "━".repeat(50).bright_purple());
+                print!("{}", "Commit with this message? [y/n/e/r] ".cyan());
+                io::Write::flush(&mut io::stdout())?;
+                let mut answer = String::new();
+                io::stdin().read_line(&mut answer)?;
+                match answer.trim().to_lowercase().as_str() {
+                    "y" | "yes" => break,
+                    "n" | "no" => {
+                        ui::print_info("Commit cancelled; nothing was committed.");
+                        return Ok(());
+                    }
+                    "e" | "edit" => match edit_in_editor(&message)? {
+                        Some(edited) if !edited.trim().is_empty() => {
+                            message = edited.trim().to_string();
+                        }
+                        _ => ui::print_warning("Keeping the previous message."),
+                    },
+                    "r" | "regenerate" => {
+                        let spinner = ui::create_spinner("Regenerating the commit message...");
+                        let regenerated = generate_commit_message(
+                            &config,
+                            &provider_type,
+                            &system_prompt,
+                            &initial_prompt,
+                            Some(&combined_instructions),
+                            conventional.as_ref(),
+                        )
+                        .await?;
+                        spinner.finish_and_clear();
+                        let regenerated = if gitmoji_in_body {
+                            move_gitmoji_to_body(&regenerated)
+                        } else {
+                            regenerated
+                        };
+                        message = append_todo_section(&regenerated, &added_todos);
+                        history::record_generation(current_dir.as_path(), &message, &diff_hash);
+                        warn_lint_violations(&message, &config.lint);
+                    }
+                    _ => ui::print_warning(
+                        "Answer y to commit, n to cancel, e to edit, or r to regenerate.",
+                    ),
+                }
+            }
+        }
+
+        // Remember what was staged, so 'undo' can restore it exactly
+        let index_tree = git::write_index_tree(current_dir.as_path())?;
+        if commit_all {
+            git::commit_all(current_dir.as_path(), &message)?;
+        } else {
+            git::commit(current_dir.as_path(), &message)?;
+        }
+        record_auto_commit(current_dir.as_path(), "HEAD", &index_tree)?;
+        ui::print_success("Committed with the generated message.");
+
+        if note {
+            let summary = format_context_note(
+                &git_info,
+                &provider_type.to_string(),
+                &combined_instructions,
+            );
+            write_note(current_dir.as_path(), "HEAD", &summary)?;
+            ui::print_info(&format!("Recorded the context summary in {}", NOTES_REF));
+        }
+        return Ok(());
+    }
+
+    if tui {
+        let settings = GenerationSettings {
+            use_gitmoji,
+            preset: preset_key,
+        };
+        let presets = preset_library
+            .list_presets()
+            .into_iter()
+            .map(|(key, _)| key.clone())
+            .collect();
+        let mut tui_commit = TuiCommit::new(
+            &tui_diff,
+            initial_message,
+            settings,
+            presets,
+            crate_name!().to_string(),
+            crate_version!().to_string(),
+        );
+        tui_commit.set_commit_all(commit_all);
+        tui_commit.set_lint_config(config.lint.clone());
+
+        let config = Arc::new(config);
+        let preset_library = Arc::new(preset_library);
+        let commit_performed = tui_commit
+            .run(move |settings| {
+                let config = Arc::clone(&config);
+                let preset_library = Arc::clone(&preset_library);
+                let repo_path = Arc::clone(&current_dir);
+                let user_prompt = user_prompt.clone();
+                let custom_instructions = custom_instructions.clone();
+                let added_todos = added_todos.clone();
+                let diff_hash = diff_hash.clone();
+                let conventional = conventional.clone();
+                async move {
+                    let preset_instructions = preset_library
+                        .get_preset(&settings.preset)
+                        .map(|p| p.instructions.clone())
+                        .unwrap_or_default();
+                    let instructions = format!(
+                        "{}\n\n{}",
+                        preset_instructions.trim(),
+                        custom_instructions.trim()
+                    )
+                    .trim()
+                    .to_string();
+                    let system_prompt = commit_system_prompt(
+                        settings.use_gitmoji,
+                        &instructions,
+                        config.language(),
+                        &config.format,
+                        conventional.as_ref(),
+                    );
+                    let message = generate_commit_message(
+                        &config,
+                        &provider_type,
+                        &system_prompt,
+                        &user_prompt,
+                        Some(&instructions),
+                        conventional.as_ref(),
+                    )
+                    .await?;
+                    let message = if settings.use_gitmoji && config.gitmoji_in_body {
+                        move_gitmoji_to_body(&message)
+                    } else {
+                        message
+                    };
+                    let message = append_todo_section(&message, &added_todos);
+                    save_last_generation(&repo_path, &message, Vec::new(), &diff_hash);
+                    Ok(message)
+                }
+            })
+            .await?;
+        log_debug!("TUI finished, commit performed: {}", commit_performed);
+        return Ok(());
+    }
+
     // Initialize interactive commit process with program name and version
     let mut interactive_commit = InteractiveCommit::new(
         initial_message,
@@ -141,30 +539,75 @@ pub async fn handle_gen_command(
         crate_name!().to_string(),
         crate_version!().to_string(),
     );
+    interactive_commit.set_commit_all(commit_all);
+    // With --all or --unstaged every change is committed, so there's nothing to pick hunks from
+    interactive_commit.set_hunk_staging(!commit_all);
+    interactive_commit.set_gitmoji(use_gitmoji);
+    interactive_commit.set_lint_config(config.lint.clone());
+    for candidate in extra_candidates {
+        interactive_commit.add_candidate(candidate);
+    }
 
+    // Staging hunks changes what the message describes, so the prompt is rebuilt when it happens
+    let initial_index_tree = git::write_index_tree(current_dir.as_path())?;
     let config = Arc::new(config);
+    let repo_path = Arc::clone(&current_dir);
 
     // Run the interactive commit process
     let commit_performed = interactive_commit
-        .run(move |edited_instructions| {
+        .run(move |edited_instructions, refinement| {
             let config = Arc::clone(&config);
+            let repo_path = Arc::clone(&current_dir);
             let provider_type = provider_type.clone();
             let system_prompt = system_prompt.clone();
             let user_prompt = user_prompt.clone();
             let instructions = edited_instructions.to_string();
+            let added_todos = added_todos.clone();
+            let initial_index_tree = initial_index_tree.clone();
+            let excluded_files = excluded_files.clone();
+            let diff_hash = diff_hash.clone();
+            let conventional = conventional.clone();
             async move {
-                get_refined_message(
+                let (user_prompt, added_todos, diff_hash) =
+                    if commit_all || git::write_index_tree(&repo_path)? == initial_index_tree {
+                        (user_prompt, added_todos, diff_hash)
+                    } else {
+                        staged_changes_prompt(
+                            &repo_path,
+                            &config,
+                            &excluded_files,
+                            todos,
+                            token_limit,
+                        )?
+                    };
+                let (user_prompt, feedback) =
+                    commit_message_prompt(&repo_path, &user_prompt, refinement.as_ref());
+                let message = generate_commit_message(
                     &config,
                     &provider_type,
                     &system_prompt,
                     &user_prompt,
                     Some(&instructions),
+                    conventional.as_ref(),
                 )
-                .await
+                .await?;
+                let message = if gitmoji_in_body {
+                    move_gitmoji_to_body(&message)
+                } else {
+                    message
+                };
+                let message = append_todo_section(&message, &added_todos);
+                save_last_generation(&repo_path, &message, feedback, &diff_hash);
+                Ok(message)
             }
         })
         .await?;
 
+    let rejected = interactive_commit.rejected_candidates();
+    if !rejected.is_empty() {
+        save_candidate_selection(&repo_path, interactive_commit.selected_message(), rejected);
+    }
+
     if commit_performed {
         log_debug!("Commit successfully created and applied.");
     } else {
@@ -174,223 +617,2968 @@ pub async fn handle_gen_command(
     Ok(())
 }
 
-/// Handle the 'config' command
-pub fn handle_config_command(
-    provider: Option<String>,
-    api_key: Option<String>,
-    model: Option<String>,
-    param: Option<Vec<String>>,
-    gitmoji: Option<bool>,
-    instructions: Option<String>,
-    token_limit: Option<usize>,
-    preset: Option<String>,
-) -> Result<()> {
-    log_debug!("Starting 'config' command with provider: {:?}, api_key: {:?}, model: {:?}, param: {:?}, gitmoji: {:?}, instructions: {:?}, token_limit: {:?}, preset: {:?}",
-               provider, api_key, model, param, gitmoji, instructions, token_limit, preset);
-
-    let mut config = Config::load()?;
-    let mut changes_made = false;
+/// The system prompt for commit messages, with the configured layout and the format rules of
+/// Conventional Commits mode
+fn commit_system_prompt(
+    use_gitmoji: bool,
+    instructions: &str,
+    language: Language,
+    format: &MessageFormatConfig,
+    conventional: Option<&ConventionalMode>,
+) -> String {
+    let mut system_prompt = prompt::create_system_prompt(use_gitmoji, instructions, language);
+    if *format != MessageFormatConfig::default() {
+        system_prompt.push_str(&format!("\n\n{}", prompt::format_instructions(format)));
+    }
+    if let Some(mode) = conventional {
+        system_prompt.push_str(&format!("\n\n{}", mode.instructions()));
+    }
+    system_prompt
+}
 
-    if let Some(provider) = provider {
-        if !get_available_providers()
-            .iter()
-            .any(|p| p.to_string() == provider)
-        {
-            return Err(anyhow!("Invalid provider: {}", provider));
-        }
-        if config.default_provider != provider {
-            config.default_provider = provider.clone();
-            changes_made = true;
+/// Generate a commit message and give it the configured layout
+///
+/// In Conventional Commits mode, a message in the wrong format is sent back to the model once
+/// to be corrected, and the inferred scope replaces whichever one the model chose.
+async fn generate_commit_message(
+    config: &Config,
+    provider_type: &LLMProviderType,
+    system_prompt: &str,
+    user_prompt: &str,
+    instructions: Option<&str>,
+    conventional: Option<&ConventionalMode>,
+) -> Result<String> {
+    let message =
+        get_refined_message(config, provider_type, system_prompt, user_prompt, instructions)
+            .await?;
+    let message = match conventional {
+        Some(mode) => {
+            let message = match mode.check(&message) {
+                Some(problem) => {
+                    log_debug!("Asking for a corrected Conventional Commits message: {}", problem);
+                    let correction =
+                        conventional::correction_prompt(user_prompt, &message, &problem);
+                    get_refined_message(
+                        config,
+                        provider_type,
+                        system_prompt,
+                        &correction,
+                        instructions,
+                    )
+                    .await?
+                }
+                None => message,
+            };
+            mode.apply_scope(&message)
         }
-        if !config.providers.contains_key(&provider) {
-            config
-                .providers
-                .insert(provider.clone(), Default::default());
-            changes_made = true;
+        None => message,
+    };
+    Ok(enforce_format(&render_gitmoji(&message), &config.format))
+}
+
+/// Build the user prompt, TODO list, and history hash for the staged changes as they are now
+fn staged_changes_prompt(
+    repo_path: &std::path::Path,
+    config: &Config,
+    excluded_files: &[String],
+    todos: bool,
+    token_limit: usize,
+) -> Result<(String, Vec<TodoItem>, String)> {
+    let mut git_info = get_git_info(repo_path, config)?;
+    let diff_hash = history::changes_hash(&git_info.staged_files);
+    let added_todos = if todos {
+        find_added_todos(&git_info.staged_files)
+    } else {
+        Vec::new()
+    };
+    exclusions::exclude_files(&mut git_info, excluded_files);
+    TokenOptimizer::new(token_limit).optimize_context(&mut git_info);
+    Ok((prompt::create_user_prompt(&git_info)?, added_todos, diff_hash))
+}
+
+/// Remember the files left out of the prompt, and suggest configuring the ones left out often
+fn suggest_prompt_exclusions(
+    repo_path: &std::path::Path,
+    excluded: &[String],
+    patterns: &[String],
+) {
+    let mut history = exclusions::ExclusionHistory::load(repo_path);
+    history.record(excluded);
+    if let Err(e) = history.save(repo_path) {
+        log_debug!("Failed to save exclusion history: {}", e);
+    }
+    for path in history.suggestions(patterns) {
+        if excluded.contains(&path) {
+            ui::print_info(&format!(
+                "'{}' is often left out. To always leave it out, run: git-iris config --prompt-exclude '{}'",
+                path, path
+            ));
         }
     }
+}
 
-    let provider_config = config.providers.get_mut(&config.default_provider).unwrap();
+/// Describe where gitmojis come from and how they're written for the config output
+fn format_gitmoji_set(config: &Config) -> String {
+    let source = config
+        .gitmoji_file
+        .as_deref()
+        .filter(|file| !file.is_empty())
+        .unwrap_or("built-in");
+    format!("{}, {}", source, config.gitmoji_style)
+}
 
-    if let Some(key) = api_key {
-        if provider_config.api_key != key {
-            provider_config.api_key = key;
-            changes_made = true;
-        }
+/// Describe the recent commit settings and their per-command overrides for the config output
+fn format_recent_commits_setting(recent: &RecentCommitsConfig) -> String {
+    let describe = |count: usize, bodies: bool| match (count, bodies) {
+        (0, _) => "none".to_string(),
+        (count, true) => format!("{} (full messages)", count),
+        (count, false) => format!("{} (subjects only)", count),
+    };
+    let mut overrides: Vec<String> = recent
+        .commands
+        .keys()
+        .map(|command| {
+            let settings = recent.for_command(command);
+            format!("{}: {}", command, describe(settings.count, settings.bodies))
+        })
+        .collect();
+    overrides.sort();
+    let global = describe(recent.count, recent.bodies);
+    if overrides.is_empty() {
+        global
+    } else {
+        format!("{}; {}", global, overrides.join(", "))
     }
-    if let Some(model) = model {
-        if provider_config.model != model {
-            provider_config.model = model;
-            changes_made = true;
-        }
+}
+
+/// Describe a provider's network settings for the config output, naming headers but not
+/// showing their values
+fn format_network_settings(provider_config: &ProviderConfig) -> String {
+    let mut settings = Vec::new();
+    if let Some(timeout) = provider_config.timeout {
+        settings.push(format!("timeout {}s", timeout));
     }
-    if let Some(params) = param {
-        let additional_params = parse_additional_params(&params);
-        if provider_config.additional_params != additional_params {
-            provider_config.additional_params = additional_params;
-            changes_made = true;
-        }
+    if let Some(retries) = provider_config.max_retries {
+        settings.push(format!("{} retries", retries));
     }
-    if let Some(use_gitmoji) = gitmoji {
-        if config.use_gitmoji != use_gitmoji {
-            config.use_gitmoji = use_gitmoji;
-            changes_made = true;
-        }
+    if let Some(proxy) = &provider_config.proxy {
+        settings.push(format!("proxy {}", proxy));
     }
-    if let Some(instr) = instructions {
-        if config.instructions != instr {
-            config.instructions = instr;
-            changes_made = true;
-        }
+    if !provider_config.headers.is_empty() {
+        let mut names: Vec<&String> = provider_config.headers.keys().collect();
+        names.sort();
+        settings.push(format!(
+            "headers {}",
+            names.into_iter().cloned().collect::<Vec<_>>().join(", ")
+        ));
     }
-    if let Some(limit) = token_limit {
-        if provider_config.token_limit != Some(limit) {
-            provider_config.token_limit = Some(limit);
-            changes_made = true;
-        }
+    if settings.is_empty() {
+        "Default".to_string()
+    } else {
+        settings.join(", ")
     }
-    if let Some(preset) = preset {
-        let preset_library = get_instruction_preset_library();
-        if preset_library.get_preset(&preset).is_some() {
-            if config.instruction_preset != preset {
-                config.instruction_preset = preset;
-                changes_made = true;
-            }
-        } else {
-            return Err(anyhow!("Invalid preset: {}", preset));
-        }
+}
+
+/// List the glossary terms for the config output
+fn format_glossary(glossary: &HashMap<String, String>) -> String {
+    if glossary.is_empty() {
+        return "None".to_string();
     }
+    let mut terms: Vec<String> = glossary
+        .iter()
+        .map(|(term, meaning)| format!("{} = {}", term, meaning))
+        .collect();
+    terms.sort();
+    terms.join(", ")
+}
 
-    if changes_made {
-        config.save()?;
-        ui::print_success("Configuration updated successfully.");
+/// Describe Conventional Commits mode and its scope mappings for the config output
+fn format_conventional_setting(config: &Config) -> String {
+    if !config.conventional.enabled {
+        return "false".to_string();
+    }
+    let mut scopes: Vec<String> = config
+        .conventional
+        .scopes
+        .iter()
+        .map(|(pattern, scope)| format!("{} → {}", pattern, scope))
+        .collect();
+    if scopes.is_empty() {
+        return "true".to_string();
     }
+    scopes.sort();
+    format!("true (scopes: {})", scopes.join(", "))
+}
 
-    ui::print_info(&format!(
-        "Current configuration:\nDefault Provider: {}\nUse Gitmoji: {}\nInstructions: {}\nInstruction Preset: {}",
-        config.default_provider,
-        config.use_gitmoji,
-        if config.instructions.is_empty() {
-            "None".to_string()
-        } else {
-            config.instructions.replace('\n', ", ")
-        },
-        config.instruction_preset
-    ));
-    for (provider, provider_config) in &config.providers {
-        ui::print_info(&format!(
-            "\nProvider: {}\nAPI Key: {}\nModel: {}\nToken Limit: {}\nAdditional Parameters: {:?}",
-            provider,
-            if provider_config.api_key.is_empty() {
-                "Not set"
-            } else {
-                "Set"
-            },
-            provider_config.model,
-            provider_config
-                .token_limit
-                .map_or("Default".to_string(), |limit| limit.to_string()),
-            provider_config.additional_params
-        ));
+/// Describe the layout enforced on generated messages for the config output
+fn format_message_layout(format: &MessageFormatConfig) -> String {
+    let subject = if format.max_subject_length == 0 {
+        format!("subject {} columns", format.subject_length)
+    } else {
+        format!(
+            "subject {} columns (shortened beyond {})",
+            format.subject_length, format.max_subject_length
+        )
+    };
+    let body = if format.wrap_body_at == 0 {
+        "body left as generated".to_string()
+    } else {
+        format!("body wrapped at {}", format.wrap_body_at)
+    };
+    let separator = if format.blank_line_after_subject {
+        "blank line after subject"
+    } else {
+        "no blank line required"
+    };
+    format!("{}, {}, {}", subject, body, separator)
+}
+
+/// Report lint problems in a generated message on stderr, keeping stdout for the message itself
+fn warn_lint_violations(message: &str, lint: &LintConfig) {
+    for violation in lint_message(message, lint) {
+        eprintln!("{}", format!("⚠️  {}", violation.message).yellow().bold());
     }
+}
 
-    Ok(())
+/// Build the user prompt for a commit message and the feedback the result will reflect
+///
+/// With a refinement, the model is asked to revise the previous message, keeping any feedback
+/// that message was already refined with.
+fn commit_message_prompt(
+    repo_path: &std::path::Path,
+    user_prompt: &str,
+    refinement: Option<&Refinement>,
+) -> (String, Vec<String>) {
+    match refinement {
+        Some(refinement) => {
+            let mut feedback = LastGeneration::feedback_for(repo_path, &refinement.previous_message);
+            let mut prompt = prompt::create_refine_user_prompt(
+                user_prompt,
+                &refinement.previous_message,
+                &feedback,
+                &refinement.feedback,
+            );
+            prompt::append_rejected_candidates(
+                &mut prompt,
+                &LastGeneration::rejected_for(repo_path, &refinement.previous_message),
+            );
+            feedback.push(refinement.feedback.clone());
+            (prompt, feedback)
+        }
+        None => (user_prompt.to_string(), Vec::new()),
+    }
 }
 
-pub async fn handle_changelog_command(
-    from: String,
-    to: Option<String>,
-    instructions: Option<String>,
+/// Cache a generated message so a later `gen --refine` can revise it, and add it to the history
+fn save_last_generation(
+    repo_path: &std::path::Path,
+    message: &str,
+    feedback: Vec<String>,
+    diff_hash: &str,
+) {
+    history::record_generation(repo_path, message, diff_hash);
+    let generation = LastGeneration {
+        message: message.to_string(),
+        feedback,
+        rejected: Vec::new(),
+    };
+    if let Err(e) = generation.save(repo_path) {
+        log_debug!("Failed to save the last generation: {}", e);
+    }
+}
+
+/// Remember which of several candidates was chosen, so `gen --refine` revises it
+/// and steers clear of the others
+fn save_candidate_selection(repo_path: &std::path::Path, selected: &str, rejected: Vec<String>) {
+    let generation = LastGeneration {
+        message: selected.to_string(),
+        feedback: LastGeneration::feedback_for(repo_path, selected),
+        rejected,
+    };
+    if let Err(e) = generation.save(repo_path) {
+        log_debug!("Failed to save the candidate selection: {}", e);
+    }
+}
+
+/// Handle the 'amend' command
+pub async fn handle_amend_command(
+    provider: Option<String>,
+    custom_instructions: Option<String>,
     preset: Option<String>,
-    detail_level: String,
-    gitmoji: Option<bool>,
+    use_gitmoji: bool,
+    print: bool,
 ) -> Result<()> {
+    log_debug!(
+        "Starting 'amend' command with provider: {:?}, custom_instructions: {:?}, preset: {:?}, use_gitmoji: {}, print: {}",
+        provider,
+        custom_instructions,
+        preset,
+        use_gitmoji,
+        print
+    );
+
     let mut config = Config::load()?;
-    let spinner = ui::create_spinner("Generating changelog...");
+    config.recent_commits = config.recent_commits.for_command("amend");
+
+    if let Err(e) = Config::check_environment() {
+        if ci::is_ci() {
+            return Err(e);
+        }
+        ui::print_error(&format!("Error: {}", e));
+        return Ok(());
+    }
+
+    let provider_type =
+        LLMProviderType::from_str(provider.as_deref().unwrap_or(&config.default_provider))?;
+
+    if let Err(e) = validate_provider_config(&config, &provider_type) {
+        if ci::is_ci() {
+            return Err(e);
+        }
+        ui::print_error(&format!("{}. Please run 'git-iris config --provider {} --api-key YOUR_API_KEY' to set it.", e, provider_type));
+        return Ok(());
+    }
+
+    let spinner = ui::create_spinner(&messages::get_random_message());
 
     let repo_path = env::current_dir()?;
-    let to = to.unwrap_or_else(|| "HEAD".to_string());
+    let mut git_info = git::get_commit_info(
+        &repo_path,
+        "HEAD",
+        &config.recent_commits,
+        &exclusions::PromptFilter::from_config(&config),
+    )?;
+    let existing_message = git::get_commit_message(&repo_path, "HEAD")?;
 
     // Set temporary instructions and preset
-    config.set_temp_instructions(instructions);
+    config.set_temp_instructions(custom_instructions);
     config.set_temp_preset(preset);
+    let instructions = config.get_effective_instructions();
 
-    // Parse detail level
-    let detail_level = DetailLevel::from_str(&detail_level)?;
+    let use_gitmoji = use_gitmoji && config.use_gitmoji;
+    let gitmoji_in_body = use_gitmoji && config.gitmoji_in_body;
 
-    // Override gitmoji setting if provided
-    if let Some(use_gitmoji) = gitmoji {
-        config.use_gitmoji = use_gitmoji;
-    }
+    // Token optimization
+    let token_limit = get_provider_metadata(&provider_type).default_token_limit;
+    let optimizer = TokenOptimizer::new(token_limit);
+    optimizer.optimize_context(&mut git_info);
 
-    let changelog =
-        ChangelogGenerator::generate(&repo_path, &from, &to, &config, detail_level).await?;
+    let conventional = config
+        .conventional
+        .enabled
+        .then(|| ConventionalMode::for_changes(&git_info.staged_files, &config.conventional));
+    if conventional.is_some() {
+        config.lint.conventional = true;
+    }
 
-    spinner.finish_and_clear();
+    let system_prompt = commit_system_prompt(
+        use_gitmoji,
+        "",
+        config.language(),
+        &config.format,
+        conventional.as_ref(),
+    );
+    let user_prompt = prompt::create_amend_user_prompt(&git_info, &existing_message)?;
 
+    let initial_message = generate_commit_message(
+        &config,
+        &provider_type,
+        &system_prompt,
+        &user_prompt,
+        Some(&instructions),
+        conventional.as_ref(),
+    )
+    .await?;
+    let initial_message = if gitmoji_in_body {
+        move_gitmoji_to_body(&initial_message)
+    } else {
+        initial_message
+    };
+
+    spinner.finish_and_clear();
+
+    // CI mode never waits for input, so print the message instead of starting the interactive process
+    if print || ci::is_ci() {
+        println!("{}", initial_message);
+        return Ok(());
+    }
+
+    let mut interactive_commit = InteractiveCommit::new(
+        initial_message,
+        instructions,
+        crate_name!().to_string(),
+        crate_version!().to_string(),
+    );
+    interactive_commit.set_amend(true);
+    interactive_commit.set_gitmoji(use_gitmoji);
+    interactive_commit.set_lint_config(config.lint.clone());
+
+    let config = Arc::new(config);
+
+    let amend_performed = interactive_commit
+        .run(move |edited_instructions, refinement| {
+            let config = Arc::clone(&config);
+            let provider_type = provider_type.clone();
+            let system_prompt = system_prompt.clone();
+            let user_prompt = match refinement {
+                Some(refinement) => prompt::create_refine_user_prompt(
+                    &user_prompt,
+                    &refinement.previous_message,
+                    &[],
+                    &refinement.feedback,
+                ),
+                None => user_prompt.clone(),
+            };
+            let instructions = edited_instructions.to_string();
+            let conventional = conventional.clone();
+            async move {
+                let message = generate_commit_message(
+                    &config,
+                    &provider_type,
+                    &system_prompt,
+                    &user_prompt,
+                    Some(&instructions),
+                    conventional.as_ref(),
+                )
+                .await?;
+                if gitmoji_in_body {
+                    Ok(move_gitmoji_to_body(&message))
+                } else {
+                    Ok(message)
+                }
+            }
+        })
+        .await?;
+
+    if amend_performed {
+        log_debug!("HEAD commit message successfully amended.");
+    } else {
+        log_debug!("Amend process cancelled.");
+    }
+
+    Ok(())
+}
+
+/// Handle the 'review' command
+pub async fn handle_review_command(
+    provider: Option<String>,
+    custom_instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    log_debug!(
+        "Starting 'review' command with provider: {:?}, custom_instructions: {:?}, preset: {:?}",
+        provider,
+        custom_instructions,
+        preset
+    );
+
+    let mut config = Config::load()?;
+    config.recent_commits = config.recent_commits.for_command("review");
+
+    if let Err(e) = Config::check_environment() {
+        if ci::is_ci() {
+            return Err(e);
+        }
+        ui::print_error(&format!("Error: {}", e));
+        return Ok(());
+    }
+
+    let provider_type =
+        LLMProviderType::from_str(provider.as_deref().unwrap_or(&config.default_provider))?;
+
+    if let Err(e) = validate_provider_config(&config, &provider_type) {
+        if ci::is_ci() {
+            return Err(e);
+        }
+        ui::print_error(&format!("{}. Please run 'git-iris config --provider {} --api-key YOUR_API_KEY' to set it.", e, provider_type));
+        return Ok(());
+    }
+
+    let spinner = ui::create_spinner("Reviewing staged changes...");
+
+    let repo_path = env::current_dir()?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(custom_instructions);
+    config.set_temp_preset(preset);
+
+    let Some(StagedReview { review, file_risks }) =
+        generate_staged_review(&repo_path, &config, &provider_type).await?
+    else {
+        spinner.finish_and_clear();
+        if ci::is_ci() {
+            return Err(ci::failure(
+                FailureKind::NoStagedChanges,
+                anyhow!("No staged changes"),
+            ));
+        }
+        ui::print_warning("No staged changes. Please stage the changes you want reviewed.");
+        ui::print_info("You can stage changes using 'git add <file>' or 'git add .'");
+        return Ok(());
+    };
+
+    spinner.finish_and_clear();
+
+    println!("{}", "\n🔍 Code Review".bright_purple().bold());
+    println!("{}", "━".repeat(50).bright_purple());
+    for (path, labels) in &file_risks {
+        let badges: Vec<String> = labels
+            .iter()
+            .map(|label| format!("[{}]", label.badge()))
+            .collect();
+        println!("{} {}", badges.join(" ").bright_yellow().bold(), path.cyan());
+    }
+    if !file_risks.is_empty() {
+        println!();
+    }
+    println!("{}", review);
+    println!("{}", "━".repeat(50).bright_purple());
+
+    Ok(())
+}
+
+/// A review of the staged changes, with the risk labels found per file
+pub(crate) struct StagedReview {
+    pub review: String,
+    pub file_risks: Vec<(String, Vec<RiskLabel>)>,
+}
+
+/// Review the staged changes without any interaction, or return None if nothing is staged
+pub(crate) async fn generate_staged_review(
+    repo_path: &std::path::Path,
+    config: &Config,
+    provider_type: &LLMProviderType,
+) -> Result<Option<StagedReview>> {
+    let mut git_info = get_git_info(repo_path, config)?;
+    if git_info.staged_files.is_empty() {
+        return Ok(None);
+    }
+
+    let instructions = config.get_effective_instructions();
+
+    // Token optimization
+    let token_limit = get_provider_metadata(provider_type).default_token_limit;
+    let optimizer = TokenOptimizer::new(token_limit);
+    optimizer.optimize_context(&mut git_info);
+
+    // Derive risk labels from the analyzer results
+    let enabled_risks = enabled_risk_labels(repo_path, config);
+    let file_risks: Vec<(String, Vec<RiskLabel>)> = git_info
+        .staged_files
+        .iter()
+        .map(|file| {
+            let mut labels = detect_risks(&file.path, &file.diff, &file.analysis);
+            labels.retain(|label| enabled_risks.contains(label));
+            (file.path.clone(), labels)
+        })
+        .filter(|(_, labels)| !labels.is_empty())
+        .collect();
+
+    let system_prompt = prompt::create_review_system_prompt(&instructions);
+    let mut user_prompt = prompt::create_review_user_prompt(&git_info)?;
+    if !file_risks.is_empty() {
+        user_prompt.push_str("\n\nRisk labels (review these files with extra care):\n");
+        for (path, labels) in &file_risks {
+            let keys: Vec<String> = labels.iter().map(|label| label.to_string()).collect();
+            user_prompt.push_str(&format!("{}: {}\n", path, keys.join(", ")));
+        }
+    }
+
+    let review =
+        get_refined_message(config, provider_type, &system_prompt, &user_prompt, None).await?;
+    Ok(Some(StagedReview { review, file_risks }))
+}
+
+/// Settings changed by the 'config' command; each one left unset keeps its current value
+#[derive(Debug)]
+pub struct ConfigUpdate {
+    pub provider: Option<String>,
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+    pub param: Option<Vec<String>>,
+    pub timeout: Option<u64>,
+    pub max_retries: Option<u32>,
+    pub proxy: Option<String>,
+    pub header: Vec<String>,
+    pub gitmoji: Option<bool>,
+    pub gitmoji_in_body: Option<bool>,
+    pub gitmoji_file: Option<String>,
+    pub gitmoji_style: Option<String>,
+    pub instructions: Option<String>,
+    pub token_limit: Option<usize>,
+    pub preset: Option<String>,
+    pub prompt_exclude: Vec<String>,
+    pub prompt_include: Vec<String>,
+    pub redact: Vec<String>,
+    pub glossary: Vec<String>,
+    pub language: Option<String>,
+    pub recent_commits: Option<usize>,
+    pub recent_commit_bodies: Option<bool>,
+    pub conventional: Option<bool>,
+    pub conventional_scope: Vec<String>,
+    pub subject_length: Option<usize>,
+    pub max_subject_length: Option<usize>,
+    pub wrap_body_at: Option<usize>,
+    pub blank_line_after_subject: Option<bool>,
+}
+
+/// Handle the 'config' command
+pub fn handle_config_command(update: ConfigUpdate) -> Result<()> {
+    log_debug!("Starting 'config' command with {:?}", update);
+    let ConfigUpdate {
+        provider,
+        api_key,
+        model,
+        param,
+        timeout,
+        max_retries,
+        proxy,
+        header,
+        gitmoji,
+        gitmoji_in_body,
+        gitmoji_file,
+        gitmoji_style,
+        instructions,
+        token_limit,
+        preset,
+        prompt_exclude,
+        prompt_include,
+        redact,
+        glossary,
+        language,
+        recent_commits,
+        recent_commit_bodies,
+        conventional,
+        conventional_scope,
+        subject_length,
+        max_subject_length,
+        wrap_body_at,
+        blank_line_after_subject,
+    } = update;
+
+    let mut config = Config::load()?;
+    let mut changes_made = false;
+
+    if let Some(provider) = provider {
+        if !get_available_providers()
+            .iter()
+            .any(|p| p.to_string() == provider)
+        {
+            return Err(anyhow!("Invalid provider: {}", provider));
+        }
+        if config.default_provider != provider {
+            config.default_provider = provider.clone();
+            changes_made = true;
+        }
+        if !config.providers.contains_key(&provider) {
+            config
+                .providers
+                .insert(provider.clone(), Default::default());
+            changes_made = true;
+        }
+    }
+
+    let provider_config = config
+        .providers
+        .get_mut(&config.default_provider)
+        .ok_or_else(|| {
+            anyhow!(
+                "The default provider '{}' has no settings. Choose a provider with --provider, or run 'git-iris config validate' for details",
+                config.default_provider
+            )
+        })?;
+
+    if let Some(key) = api_key {
+        if provider_config.api_key != key {
+            provider_config.api_key = key;
+            changes_made = true;
+        }
+    }
+    if let Some(model) = model {
+        if provider_config.model != model {
+            provider_config.model = model;
+            changes_made = true;
+        }
+    }
+    if let Some(params) = param {
+        let additional_params = parse_additional_params(&params)?;
+        if provider_config.additional_params != additional_params {
+            provider_config.additional_params = additional_params;
+            changes_made = true;
+        }
+    }
+    if let Some(seconds) = timeout {
+        let timeout = Some(seconds).filter(|seconds| *seconds > 0);
+        if provider_config.timeout != timeout {
+            provider_config.timeout = timeout;
+            changes_made = true;
+        }
+    }
+    if let Some(retries) = max_retries {
+        if provider_config.max_retries != Some(retries) {
+            provider_config.max_retries = Some(retries);
+            changes_made = true;
+        }
+    }
+    if let Some(url) = proxy {
+        let proxy = Some(url.trim().to_string()).filter(|url| !url.is_empty());
+        if provider_config.proxy != proxy {
+            provider_config.proxy = proxy;
+            changes_made = true;
+        }
+    }
+    for entry in header {
+        let (name, value) = entry
+            .split_once('=')
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .filter(|(name, _)| !name.is_empty())
+            .ok_or_else(|| anyhow!("Invalid header '{}'. Use NAME=VALUE", entry))?;
+        if value.is_empty() {
+            changes_made |= provider_config.headers.remove(&name).is_some();
+        } else if provider_config.headers.get(&name) != Some(&value) {
+            provider_config.headers.insert(name, value);
+            changes_made = true;
+        }
+    }
+    // Fail on an invalid proxy URL or header now rather than on the next request
+    provider_config.network_settings().client()?;
+    if let Some(use_gitmoji) = gitmoji {
+        if config.use_gitmoji != use_gitmoji {
+            config.use_gitmoji = use_gitmoji;
+            changes_made = true;
+        }
+    }
+    if let Some(in_body) = gitmoji_in_body {
+        if config.gitmoji_in_body != in_body {
+            config.gitmoji_in_body = in_body;
+            changes_made = true;
+        }
+    }
+    let mut gitmoji_file_changed = false;
+    if let Some(file) = gitmoji_file {
+        let file = Some(file).filter(|file| !file.is_empty());
+        if config.gitmoji_file != file {
+            config.gitmoji_file = file;
+            gitmoji_file_changed = true;
+            changes_made = true;
+        }
+    }
+    if let Some(style) = gitmoji_style {
+        let style = GitmojiStyle::from_str(&style)?;
+        if config.gitmoji_style != style {
+            config.gitmoji_style = style;
+            changes_made = true;
+        }
+    }
+    if let Some(instr) = instructions {
+        if config.instructions != instr {
+            config.instructions = instr;
+            changes_made = true;
+        }
+    }
+    if let Some(limit) = token_limit {
+        if provider_config.token_limit != Some(limit) {
+            provider_config.token_limit = Some(limit);
+            changes_made = true;
+        }
+    }
+    if let Some(preset) = preset {
+        let preset_library = config.preset_library();
+        if preset_library.get_preset(&preset).is_some() {
+            if config.instruction_preset != preset {
+                config.instruction_preset = preset;
+                changes_made = true;
+            }
+        } else {
+            return Err(anyhow!("Invalid preset: {}", preset));
+        }
+    }
+    for pattern in prompt_exclude {
+        if !config.prompt_exclude.contains(&pattern) {
+            config.prompt_exclude.push(pattern);
+            changes_made = true;
+        }
+    }
+    for pattern in prompt_include {
+        if !config.prompt_include.contains(&pattern) {
+            config.prompt_include.push(pattern);
+            changes_made = true;
+        }
+    }
+    for pattern in redact {
+        compile_pattern(&pattern)?;
+        if !config.redaction.patterns.contains(&pattern) {
+            config.redaction.patterns.push(pattern);
+            changes_made = true;
+        }
+    }
+    for entry in glossary {
+        let (term, meaning) = glossary::parse_entry(&entry)?;
+        if meaning.is_empty() {
+            changes_made |= config.glossary.remove(&term).is_some();
+        } else if config.glossary.get(&term) != Some(&meaning) {
+            config.glossary.insert(term, meaning);
+            changes_made = true;
+        }
+    }
+    if let Some(language) = language {
+        if config.language != language {
+            config.language = language;
+            changes_made = true;
+        }
+    }
+    if let Some(count) = recent_commits {
+        if config.recent_commits.count != count {
+            config.recent_commits.count = count;
+            changes_made = true;
+        }
+    }
+    if let Some(bodies) = recent_commit_bodies {
+        if config.recent_commits.bodies != bodies {
+            config.recent_commits.bodies = bodies;
+            changes_made = true;
+        }
+    }
+    if let Some(enabled) = conventional {
+        if config.conventional.enabled != enabled {
+            config.conventional.enabled = enabled;
+            changes_made = true;
+        }
+    }
+    for mapping in conventional_scope {
+        let (pattern, scope) = mapping
+            .split_once('=')
+            .map(|(pattern, scope)| (pattern.trim(), scope.trim()))
+            .filter(|(pattern, scope)| !pattern.is_empty() && !scope.is_empty())
+            .ok_or_else(|| anyhow!("Invalid scope mapping '{}'. Use PATTERN=SCOPE", mapping))?;
+        if config.conventional.scopes.get(pattern).map(String::as_str) != Some(scope) {
+            config
+                .conventional
+                .scopes
+                .insert(pattern.to_string(), scope.to_string());
+            changes_made = true;
+        }
+    }
+    if let Some(length) = subject_length {
+        if length == 0 {
+            return Err(anyhow!("The subject length must be at least 1"));
+        }
+        if config.format.subject_length != length {
+            config.format.subject_length = length;
+            changes_made = true;
+        }
+    }
+    if let Some(length) = max_subject_length {
+        if config.format.max_subject_length != length {
+            config.format.max_subject_length = length;
+            changes_made = true;
+        }
+    }
+    if let Some(column) = wrap_body_at {
+        if config.format.wrap_body_at != column {
+            config.format.wrap_body_at = column;
+            changes_made = true;
+        }
+    }
+    if let Some(blank_line) = blank_line_after_subject {
+        if config.format.blank_line_after_subject != blank_line {
+            config.format.blank_line_after_subject = blank_line;
+            changes_made = true;
+        }
+    }
+
+    if changes_made {
+        // Check a new mapping file now rather than warning about it on every later run
+        if let Some(path) = config.gitmoji_file_path()?.filter(|_| gitmoji_file_changed) {
+            gitmoji::load_mapping(&path)?;
+        }
+        config.save()?;
+        ui::print_success("Configuration updated successfully.");
+    }
+
+    if let Some(profile) = &config.active_profile {
+        ui::print_info(&format!("Profile: {}", profile));
+    }
+    ui::print_info(&format!(
+        "Current configuration:\nDefault Provider: {}\nUse Gitmoji: {}\nGitmoji in Body: {}\nGitmoji Set: {}\nConventional Commits: {}\nMessage Layout: {}\nInstructions: {}\nInstruction Preset: {}\nPrompt Exclusions: {}\nPrompt Inclusions: {}\nRedactions: {}\nGlossary: {}\nLanguage: {}\nRecent Commits: {}",
+        config.default_provider,
+        config.use_gitmoji,
+        config.gitmoji_in_body,
+        format_gitmoji_set(&config),
+        format_conventional_setting(&config),
+        format_message_layout(&config.format),
+        if config.instructions.is_empty() {
+            "None".to_string()
+        } else {
+            config.instructions.replace('\n', ", ")
+        },
+        config.instruction_preset,
+        if config.prompt_exclude.is_empty() {
+            "None".to_string()
+        } else {
+            config.prompt_exclude.join(", ")
+        },
+        if config.prompt_include.is_empty() {
+            "None".to_string()
+        } else {
+            config.prompt_include.join(", ")
+        },
+        if config.redaction.patterns.is_empty() {
+            "None".to_string()
+        } else {
+            config.redaction.patterns.join(", ")
+        },
+        format_glossary(&config.glossary),
+        config.language(),
+        format_recent_commits_setting(&config.recent_commits)
+    ));
+    for (provider, provider_config) in &config.providers {
+        ui::print_info(&format!(
+            "\nProvider: {}\nAPI Key: {}\nModel: {}\nToken Limit: {}\nAdditional Parameters: {:?}\nNetwork: {}",
+            provider,
+            if provider_config.api_key.is_empty() {
+                "Not set"
+            } else if provider_config.keyring {
+                "Set (system keyring)"
+            } else {
+                "Set"
+            },
+            provider_config.model,
+            provider_config
+                .token_limit
+                .map_or("Default".to_string(), |limit| limit.to_string()),
+            provider_config.additional_params,
+            format_network_settings(provider_config)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Handle the 'init' command, a guided setup for first-time users
+pub fn handle_init_command() -> Result<()> {
+    if ci::is_ci() {
+        return Err(anyhow!(
+            "'init' asks questions and can't run in CI mode. Use 'git-iris config' instead"
+        ));
+    }
+
+    let mut config = Config::load()?;
+    let theme = ColorfulTheme::default();
+    println!(
+        "{}",
+        ui::create_gradient_text("Welcome to Git-Iris! Let's get you set up.")
+    );
+    println!("{}", "━".repeat(50).bright_purple());
+
+    let providers: Vec<String> = get_available_providers()
+        .into_iter()
+        .filter(|provider| *provider != LLMProviderType::Test)
+        .map(|provider| provider.to_string())
+        .collect();
+    let current = providers
+        .iter()
+        .position(|provider| *provider == config.default_provider)
+        .unwrap_or(0);
+    let choice = Select::with_theme(&theme)
+        .with_prompt("Which LLM provider do you want to use?")
+        .items(&providers)
+        .default(current)
+        .interact()?;
+    let provider = providers[choice].clone();
+    let metadata = get_provider_metadata(&LLMProviderType::from_str(&provider)?);
+    config.default_provider = provider.clone();
+    let provider_config = config
+        .providers
+        .entry(provider.clone())
+        .or_insert_with(|| ProviderConfig::default_for(&provider));
+
+    if metadata.requires_api_key {
+        if keyring::is_available() {
+            let location = Select::with_theme(&theme)
+                .with_prompt("Where should the API key be kept?")
+                .items(&["Configuration file", "System keyring"])
+                .default(usize::from(provider_config.keyring))
+                .interact()?;
+            provider_config.keyring = location == 1;
+        }
+        let has_key = !provider_config.api_key.is_empty();
+        let prompt = if has_key {
+            format!("{} API key (leave empty to keep the current one)", metadata.name)
+        } else {
+            format!("{} API key", metadata.name)
+        };
+        let api_key = Password::with_theme(&theme)
+            .with_prompt(prompt)
+            .allow_empty_password(has_key)
+            .interact()?;
+        if !api_key.trim().is_empty() {
+            provider_config.api_key = api_key.trim().to_string();
+        }
+    }
+
+    let default_model = if provider_config.model.is_empty() {
+        metadata.default_model.to_string()
+    } else {
+        provider_config.model.clone()
+    };
+    let model: String = Input::with_theme(&theme)
+        .with_prompt("Model")
+        .default(default_model)
+        .interact_text()?;
+    provider_config.model = model.trim().to_string();
+    let keyring_used = metadata.requires_api_key && provider_config.keyring;
+
+    config.use_gitmoji = Confirm::with_theme(&theme)
+        .with_prompt("Start commit messages with a Gitmoji?")
+        .default(config.use_gitmoji)
+        .interact()?;
+
+    config.save()?;
+    ui::print_success(&format!(
+        "Configuration saved to {}",
+        Config::get_config_path()?.display()
+    ));
+    if keyring_used {
+        ui::print_info("The API key is kept in the system keyring.");
+    }
+
+    let repo_path = env::current_dir()?;
+    if git::is_inside_work_tree().unwrap_or(false) && !hooks::is_hook_installed(&repo_path) {
+        let install = Confirm::with_theme(&theme)
+            .with_prompt(format!(
+                "Install the {} hook here, so 'git commit' fills in the message?",
+                hooks::HOOK_NAME
+            ))
+            .default(false)
+            .interact()?;
+        if install {
+            match hooks::install_hook(&repo_path, false) {
+                Ok(hook_path) => ui::print_success(&format!(
+                    "Installed {} hook at {}",
+                    hooks::HOOK_NAME,
+                    hook_path.display()
+                )),
+                Err(e) => ui::print_warning(&format!("Could not install the hook: {}", e)),
+            }
+        }
+    }
+
+    ui::print_info(
+        "\nYou're all set. Stage some changes and run 'git-iris gen', or check your setup with 'git-iris doctor'.",
+    );
+    Ok(())
+}
+
+/// Handle the 'config show' command
+pub fn handle_config_show_command() -> Result<()> {
+    let config = Config::load()?;
+    print!("{}", config.to_masked_toml()?);
+    Ok(())
+}
+
+/// Handle the 'config get' command
+pub fn handle_config_get_command(key: &str) -> Result<()> {
+    let config = Config::load()?;
+    match config.get_value(key)? {
+        toml::Value::String(value) => println!("{}", value),
+        toml::Value::Table(table) => print!("{}", toml::to_string_pretty(&table)?),
+        value => println!("{}", value),
+    }
+    Ok(())
+}
+
+/// Handle the 'config validate' command
+pub fn handle_config_validate_command() -> Result<()> {
+    config::skip_validation_on_load();
+    let config = Config::load()?;
+    let problems = validate_all(&config);
+    if problems.is_empty() {
+        ui::print_success("The configuration is valid.");
+        return Ok(());
+    }
+
+    for problem in &problems {
+        println!(
+            "{} {} {}",
+            "✘".bright_red(),
+            problem.key.bright_red().bold(),
+            problem.message.bright_white()
+        );
+        println!("  {} {}", "→".cyan(), problem.suggestion.yellow());
+    }
+    Err(anyhow!(
+        "Found {} problem(s) in the configuration",
+        problems.len()
+    ))
+}
+
+/// Handle the 'config migrate' command
+pub fn handle_config_migrate_command(yes: bool) -> Result<()> {
+    config::skip_migration_notice();
+    let config_path = Config::get_config_path()?;
+    if !config_path.exists() {
+        ui::print_info("There is no configuration file yet, so there is nothing to upgrade.");
+        return Ok(());
+    }
+    let content = fs::read_to_string(&config_path)?;
+    let mut value: toml::Value = toml::from_str(&content)
+        .map_err(|e| anyhow!("Invalid configuration in {}: {}", config_path.display(), e))?;
+    let version = config_migration::version_of(&value);
+    if version > CONFIG_VERSION {
+        ui::print_warning(&format!(
+            "{} was written by a newer version of Git-Iris (layout {}); upgrade Git-Iris instead.",
+            config_path.display(),
+            version
+        ));
+        return Ok(());
+    }
+    let changes = config_migration::migrate(&mut value)?;
+    if version == CONFIG_VERSION {
+        ui::print_success(&format!(
+            "The configuration already uses the current layout (version {}).",
+            CONFIG_VERSION
+        ));
+        return Ok(());
+    }
+
+    println!(
+        "Upgrading {} from layout version {} to {}:",
+        config_path.display(),
+        version,
+        CONFIG_VERSION
+    );
+    for change in &changes {
+        println!("  {} {}", "•".bright_cyan(), change);
+    }
+    if changes.is_empty() {
+        println!("  {} Record the layout version", "•".bright_cyan());
+    }
+    println!();
+
+    if !yes && ci::is_ci() {
+        return Err(anyhow!(
+            "Refusing to rewrite the configuration without confirmation in CI mode. Pass --yes to apply"
+        ));
+    }
+    if !yes {
+        print!("{}", "Write the upgraded configuration? [y/N] ".cyan());
+        io::Write::flush(&mut io::stdout())?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            ui::print_info("Upgrade cancelled; the configuration was not changed.");
+            return Ok(());
+        }
+    }
+
+    let backup = config_path.with_extension("toml.bak");
+    fs::write(&backup, &content)?;
+    fs::write(&config_path, toml::to_string(&value)?)?;
+    ui::print_success(&format!(
+        "Upgraded the configuration. The previous file is kept as {}",
+        backup.display()
+    ));
+    Ok(())
+}
+
+/// Handle the 'config export' command
+pub fn handle_config_export_command(
+    no_secrets: bool,
+    format: &str,
+    output: Option<String>,
+) -> Result<()> {
+    let format = BundleFormat::from_str(format)?;
+    let bundle = ConfigBundle::export(!no_secrets)?;
+    let rendered = bundle.render(format)?;
+    match output {
+        Some(path) => {
+            fs::write(&path, rendered)?;
+            ui::print_success(&format!("Configuration exported to {}", path));
+            if !no_secrets {
+                ui::print_warning("The bundle contains your API keys; keep it private.");
+            }
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+/// Handle the 'config import' command
+pub fn handle_config_import_command(file: &str, replace: bool) -> Result<()> {
+    let content = if file == "-" {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        fs::read_to_string(file).map_err(|e| anyhow!("Unable to read {}: {}", file, e))?
+    };
+    let bundle = ConfigBundle::parse(&content)?;
+
+    let value = bundle.apply(&config::read_global_value()?, replace);
+    let mut imported: Config = value
+        .try_into()
+        .map_err(|e| anyhow!("The bundle's settings are not valid: {}", e))?;
+    let config_path = Config::get_config_path()?;
+    bundle.write_files(config_path.parent().unwrap_or(std::path::Path::new(".")))?;
+    if !imported.providers.contains_key(&imported.default_provider) {
+        imported.providers.insert(
+            imported.default_provider.clone(),
+            ProviderConfig::default_for(&imported.default_provider),
+        );
+    }
+    imported.save()?;
+
+    ui::print_success(&format!(
+        "Imported the configuration{}{}.",
+        match bundle.templates.len() {
+            0 => String::new(),
+            count => format!(", {} prompt template(s)", count),
+        },
+        if bundle.gitmoji_mapping.is_some() {
+            " and a gitmoji mapping"
+        } else {
+            ""
+        }
+    ));
+    for problem in validate_all(&imported) {
+        ui::print_warning(&format!("{} ({})", problem, problem.suggestion));
+    }
+    Ok(())
+}
+
+/// Handle the 'config unset' command
+pub fn handle_config_unset_command(key: &str) -> Result<()> {
+    let mut config = Config::load()?;
+    config.unset_value(key)?;
+    config.save()?;
+    ui::print_success(&format!("Unset '{}'.", key));
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_changelog_command(
+    from: String,
+    to: Option<String>,
+    instructions: Option<String>,
+    preset: Option<String>,
+    detail_level: Option<String>,
+    format: Option<String>,
+    output: Option<String>,
+    gitmoji: Option<bool>,
+    review: bool,
+    language: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+
+    let repo_path = env::current_dir()?;
+    let to = to.unwrap_or_else(|| "HEAD".to_string());
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    // Flags take precedence over the configured defaults
+    let detail_level = DetailLevel::from_str(
+        &detail_level.unwrap_or_else(|| config.changelog.detail_level.clone()),
+    )?;
+    let format = OutputFormat::from_str(&format.unwrap_or_else(|| config.changelog.format.clone()))?;
+    let output = output.or_else(|| config.changelog.output.clone());
+
+    // Override gitmoji setting if provided
+    if let Some(use_gitmoji) = gitmoji {
+        config.use_gitmoji = use_gitmoji;
+    }
+    if let Some(language) = language {
+        config.language = language;
+    }
+
+    // The generator shows the progress of each phase
+    let changelog =
+        ChangelogGenerator::generate(&repo_path, &from, &to, &config, detail_level).await?;
+    let Some(changelog) = review_if_requested(changelog, review)? else {
+        ui::print_info("Changelog review cancelled.");
+        return Ok(());
+    };
+
+    write_release_text(&changelog, &from, &to, format, output, "Changelog")
+}
+
+/// Print a generated changelog or release notes, or write them to a file
+fn write_release_text(
+    text: &str,
+    from: &str,
+    to: &str,
+    format: OutputFormat,
+    output: Option<String>,
+    name: &str,
+) -> Result<()> {
+    let rendered = format.render(text, from, to)?;
+    match output {
+        Some(path) => {
+            fs::write(&path, rendered).map_err(|e| anyhow!("Unable to write {}: {}", path, e))?;
+            ui::print_success(&format!("{} written to {}", name, path));
+        }
+        // JSON is printed as is so that it can be piped into other tools
+        None if format == OutputFormat::Json => print!("{}", rendered),
+        None => {
+            println!("{}", "━".repeat(50).bright_purple());
+            println!("{}", text);
+            println!("{}", "━".repeat(50).bright_purple());
+        }
+    }
+    Ok(())
+}
+
+/// Let the user review a generated changelog when asked to, unless nobody is there to answer
+///
+/// Returns `None` if the review was cancelled.
+fn review_if_requested(changelog: String, review: bool) -> Result<Option<String>> {
+    if !review {
+        return Ok(Some(changelog));
+    }
+    if ci::is_ci() {
+        ui::print_warning("Skipping the changelog review in CI mode.");
+        return Ok(Some(changelog));
+    }
+    review_changelog(&changelog)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_release_notes_command(
+    from: String,
+    to: Option<String>,
+    instructions: Option<String>,
+    preset: Option<String>,
+    detail_level: Option<String>,
+    format: Option<String>,
+    output: Option<String>,
+    gitmoji: Option<bool>,
+    fresh: bool,
+    language: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let repo_path = env::current_dir()?;
+    let to = to.unwrap_or_else(|| "HEAD".to_string());
+
+    if fresh {
+        ReleaseNotesSession::discard_all(&repo_path)?;
+    } else if ReleaseNotesSession::has_saved(&repo_path) {
+        ui::print_info("Resuming from saved progress of an interrupted run (use --fresh to start over)");
+    }
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    // Flags take precedence over the configured defaults
+    let detail_level = DetailLevel::from_str(
+        &detail_level.unwrap_or_else(|| config.release_notes.detail_level.clone()),
+    )?;
+    let format =
+        OutputFormat::from_str(&format.unwrap_or_else(|| config.release_notes.format.clone()))?;
+    let output = output.or_else(|| config.release_notes.output.clone());
+
+    // Override gitmoji setting if provided
+    if let Some(use_gitmoji) = gitmoji {
+        config.use_gitmoji = use_gitmoji;
+    }
+    if let Some(language) = language {
+        config.language = language;
+    }
+
+    // The generator shows the progress of each phase
+    let release_notes =
+        ReleaseNotesGenerator::generate(&repo_path, &from, &to, &config, detail_level).await?;
+
+    write_release_text(&release_notes, &from, &to, format, output, "Release notes")
+}
+
+/// Handle the 'migration-guide' command
+pub async fn handle_migration_guide_command(
+    from: String,
+    to: Option<String>,
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let repo_path = env::current_dir()?;
+    let to = to.unwrap_or_else(|| "HEAD".to_string());
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let spinner = ui::create_spinner("Writing migration guide...");
+    let context = MigrationContext::load(
+        &repo_path,
+        &from,
+        &to,
+        &exclusions::PromptFilter::from_config(&config),
+    )?;
+    let guide = MigrationGuideGenerator::generate(&context, &config).await?;
+    spinner.finish_and_clear();
+
+    println!("{}", "━".repeat(50).bright_purple());
+    println!("{}", &guide);
+    println!("{}", "━".repeat(50).bright_purple());
+
+    Ok(())
+}
+
+/// Handle the 'tag' command
+pub async fn handle_tag_command(
+    name: String,
+    from: Option<String>,
+    to: Option<String>,
+    instructions: Option<String>,
+    preset: Option<String>,
+    gitmoji: Option<bool>,
+    create: bool,
+) -> Result<()> {
+    let mut config = Config::load()?;
+
+    let repo_path = env::current_dir()?;
+    let to = to.unwrap_or_else(|| "HEAD".to_string());
+
+    let from = match from {
+        Some(from) => from,
+        None => match git::get_latest_tag(&repo_path, &to)? {
+            Some(tag) => tag,
+            None => {
+                ui::print_error("No previous tag found. Please specify a starting reference with '--from'.");
+                return Ok(());
+            }
+        },
+    };
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    // Override gitmoji setting if provided
+    if let Some(use_gitmoji) = gitmoji {
+        config.use_gitmoji = use_gitmoji;
+    }
+
+    let tag_message = TagMessageGenerator::generate(&repo_path, &name, &from, &to, &config).await?;
+
+    println!("{}", "━".repeat(50).bright_purple());
+    println!("{}", &tag_message);
+    println!("{}", "━".repeat(50).bright_purple());
+
+    if create {
+        git::create_annotated_tag(&repo_path, &name, &to, &tag_message)?;
+        ui::print_success(&format!("Created annotated tag '{}'.", name));
+        ui::print_info(&format!("Push it with 'git push origin {}'.", name));
+    }
+
+    Ok(())
+}
+
+/// Handle the 'pr-title' command
+pub async fn handle_pr_title_command(
+    base: String,
+    include_body: bool,
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let spinner = ui::create_spinner("Generating squash-merge title...");
+
+    let repo_path = env::current_dir()?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let message = SquashTitleGenerator::generate(&repo_path, &base, &config, include_body).await?;
+
+    spinner.finish_and_clear();
+
+    // Print plain text only so the output can be passed straight to `gh pr merge`
+    println!("{}", message.title);
+    if let Some(body) = message.body {
+        println!();
+        println!("{}", body);
+    }
+
+    Ok(())
+}
+
+/// Handle the 'squash-msg' command
+pub async fn handle_squash_msg_command(
+    from: Option<String>,
+    to: Option<String>,
+    count: Option<usize>,
+    instructions: Option<String>,
+    preset: Option<String>,
+    write: bool,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let repo_path = env::current_dir()?;
+    let to = to.unwrap_or_else(|| "HEAD".to_string());
+
+    let from = match (from, count) {
+        (Some(from), _) => from,
+        (None, Some(0)) => return Err(anyhow!("--count must be at least 1")),
+        (None, Some(count)) => format!("{}~{}", to, count),
+        (None, None) => return Err(anyhow!("Specify the range with --from or --count")),
+    };
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let spinner = ui::create_spinner("Generating squash message...");
+    let message = SquashTitleGenerator::generate_for_range(&repo_path, &from, &to, &config).await?;
+    spinner.finish_and_clear();
+
+    let message = message.to_commit_message();
+    if write {
+        let path = git::write_squash_msg(&repo_path, &message)?;
+        // Report on stderr so stdout stays clean for piping
+        eprintln!(
+            "{}",
+            format!("Squash message written to {}", path.display())
+                .green()
+                .bold()
+        );
+    }
+
+    // Print plain text only so the output can be piped into other tools
+    println!("{}", message);
+
+    Ok(())
+}
+
+/// Handle the 'merge-msg' command
+pub async fn handle_merge_msg_command(
+    branch: Option<String>,
+    instructions: Option<String>,
+    preset: Option<String>,
+    message_file: Option<String>,
+    source: Option<String>,
+) -> Result<()> {
+    // As a prepare-commit-msg hook, only act on merge commits
+    if source.as_deref().is_some_and(|source| source != "merge") {
+        log_debug!("Skipping merge message for commit source: {:?}", source);
+        return Ok(());
+    }
+
+    let mut config = Config::load()?;
+    let repo_path = env::current_dir()?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let spinner = ui::create_spinner("Generating merge message...");
+    let result = match MergeContext::load(
+        &repo_path,
+        branch.as_deref(),
+        &exclusions::PromptFilter::from_config(&config),
+    ) {
+        Ok(context) => MergeMessageGenerator::generate(&context, &config).await,
+        Err(e) => Err(e),
+    };
+    spinner.finish_and_clear();
+
+    match message_file {
+        Some(path) => match result {
+            Ok(message) => write_message_file(
+                std::path::Path::new(&path),
+                &message,
+                git::get_comment_char(&repo_path),
+            )?,
+            // Never block the merge from a hook; Git's default message stays in place
+            Err(e) => ui::print_warning(&format!("Keeping the default merge message: {}", e)),
+        },
+        None => println!("{}", result?),
+    }
+
+    Ok(())
+}
+
+/// Handle the 'cherry-pick-msg' command
+pub async fn handle_cherry_pick_msg_command(
+    commit: Option<String>,
+    instructions: Option<String>,
+    preset: Option<String>,
+    message_file: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let repo_path = env::current_dir()?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let spinner = ui::create_spinner("Adapting cherry-pick message...");
+    let result = match CherryPickContext::load(
+        &repo_path,
+        commit.as_deref(),
+        &exclusions::PromptFilter::from_config(&config),
+    ) {
+        Ok(context) => CherryPickMessageGenerator::generate(&context, &config).await,
+        Err(e) => Err(e),
+    };
+    spinner.finish_and_clear();
+
+    match message_file {
+        Some(path) => match result {
+            Ok(message) => write_message_file(
+                std::path::Path::new(&path),
+                &message,
+                git::get_comment_char(&repo_path),
+            )?,
+            // Never block the cherry-pick from a hook; the original message stays in place
+            Err(e) => ui::print_warning(&format!("Keeping the original message: {}", e)),
+        },
+        None => println!("{}", result?),
+    }
+
+    Ok(())
+}
+
+/// Handle the 'branch-name' command
+pub async fn handle_branch_name_command(
+    issue: Option<String>,
+    create: bool,
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    config.recent_commits = config.recent_commits.for_command("branch-name");
+    let repo_path = env::current_dir()?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let spinner = ui::create_spinner("Suggesting branch names...");
+    let names = BranchNameGenerator::generate(&repo_path, issue.as_deref(), &config).await?;
+    spinner.finish_and_clear();
+
+    if !create {
+        for name in &names {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+
+    for (i, name) in names.iter().enumerate() {
+        println!("{} {}", format!("{}.", i + 1).bright_purple().bold(), name);
+    }
+    // CI mode never waits for input, so it takes the default choice
+    let mut choice = String::new();
+    if !ci::is_ci() {
+        print!("{}", format!("Branch to create [1-{}, default 1]: ", names.len()).cyan());
+        io::Write::flush(&mut io::stdout())?;
+        io::stdin().read_line(&mut choice)?;
+    }
+    let choice = choice.trim();
+    let index = if choice.is_empty() {
+        0
+    } else {
+        match choice.parse::<usize>() {
+            Ok(n) if (1..=names.len()).contains(&n) => n - 1,
+            _ => return Err(anyhow!("Invalid choice: {}", choice)),
+        }
+    };
+
+    git::create_and_checkout_branch(&repo_path, &names[index])?;
+    ui::print_success(&format!("Switched to a new branch '{}'", names[index]));
+
+    Ok(())
+}
+
+/// Handle the 'semver' command
+pub fn handle_semver_command(to: Option<String>, format: String) -> Result<()> {
+    let config = Config::load()?;
+    let repo_path = env::current_dir()?;
+    let to = to.unwrap_or_else(|| "HEAD".to_string());
+
+    let suggestion = suggest_next_version(&repo_path, &to, &config)?;
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&suggestion)?);
+        return Ok(());
+    }
+
+    let version = match (&suggestion.current_version, &suggestion.next_version) {
+        (Some(current), Some(next)) => format!(" ({} → {})", current, next),
+        _ => String::new(),
+    };
+    println!(
+        "{}{}",
+        suggestion.bump.to_string().bright_purple().bold(),
+        version
+    );
+    for reason in &suggestion.justification {
+        println!("  • {}", reason);
+    }
+
+    Ok(())
+}
+
+/// Handle the 'todo' command
+pub fn handle_todo_command(format: String) -> Result<()> {
+    let config = Config::load()?;
+    let repo_path = env::current_dir()?;
+
+    let git_info = get_git_info(&repo_path, &config)?;
+    let items = find_added_todos(&git_info.staged_files);
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&items)?);
+        return Ok(());
+    }
+
+    if items.is_empty() {
+        ui::print_success("No TODO, FIXME, or HACK comments added by the staged changes.");
+        return Ok(());
+    }
+
+    for item in &items {
+        let kind = match item.kind {
+            TodoKind::Todo => item.kind.to_string().bright_cyan().bold(),
+            TodoKind::Fixme => item.kind.to_string().bright_red().bold(),
+            TodoKind::Hack => item.kind.to_string().bright_yellow().bold(),
+        };
+        println!(
+            "{} {} {}",
+            format!("{}:{}", item.path, item.line).bright_purple(),
+            kind,
+            item.text
+        );
+    }
+    ui::print_warning(&format!(
+        "{} comment{} added by the staged changes.",
+        items.len(),
+        if items.len() == 1 { "" } else { "s" }
+    ));
+
+    Ok(())
+}
+
+/// Handle the 'hooks install' command
+pub fn handle_hooks_install_command(force: bool, lint: bool) -> Result<()> {
+    let repo_path = env::current_dir()?;
+    let hook_path = hooks::install_hook(&repo_path, force)?;
+    ui::print_success(&format!("Installed {} hook at {}", hooks::HOOK_NAME, hook_path.display()));
+    if lint {
+        let lint_hook_path = hooks::install_lint_hook(&repo_path, force)?;
+        ui::print_success(&format!(
+            "Installed {} hook at {}",
+            hooks::LINT_HOOK_NAME,
+            lint_hook_path.display()
+        ));
+    }
+    ui::print_info("Run 'git commit' without -m and Git-Iris will fill in the message.");
+    Ok(())
+}
+
+/// Handle the 'hooks uninstall' command
+pub fn handle_hooks_uninstall_command() -> Result<()> {
+    let repo_path = env::current_dir()?;
+    if hooks::uninstall_hook(&repo_path)? {
+        ui::print_success(&format!("Removed {} hook", hooks::HOOK_NAME));
+    } else {
+        ui::print_info(&format!("No {} hook installed", hooks::HOOK_NAME));
+    }
+    if hooks::uninstall_lint_hook(&repo_path)? {
+        ui::print_success(&format!("Removed {} hook", hooks::LINT_HOOK_NAME));
+    }
+    Ok(())
+}
+
+/// Handle the 'hooks run' command, called by Git as prepare-commit-msg
+pub async fn handle_hooks_run_command(message_file: String, source: Option<String>) -> Result<()> {
+    let repo_path = env::current_dir()?;
+    let action = hooks::hook_action_in_repo(
+        &repo_path,
+        std::path::Path::new(&message_file),
+        source.as_deref(),
+    );
+    log_debug!("prepare-commit-msg hook action: {:?}", action);
+    if action == HookAction::Skip {
+        return Ok(());
+    }
+
+    let mut config = Config::load()?;
+    config.recent_commits = config.recent_commits.for_command("gen");
+    let filter = exclusions::PromptFilter::from_config(&config);
+
+    let spinner = ui::create_spinner(&messages::get_random_message());
+    let result = match action {
+        HookAction::MergeMessage => match MergeContext::load(&repo_path, None, &filter) {
+            Ok(context) => MergeMessageGenerator::generate(&context, &config).await.map(Some),
+            Err(e) => Err(e),
+        },
+        HookAction::CherryPickMessage => match CherryPickContext::load(&repo_path, None, &filter) {
+            Ok(context) => CherryPickMessageGenerator::generate(&context, &config)
+                .await
+                .map(Some),
+            Err(e) => Err(e),
+        },
+        _ => generate_staged_commit_message(&repo_path, &config).await,
+    };
+    spinner.finish_and_clear();
+
+    match result {
+        Ok(Some(message)) => {
+            warn_lint_violations(&message, &config.lint);
+            write_message_file(
+                std::path::Path::new(&message_file),
+                &message,
+                git::get_comment_char(&repo_path),
+            )?;
+        }
+        Ok(None) => {}
+        // Never block the commit from a hook; Git's message stays in place
+        Err(e) => ui::print_warning(&format!("Git-Iris could not generate a message: {}", e)),
+    }
+
+    Ok(())
+}
+
+/// Generate a commit message for the staged changes without any interaction
+pub(crate) async fn generate_staged_commit_message(
+    repo_path: &std::path::Path,
+    config: &Config,
+) -> Result<Option<String>> {
+    let provider_type = LLMProviderType::from_str(&config.default_provider)?;
+    validate_provider_config(config, &provider_type)?;
+
+    let mut git_info = get_git_info(repo_path, config)?;
+    if git_info.staged_files.is_empty() {
+        return Ok(None);
+    }
+
+    let token_limit = get_provider_metadata(&provider_type).default_token_limit;
+    let optimizer = TokenOptimizer::new(token_limit);
+    optimizer.optimize_context(&mut git_info);
+
+    let conventional = config
+        .conventional
+        .enabled
+        .then(|| ConventionalMode::for_changes(&git_info.staged_files, &config.conventional));
+    let instructions = config.get_effective_instructions();
+    let system_prompt = commit_system_prompt(
+        config.use_gitmoji,
+        &instructions,
+        config.language(),
+        &config.format,
+        conventional.as_ref(),
+    );
+    let user_prompt = prompt::create_user_prompt(&git_info)?;
+
+    let message = generate_commit_message(
+        config,
+        &provider_type,
+        &system_prompt,
+        &user_prompt,
+        None,
+        conventional.as_ref(),
+    )
+    .await?;
+    if config.use_gitmoji && config.gitmoji_in_body {
+        Ok(Some(move_gitmoji_to_body(&message)))
+    } else {
+        Ok(Some(message))
+    }
+}
+
+/// Handle the 'lint' command
+pub fn handle_lint_command(range: Option<String>, message_file: Option<String>) -> Result<()> {
+    let config = Config::load()?;
+    let repo_path = env::current_dir()?;
+
+    if let Some(message_file) = message_file {
+        let content = fs::read_to_string(&message_file)?;
+        let message = strip_comments(&content, git::get_comment_char(&repo_path));
+        let violations = lint_message(&message, &config.lint);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        for violation in &violations {
+            ui::print_warning(&violation.to_string());
+        }
+        ui::print_info("Fix the message, or commit with --no-verify to skip the check.");
+        return Err(anyhow!(
+            "Commit message has {} lint problem(s)",
+            violations.len()
+        ));
+    }
+
+    let range = range.unwrap_or_else(|| "HEAD".to_string());
+    let commits = commits_in_range(&repo_path, &range)?;
+    let mut failing = 0;
+    for commit in &commits {
+        let message = git::get_commit_message(&repo_path, commit)?;
+        let violations = lint_message(&message, &config.lint);
+        if violations.is_empty() {
+            continue;
+        }
+        failing += 1;
+        println!(
+            "{} {}",
+            commit[..7].yellow(),
+            subject_line(&message).bold()
+        );
+        for violation in &violations {
+            println!("  {}", violation);
+        }
+    }
+
+    if failing > 0 {
+        return Err(anyhow!(
+            "{} of {} commit message(s) have lint problems",
+            failing,
+            commits.len()
+        ));
+    }
+    ui::print_success(&format!("{} commit message(s) pass", commits.len()));
+    Ok(())
+}
+
+/// Handle the 'report' command
+pub async fn handle_report_command(
+    since: String,
+    author: Option<String>,
+    format: String,
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let repo_path = env::current_dir()?;
+    let since = parse_since(&since, chrono::Local::now())?;
+    let format = ReportFormat::from_str(&format)?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let spinner = ui::create_spinner("Writing progress report...");
+    let report =
+        ReportGenerator::generate(&repo_path, since, author.as_deref(), format, &config).await?;
+    spinner.finish_and_clear();
+
+    // Printed as is, so it can be redirected to a file
+    print!("{}", report);
+
+    Ok(())
+}
+
+/// Handle the 'stats' command
+pub async fn handle_stats_command(
+    from: Option<String>,
+    to: Option<String>,
+    top: usize,
+    narrative: bool,
+    format: String,
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let repo_path = env::current_dir()?;
+    let to = to.unwrap_or_else(|| "HEAD".to_string());
+
+    let stats = compute_stats(&repo_path, from.as_deref(), &to, top)?;
+    if stats.commits == 0 {
+        ui::print_info(&format!("No commits in {}", stats.range));
+        return Ok(());
+    }
+
+    let narrative = if narrative {
+        let mut config = Config::load()?;
+        config.set_temp_instructions(instructions);
+        config.set_temp_preset(preset);
+
+        let spinner = ui::create_spinner("Interpreting the statistics...");
+        let narrative = StatsNarrativeGenerator::generate(&stats, &config).await?;
+        spinner.finish_and_clear();
+        Some(narrative)
+    } else {
+        None
+    };
+
+    if format == "json" {
+        let mut value = serde_json::to_value(&stats)?;
+        if let Some(narrative) = narrative {
+            value["narrative"] = serde_json::Value::String(narrative);
+        }
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
+    print!("{}", format_stats_report(&stats));
+    if let Some(narrative) = narrative {
+        println!();
+        println!("{}", "━".repeat(50).bright_purple());
+        println!("{}", narrative);
+        println!("{}", "━".repeat(50).bright_purple());
+    }
+
+    Ok(())
+}
+
+/// Handle the 'summary' command
+pub async fn handle_summary_command(
+    since: String,
+    author: Option<String>,
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let repo_path = env::current_dir()?;
+    let since = parse_since(&since, chrono::Local::now())?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let spinner = ui::create_spinner("Summarizing recent work...");
+    let summary =
+        WorkSummaryGenerator::generate(&repo_path, author.as_deref(), since, &config).await?;
+    spinner.finish_and_clear();
+
+    println!("{}", "━".repeat(50).bright_purple());
+    println!("{}", &summary);
+    println!("{}", "━".repeat(50).bright_purple());
+
+    Ok(())
+}
+
+/// Handle the 'rewrite' command
+pub async fn handle_rewrite_command(
+    from: String,
+    to: Option<String>,
+    conventional: bool,
+    instructions: Option<String>,
+    preset: Option<String>,
+    yes: bool,
+    force: bool,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    config.recent_commits = config.recent_commits.for_command("rewrite");
+    config.conventional.enabled |= conventional;
+    let repo_path = env::current_dir()?;
+    let to = to.unwrap_or_else(|| "HEAD".to_string());
+
+    let provider_type = LLMProviderType::from_str(&config.default_provider)?;
+    if let Err(e) = validate_provider_config(&config, &provider_type) {
+        if ci::is_ci() {
+            return Err(e);
+        }
+        ui::print_error(&format!("{}. Please run 'git-iris config --provider {} --api-key YOUR_API_KEY' to set it.", e, provider_type));
+        return Ok(());
+    }
+
+    let commits = commits_to_rewrite(&repo_path, &from, &to)?;
+    if !force {
+        check_not_pushed(&repo_path, &commits)?;
+    }
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let spinner = ui::create_spinner("Rewriting commit messages...");
+    let entries = generate_rewrites(
+        &repo_path,
+        &commits,
+        &config,
+        &provider_type,
+        |current, total| {
+            spinner.set_message(format!("Rewriting commit message {}/{}...", current, total))
+        },
+    )
+    .await?;
+    spinner.finish_and_clear();
+
+    // Preview table of old and new subject lines
+    let width = 34;
+    println!(
+        "{}  {}  {}",
+        "Commit ".bold(),
+        pad_to_width("Current subject", width).bold(),
+        "New subject".bold()
+    );
+    println!("{}", "━".repeat(7 + 2 + width + 2 + width).bright_purple());
+    for entry in &entries {
+        let original = entry.original.lines().next().unwrap_or("");
+        let rewritten = entry.rewritten.lines().next().unwrap_or("");
+        println!(
+            "{}  {}  {}",
+            entry.commit_id[..7].yellow(),
+            pad_to_width(original, width).dimmed(),
+            rewritten.green()
+        );
+    }
+    println!();
+
+    if !yes && ci::is_ci() {
+        return Err(anyhow!(
+            "Refusing to rewrite commits without confirmation in CI mode. Pass --yes to apply"
+        ));
+    }
+    if !yes {
+        print!("{}", "Apply these messages? [y/N] ".cyan());
+        io::Write::flush(&mut io::stdout())?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            ui::print_info("Rewrite cancelled; no commits were changed.");
+            return Ok(());
+        }
+    }
+
+    let new_head = apply_rewrites(&repo_path, &from, &entries)?;
+    ui::print_success(&format!(
+        "Rewrote {} commit message(s). HEAD is now {}",
+        entries.len(),
+        &new_head[..7]
+    ));
+
+    Ok(())
+}
+
+/// Truncate or pad a string to an exact display width
+fn pad_to_width(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return format!("{}{}", text, " ".repeat(width - text.width()));
+    }
+
+    let mut result = String::new();
+    let mut current = 0;
+    for c in text.chars() {
+        let char_width = UnicodeWidthChar::width(c).unwrap_or(0);
+        if current + char_width >= width {
+            break;
+        }
+        result.push(c);
+        current += char_width;
+    }
+    result.push('…');
+    format!("{}{}", result, " ".repeat(width.saturating_sub(current + 1)))
+}
+
+/// Handle the 'stash-msg' command
+pub async fn handle_stash_msg_command(
+    push: bool,
+    include_untracked: bool,
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let repo_path = env::current_dir()?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let spinner = ui::create_spinner("Generating stash message...");
+    let message = StashMessageGenerator::generate(&repo_path, include_untracked, &config).await?;
+    spinner.finish_and_clear();
+
+    if !push {
+        println!("{}", message);
+        return Ok(());
+    }
+
+    git::stash_push(&repo_path, &message, include_untracked)?;
+    ui::print_success(&format!("Stashed changes: {}", message));
+
+    Ok(())
+}
+
+/// Handle the 'revert' command
+pub async fn handle_revert_command(
+    commit: String,
+    apply: bool,
+    mainline: Option<u32>,
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    config.recent_commits = config.recent_commits.for_command("revert");
+    let repo_path = env::current_dir()?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let spinner = ui::create_spinner("Generating revert message...");
+    let message = RevertMessageGenerator::generate(&repo_path, &commit, &config).await?;
+    spinner.finish_and_clear();
+
+    if !apply {
+        println!("{}", message);
+        return Ok(());
+    }
+
+    match revert_commit(&repo_path, &commit, mainline, &message)? {
+        RevertOutcome::Committed(commit_id) => {
+            ui::print_success(&format!("Reverted {} in {}", commit, &commit_id[..7]));
+        }
+        RevertOutcome::Conflicts(paths) => {
+            ui::print_warning(&format!(
+                "Reverting {} conflicts in: {}",
+                commit,
+                paths.join(", ")
+            ));
+            ui::print_info(
+                "Resolve the conflicts, stage the files, and run 'git commit'; the generated message is already filled in.",
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Handle the 'undo' command
+pub fn handle_undo_command(force: bool) -> Result<()> {
+    let repo_path = env::current_dir()?;
+    let undone = undo_auto_commit(&repo_path, force)?;
+    ui::print_success(&format!(
+        "Undid commit {}: {}",
+        &undone.commit_id[..7],
+        undone.summary
+    ));
+    ui::print_info("Its changes are staged again, as they were before the commit.");
+    Ok(())
+}
+
+/// Hash of the staged changes, to match history entries against
+fn current_changes_hash(repo_path: &std::path::Path) -> Option<String> {
+    let config = Config::load().ok()?;
+    let context = get_git_info(repo_path, &config).ok()?;
+    (!context.staged_files.is_empty()).then(|| history::changes_hash(&context.staged_files))
+}
+
+/// Handle the 'history' command
+pub fn handle_history_list_command(limit: usize) -> Result<()> {
+    let repo_path = env::current_dir()?;
+    let history = GenerationHistory::load(&repo_path);
+    if history.entries.is_empty() {
+        ui::print_info("No generated messages yet. Messages from 'git-iris gen' are saved here.");
+        return Ok(());
+    }
+
+    let current = current_changes_hash(&repo_path);
+    for (number, entry) in history.newest_first().take(limit) {
+        let marker = if current.as_deref() == Some(entry.diff_hash.as_str()) {
+            "●".green().to_string()
+        } else {
+            " ".to_string()
+        };
+        println!(
+            "{:>3} {} {}  {}",
+            number.to_string().bright_cyan(),
+            marker,
+            entry.local_time().dimmed(),
+            subject_line(&entry.message)
+        );
+    }
+    if current.is_some() {
+        ui::print_info(&format!("\n{} generated for the staged changes", "●".green()));
+    }
+    ui::print_info(
+        "Print one with 'git-iris history show <n>', or commit with it using 'git-iris history use <n>'",
+    );
+    Ok(())
+}
+
+/// Handle the 'history show' command
+pub fn handle_history_show_command(number: usize) -> Result<()> {
+    let repo_path = env::current_dir()?;
+    let history = GenerationHistory::load(&repo_path);
+    let entry = history.get(number)?;
+
+    ui::print_info(&format!("Generated {}", entry.local_time()));
+    println!("{}", "━".repeat(50).bright_purple());
+    println!("{}", entry.message);
     println!("{}", "━".repeat(50).bright_purple());
-    println!("{}", &changelog);
-    println!("{}", "━".repeat(50).bright_purple());
+    Ok(())
+}
+
+/// Handle the 'history use' command
+pub fn handle_history_use_command(number: usize) -> Result<()> {
+    let repo_path = env::current_dir()?;
+    let config = Config::load()?;
+    let history = GenerationHistory::load(&repo_path);
+    let entry = history.get(number)?;
+
+    if !git::has_staged_changes(&repo_path)? {
+        return Err(anyhow!("No staged changes to commit"));
+    }
+    if current_changes_hash(&repo_path).as_deref() != Some(entry.diff_hash.as_str()) {
+        ui::print_warning(&format!(
+            "Message #{} was generated for different changes than the ones staged now.",
+            number
+        ));
+    }
+
+    warn_lint_violations(&entry.message, &config.lint);
+    git::commit(&repo_path, &entry.message)?;
+    ui::print_success(&format!(
+        "Committed with message #{}: {}",
+        number,
+        subject_line(&entry.message)
+    ));
+    Ok(())
+}
+
+/// Handle the 'history diff' command
+pub fn handle_history_diff_command(from: usize, to: usize) -> Result<()> {
+    let repo_path = env::current_dir()?;
+    let history = GenerationHistory::load(&repo_path);
+    let (old, new) = (history.get(from)?, history.get(to)?);
+
+    let changes = candidate_diff(&old.message, &new.message, usize::MAX);
+    if changes.is_empty() {
+        ui::print_info(&format!("Messages #{} and #{} are the same.", from, to));
+        return Ok(());
+    }
+    println!("{}", format!("--- #{} ({})", from, old.local_time()).red());
+    println!("{}", format!("+++ #{} ({})", to, new.local_time()).green());
+    for change in changes {
+        if change.starts_with("+ ") {
+            println!("{}", change.green());
+        } else {
+            println!("{}", change.red());
+        }
+    }
+    Ok(())
+}
+
+/// Handle the 'history clear' command
+pub fn handle_history_clear_command() -> Result<()> {
+    let repo_path = env::current_dir()?;
+    GenerationHistory::default().save(&repo_path)?;
+    ui::print_success("Cleared the generation history.");
+    Ok(())
+}
+
+/// Handle the 'split' command
+pub async fn handle_split_command(
+    apply: bool,
+    yes: bool,
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    config.recent_commits = config.recent_commits.for_command("split");
+    let repo_path = env::current_dir()?;
+
+    if apply && !yes && ci::is_ci() {
+        return Err(anyhow!(
+            "Refusing to create commits without confirmation in CI mode. Pass --yes to apply"
+        ));
+    }
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let spinner = ui::create_spinner("Looking for separate concerns in the staged changes...");
+    let (plan, hunks) = SplitPlanner::plan(&repo_path, &config).await?;
+    spinner.finish_and_clear();
+
+    if plan.groups.len() < 2 {
+        ui::print_info("The staged changes form a single concern; commit them together.");
+        return Ok(());
+    }
+
+    if !apply {
+        println!("{}", format_split_plan(&plan, &hunks));
+        return Ok(());
+    }
+
+    let staged = StagedPatch::capture(&repo_path)?;
+    unstage_all(&repo_path)?;
+
+    // Hunks of groups that were skipped or not reached go back to the index at the end
+    let mut left_staged: Vec<usize> = Vec::new();
+    let mut committed = 0;
+    let mut error = None;
+    for (index, group) in plan.groups.iter().enumerate() {
+        let step = commit_split_group(
+            &repo_path,
+            &config,
+            &staged,
+            &hunks,
+            group,
+            (index + 1, plan.groups.len()),
+            yes,
+        )
+        .await;
+        unstage_all(&repo_path)?;
+
+        match step {
+            Ok(SplitStep::Committed) => committed += 1,
+            Ok(SplitStep::Skipped) => left_staged.extend(&group.hunk_ids),
+            Ok(SplitStep::Quit) | Err(_) => {
+                for group in &plan.groups[index..] {
+                    left_staged.extend(&group.hunk_ids);
+                }
+                error = step.err();
+                break;
+            }
+        }
+    }
+    staged.stage(&repo_path, &hunks, &left_staged)?;
+    if let Some(e) = error {
+        return Err(e);
+    }
+
+    ui::print_success(&format!("Created {} commit(s).", committed));
+    if !left_staged.is_empty() {
+        ui::print_info("The changes of the groups that were not committed are still staged.");
+    }
+    Ok(())
+}
+
+/// What happened to one group of a split plan
+enum SplitStep {
+    Committed,
+    Skipped,
+    Quit,
+}
+
+/// Stage one group of a split plan, generate its message, and commit it once confirmed
+///
+/// `position` is the group's number, starting at 1, and the number of groups.
+async fn commit_split_group(
+    repo_path: &std::path::Path,
+    config: &Config,
+    staged: &StagedPatch,
+    hunks: &[StagedHunk],
+    group: &SplitGroup,
+    position: (usize, usize),
+    yes: bool,
+) -> Result<SplitStep> {
+    staged.stage(repo_path, hunks, &group.hunk_ids)?;
+
+    let (number, total) = position;
+    let spinner = ui::create_spinner(&format!(
+        "Generating the message for commit {}/{}...",
+        number, total
+    ));
+    let message = SplitPlanner::generate_message(repo_path, group, config).await?;
+    spinner.finish_and_clear();
+
+    println!(
+        "{} {}",
+        format!("Commit {}/{}:", number, total).bold(),
+        group.title.bright_purple()
+    );
+    for step in group_steps(group, hunks) {
+        println!("  {}", step.dimmed());
+    }
+    println!("\n{}\n", message);
+
+    if !yes {
+        print!("{}", "Create this commit? [y/n/q] ".cyan());
+        io::Write::flush(&mut io::stdout())?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => {}
+            "q" | "quit" => return Ok(SplitStep::Quit),
+            _ => return Ok(SplitStep::Skipped),
+        }
+    }
+
+    git::commit(repo_path, &message)?;
+    Ok(SplitStep::Committed)
+}
+
+/// Handle the 'fixup' command
+pub fn handle_fixup_command(commit: bool, max_commits: usize) -> Result<()> {
+    let repo_path = env::current_dir()?;
+
+    let candidates = suggest_fixup_targets(&repo_path, max_commits)?;
+    if candidates.is_empty() {
+        ui::print_info(&format!(
+            "None of the last {} commits touched the staged changes; commit them on their own.",
+            max_commits
+        ));
+        return Ok(());
+    }
+
+    let shown = &candidates[..candidates.len().min(3)];
+    println!("{}", "Commits the staged changes most likely belong to:".bold());
+    for (index, candidate) in shown.iter().enumerate() {
+        println!(
+            "  {}. {} {} {}",
+            index + 1,
+            candidate.commit_id[..7].yellow(),
+            candidate.subject,
+            format!(
+                "({} line(s) last changed here, {} shared file(s))",
+                candidate.blamed_lines,
+                candidate.shared_files.len()
+            )
+            .dimmed()
+        );
+    }
+    println!();
+
+    let target = if commit {
+        Some(&shown[0])
+    } else if ci::is_ci() || !io::stdin().is_terminal() {
+        None
+    } else {
+        print!(
+            "{}",
+            "Create a fixup! commit? Enter a number, or press Enter to skip: ".cyan()
+        );
+        io::Write::flush(&mut io::stdout())?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        answer
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| shown.get(number.wrapping_sub(1)))
+    };
+
+    if let Some(target) = target {
+        git::commit(&repo_path, &target.fixup_message())?;
+        ui::print_success(&format!("Created {}", target.fixup_message()));
+        ui::print_info(&format!(
+            "Fold it in with: git rebase -i --autosquash {}~1",
+            &target.commit_id[..7]
+        ));
+    }
+
+    Ok(())
+}
+
+/// Handle the 'wip' command
+pub async fn handle_wip_command(
+    commit: bool,
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let repo_path = env::current_dir()?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let spinner = ui::create_spinner("Summarizing work in progress...");
+    let summary = WipSummaryGenerator::generate(&repo_path, &config).await?;
+    spinner.finish_and_clear();
+
+    if !commit {
+        println!("{}", summary.subject);
+        if !summary.narrative.is_empty() {
+            println!("\n{}", summary.narrative);
+        }
+        return Ok(());
+    }
+
+    let message = summary.to_commit_message();
+    git::stage_all(&repo_path, true)?;
+    git::commit(&repo_path, &message)?;
+    ui::print_success(&format!("Committed work in progress: {}{}", WIP_PREFIX, summary.subject));
+
+    Ok(())
+}
+
+/// Handle the 'notes' command
+pub async fn handle_notes_command(
+    range: String,
+    instructions: Option<String>,
+    preset: Option<String>,
+    force: bool,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let repo_path = env::current_dir()?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let commits = commits_in_range(&repo_path, &range)?;
+
+    let mut written = 0;
+    let mut skipped = 0;
+    for (i, commit) in commits.iter().enumerate() {
+        let short_id = &commit[..7];
+        if !force && read_note(&repo_path, commit)?.is_some() {
+            skipped += 1;
+            continue;
+        }
+
+        let spinner = ui::create_spinner(&format!(
+            "Explaining {} ({}/{})...",
+            short_id,
+            i + 1,
+            commits.len()
+        ));
+        let explanation = CommitExplainer::explain(&repo_path, commit, &config).await?;
+        spinner.finish_and_clear();
+
+        write_note(&repo_path, commit, &explanation)?;
+        written += 1;
+
+        let message = git::get_commit_message(&repo_path, commit)?;
+        println!(
+            "{} {} {}",
+            "✔".bright_green(),
+            short_id.yellow(),
+            message.lines().next().unwrap_or_default()
+        );
+    }
+
+    ui::print_success(&format!("Added {} note(s) under {}.", written, NOTES_REF));
+    if skipped > 0 {
+        ui::print_info(&format!(
+            "Skipped {} commit(s) that already have a note. Use --force to regenerate them.",
+            skipped
+        ));
+    }
+    ui::print_info("View the notes with: git log --notes=iris");
 
     Ok(())
 }
 
-pub async fn handle_release_notes_command(
-    from: String,
-    to: Option<String>,
+/// Handle the 'serve' command
+pub async fn handle_serve_command() -> Result<()> {
+    // Stdout carries the protocol, so nothing else may be printed there
+    let server = McpServer::new(env::current_dir()?);
+    server.run().await
+}
+
+/// Handle the 'release' command
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_release_command(
+    version: Option<String>,
+    bump: Option<String>,
+    from: Option<String>,
+    changelog_file: String,
+    detail_level: Option<String>,
     instructions: Option<String>,
     preset: Option<String>,
-    detail_level: String,
     gitmoji: Option<bool>,
+    publish: bool,
+    dry_run: bool,
+    review: bool,
+    language: Option<String>,
 ) -> Result<()> {
     let mut config = Config::load()?;
-    let spinner = ui::create_spinner("Generating release notes...");
-
     let repo_path = env::current_dir()?;
-    let to = to.unwrap_or_else(|| "HEAD".to_string());
+    let changelog_detail = DetailLevel::from_str(
+        detail_level.as_deref().unwrap_or(&config.changelog.detail_level),
+    )?;
+    let release_notes_detail = DetailLevel::from_str(
+        detail_level.as_deref().unwrap_or(&config.release_notes.detail_level),
+    )?;
 
     // Set temporary instructions and preset
     config.set_temp_instructions(instructions);
     config.set_temp_preset(preset);
 
-    // Parse detail level
-    let detail_level = DetailLevel::from_str(&detail_level)?;
-
     // Override gitmoji setting if provided
     if let Some(use_gitmoji) = gitmoji {
         config.use_gitmoji = use_gitmoji;
     }
+    if let Some(language) = language {
+        config.language = language;
+    }
+
+    if dry_run {
+        ui::print_info("Dry run: nothing will be written, committed, tagged, or published.");
+    } else if git::has_staged_changes(&repo_path)? {
+        return Err(anyhow!(
+            "Commit or unstage your staged changes first; the release commit should only update {}",
+            changelog_file
+        ));
+    }
+
+    print_release_step(1, "Last release");
+    let previous_tag = git::get_latest_tag(&repo_path, "HEAD")?;
+    let from = match from.or_else(|| previous_tag.clone()) {
+        Some(from) => from,
+        None => {
+            return Err(anyhow!(
+                "No previous tag found. Please specify a starting reference with '--from'."
+            ))
+        }
+    };
+    println!("{}", previous_tag.as_deref().unwrap_or("None").cyan());
+
+    print_release_step(2, "Version");
+    let suggestion = suggest_next_version(&repo_path, "HEAD", &config)?;
+    if suggestion.commits == 0 {
+        return Err(anyhow!("No commits since the last release; nothing to release"));
+    }
+    let next = match (version, bump) {
+        (Some(version), _) => version,
+        (None, Some(bump)) => suggestion
+            .current_version
+            .as_deref()
+            .and_then(|current| next_version(current, Bump::from_str(&bump).ok()?))
+            .ok_or_else(|| anyhow!("Could not apply the bump. Pass the version to release explicitly"))?,
+        (None, None) => suggestion.next_version.clone().ok_or_else(|| {
+            anyhow!("Could not determine the next version. Pass the version to release explicitly")
+        })?,
+    };
+    let version = next.trim_start_matches(['v', 'V']).to_string();
+    let tag = release_tag_name(previous_tag.as_deref(), &version);
+    if git::tag_exists(&repo_path, &tag)? {
+        return Err(anyhow!("Tag '{}' already exists", tag));
+    }
+    println!(
+        "{} {} {}",
+        suggestion.current_version.as_deref().unwrap_or("none").cyan(),
+        "→".bright_purple(),
+        version.bright_green().bold()
+    );
+    for reason in &suggestion.justification {
+        println!("  {} {}", "•".bright_cyan(), reason);
+    }
+
+    print_release_step(3, "Changelog");
+    let changelog =
+        ChangelogGenerator::generate(&repo_path, &from, "HEAD", &config, changelog_detail)
+            .await?;
+    let Some(changelog) = review_if_requested(changelog, review)? else {
+        return Err(anyhow!("Changelog review cancelled; nothing was released"));
+    };
+    println!("{}", "━".repeat(50).bright_purple());
+    println!("{}", &changelog);
+    println!("{}", "━".repeat(50).bright_purple());
+
+    print_release_step(4, &format!("Update {}", changelog_file));
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let release_message = format!("Release {}", tag);
+    if dry_run {
+        println!(
+            "Would add a '## [{}] - {}' section to {} and commit it as '{}'",
+            version, date, changelog_file, release_message
+        );
+    } else {
+        update_changelog_file(&repo_path.join(&changelog_file), &version, &date, &changelog)?;
+        git::commit_paths(&repo_path, &[std::path::Path::new(&changelog_file)], &release_message)?;
+        ui::print_success(&format!("Updated {} and committed '{}'.", changelog_file, release_message));
+    }
+
+    print_release_step(5, &format!("Tag {}", tag));
+    let tag_message = TagMessageGenerator::generate(&repo_path, &tag, &from, "HEAD", &config).await?;
+    println!("{}", "━".repeat(50).bright_purple());
+    println!("{}", &tag_message);
+    println!("{}", "━".repeat(50).bright_purple());
+    if dry_run {
+        println!("Would create the annotated tag '{}'", tag);
+    } else {
+        git::create_annotated_tag(&repo_path, &tag, "HEAD", &tag_message)?;
+        ui::print_success(&format!("Created annotated tag '{}'.", tag));
+    }
 
+    print_release_step(6, "Release notes");
     let release_notes =
-        ReleaseNotesGenerator::generate(&repo_path, &from, &to, &config, detail_level).await?;
+        ReleaseNotesGenerator::generate(&repo_path, &from, "HEAD", &config, release_notes_detail)
+            .await?;
+    println!("{}", "━".repeat(50).bright_purple());
+    println!("{}", &release_notes);
+    println!("{}", "━".repeat(50).bright_purple());
+
+    print_release_step(7, "Publish");
+    if !publish {
+        println!("Skipped. Pass --publish to push the tag and publish a release.");
+        if !dry_run {
+            ui::print_info(&format!("Push the release with 'git push origin HEAD {}'.", tag));
+        }
+    } else if dry_run {
+        println!("Would push '{}' to origin and publish a release with the notes above", tag);
+    } else {
+        git::push_tag(&repo_path, "origin", &tag)?;
+        let url = publish_release(&repo_path, &tag, &release_notes).await?;
+        ui::print_success(&format!("Published release: {}", url));
+        ui::print_info("Push the release commit with 'git push origin HEAD'.");
+    }
+
+    Ok(())
+}
+
+fn print_release_step(step: usize, title: &str) {
+    println!(
+        "{}",
+        format!("\n[{}/7] {}", step, title).bright_purple().bold()
+    );
+}
+
+/// Handle the 'pr' command
+pub async fn handle_pr_command(
+    base: String,
+    instructions: Option<String>,
+    preset: Option<String>,
+    publish: bool,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let spinner = ui::create_spinner("Generating pull request description...");
+
+    let repo_path = env::current_dir()?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let description = PullRequestGenerator::generate(&repo_path, &base, &config).await?;
 
     spinner.finish_and_clear();
 
     println!("{}", "━".repeat(50).bright_purple());
-    println!("{}", &release_notes);
+    println!("{}", description.title.bold());
+    println!();
+    println!("{}", description.body);
+    println!("{}", "━".repeat(50).bright_purple());
+
+    if publish {
+        let spinner = ui::create_spinner("Opening pull request...");
+        let result = publish_pull_request(&repo_path, &base, &description).await;
+        spinner.finish_and_clear();
+
+        match result {
+            Ok(url) => ui::print_success(&format!("Pull request opened: {}", url)),
+            Err(e) => ui::print_error(&format!("Failed to open pull request: {}", e)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the 'doctor' command
+pub async fn handle_doctor_command(providers_only: bool) -> Result<()> {
+    log_debug!(
+        "Starting 'doctor' command with providers_only: {}",
+        providers_only
+    );
+
+    let mut failures = 0;
+    let mut warnings = 0;
+
+    if !providers_only {
+        println!("{}", "\n🩺 Setup Checks".bright_purple().bold());
+        println!("{}", "━".repeat(50).bright_purple());
+
+        for result in run_setup_checks(&env::current_dir()?) {
+            let (symbol, name) = match result.status {
+                CheckStatus::Pass => ("✔".bright_green(), result.name.bright_green().bold()),
+                CheckStatus::Warn => {
+                    warnings += 1;
+                    ("!".bright_yellow(), result.name.bright_yellow().bold())
+                }
+                CheckStatus::Fail => {
+                    failures += 1;
+                    ("✘".bright_red(), result.name.bright_red().bold())
+                }
+            };
+            println!("{} {:<10} {}", symbol, name, result.detail.bright_white());
+            if let Some(fix) = result.fix {
+                println!("  {} {}", "→".cyan(), fix.yellow());
+            }
+        }
+    }
+
+    // Provider checks need a readable configuration; the setup checks already report why it isn't
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) if !providers_only => {
+            log_debug!("Skipping provider checks: {}", e);
+            println!("{}", "━".repeat(50).bright_purple());
+            ui::print_warning(&format!(
+                "{} check(s) failed, {} warning(s). Provider checks were skipped.",
+                failures, warnings
+            ));
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    println!("{}", "\n🩺 Provider Checks".bright_purple().bold());
+    println!("{}", "━".repeat(50).bright_purple());
+
+    for provider_type in get_available_providers() {
+        if provider_type == LLMProviderType::Test {
+            continue;
+        }
+
+        // Only check the default provider and providers that have been set up with a key
+        let name = provider_type.to_string();
+        let is_configured = config
+            .get_provider_config(&name)
+            .is_some_and(|p| !p.api_key.is_empty());
+        if name != config.default_provider && !is_configured {
+            continue;
+        }
+
+        let model = config
+            .get_provider_config(&name)
+            .map(|p| p.model.clone())
+            .unwrap_or_else(|| get_provider_metadata(&provider_type).default_model.to_string());
+
+        match ping_provider(&config, &provider_type).await {
+            Ok(latency) => println!(
+                "{} {:<8} {} {}",
+                "✔".bright_green(),
+                name.bright_green().bold(),
+                model.cyan(),
+                format!("({} ms)", latency.as_millis()).bright_white()
+            ),
+            Err(e) => {
+                failures += 1;
+                println!(
+                    "{} {:<8} {}",
+                    "✘".bright_red(),
+                    name.bright_red().bold(),
+                    model.cyan()
+                );
+                println!("  {}", e.to_string().yellow());
+            }
+        }
+    }
+
     println!("{}", "━".repeat(50).bright_purple());
+    if failures == 0 && warnings == 0 {
+        ui::print_success("All checks passed.");
+    } else if failures == 0 {
+        ui::print_info(&format!("All checks passed, with {} warning(s).", warnings));
+    } else {
+        ui::print_warning(&format!(
+            "{} check(s) failed, {} warning(s).",
+            failures, warnings
+        ));
+    }
 
     Ok(())
 }
 
 /// Parse additional parameters from the command line
-fn parse_additional_params(params: &[String]) -> HashMap<String, String> {
+fn parse_additional_params(params: &[String]) -> Result<HashMap<String, String>> {
     params
         .iter()
-        .filter_map(|param| {
-            let parts: Vec<&str> = param.splitn(2, '=').collect();
-            if parts.len() == 2 {
-                Some((parts[0].to_string(), parts[1].to_string()))
-            } else {
-                None
+        .map(|param| match param.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.to_string()))
             }
+            _ => Err(anyhow!(
+                "Invalid parameter '{}'. Use --param name=value, e.g. --param temperature=0.7",
+                param
+            )),
         })
         .collect()
 }
 
 /// Handle the 'list_presets' command
 pub fn handle_list_presets_command() -> Result<()> {
-    let preset_library = get_instruction_preset_library();
+    let config = Config::load()?;
+    let preset_library = config.preset_library();
 
     println!(
         "{}",
@@ -411,10 +3599,17 @@ pub fn handle_list_presets_command() -> Result<()> {
 
     for (key, preset) in presets {
         println!(
-            "{} {:<width$} {}",
+            "{} {:<width$} {}{}",
             "•".bright_cyan(),
             key.bright_green().bold(),
             preset.name.cyan().italic(),
+            if config.repo_presets.contains_key(key) {
+                " (repository)".bright_yellow().to_string()
+            } else if config.custom_presets.contains_key(key) {
+                " (custom)".bright_yellow().to_string()
+            } else {
+                String::new()
+            },
             width = max_key_length
         );
         println!("  {}", format!("\"{}\"", preset.description).bright_white());
@@ -431,3 +3626,253 @@ pub fn handle_list_presets_command() -> Result<()> {
 
     Ok(())
 }
+
+/// Handle the 'preset add' command
+pub fn handle_preset_add_command(
+    name: String,
+    text: Option<String>,
+    from_file: Option<String>,
+    description: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    if config.custom_presets.contains_key(&name) {
+        return Err(anyhow!(
+            "A custom preset named '{}' already exists. Use 'git-iris preset edit' to change it",
+            name
+        ));
+    }
+
+    let instructions = read_preset_instructions(text, from_file)?
+        .ok_or_else(|| anyhow!("Provide the preset instructions with --text or --from-file"))?;
+
+    config.set_custom_preset(
+        &name,
+        InstructionPreset {
+            name: name.clone(),
+            description: description.unwrap_or_else(|| "Custom preset".to_string()),
+            instructions,
+        },
+    )?;
+    config.save()?;
+
+    ui::print_success(&format!(
+        "Added preset '{}'. Use it with --preset {}",
+        name, name
+    ));
+    Ok(())
+}
+
+/// Handle the 'preset edit' command
+pub fn handle_preset_edit_command(
+    name: String,
+    text: Option<String>,
+    from_file: Option<String>,
+    description: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let mut preset = config
+        .custom_presets
+        .get(&name)
+        .cloned()
+        .ok_or_else(|| anyhow!("No custom preset named '{}'", name))?;
+
+    match read_preset_instructions(text, from_file)? {
+        Some(instructions) => preset.instructions = instructions,
+        // Without new text or a new description, edit the instructions in $EDITOR
+        None if description.is_none() => match edit_in_editor(&preset.instructions)? {
+            Some(instructions) if !instructions.trim().is_empty() => {
+                preset.instructions = instructions.trim().to_string();
+            }
+            _ => {
+                ui::print_info("Preset left unchanged.");
+                return Ok(());
+            }
+        },
+        None => {}
+    }
+    if let Some(description) = description {
+        preset.description = description;
+    }
+
+    config.set_custom_preset(&name, preset)?;
+    config.save()?;
+
+    ui::print_success(&format!("Updated preset '{}'.", name));
+    Ok(())
+}
+
+/// Handle the 'preset remove' command
+pub fn handle_preset_remove_command(name: String) -> Result<()> {
+    let mut config = Config::load()?;
+    config.remove_custom_preset(&name)?;
+    config.save()?;
+
+    ui::print_success(&format!("Removed preset '{}'.", name));
+    Ok(())
+}
+
+/// Read preset instructions from --text or --from-file, whichever was given
+fn read_preset_instructions(
+    text: Option<String>,
+    from_file: Option<String>,
+) -> Result<Option<String>> {
+    let instructions = match (text, from_file) {
+        (Some(text), _) => text,
+        (None, Some(path)) => fs::read_to_string(&path)
+            .map_err(|e| anyhow!("Failed to read preset file '{}': {}", path, e))?,
+        (None, None) => return Ok(None),
+    };
+
+    let instructions = instructions.trim().to_string();
+    if instructions.is_empty() {
+        return Err(anyhow!("Preset instructions can't be empty"));
+    }
+    Ok(Some(instructions))
+}
+
+/// Open text in $EDITOR and return the edited text, or None if the editor failed
+fn edit_in_editor(text: &str) -> Result<Option<String>> {
+    if ci::is_ci() {
+        return Err(anyhow!(
+            "Cannot open an editor in CI mode. Pass the text on the command line instead"
+        ));
+    }
+
+    let mut file = tempfile::NamedTempFile::new()?;
+    io::Write::write_all(&mut file, text.as_bytes())?;
+
+    let path = file.into_temp_path();
+    let editor = env::var("EDITOR").unwrap_or_else(|_| "vim".to_string());
+
+    let status = std::process::Command::new(editor).arg(&path).status()?;
+    if status.success() {
+        Ok(Some(fs::read_to_string(&path)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Handle the 'explain' command
+pub async fn handle_explain_command(
+    commit: String,
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let spinner = ui::create_spinner("Explaining commit...");
+
+    let repo_path = env::current_dir()?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let explanation = CommitExplainer::explain(&repo_path, &commit, &config).await?;
+
+    spinner.finish_and_clear();
+
+    println!("{}", "━".repeat(50).bright_purple());
+    println!("{}", &explanation);
+    println!("{}", "━".repeat(50).bright_purple());
+
+    Ok(())
+}
+
+/// Handle the 'conflicts' command
+pub async fn handle_conflicts_command(
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let repo_path = env::current_dir()?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let context = ConflictContext::load(&repo_path)?;
+    if context.files.is_empty() {
+        ui::print_info("No conflicts found in the working tree.");
+        return Ok(());
+    }
+
+    let spinner = ui::create_spinner(&format!(
+        "Explaining conflicts in {} file(s)...",
+        context.files.len()
+    ));
+    let explanation = ConflictExplainer::explain(&context, &config).await?;
+    spinner.finish_and_clear();
+
+    println!("{}", "━".repeat(50).bright_purple());
+    println!("{}", &explanation);
+    println!("{}", "━".repeat(50).bright_purple());
+
+    Ok(())
+}
+
+/// Handle the 'explain-diff' command
+pub async fn handle_explain_diff_command(
+    file: Option<String>,
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+
+    let diff = match file {
+        Some(path) => fs::read_to_string(&path)
+            .map_err(|e| anyhow!("Failed to read diff from '{}': {}", path, e))?,
+        None => {
+            if io::stdin().is_terminal() {
+                return Err(anyhow!(
+                    "No diff provided. Pipe a diff into git-iris explain-diff or use --file"
+                ));
+            }
+            let mut diff = String::new();
+            io::stdin().read_to_string(&mut diff)?;
+            diff
+        }
+    };
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let spinner = ui::create_spinner("Explaining diff...");
+    let explanation = DiffExplainer::explain(&diff, &config).await?;
+    spinner.finish_and_clear();
+
+    println!("{}", "━".repeat(50).bright_purple());
+    println!("{}", &explanation);
+    println!("{}", "━".repeat(50).bright_purple());
+
+    Ok(())
+}
+
+/// Handle the 'blame-explain' command
+pub async fn handle_blame_explain_command(
+    target: String,
+    max_commits: usize,
+    instructions: Option<String>,
+    preset: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let (path, start, end) = parse_line_target(&target)?;
+    let spinner = ui::create_spinner("Explaining line history...");
+
+    let repo_path = env::current_dir()?;
+
+    // Set temporary instructions and preset
+    config.set_temp_instructions(instructions);
+    config.set_temp_preset(preset);
+
+    let explanation =
+        BlameExplainer::explain(&repo_path, &path, start, end, max_commits, &config).await?;
+
+    spinner.finish_and_clear();
+
+    println!("{}", "━".repeat(50).bright_purple());
+    println!("{}", &explanation);
+    println!("{}", "━".repeat(50).bright_purple());
+
+    Ok(())
+}
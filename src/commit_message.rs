@@ -0,0 +1,299 @@
+use crate::config::MessageFormatConfig;
+use crate::gitmoji::get_gitmoji_emojis;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Maximum display width of a commit subject line, in terminal columns
+pub const MAX_SUBJECT_WIDTH: usize = 72;
+
+/// Words a shortened subject line shouldn't end with
+const DANGLING_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "by", "for", "from", "in", "into", "of", "on", "or", "the",
+    "to", "with",
+];
+
+/// Get the subject (first line) of a commit message
+pub fn subject_line(message: &str) -> &str {
+    message.trim_start().lines().next().unwrap_or("").trim_end()
+}
+
+/// Get the display width of the subject line, counting wide characters such as emoji as two columns
+pub fn subject_width(message: &str) -> usize {
+    subject_line(message).width()
+}
+
+/// Check whether the subject line is wider than the given number of columns
+pub fn is_subject_too_long(message: &str, limit: usize) -> bool {
+    subject_width(message) > limit
+}
+
+/// Split a leading emoji (gitmoji or otherwise) off the start of a subject line
+pub fn split_leading_emoji(subject: &str) -> (Option<&str>, &str) {
+    let subject = subject.trim_start();
+    let (first, rest) = subject.split_once(' ').unwrap_or((subject, ""));
+
+    let is_gitmoji = get_gitmoji_emojis().iter().any(|emoji| emoji == first);
+    let is_symbol = !first.is_ascii() && !first.chars().any(char::is_alphanumeric);
+
+    if !first.is_empty() && (is_gitmoji || is_symbol) {
+        (Some(first), rest.trim_start())
+    } else {
+        (None, subject)
+    }
+}
+
+/// Move a leading emoji from the subject line to the start of the body
+pub fn move_gitmoji_to_body(message: &str) -> String {
+    let message = message.trim();
+    let (subject, body) = message.split_once('\n').unwrap_or((message, ""));
+
+    match split_leading_emoji(subject) {
+        (Some(emoji), subject) => {
+            let body = body.trim();
+            if body.is_empty() {
+                format!("{}\n\n{}", subject, emoji)
+            } else {
+                format!("{}\n\n{} {}", subject, emoji, body)
+            }
+        }
+        (None, _) => message.to_string(),
+    }
+}
+
+/// Replace the message's gitmoji, wherever `move_gitmoji_to_body` may have put it, adding it to
+/// the subject line if there is none
+pub fn replace_gitmoji(message: &str, emoji: &str) -> String {
+    let message = message.trim();
+    let (subject, rest) = message.split_once('\n').unwrap_or((message, ""));
+    if let (Some(_), text) = split_leading_emoji(subject) {
+        return join_subject(&format!("{} {}", emoji, text), rest);
+    }
+
+    let body = rest.trim_start_matches('\n');
+    if let (Some(_), text) = split_leading_emoji(body) {
+        let body = if text.is_empty() {
+            emoji.to_string()
+        } else {
+            format!("{} {}", emoji, text)
+        };
+        return format!("{}\n\n{}", subject, body);
+    }
+    join_subject(&format!("{} {}", emoji, subject), rest)
+}
+
+fn join_subject(subject: &str, rest: &str) -> String {
+    if rest.is_empty() {
+        subject.to_string()
+    } else {
+        format!("{}\n{}", subject, rest)
+    }
+}
+
+/// Give a generated message the configured layout: shorten a long subject line at a word
+/// boundary, moving the rest of it to the start of the body, separate the subject from the
+/// body with a blank line, and re-wrap the body's paragraphs
+///
+/// List items keep their markers and wrap with a hanging indent. Code blocks, indented lines,
+/// tables, and trailers such as `Signed-off-by:` are left as they are.
+pub fn enforce_format(message: &str, format: &MessageFormatConfig) -> String {
+    let message = message.trim();
+    let (subject, rest) = message.split_once('\n').unwrap_or((message, ""));
+    let (subject, overflow) = split_subject(subject.trim_end(), format.max_subject_length);
+
+    let had_blank_line = rest.lines().next().is_some_and(|line| line.trim().is_empty());
+    let body = rest
+        .lines()
+        .skip_while(|line| line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let body = match overflow {
+        Some(overflow) if body.trim().is_empty() => overflow,
+        Some(overflow) => format!("{}\n\n{}", overflow, body),
+        None => body,
+    };
+    let body = if format.wrap_body_at > 0 {
+        wrap_body(&body, format.wrap_body_at)
+    } else {
+        body.trim_end().to_string()
+    };
+    if body.trim().is_empty() {
+        subject
+    } else if format.blank_line_after_subject || had_blank_line {
+        format!("{}\n\n{}", subject, body)
+    } else {
+        format!("{}\n{}", subject, body)
+    }
+}
+
+/// Split a subject line wider than the given width into a shortened subject ending in `…` and
+/// the rest of it, starting with `…`, so no words are lost
+pub fn split_subject(subject: &str, max_width: usize) -> (String, Option<String>) {
+    if max_width == 0 || subject.width() <= max_width {
+        return (subject.to_string(), None);
+    }
+    let subject = subject.split_whitespace().collect::<Vec<_>>().join(" ");
+    // One column is left for the ellipsis
+    let shortened = shorten_subject(&subject, max_width.saturating_sub(1).max(1));
+    let rest = subject[shortened.len()..].trim_start_matches([',', ';', ' ']);
+    (format!("{}…", shortened), Some(format!("…{}", rest)))
+}
+
+/// Cut a subject line down to the given width at a word boundary
+pub fn shorten_subject(subject: &str, max_width: usize) -> String {
+    if max_width == 0 || subject.width() <= max_width {
+        return subject.to_string();
+    }
+    let mut words: Vec<&str> = Vec::new();
+    for word in subject.split_whitespace() {
+        let candidate = if words.is_empty() {
+            word.width()
+        } else {
+            words.join(" ").width() + 1 + word.width()
+        };
+        if candidate > max_width {
+            break;
+        }
+        words.push(word);
+    }
+    while words.len() > 1
+        && words
+            .last()
+            .is_some_and(|word| DANGLING_WORDS.contains(&word.to_lowercase().as_str()))
+    {
+        words.pop();
+    }
+    let shortened = words.join(" ");
+    let shortened = shortened.trim_end_matches([',', ';', ':', '-', '(']);
+    if shortened.is_empty() {
+        // A single word wider than the limit
+        return subject
+            .chars()
+            .scan(0, |width, c| {
+                *width += c.width().unwrap_or(0);
+                (*width <= max_width).then_some(c)
+            })
+            .collect();
+    }
+    shortened.to_string()
+}
+
+fn wrap_body(body: &str, width: usize) -> String {
+    let mut output: Vec<String> = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut in_code_block = false;
+
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            output.extend(wrap_paragraph(&paragraph, width));
+            paragraph.clear();
+            in_code_block = !in_code_block;
+            output.push(line.trim_end().to_string());
+        } else if in_code_block || is_verbatim_line(line) {
+            output.extend(wrap_paragraph(&paragraph, width));
+            paragraph.clear();
+            output.push(line.trim_end().to_string());
+        } else if line.trim().is_empty() {
+            output.extend(wrap_paragraph(&paragraph, width));
+            paragraph.clear();
+            output.push(String::new());
+        } else {
+            if list_marker(line).is_some() {
+                output.extend(wrap_paragraph(&paragraph, width));
+                paragraph.clear();
+            }
+            paragraph.push(line);
+        }
+    }
+    output.extend(wrap_paragraph(&paragraph, width));
+    output.join("\n").trim_end().to_string()
+}
+
+/// Wrap the lines of one paragraph or list item as a single block of text
+fn wrap_paragraph(lines: &[&str], width: usize) -> Vec<String> {
+    let Some(first) = lines.first() else {
+        return Vec::new();
+    };
+    let text = lines
+        .iter()
+        .map(|line| line.trim())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let indent = " ".repeat(list_marker(first).unwrap_or(0));
+    // Break only at spaces, so URLs and hyphenated names stay whole
+    let options = textwrap::Options::new(width)
+        .break_words(false)
+        .word_separator(textwrap::WordSeparator::AsciiSpace)
+        .word_splitter(textwrap::WordSplitter::NoHyphenation)
+        .subsequent_indent(&indent);
+    textwrap::wrap(&text, options)
+        .into_iter()
+        .map(|line| line.into_owned())
+        .collect()
+}
+
+/// Width of a list item's marker, such as `- ` or `2. `, including its indentation
+fn list_marker(line: &str) -> Option<usize> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+    if ["- ", "* ", "+ "].iter().any(|marker| trimmed.starts_with(marker)) {
+        return Some(indent + 2);
+    }
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    (digits > 0 && trimmed[digits..].starts_with(". ")).then_some(indent + digits + 2)
+}
+
+/// Lines that must keep their layout: indented code, tables, and trailers
+fn is_verbatim_line(line: &str) -> bool {
+    if line.starts_with("    ") || line.starts_with('\t') || line.trim_start().starts_with('|') {
+        return true;
+    }
+    // Trailers such as "Signed-off-by: Name <email>" or "BREAKING CHANGE: ..."
+    line.split_once(": ").is_some_and(|(key, _)| {
+        !key.is_empty()
+            && (key == "BREAKING CHANGE"
+                || key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') && key.contains('-'))
+    })
+}
+
+/// Summarize how one candidate message differs from another, line by line
+///
+/// Lines only in `from` are prefixed with `- ` and lines only in `to` with `+ `. At most
+/// `max_lines` changes are listed, followed by a count of the rest.
+pub fn candidate_diff(from: &str, to: &str, max_lines: usize) -> Vec<String> {
+    let old: Vec<&str> = from.trim().lines().collect();
+    let new: Vec<&str> = to.trim().lines().collect();
+
+    // Longest common subsequence table, filled from the end
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            changes.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            changes.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+
+    if changes.len() > max_lines {
+        let hidden = changes.len() - max_lines;
+        changes.truncate(max_lines);
+        changes.push(format!("… {} more changed line(s)", hidden));
+    }
+    changes
+}
@@ -1,19 +1,89 @@
-use crate::instruction_presets::get_instruction_preset_library;
+use crate::commit_message::MAX_SUBJECT_WIDTH;
+use crate::config_migration::{self, CONFIG_VERSION};
+use crate::config_validation::{validate_all, ConfigProblem};
+use crate::gitmoji::{self, GitmojiStyle};
+use crate::glossary;
+use crate::instruction_presets::{
+    get_instruction_preset_library, is_builtin_preset, load_repo_presets, InstructionPreset,
+    InstructionPresetLibrary, REPO_PRESETS_DIR,
+};
+use crate::keyring;
+use crate::language::{Language, DEFAULT_LANGUAGE};
 use crate::llm_providers::{
     get_available_providers, get_provider_metadata, LLMProviderConfig, LLMProviderType,
+    NetworkSettings,
 };
 use crate::log_debug;
+use crate::risk::RiskLabel;
 use anyhow::{anyhow, Result};
+use colored::*;
 use dirs::config_dir;
+use git2::Repository;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Settings shared by everyone working on a repository, committed at its root
+pub const SHARED_CONFIG_FILE: &str = ".git-iris.toml";
+
+/// Settings for a single clone, kept inside its `.git` directory
+const LOCAL_CONFIG_FILE: &str = "iris/config.toml";
+
+/// Environment variables that override a setting, as `GIT_IRIS_PROVIDER=claude` does
+///
+/// API keys and models are set per provider with `GIT_IRIS_<PROVIDER>_API_KEY` and
+/// `GIT_IRIS_<PROVIDER>_MODEL`.
+pub const ENV_SETTINGS: &[(&str, &str)] = &[
+    ("GIT_IRIS_PROVIDER", "default_provider"),
+    ("GIT_IRIS_PROFILE", "profile"),
+    ("GIT_IRIS_PRESET", "instruction_preset"),
+    ("GIT_IRIS_INSTRUCTIONS", "instructions"),
+    ("GIT_IRIS_LANGUAGE", "language"),
+    ("GIT_IRIS_GITMOJI", "use_gitmoji"),
+];
+
+/// Configuration file in the home directory used by earlier versions
+const LEGACY_CONFIG_FILE: &str = ".git-iris";
+
+/// Profile chosen on the command line with `--profile`
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Whether the user has been told in this process that the configuration file uses an older
+/// layout
+static MIGRATION_NOTICE: AtomicBool = AtomicBool::new(false);
+
+/// Whether the configuration has been checked for problems in this process
+static VALIDATED: AtomicBool = AtomicBool::new(false);
+
+/// Configuration file chosen on the command line with `--config`
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Read and write the configuration at the given path instead of the default location
+pub fn set_config_path(path: PathBuf) {
+    let _ = CONFIG_PATH.set(path);
+}
+
+/// Use the named profile instead of the configured default one
+pub fn set_profile(name: String) {
+    let _ = PROFILE.set(name);
+}
+
+/// Don't warn about configuration problems when loading, for commands that report them in full
+pub fn skip_validation_on_load() {
+    VALIDATED.store(true, Ordering::Relaxed);
+}
 
 /// Configuration structure for the Git-Iris application
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Config {
+    /// Layout version of the settings, used to upgrade files written by older versions
+    #[serde(default = "default_config_version")]
+    pub version: u32,
     /// Default LLM provider
     pub default_provider: String,
     /// Provider-specific configurations
@@ -21,15 +91,87 @@ pub struct Config {
     /// Flag indicating whether to use Gitmoji
     #[serde(default = "default_gitmoji")]
     pub use_gitmoji: bool,
+    /// Flag indicating whether to move the Gitmoji from the subject line to the body
+    #[serde(default)]
+    pub gitmoji_in_body: bool,
+    /// File mapping change types to the user's own gitmojis, relative to the configuration
+    /// directory unless absolute
+    pub gitmoji_file: Option<String>,
+    /// Whether gitmojis are written as emoji or as `:shortcode:`s
+    #[serde(default)]
+    pub gitmoji_style: GitmojiStyle,
     /// Instructions for commit messages
     #[serde(default)]
     pub instructions: String,
     #[serde(default = "default_instruction_preset")]
     pub instruction_preset: String,
+    /// Natural language generated text is written in, as an ISO 639-1 code
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// User-defined instruction presets, keyed by name
+    #[serde(default)]
+    pub custom_presets: HashMap<String, InstructionPreset>,
+    /// Risk labels to surface in reviews and changelogs
+    #[serde(default = "default_risk_labels")]
+    pub risk_labels: Vec<String>,
+    /// Pattern for suggested branch names
+    #[serde(default = "default_branch_name_pattern")]
+    pub branch_name_pattern: String,
+    /// Rules for validating commit messages
+    #[serde(default)]
+    pub lint: LintConfig,
+    /// Layout enforced on generated commit messages
+    #[serde(default)]
+    pub format: MessageFormatConfig,
+    /// Conventional Commits mode for generated commit messages
+    #[serde(default)]
+    pub conventional: ConventionalConfig,
+    /// Patterns for text replaced in prompts before they are sent to a provider
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+    /// How many recent commits prompts include, and how much of each
+    #[serde(default)]
+    pub recent_commits: RecentCommitsConfig,
+    /// Defaults for the `changelog` command
+    #[serde(default)]
+    pub changelog: ReleaseTextConfig,
+    /// Defaults for the `release-notes` command
+    #[serde(default)]
+    pub release_notes: ReleaseTextConfig,
+    /// Project terms and what they mean, so generated text uses the project's own vocabulary
+    #[serde(default)]
+    pub glossary: HashMap<String, String>,
+    /// Rules the repository sets for everyone working on it, read only from its shared settings
+    #[serde(default, skip_serializing_if = "PolicyConfig::is_empty")]
+    pub policy: PolicyConfig,
+    /// Glob patterns for staged files whose contents are left out of prompts
+    #[serde(default)]
+    pub prompt_exclude: Vec<String>,
+    /// Glob patterns for files whose contents are kept in prompts, even if excluded by default
+    #[serde(default)]
+    pub prompt_include: Vec<String>,
+    /// Profile to use when none is given with `--profile`
+    pub profile: Option<String>,
+    /// Named sets of settings layered over the global ones, such as a provider for work
+    #[serde(default)]
+    pub profiles: HashMap<String, toml::Table>,
     #[serde(skip)]
     pub temp_instructions: Option<String>,
     #[serde(skip)]
     pub temp_preset: Option<String>,
+    /// The profile in use, if any
+    #[serde(skip)]
+    pub active_profile: Option<String>,
+    /// Presets from the current repository's preset directory, keyed by name
+    #[serde(skip)]
+    pub repo_presets: HashMap<String, InstructionPreset>,
+    /// The current repository's preset directory, if it is in one
+    #[serde(skip)]
+    pub repo_presets_dir: Option<PathBuf>,
+    /// Settings from the profile and the repository's configuration files, layered over the
+    /// global ones
+    #[serde(skip)]
+    pub layers: Option<toml::Value>,
 }
 
 /// Provider-specific configuration structure
@@ -44,6 +186,218 @@ pub struct ProviderConfig {
     pub additional_params: HashMap<String, String>,
     /// Token limit, if set by the user
     pub token_limit: Option<usize>,
+    /// Keep the API key in the system keyring instead of the configuration file
+    #[serde(default)]
+    pub keyring: bool,
+    /// Seconds to wait for a response, if limited
+    pub timeout: Option<u64>,
+    /// Times a request is retried after a timeout, a connection error, a rate limit, or a
+    /// server error
+    pub max_retries: Option<u32>,
+    /// Proxy URL for requests to the provider
+    pub proxy: Option<String>,
+    /// Headers added to every request, such as those required by an API gateway
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+}
+
+/// Rules `git-iris lint` checks commit messages against
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct LintConfig {
+    /// Require the Conventional Commits format for subject lines
+    pub conventional: bool,
+    /// Maximum width of the subject line in columns, or 0 for no limit
+    pub max_subject_length: usize,
+    /// Require the subject line to use the imperative mood
+    pub imperative_mood: bool,
+    /// Column to wrap body lines at, or 0 to allow any width
+    pub max_body_line_length: usize,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            conventional: false,
+            max_subject_length: MAX_SUBJECT_WIDTH,
+            imperative_mood: true,
+            max_body_line_length: 72,
+        }
+    }
+}
+
+/// Layout of generated commit messages, applied after generation instead of left to the model
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct MessageFormatConfig {
+    /// Subject width in columns the model is asked to stay within
+    pub subject_length: usize,
+    /// Subject width in columns that longer subjects are shortened to, or 0 for no limit
+    pub max_subject_length: usize,
+    /// Column to re-wrap body paragraphs at, or 0 to leave the body as generated
+    pub wrap_body_at: usize,
+    /// Separate the subject from the body with a blank line
+    pub blank_line_after_subject: bool,
+}
+
+impl Default for MessageFormatConfig {
+    fn default() -> Self {
+        Self {
+            subject_length: 50,
+            max_subject_length: MAX_SUBJECT_WIDTH,
+            wrap_body_at: 72,
+            blank_line_after_subject: true,
+        }
+    }
+}
+
+/// Settings for generating commit messages in the Conventional Commits format
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct ConventionalConfig {
+    /// Generate `type(scope): subject` messages and validate them before they are shown
+    pub enabled: bool,
+    /// Scopes for changed paths, keyed by glob pattern; the most specific match wins
+    pub scopes: HashMap<String, String>,
+}
+
+/// Text to hide from providers: every match in a prompt is replaced before it is sent
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct RedactionConfig {
+    /// Regular expressions, or globs written as `glob:<pattern>`
+    pub patterns: Vec<String>,
+    /// Text that replaces each match
+    pub replacement: String,
+    /// Whether likely secrets, such as API keys, private keys, and passwords, are replaced
+    /// too, without a pattern for them
+    pub detect_secrets: bool,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            patterns: Vec::new(),
+            replacement: "[REDACTED]".to_string(),
+            detect_secrets: true,
+        }
+    }
+}
+
+/// Commands whose prompts include recent commits, which can override the recent commit settings
+pub const RECENT_COMMIT_COMMANDS: &[&str] =
+    &["gen", "amend", "review", "split", "rewrite", "revert", "branch-name"];
+
+/// Recent commits shown to the model as examples of the repository's style
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct RecentCommitsConfig {
+    /// Number of commits, 0 to leave them out
+    pub count: usize,
+    /// Whether to include each commit's full message rather than only its subject
+    pub bodies: bool,
+    /// Overrides for single commands, keyed by command name such as `review`
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub commands: HashMap<String, RecentCommitsOverride>,
+}
+
+impl Default for RecentCommitsConfig {
+    fn default() -> Self {
+        Self {
+            count: 5,
+            bodies: true,
+            commands: HashMap::new(),
+        }
+    }
+}
+
+impl RecentCommitsConfig {
+    /// The settings for a command, with its overrides applied
+    pub fn for_command(&self, command: &str) -> Self {
+        let mut settings = self.clone();
+        if let Some(overrides) = self.commands.get(command) {
+            settings.count = overrides.count.unwrap_or(self.count);
+            settings.bodies = overrides.bodies.unwrap_or(self.bodies);
+        }
+        settings
+    }
+}
+
+/// Recent commit settings for one command; unset fields use the global ones
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RecentCommitsOverride {
+    pub count: Option<usize>,
+    pub bodies: Option<bool>,
+}
+
+/// Defaults for a command generating a changelog or release notes, used when its flags are
+/// not given
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct ReleaseTextConfig {
+    /// Detail level: minimal, standard, or detailed
+    pub detail_level: String,
+    /// Output format: markdown or json
+    pub format: String,
+    /// File to write to instead of printing, relative to the current directory unless absolute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl Default for ReleaseTextConfig {
+    fn default() -> Self {
+        Self {
+            detail_level: "standard".to_string(),
+            format: "markdown".to_string(),
+            output: None,
+        }
+    }
+}
+
+/// Rules a repository enforces through its shared settings, which no other settings or flags
+/// can relax
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct PolicyConfig {
+    /// Whether generated commit messages always follow Conventional Commits
+    pub conventional: bool,
+    /// Providers that prompts must never be sent to
+    pub forbidden_providers: Vec<String>,
+}
+
+impl PolicyConfig {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Fail if the policy forbids sending prompts to the provider
+    pub fn check_provider(&self, provider: &str) -> Result<()> {
+        if self
+            .forbidden_providers
+            .iter()
+            .any(|forbidden| forbidden.eq_ignore_ascii_case(provider))
+        {
+            return Err(anyhow!(
+                "The repository's policy in {} forbids the provider '{}'. Choose another with \
+                --provider or 'git-iris config --provider'",
+                SHARED_CONFIG_FILE,
+                provider
+            ));
+        }
+        Ok(())
+    }
+
+    /// The settings the policy forces, as a layer over all others
+    fn layer(&self) -> Option<toml::Value> {
+        if !self.conventional {
+            return None;
+        }
+        let mut conventional = toml::Table::new();
+        conventional.insert("enabled".to_string(), toml::Value::Boolean(true));
+        let mut layer = toml::Table::new();
+        layer.insert("conventional".to_string(), toml::Value::Table(conventional));
+        Some(toml::Value::Table(layer))
+    }
 }
 
 /// Default function for use_gitmoji
@@ -56,35 +410,245 @@ fn default_instruction_preset() -> String {
     "default".to_string()
 }
 
+fn default_config_version() -> u32 {
+    CONFIG_VERSION
+}
+
+fn default_language() -> String {
+    DEFAULT_LANGUAGE.to_string()
+}
+
+// All risk labels are enabled by default
+fn default_risk_labels() -> Vec<String> {
+    RiskLabel::all()
+        .iter()
+        .map(|label| label.key().to_string())
+        .collect()
+}
+
+// Default pattern for suggested branch names
+fn default_branch_name_pattern() -> String {
+    "{type}/{description}".to_string()
+}
+
 impl Config {
-    /// Load the configuration from the file
+    /// Load the global configuration, with the settings of the current repository layered over it
     pub fn load() -> Result<Self> {
-        let config_path = Config::get_config_path()?;
-        if !config_path.exists() {
-            return Ok(Config::default());
+        let mut repo_layer = match std::env::current_dir() {
+            Ok(dir) => read_repo_layer(&dir)?,
+            Err(_) => None,
+        };
+        // Environment variables come right below command-line flags
+        if let Some(env) = env_layer(std::env::vars())? {
+            match &mut repo_layer {
+                Some(layer) => merge_toml(layer, &env),
+                None => repo_layer = Some(env),
+            }
         }
-        let config_content = fs::read_to_string(config_path)?;
-        let config: Config = toml::from_str(&config_content)?;
-        log_debug!("Configuration loaded: {:?}", config);
+        let profile = PROFILE.get().map(String::as_str);
+        let mut config = Config::from_layers(read_global_value()?, repo_layer, profile)?;
+        if let Ok(dir) = std::env::current_dir() {
+            config.load_repo_presets(&dir);
+        }
+        for (name, provider_config) in &mut config.providers {
+            if provider_config.keyring && provider_config.api_key.is_empty() {
+                match keyring::load_api_key(name) {
+                    Ok(Some(api_key)) => provider_config.api_key = api_key,
+                    Ok(None) => log_debug!("No {} API key in the system keyring", name),
+                    Err(e) => log_debug!("Failed to read the {} API key: {}", name, e),
+                }
+            }
+        }
+        // A broken mapping file is reported with the other problems below
+        let gitmoji_file = config.gitmoji_file_path().ok().flatten();
+        if let Err(e) = gitmoji::configure(gitmoji_file.as_deref(), config.gitmoji_style) {
+            log_debug!("Using the built-in gitmojis: {}", e);
+        }
+        if !VALIDATED.swap(true, Ordering::Relaxed) {
+            warn_about_problems(&validate_all(&config));
+        }
+        log_debug!("Configuration loaded: {:?}", config.without_keyring_keys());
         Ok(config)
     }
 
-    /// Save the configuration to the file
+    /// Where the gitmoji mapping file is, if one is configured
+    pub fn gitmoji_file_path(&self) -> Result<Option<PathBuf>> {
+        let Some(file) = self.gitmoji_file.as_deref().filter(|file| !file.is_empty()) else {
+            return Ok(None);
+        };
+        let path = match file.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir()
+                .ok_or_else(|| anyhow!("Unable to determine the home directory"))?
+                .join(rest),
+            None => PathBuf::from(file),
+        };
+        if path.is_absolute() {
+            return Ok(Some(path));
+        }
+        let config_path = Config::get_config_path()?;
+        let dir = config_path.parent().unwrap_or(Path::new("."));
+        Ok(Some(dir.join(path)))
+    }
+
+    /// Save the configuration to the file, and API keys kept in the keyring to the keyring
     pub fn save(&self) -> Result<()> {
+        for (name, provider_config) in &self.providers {
+            let api_key = &provider_config.api_key;
+            if provider_config.keyring
+                && !api_key.is_empty()
+                && keyring::load_api_key(name)?.as_ref() != Some(api_key)
+            {
+                keyring::store_api_key(name, api_key)?;
+            }
+        }
+        let stored = self.global_value(read_global_value()?)?;
         let config_path = Config::get_config_path()?;
-        let config_content = toml::to_string(self)?;
+        let config_content = toml::to_string(&stored)?;
         fs::write(config_path, config_content)?;
-        log_debug!("Configuration saved: {:?}", self);
+        log_debug!("Configuration saved: {:?}", stored);
         Ok(())
     }
 
-    /// Get the path to the configuration file
-    fn get_config_path() -> Result<PathBuf> {
+    /// Build the configuration from the global settings, the given profile or else the
+    /// configured default one, and the repository's settings, in that order
+    ///
+    /// A policy is only taken from the repository's settings, and the settings it forces are
+    /// layered over everything else.
+    pub fn from_layers(
+        mut global: toml::Value,
+        repo_layer: Option<toml::Value>,
+        profile: Option<&str>,
+    ) -> Result<Self> {
+        if let Some(table) = global.as_table_mut() {
+            table.remove("policy");
+        }
+        let mut merged = global.clone();
+        if let Some(layer) = &repo_layer {
+            merge_toml(&mut merged, layer);
+        }
+        let profile = profile.map(str::to_string).or_else(|| {
+            merged
+                .get("profile")
+                .and_then(|profile| profile.as_str())
+                .map(str::to_string)
+        });
+
+        let layers = match &profile {
+            Some(name) => {
+                let Some(toml::Value::Table(mut layer)) =
+                    merged.get("profiles").and_then(|profiles| profiles.get(name)).cloned()
+                else {
+                    let mut available: Vec<&String> = merged
+                        .get("profiles")
+                        .and_then(|profiles| profiles.as_table())
+                        .map(|profiles| profiles.keys().collect())
+                        .unwrap_or_default();
+                    available.sort();
+                    return Err(anyhow!(
+                        "Unknown profile '{}'. Available profiles: {}",
+                        name,
+                        if available.is_empty() {
+                            "none".to_string()
+                        } else {
+                            available.into_iter().cloned().collect::<Vec<_>>().join(", ")
+                        }
+                    ));
+                };
+                layer.remove("profile");
+                layer.remove("profiles");
+                layer.remove("policy");
+                let mut layer = toml::Value::Table(layer);
+                if let Some(repo_layer) = &repo_layer {
+                    merge_toml(&mut layer, repo_layer);
+                }
+                Some(layer)
+            }
+            None => repo_layer,
+        };
+
+        let policy: PolicyConfig = match layers.as_ref().and_then(|layer| layer.get("policy")) {
+            Some(policy) => policy
+                .clone()
+                .try_into()
+                .map_err(|e| anyhow!("Invalid policy in {}: {}", SHARED_CONFIG_FILE, e))?,
+            None => PolicyConfig::default(),
+        };
+        let layers = match (layers, policy.layer()) {
+            (Some(mut layer), Some(forced)) => {
+                merge_toml(&mut layer, &forced);
+                Some(layer)
+            }
+            (layers, forced) => layers.or(forced),
+        };
+
+        let mut value = global;
+        if let Some(layer) = &layers {
+            merge_toml(&mut value, layer);
+        }
+        complete_provider_tables(&mut value);
+        let mut config: Config = value.try_into()?;
+        config.active_profile = profile;
+        config.layers = layers;
+        Ok(config)
+    }
+
+    /// The settings to write to the global file, given what it holds now
+    ///
+    /// Settings that come from the profile or the repository are left as they were in the global
+    /// file, unless they were changed, and API keys kept in the keyring are left out.
+    pub fn global_value(&self, saved_global: toml::Value) -> Result<toml::Value> {
+        let value = toml::Value::try_from(self.without_keyring_keys())?;
+        Ok(match &self.layers {
+            Some(layer) => without_layer(value, layer, Some(&saved_global))
+                .unwrap_or_else(|| toml::Value::Table(toml::Table::new())),
+            None => value,
+        })
+    }
+
+    /// A copy of the configuration without the API keys that belong in the keyring
+    pub fn without_keyring_keys(&self) -> Config {
+        let mut config = self.clone();
+        for provider_config in config.providers.values_mut() {
+            if provider_config.keyring {
+                provider_config.api_key.clear();
+            }
+        }
+        config
+    }
+
+    /// Get the path to the configuration file: the one given with `--config`, or
+    /// `git-iris/config.toml` in the platform's configuration directory, such as
+    /// `$XDG_CONFIG_HOME` on Linux
+    pub fn get_config_path() -> Result<PathBuf> {
+        if let Some(path) = CONFIG_PATH.get() {
+            if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+                fs::create_dir_all(parent)?;
+            }
+            return Ok(path.clone());
+        }
+
         let mut path =
             config_dir().ok_or_else(|| anyhow!("Unable to determine config directory"))?;
         path.push("git-iris");
         std::fs::create_dir_all(&path)?;
         path.push("config.toml");
+
+        if let Some(legacy) = dirs::home_dir().map(|home| home.join(LEGACY_CONFIG_FILE)) {
+            match migrate_legacy_config(&legacy, &path) {
+                Ok(true) => eprintln!(
+                    "{}",
+                    format!(
+                        "Moved the configuration from {} to {}. The old file is no longer read and can be removed.",
+                        legacy.display(),
+                        path.display()
+                    )
+                    .cyan()
+                ),
+                Ok(false) => {}
+                // A broken legacy file shouldn't stop Git-Iris from starting
+                Err(e) => eprintln!("{}", format!("Not migrating the configuration: {}", e).yellow()),
+            }
+        }
         Ok(path)
     }
 
@@ -111,7 +675,7 @@ impl Config {
     }
 
     pub fn get_effective_instructions(&self) -> String {
-        let preset_library = get_instruction_preset_library();
+        let preset_library = self.preset_library();
         let preset_instructions = self
             .temp_preset
             .as_ref()
@@ -125,9 +689,81 @@ impl Config {
             .as_ref()
             .unwrap_or(&self.instructions);
 
-        format!("{}\n\n{}", preset_instructions, custom_instructions)
-            .trim()
-            .to_string()
+        let language_instructions = self.language().instructions();
+        let glossary_instructions = glossary::glossary_instructions(&self.glossary);
+
+        [
+            preset_instructions.trim(),
+            custom_instructions.trim(),
+            language_instructions.as_str(),
+            glossary_instructions.as_str(),
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+    }
+
+    /// The language generated text is written in, or English if the setting is invalid
+    pub fn language(&self) -> Language {
+        Language::from_str(&self.language).unwrap_or_default()
+    }
+
+    /// Get the built-in instruction presets together with the user-defined ones and the
+    /// repository's, which replace user-defined presets of the same name
+    pub fn preset_library(&self) -> InstructionPresetLibrary {
+        let mut library = get_instruction_preset_library();
+        for (key, preset) in self.custom_presets.iter().chain(&self.repo_presets) {
+            library.add_preset(key.clone(), preset.clone());
+        }
+        library
+    }
+
+    /// Read the presets of the repository containing `path`, from its preset directory
+    pub fn load_repo_presets(&mut self, path: &Path) {
+        let Some(workdir) = Repository::discover(path)
+            .ok()
+            .and_then(|repo| repo.workdir().map(Path::to_path_buf))
+        else {
+            return;
+        };
+        let dir = workdir.join(REPO_PRESETS_DIR);
+        self.repo_presets = load_repo_presets(&dir);
+        self.repo_presets_dir = Some(dir);
+    }
+
+    /// Add or replace a user-defined instruction preset
+    pub fn set_custom_preset(&mut self, key: &str, preset: InstructionPreset) -> Result<()> {
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(anyhow!(
+                "Invalid preset name '{}'. Use letters, digits, '-', and '_'",
+                key
+            ));
+        }
+        if is_builtin_preset(key) {
+            return Err(anyhow!("'{}' is a built-in preset and can't be changed", key));
+        }
+        self.custom_presets.insert(key.to_string(), preset);
+        Ok(())
+    }
+
+    /// Remove a user-defined instruction preset, resetting the default preset if it was selected
+    pub fn remove_custom_preset(&mut self, key: &str) -> Result<()> {
+        if self.custom_presets.remove(key).is_none() {
+            return Err(if is_builtin_preset(key) {
+                anyhow!("'{}' is a built-in preset and can't be removed", key)
+            } else {
+                anyhow!("No custom preset named '{}'", key)
+            });
+        }
+        if self.instruction_preset == key {
+            self.instruction_preset = default_instruction_preset();
+        }
+        Ok(())
     }
 
     /// Update the configuration with new values
@@ -178,6 +814,69 @@ impl Config {
         log_debug!("Configuration updated: {:?}", self);
     }
 
+    /// Get a setting by its dotted key, such as `providers.openai.model`
+    pub fn get_value(&self, key: &str) -> Result<toml::Value> {
+        let value = toml::Value::try_from(self)?;
+        key.split('.')
+            .try_fold(&value, |current, part| current.get(part))
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown configuration key: {}", key))
+    }
+
+    /// Remove a setting by its dotted key, falling back to its default value
+    pub fn unset_value(&mut self, key: &str) -> Result<()> {
+        if key == format!("providers.{}", self.default_provider) {
+            return Err(anyhow!(
+                "Cannot unset the configuration of the default provider '{}'",
+                self.default_provider
+            ));
+        }
+
+        let mut value = toml::Value::try_from(&*self)?;
+        let removed = parent_table(&mut value, key).and_then(|(table, name)| table.remove(name));
+        if removed.is_none() {
+            return Err(anyhow!("Unknown configuration key: {}", key));
+        }
+
+        let mut config: Config = match value.clone().try_into() {
+            Ok(config) => config,
+            Err(_) => {
+                // Required settings can't be removed, so reset them to their default instead
+                let default = Config::default()
+                    .get_value(key)
+                    .map_err(|_| anyhow!("Cannot unset required configuration key: {}", key))?;
+                if let Some((table, name)) = parent_table(&mut value, key) {
+                    table.insert(name.to_string(), default);
+                }
+                value.try_into()?
+            }
+        };
+
+        if !config.providers.contains_key(&config.default_provider) {
+            config.providers.insert(
+                config.default_provider.clone(),
+                ProviderConfig::default_for(&config.default_provider),
+            );
+        }
+        config.temp_instructions = self.temp_instructions.take();
+        config.temp_preset = self.temp_preset.take();
+        config.active_profile = self.active_profile.take();
+        config.repo_presets = std::mem::take(&mut self.repo_presets);
+        config.repo_presets_dir = self.repo_presets_dir.take();
+        config.layers = self.layers.take();
+        *self = config;
+
+        log_debug!("Configuration key unset: {}", key);
+        Ok(())
+    }
+
+    /// Render the configuration as TOML with API keys and other secrets masked
+    pub fn to_masked_toml(&self) -> Result<String> {
+        let mut value = toml::Value::try_from(self)?;
+        mask_secrets(&mut value);
+        Ok(toml::to_string_pretty(&value)?)
+    }
+
     /// Get the configuration for a specific provider
     pub fn get_provider_config(&self, provider: &str) -> Option<&ProviderConfig> {
         self.providers.get(provider).or_else(|| {
@@ -194,6 +893,296 @@ impl Config {
     }
 }
 
+/// Copy a configuration file from its legacy location, unless there already is one at `path`
+///
+/// Returns whether the configuration was migrated. The legacy file is left in place.
+pub fn migrate_legacy_config(legacy: &Path, path: &Path) -> Result<bool> {
+    if path.exists() || !legacy.is_file() {
+        return Ok(false);
+    }
+    let content = fs::read_to_string(legacy)?;
+    toml::from_str::<toml::Value>(&content)
+        .map_err(|e| anyhow!("Invalid configuration in {}: {}", legacy.display(), e))?;
+    fs::write(path, content)?;
+    log_debug!("Migrated the configuration from {}", legacy.display());
+    Ok(true)
+}
+
+/// Print a short warning for each problem, pointing to `config validate` for the suggestions
+fn warn_about_problems(problems: &[ConfigProblem]) {
+    if problems.is_empty() {
+        return;
+    }
+    for problem in problems {
+        eprintln!("{}", format!("Configuration problem: {}", problem).yellow());
+    }
+    eprintln!(
+        "{}",
+        "Run 'git-iris config validate' for suggestions on fixing them.".yellow()
+    );
+}
+
+/// The saved global settings, or the defaults if there is no configuration file yet
+pub fn read_global_value() -> Result<toml::Value> {
+    let config_path = Config::get_config_path()?;
+    if !config_path.exists() {
+        return Ok(toml::Value::try_from(Config::default())?);
+    }
+    let mut value = toml::from_str(&fs::read_to_string(&config_path)?)?;
+    // The file itself is only rewritten by 'config migrate', or when a setting is changed
+    let changes = config_migration::migrate(&mut value)?;
+    if !changes.is_empty() && !MIGRATION_NOTICE.swap(true, Ordering::Relaxed) {
+        eprintln!(
+            "{}",
+            format!(
+                "{} uses an older layout. Run 'git-iris config migrate' to update it.",
+                config_path.display()
+            )
+            .cyan()
+        );
+    }
+    Ok(value)
+}
+
+/// Don't point out an older layout of the configuration file, for commands that handle it
+pub fn skip_migration_notice() {
+    MIGRATION_NOTICE.store(true, Ordering::Relaxed);
+}
+
+/// Read the shared and the local configuration files of the repository containing `path`,
+/// with the local settings layered over the shared ones
+///
+/// API keys in the shared file are ignored, since it is meant to be committed.
+pub fn read_repo_layer(path: &Path) -> Result<Option<toml::Value>> {
+    let Ok(repo) = Repository::discover(path) else {
+        return Ok(None);
+    };
+    let mut layer: Option<toml::Value> = None;
+    let files = [
+        repo.workdir().map(|workdir| workdir.join(SHARED_CONFIG_FILE)),
+        Some(repo.path().join(LOCAL_CONFIG_FILE)),
+    ];
+    for (index, file) in files.into_iter().flatten().enumerate() {
+        if !file.exists() {
+            continue;
+        }
+        let content = fs::read_to_string(&file)?;
+        let mut value: toml::Value = toml::from_str(&content)
+            .map_err(|e| anyhow!("Invalid configuration in {}: {}", file.display(), e))?;
+        for change in config_migration::migrate(&mut value)? {
+            log_debug!("Upgraded {} in memory: {}", file.display(), change);
+        }
+        if index == 0 {
+            remove_api_keys(&mut value);
+        } else if let Some(table) = value.as_table_mut() {
+            // Only the shared settings can set a policy, so that a clone can't relax it
+            if table.remove("policy").is_some() {
+                log_debug!("Ignoring a policy in {}", file.display());
+            }
+        }
+        log_debug!("Repository configuration loaded from {}", file.display());
+        match &mut layer {
+            Some(layer) => merge_toml(layer, &value),
+            None => layer = Some(value),
+        }
+    }
+    Ok(layer)
+}
+
+/// Settings given through `GIT_IRIS_*` environment variables, as a layer over the others
+pub fn env_layer(vars: impl IntoIterator<Item = (String, String)>) -> Result<Option<toml::Value>> {
+    let providers: Vec<String> =
+        get_available_providers().iter().map(ToString::to_string).collect();
+    let mut layer = toml::Table::new();
+    for (name, value) in vars {
+        if let Some((_, key)) = ENV_SETTINGS.iter().find(|(var, _)| *var == name) {
+            let value = if *key == "use_gitmoji" {
+                toml::Value::Boolean(parse_bool(&value).ok_or_else(|| {
+                    anyhow!("{} must be true or false, not '{}'", name, value)
+                })?)
+            } else {
+                toml::Value::String(value)
+            };
+            layer.insert((*key).to_string(), value);
+            continue;
+        }
+
+        let Some(rest) = name.strip_prefix("GIT_IRIS_") else {
+            continue;
+        };
+        for provider in &providers {
+            let field = match rest.strip_prefix(&format!("{}_", provider.to_uppercase())) {
+                Some("API_KEY") => "api_key",
+                Some("MODEL") => "model",
+                _ => continue,
+            };
+            let provider_table = layer
+                .entry("providers")
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .and_then(|providers| {
+                    providers
+                        .entry(provider.as_str())
+                        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                        .as_table_mut()
+                });
+            if let Some(provider_table) = provider_table {
+                provider_table.insert(field.to_string(), toml::Value::String(value.clone()));
+            }
+        }
+    }
+    Ok(if layer.is_empty() {
+        None
+    } else {
+        Some(toml::Value::Table(layer))
+    })
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" | "yes" => Some(true),
+        "false" | "0" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Fill in the required fields of providers that a layer added without them, such as a
+/// provider whose API key only comes from the environment
+fn complete_provider_tables(value: &mut toml::Value) {
+    let providers = value
+        .get_mut("providers")
+        .and_then(|providers| providers.as_table_mut());
+    for (name, provider) in providers.into_iter().flat_map(|providers| providers.iter_mut()) {
+        if let Some(provider) = provider.as_table_mut() {
+            if !provider.contains_key("model") {
+                let model = ProviderConfig::default_for(name).model;
+                provider.insert("model".to_string(), toml::Value::String(model));
+            }
+            provider
+                .entry("api_key")
+                .or_insert_with(|| toml::Value::String(String::new()));
+        }
+    }
+}
+
+/// Layer one set of settings over another, merging tables and replacing everything else
+pub fn merge_toml(base: &mut toml::Value, layer: &toml::Value) {
+    match (base, layer) {
+        (toml::Value::Table(base), toml::Value::Table(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, layer) => *base = layer.clone(),
+    }
+}
+
+/// Take a layer back out of merged settings: values the layer still dictates revert to
+/// `original`, and are dropped if they weren't set there
+fn without_layer(
+    value: toml::Value,
+    layer: &toml::Value,
+    original: Option<&toml::Value>,
+) -> Option<toml::Value> {
+    match (value, layer) {
+        (toml::Value::Table(table), toml::Value::Table(layer)) => {
+            let mut result = toml::Table::new();
+            for (key, value) in table {
+                let original = original.and_then(|original| original.get(&key));
+                let kept = match layer.get(&key) {
+                    Some(layered) => without_layer(value, layered, original),
+                    None => Some(value),
+                };
+                if let Some(kept) = kept {
+                    result.insert(key, kept);
+                }
+            }
+            Some(toml::Value::Table(result))
+        }
+        (value, layer) if value == *layer => original.cloned(),
+        (value, _) => Some(value),
+    }
+}
+
+fn remove_api_keys(value: &mut toml::Value) {
+    let providers = value
+        .get_mut("providers")
+        .and_then(|providers| providers.as_table_mut());
+    for (_, provider) in providers.into_iter().flat_map(|providers| providers.iter_mut()) {
+        if let Some(provider) = provider.as_table_mut() {
+            if provider.remove("api_key").is_some() {
+                log_debug!("Ignoring an API key in {}", SHARED_CONFIG_FILE);
+            }
+        }
+    }
+}
+
+/// Find the table holding the last part of a dotted key
+fn parent_table<'a, 'k>(
+    value: &'a mut toml::Value,
+    key: &'k str,
+) -> Option<(&'a mut toml::Table, &'k str)> {
+    let (parents, name) = match key.rsplit_once('.') {
+        Some((parents, name)) => (Some(parents), name),
+        None => (None, key),
+    };
+
+    let mut current = value;
+    if let Some(parents) = parents {
+        for part in parents.split('.') {
+            current = current.get_mut(part)?;
+        }
+    }
+    current.as_table_mut().map(|table| (table, name))
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    key.ends_with("key")
+        || key.ends_with("token")
+        || key.contains("secret")
+        || key.contains("password")
+        || key == "authorization"
+}
+
+/// Remove API keys and other secrets, at any depth
+pub fn remove_secrets(value: &mut toml::Value) {
+    if let Some(table) = value.as_table_mut() {
+        table.retain(|key, entry| !(is_secret_key(key) && entry.is_str()));
+        for (_, entry) in table.iter_mut() {
+            remove_secrets(entry);
+        }
+    }
+}
+
+fn mask_secrets(value: &mut toml::Value) {
+    if let Some(table) = value.as_table_mut() {
+        for (key, entry) in table.iter_mut() {
+            match entry {
+                toml::Value::String(secret) if is_secret_key(key) && !secret.is_empty() => {
+                    *secret = mask_secret(secret);
+                }
+                _ => mask_secrets(entry),
+            }
+        }
+    }
+}
+
+/// Hide a secret, keeping the last few characters of long ones so keys can be told apart
+fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() < 12 {
+        return "********".to_string();
+    }
+    let suffix: String = chars[chars.len() - 4..].iter().collect();
+    format!("********{}", suffix)
+}
+
 impl Default for Config {
     fn default() -> Self {
         let mut providers = HashMap::new();
@@ -205,13 +1194,38 @@ impl Default for Config {
         }
 
         Config {
+            version: CONFIG_VERSION,
             default_provider: get_available_providers().first().unwrap().to_string(),
             providers,
             use_gitmoji: true,
+            gitmoji_in_body: false,
+            gitmoji_file: None,
+            gitmoji_style: GitmojiStyle::default(),
             instructions: String::new(),
             instruction_preset: default_instruction_preset(),
+            language: default_language(),
+            custom_presets: HashMap::new(),
+            risk_labels: default_risk_labels(),
+            branch_name_pattern: default_branch_name_pattern(),
+            lint: LintConfig::default(),
+            format: MessageFormatConfig::default(),
+            conventional: ConventionalConfig::default(),
+            redaction: RedactionConfig::default(),
+            recent_commits: RecentCommitsConfig::default(),
+            changelog: ReleaseTextConfig::default(),
+            release_notes: ReleaseTextConfig::default(),
+            glossary: HashMap::new(),
+            policy: PolicyConfig::default(),
+            prompt_exclude: Vec::new(),
+            prompt_include: Vec::new(),
+            profile: None,
+            profiles: HashMap::new(),
             temp_instructions: None,
             temp_preset: None,
+            active_profile: None,
+            repo_presets: HashMap::new(),
+            repo_presets_dir: None,
+            layers: None,
         }
     }
 }
@@ -227,6 +1241,11 @@ impl ProviderConfig {
             model: metadata.default_model.to_string(),
             additional_params: HashMap::new(),
             token_limit: Some(metadata.default_token_limit),
+            keyring: false,
+            timeout: None,
+            max_retries: None,
+            proxy: None,
+            headers: HashMap::new(),
         }
     }
 
@@ -245,6 +1264,17 @@ impl ProviderConfig {
             api_key: self.api_key.clone(),
             model: self.model.clone(),
             additional_params: self.additional_params.clone(),
+            network: self.network_settings(),
+        }
+    }
+
+    /// How requests to this provider are sent
+    pub fn network_settings(&self) -> NetworkSettings {
+        NetworkSettings {
+            timeout: self.timeout.map(Duration::from_secs),
+            max_retries: self.max_retries.unwrap_or(0),
+            proxy: self.proxy.clone(),
+            headers: self.headers.clone(),
         }
     }
 }
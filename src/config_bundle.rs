@@ -0,0 +1,224 @@
+use crate::config::{merge_toml, remove_secrets, Config};
+use crate::config_migration;
+use crate::keyring;
+use crate::log_debug;
+use crate::templates::{self, TEMPLATE_NAMES};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Version of the bundle format, raised when it changes incompatibly
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// File name an imported gitmoji mapping is written to, next to the configuration file
+pub const IMPORTED_GITMOJI_FILE: &str = "gitmoji.toml";
+
+/// Format of an exported bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleFormat {
+    Toml,
+    Json,
+}
+
+impl FromStr for BundleFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "toml" => Ok(BundleFormat::Toml),
+            "json" => Ok(BundleFormat::Json),
+            _ => Err(anyhow!("Invalid bundle format: {}. Use toml or json", s)),
+        }
+    }
+}
+
+/// The global settings with the files they depend on, to move them to another machine or share
+/// them with a team
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConfigBundle {
+    pub version: u32,
+    /// The settings from the global configuration file
+    pub config: toml::Value,
+    /// Prompt template overrides, keyed by prompt name
+    #[serde(default)]
+    pub templates: BTreeMap<String, String>,
+    /// Contents of the gitmoji mapping file, if one is configured
+    pub gitmoji_mapping: Option<String>,
+}
+
+impl ConfigBundle {
+    /// Bundle the global settings, with API keys from the keyring unless secrets are left out
+    pub fn export(include_secrets: bool) -> Result<Self> {
+        let mut config = crate::config::read_global_value()?;
+        if include_secrets {
+            add_keyring_keys(&mut config);
+        }
+        let loaded: Config = config.clone().try_into()?;
+        let gitmoji_file = loaded.gitmoji_file_path()?;
+        Self::from_parts(
+            config,
+            &templates::templates_dir()?,
+            gitmoji_file.as_deref(),
+            include_secrets,
+        )
+    }
+
+    /// Bundle the given settings with the templates in `templates_dir` and the gitmoji file
+    pub fn from_parts(
+        mut config: toml::Value,
+        templates_dir: &Path,
+        gitmoji_file: Option<&Path>,
+        include_secrets: bool,
+    ) -> Result<Self> {
+        if !include_secrets {
+            remove_secrets(&mut config);
+        }
+        let mut templates = BTreeMap::new();
+        for name in TEMPLATE_NAMES {
+            let path = templates_dir.join(format!("{}.tmpl", name));
+            if path.is_file() {
+                templates.insert(name.to_string(), fs::read_to_string(&path)?);
+            }
+        }
+        let gitmoji_mapping = match gitmoji_file {
+            Some(path) => Some(fs::read_to_string(path).map_err(|e| {
+                anyhow!("Unable to read the gitmoji file {}: {}", path.display(), e)
+            })?),
+            None => None,
+        };
+        Ok(Self {
+            version: BUNDLE_VERSION,
+            config,
+            templates,
+            gitmoji_mapping,
+        })
+    }
+
+    /// Write the bundle out in the given format
+    pub fn render(&self, format: BundleFormat) -> Result<String> {
+        Ok(match format {
+            BundleFormat::Toml => toml::to_string_pretty(self)?,
+            BundleFormat::Json => serde_json::to_string_pretty(self)? + "\n",
+        })
+    }
+
+    /// Read a bundle written by `render` in either format
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut bundle: Self = if content.trim_start().starts_with('{') {
+            serde_json::from_str(content).map_err(|e| anyhow!("Invalid JSON bundle: {}", e))?
+        } else {
+            toml::from_str(content).map_err(|e| anyhow!("Invalid TOML bundle: {}", e))?
+        };
+        if bundle.version > BUNDLE_VERSION {
+            return Err(anyhow!(
+                "The bundle was made by a newer version of Git-Iris (format {}); upgrade to import it",
+                bundle.version
+            ));
+        }
+        if !bundle.config.is_table() {
+            return Err(anyhow!("The bundle's 'config' must be a table of settings"));
+        }
+        // Bundles exported by older versions hold settings in their layout at the time
+        config_migration::migrate(&mut bundle.config)?;
+        if let Some(name) = bundle
+            .templates
+            .keys()
+            .find(|name| !TEMPLATE_NAMES.contains(&name.as_str()))
+        {
+            return Err(anyhow!("The bundle has a template for an unknown prompt: {}", name));
+        }
+        Ok(bundle)
+    }
+
+    /// The settings after importing the bundle over the current ones
+    ///
+    /// Settings are merged into the current ones, or replace them all with `replace`. Either way,
+    /// API keys already set here are kept for providers the bundle has no key for, and an
+    /// included gitmoji mapping is pointed to at its imported location.
+    pub fn apply(&self, current: &toml::Value, replace: bool) -> toml::Value {
+        let mut value = if replace {
+            let mut value = self.config.clone();
+            keep_api_keys(&mut value, current);
+            value
+        } else {
+            let mut value = current.clone();
+            merge_toml(&mut value, &self.config);
+            value
+        };
+        if self.gitmoji_mapping.is_some() {
+            if let Some(table) = value.as_table_mut() {
+                table.insert(
+                    "gitmoji_file".to_string(),
+                    toml::Value::String(IMPORTED_GITMOJI_FILE.to_string()),
+                );
+            }
+        }
+        value
+    }
+
+    /// Write the bundled templates and gitmoji mapping next to the configuration file
+    pub fn write_files(&self, config_dir: &Path) -> Result<()> {
+        if !self.templates.is_empty() {
+            let dir = config_dir.join(templates::TEMPLATES_DIR);
+            fs::create_dir_all(&dir)?;
+            for (name, template) in &self.templates {
+                fs::write(dir.join(format!("{}.tmpl", name)), template)?;
+            }
+        }
+        if let Some(mapping) = &self.gitmoji_mapping {
+            fs::write(config_dir.join(IMPORTED_GITMOJI_FILE), mapping)?;
+        }
+        Ok(())
+    }
+}
+
+/// Fill in the API keys kept in the keyring, so they travel with the bundle
+fn add_keyring_keys(config: &mut toml::Value) {
+    let providers = config
+        .get_mut("providers")
+        .and_then(|providers| providers.as_table_mut());
+    for (name, provider) in providers.into_iter().flat_map(|providers| providers.iter_mut()) {
+        let Some(provider) = provider.as_table_mut() else {
+            continue;
+        };
+        let in_keyring = provider.get("keyring").and_then(toml::Value::as_bool) == Some(true);
+        if !in_keyring {
+            continue;
+        }
+        match keyring::load_api_key(name) {
+            Ok(Some(api_key)) => {
+                provider.insert("api_key".to_string(), toml::Value::String(api_key));
+            }
+            Ok(None) => {}
+            Err(e) => log_debug!("Failed to read the {} API key: {}", name, e),
+        }
+    }
+}
+
+/// Copy API keys from `current` for providers that have none in `value`
+fn keep_api_keys(value: &mut toml::Value, current: &toml::Value) {
+    let Some(current) = current.get("providers").and_then(|p| p.as_table()) else {
+        return;
+    };
+    let Some(providers) = value.get_mut("providers").and_then(|p| p.as_table_mut()) else {
+        return;
+    };
+    for (name, provider) in providers.iter_mut() {
+        let (Some(provider), Some(api_key)) = (
+            provider.as_table_mut(),
+            current.get(name).and_then(|current| current.get("api_key")),
+        ) else {
+            continue;
+        };
+        let has_key = provider
+            .get("api_key")
+            .and_then(toml::Value::as_str)
+            .is_some_and(|key| !key.is_empty());
+        if !has_key {
+            provider.insert("api_key".to_string(), api_key.clone());
+        }
+    }
+}
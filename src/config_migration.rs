@@ -0,0 +1,111 @@
+use anyhow::{anyhow, Result};
+
+/// Layout version of the configuration written by this version of Git-Iris
+pub const CONFIG_VERSION: u32 = 1;
+
+/// An upgrade of the configuration layout from the version before `to`
+struct Migration {
+    to: u32,
+    /// What changes, as shown before the upgraded file is written
+    description: &'static str,
+    apply: fn(&mut toml::Table),
+}
+
+/// Every upgrade, oldest first; add one here whenever a setting is renamed or moved
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        to: 1,
+        description: "Rename 'custom_instructions' to 'instructions'",
+        apply: rename_custom_instructions,
+    },
+    Migration {
+        to: 1,
+        description: "Move top-level 'api_key', 'model', 'token_limit', and 'additional_params' \
+            into the default provider's table",
+        apply: move_provider_settings,
+    },
+];
+
+/// The layout version of a configuration; files from before versioning have none and are
+/// version 0
+pub fn version_of(value: &toml::Value) -> u32 {
+    value
+        .get("version")
+        .and_then(toml::Value::as_integer)
+        .and_then(|version| u32::try_from(version).ok())
+        .unwrap_or(0)
+}
+
+/// Upgrade a configuration to the current layout in place, returning a description of each
+/// change that applied
+///
+/// A configuration from a newer version of Git-Iris is left as it is; settings this version
+/// doesn't know are ignored.
+pub fn migrate(value: &mut toml::Value) -> Result<Vec<&'static str>> {
+    let version = version_of(value);
+    if version >= CONFIG_VERSION {
+        return Ok(Vec::new());
+    }
+    let table = value
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("The configuration must be a table of settings"))?;
+
+    let mut changes = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|migration| migration.to > version) {
+        let before = table.clone();
+        (migration.apply)(table);
+        if *table != before {
+            changes.push(migration.description);
+        }
+    }
+    table.insert(
+        "version".to_string(),
+        toml::Value::Integer(i64::from(CONFIG_VERSION)),
+    );
+    Ok(changes)
+}
+
+fn rename_custom_instructions(table: &mut toml::Table) {
+    if let Some(instructions) = table.remove("custom_instructions") {
+        table.entry("instructions").or_insert(instructions);
+    }
+}
+
+fn move_provider_settings(table: &mut toml::Table) {
+    let Some(provider) = table
+        .get("default_provider")
+        .and_then(toml::Value::as_str)
+        .map(str::to_string)
+    else {
+        return;
+    };
+    let moved: Vec<(String, toml::Value)> =
+        ["api_key", "model", "token_limit", "additional_params"]
+            .iter()
+            .filter_map(|key| table.remove(*key).map(|value| (key.to_string(), value)))
+            .collect();
+    if moved.is_empty() {
+        return;
+    }
+
+    let providers = table
+        .entry("providers")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    let Some(provider_table) = providers.as_table_mut().and_then(|providers| {
+        providers
+            .entry(provider)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+    }) else {
+        return;
+    };
+    // Settings already in the provider's table are newer than the top-level ones, unless empty
+    for (key, value) in moved {
+        let keep = provider_table
+            .get(&key)
+            .is_some_and(|existing| existing.as_str() != Some(""));
+        if !keep {
+            provider_table.insert(key, value);
+        }
+    }
+}
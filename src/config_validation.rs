@@ -0,0 +1,480 @@
+use crate::changelog::{DetailLevel, OutputFormat};
+use crate::config::{Config, ReleaseTextConfig, RECENT_COMMIT_COMMANDS};
+use crate::config_migration::CONFIG_VERSION;
+use crate::gitmoji;
+use crate::instruction_presets::{is_builtin_preset, read_repo_preset, REPO_PRESETS_DIR};
+use crate::language::Language;
+use crate::llm_providers::{get_available_providers, get_provider_metadata, LLMProviderType};
+use crate::redaction::compile_pattern;
+use crate::risk::RiskLabel;
+use crate::templates::{self, TEMPLATE_NAMES};
+use serde_json::json;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Provider parameters that must be numbers
+const NUMERIC_PARAMS: &[&str] = &[
+    "temperature",
+    "top_p",
+    "top_k",
+    "max_tokens",
+    "frequency_penalty",
+    "presence_penalty",
+    "seed",
+];
+
+/// A setting that would fail or be ignored later, with what to do about it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// Dotted key of the setting, as used by `config get` and `config unset`
+    pub key: String,
+    pub message: String,
+    pub suggestion: String,
+}
+
+impl ConfigProblem {
+    fn new(
+        key: impl Into<String>,
+        message: impl Into<String>,
+        suggestion: impl Into<String>,
+    ) -> Self {
+        Self {
+            key: key.into(),
+            message: message.into(),
+            suggestion: suggestion.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// Check every setting, returning all the problems found rather than stopping at the first
+///
+/// Template overrides are checked separately by `check_templates`, since they live next to
+/// the configuration file rather than in it.
+pub fn validate_config(config: &Config) -> Vec<ConfigProblem> {
+    let mut problems = Vec::new();
+    let provider_names: Vec<String> =
+        get_available_providers().iter().map(ToString::to_string).collect();
+
+    if config.version > CONFIG_VERSION {
+        problems.push(ConfigProblem::new(
+            "version",
+            format!(
+                "The configuration was written by a newer version of Git-Iris (layout {}, \
+                this version reads {}), so some settings may be ignored",
+                config.version, CONFIG_VERSION
+            ),
+            "Upgrade Git-Iris",
+        ));
+    }
+
+    if LLMProviderType::from_str(&config.default_provider).is_err() {
+        problems.push(ConfigProblem::new(
+            "default_provider",
+            format!("Unknown provider '{}'", config.default_provider),
+            choose_suggestion(
+                &config.default_provider,
+                &provider_names,
+                "git-iris config --provider",
+            ),
+        ));
+    } else if !config.providers.contains_key(&config.default_provider) {
+        problems.push(ConfigProblem::new(
+            "default_provider",
+            format!("'{}' has no settings in 'providers'", config.default_provider),
+            format!(
+                "Run 'git-iris config --provider {}' to add them",
+                config.default_provider
+            ),
+        ));
+    }
+
+    let mut names: Vec<&String> = config.providers.keys().collect();
+    names.sort();
+    for name in names {
+        let provider_config = &config.providers[name];
+        let key = format!("providers.{}", name);
+        let Ok(provider_type) = LLMProviderType::from_str(name) else {
+            problems.push(ConfigProblem::new(
+                &key,
+                format!("Unknown provider '{}'", name),
+                match closest(name, &provider_names) {
+                    Some(known) => format!("Did you mean '{}'? Rename the table", known),
+                    None => format!(
+                        "Remove it with 'git-iris config unset {}'; known providers are {}",
+                        key,
+                        provider_names.join(", ")
+                    ),
+                },
+            ));
+            continue;
+        };
+        if provider_config.model.trim().is_empty() {
+            problems.push(ConfigProblem::new(
+                format!("{}.model", key),
+                "No model set",
+                format!(
+                    "Set one with 'git-iris config --provider {} --model {}'",
+                    name,
+                    get_provider_metadata(&provider_type).default_model
+                ),
+            ));
+        }
+        if provider_config.token_limit == Some(0) {
+            problems.push(ConfigProblem::new(
+                format!("{}.token_limit", key),
+                "The token limit is 0, which leaves no room for the changes",
+                format!(
+                    "Remove it with 'git-iris config unset {}.token_limit' to use the default",
+                    key
+                ),
+            ));
+        }
+        let network = provider_config.network_settings();
+        if let Err(e) = network.header_map() {
+            problems.push(ConfigProblem::new(
+                format!("{}.headers", key),
+                e.to_string(),
+                format!(
+                    "Set it again with 'git-iris config --provider {} --header NAME=VALUE'",
+                    name
+                ),
+            ));
+        } else if let Err(e) = network.client() {
+            problems.push(ConfigProblem::new(
+                format!("{}.proxy", key),
+                e.to_string(),
+                format!(
+                    "Set a URL such as http://proxy.example.com:8080 with \
+                    'git-iris config --provider {} --proxy URL'",
+                    name
+                ),
+            ));
+        }
+        let mut params: Vec<(&String, &String)> =
+            provider_config.additional_params.iter().collect();
+        params.sort();
+        for (param, value) in params {
+            problems.extend(check_param(&key, param, value));
+        }
+    }
+
+    let library = config.preset_library();
+    if library.get_preset(&config.instruction_preset).is_none() {
+        let presets: Vec<String> =
+            library.list_presets().into_iter().map(|(key, _)| key.clone()).collect();
+        problems.push(ConfigProblem::new(
+            "instruction_preset",
+            format!("Unknown preset '{}'", config.instruction_preset),
+            match closest(&config.instruction_preset, &presets) {
+                Some(known) => format!(
+                    "Did you mean '{}'? Run 'git-iris config --preset {}'",
+                    known, known
+                ),
+                None => "Choose a preset from 'git-iris list-presets' with \
+                    'git-iris config --preset <name>'"
+                    .to_string(),
+            },
+        ));
+    }
+
+    if Language::from_str(&config.language).is_err() {
+        let codes: Vec<String> =
+            Language::all().iter().map(|language| language.code.to_string()).collect();
+        problems.push(ConfigProblem::new(
+            "language",
+            format!("Unsupported language '{}'; English is used instead", config.language),
+            choose_suggestion(&config.language, &codes, "git-iris config --language"),
+        ));
+    }
+
+    if let Some(dir) = &config.repo_presets_dir {
+        problems.extend(check_repo_presets(dir));
+    }
+
+    let risk_labels: Vec<String> =
+        RiskLabel::all().iter().map(|label| label.key().to_string()).collect();
+    for label in &config.risk_labels {
+        if RiskLabel::from_str(label).is_err() {
+            problems.push(ConfigProblem::new(
+                "risk_labels",
+                format!("Unknown risk label '{}'", label),
+                match closest(label, &risk_labels) {
+                    Some(known) => format!("Did you mean '{}'?", known),
+                    None => format!("Known labels are {}", risk_labels.join(", ")),
+                },
+            ));
+        }
+    }
+
+    if let Some(profile) = &config.profile {
+        if !config.profiles.contains_key(profile) {
+            let profiles: Vec<String> = config.profiles.keys().cloned().collect();
+            problems.push(ConfigProblem::new(
+                "profile",
+                format!("There is no profile named '{}'", profile),
+                match closest(profile, &profiles) {
+                    Some(known) => format!("Did you mean '{}'?", known),
+                    None => format!("Add a [profiles.{}] table or unset 'profile'", profile),
+                },
+            ));
+        }
+    }
+
+    let format = &config.format;
+    if format.max_subject_length != 0 && format.subject_length > format.max_subject_length {
+        problems.push(ConfigProblem::new(
+            "format.subject_length",
+            format!(
+                "The model is asked for subjects up to {} columns, but they are shortened to {}",
+                format.subject_length, format.max_subject_length
+            ),
+            "Lower it with 'git-iris config --subject-length' or raise --max-subject-length",
+        ));
+    }
+
+    for (pattern, scope) in &config.conventional.scopes {
+        if pattern.trim().is_empty() {
+            problems.push(ConfigProblem::new(
+                "conventional.scopes",
+                format!("The scope '{}' has an empty pattern", scope),
+                "Map a glob pattern with 'git-iris config --conventional-scope PATTERN=SCOPE'",
+            ));
+        }
+    }
+
+    for pattern in &config.redaction.patterns {
+        if let Err(e) = compile_pattern(pattern) {
+            problems.push(ConfigProblem::new(
+                "redaction.patterns",
+                e.to_string(),
+                "Fix the regular expression, or write a glob as 'glob:<pattern>'; \
+                prompts are not sent until it is fixed",
+            ));
+        }
+    }
+
+    let commands: Vec<String> = RECENT_COMMIT_COMMANDS.iter().map(ToString::to_string).collect();
+    let mut overridden: Vec<&String> = config.recent_commits.commands.keys().collect();
+    overridden.sort();
+    for command in overridden {
+        if !RECENT_COMMIT_COMMANDS.contains(&command.as_str()) {
+            problems.push(ConfigProblem::new(
+                format!("recent_commits.commands.{}", command),
+                format!("'{}' doesn't include recent commits, so this is ignored", command),
+                match closest(command, &commands) {
+                    Some(known) => format!("Did you mean '{}'?", known),
+                    None => format!("Overrides apply to {}", commands.join(", ")),
+                },
+            ));
+        }
+    }
+
+    if config.policy.check_provider(&config.default_provider).is_err() {
+        problems.push(ConfigProblem::new(
+            "default_provider",
+            format!(
+                "The repository's policy forbids sending prompts to '{}'",
+                config.default_provider
+            ),
+            "Choose another provider with 'git-iris config --provider'",
+        ));
+    }
+
+    problems.extend(check_release_text("changelog", &config.changelog));
+    problems.extend(check_release_text("release_notes", &config.release_notes));
+
+    let mut terms: Vec<(&String, &String)> = config.glossary.iter().collect();
+    terms.sort();
+    for (term, meaning) in terms {
+        if term.trim().is_empty() || meaning.trim().is_empty() {
+            problems.push(ConfigProblem::new(
+                "glossary",
+                format!("The entry '{} = {}' is missing a term or its meaning", term, meaning),
+                "It is left out of prompts; set it with 'git-iris config --glossary TERM=MEANING'",
+            ));
+        }
+    }
+
+    match config.gitmoji_file_path() {
+        Ok(Some(path)) => {
+            if let Err(e) = gitmoji::load_mapping(&path) {
+                problems.push(ConfigProblem::new(
+                    "gitmoji_file",
+                    e.to_string(),
+                    "Fix the file, or go back to the built-in gitmojis with \
+                    'git-iris config unset gitmoji_file'",
+                ));
+            }
+        }
+        Ok(None) => {}
+        Err(e) => problems.push(ConfigProblem::new(
+            "gitmoji_file",
+            e.to_string(),
+            "Use an absolute path",
+        )),
+    }
+
+    problems
+}
+
+/// Check the presets in a repository's preset directory: unreadable files, files without
+/// instructions, and files named after a built-in preset
+fn check_repo_presets(dir: &Path) -> Vec<ConfigProblem> {
+    let mut problems = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return problems;
+    };
+    let mut paths: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+    for path in paths {
+        match read_repo_preset(&path) {
+            Ok(Some((key, _))) if is_builtin_preset(&key) => problems.push(ConfigProblem::new(
+                REPO_PRESETS_DIR,
+                format!("{} has the name of a built-in preset, so it is ignored", path.display()),
+                "Rename the file",
+            )),
+            Ok(_) => {}
+            Err(e) => problems.push(ConfigProblem::new(
+                REPO_PRESETS_DIR,
+                e.to_string(),
+                "Fix the file; a .toml preset needs 'instructions', and other presets need text",
+            )),
+        }
+    }
+    problems
+}
+
+/// Check the defaults of the `changelog` or `release-notes` command
+fn check_release_text(section: &str, settings: &ReleaseTextConfig) -> Vec<ConfigProblem> {
+    let mut problems = Vec::new();
+    if DetailLevel::from_str(&settings.detail_level).is_err() {
+        problems.push(ConfigProblem::new(
+            format!("{}.detail_level", section),
+            format!("Unknown detail level '{}'", settings.detail_level),
+            "Use minimal, standard, or detailed",
+        ));
+    }
+    if OutputFormat::from_str(&settings.format).is_err() {
+        problems.push(ConfigProblem::new(
+            format!("{}.format", section),
+            format!("Unknown output format '{}'", settings.format),
+            "Use markdown or json",
+        ));
+    }
+    problems
+}
+
+/// Check the prompt templates in a directory: unreadable or invalid templates, and files that
+/// don't override any prompt because of their name
+pub fn check_templates(dir: &Path) -> Vec<ConfigProblem> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let names: Vec<String> = TEMPLATE_NAMES.iter().map(ToString::to_string).collect();
+    let mut paths: Vec<_> = entries.filter_map(|entry| entry.ok().map(|e| e.path())).collect();
+    paths.sort();
+
+    let mut problems = Vec::new();
+    for path in paths {
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let key = format!("{}/{}", templates::TEMPLATES_DIR, file_name);
+        let Some(stem) = file_name.strip_suffix(".tmpl") else {
+            continue;
+        };
+        if !TEMPLATE_NAMES.contains(&stem) {
+            problems.push(ConfigProblem::new(
+                key,
+                "Doesn't override any prompt",
+                match closest(stem, &names) {
+                    Some(known) => format!("Did you mean '{}.tmpl'?", known),
+                    None => format!("Templates are named {}.tmpl", names.join(".tmpl, ")),
+                },
+            ));
+            continue;
+        }
+        let checked = fs::read_to_string(&path)
+            .map_err(|e| format!("Unable to read it: {}", e))
+            .and_then(|template| {
+                templates::render(&template, &json!({})).map_err(|e| e.to_string())
+            });
+        if let Err(message) = checked {
+            problems.push(ConfigProblem::new(
+                key,
+                message,
+                "Fix the template, or remove it to use the built-in prompt",
+            ));
+        }
+    }
+    problems
+}
+
+/// Every problem with the configuration that was loaded, including its template overrides
+pub fn validate_all(config: &Config) -> Vec<ConfigProblem> {
+    let mut problems = validate_config(config);
+    if let Ok(dir) = templates::templates_dir() {
+        problems.extend(check_templates(&dir));
+    }
+    problems
+}
+
+fn check_param(key: &str, param: &str, value: &str) -> Option<ConfigProblem> {
+    let key = format!("{}.additional_params.{}", key, param);
+    if param.trim().is_empty() || param.contains(char::is_whitespace) {
+        return Some(ConfigProblem::new(
+            key,
+            format!("'{}' is not a valid parameter name", param),
+            "Set parameters as --param name=value",
+        ));
+    }
+    if NUMERIC_PARAMS.contains(&param) && value.trim().parse::<f64>().is_err() {
+        return Some(ConfigProblem::new(
+            key,
+            format!("'{}' should be a number, but is '{}'", param, value),
+            format!("Set a number with --param {}=<number>", param),
+        ));
+    }
+    None
+}
+
+/// Suggest the closest known value, or list them all, with the command that sets it
+fn choose_suggestion(value: &str, known: &[String], command: &str) -> String {
+    match closest(value, known) {
+        Some(known) => format!("Did you mean '{}'? Run '{} {}'", known, command, known),
+        None => format!("Choose one of {} with '{} <name>'", known.join(", "), command),
+    }
+}
+
+/// The known value a misspelled one was most likely meant to be
+fn closest<'a>(value: &str, known: &'a [String]) -> Option<&'a str> {
+    let value = value.to_lowercase();
+    known
+        .iter()
+        .map(|candidate| (edit_distance(&value, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
@@ -0,0 +1,399 @@
+use crate::change_analyzer::{AnalyzedChange, ChangeAnalyzer};
+use crate::config::Config;
+use crate::llm;
+use crate::llm_providers::{get_provider_metadata, LLMProviderType};
+use crate::token_optimizer::TokenOptimizer;
+use anyhow::{anyhow, Context, Result};
+use git2::{Oid, Repository, Status, StatusOptions};
+use std::fs;
+use std::path::Path;
+
+/// Most commits listed per side of a conflicted file
+const MAX_COMMITS_PER_SIDE: usize = 5;
+
+/// The operation that stopped on conflicts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictOperation {
+    Merge,
+    CherryPick,
+    Revert,
+    Rebase,
+    /// Conflict markers without an operation in progress, e.g. from `git stash pop`
+    Unknown,
+}
+
+impl std::fmt::Display for ConflictOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Merge => write!(f, "merge"),
+            Self::CherryPick => write!(f, "cherry-pick"),
+            Self::Revert => write!(f, "revert"),
+            Self::Rebase => write!(f, "rebase"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// One `<<<<<<<` ... `>>>>>>>` block in a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictRegion {
+    /// Line of the `<<<<<<<` marker, starting at 1
+    pub start_line: usize,
+    pub ours: String,
+    /// The common ancestor's version, present with `merge.conflictStyle = diff3`
+    pub base: Option<String>,
+    pub theirs: String,
+}
+
+/// A file with conflicts, and the commits on each side that changed it
+pub struct ConflictedFile {
+    pub path: String,
+    pub regions: Vec<ConflictRegion>,
+    pub ours_commits: Vec<AnalyzedChange>,
+    pub theirs_commits: Vec<AnalyzedChange>,
+}
+
+pub struct ConflictContext {
+    pub operation: ConflictOperation,
+    /// Label of the incoming side, such as the branch being merged or the commit being picked
+    pub incoming: Option<String>,
+    pub files: Vec<ConflictedFile>,
+}
+
+impl ConflictContext {
+    /// Find the conflicted files in the working tree and the commits behind both sides
+    pub fn load(repo_path: &Path) -> Result<Self> {
+        let repo = Repository::open(repo_path)?;
+        let (operation, incoming) = detect_operation(&repo);
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| anyhow!("Conflicts can only be explained in a working tree"))?
+            .to_path_buf();
+
+        let head = repo.head().ok().and_then(|head| head.target());
+        let incoming_id = incoming
+            .as_deref()
+            .and_then(|reference| repo.revparse_single(reference).ok())
+            .map(|obj| obj.id());
+        let sides = match (head, incoming_id) {
+            (Some(head), Some(incoming_id)) => {
+                Some(side_ranges(&repo, operation, head, incoming_id)?)
+            }
+            _ => None,
+        };
+
+        let analyzer = ChangeAnalyzer::new(&repo);
+        let mut files = Vec::new();
+        for path in find_conflicted_paths(&repo)? {
+            let content = fs::read(workdir.join(&path)).unwrap_or_default();
+            let regions = parse_conflict_regions(&String::from_utf8_lossy(&content));
+
+            let (ours_commits, theirs_commits) = match &sides {
+                Some((ours, theirs)) => (
+                    commits_touching(&repo, &analyzer, ours, &path),
+                    commits_touching(&repo, &analyzer, theirs, &path),
+                ),
+                None => (Vec::new(), Vec::new()),
+            };
+
+            files.push(ConflictedFile {
+                path,
+                regions,
+                ours_commits,
+                theirs_commits,
+            });
+        }
+
+        Ok(ConflictContext {
+            operation,
+            incoming,
+            files,
+        })
+    }
+}
+
+pub struct ConflictExplainer;
+
+impl ConflictExplainer {
+    /// Explain what each side of every conflict was trying to do, and how to resolve it
+    pub async fn explain(context: &ConflictContext, config: &Config) -> Result<String> {
+        if context.files.is_empty() {
+            return Err(anyhow!("No conflicts found"));
+        }
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let mut system_prompt = create_conflicts_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        // Keep large conflicts within the provider's context window
+        let token_limit = config
+            .providers
+            .get(&config.default_provider)
+            .and_then(|provider| provider.token_limit)
+            .unwrap_or_else(|| get_provider_metadata(&provider_type).default_token_limit);
+        let user_prompt = TokenOptimizer::new(token_limit)
+            .truncate_string(&create_conflicts_user_prompt(context), token_limit / 2);
+
+        llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+            .await
+            .context("Failed to generate conflict explanation")
+    }
+}
+
+pub fn create_conflicts_system_prompt() -> String {
+    String::from(
+        "You are an AI assistant specialized in helping developers resolve Git conflicts. \
+        Your task is to explain, for each conflicted file, what each side of the conflict was trying to do, and to suggest how to resolve it. \
+        Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Write the explanation in Markdown with one '## <file path>' section per conflicted file, in the order given.
+        2. In each section, add the bullet points '**Ours:**' and '**Theirs:**' describing what that side changed and why, based on its commits and its version of the code.
+        3. Then add '**Suggested resolution:**' with a concrete strategy: keep one side, combine both, or rewrite the region, and say which changes must survive.
+        4. When both sides must be combined, describe the combined result in words or a short code snippet.
+        5. Point out when a side looks like a refactoring, such as a rename or move, that the other side's change must be carried over to.
+        6. For files without conflict regions, such as one side deleting a file the other changed, explain the situation and the options.
+        7. Base the intent of each side on its commit messages; clearly mark any guess as likely, and never present it as certain.
+        8. Avoid common cliché words (like 'enhance', 'streamline', 'leverage', etc) and phrases.
+        9. NO YAPPING!
+
+        Generate only the explanation, without any introduction or closing remarks.",
+    )
+}
+
+pub fn create_conflicts_user_prompt(context: &ConflictContext) -> String {
+    let mut prompt = String::from(
+        "Based on the following conflicts, explain what each side was trying to do and suggest a resolution for each file:\n\n",
+    );
+
+    prompt.push_str(&format!("Operation: {}\n", context.operation));
+    if let Some(incoming) = &context.incoming {
+        prompt.push_str(&format!("Incoming: {}\n", incoming));
+    }
+    prompt.push('\n');
+
+    for file in &context.files {
+        prompt.push_str(&format!("File: {}\n", file.path));
+        push_side_commits(&mut prompt, "Ours", &file.ours_commits);
+        push_side_commits(&mut prompt, "Theirs", &file.theirs_commits);
+
+        if file.regions.is_empty() {
+            prompt.push_str("No conflict regions in the file content\n\n");
+            continue;
+        }
+        for region in &file.regions {
+            prompt.push_str(&format!("Conflict at line {}:\n", region.start_line));
+            prompt.push_str(&format!("Ours:\n{}\n", region.ours));
+            if let Some(base) = &region.base {
+                prompt.push_str(&format!("Base:\n{}\n", base));
+            }
+            prompt.push_str(&format!("Theirs:\n{}\n", region.theirs));
+        }
+        prompt.push('\n');
+    }
+
+    prompt
+}
+
+fn push_side_commits(prompt: &mut String, side: &str, commits: &[AnalyzedChange]) {
+    if commits.is_empty() {
+        prompt.push_str(&format!("{} commits: unknown\n", side));
+        return;
+    }
+    prompt.push_str(&format!("{} commits:\n", side));
+    for change in commits {
+        prompt.push_str(&format!(
+            "  - {} {}\n",
+            &change.commit_hash[..7],
+            change.commit_message.trim()
+        ));
+    }
+}
+
+/// Split a file's content into its conflict regions
+///
+/// Understands both the default and the `diff3` conflict styles. An unterminated region at the end
+/// of the file is ignored.
+pub fn parse_conflict_regions(content: &str) -> Vec<ConflictRegion> {
+    enum Section {
+        Outside,
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let mut regions = Vec::new();
+    let mut section = Section::Outside;
+    let mut start_line = 0;
+    let mut ours = String::new();
+    let mut base: Option<String> = None;
+    let mut theirs = String::new();
+
+    for (index, line) in content.lines().enumerate() {
+        match section {
+            Section::Outside if line.starts_with("<<<<<<<") => {
+                section = Section::Ours;
+                start_line = index + 1;
+                ours.clear();
+                base = None;
+                theirs.clear();
+            }
+            Section::Outside => {}
+            Section::Ours | Section::Base if line.starts_with("=======") => {
+                section = Section::Theirs;
+            }
+            Section::Ours if line.starts_with("|||||||") => {
+                section = Section::Base;
+                base = Some(String::new());
+            }
+            Section::Ours => push_line(&mut ours, line),
+            Section::Base => push_line(base.get_or_insert_with(String::new), line),
+            Section::Theirs if line.starts_with(">>>>>>>") => {
+                regions.push(ConflictRegion {
+                    start_line,
+                    ours: std::mem::take(&mut ours),
+                    base: base.take(),
+                    theirs: std::mem::take(&mut theirs),
+                });
+                section = Section::Outside;
+            }
+            Section::Theirs => push_line(&mut theirs, line),
+        }
+    }
+
+    regions
+}
+
+fn push_line(text: &mut String, line: &str) {
+    text.push_str(line);
+    text.push('\n');
+}
+
+fn detect_operation(repo: &Repository) -> (ConflictOperation, Option<String>) {
+    let read_head = |name: &str| {
+        fs::read_to_string(repo.path().join(name))
+            .ok()
+            .and_then(|content| content.lines().next().map(|line| line.trim().to_string()))
+    };
+
+    // During a rebase, REBASE_HEAD is the commit being applied, and a pick writes CHERRY_PICK_HEAD too
+    if let Some(rebase_head) = read_head("REBASE_HEAD") {
+        return (ConflictOperation::Rebase, Some(rebase_head));
+    }
+    if let Some(merge_head) = read_head("MERGE_HEAD") {
+        return (ConflictOperation::Merge, Some(merge_head));
+    }
+    if let Some(cherry_pick_head) = read_head("CHERRY_PICK_HEAD") {
+        return (ConflictOperation::CherryPick, Some(cherry_pick_head));
+    }
+    if let Some(revert_head) = read_head("REVERT_HEAD") {
+        return (ConflictOperation::Revert, Some(revert_head));
+    }
+    (ConflictOperation::Unknown, None)
+}
+
+/// Commit ranges, as (tip, boundary), whose commits make up our side and their side
+type SideRange = (Oid, Option<Oid>);
+
+fn side_ranges(
+    repo: &Repository,
+    operation: ConflictOperation,
+    head: Oid,
+    incoming: Oid,
+) -> Result<(SideRange, SideRange)> {
+    let base = repo.merge_base(head, incoming).ok();
+    let ours = (head, base);
+    let theirs = match operation {
+        ConflictOperation::Merge => (incoming, base),
+        // A single commit is applied; only it makes up their side
+        _ => {
+            let parent = repo.find_commit(incoming)?.parent_id(0).ok();
+            (incoming, parent)
+        }
+    };
+    Ok((ours, theirs))
+}
+
+/// The most recent commits in a range that changed the given path
+fn commits_touching(
+    repo: &Repository,
+    analyzer: &ChangeAnalyzer,
+    (tip, boundary): &SideRange,
+    path: &str,
+) -> Vec<AnalyzedChange> {
+    let Ok(mut revwalk) = repo.revwalk() else {
+        return Vec::new();
+    };
+    if revwalk.push(*tip).is_err() {
+        return Vec::new();
+    }
+    if let Some(boundary) = boundary {
+        let _ = revwalk.hide(*boundary);
+    }
+
+    revwalk
+        .filter_map(|id| id.ok())
+        .filter_map(|id| repo.find_commit(id).ok())
+        .filter(|commit| commit.parent_count() <= 1)
+        .filter_map(|commit| analyzer.analyze_commit(&commit).ok())
+        .filter(|change| {
+            change
+                .file_changes
+                .iter()
+                .any(|file| file.new_path == path || file.old_path == path)
+        })
+        .take(MAX_COMMITS_PER_SIDE)
+        .collect()
+}
+
+/// Files still conflicted in the index, and changed files that contain conflict markers
+fn find_conflicted_paths(repo: &Repository) -> Result<Vec<String>> {
+    let mut paths: Vec<String> = Vec::new();
+
+    let index = repo.index()?;
+    if index.has_conflicts() {
+        paths.extend(
+            index
+                .conflicts()?
+                .filter_map(|conflict| conflict.ok())
+                .filter_map(|conflict| conflict.our.or(conflict.their).or(conflict.ancestor))
+                .map(|entry| String::from_utf8_lossy(&entry.path).to_string()),
+        );
+    }
+
+    // Files marked as resolved with `git add` may still contain markers
+    let workdir = repo.workdir().unwrap_or_else(|| repo.path());
+    let mut options = StatusOptions::new();
+    options.include_untracked(false);
+    for entry in repo.statuses(Some(&mut options))?.iter() {
+        let Some(path) = entry.path() else {
+            continue;
+        };
+        if paths.iter().any(|p| p == path) || !entry.status().intersects(changed_statuses()) {
+            continue;
+        }
+        let content = fs::read(workdir.join(path)).unwrap_or_default();
+        if !parse_conflict_regions(&String::from_utf8_lossy(&content)).is_empty() {
+            paths.push(path.to_string());
+        }
+    }
+
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+fn changed_statuses() -> Status {
+    Status::INDEX_MODIFIED | Status::INDEX_NEW | Status::WT_MODIFIED | Status::WT_NEW
+}
@@ -2,6 +2,7 @@ use serde::Serialize;
 use std::fmt;
 
 use crate::token_optimizer::TokenOptimizer;
+use crate::workspace::WorkspaceImpact;
 
 #[derive(Serialize, Debug, Clone)]
 pub struct CommitContext {
@@ -10,6 +11,7 @@ pub struct CommitContext {
     pub staged_files: Vec<StagedFile>,
     pub unstaged_files: Vec<String>,
     pub project_metadata: ProjectMetadata,
+    pub workspace_impact: Vec<WorkspaceImpact>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -27,6 +29,49 @@ pub struct StagedFile {
     pub diff: String,
     pub analysis: Vec<String>,
     pub content_excluded: bool,
+    pub stage_status: StageStatus,
+}
+
+/// Where a file's change lives: in the index, only in the working tree, or both
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageStatus {
+    Staged,
+    Unstaged,
+    PartiallyStaged,
+}
+
+impl fmt::Display for StageStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StageStatus::Staged => write!(f, "staged"),
+            StageStatus::Unstaged => write!(f, "unstaged"),
+            StageStatus::PartiallyStaged => write!(f, "staged and unstaged"),
+        }
+    }
+}
+
+/// Which changes to collect for a commit message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChangeScope {
+    /// Only changes in the index
+    #[default]
+    Staged,
+    /// Staged and working-tree changes to tracked files, as `git commit -a` commits them
+    All,
+    /// Only working-tree changes to tracked files that are not in the index
+    Unstaged,
+}
+
+impl ChangeScope {
+    pub fn from_flags(all: bool, unstaged: bool) -> Self {
+        if all {
+            ChangeScope::All
+        } else if unstaged {
+            ChangeScope::Unstaged
+        } else {
+            ChangeScope::Staged
+        }
+    }
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -78,6 +123,7 @@ impl CommitContext {
         staged_files: Vec<StagedFile>,
         unstaged_files: Vec<String>,
         project_metadata: ProjectMetadata,
+        workspace_impact: Vec<WorkspaceImpact>,
     ) -> Self {
         CommitContext {
             branch,
@@ -85,6 +131,7 @@ impl CommitContext {
             staged_files,
             unstaged_files,
             project_metadata,
+            workspace_impact,
         }
     }
     pub fn optimize(&mut self, max_tokens: usize) {
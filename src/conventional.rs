@@ -0,0 +1,152 @@
+use crate::commit_message::{split_leading_emoji, subject_line};
+use crate::config::{ConventionalConfig, LintConfig};
+use crate::context::StagedFile;
+use crate::exclusions::matches_pattern;
+use crate::lint::{lint_message, LintRule, CONVENTIONAL_TYPES};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+
+lazy_static! {
+    // Conventional commit subject, with the parts needed to replace the scope
+    static ref SUBJECT: Regex = Regex::new(
+        r"^(?P<type>[a-z]+)(?:\([^()\s]+\))?(?P<breaking>!)?: (?P<description>\S.*)$"
+    )
+    .unwrap();
+}
+
+/// Directories whose subdirectories are separate packages, named after the package
+const PACKAGE_DIRS: &[&str] = &["crates", "packages", "apps", "libs", "modules"];
+
+/// Source roots that are skipped when deriving a scope from a path
+const SOURCE_DIRS: &[&str] = &["src", "lib", "source"];
+
+/// File names that don't tell which part of a project changed
+const ENTRY_POINTS: &[&str] = &["lib", "main", "mod", "index", "__init__"];
+
+/// Conventional Commits settings for one set of changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalMode {
+    /// Scope inferred from the changed paths, if they all belong to one part of the project
+    pub scope: Option<String>,
+}
+
+impl ConventionalMode {
+    pub fn for_changes(files: &[StagedFile], config: &ConventionalConfig) -> Self {
+        let paths: Vec<String> = files.iter().map(|file| file.path.clone()).collect();
+        Self {
+            scope: infer_scope(&paths, &config.scopes),
+        }
+    }
+
+    /// Instructions for the system prompt
+    pub fn instructions(&self) -> String {
+        let scope = match &self.scope {
+            Some(scope) => format!("Use '{}' as the scope.", scope),
+            None => "Leave out the scope unless all changes clearly belong to one part of \
+                the project."
+                .to_string(),
+        };
+        format!(
+            "Write the subject line strictly in the Conventional Commits format \
+            'type(scope): subject'. Use one of these types: {}. {} \
+            Start the subject after the colon with a lowercase letter. \
+            Mark breaking changes with '!' before the colon and a 'BREAKING CHANGE:' footer. \
+            If you use a gitmoji, put it before the type.",
+            CONVENTIONAL_TYPES.join(", "),
+            scope
+        )
+    }
+
+    /// What's wrong with a generated message, or `None` if it follows the format
+    pub fn check(&self, message: &str) -> Option<String> {
+        let rules = LintConfig {
+            conventional: true,
+            max_subject_length: 0,
+            imperative_mood: false,
+            max_body_line_length: 0,
+        };
+        lint_message(message, &rules)
+            .into_iter()
+            .find(|violation| matches!(violation.rule, LintRule::Conventional | LintRule::Empty))
+            .map(|violation| violation.message)
+    }
+
+    /// Give a generated message the inferred scope, replacing whichever one the model chose
+    pub fn apply_scope(&self, message: &str) -> String {
+        match &self.scope {
+            Some(scope) => replace_scope(message, scope),
+            None => message.to_string(),
+        }
+    }
+}
+
+/// Ask the model to fix a message that doesn't follow the Conventional Commits format
+pub fn correction_prompt(user_prompt: &str, message: &str, problem: &str) -> String {
+    format!(
+        "{}\n\nYou previously wrote this commit message for the changes above:\n{}\n\n\
+        It is not a valid Conventional Commits message: {}. \
+        Rewrite it so the subject line follows the 'type(scope): subject' format, \
+        keeping the rest of the message.",
+        user_prompt,
+        message.trim(),
+        problem
+    )
+}
+
+/// The scope shared by every changed path that has one, or `None` if they differ
+pub fn infer_scope(paths: &[String], scopes: &HashMap<String, String>) -> Option<String> {
+    let mut found = paths.iter().filter_map(|path| scope_for_path(path, scopes));
+    let first = found.next()?;
+    found.all(|scope| scope == first).then_some(first)
+}
+
+/// The scope of a changed path: the most specific configured pattern that matches it, or else
+/// its package, directory, or module name
+///
+/// Files at the top of the repository have no scope.
+pub fn scope_for_path(path: &str, scopes: &HashMap<String, String>) -> Option<String> {
+    if let Some((_, scope)) = scopes
+        .iter()
+        .filter(|(pattern, _)| matches_pattern(pattern, path))
+        .max_by_key(|(pattern, _)| pattern.len())
+    {
+        return Some(scope.clone()).filter(|scope| !scope.is_empty());
+    }
+
+    let components: Vec<&str> = path.split('/').collect();
+    let scope = match components.as_slice() {
+        [root, package, _, ..] if PACKAGE_DIRS.contains(root) => *package,
+        [root, file] if SOURCE_DIRS.contains(root) => {
+            let stem = file.split('.').next().unwrap_or(file);
+            if ENTRY_POINTS.contains(&stem) {
+                return None;
+            }
+            stem
+        }
+        [root, dir, _, ..] if SOURCE_DIRS.contains(root) => *dir,
+        [dir, _, ..] => *dir,
+        _ => return None,
+    };
+    let scope = scope.trim_start_matches('.').to_lowercase();
+    Some(scope).filter(|scope| !scope.is_empty())
+}
+
+fn replace_scope(message: &str, scope: &str) -> String {
+    let message = message.trim();
+    let subject = subject_line(message);
+    let (emoji, text) = split_leading_emoji(subject);
+    let Some(captures) = SUBJECT.captures(text) else {
+        return message.to_string();
+    };
+    let emoji = emoji.map(|emoji| format!("{} ", emoji)).unwrap_or_default();
+    format!(
+        "{}{}({}){}: {}{}",
+        emoji,
+        &captures["type"],
+        scope,
+        captures.name("breaking").map_or("", |m| m.as_str()),
+        &captures["description"],
+        &message[subject.len()..]
+    )
+}
@@ -0,0 +1,234 @@
+use crate::config::Config;
+use crate::hooks;
+use crate::llm::validate_provider_config;
+use crate::llm_providers::{get_provider_metadata, LLMProviderType};
+use crate::logger;
+use git2::Repository;
+use std::path::Path;
+use std::process::Command;
+
+/// Outcome of a single diagnostic check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// Result of a single diagnostic check, with a suggested fix when it did not pass
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub fix: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// Run the local setup checks: Git, repository, configuration, API key, model, hook, and log file
+pub fn run_setup_checks(repo_path: &Path) -> Vec<CheckResult> {
+    let mut results = vec![check_git(), check_repository(repo_path)];
+
+    match Config::load() {
+        Ok(config) => {
+            results.push(CheckResult::pass("Config", config_location()));
+            results.extend(check_config(&config));
+        }
+        Err(e) => results.push(CheckResult::fail(
+            "Config",
+            format!("{} could not be loaded: {}", config_location(), e),
+            "Fix the syntax in the configuration file, or delete it to start over with the defaults",
+        )),
+    }
+
+    results.push(check_hook(repo_path));
+    results.push(check_log_file());
+    results
+}
+
+fn config_location() -> String {
+    Config::get_config_path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|_| "The configuration file".to_string())
+}
+
+fn check_git() -> CheckResult {
+    match Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => CheckResult::pass(
+            "Git",
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        _ => CheckResult::fail(
+            "Git",
+            "Git is not installed or not in the PATH",
+            "Install Git from https://git-scm.com and make sure the 'git' command is on your PATH",
+        ),
+    }
+}
+
+fn check_repository(repo_path: &Path) -> CheckResult {
+    let repo = match Repository::discover(repo_path) {
+        Ok(repo) => repo,
+        Err(_) => {
+            return CheckResult::fail(
+                "Repository",
+                "Not inside a Git repository",
+                "Run Git-Iris from inside a Git repository, or create one with 'git init'",
+            )
+        }
+    };
+
+    let location = repo
+        .workdir()
+        .unwrap_or_else(|| repo.path())
+        .display()
+        .to_string();
+    if repo.is_bare() {
+        return CheckResult::fail(
+            "Repository",
+            format!("{} is a bare repository", location),
+            "Run Git-Iris from a repository with a working tree",
+        );
+    }
+
+    let result = match repo.head() {
+        Ok(head) => CheckResult::pass(
+            "Repository",
+            format!(
+                "{} (on {})",
+                location,
+                head.shorthand().unwrap_or("HEAD detached")
+            ),
+        ),
+        Err(_) => CheckResult::warn(
+            "Repository",
+            format!("{} has no commits yet", location),
+            "Make an initial commit; commands that compare against HEAD need one",
+        ),
+    };
+    result
+}
+
+fn check_config(config: &Config) -> Vec<CheckResult> {
+    let mut results = Vec::new();
+
+    let provider_type: LLMProviderType = match config.default_provider.parse() {
+        Ok(provider_type) => provider_type,
+        Err(_) => {
+            results.push(CheckResult::fail(
+                "Provider",
+                format!("Unknown default provider '{}'", config.default_provider),
+                "Choose a provider with 'git-iris config --provider <name>'",
+            ));
+            return results;
+        }
+    };
+    results.push(CheckResult::pass("Provider", config.default_provider.clone()));
+
+    match validate_provider_config(config, &provider_type) {
+        Ok(()) if get_provider_metadata(&provider_type).requires_api_key => {
+            results.push(CheckResult::pass("API key", "Set"));
+        }
+        Ok(()) => results.push(CheckResult::pass("API key", "Not required")),
+        Err(e) => results.push(CheckResult::fail(
+            "API key",
+            e.to_string(),
+            format!(
+                "Set a key with 'git-iris config --provider {} --api-key <key>'",
+                config.default_provider
+            ),
+        )),
+    }
+
+    let model = config
+        .get_provider_config(&config.default_provider)
+        .map(|p| p.model.clone())
+        .unwrap_or_else(|| get_provider_metadata(&provider_type).default_model.to_string());
+    if model.trim().is_empty() {
+        results.push(CheckResult::fail(
+            "Model",
+            "No model configured",
+            format!(
+                "Set a model with 'git-iris config --provider {} --model <model>'",
+                config.default_provider
+            ),
+        ));
+    } else {
+        results.push(CheckResult::pass("Model", model));
+    }
+
+    if config
+        .preset_library()
+        .get_preset(&config.instruction_preset)
+        .is_none()
+    {
+        results.push(CheckResult::warn(
+            "Preset",
+            format!("Unknown default preset '{}'", config.instruction_preset),
+            "Choose a preset from 'git-iris list-presets' with 'git-iris config --preset <name>'",
+        ));
+    }
+
+    results
+}
+
+fn check_hook(repo_path: &Path) -> CheckResult {
+    let repo_root = match Repository::discover(repo_path)
+        .ok()
+        .and_then(|repo| repo.workdir().map(Path::to_path_buf))
+    {
+        Some(root) => root,
+        None => return CheckResult::pass("Hook", "Skipped outside a repository"),
+    };
+
+    if hooks::is_hook_installed(&repo_root) {
+        CheckResult::pass("Hook", format!("{} hook installed", hooks::HOOK_NAME))
+    } else if hooks::has_other_hook(&repo_root) {
+        CheckResult::warn(
+            "Hook",
+            format!("Another {} hook is installed", hooks::HOOK_NAME),
+            "Replace it with 'git-iris hooks install --force' if you want Git-Iris to write commit messages; a backup is kept",
+        )
+    } else {
+        CheckResult::pass("Hook", "Not installed (optional)")
+    }
+}
+
+fn check_log_file() -> CheckResult {
+    match logger::check_log_file() {
+        Ok(path) => CheckResult::pass("Log file", format!("{} is writable", path.display())),
+        Err(e) => CheckResult::warn(
+            "Log file",
+            format!("{} is not writable: {}", logger::LOG_FILE_NAME, e),
+            "Run Git-Iris from a writable directory, or fix the permissions on the log file, to use --log",
+        ),
+    }
+}
@@ -0,0 +1,202 @@
+use crate::config::Config;
+use crate::context::CommitContext;
+use crate::log_debug;
+use anyhow::Result;
+use dialoguer::{theme::ColorfulTheme, MultiSelect};
+use git2::Repository;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const HISTORY_FILE: &str = "exclusions.json";
+
+/// Number of times a file must be left out before it is suggested for the configuration
+pub const SUGGEST_AFTER: u32 = 3;
+
+/// Files whose contents are always left out of prompts unless a `prompt_include` pattern
+/// matches them: version control and editor files, dependencies, build output, lockfiles,
+/// logs, temporary files, and minified code
+pub const DEFAULT_EXCLUDE_PATTERNS: &[&str] = &[
+    "**/.git",
+    "**/.svn",
+    "**/.hg",
+    ".DS_Store",
+    "**/node_modules",
+    "**/target",
+    "**/build",
+    "**/dist",
+    "**/.vscode",
+    "**/.idea",
+    "**/.vs",
+    "package-lock.json",
+    "*.lock",
+    "*.log",
+    "*.tmp",
+    "*.temp",
+    "*.swp",
+    "*.min.js",
+];
+
+/// Decides which files are listed in prompts without their contents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptFilter {
+    pub exclude: Vec<String>,
+    /// Patterns for files whose contents are kept even if an exclude pattern matches them
+    pub include: Vec<String>,
+}
+
+impl Default for PromptFilter {
+    fn default() -> Self {
+        PromptFilter {
+            exclude: DEFAULT_EXCLUDE_PATTERNS.iter().map(|p| p.to_string()).collect(),
+            include: Vec::new(),
+        }
+    }
+}
+
+impl PromptFilter {
+    /// The built-in patterns together with the `prompt_exclude` and `prompt_include` settings
+    pub fn from_config(config: &Config) -> Self {
+        let mut filter = PromptFilter::default();
+        filter.exclude.extend(config.prompt_exclude.iter().cloned());
+        filter.include.extend(config.prompt_include.iter().cloned());
+        filter
+    }
+
+    /// Whether a file matches an exclude pattern and no include pattern
+    pub fn is_excluded(&self, path: &str) -> bool {
+        let matches_any =
+            |patterns: &[String]| patterns.iter().any(|pattern| matches_pattern(pattern, path));
+        matches_any(&self.exclude) && !matches_any(&self.include)
+    }
+}
+
+/// Whether a path matches a glob pattern from the `prompt_exclude` setting
+///
+/// `*` matches within a path component, `**` across components, and `?` a single character.
+/// Like `.gitignore`, a pattern without a `/` matches the file name in any directory.
+pub fn matches_pattern(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim().trim_start_matches('/');
+    if pattern.is_empty() {
+        return false;
+    }
+    let target = if pattern.contains('/') {
+        path
+    } else {
+        path.rsplit('/').next().unwrap_or(path)
+    };
+
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // "**/" also matches no directories at all
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    // A directory pattern covers everything below it
+    regex.push_str("(?:/.*)?$");
+
+    Regex::new(&regex)
+        .map(|regex| regex.is_match(target))
+        .unwrap_or(false)
+}
+
+/// Leave the diffs and analysis of the given files out of the prompt, keeping them staged
+pub fn exclude_files(context: &mut CommitContext, paths: &[String]) {
+    for file in &mut context.staged_files {
+        if paths.contains(&file.path) {
+            file.content_excluded = true;
+            file.diff = String::from("[Content excluded]");
+            file.analysis = vec!["[Analysis excluded]".to_string()];
+        }
+    }
+}
+
+/// Ask which files to describe, with every file checked to start with.
+/// Returns the files the user unchecked.
+pub fn select_prompt_files(context: &CommitContext) -> Result<Vec<String>> {
+    let files: Vec<&str> = context
+        .staged_files
+        .iter()
+        .filter(|file| !file.content_excluded)
+        .map(|file| file.path.as_str())
+        .collect();
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let defaults = vec![true; files.len()];
+    let selected = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Files to describe (space toggles, enter confirms; unchecked files stay staged)")
+        .items(&files)
+        .defaults(&defaults)
+        .interact()?;
+
+    Ok(files
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !selected.contains(index))
+        .map(|(_, path)| path.to_string())
+        .collect())
+}
+
+/// How often each file was left out of the prompt, kept per repository
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ExclusionHistory {
+    pub counts: BTreeMap<String, u32>,
+}
+
+impl ExclusionHistory {
+    pub fn load(repo_path: &Path) -> Self {
+        history_path(repo_path)
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, repo_path: &Path) -> Result<()> {
+        fs::write(history_path(repo_path)?, serde_json::to_string_pretty(self)?)?;
+        log_debug!("Exclusion history saved");
+        Ok(())
+    }
+
+    pub fn record(&mut self, paths: &[String]) {
+        for path in paths {
+            *self.counts.entry(path.clone()).or_insert(0) += 1;
+        }
+    }
+
+    /// Files left out often enough to suggest adding them to `prompt_exclude`,
+    /// skipping those the configured patterns already cover
+    pub fn suggestions(&self, patterns: &[String]) -> Vec<String> {
+        self.counts
+            .iter()
+            .filter(|(_, count)| **count >= SUGGEST_AFTER)
+            .filter(|(path, _)| !patterns.iter().any(|pattern| matches_pattern(pattern, path)))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
+
+/// The history lives inside the repository's `.git` directory so it never shows up as a change
+fn history_path(repo_path: &Path) -> Result<PathBuf> {
+    let repo = Repository::open(repo_path)?;
+    let dir = repo.path().join("git-iris");
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(HISTORY_FILE))
+}
@@ -0,0 +1,233 @@
+use crate::config::Config;
+use crate::context::{ChangeType, StageStatus, StagedFile};
+use crate::exclusions::PromptFilter;
+use crate::explain_prompts;
+use crate::file_analyzers;
+use crate::git;
+use crate::llm;
+use crate::llm_providers::{get_provider_metadata, LLMProviderType};
+use crate::token_optimizer::TokenOptimizer;
+use anyhow::{anyhow, Context, Result};
+use std::path::Path;
+
+pub struct CommitExplainer;
+
+impl CommitExplainer {
+    /// Explain what the given commit does, why it likely matters, and what it touches
+    pub async fn explain(repo_path: &Path, reference: &str, config: &Config) -> Result<String> {
+        let filter = PromptFilter::from_config(config);
+        let change = git::analyze_commit(repo_path, reference, &filter)?;
+        let patch = git::get_commit_patch(repo_path, reference, &filter)?;
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let mut system_prompt = explain_prompts::create_explain_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        // Keep large commits within the provider's context window
+        let token_limit = config
+            .providers
+            .get(&config.default_provider)
+            .and_then(|provider| provider.token_limit)
+            .unwrap_or_else(|| get_provider_metadata(&provider_type).default_token_limit);
+        let patch = TokenOptimizer::new(token_limit).truncate_string(&patch, token_limit / 2);
+
+        let user_prompt = explain_prompts::create_explain_commit_user_prompt(&change, &patch);
+
+        llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+            .await
+            .context("Failed to generate commit explanation")
+    }
+}
+
+pub struct DiffExplainer;
+
+impl DiffExplainer {
+    /// Explain a unified diff that does not need to come from the current repository
+    pub async fn explain(diff: &str, config: &Config) -> Result<String> {
+        let files = analyze_unified_diff(diff);
+        if files.is_empty() {
+            return Err(anyhow!("No file changes found in the provided diff"));
+        }
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let mut system_prompt = explain_prompts::create_explain_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let token_limit = config
+            .providers
+            .get(&config.default_provider)
+            .and_then(|provider| provider.token_limit)
+            .unwrap_or_else(|| get_provider_metadata(&provider_type).default_token_limit);
+        let optimizer = TokenOptimizer::new(token_limit);
+        let per_file_limit = (token_limit / 2) / files.len();
+        let files: Vec<StagedFile> = files
+            .into_iter()
+            .map(|mut file| {
+                file.diff = optimizer.truncate_string(&file.diff, per_file_limit.max(1));
+                file
+            })
+            .collect();
+
+        let user_prompt = explain_prompts::create_explain_diff_user_prompt(&files);
+
+        llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+            .await
+            .context("Failed to generate diff explanation")
+    }
+}
+
+/// Split a unified diff into per-file changes and run the file analyzers on each
+pub fn analyze_unified_diff(diff: &str) -> Vec<StagedFile> {
+    parse_unified_diff(diff)
+        .into_iter()
+        .map(|mut file| {
+            let analyzer = file_analyzers::get_analyzer(&file.path);
+            file.analysis = analyzer.analyze(&file.path, &file);
+            file
+        })
+        .collect()
+}
+
+/// Split a unified diff, as produced by `git diff` or `diff -u`, into per-file changes
+pub fn parse_unified_diff(diff: &str) -> Vec<StagedFile> {
+    let mut files: Vec<StagedFile> = Vec::new();
+    // Whether the current file was opened by a `diff --git` header and has no hunks yet
+    let mut awaiting_hunks = false;
+    let mut old_path: Option<String> = None;
+    // Lines left in the current hunk, so removed lines starting with `--` are not taken as headers
+    let mut remaining_old = 0usize;
+    let mut remaining_new = 0usize;
+
+    for line in diff.lines() {
+        if remaining_old > 0 || remaining_new > 0 {
+            match line.chars().next() {
+                Some('+') => remaining_new = remaining_new.saturating_sub(1),
+                Some('-') => remaining_old = remaining_old.saturating_sub(1),
+                Some('\\') => {}
+                _ => {
+                    remaining_old = remaining_old.saturating_sub(1);
+                    remaining_new = remaining_new.saturating_sub(1);
+                }
+            }
+            if let Some(file) = files.last_mut() {
+                file.diff.push_str(line);
+                file.diff.push('\n');
+            }
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            files.push(new_diff_file(git_header_path(header)));
+            awaiting_hunks = true;
+            old_path = None;
+        } else if line.starts_with("new file mode") && awaiting_hunks {
+            if let Some(file) = files.last_mut() {
+                file.change_type = ChangeType::Added;
+            }
+        } else if line.starts_with("deleted file mode") && awaiting_hunks {
+            if let Some(file) = files.last_mut() {
+                file.change_type = ChangeType::Deleted;
+            }
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            if !awaiting_hunks {
+                files.push(new_diff_file(String::new()));
+                awaiting_hunks = true;
+            }
+            old_path = diff_path(path);
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            if let Some(file) = files.last_mut() {
+                match (old_path.take(), diff_path(path)) {
+                    (None, Some(new_path)) => {
+                        file.path = new_path;
+                        file.change_type = ChangeType::Added;
+                    }
+                    (Some(old), None) => {
+                        file.path = old;
+                        file.change_type = ChangeType::Deleted;
+                    }
+                    (_, Some(new_path)) => file.path = new_path,
+                    (None, None) => {}
+                }
+            }
+        } else if line.starts_with("@@") {
+            if let Some(file) = files.last_mut() {
+                (remaining_old, remaining_new) = hunk_line_counts(line);
+                awaiting_hunks = false;
+                file.diff.push_str(line);
+                file.diff.push('\n');
+            }
+        }
+    }
+
+    files.retain(|file| !file.path.is_empty());
+    files
+}
+
+/// Read the old and new line counts from a `@@ -a,b +c,d @@` hunk header
+fn hunk_line_counts(header: &str) -> (usize, usize) {
+    let count = |range: Option<&str>| {
+        range
+            .map(|range| match range.split_once(',') {
+                Some((_, count)) => count.parse().unwrap_or(0),
+                None => 1,
+            })
+            .unwrap_or(0)
+    };
+    let mut ranges = header.split_whitespace().skip(1);
+    let old = count(ranges.next().and_then(|r| r.strip_prefix('-')));
+    let new = count(ranges.next().and_then(|r| r.strip_prefix('+')));
+    (old, new)
+}
+
+fn new_diff_file(path: String) -> StagedFile {
+    StagedFile {
+        path,
+        change_type: ChangeType::Modified,
+        diff: String::new(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    }
+}
+
+/// Extract the new path from the `a/<old> b/<new>` part of a `diff --git` header
+fn git_header_path(header: &str) -> String {
+    header
+        .rsplit_once(" b/")
+        .map(|(_, path)| path.to_string())
+        .unwrap_or_default()
+}
+
+/// Parse the path of a `---`/`+++` line, returning `None` for `/dev/null`
+fn diff_path(path: &str) -> Option<String> {
+    // Drop the timestamp that `diff -u` appends after a tab
+    let path = path.split('\t').next().unwrap_or(path).trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
@@ -0,0 +1,85 @@
+use crate::change_analyzer::AnalyzedChange;
+use crate::context::StagedFile;
+
+pub fn create_explain_system_prompt() -> String {
+    String::from(
+        "You are an AI assistant specialized in explaining code changes to developers who are unfamiliar with them. \
+        Your task is to explain a single change based on its analysis and diff, and its message when one is available. \
+        Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Write the explanation in Markdown with exactly these sections: '## What it does', '## Why it matters', and '## Areas touched'.
+        2. In 'What it does', describe the concrete behavior that changed in a short paragraph, followed by bullet points for the notable details.
+        3. In 'Why it matters', explain the likely purpose and consequences of the change, based on the available context.
+        4. Clearly mark any reasoning about intent as likely, and never present it as certain.
+        5. In 'Areas touched', list the parts of the codebase the change affects, grouped by module or directory rather than by individual file.
+        6. Mention any risk labels provided in the context and explain what they mean for this change.
+        7. Use the provided analysis to name specific functions, types, or settings that were added, modified, or removed.
+        8. Do not restate the diff line by line.
+        9. Avoid common cliché words (like 'enhance', 'streamline', 'leverage', etc) and phrases.
+        10. NO YAPPING!
+
+        Generate only the explanation, without any introduction or closing remarks."
+    )
+}
+
+pub fn create_explain_commit_user_prompt(change: &AnalyzedChange, patch: &str) -> String {
+    let mut prompt = format!(
+        "Based on the following commit, explain what it does, why it likely matters, and what areas of the codebase it touches:\n\n\
+        Commit: {}\n\
+        Author: {}\n\
+        Message: {}\n\
+        Impact score: {:.2}\n\
+        Files changed: {}, insertions: {}, deletions: {}\n\n\
+        Analysis:\n",
+        change.commit_hash,
+        change.author,
+        change.commit_message.trim(),
+        change.impact_score,
+        change.metrics.files_changed,
+        change.metrics.insertions,
+        change.metrics.deletions,
+    );
+
+    for file_change in &change.file_changes {
+        prompt.push_str(&format!(
+            "- {} ({})\n",
+            file_change.new_path, file_change.change_type
+        ));
+        for analysis in &file_change.analysis {
+            prompt.push_str(&format!("  - {}\n", analysis));
+        }
+        if !file_change.risk_labels.is_empty() {
+            let labels = file_change
+                .risk_labels
+                .iter()
+                .map(|label| label.key())
+                .collect::<Vec<_>>()
+                .join(", ");
+            prompt.push_str(&format!("  - Risk labels: {}\n", labels));
+        }
+    }
+
+    prompt.push_str(&format!("\nDiff:\n{}", patch));
+
+    prompt
+}
+
+pub fn create_explain_diff_user_prompt(files: &[StagedFile]) -> String {
+    let mut prompt = String::from(
+        "Based on the following diff, explain what it does, why it likely matters, and what areas of the codebase it touches:\n\n",
+    );
+
+    for file in files {
+        prompt.push_str(&format!(
+            "File: {} ({})\nAnalysis:\n{}\n\nDiff:\n{}\n---\n\n",
+            file.path,
+            file.change_type,
+            file.analysis.join("\n"),
+            file.diff
+        ));
+    }
+
+    prompt
+}
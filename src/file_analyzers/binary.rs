@@ -0,0 +1,421 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref SVG_TAG: Regex = Regex::new(r"(?s)<svg\b[^>]*>").unwrap();
+    static ref SVG_ATTRIBUTE: Regex =
+        Regex::new(r#"\s(width|height|viewBox)\s*=\s*["']([^"']*)["']"#).unwrap();
+}
+
+/// What a binary file holds, as far as its header tells
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Asset {
+    Image {
+        format: &'static str,
+        dimensions: Option<String>,
+    },
+    Font {
+        format: &'static str,
+        name: Option<String>,
+    },
+    Archive {
+        format: &'static str,
+        entries: Option<usize>,
+    },
+    Other,
+}
+
+impl Asset {
+    fn read(bytes: &[u8]) -> Self {
+        if let Some((format, dimensions)) = image(bytes) {
+            Asset::Image {
+                format,
+                dimensions: dimensions.map(|(width, height)| format!("{}×{}", width, height)),
+            }
+        } else if let Some(format) = font_format(bytes) {
+            Asset::Font {
+                format,
+                name: font_name(bytes),
+            }
+        } else if let Some((format, entries)) = archive(bytes) {
+            Asset::Archive { format, entries }
+        } else {
+            Asset::Other
+        }
+    }
+
+    /// The asset as a phrase, such as "PNG image, 640×480"
+    fn describe(&self) -> String {
+        match self {
+            Asset::Image { format, dimensions } => match dimensions {
+                Some(dimensions) => format!("{} image, {}", format, dimensions),
+                None => format!("{} image", format),
+            },
+            Asset::Font { format, name } => match name {
+                Some(name) => format!("font {} ({})", name, format),
+                None => format!("{} font", format),
+            },
+            Asset::Archive { format, entries } => match entries {
+                Some(1) => format!("{} archive with 1 entry", format),
+                Some(entries) => format!("{} archive with {} entries", format, entries),
+                None => format!("{} archive", format),
+            },
+            Asset::Other => "binary file".to_string(),
+        }
+    }
+}
+
+/// Describe a change to a binary file from its bytes before and after, where `None` means the
+/// file didn't exist
+pub(super) fn analyze(old: Option<&[u8]>, new: Option<&[u8]>) -> Vec<String> {
+    match (old, new) {
+        (None, Some(new)) => vec![format!(
+            "Added {}, {}",
+            Asset::read(new).describe(),
+            format_size(new.len())
+        )],
+        (Some(old), None) => vec![format!(
+            "Removed {}, {}",
+            Asset::read(old).describe(),
+            format_size(old.len())
+        )],
+        (Some(old), Some(new)) => {
+            let mut analysis = Vec::new();
+            match (Asset::read(old), Asset::read(new)) {
+                (
+                    Asset::Image {
+                        dimensions: Some(before),
+                        ..
+                    },
+                    Asset::Image {
+                        dimensions: Some(after),
+                        ..
+                    },
+                ) => analysis.push(dimensions_change(&before, &after)),
+                (
+                    Asset::Font {
+                        name: Some(before), ..
+                    },
+                    Asset::Font {
+                        name: Some(after), ..
+                    },
+                ) if before != after => {
+                    analysis.push(format!("Font changed: {} → {}", before, after));
+                }
+                (
+                    Asset::Archive {
+                        entries: Some(before),
+                        ..
+                    },
+                    Asset::Archive {
+                        entries: Some(after),
+                        ..
+                    },
+                ) if before != after => {
+                    analysis.push(format!("Archive entries changed: {} → {}", before, after));
+                }
+                (before, after) if before != after => {
+                    analysis.push(format!(
+                        "Changed from {} to {}",
+                        before.describe(),
+                        after.describe()
+                    ));
+                }
+                _ => {}
+            }
+            analysis.push(size_change(old.len(), new.len()));
+            analysis
+        }
+        (None, None) => Vec::new(),
+    }
+}
+
+/// Describe a change to an SVG image, whose markup diffs as text but whose dimensions and
+/// size are what a reader of the history cares about
+pub(super) fn analyze_svg(old: Option<&str>, new: Option<&str>) -> Vec<String> {
+    let describe = |svg: &str| match svg_dimensions(svg) {
+        Some(dimensions) => format!("SVG image, {}, {}", dimensions, format_size(svg.len())),
+        None => format!("SVG image, {}", format_size(svg.len())),
+    };
+    match (old, new) {
+        (None, Some(new)) => vec![format!("Added {}", describe(new))],
+        (Some(old), None) => vec![format!("Removed {}", describe(old))],
+        (Some(old), Some(new)) => {
+            let mut analysis = Vec::new();
+            if let (Some(before), Some(after)) = (svg_dimensions(old), svg_dimensions(new)) {
+                analysis.push(dimensions_change(&before, &after));
+            }
+            analysis.push(size_change(old.len(), new.len()));
+            analysis
+        }
+        (None, None) => Vec::new(),
+    }
+}
+
+pub(super) fn is_svg(file: &str) -> bool {
+    file.to_lowercase().ends_with(".svg")
+}
+
+fn dimensions_change(before: &str, after: &str) -> String {
+    if before == after {
+        format!("Image dimensions unchanged: {}", before)
+    } else {
+        format!("Image dimensions changed: {} → {}", before, after)
+    }
+}
+
+fn size_change(before: usize, after: usize) -> String {
+    if before == after {
+        return format!("Size unchanged: {}", format_size(after));
+    }
+    let (sign, delta) = if after > before {
+        ('+', after - before)
+    } else {
+        ('-', before - after)
+    };
+    format!(
+        "Size changed: {} → {} ({}{})",
+        format_size(before),
+        format_size(after),
+        sign,
+        format_size(delta)
+    )
+}
+
+/// A size in bytes, KB, or MB, as in "12.3 KB"
+fn format_size(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    let size = bytes as f64;
+    if size < KB {
+        format!("{} B", bytes)
+    } else if size < KB * KB {
+        format!("{:.1} KB", size / KB)
+    } else {
+        format!("{:.1} MB", size / (KB * KB))
+    }
+}
+
+/// The dimensions of an SVG image, from its `width` and `height` or else its `viewBox`
+fn svg_dimensions(svg: &str) -> Option<String> {
+    let tag = SVG_TAG.find(svg)?.as_str();
+    let attribute = |name: &str| {
+        SVG_ATTRIBUTE
+            .captures_iter(tag)
+            .find(|captures| &captures[1] == name)
+            .map(|captures| captures[2].trim().to_string())
+    };
+    if let (Some(width), Some(height)) = (attribute("width"), attribute("height")) {
+        return Some(format!("{}×{}", width, height));
+    }
+    let view_box = attribute("viewBox")?;
+    let values: Vec<&str> = view_box
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|value| !value.is_empty())
+        .collect();
+    match values.as_slice() {
+        [_, _, width, height] => Some(format!("{}×{}", width, height)),
+        _ => None,
+    }
+}
+
+fn u16_be(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_be(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn u16_le(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_le(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// The format of a raster image and, when its header has them, its width and height
+fn image(bytes: &[u8]) -> Option<(&'static str, Option<(u32, u32)>)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        let dimensions = u32_be(bytes, 16).zip(u32_be(bytes, 20));
+        Some(("PNG", dimensions))
+    } else if bytes.starts_with(b"\xff\xd8") {
+        Some(("JPEG", jpeg_dimensions(bytes)))
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        let dimensions = u16_le(bytes, 6).zip(u16_le(bytes, 8));
+        Some(("GIF", dimensions.map(|(w, h)| (u32::from(w), u32::from(h)))))
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        Some(("WebP", webp_dimensions(bytes)))
+    } else if bytes.starts_with(b"BM") && bytes.len() > 26 {
+        let width = u32_le(bytes, 18)?;
+        // Rows stored top-down have a negative height
+        let height = (u32_le(bytes, 22)? as i32).unsigned_abs();
+        Some(("BMP", Some((width, height))))
+    } else {
+        None
+    }
+}
+
+/// The dimensions in the first start-of-frame segment of a JPEG
+fn jpeg_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut at = 2;
+    while at + 1 < bytes.len() {
+        if bytes[at] != 0xff {
+            return None;
+        }
+        let marker = bytes[at + 1];
+        // Markers may be padded with any number of 0xFF bytes
+        if marker == 0xff {
+            at += 1;
+            continue;
+        }
+        // Markers without a length: restarts, start of image, and TEM
+        if (0xd0..=0xd8).contains(&marker) || marker == 0x01 {
+            at += 2;
+            continue;
+        }
+        let is_start_of_frame =
+            (0xc0..=0xcf).contains(&marker) && marker != 0xc4 && marker != 0xc8 && marker != 0xcc;
+        if is_start_of_frame {
+            let height = u16_be(bytes, at + 5)?;
+            let width = u16_be(bytes, at + 7)?;
+            return Some((u32::from(width), u32::from(height)));
+        }
+        at += 2 + usize::from(u16_be(bytes, at + 2)?);
+    }
+    None
+}
+
+/// The dimensions of a lossy, lossless, or extended WebP image
+fn webp_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let u24_le = |at: usize| -> Option<u32> {
+        let b = bytes.get(at..at + 3)?;
+        Some(u32::from(b[0]) | u32::from(b[1]) << 8 | u32::from(b[2]) << 16)
+    };
+    match bytes.get(12..16)? {
+        b"VP8X" => Some((u24_le(24)? + 1, u24_le(27)? + 1)),
+        b"VP8 " => Some((
+            u32::from(u16_le(bytes, 26)? & 0x3fff),
+            u32::from(u16_le(bytes, 28)? & 0x3fff),
+        )),
+        b"VP8L" => {
+            let bits = u32_le(bytes, 21)?;
+            Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+        }
+        _ => None,
+    }
+}
+
+fn font_format(bytes: &[u8]) -> Option<&'static str> {
+    match bytes.get(..4)? {
+        b"\x00\x01\x00\x00" | b"true" => Some("TrueType"),
+        b"OTTO" => Some("OpenType"),
+        b"ttcf" => Some("TrueType collection"),
+        b"wOFF" => Some("WOFF"),
+        b"wOF2" => Some("WOFF2"),
+        _ => None,
+    }
+}
+
+/// The full name of a TrueType or OpenType font, from its `name` table; WOFF fonts compress
+/// the table, so their names aren't read
+fn font_name(bytes: &[u8]) -> Option<String> {
+    let tables = usize::from(u16_be(bytes, 4)?);
+    let name_table = (0..tables)
+        .map(|index| 12 + index * 16)
+        .find(|&record| bytes.get(record..record + 4) == Some(b"name"))
+        .and_then(|record| u32_be(bytes, record + 8))? as usize;
+
+    let count = usize::from(u16_be(bytes, name_table + 2)?);
+    let strings = name_table + usize::from(u16_be(bytes, name_table + 4)?);
+    let mut family = None;
+    for index in 0..count {
+        let record = name_table + 6 + index * 12;
+        let platform = u16_be(bytes, record)?;
+        let name_id = u16_be(bytes, record + 6)?;
+        let length = usize::from(u16_be(bytes, record + 8)?);
+        let offset = strings + usize::from(u16_be(bytes, record + 10)?);
+        // The full name, or the family when there is none
+        if name_id != 4 && name_id != 1 {
+            continue;
+        }
+        let Some(raw) = bytes.get(offset..offset + length) else {
+            continue;
+        };
+        let name = match platform {
+            // Unicode and Windows names are UTF-16, big-endian
+            0 | 3 => {
+                let units: Vec<u16> = raw
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                    .collect();
+                String::from_utf16(&units).ok()
+            }
+            1 => Some(raw.iter().map(|&byte| char::from(byte)).collect()),
+            _ => None,
+        };
+        match (name, name_id) {
+            (Some(name), 4) if !name.trim().is_empty() => return Some(name.trim().to_string()),
+            (Some(name), 1) if family.is_none() && !name.trim().is_empty() => {
+                family = Some(name.trim().to_string());
+            }
+            _ => {}
+        }
+    }
+    family
+}
+
+/// The format of an archive and, for ZIP-based and tar archives, the number of entries in it
+fn archive(bytes: &[u8]) -> Option<(&'static str, Option<usize>)> {
+    if bytes.starts_with(b"PK\x03\x04") || bytes.starts_with(b"PK\x05\x06") {
+        Some(("ZIP", zip_entries(bytes)))
+    } else if bytes.get(257..262) == Some(b"ustar") {
+        Some(("tar", tar_entries(bytes)))
+    } else if bytes.starts_with(b"\x1f\x8b") {
+        Some(("gzip", None))
+    } else if bytes.starts_with(b"7z\xbc\xaf\x27\x1c") {
+        Some(("7z", None))
+    } else if bytes.starts_with(b"Rar!\x1a\x07") {
+        Some(("RAR", None))
+    } else {
+        None
+    }
+}
+
+/// The entry count in the end of central directory record of a ZIP archive, which follows
+/// the entries and a comment of at most 64 KB
+fn zip_entries(bytes: &[u8]) -> Option<usize> {
+    let search_from = bytes.len().saturating_sub(22 + usize::from(u16::MAX));
+    let end = (search_from..=bytes.len().checked_sub(22)?)
+        .rev()
+        .find(|&at| bytes[at..].starts_with(b"PK\x05\x06"))?;
+    match u16_le(bytes, end + 10)? {
+        // ZIP64 archives keep the count elsewhere
+        u16::MAX => None,
+        entries => Some(usize::from(entries)),
+    }
+}
+
+/// The number of files, directories, and links in a tar archive
+fn tar_entries(bytes: &[u8]) -> Option<usize> {
+    let mut entries = 0;
+    let mut at = 0;
+    while let Some(header) = bytes.get(at..at + 512) {
+        if header[0] == 0 {
+            break;
+        }
+        let size = std::str::from_utf8(&header[124..136])
+            .ok()
+            .map(|size| size.trim_matches(|c: char| c == '\0' || c == ' '))
+            .and_then(|size| match size {
+                "" => Some(0),
+                size => usize::from_str_radix(size, 8).ok(),
+            })?;
+        // Extended headers describe the entry after them rather than being entries
+        if !matches!(header[156], b'x' | b'g' | b'L' | b'K') {
+            entries += 1;
+        }
+        at += 512 + size.div_ceil(512) * 512;
+    }
+    Some(entries)
+}
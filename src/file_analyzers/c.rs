@@ -13,12 +13,27 @@ impl FileAnalyzer for CAnalyzer {
             analysis.push(format!("Modified functions: {}", functions.join(", ")));
         }
 
+        if let Some(signatures) = extract_signature_changes(&staged_file.diff, FUNCTION_PATTERN) {
+            analysis.push(format!("Changed function signatures: {}", signatures.join(", ")));
+        }
+
         if let Some(structs) = extract_modified_structs(&staged_file.diff) {
             analysis.push(format!("Modified structs: {}", structs.join(", ")));
         }
 
+        if let Some(macros) = extract_modified_macros(&staged_file.diff) {
+            analysis.push(format!("Modified macros: {}", macros.join(", ")));
+        }
+
         if has_include_changes(&staged_file.diff) {
             analysis.push("Include statements have been modified".to_string());
+            let (added, removed) = extract_include_changes(&staged_file.diff);
+            if !added.is_empty() {
+                analysis.push(format!("Added includes: {}", added.join(", ")));
+            }
+            if !removed.is_empty() {
+                analysis.push(format!("Removed includes: {}", removed.join(", ")));
+            }
         }
 
         analysis
@@ -29,10 +44,12 @@ impl FileAnalyzer for CAnalyzer {
     }
 
     fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
-        let mut metadata = ProjectMetadata::default();
-        metadata.language = Some("C".to_string());
+        let mut metadata = ProjectMetadata {
+            language: Some("C".to_string()),
+            ..Default::default()
+        };
 
-        if file == "Makefile" {
+        if file == "Makefile" || file.ends_with("/Makefile") {
             self.extract_makefile_metadata(content, &mut metadata);
         } else {
             self.extract_c_file_metadata(content, &mut metadata);
@@ -68,11 +85,15 @@ impl CAnalyzer {
     }
 }
 
+/// A function definition or declaration on a changed line: the sign, the name, and the
+/// signature up to the end of the parameter list
+const FUNCTION_PATTERN: &str = r"(?m)^([+-])\s*((?:(?:static|inline|extern|const|volatile|unsigned|signed|short|long)\s+)*(?:void|int|char|float|double|bool|size_t|\w+_t|struct\s+\w+)(?:\s+|\s*\*+\s*)(\w+)\s*\([^)]*\)?)";
+
 fn extract_modified_functions(diff: &str) -> Option<Vec<String>> {
-    let re = Regex::new(r"(?m)^[+-]\s*(?:static\s+)?(?:inline\s+)?(?:const\s+)?(?:volatile\s+)?(?:unsigned\s+)?(?:signed\s+)?(?:short\s+)?(?:long\s+)?(?:void|int|char|float|double|struct\s+\w+)\s+(\w+)\s*\(").unwrap();
+    let re = Regex::new(FUNCTION_PATTERN).unwrap();
     let functions: HashSet<String> = re
         .captures_iter(diff)
-        .filter_map(|cap| cap.get(1).map(|m| m.as_str().to_string()))
+        .filter_map(|cap| cap.get(3).map(|m| m.as_str().to_string()))
         .collect();
 
     if functions.is_empty() {
@@ -82,6 +103,39 @@ fn extract_modified_functions(diff: &str) -> Option<Vec<String>> {
     }
 }
 
+/// Functions removed with one signature and added back with another, given a pattern that
+/// captures the sign, the signature, and the name of a function like `FUNCTION_PATTERN`
+pub(super) fn extract_signature_changes(diff: &str, pattern: &str) -> Option<Vec<String>> {
+    let re = Regex::new(pattern).unwrap();
+    let mut removed: Vec<(String, String)> = Vec::new();
+    let mut added: Vec<(String, String)> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let signature = cap[2].split_whitespace().collect::<Vec<_>>().join(" ");
+        let entry = (cap[3].to_string(), signature);
+        if &cap[1] == "-" {
+            removed.push(entry);
+        } else {
+            added.push(entry);
+        }
+    }
+
+    let mut changes: Vec<String> = Vec::new();
+    for (name, before) in &removed {
+        let changed = added
+            .iter()
+            .any(|(added_name, after)| added_name == name && after != before);
+        if changed && !changes.contains(name) {
+            changes.push(name.clone());
+        }
+    }
+
+    if changes.is_empty() {
+        None
+    } else {
+        Some(changes)
+    }
+}
+
 fn extract_modified_structs(diff: &str) -> Option<Vec<String>> {
     let re = Regex::new(r"(?m)^[+-]\s*struct\s+(\w+)").unwrap();
     let structs: HashSet<String> = re
@@ -100,3 +154,44 @@ fn has_include_changes(diff: &str) -> bool {
     let re = Regex::new(r"(?m)^[+-]\s*#include").unwrap();
     re.is_match(diff)
 }
+
+/// Headers included on added lines but not removed ones, and the other way around, so
+/// that moving an include isn't reported
+pub(super) fn extract_include_changes(diff: &str) -> (Vec<String>, Vec<String>) {
+    let re = Regex::new(r#"(?m)^([+-])\s*#\s*include\s*([<"][^>"]+[>"])"#).unwrap();
+    let mut added: Vec<String> = Vec::new();
+    let mut removed: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let header = cap[2].to_string();
+        let list = if &cap[1] == "+" { &mut added } else { &mut removed };
+        if !list.contains(&header) {
+            list.push(header);
+        }
+    }
+
+    let moved: Vec<String> = added
+        .iter()
+        .filter(|header| removed.contains(header))
+        .cloned()
+        .collect();
+    added.retain(|header| !moved.contains(header));
+    removed.retain(|header| !moved.contains(header));
+    (added, removed)
+}
+
+pub(super) fn extract_modified_macros(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r"(?m)^[+-]\s*#\s*(?:define|undef)\s+(\w+)").unwrap();
+    let mut macros: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let name = cap[1].to_string();
+        if !macros.contains(&name) {
+            macros.push(name);
+        }
+    }
+
+    if macros.is_empty() {
+        None
+    } else {
+        Some(macros)
+    }
+}
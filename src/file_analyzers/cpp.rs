@@ -1,3 +1,4 @@
+use super::c::{extract_include_changes, extract_modified_macros, extract_signature_changes};
 use super::{FileAnalyzer, ProjectMetadata};
 use crate::context::StagedFile;
 use regex::Regex;
@@ -6,19 +7,44 @@ use std::collections::HashSet;
 pub struct CppAnalyzer;
 
 impl FileAnalyzer for CppAnalyzer {
-    fn analyze(&self, _file: &str, staged_file: &StagedFile) -> Vec<String> {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
         let mut analysis = Vec::new();
 
+        if is_cmake_file(file) {
+            if let Some(targets) = extract_modified_targets(&staged_file.diff) {
+                analysis.push(format!("Modified targets: {}", targets.join(", ")));
+            }
+            if let Some(dependencies) = extract_modified_cmake_dependencies(&staged_file.diff) {
+                analysis.push(format!("Modified dependencies: {}", dependencies.join(", ")));
+            }
+            return analysis;
+        }
+
         if let Some(functions) = extract_modified_functions(&staged_file.diff) {
             analysis.push(format!("Modified functions: {}", functions.join(", ")));
         }
 
+        if let Some(signatures) = extract_signature_changes(&staged_file.diff, FUNCTION_PATTERN) {
+            analysis.push(format!("Changed function signatures: {}", signatures.join(", ")));
+        }
+
         if let Some(classes) = extract_modified_classes(&staged_file.diff) {
             analysis.push(format!("Modified classes: {}", classes.join(", ")));
         }
 
+        if let Some(macros) = extract_modified_macros(&staged_file.diff) {
+            analysis.push(format!("Modified macros: {}", macros.join(", ")));
+        }
+
         if has_include_changes(&staged_file.diff) {
             analysis.push("Include statements have been modified".to_string());
+            let (added, removed) = extract_include_changes(&staged_file.diff);
+            if !added.is_empty() {
+                analysis.push(format!("Added includes: {}", added.join(", ")));
+            }
+            if !removed.is_empty() {
+                analysis.push(format!("Removed includes: {}", removed.join(", ")));
+            }
         }
 
         analysis
@@ -29,10 +55,12 @@ impl FileAnalyzer for CppAnalyzer {
     }
 
     fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
-        let mut metadata = ProjectMetadata::default();
-        metadata.language = Some("C++".to_string());
+        let mut metadata = ProjectMetadata {
+            language: Some("C++".to_string()),
+            ..Default::default()
+        };
 
-        if file == "CMakeLists.txt" {
+        if is_cmake_file(file) {
             self.extract_cmake_metadata(content, &mut metadata);
         } else {
             self.extract_cpp_file_metadata(content, &mut metadata);
@@ -56,6 +84,17 @@ impl CppAnalyzer {
             let package = cap[1].split(' ').next().unwrap_or(&cap[1]);
             metadata.dependencies.push(package.to_string());
         }
+
+        let fetched_re = Regex::new(r"FetchContent_Declare\(\s*([\w.-]+)").unwrap();
+        for cap in fetched_re.captures_iter(content) {
+            if !metadata.dependencies.contains(&cap[1].to_string()) {
+                metadata.dependencies.push(cap[1].to_string());
+            }
+        }
+
+        if let Some(test_framework) = detect_test_framework(content) {
+            metadata.test_framework = Some(test_framework.to_string());
+        }
     }
 
     fn extract_cpp_file_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
@@ -66,14 +105,89 @@ impl CppAnalyzer {
         if content.contains("#include <vector>") {
             metadata.framework = Some("Standard Library".to_string());
         }
+
+        if let Some(test_framework) = detect_test_framework(content) {
+            metadata.test_framework = Some(test_framework.to_string());
+        }
     }
 }
 
+fn is_cmake_file(file: &str) -> bool {
+    file == "CMakeLists.txt" || file.ends_with("/CMakeLists.txt") || file.ends_with(".cmake")
+}
+
+fn detect_test_framework(content: &str) -> Option<&'static str> {
+    if content.contains("gtest") || content.contains("GTest") {
+        Some("GoogleTest")
+    } else if content.contains("catch2") || content.contains("Catch2") {
+        Some("Catch2")
+    } else {
+        None
+    }
+}
+
+/// Executables and libraries whose `add_executable` or `add_library` line changed
+fn extract_modified_targets(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r"(?m)^[+-]\s*add_(?:executable|library)\s*\(\s*([\w.-]+)").unwrap();
+    unique_names(re.captures_iter(diff).map(|cap| cap[1].to_string()))
+}
+
+/// Packages found or fetched on changed lines, and libraries linked to or unlinked from a
+/// target
+///
+/// A library still linked to the same target after its `target_link_libraries` line changed
+/// isn't counted.
+fn extract_modified_cmake_dependencies(diff: &str) -> Option<Vec<String>> {
+    let package_re = Regex::new(
+        r"(?m)^[+-]\s*(?:find_package|FetchContent_Declare|pkg_check_modules)\s*\(\s*([\w.-]+)",
+    )
+    .unwrap();
+    let link_re =
+        Regex::new(r"(?m)^([+-])\s*target_link_libraries\s*\(\s*([\w.-]+)\s+([^)\n]*)").unwrap();
+
+    let mut removed: Vec<(String, String)> = Vec::new();
+    let mut added: Vec<(String, String)> = Vec::new();
+    for cap in link_re.captures_iter(diff) {
+        let links = if &cap[1] == "-" { &mut removed } else { &mut added };
+        links.extend(
+            cap[3]
+                .split_whitespace()
+                .filter(|library| !matches!(*library, "PRIVATE" | "PUBLIC" | "INTERFACE"))
+                .map(|library| (cap[2].to_string(), library.to_string())),
+        );
+    }
+
+    let packages = package_re.captures_iter(diff).map(|cap| cap[1].to_string());
+    let unlinked = removed.iter().filter(|link| !added.contains(link));
+    let linked = added.iter().filter(|link| !removed.contains(link));
+    let libraries = unlinked.chain(linked).map(|(_, library)| library.clone());
+    unique_names(packages.chain(libraries))
+}
+
+fn unique_names(names: impl Iterator<Item = String>) -> Option<Vec<String>> {
+    let mut unique: Vec<String> = Vec::new();
+    for name in names {
+        if !unique.contains(&name) {
+            unique.push(name);
+        }
+    }
+
+    if unique.is_empty() {
+        None
+    } else {
+        Some(unique)
+    }
+}
+
+/// A function definition or declaration on a changed line: the sign, the signature up to
+/// the end of the parameter list, and the name, qualified when defined outside its class
+const FUNCTION_PATTERN: &str = r"(?m)^([+-])\s*((?:(?:static|inline|virtual|explicit|constexpr|extern|const|volatile|unsigned|signed|short|long)\s+)*(?:void|int|char|float|double|bool|auto|size_t|\w+_t|std::[\w:]+(?:<[^>]*>)?|struct\s+\w+|class\s+\w+)(?:\s+|\s*[*&]+\s*)(\w+(?:::~?\w+)?)\s*\([^)]*\)?(?:\s*const)?)";
+
 fn extract_modified_functions(diff: &str) -> Option<Vec<String>> {
-    let re = Regex::new(r"(?m)^[+-]\s*(?:static\s+)?(?:inline\s+)?(?:const\s+)?(?:volatile\s+)?(?:unsigned\s+)?(?:signed\s+)?(?:short\s+)?(?:long\s+)?(?:void|int|char|float|double|struct\s+\w+|class\s+\w+)\s+(\w+)\s*\(").unwrap();
+    let re = Regex::new(FUNCTION_PATTERN).unwrap();
     let functions: HashSet<String> = re
         .captures_iter(diff)
-        .filter_map(|cap| cap.get(1).map(|m| m.as_str().to_string()))
+        .filter_map(|cap| cap.get(3).map(|m| m.as_str().to_string()))
         .collect();
 
     if functions.is_empty() {
@@ -0,0 +1,171 @@
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for C# source files and `.csproj` project files
+pub struct CSharpAnalyzer;
+
+impl FileAnalyzer for CSharpAnalyzer {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let mut analysis = Vec::new();
+
+        if is_project_file(file) {
+            if let Some(packages) = extract_modified_packages(&staged_file.diff) {
+                analysis.push(format!("Modified package references: {}", packages.join(", ")));
+            }
+            if has_target_framework_change(&staged_file.diff) {
+                analysis.push("Target framework has been modified".to_string());
+            }
+            return analysis;
+        }
+
+        if let Some(namespaces) = extract_modified_namespaces(&staged_file.diff) {
+            analysis.push(format!("Modified namespaces: {}", namespaces.join(", ")));
+        }
+
+        if let Some(types) = extract_modified_types(&staged_file.diff) {
+            analysis.push(format!("Modified classes: {}", types.join(", ")));
+        }
+
+        if let Some(methods) = extract_modified_methods(&staged_file.diff) {
+            analysis.push(format!("Modified methods: {}", methods.join(", ")));
+        }
+
+        if has_using_changes(&staged_file.diff) {
+            analysis.push("Using directives have been modified".to_string());
+        }
+
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "C# source file"
+    }
+
+    fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
+        let mut metadata = ProjectMetadata {
+            language: Some("C#".to_string()),
+            ..Default::default()
+        };
+
+        if is_project_file(file) {
+            self.extract_project_metadata(content, &mut metadata);
+        } else {
+            self.extract_cs_file_metadata(content, &mut metadata);
+        }
+
+        metadata
+    }
+}
+
+impl CSharpAnalyzer {
+    fn extract_project_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
+        metadata.build_system = Some("MSBuild".to_string());
+
+        let version_re = Regex::new(r"<Version>(.+?)</Version>").unwrap();
+        if let Some(cap) = version_re.captures(content) {
+            metadata.version = Some(cap[1].to_string());
+        }
+
+        let package_re = Regex::new(r#"<PackageReference\s+Include="([^"]+)""#).unwrap();
+        metadata.dependencies = package_re
+            .captures_iter(content)
+            .map(|cap| cap[1].to_string())
+            .collect();
+
+        let framework = if content.contains("Microsoft.NET.Sdk.Web")
+            || content.contains("Microsoft.AspNetCore")
+        {
+            "ASP.NET Core"
+        } else {
+            ".NET"
+        };
+        let target_re = Regex::new(r"<TargetFrameworks?>(.+?)</TargetFrameworks?>").unwrap();
+        metadata.framework = Some(match target_re.captures(content) {
+            Some(cap) => format!("{} ({})", framework, &cap[1]),
+            None => framework.to_string(),
+        });
+
+        if let Some(test_framework) = detect_test_framework(content) {
+            metadata.test_framework = Some(test_framework.to_string());
+        }
+    }
+
+    fn extract_cs_file_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
+        if content.contains("using Microsoft.AspNetCore") {
+            metadata.framework = Some("ASP.NET Core".to_string());
+        }
+        if let Some(test_framework) = detect_test_framework(content) {
+            metadata.test_framework = Some(test_framework.to_string());
+        }
+    }
+}
+
+fn is_project_file(file: &str) -> bool {
+    file.ends_with(".csproj")
+}
+
+/// Test frameworks by their namespace in sources or their package in project files
+fn detect_test_framework(content: &str) -> Option<&'static str> {
+    if content.contains("Xunit") || content.contains("xunit") {
+        Some("xUnit")
+    } else if content.contains("NUnit") {
+        Some("NUnit")
+    } else if content.contains("MSTest") || content.contains("VisualStudio.TestTools") {
+        Some("MSTest")
+    } else {
+        None
+    }
+}
+
+fn extract_modified_packages(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r#"(?m)^[+-]\s*<PackageReference\s+Include="([^"]+)""#).unwrap();
+    unique_names(&re, diff)
+}
+
+fn has_target_framework_change(diff: &str) -> bool {
+    let re = Regex::new(r"(?m)^[+-]\s*<TargetFrameworks?>").unwrap();
+    re.is_match(diff)
+}
+
+fn extract_modified_namespaces(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r"(?m)^[+-]\s*namespace\s+([\w.]+)").unwrap();
+    unique_names(&re, diff)
+}
+
+fn extract_modified_types(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r"(?m)^[+-]\s*(?:(?:public|private|protected|internal|static|abstract|sealed|partial|readonly)\s+)*(?:class|interface|struct|record|enum)\s+(\w+)",
+    )
+    .unwrap();
+    unique_names(&re, diff)
+}
+
+fn extract_modified_methods(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r"(?m)^[+-]\s*(?:(?:public|private|protected|internal|static|virtual|override|abstract|async|sealed|extern)\s+)+[\w<>\[\],.?]+\s+(\w+)\s*(?:<[^>]*>)?\s*\(",
+    )
+    .unwrap();
+    unique_names(&re, diff)
+}
+
+fn unique_names(re: &Regex, diff: &str) -> Option<Vec<String>> {
+    let mut names: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let name = cap[1].to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    if names.is_empty() {
+        None
+    } else {
+        Some(names)
+    }
+}
+
+fn has_using_changes(diff: &str) -> bool {
+    let re = Regex::new(r"(?m)^[+-]\s*using\s+[\w.]+\s*;").unwrap();
+    re.is_match(diff)
+}
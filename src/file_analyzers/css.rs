@@ -0,0 +1,347 @@
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for CSS, SCSS, and Less stylesheets and Tailwind configuration
+pub struct CssAnalyzer;
+
+/// Properties that move or size elements, matched by prefix
+const LAYOUT_PROPERTIES: &[&str] = &[
+    "display",
+    "position",
+    "top",
+    "right",
+    "bottom",
+    "left",
+    "inset",
+    "float",
+    "clear",
+    "width",
+    "height",
+    "min-",
+    "max-",
+    "margin",
+    "padding",
+    "gap",
+    "row-",
+    "column",
+    "grid",
+    "flex",
+    "align",
+    "justify",
+    "place-",
+    "order",
+    "overflow",
+    "z-index",
+    "aspect-ratio",
+    "box-sizing",
+];
+
+/// Properties that change how elements look without moving them, matched by prefix
+const THEME_PROPERTIES: &[&str] = &[
+    "color",
+    "background",
+    "border-color",
+    "border-radius",
+    "outline-color",
+    "box-shadow",
+    "text-shadow",
+    "font",
+    "letter-spacing",
+    "line-height",
+    "opacity",
+    "fill",
+    "stroke",
+    "filter",
+    "accent-color",
+    "caret-color",
+];
+
+impl FileAnalyzer for CssAnalyzer {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
+        if is_tailwind_config(file) {
+            return analyze_tailwind_config(&staged_file.diff);
+        }
+
+        let mut analysis = Vec::new();
+        let before = Stylesheet::parse(&staged_file.diff, '-');
+        let after = Stylesheet::parse(&staged_file.diff, '+');
+
+        let added: Vec<&String> = after
+            .selectors
+            .iter()
+            .filter(|selector| !before.selectors.contains(selector))
+            .collect();
+        let removed: Vec<&String> = before
+            .selectors
+            .iter()
+            .filter(|selector| !after.selectors.contains(selector))
+            .collect();
+        let mut modified: Vec<&String> = Vec::new();
+        for selector in before.modified_rules.iter().chain(&after.modified_rules) {
+            if !added.contains(&selector)
+                && !removed.contains(&selector)
+                && !modified.contains(&selector)
+            {
+                modified.push(selector);
+            }
+        }
+        for (label, selectors) in [
+            ("New selectors", &added),
+            ("Removed selectors", &removed),
+            ("Modified rules", &modified),
+        ] {
+            if !selectors.is_empty() {
+                let names: Vec<&str> = selectors.iter().map(|selector| selector.as_str()).collect();
+                analysis.push(format!("{}: {}", label, names.join(", ")));
+            }
+        }
+
+        let tokens = extract_modified_tokens(&staged_file.diff);
+        if let Some(tokens) = &tokens {
+            analysis.push(format!("Modified design tokens: {}", tokens.join(", ")));
+        }
+
+        let properties: Vec<&String> = before
+            .modified_properties
+            .iter()
+            .chain(&after.modified_properties)
+            .collect();
+        let mut categories = Vec::new();
+        if properties
+            .iter()
+            .any(|property| matches_any(property, LAYOUT_PROPERTIES))
+        {
+            categories.push("layout");
+        }
+        if tokens.is_some()
+            || properties
+                .iter()
+                .any(|property| matches_any(property, THEME_PROPERTIES))
+        {
+            categories.push("theming");
+        }
+        if !added.is_empty() {
+            categories.push("new component styles");
+        }
+        if !categories.is_empty() {
+            analysis.push(format!("Style changes: {}", categories.join(", ")));
+        }
+
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "Stylesheet"
+    }
+
+    fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
+        if is_tailwind_config(file) {
+            let plugin_re = Regex::new(r#"(?:require\(|from\s+)['"]([^'"]+)['"]"#).unwrap();
+            return ProjectMetadata {
+                language: Some("JavaScript".to_string()),
+                framework: Some("Tailwind CSS".to_string()),
+                plugins: plugin_re
+                    .captures_iter(content)
+                    .map(|cap| cap[1].to_string())
+                    .filter(|module| module != "tailwindcss")
+                    .collect(),
+                ..Default::default()
+            };
+        }
+
+        let language = if file.ends_with(".scss") {
+            "SCSS"
+        } else if file.ends_with(".less") {
+            "Less"
+        } else {
+            "CSS"
+        };
+        let framework = if content.contains("@tailwind") || content.contains("@apply") {
+            Some("Tailwind CSS".to_string())
+        } else if content.contains("bootstrap") {
+            Some("Bootstrap".to_string())
+        } else {
+            None
+        };
+        ProjectMetadata {
+            language: Some(language.to_string()),
+            framework,
+            ..Default::default()
+        }
+    }
+}
+
+pub(super) fn is_tailwind_config(file: &str) -> bool {
+    let name = file.rsplit('/').next().unwrap_or(file);
+    name.starts_with("tailwind.config.")
+}
+
+/// What one side of a diff shows of a stylesheet
+#[derive(Default)]
+struct Stylesheet {
+    selectors: Vec<String>,
+    /// Selectors of the rules whose properties change on this side
+    modified_rules: Vec<String>,
+    modified_properties: Vec<String>,
+}
+
+impl Stylesheet {
+    /// Parse the context lines and the lines on one side of a diff, following nested rules
+    fn parse(diff: &str, sign: char) -> Self {
+        let property_re = Regex::new(r"^((?:--)?[a-z][\w-]*)\s*:[^{]*[^,]$").unwrap();
+
+        let mut stylesheet = Stylesheet::default();
+        let mut rules: Vec<String> = Vec::new();
+        for line in diff.lines() {
+            if !(line.starts_with(' ') || line.starts_with(sign)) {
+                continue;
+            }
+            let changed = line.starts_with(sign);
+            let content = line[1..].trim();
+            if content.starts_with("@@") {
+                rules.clear();
+                continue;
+            }
+
+            if let Some(selector) = content.strip_suffix('{') {
+                let selector = selector.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !selector.starts_with('@') && !stylesheet.selectors.contains(&selector) {
+                    stylesheet.selectors.push(selector.clone());
+                }
+                rules.push(selector);
+            } else if content.starts_with('}') {
+                rules.pop();
+            } else if let Some(cap) = property_re.captures(content).filter(|_| changed) {
+                let property = cap[1].to_string();
+                if !stylesheet.modified_properties.contains(&property) {
+                    stylesheet.modified_properties.push(property);
+                }
+                if let Some(rule) = rules.iter().rev().find(|rule| !rule.starts_with('@')) {
+                    if !stylesheet.modified_rules.contains(rule) {
+                        stylesheet.modified_rules.push(rule.clone());
+                    }
+                }
+            }
+        }
+        stylesheet
+    }
+}
+
+fn matches_any(property: &str, prefixes: &[&str]) -> bool {
+    prefixes.iter().any(|prefix| property.starts_with(prefix))
+}
+
+/// Custom properties and SCSS or Less variables that are defined on added or removed lines
+fn extract_modified_tokens(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r"(?m)^[+-]\s*(--[\w-]+|\$[\w-]+|@[\w-]+)\s*:").unwrap();
+    unique_names(&re, diff)
+}
+
+fn analyze_tailwind_config(diff: &str) -> Vec<String> {
+    let key_re = Regex::new(r#"^['"]?([\w-]+)['"]?\s*:\s*(.*)$"#).unwrap();
+    let plugin_re = Regex::new(r#"(?m)^[+-].*?(?:require\(|from\s+)['"]([^'"]+)['"]"#).unwrap();
+
+    let mut theme_values: Vec<String> = Vec::new();
+    let mut settings: Vec<String> = Vec::new();
+    let mut content_changed = false;
+    // Keys of the objects and arrays enclosing the current line, with their indentation
+    let mut keys: Vec<(usize, String)> = Vec::new();
+    for line in diff.lines() {
+        let Some(first) = line.chars().next() else {
+            continue;
+        };
+        let text = &line[first.len_utf8()..];
+        if text.trim_start().starts_with("@@") {
+            keys.clear();
+            continue;
+        }
+        let indent = text.len() - text.trim_start().len();
+        let content = text.trim();
+        if content.is_empty() {
+            continue;
+        }
+        while keys
+            .last()
+            .is_some_and(|(key_indent, _)| *key_indent >= indent)
+        {
+            keys.pop();
+        }
+
+        let entry = key_re.captures(content);
+        // Keys that open an object or array are reported through the values inside them
+        let opens = entry.as_ref().is_some_and(|cap| {
+            let value = cap[2].trim();
+            value.starts_with('{') && !value.contains('}')
+                || value.starts_with('[') && !value.contains(']')
+        });
+        if first == '+' || first == '-' {
+            let mut path: Vec<&str> = keys
+                .iter()
+                .map(|(_, key)| key.as_str())
+                .filter(|key| !matches!(*key, "theme" | "extend"))
+                .collect();
+            if let Some(cap) = &entry {
+                path.push(cap.get(1).map_or("", |m| m.as_str()));
+            }
+            let in_theme = keys.iter().any(|(_, key)| key == "theme");
+            if keys.iter().any(|(_, key)| key == "content") || path.first() == Some(&"content") {
+                content_changed = true;
+            } else if keys.iter().any(|(_, key)| key == "plugins")
+                || path.first() == Some(&"plugins")
+            {
+                // Reported from the modules they come from
+            } else if !path.is_empty() && !opens {
+                let list = if in_theme {
+                    &mut theme_values
+                } else {
+                    &mut settings
+                };
+                let name = path.join(".");
+                if !list.contains(&name) {
+                    list.push(name);
+                }
+            }
+        }
+        if let Some(cap) = entry.filter(|_| opens) {
+            keys.push((indent, cap[1].to_string()));
+        }
+    }
+
+    let mut analysis = Vec::new();
+    if !theme_values.is_empty() {
+        analysis.push(format!(
+            "Modified Tailwind theme values: {}",
+            theme_values.join(", ")
+        ));
+    }
+    if !settings.is_empty() {
+        analysis.push(format!(
+            "Modified Tailwind settings: {}",
+            settings.join(", ")
+        ));
+    }
+    if let Some(plugins) = unique_names(&plugin_re, diff) {
+        analysis.push(format!("Modified Tailwind plugins: {}", plugins.join(", ")));
+    }
+    if content_changed {
+        analysis.push("Tailwind content paths have been modified".to_string());
+    }
+    analysis
+}
+
+fn unique_names(re: &Regex, diff: &str) -> Option<Vec<String>> {
+    let mut names: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let name = cap[1].to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    if names.is_empty() {
+        None
+    } else {
+        Some(names)
+    }
+}
@@ -0,0 +1,89 @@
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Most packages named in one line of the analysis; lockfile updates can touch hundreds
+const MAX_LISTED: usize = 10;
+
+/// Dependencies added, removed, and moved to another version between two states of a
+/// manifest or lockfile
+#[derive(Default)]
+pub struct DependencyChanges {
+    added: Vec<String>,
+    removed: Vec<String>,
+    upgraded: Vec<String>,
+    downgraded: Vec<String>,
+    /// Upgrades across a major version, or a minor version before 1.0
+    major: Vec<String>,
+}
+
+impl DependencyChanges {
+    /// Compare the versions of each package, as the version or range on each side
+    pub fn between(before: &BTreeMap<String, String>, after: &BTreeMap<String, String>) -> Self {
+        let mut changes = DependencyChanges::default();
+        for (name, version) in after {
+            match before.get(name) {
+                None => changes.added.push(format!("{} {}", name, version)),
+                Some(old) if old != version => {
+                    let change = format!("{} {} → {}", name, old, version);
+                    match (version_core(old), version_core(version)) {
+                        (Some(old), Some(new)) if new < old => changes.downgraded.push(change),
+                        (Some(old), Some(new)) => {
+                            if new[0] > old[0] || (old[0] == 0 && new[0] == 0 && new[1] > old[1]) {
+                                changes.major.push(change.clone());
+                            }
+                            changes.upgraded.push(change);
+                        }
+                        _ => changes.upgraded.push(change),
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, version) in before {
+            if !after.contains_key(name) {
+                changes.removed.push(format!("{} {}", name, version));
+            }
+        }
+        changes
+    }
+
+    pub fn describe(&self) -> Vec<String> {
+        let mut analysis = Vec::new();
+        for (label, packages) in [
+            ("Major version bumps (potentially breaking)", &self.major),
+            ("Added dependencies", &self.added),
+            ("Removed dependencies", &self.removed),
+            ("Upgraded dependencies", &self.upgraded),
+            ("Downgraded dependencies", &self.downgraded),
+        ] {
+            if !packages.is_empty() {
+                analysis.push(format!("{}: {}", label, list(packages)));
+            }
+        }
+        analysis
+    }
+}
+
+fn list(packages: &[String]) -> String {
+    if packages.len() <= MAX_LISTED {
+        packages.join(", ")
+    } else {
+        format!(
+            "{}, and {} more",
+            packages[..MAX_LISTED].join(", "),
+            packages.len() - MAX_LISTED
+        )
+    }
+}
+
+/// Major, minor, and patch numbers of a version or the lowest version of a range
+fn version_core(version: &str) -> Option<[u64; 3]> {
+    let re = Regex::new(r"(\d+)(?:\.(\d+))?(?:\.(\d+))?").unwrap();
+    let version = version.trim_start_matches(|c: char| !c.is_ascii_digit());
+    let cap = re.captures(version)?;
+    let part = |index: usize| {
+        cap.get(index)
+            .map_or(Some(0), |part| part.as_str().parse().ok())
+    };
+    Some([part(1)?, part(2)?, part(3)?])
+}
@@ -0,0 +1,317 @@
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for Dockerfiles and Containerfiles
+pub struct DockerfileAnalyzer;
+
+impl FileAnalyzer for DockerfileAnalyzer {
+    fn analyze(&self, _file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let mut analysis = Vec::new();
+
+        // Compare the instructions before and after the change, so that a line that only
+        // moved or gained a continuation isn't reported
+        let before = instructions(side_of_diff(&staged_file.diff, '-'));
+        let after = instructions(side_of_diff(&staged_file.diff, '+'));
+
+        let warnings = security_warnings(&before, &after);
+        if !warnings.is_empty() {
+            analysis.push(format!(
+                "Security-relevant changes: {}",
+                warnings.join(", ")
+            ));
+        }
+
+        let base_images = base_image_changes(&before, &after);
+        if !base_images.is_empty() {
+            analysis.push(format!("Base image changes: {}", base_images.join(", ")));
+        }
+
+        let (stages_added, stages_removed) = difference(&stages(&before), &stages(&after));
+        if !stages_added.is_empty() {
+            analysis.push(format!("Build stages added: {}", stages_added.join(", ")));
+        }
+        if !stages_removed.is_empty() {
+            analysis.push(format!(
+                "Build stages removed: {}",
+                stages_removed.join(", ")
+            ));
+        }
+        let copies = |instructions: &[Instruction]| arguments_of(instructions, "COPY", "--from=");
+        if copies(&before) != copies(&after) {
+            analysis.push("Multi-stage build copies have been modified".to_string());
+        }
+
+        let (ports_added, ports_removed) = difference(&ports(&before), &ports(&after));
+        if !ports_added.is_empty() {
+            analysis.push(format!("Newly exposed ports: {}", ports_added.join(", ")));
+        }
+        if !ports_removed.is_empty() {
+            analysis.push(format!(
+                "Ports no longer exposed: {}",
+                ports_removed.join(", ")
+            ));
+        }
+
+        let (packages_added, packages_removed) = difference(&packages(&before), &packages(&after));
+        if !packages_added.is_empty() {
+            analysis.push(format!("Added packages: {}", packages_added.join(", ")));
+        }
+        if !packages_removed.is_empty() {
+            analysis.push(format!("Removed packages: {}", packages_removed.join(", ")));
+        }
+
+        let users = |instructions: &[Instruction]| arguments_of(instructions, "USER", "");
+        if users(&before) != users(&after) {
+            analysis.push(format!(
+                "User changed: {} → {}",
+                users(&before).last().map_or("(default)", String::as_str),
+                users(&after).last().map_or("(default)", String::as_str)
+            ));
+        }
+        if permission_commands(&before) != permission_commands(&after) {
+            analysis.push("File ownership or permissions have been modified".to_string());
+        }
+
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "Dockerfile"
+    }
+
+    fn extract_metadata(&self, _file: &str, content: &str) -> ProjectMetadata {
+        let instructions = instructions(content.to_string());
+        ProjectMetadata {
+            build_system: Some("Docker".to_string()),
+            dependencies: base_images(&instructions)
+                .into_iter()
+                .map(|(image, _)| image)
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Whether a file is a Dockerfile, going by the usual names
+pub(super) fn is_dockerfile(file: &str) -> bool {
+    let name = file.rsplit('/').next().unwrap_or(file);
+    name == "Dockerfile"
+        || name == "Containerfile"
+        || name.starts_with("Dockerfile.")
+        || name.ends_with(".dockerfile")
+}
+
+/// An instruction with its continuation lines joined, such as `("RUN", "apt-get ...")`
+struct Instruction {
+    keyword: String,
+    arguments: String,
+}
+
+/// The file as it was (`-`) or is (`+`): context lines and the lines on that side
+fn side_of_diff(diff: &str, sign: char) -> String {
+    diff.lines()
+        .filter(|line| line.starts_with(' ') || line.starts_with(sign))
+        .map(|line| &line[1..])
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn instructions(text: String) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('#') || (line.is_empty() && current.is_empty()) {
+            continue;
+        }
+        match line.strip_suffix('\\') {
+            Some(continued) => {
+                current.push_str(continued);
+                current.push(' ');
+            }
+            None => {
+                current.push_str(line);
+                if let Some((keyword, arguments)) = current.trim().split_once(char::is_whitespace) {
+                    instructions.push(Instruction {
+                        keyword: keyword.to_uppercase(),
+                        arguments: arguments.trim().to_string(),
+                    });
+                }
+                current.clear();
+            }
+        }
+    }
+    instructions
+}
+
+/// Names on the second side but not the first, and on the first but not the second
+fn difference(before: &[String], after: &[String]) -> (Vec<String>, Vec<String>) {
+    let added = after
+        .iter()
+        .filter(|name| !before.contains(name))
+        .cloned()
+        .collect();
+    let removed = before
+        .iter()
+        .filter(|name| !after.contains(name))
+        .cloned()
+        .collect();
+    (added, removed)
+}
+
+/// The arguments of each instruction with a keyword, when they contain `filter`
+fn arguments_of(instructions: &[Instruction], keyword: &str, filter: &str) -> Vec<String> {
+    instructions
+        .iter()
+        .filter(|instruction| instruction.keyword == keyword)
+        .filter(|instruction| instruction.arguments.contains(filter))
+        .map(|instruction| instruction.arguments.clone())
+        .collect()
+}
+
+/// The image and stage name of each `FROM` instruction
+fn base_images(instructions: &[Instruction]) -> Vec<(String, Option<String>)> {
+    let re = Regex::new(r"(?i)^(?:--platform=\S+\s+)?(\S+)(?:\s+AS\s+(\S+))?").unwrap();
+    arguments_of(instructions, "FROM", "")
+        .iter()
+        .filter_map(|arguments| {
+            let cap = re.captures(arguments)?;
+            Some((
+                cap[1].to_string(),
+                cap.get(2).map(|m| m.as_str().to_string()),
+            ))
+        })
+        .collect()
+}
+
+fn stages(instructions: &[Instruction]) -> Vec<String> {
+    base_images(instructions)
+        .into_iter()
+        .filter_map(|(_, stage)| stage)
+        .collect()
+}
+
+/// Images replaced in a stage, matched by stage name, or by position when the number of
+/// stages is unchanged
+fn base_image_changes(before: &[Instruction], after: &[Instruction]) -> Vec<String> {
+    let before = base_images(before);
+    let after = base_images(after);
+    let mut changes = Vec::new();
+    for (index, (old_image, old_stage)) in before.iter().enumerate() {
+        let replacement = match old_stage {
+            Some(stage) => after
+                .iter()
+                .find(|(_, new_stage)| new_stage.as_ref() == Some(stage)),
+            None if before.len() == after.len() => after.get(index),
+            None => None,
+        };
+        if let Some((new_image, _)) = replacement.filter(|(image, _)| image != old_image) {
+            changes.push(format!("{} → {}", old_image, new_image));
+        }
+    }
+    changes
+}
+
+fn ports(instructions: &[Instruction]) -> Vec<String> {
+    arguments_of(instructions, "EXPOSE", "")
+        .iter()
+        .flat_map(|arguments| arguments.split_whitespace().map(str::to_string))
+        .collect()
+}
+
+/// Packages installed by `RUN` instructions with apt, apk, yum, dnf, or pip, without their
+/// version pins
+fn packages(instructions: &[Instruction]) -> Vec<String> {
+    let install_re = Regex::new(
+        r"^(?:apt-get|apt|yum|dnf|microdnf)\s+(?:\S+\s+)*?install\b|^apk\s+(?:\S+\s+)*?add\b|^pip3?\s+install\b",
+    )
+    .unwrap();
+    let mut packages = Vec::new();
+    for arguments in arguments_of(instructions, "RUN", "") {
+        for command in arguments.split("&&").flat_map(|command| command.split(';')) {
+            let command = command.trim();
+            let Some(install) = install_re.find(command) else {
+                continue;
+            };
+            for word in command[install.end()..].split_whitespace() {
+                if word.starts_with('-') || word.contains('$') || word == "\\" {
+                    continue;
+                }
+                let name = word
+                    .split(['=', '<', '>'])
+                    .next()
+                    .unwrap_or(word)
+                    .to_string();
+                if !name.is_empty() && !packages.contains(&name) {
+                    packages.push(name);
+                }
+            }
+        }
+    }
+    packages
+}
+
+/// `chmod` and `chown` commands, and `COPY` or `ADD` options that set ownership
+fn permission_commands(instructions: &[Instruction]) -> Vec<String> {
+    let re = Regex::new(r"\b(?:chmod|chown)\s+[^&;]+|--(?:chown|chmod)=\S+").unwrap();
+    instructions
+        .iter()
+        .flat_map(|instruction| re.find_iter(&instruction.arguments))
+        .map(|m| m.as_str().trim().to_string())
+        .collect()
+}
+
+fn security_warnings(before: &[Instruction], after: &[Instruction]) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let users_before = arguments_of(before, "USER", "");
+    let users_after = arguments_of(after, "USER", "");
+    if users_before != users_after {
+        match users_after.last().map(String::as_str) {
+            Some("root" | "0" | "0:0" | "root:root") => {
+                warnings.push("switches to the root user".to_string())
+            }
+            None if !users_before.is_empty() => {
+                warnings.push("no longer switches to a non-root user".to_string())
+            }
+            _ => {}
+        }
+    }
+
+    let remote_adds = |instructions: &[Instruction]| {
+        arguments_of(instructions, "ADD", "")
+            .into_iter()
+            .flat_map(|arguments| {
+                arguments
+                    .split_whitespace()
+                    .filter(|word| word.starts_with("http://") || word.starts_with("https://"))
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+    };
+    let (urls_added, _) = difference(&remote_adds(before), &remote_adds(after));
+    for url in urls_added {
+        warnings.push(format!("ADD of remote URL {}", url));
+    }
+
+    let runs_before = arguments_of(before, "RUN", "");
+    let runs_after = arguments_of(after, "RUN", "");
+    let new_runs: Vec<&String> = runs_after
+        .iter()
+        .filter(|run| !runs_before.contains(run))
+        .collect();
+    let pipe_re = Regex::new(r"\b(?:curl|wget)\b[^|;&]*\|\s*(?:sudo\s+)?(?:ba|z)?sh\b").unwrap();
+    if new_runs.iter().any(|run| pipe_re.is_match(run)) {
+        warnings.push("pipes a downloaded script into a shell".to_string());
+    }
+    if new_runs
+        .iter()
+        .any(|run| run.contains("chmod 777") || run.contains("chmod -R 777"))
+    {
+        warnings.push("makes files world-writable".to_string());
+    }
+
+    warnings
+}
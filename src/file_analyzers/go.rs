@@ -0,0 +1,179 @@
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for Go source files and `go.mod`
+pub struct GoAnalyzer;
+
+impl FileAnalyzer for GoAnalyzer {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let mut analysis = Vec::new();
+
+        if is_go_mod(file) {
+            if let Some(dependencies) = extract_modified_dependencies(&staged_file.diff) {
+                analysis.push(format!("Modified dependencies: {}", dependencies.join(", ")));
+            }
+            if has_go_version_change(&staged_file.diff) {
+                analysis.push("Go version directive has been modified".to_string());
+            }
+            return analysis;
+        }
+
+        if let Some(functions) = extract_exported_functions(&staged_file.diff) {
+            analysis.push(format!("Modified exported functions: {}", functions.join(", ")));
+        }
+
+        if let Some(structs) = extract_exported_types(&staged_file.diff, "struct") {
+            analysis.push(format!("Modified exported structs: {}", structs.join(", ")));
+        }
+
+        if let Some(interfaces) = extract_exported_types(&staged_file.diff, "interface") {
+            analysis.push(format!("Modified exported interfaces: {}", interfaces.join(", ")));
+        }
+
+        if has_build_tag_changes(&staged_file.diff) {
+            analysis.push("Build constraints have been modified".to_string());
+        }
+
+        if file.ends_with("_test.go") {
+            if let Some(cases) = extract_modified_test_cases(&staged_file.diff) {
+                analysis.push(format!("Modified table-driven test cases: {}", cases.join(", ")));
+            }
+        }
+
+        if has_import_changes(&staged_file.diff) {
+            analysis.push("Import statements have been modified".to_string());
+        }
+
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "Go source file"
+    }
+
+    fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
+        let mut metadata = ProjectMetadata {
+            language: Some("Go".to_string()),
+            ..Default::default()
+        };
+
+        if is_go_mod(file) {
+            self.extract_go_mod_metadata(content, &mut metadata);
+        } else {
+            self.extract_go_file_metadata(content, &mut metadata);
+        }
+
+        metadata
+    }
+}
+
+impl GoAnalyzer {
+    fn extract_go_mod_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
+        metadata.build_system = Some("Go modules".to_string());
+        metadata.dependencies = go_mod_requirements(content);
+        if let Some(framework) = detect_framework(content) {
+            metadata.framework = Some(framework.to_string());
+        }
+        if content.contains("github.com/stretchr/testify") {
+            metadata.test_framework = Some("testify".to_string());
+        }
+    }
+
+    fn extract_go_file_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
+        if let Some(framework) = detect_framework(content) {
+            metadata.framework = Some(framework.to_string());
+        }
+        if content.contains("github.com/stretchr/testify") {
+            metadata.test_framework = Some("testify".to_string());
+        } else if content.contains("\"testing\"") {
+            metadata.test_framework = Some("built-in".to_string());
+        }
+    }
+}
+
+fn is_go_mod(file: &str) -> bool {
+    file == "go.mod" || file.ends_with("/go.mod")
+}
+
+fn detect_framework(content: &str) -> Option<&'static str> {
+    if content.contains("github.com/gin-gonic/gin") {
+        Some("Gin")
+    } else if content.contains("github.com/labstack/echo") {
+        Some("Echo")
+    } else if content.contains("github.com/gofiber/fiber") {
+        Some("Fiber")
+    } else if content.contains("github.com/go-chi/chi") {
+        Some("Chi")
+    } else {
+        None
+    }
+}
+
+/// Module paths in the `require` directives of a `go.mod` file
+fn go_mod_requirements(content: &str) -> Vec<String> {
+    let single_re = Regex::new(r"(?m)^require\s+(\S+)\s+v\S+").unwrap();
+    let block_re = Regex::new(r"(?s)require\s*\((.*?)\)").unwrap();
+    let entry_re = Regex::new(r"(?m)^\s*(\S+)\s+v\S+").unwrap();
+
+    let mut requirements: Vec<String> = single_re
+        .captures_iter(content)
+        .map(|cap| cap[1].to_string())
+        .collect();
+    for block in block_re.captures_iter(content) {
+        requirements.extend(entry_re.captures_iter(&block[1]).map(|cap| cap[1].to_string()));
+    }
+    requirements
+}
+
+fn extract_modified_dependencies(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r"(?m)^[+-]\s*(?:require\s+)?([\w.-]+\.[\w.-]+/\S+)\s+v\S+").unwrap();
+    unique_names(&re, diff)
+}
+
+fn has_go_version_change(diff: &str) -> bool {
+    let re = Regex::new(r"(?m)^[+-]\s*(go|toolchain)\s+\S+").unwrap();
+    re.is_match(diff)
+}
+
+/// Exported functions and methods, whose names start with an upper-case letter
+fn extract_exported_functions(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r"(?m)^[+-]\s*func\s+(?:\([^)]*\)\s*)?([A-Z]\w*)").unwrap();
+    unique_names(&re, diff)
+}
+
+fn extract_exported_types(diff: &str, kind: &str) -> Option<Vec<String>> {
+    let re = Regex::new(&format!(r"(?m)^[+-]\s*(?:type\s+)?([A-Z]\w*)\s+{}\b", kind)).unwrap();
+    unique_names(&re, diff)
+}
+
+fn extract_modified_test_cases(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r#"(?m)^[+-]\s*\{\s*(?:name:\s*)?"([^"]+)""#).unwrap();
+    unique_names(&re, diff)
+}
+
+fn unique_names(re: &Regex, diff: &str) -> Option<Vec<String>> {
+    let mut names: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let name = cap[1].to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    if names.is_empty() {
+        None
+    } else {
+        Some(names)
+    }
+}
+
+fn has_build_tag_changes(diff: &str) -> bool {
+    let re = Regex::new(r"(?m)^[+-]\s*//(?:go:build\s|\s*\+build\s)").unwrap();
+    re.is_match(diff)
+}
+
+fn has_import_changes(diff: &str) -> bool {
+    let re = Regex::new(r#"(?m)^[+-]\s*(?:import\s|"[\w./-]+"\s*$)"#).unwrap();
+    re.is_match(diff)
+}
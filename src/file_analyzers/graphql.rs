@@ -0,0 +1,262 @@
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for GraphQL schema files
+pub struct GraphQlAnalyzer;
+
+impl FileAnalyzer for GraphQlAnalyzer {
+    fn analyze(&self, _file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let before = Schema::parse(&staged_file.diff, '-');
+        let after = Schema::parse(&staged_file.diff, '+');
+        let mut analysis = Vec::new();
+
+        let breaking = breaking_changes(&before, &after);
+        if !breaking.is_empty() {
+            analysis.push(format!("Breaking changes: {}", breaking.join(", ")));
+        }
+
+        let report = |analysis: &mut Vec<String>, label: &str, names: Vec<String>| {
+            if !names.is_empty() {
+                analysis.push(format!("{}: {}", label, names.join(", ")));
+            }
+        };
+        let type_names = |schema: &Schema| -> Vec<String> {
+            schema.types.iter().map(|(_, name)| name.clone()).collect()
+        };
+        let (added, removed) = difference(&type_names(&before), &type_names(&after));
+        report(&mut analysis, "Types added", added);
+        report(&mut analysis, "Types removed", removed);
+
+        let field_names = |schema: &Schema| -> Vec<String> {
+            schema
+                .fields
+                .iter()
+                .map(|field| field.qualified_name())
+                .collect()
+        };
+        let (added, removed) = difference(&field_names(&before), &field_names(&after));
+        report(&mut analysis, "Fields added", added);
+        report(&mut analysis, "Fields removed", removed);
+
+        let deprecated: Vec<String> = after
+            .deprecations
+            .iter()
+            .filter(|deprecation| !before.deprecations.contains(deprecation))
+            .cloned()
+            .collect();
+        report(&mut analysis, "Deprecated", deprecated);
+
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "GraphQL schema"
+    }
+
+    fn extract_metadata(&self, _file: &str, content: &str) -> ProjectMetadata {
+        let federation_re = Regex::new(r"@(?:key|link)\s*\(").unwrap();
+        ProjectMetadata {
+            language: Some("GraphQL".to_string()),
+            framework: federation_re
+                .is_match(content)
+                .then(|| "Apollo Federation".to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+struct Field {
+    /// The type the field belongs to
+    parent: String,
+    /// Whether the parent is an input type, where a required field breaks existing callers
+    input: bool,
+    name: String,
+    /// The declared type, such as `[Order!]!`, or `None` for an enum value
+    field_type: Option<String>,
+}
+
+impl Field {
+    fn qualified_name(&self) -> String {
+        format!("{}.{}", self.parent, self.name)
+    }
+}
+
+/// What one side of a diff shows of a schema
+#[derive(Default)]
+struct Schema {
+    /// Each type with its kind, such as `("input", "OrderFilter")`
+    types: Vec<(String, String)>,
+    fields: Vec<Field>,
+    /// Deprecated fields and enum values, with the reason when one is given
+    deprecations: Vec<String>,
+}
+
+impl Schema {
+    /// Parse the context lines and the lines on one side of a diff
+    ///
+    /// A hunk that starts inside a type takes it from the hunk header, where Git shows the
+    /// line that opened it.
+    fn parse(diff: &str, sign: char) -> Self {
+        let type_re = Regex::new(
+            r"^(?:extend\s+)?(type|input|interface|enum|union|scalar)\s+(\w+)[^{]*(\{)?",
+        )
+        .unwrap();
+        let field_re = Regex::new(r"^(\w+)\s*(?:\([^)]*\))?\s*:\s*([\w!\[\]]+)").unwrap();
+        let enum_value_re = Regex::new(r"^([A-Z_][A-Z0-9_]*)\b").unwrap();
+        let deprecated_re =
+            Regex::new(r#"@deprecated(?:\s*\(\s*reason:\s*"([^"]*)"\s*\))?"#).unwrap();
+
+        let mut schema = Schema::default();
+        let mut scope: Option<(String, String)> = None;
+        for line in diff.lines() {
+            if !(line.starts_with(' ') || line.starts_with(sign)) {
+                continue;
+            }
+            let content = line[1..].trim();
+            if let Some(header) = content.strip_prefix("@@") {
+                let context = header.rsplit("@@").next().unwrap_or("").trim();
+                scope = type_re
+                    .captures(context)
+                    .filter(|cap| cap.get(3).is_some())
+                    .map(|cap| (cap[1].to_string(), cap[2].to_string()));
+                continue;
+            }
+            let content = content.split('#').next().unwrap_or("").trim();
+
+            if let Some(cap) = type_re.captures(content) {
+                let declaration = (cap[1].to_string(), cap[2].to_string());
+                if !content.starts_with("extend") && !schema.types.contains(&declaration) {
+                    schema.types.push(declaration.clone());
+                }
+                if cap.get(3).is_some() && !content.ends_with('}') {
+                    scope = Some(declaration);
+                }
+                continue;
+            }
+            if content.starts_with('}') {
+                scope = None;
+                continue;
+            }
+
+            let Some((kind, parent)) = &scope else {
+                continue;
+            };
+            let field = if kind == "enum" {
+                enum_value_re.captures(content).map(|cap| Field {
+                    parent: parent.clone(),
+                    input: false,
+                    name: cap[1].to_string(),
+                    field_type: None,
+                })
+            } else {
+                field_re.captures(content).map(|cap| Field {
+                    parent: parent.clone(),
+                    input: kind == "input",
+                    name: cap[1].to_string(),
+                    field_type: Some(cap[2].to_string()),
+                })
+            };
+            let Some(field) = field else {
+                continue;
+            };
+            if let Some(cap) = deprecated_re.captures(content) {
+                schema.deprecations.push(match cap.get(1) {
+                    Some(reason) => format!("{} ({})", field.qualified_name(), reason.as_str()),
+                    None => field.qualified_name(),
+                });
+            }
+            schema.fields.push(field);
+        }
+        schema
+    }
+}
+
+fn difference(before: &[String], after: &[String]) -> (Vec<String>, Vec<String>) {
+    let added = after
+        .iter()
+        .filter(|name| !before.contains(name))
+        .cloned()
+        .collect();
+    let removed = before
+        .iter()
+        .filter(|name| !after.contains(name))
+        .cloned()
+        .collect();
+    (added, removed)
+}
+
+/// Changes that can fail queries or mutations written against the old schema
+fn breaking_changes(before: &Schema, after: &Schema) -> Vec<String> {
+    let mut breaking = Vec::new();
+
+    let mut removed_types = Vec::new();
+    for (kind, name) in &before.types {
+        if !after.types.iter().any(|(_, after_name)| after_name == name) {
+            breaking.push(format!("{} {} removed", kind, name));
+            removed_types.push(name);
+        }
+    }
+
+    for old in before
+        .fields
+        .iter()
+        .filter(|field| !removed_types.contains(&&field.parent))
+    {
+        let Some(new) = after
+            .fields
+            .iter()
+            .find(|new| new.parent == old.parent && new.name == old.name)
+        else {
+            breaking.push(format!("{} removed", old.qualified_name()));
+            continue;
+        };
+        let (Some(old_type), Some(new_type)) = (&old.field_type, &new.field_type) else {
+            continue;
+        };
+        if old_type == new_type {
+            continue;
+        }
+        if old_type.replace('!', "") != new_type.replace('!', "") {
+            breaking.push(format!(
+                "{} type changed ({} → {})",
+                old.qualified_name(),
+                old_type,
+                new_type
+            ));
+        } else if is_looser(old_type, new_type) != new.input {
+            // Clients may not expect nulls they never got, and callers may not send inputs
+            // they never had to
+            breaking.push(format!(
+                "{} nullability changed ({} → {})",
+                old.qualified_name(),
+                old_type,
+                new_type
+            ));
+        }
+    }
+
+    // A new required input field breaks every caller that doesn't send it
+    for new in after.fields.iter().filter(|field| field.input) {
+        let required = new
+            .field_type
+            .as_deref()
+            .is_some_and(|ty| ty.ends_with('!'));
+        let existed = before
+            .fields
+            .iter()
+            .any(|old| old.parent == new.parent && old.name == new.name);
+        let new_type = before.types.iter().all(|(_, name)| *name != new.parent)
+            && after.types.iter().any(|(_, name)| *name == new.parent);
+        if required && !existed && !new_type {
+            breaking.push(format!("required input {} added", new.qualified_name()));
+        }
+    }
+
+    breaking
+}
+
+/// Whether a type allows nulls somewhere the other didn't, such as `String!` → `String`
+fn is_looser(old_type: &str, new_type: &str) -> bool {
+    new_type.matches('!').count() < old_type.matches('!').count()
+}
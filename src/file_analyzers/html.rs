@@ -0,0 +1,235 @@
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for HTML and server-side templates in Jinja, Tera, Askama, Handlebars, and ERB
+pub struct HtmlAnalyzer;
+
+/// Extensions of the files this analyzer handles
+pub(super) const EXTENSIONS: &[&str] = &[
+    ".html",
+    ".htm",
+    ".erb",
+    ".hbs",
+    ".handlebars",
+    ".mustache",
+    ".jinja",
+    ".jinja2",
+    ".j2",
+    ".njk",
+    ".tera",
+];
+
+impl FileAnalyzer for HtmlAnalyzer {
+    fn analyze(&self, _file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let mut analysis = Vec::new();
+        let diff = &staged_file.diff;
+
+        if let Some(templates) = extract_modified_parent_templates(diff) {
+            analysis.push(format!(
+                "Modified parent templates: {}",
+                templates.join(", ")
+            ));
+        }
+
+        if let Some(blocks) = extract_modified_blocks(diff) {
+            analysis.push(format!("Modified blocks: {}", blocks.join(", ")));
+        }
+
+        if let Some(partials) = extract_modified_partials(diff) {
+            analysis.push(format!(
+                "Modified includes and partials: {}",
+                partials.join(", ")
+            ));
+        }
+
+        if let Some(forms) = extract_modified_forms(diff) {
+            analysis.push(format!("Modified forms: {}", forms.join(", ")));
+        }
+
+        if let Some(routes) = extract_modified_routes(diff) {
+            analysis.push(format!("Modified route references: {}", routes.join(", ")));
+        }
+
+        if let Some(assets) = extract_modified_assets(diff) {
+            analysis.push(format!(
+                "Modified scripts and stylesheets: {}",
+                assets.join(", ")
+            ));
+        }
+
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "HTML template"
+    }
+
+    fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
+        let framework = if file.ends_with(".erb") {
+            Some("ERB")
+        } else if [".hbs", ".handlebars", ".mustache"]
+            .iter()
+            .any(|extension| file.ends_with(extension))
+        {
+            Some("Handlebars")
+        } else if file.ends_with(".tera") {
+            Some("Tera")
+        } else if file.ends_with(".njk") {
+            Some("Nunjucks")
+        } else if [".jinja", ".jinja2", ".j2"]
+            .iter()
+            .any(|extension| file.ends_with(extension))
+        {
+            Some("Jinja")
+        } else if content.contains("{%") {
+            // Jinja, Tera, and Askama share this syntax, and all use plain `.html` files
+            Some("Jinja-style templates")
+        } else if content.contains("{{#") || content.contains("{{>") {
+            Some("Handlebars")
+        } else {
+            None
+        };
+        ProjectMetadata {
+            language: Some("HTML".to_string()),
+            framework: framework.map(str::to_string),
+            ..Default::default()
+        }
+    }
+}
+
+fn extract_modified_parent_templates(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r#"(?m)^[+-].*\{%-?\s*extends\s+["']([^"']+)["']"#).unwrap();
+    unique_names(&re, diff)
+}
+
+/// Named blocks: `{% block %}` in Jinja-style templates, inline partials in Handlebars, and
+/// `content_for` and `yield` in ERB
+fn extract_modified_blocks(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r#"(?m)^[+-].*?(?:\{%-?\s*block\s+(\w+)|\{\{#\*inline\s+["']([^"']+)["']|(?:content_for|yield)[\s(]+:(\w+))"#,
+    )
+    .unwrap();
+    unique_captures(&re, diff)
+}
+
+fn extract_modified_partials(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r#"(?m)^[+-].*?(?:\{%-?\s*(?:include|import|from)\s+["']([^"']+)["']|\{\{#?>\s*([\w./-]+)|render[\s(]+(?:partial:\s*)?["']([^"']+)["'])"#,
+    )
+    .unwrap();
+    unique_captures(&re, diff)
+}
+
+/// Forms as their method and action, such as `POST /login`
+fn extract_modified_forms(diff: &str) -> Option<Vec<String>> {
+    let form_re = Regex::new(r"(?mi)^[+-].*?<form\b([^>]*)").unwrap();
+    let action_re = Regex::new(r#"(?i)\baction=(?:"([^"]*)"|'([^']*)')"#).unwrap();
+    let method_re = Regex::new(r#"(?i)\bmethod=["']([^"']*)["']"#).unwrap();
+
+    let mut forms: Vec<String> = Vec::new();
+    for cap in form_re.captures_iter(diff) {
+        let attributes = &cap[1];
+        let action = action_re
+            .captures(attributes)
+            .and_then(|action| action.iter().skip(1).flatten().next())
+            .map_or("(current page)".to_string(), |action| {
+                action.as_str().to_string()
+            });
+        let method = method_re
+            .captures(attributes)
+            .map_or("GET".to_string(), |method| method[1].to_uppercase());
+        let form = format!("{} {}", method, action);
+        if !forms.contains(&form) {
+            forms.push(form);
+        }
+    }
+
+    if forms.is_empty() {
+        None
+    } else {
+        Some(forms)
+    }
+}
+
+/// Named routes such as `url_for('login')`, `{% url 'login' %}`, and `<%= login_path %>`, and
+/// site-relative links
+fn extract_modified_routes(diff: &str) -> Option<Vec<String>> {
+    let named_re = Regex::new(
+        r#"(?:url_for\(\s*["']([^"']+)["']|\{%-?\s*url\s+["']([^"']+)["']|<%=?[^%]*?\b(\w+_(?:path|url))\b)"#,
+    )
+    .unwrap();
+    let link_re =
+        Regex::new(r#"\b(?:href|action|hx-(?:get|post|put|patch|delete))=["'](/[^"'{<]*)["']"#)
+            .unwrap();
+
+    let mut routes: Vec<String> = Vec::new();
+    for line in diff
+        .lines()
+        .filter(|line| line.starts_with('+') || line.starts_with('-'))
+    {
+        let names = named_re.captures_iter(line).filter_map(|cap| {
+            cap.iter()
+                .skip(1)
+                .flatten()
+                .next()
+                .map(|name| name.as_str().to_string())
+        });
+        let links = link_re.captures_iter(line).map(|cap| cap[1].to_string());
+        for route in names.chain(links) {
+            if !routes.contains(&route) {
+                routes.push(route);
+            }
+        }
+    }
+
+    if routes.is_empty() {
+        None
+    } else {
+        Some(routes)
+    }
+}
+
+fn extract_modified_assets(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r#"(?mi)^[+-].*?(?:<script\b[^>]*\bsrc=["']([^"']+)["']|<link\b[^>]*\bhref=["']([^"']+\.css[^"']*)["'])"#,
+    )
+    .unwrap();
+    unique_captures(&re, diff)
+}
+
+fn unique_names(re: &Regex, diff: &str) -> Option<Vec<String>> {
+    let mut names: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let name = cap[1].to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    if names.is_empty() {
+        None
+    } else {
+        Some(names)
+    }
+}
+
+/// Like `unique_names`, for patterns with a group for each dialect
+fn unique_captures(re: &Regex, diff: &str) -> Option<Vec<String>> {
+    let mut names: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let Some(name) = cap.iter().skip(1).flatten().next() else {
+            continue;
+        };
+        let name = name.as_str().to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    if names.is_empty() {
+        None
+    } else {
+        Some(names)
+    }
+}
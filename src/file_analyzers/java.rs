@@ -6,9 +6,20 @@ use std::collections::HashSet;
 pub struct JavaAnalyzer;
 
 impl FileAnalyzer for JavaAnalyzer {
-    fn analyze(&self, _file: &str, staged_file: &StagedFile) -> Vec<String> {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
         let mut analysis = Vec::new();
 
+        if is_pom(file) {
+            let (dependencies, plugins) = extract_modified_maven_artifacts(&staged_file.diff);
+            if !dependencies.is_empty() {
+                analysis.push(format!("Modified dependencies: {}", dependencies.join(", ")));
+            }
+            if !plugins.is_empty() {
+                analysis.push(format!("Modified plugins: {}", plugins.join(", ")));
+            }
+            return analysis;
+        }
+
         if let Some(classes) = extract_modified_classes(&staged_file.diff) {
             analysis.push(format!("Modified classes: {}", classes.join(", ")));
         }
@@ -17,6 +28,14 @@ impl FileAnalyzer for JavaAnalyzer {
             analysis.push(format!("Modified methods: {}", methods.join(", ")));
         }
 
+        if let Some(annotations) = extract_modified_annotations(&staged_file.diff) {
+            analysis.push(format!("Modified annotations: {}", annotations.join(", ")));
+        }
+
+        if let Some(changes) = extract_visibility_changes(&staged_file.diff) {
+            analysis.push(format!("Visibility changed: {}", changes.join(", ")));
+        }
+
         if has_import_changes(&staged_file.diff) {
             analysis.push("Import statements have been modified".to_string());
         }
@@ -29,10 +48,12 @@ impl FileAnalyzer for JavaAnalyzer {
     }
 
     fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
-        let mut metadata = ProjectMetadata::default();
-        metadata.language = Some("Java".to_string());
+        let mut metadata = ProjectMetadata {
+            language: Some("Java".to_string()),
+            ..Default::default()
+        };
 
-        if file == "pom.xml" {
+        if is_pom(file) {
             self.extract_maven_metadata(content, &mut metadata);
         } else if file == "build.gradle" {
             self.extract_gradle_metadata(content, &mut metadata);
@@ -57,6 +78,20 @@ impl JavaAnalyzer {
         for cap in dependency_re.captures_iter(content) {
             metadata.dependencies.push(format!("{}:{}", &cap[1], &cap[2]));
         }
+
+        let plugin_re = Regex::new(r"(?s)<plugin>.*?<artifactId>(.+?)</artifactId>").unwrap();
+        for cap in plugin_re.captures_iter(content) {
+            metadata.plugins.push(cap[1].to_string());
+        }
+
+        if content.contains("<groupId>org.springframework") {
+            metadata.framework = Some("Spring".to_string());
+        }
+        if content.contains("<groupId>org.junit") || content.contains("<artifactId>junit</artifactId>") {
+            metadata.test_framework = Some("JUnit".to_string());
+        } else if content.contains("<groupId>org.testng</groupId>") {
+            metadata.test_framework = Some("TestNG".to_string());
+        }
     }
 
     fn extract_gradle_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
@@ -88,6 +123,52 @@ impl JavaAnalyzer {
     }
 }
 
+fn is_pom(file: &str) -> bool {
+    file == "pom.xml" || file.ends_with("/pom.xml")
+}
+
+/// Artifacts of the `<dependency>` and `<plugin>` blocks a `pom.xml` diff touches, including
+/// blocks whose `<artifactId>` is only in the surrounding context
+fn extract_modified_maven_artifacts(diff: &str) -> (Vec<String>, Vec<String>) {
+    let artifact_re = Regex::new(r"<artifactId>(.+?)</artifactId>").unwrap();
+    let mut dependencies = Vec::new();
+    let mut plugins = Vec::new();
+
+    let mut block: Option<&str> = None;
+    let mut artifact: Option<String> = None;
+    let mut changed = false;
+    for line in diff.lines() {
+        let body = line.get(1..).unwrap_or_default().trim();
+        if body == "<dependency>" || body == "<plugin>" {
+            block = Some(if body == "<dependency>" { "dependency" } else { "plugin" });
+            artifact = None;
+            changed = false;
+        }
+        if block.is_none() {
+            continue;
+        }
+        changed |= line.starts_with('+') || line.starts_with('-');
+        if artifact.is_none() {
+            artifact = artifact_re.captures(body).map(|cap| cap[1].to_string());
+        }
+        if body == "</dependency>" || body == "</plugin>" {
+            if let Some(name) = artifact.take().filter(|_| changed) {
+                let names = if block == Some("plugin") {
+                    &mut plugins
+                } else {
+                    &mut dependencies
+                };
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            block = None;
+        }
+    }
+
+    (dependencies, plugins)
+}
+
 fn extract_modified_classes(diff: &str) -> Option<Vec<String>> {
     let re =
         Regex::new(r"(?m)^[+-]\s*(public\s+|private\s+)?(class|interface|enum)\s+(\w+)").unwrap();
@@ -122,3 +203,69 @@ fn has_import_changes(diff: &str) -> bool {
     let re = Regex::new(r"(?m)^[+-]\s*import\s+").unwrap();
     re.is_match(diff)
 }
+
+fn extract_modified_annotations(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r"(?m)^[+-]\s*@(\w+)").unwrap();
+    let mut annotations: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        // `@interface` declares an annotation type rather than applying one
+        let annotation = format!("@{}", &cap[1]);
+        if &cap[1] != "interface" && !annotations.contains(&annotation) {
+            annotations.push(annotation);
+        }
+    }
+
+    if annotations.is_empty() {
+        None
+    } else {
+        Some(annotations)
+    }
+}
+
+/// Declarations removed with one access modifier and added back with another, such as a
+/// method made public
+fn extract_visibility_changes(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r"(?m)^([+-])\s*(public\s+|protected\s+|private\s+)?(?:(?:static|final|abstract|synchronized|default|sealed)\s+)*(?:(?:class|interface|enum|record)\s+(\w+)|[\w<>\[\],.? ]+?\s+(\w+)\s*\()",
+    )
+    .unwrap();
+
+    let mut removed: Vec<(String, &str)> = Vec::new();
+    let mut added: Vec<(String, &str)> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let Some(name) = cap.get(3).or_else(|| cap.get(4)) else {
+            continue;
+        };
+        // Calls in statements look like declarations to the pattern above
+        let statement = cap[0][1..].trim_start();
+        if ["return ", "new ", "throw ", "else "]
+            .iter()
+            .any(|keyword| statement.starts_with(keyword))
+        {
+            continue;
+        }
+        let visibility = cap.get(2).map_or("package-private", |m| m.as_str().trim());
+        let entry = (name.as_str().to_string(), visibility);
+        if &cap[1] == "-" {
+            removed.push(entry);
+        } else {
+            added.push(entry);
+        }
+    }
+
+    let mut changes = Vec::new();
+    for (name, before) in &removed {
+        if let Some((_, after)) = added.iter().find(|(added_name, _)| added_name == name) {
+            let change = format!("{} ({} → {})", name, before, after);
+            if before != after && !changes.contains(&change) {
+                changes.push(change);
+            }
+        }
+    }
+
+    if changes.is_empty() {
+        None
+    } else {
+        Some(changes)
+    }
+}
@@ -6,28 +6,64 @@ use std::collections::HashSet;
 pub struct JavaScriptAnalyzer;
 
 impl FileAnalyzer for JavaScriptAnalyzer {
-    fn analyze(&self, _file: &str, staged_file: &StagedFile) -> Vec<String> {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
         let mut analysis = Vec::new();
 
         if let Some(functions) = extract_modified_functions(&staged_file.diff) {
             analysis.push(format!("Modified functions: {}", functions.join(", ")));
         }
 
+        let exports = DeclarationChanges::from_diff(&staged_file.diff, EXPORTED_FUNCTION_PATTERN);
+        if !exports.added.is_empty() {
+            analysis.push(format!("Added exported functions: {}", exports.added.join(", ")));
+        }
+        if !exports.removed.is_empty() {
+            analysis.push(format!(
+                "Removed exported functions: {}",
+                exports.removed.join(", ")
+            ));
+        }
+        if !exports.changed.is_empty() {
+            analysis.push(format!(
+                "Changed exported functions: {}",
+                exports.changed.join(", ")
+            ));
+        }
+
         if let Some(classes) = extract_modified_classes(&staged_file.diff) {
             analysis.push(format!("Modified classes: {}", classes.join(", ")));
         }
 
+        if let Some(types) = extract_modified_types(&staged_file.diff) {
+            analysis.push(format!("Modified types and interfaces: {}", types.join(", ")));
+        }
+
         if has_import_changes(&staged_file.diff) {
             analysis.push("Import statements have been modified".to_string());
+            let imports = DeclarationChanges::from_diff(&staged_file.diff, IMPORT_SOURCE_PATTERN);
+            if !imports.added.is_empty() {
+                analysis.push(format!("Added imports from: {}", imports.added.join(", ")));
+            }
+            if !imports.removed.is_empty() {
+                analysis.push(format!("Removed imports from: {}", imports.removed.join(", ")));
+            }
         }
 
-        if let Some(components) = extract_modified_react_components(&staged_file.diff) {
+        if let Some(components) = extract_modified_react_components(file, &staged_file.diff) {
             analysis.push(format!(
                 "Modified React components: {}",
                 components.join(", ")
             ));
         }
 
+        if let Some(hooks) = extract_modified_hooks(&staged_file.diff) {
+            analysis.push(format!("Modified custom hooks: {}", hooks.join(", ")));
+        }
+
+        if let Some(hooks) = extract_hook_calls(&staged_file.diff) {
+            analysis.push(format!("Modified hook calls: {}", hooks.join(", ")));
+        }
+
         analysis
     }
 
@@ -36,8 +72,15 @@ impl FileAnalyzer for JavaScriptAnalyzer {
     }
 
     fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
-        let mut metadata = ProjectMetadata::default();
-        metadata.language = Some(if file.ends_with(".ts") { "TypeScript" } else { "JavaScript" }.to_string());
+        let language = if is_typescript(file) {
+            "TypeScript"
+        } else {
+            "JavaScript"
+        };
+        let mut metadata = ProjectMetadata {
+            language: Some(language.to_string()),
+            ..Default::default()
+        };
 
         if file == "package.json" {
             self.extract_package_json_metadata(content, &mut metadata);
@@ -86,6 +129,55 @@ impl JavaScriptAnalyzer {
     }
 }
 
+fn is_typescript(file: &str) -> bool {
+    [".ts", ".tsx", ".mts", ".cts"]
+        .iter()
+        .any(|extension| file.ends_with(extension))
+}
+
+/// Files whose function components return JSX
+fn is_jsx(file: &str) -> bool {
+    file.ends_with(".jsx") || file.ends_with(".tsx")
+}
+
+/// Exported function declarations and arrow functions: the sign and the name
+const EXPORTED_FUNCTION_PATTERN: &str = r"(?m)^([+-])\s*export\s+(?:default\s+)?(?:(?:async\s+)?function\*?\s+(\w+)|const\s+(\w+)(?:\s*:\s*[^=]+)?\s*=\s*(?:async\s+)?(?:\([^)]*\)|\w+)\s*(?::\s*[^=]+)?=>)";
+
+/// Modules imported, required, or re-exported from: the sign and the module
+const IMPORT_SOURCE_PATTERN: &str = r#"(?m)^([+-])\s*(?:(?:import|export)\b[^'"]*?from\s*|import\s*|.*\brequire\(\s*)['"]([^'"]+)['"]"#;
+
+/// Names declared on added lines only, removed lines only, and both
+struct DeclarationChanges {
+    added: Vec<String>,
+    removed: Vec<String>,
+    changed: Vec<String>,
+}
+
+impl DeclarationChanges {
+    /// Sort the names a pattern captures after the sign into the added, removed, and
+    /// changed lists
+    fn from_diff(diff: &str, pattern: &str) -> Self {
+        let re = Regex::new(pattern).unwrap();
+        let mut plus: Vec<String> = Vec::new();
+        let mut minus: Vec<String> = Vec::new();
+        for cap in re.captures_iter(diff) {
+            let Some(name) = cap.iter().skip(2).flatten().next() else {
+                continue;
+            };
+            let list = if &cap[1] == "+" { &mut plus } else { &mut minus };
+            if !list.contains(&name.as_str().to_string()) {
+                list.push(name.as_str().to_string());
+            }
+        }
+
+        DeclarationChanges {
+            added: plus.iter().filter(|name| !minus.contains(name)).cloned().collect(),
+            removed: minus.iter().filter(|name| !plus.contains(name)).cloned().collect(),
+            changed: plus.iter().filter(|name| minus.contains(name)).cloned().collect(),
+        }
+    }
+}
+
 fn extract_modified_functions(diff: &str) -> Option<Vec<String>> {
     let re = Regex::new(
         r"(?m)^[+-]\s*(function\s+(\w+)|const\s+(\w+)\s*=\s*(\([^)]*\)\s*=>|\function))",
@@ -122,7 +214,7 @@ fn has_import_changes(diff: &str) -> bool {
     re.is_match(diff)
 }
 
-fn extract_modified_react_components(diff: &str) -> Option<Vec<String>> {
+fn extract_modified_react_components(file: &str, diff: &str) -> Option<Vec<String>> {
     let class_re = Regex::new(r"(?m)^[+-]\s*class\s+(\w+)\s+extends\s+React\.Component").unwrap();
     let func_re = Regex::new(r"(?m)^[+-]\s*(?:function\s+(\w+)|const\s+(\w+)\s*=)(?:\s*\([^)]*\))?\s*(?:=>)?\s*(?:\{[^}]*return|=>)\s*(?:<|\()").unwrap();
 
@@ -140,9 +232,89 @@ fn extract_modified_react_components(diff: &str) -> Option<Vec<String>> {
         }
     }
 
+    // In JSX files, capitalized functions are components even when the JSX they return
+    // isn't on the same line
+    if is_jsx(file) {
+        let jsx_func_re = Regex::new(
+            r"(?m)^[+-]\s*(?:export\s+(?:default\s+)?)?(?:function\s+([A-Z]\w*)|const\s+([A-Z]\w*)(?:\s*:\s*[\w.<>]+)?\s*=\s*(?:\(|function|React\.memo|memo|forwardRef))",
+        )
+        .unwrap();
+        for cap in jsx_func_re.captures_iter(diff) {
+            if let Some(m) = cap.get(1).or(cap.get(2)) {
+                components.insert(m.as_str().to_string());
+            }
+        }
+    }
+
     if components.is_empty() {
         None
     } else {
         Some(components.into_iter().collect())
     }
-}
\ No newline at end of file
+}
+
+/// Custom hooks declared on changed lines
+fn extract_modified_hooks(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r"(?m)^[+-]\s*(?:export\s+(?:default\s+)?)?(?:function\s+(use[A-Z]\w*)|const\s+(use[A-Z]\w*)\s*=)",
+    )
+    .unwrap();
+    let mut hooks: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        if let Some(m) = cap.get(1).or(cap.get(2)) {
+            if !hooks.contains(&m.as_str().to_string()) {
+                hooks.push(m.as_str().to_string());
+            }
+        }
+    }
+
+    if hooks.is_empty() {
+        None
+    } else {
+        Some(hooks)
+    }
+}
+
+/// Hooks called on changed lines, such as `useEffect` or a custom hook
+fn extract_hook_calls(diff: &str) -> Option<Vec<String>> {
+    let line_re = Regex::new(r"(?m)^[+-].*$").unwrap();
+    let call_re = Regex::new(r"\b(use[A-Z]\w*)\s*(?:<[^>]*>)?\(").unwrap();
+    let declaration_re = Regex::new(r"(?:function|const)\s+use[A-Z]").unwrap();
+    let mut hooks: Vec<String> = Vec::new();
+    for line in line_re.find_iter(diff) {
+        if declaration_re.is_match(line.as_str()) {
+            continue;
+        }
+        for cap in call_re.captures_iter(line.as_str()) {
+            if !hooks.contains(&cap[1].to_string()) {
+                hooks.push(cap[1].to_string());
+            }
+        }
+    }
+
+    if hooks.is_empty() {
+        None
+    } else {
+        Some(hooks)
+    }
+}
+
+/// TypeScript interfaces, type aliases, and enums declared on changed lines
+fn extract_modified_types(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r"(?m)^[+-]\s*(?:export\s+(?:default\s+)?)?(?:declare\s+)?(?:const\s+)?(?:interface|type|enum)\s+(\w+)",
+    )
+    .unwrap();
+    let mut types: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        if !types.contains(&cap[1].to_string()) {
+            types.push(cap[1].to_string());
+        }
+    }
+
+    if types.is_empty() {
+        None
+    } else {
+        Some(types)
+    }
+}
@@ -28,12 +28,10 @@ impl FileAnalyzer for JsonAnalyzer {
         "JSON configuration file"
     }
 
-    fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
+    fn extract_metadata(&self, file: &str, _content: &str) -> ProjectMetadata {
         let mut metadata = ProjectMetadata::default();
 
-        if file == "package.json" {
-            self.extract_package_json_metadata(content, &mut metadata);
-        } else if file == "tsconfig.json" {
+        if file == "tsconfig.json" {
             metadata.language = Some("TypeScript".to_string());
         }
 
@@ -41,47 +39,6 @@ impl FileAnalyzer for JsonAnalyzer {
     }
 }
 
-impl JsonAnalyzer {
-    fn extract_package_json_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(content) {
-            if let Some(version) = json["version"].as_str() {
-                metadata.version = Some(version.to_string());
-            }
-
-            if let Some(dependencies) = json["dependencies"].as_object() {
-                for dep in dependencies.keys() {
-                    metadata.dependencies.push(dep.to_string());
-                }
-            }
-
-            if let Some(dev_dependencies) = json["devDependencies"].as_object() {
-                for dep in dev_dependencies.keys() {
-                    metadata.dependencies.push(dep.to_string());
-                }
-            }
-
-            metadata.language = Some("JavaScript".to_string());
-            metadata.build_system = Some("npm".to_string());
-
-            // Detect framework
-            if json["dependencies"].get("react").is_some() {
-                metadata.framework = Some("React".to_string());
-            } else if json["dependencies"].get("vue").is_some() {
-                metadata.framework = Some("Vue".to_string());
-            } else if json["dependencies"].get("@angular/core").is_some() {
-                metadata.framework = Some("Angular".to_string());
-            }
-
-            // Detect test framework
-            if json["devDependencies"].get("jest").is_some() {
-                metadata.test_framework = Some("Jest".to_string());
-            } else if json["devDependencies"].get("mocha").is_some() {
-                metadata.test_framework = Some("Mocha".to_string());
-            }
-        }
-    }
-}
-
 fn extract_modified_top_level_keys(diff: &str) -> Option<Vec<String>> {
     let lines: Vec<&str> = diff.lines().collect();
     let re = Regex::new(r#"^[+-]\s*"(\w+)"\s*:"#).unwrap();
@@ -6,9 +6,19 @@ use std::collections::HashSet;
 pub struct KotlinAnalyzer;
 
 impl FileAnalyzer for KotlinAnalyzer {
-    fn analyze(&self, _file: &str, staged_file: &StagedFile) -> Vec<String> {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
         let mut analysis = Vec::new();
 
+        if is_gradle_script(file) {
+            if let Some(dependencies) = extract_modified_dependencies(&staged_file.diff) {
+                analysis.push(format!("Modified dependencies: {}", dependencies.join(", ")));
+            }
+            if let Some(plugins) = extract_modified_plugins(&staged_file.diff) {
+                analysis.push(format!("Modified plugins: {}", plugins.join(", ")));
+            }
+            return analysis;
+        }
+
         if let Some(classes) = extract_modified_classes(&staged_file.diff) {
             analysis.push(format!("Modified classes: {}", classes.join(", ")));
         }
@@ -17,6 +27,14 @@ impl FileAnalyzer for KotlinAnalyzer {
             analysis.push(format!("Modified functions: {}", functions.join(", ")));
         }
 
+        if let Some(composables) = extract_modified_composables(&staged_file.diff) {
+            analysis.push(format!("Modified composables: {}", composables.join(", ")));
+        }
+
+        if has_coroutine_changes(&staged_file.diff) {
+            analysis.push("Coroutine usage has been modified".to_string());
+        }
+
         if has_import_changes(&staged_file.diff) {
             analysis.push("Import statements have been modified".to_string());
         }
@@ -29,10 +47,12 @@ impl FileAnalyzer for KotlinAnalyzer {
     }
 
     fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
-        let mut metadata = ProjectMetadata::default();
-        metadata.language = Some("Kotlin".to_string());
+        let mut metadata = ProjectMetadata {
+            language: Some("Kotlin".to_string()),
+            ..Default::default()
+        };
 
-        if file == "build.gradle.kts" {
+        if is_gradle_script(file) {
             self.extract_gradle_metadata(content, &mut metadata);
         } else {
             self.extract_kotlin_file_metadata(content, &mut metadata);
@@ -51,29 +71,127 @@ impl KotlinAnalyzer {
             metadata.version = Some(cap[1].to_string());
         }
 
-        let dependency_re = Regex::new(r#"implementation\s*\(\s*["'](.+?):(.+?):(.+?)["']\)"#).unwrap();
+        let dependency_re = Regex::new(
+            r#"(?:implementation|api|compileOnly|runtimeOnly|kapt|ksp|testImplementation|androidTestImplementation)\s*\(\s*["'](.+?):(.+?):(.+?)["']\)"#,
+        )
+        .unwrap();
         for cap in dependency_re.captures_iter(content) {
             metadata.dependencies.push(format!("{}:{}:{}", &cap[1], &cap[2], &cap[3]));
         }
+
+        metadata.plugins = gradle_plugins(content);
+
+        if let Some(framework) = detect_framework(content) {
+            metadata.framework = Some(framework.to_string());
+        }
+        if let Some(test_framework) = detect_test_framework(content) {
+            metadata.test_framework = Some(test_framework.to_string());
+        }
     }
 
     fn extract_kotlin_file_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
-        if content.contains("import org.springframework") {
-            metadata.framework = Some("Spring".to_string());
+        if let Some(framework) = detect_framework(content) {
+            metadata.framework = Some(framework.to_string());
         } else if content.contains("import javax.ws.rs") {
             metadata.framework = Some("JAX-RS".to_string());
         }
 
-        if content.contains("import org.junit.") {
-            metadata.test_framework = Some("JUnit".to_string());
-        } else if content.contains("import org.testng.") {
-            metadata.test_framework = Some("TestNG".to_string());
+        if let Some(test_framework) = detect_test_framework(content) {
+            metadata.test_framework = Some(test_framework.to_string());
+        }
+    }
+}
+
+/// Whether a file is a Gradle build script in the Kotlin DSL, such as `build.gradle.kts`
+fn is_gradle_script(file: &str) -> bool {
+    file.ends_with(".gradle.kts")
+}
+
+fn detect_framework(content: &str) -> Option<&'static str> {
+    if content.contains("androidx.compose") {
+        Some("Jetpack Compose")
+    } else if content.contains("io.ktor") {
+        Some("Ktor")
+    } else if content.contains("org.springframework") {
+        Some("Spring")
+    } else {
+        None
+    }
+}
+
+fn detect_test_framework(content: &str) -> Option<&'static str> {
+    if content.contains("io.kotest") {
+        Some("Kotest")
+    } else if content.contains("org.junit") || content.contains("useJUnitPlatform") {
+        Some("JUnit")
+    } else if content.contains("org.testng") {
+        Some("TestNG")
+    } else {
+        None
+    }
+}
+
+/// Plugin ids in a Kotlin DSL build script, with `kotlin("jvm")` written out in full
+fn gradle_plugins(content: &str) -> Vec<String> {
+    let re = Regex::new(r#"(?m)\b(id|kotlin)\s*\(\s*"([^"]+)"\s*\)"#).unwrap();
+    let mut plugins = Vec::new();
+    for cap in re.captures_iter(content) {
+        let plugin = if &cap[1] == "kotlin" {
+            format!("org.jetbrains.kotlin.{}", &cap[2])
+        } else {
+            cap[2].to_string()
+        };
+        if !plugins.contains(&plugin) {
+            plugins.push(plugin);
+        }
+    }
+    plugins
+}
+
+/// Coordinates or version catalog entries of the dependencies on changed lines
+fn extract_modified_dependencies(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r#"(?m)^[+-]\s*(?:implementation|api|compileOnly|runtimeOnly|kapt|ksp|testImplementation|androidTestImplementation)\s*\(\s*(?:"([^":]+:[^":]+)[^"]*"|(libs\.[\w.]+))"#,
+    )
+    .unwrap();
+    let mut dependencies: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let Some(dependency) = cap.get(1).or_else(|| cap.get(2)) else {
+            continue;
+        };
+        let dependency = dependency.as_str().to_string();
+        if !dependencies.contains(&dependency) {
+            dependencies.push(dependency);
         }
     }
+
+    if dependencies.is_empty() {
+        None
+    } else {
+        Some(dependencies)
+    }
+}
+
+fn extract_modified_plugins(diff: &str) -> Option<Vec<String>> {
+    let changed: String = diff
+        .lines()
+        .filter(|line| line.starts_with('+') || line.starts_with('-'))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let plugins = gradle_plugins(&changed);
+
+    if plugins.is_empty() {
+        None
+    } else {
+        Some(plugins)
+    }
 }
 
 fn extract_modified_classes(diff: &str) -> Option<Vec<String>> {
-    let re = Regex::new(r"(?m)^[+-]\s*(class|interface|object)\s+(\w+)").unwrap();
+    let re = Regex::new(
+        r"(?m)^[+-]\s*(?:(?:data|sealed|abstract|open|enum|annotation|inner|value|private|internal|public|protected)\s+)*(class|interface|object)\s+(\w+)",
+    )
+    .unwrap();
     let classes: HashSet<String> = re
         .captures_iter(diff)
         .filter_map(|cap| cap.get(2).map(|m| m.as_str().to_string()))
@@ -87,7 +205,10 @@ fn extract_modified_classes(diff: &str) -> Option<Vec<String>> {
 }
 
 fn extract_modified_functions(diff: &str) -> Option<Vec<String>> {
-    let re = Regex::new(r"(?m)^[+-]\s*(fun)\s+(\w+)").unwrap();
+    let re = Regex::new(
+        r"(?m)^[+-]\s*(?:(?:private|internal|public|protected|override|open|suspend|inline|operator|infix)\s+)*(fun)\s+(?:<[^>]*>\s*)?(?:[\w.]+\.)?(\w+)\s*\(",
+    )
+    .unwrap();
     let functions: HashSet<String> = re
         .captures_iter(diff)
         .filter_map(|cap| cap.get(2).map(|m| m.as_str().to_string()))
@@ -100,7 +221,48 @@ fn extract_modified_functions(diff: &str) -> Option<Vec<String>> {
     }
 }
 
+/// Functions annotated with `@Composable` whose declaration or annotation changed
+fn extract_modified_composables(diff: &str) -> Option<Vec<String>> {
+    let fun_re = Regex::new(r"\bfun\s+(\w+)\s*\(").unwrap();
+    let mut composables: Vec<String> = Vec::new();
+    let mut annotated = false;
+    let mut annotation_changed = false;
+    for line in diff.lines() {
+        let changed = line.starts_with('+') || line.starts_with('-');
+        let body = line.get(1..).unwrap_or_default().trim();
+        if body.starts_with("@Composable") {
+            annotated = true;
+            annotation_changed |= changed;
+        }
+        if let Some(cap) = fun_re.captures(body) {
+            let name = cap[1].to_string();
+            if annotated && (changed || annotation_changed) && !composables.contains(&name) {
+                composables.push(name);
+            }
+            annotated = false;
+            annotation_changed = false;
+        } else if !body.starts_with('@') && !body.is_empty() {
+            annotated = false;
+            annotation_changed = false;
+        }
+    }
+
+    if composables.is_empty() {
+        None
+    } else {
+        Some(composables)
+    }
+}
+
+fn has_coroutine_changes(diff: &str) -> bool {
+    let re = Regex::new(
+        r"(?m)^[+-].*\b(suspend\s+fun|launch\s*[({]|async\s*[({]|withContext\s*\(|runBlocking|coroutineScope|supervisorScope|Dispatchers\.|Flow<|flow\s*\{|CoroutineScope)",
+    )
+    .unwrap();
+    re.is_match(diff)
+}
+
 fn has_import_changes(diff: &str) -> bool {
     let re = Regex::new(r"(?m)^[+-]\s*import\s+").unwrap();
     re.is_match(diff)
-}
\ No newline at end of file
+}
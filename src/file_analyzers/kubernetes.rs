@@ -0,0 +1,284 @@
+use super::yaml::{yaml_entries, YamlEntry};
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for Helm charts: `Chart.yaml` and values files
+///
+/// Plain manifests are recognized by their content instead, so the YAML analyzer hands them
+/// to [`analyze_manifest`].
+pub struct HelmAnalyzer;
+
+impl FileAnalyzer for HelmAnalyzer {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let before = yaml_entries(&staged_file.diff, '-', "");
+        let after = yaml_entries(&staged_file.diff, '+', "");
+        let mut analysis = Vec::new();
+
+        if is_chart(file) {
+            for (key, label) in [("version", "Chart version"), ("appVersion", "App version")] {
+                if let Some(change) = value_change(&before, &after, &[key]) {
+                    analysis.push(format!("{} changed: {}", label, change));
+                }
+            }
+            let dependencies = |entries: &[YamlEntry]| -> Vec<String> {
+                entries
+                    .iter()
+                    .filter(|entry| entry.path == ["dependencies", "name"])
+                    .filter_map(|entry| entry.value.clone())
+                    .collect()
+            };
+            let (added, removed) = difference(&dependencies(&before), &dependencies(&after));
+            let mut changed = added;
+            changed.extend(removed);
+            if !changed.is_empty() || changed_paths(&before, &after, "dependencies").is_some() {
+                let names = if changed.is_empty() {
+                    "versions".to_string()
+                } else {
+                    changed.join(", ")
+                };
+                analysis.push(format!("Chart dependencies modified: {}", names));
+            }
+            return analysis;
+        }
+
+        if let Some(paths) = changed_paths(&before, &after, "") {
+            analysis.push(format!("Modified values: {}", paths.join(", ")));
+        }
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "Helm chart file"
+    }
+
+    fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
+        let mut metadata = ProjectMetadata {
+            build_system: Some("Helm".to_string()),
+            ..Default::default()
+        };
+        if is_chart(file) {
+            let diff: String = content.lines().map(|line| format!(" {}\n", line)).collect();
+            for entry in yaml_entries(&diff, ' ', "") {
+                match entry.path.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+                    ["version"] => metadata.version = entry.value,
+                    ["dependencies", "name"] => metadata.dependencies.extend(entry.value),
+                    _ => {}
+                }
+            }
+        }
+        metadata
+    }
+}
+
+pub(super) fn is_helm_file(file: &str) -> bool {
+    let name = file.rsplit('/').next().unwrap_or(file);
+    is_chart(file)
+        || ((name.starts_with("values.") || name.starts_with("values-"))
+            && (name.ends_with(".yaml") || name.ends_with(".yml")))
+}
+
+fn is_chart(file: &str) -> bool {
+    file == "Chart.yaml" || file.ends_with("/Chart.yaml")
+}
+
+/// Whether a YAML diff shows a Kubernetes resource, going by its `apiVersion` and `kind`
+pub(super) fn is_manifest(diff: &str) -> bool {
+    let api_re = Regex::new(r"(?m)^[ +-]apiVersion:\s*\S+").unwrap();
+    let kind_re = Regex::new(r"(?m)^[ +-]kind:\s*[A-Z]\w*").unwrap();
+    api_re.is_match(diff) && kind_re.is_match(diff)
+}
+
+/// Summarize a diff of Kubernetes manifests: the resources it touches and the image,
+/// replica, resource limit, and RBAC rule changes in them
+pub(super) fn analyze_manifest(diff: &str) -> Vec<String> {
+    let before = yaml_entries(diff, '-', "");
+    let after = yaml_entries(diff, '+', "");
+    let mut analysis = Vec::new();
+
+    let (added, removed) = difference(&resources(&before), &resources(&after));
+    if !added.is_empty() {
+        analysis.push(format!("Resources added: {}", added.join(", ")));
+    }
+    if !removed.is_empty() {
+        analysis.push(format!("Resources removed: {}", removed.join(", ")));
+    }
+
+    let images = image_changes(&before, &after);
+    if !images.is_empty() {
+        analysis.push(format!("Image changes: {}", images.join(", ")));
+    }
+
+    if let Some(change) = value_change(&before, &after, &["spec", "replicas"]) {
+        analysis.push(format!("Replica count changed: {}", change));
+    }
+
+    let limits = resource_limit_changes(&before, &after);
+    if !limits.is_empty() {
+        analysis.push(format!("Resource limits changed: {}", limits.join(", ")));
+    }
+
+    let rules = |entries: &[YamlEntry]| -> Vec<String> {
+        entries
+            .iter()
+            .filter(|entry| entry.path.len() == 2 && entry.path[0] == "rules")
+            .filter_map(|entry| {
+                let value = entry
+                    .value
+                    .as_ref()?
+                    .trim_matches(['[', ']'])
+                    .replace(['"', '\''], "");
+                // An empty API group is the core group, which says nothing on its own
+                (!value.is_empty()).then(|| format!("{} {}", entry.path[1], value))
+            })
+            .collect()
+    };
+    let (added, _) = difference(&rules(&before), &rules(&after));
+    if !added.is_empty() {
+        analysis.push(format!("New RBAC rules: {}", added.join(", ")));
+    }
+
+    analysis
+}
+
+fn difference(before: &[String], after: &[String]) -> (Vec<String>, Vec<String>) {
+    let added = after
+        .iter()
+        .filter(|name| !before.contains(name))
+        .cloned()
+        .collect();
+    let removed = before
+        .iter()
+        .filter(|name| !after.contains(name))
+        .cloned()
+        .collect();
+    (added, removed)
+}
+
+fn value_of<'a>(entries: &'a [YamlEntry], path: &[&str]) -> Option<&'a String> {
+    entries
+        .iter()
+        .find(|entry| entry.path == path)
+        .and_then(|entry| entry.value.as_ref())
+}
+
+/// The value at a path before and after, when it changed, written `old → new`
+fn value_change(before: &[YamlEntry], after: &[YamlEntry], path: &[&str]) -> Option<String> {
+    let old = value_of(before, path)?;
+    let new = value_of(after, path)?;
+    (old != new).then(|| format!("{} → {}", old, new))
+}
+
+/// Dotted paths under `prefix` whose value differs between the sides, or that only one side
+/// has
+fn changed_paths(before: &[YamlEntry], after: &[YamlEntry], prefix: &str) -> Option<Vec<String>> {
+    let settings = |entries: &[YamlEntry]| -> Vec<String> {
+        entries
+            .iter()
+            .filter(|entry| {
+                prefix.is_empty() || entry.path.first().map(String::as_str) == Some(prefix)
+            })
+            .map(|entry| {
+                format!(
+                    "{}={}",
+                    entry.path.join("."),
+                    entry.value.as_deref().unwrap_or("")
+                )
+            })
+            .collect()
+    };
+    let (added, removed) = difference(&settings(before), &settings(after));
+    let mut paths: Vec<String> = Vec::new();
+    for setting in added.iter().chain(&removed) {
+        let path = setting.split('=').next().unwrap_or(setting).to_string();
+        if !path.is_empty() && !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    (!paths.is_empty()).then_some(paths)
+}
+
+/// `Kind/name` of each resource; the name is left out when the diff doesn't show it
+fn resources(entries: &[YamlEntry]) -> Vec<String> {
+    let mut resources: Vec<String> = Vec::new();
+    let mut kind: Option<&String> = None;
+    for entry in entries {
+        match entry.path.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+            ["kind"] => {
+                kind = entry.value.as_ref();
+                if let Some(kind) = kind {
+                    resources.push(kind.clone());
+                }
+            }
+            ["metadata", "name"] => {
+                if let (Some(kind), Some(name), Some(last)) =
+                    (kind, &entry.value, resources.last_mut())
+                {
+                    if last == kind {
+                        *last = format!("{}/{}", kind, name);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    resources
+}
+
+fn image_changes(before: &[YamlEntry], after: &[YamlEntry]) -> Vec<String> {
+    let images = |entries: &[YamlEntry]| -> Vec<(String, String)> {
+        entries
+            .iter()
+            .filter(|entry| entry.path.last().map(String::as_str) == Some("image"))
+            .filter_map(|entry| {
+                let image = entry.value.as_ref()?;
+                Some(match image.rsplit_once(':') {
+                    Some((name, tag)) if !tag.contains('/') => (name.to_string(), tag.to_string()),
+                    _ => (image.clone(), "latest".to_string()),
+                })
+            })
+            .collect()
+    };
+    let old_images = images(before);
+    let mut changes = Vec::new();
+    for (name, tag) in images(after) {
+        if let Some((_, old_tag)) = old_images.iter().find(|(old_name, _)| *old_name == name) {
+            let change = format!("{} ({} → {})", name, old_tag, tag);
+            if *old_tag != tag && !changes.contains(&change) {
+                changes.push(change);
+            }
+        }
+    }
+    changes
+}
+
+/// Changes to container `limits` and `requests`, such as `limits.memory 256Mi → 512Mi`
+fn resource_limit_changes(before: &[YamlEntry], after: &[YamlEntry]) -> Vec<String> {
+    let limits = |entries: &[YamlEntry]| -> Vec<(String, String)> {
+        entries
+            .iter()
+            .filter_map(|entry| {
+                let [.., kind, resource] = &entry.path[..] else {
+                    return None;
+                };
+                if kind != "limits" && kind != "requests" {
+                    return None;
+                }
+                Some((format!("{}.{}", kind, resource), entry.value.clone()?))
+            })
+            .collect()
+    };
+    let old_limits = limits(before);
+    let mut changes = Vec::new();
+    for (key, value) in limits(after) {
+        let change = match old_limits.iter().find(|(old_key, _)| *old_key == key) {
+            Some((_, old_value)) if *old_value == value => continue,
+            Some((_, old_value)) => format!("{} {} → {}", key, old_value, value),
+            None => format!("{} set to {}", key, value),
+        };
+        if !changes.contains(&change) {
+            changes.push(change);
+        }
+    }
+    changes
+}
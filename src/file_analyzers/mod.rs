@@ -7,12 +7,28 @@ pub trait FileAnalyzer {
     fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata;
 }
 
+/// Module for describing images, fonts, and archives
+mod binary;
 /// Module for analyzing C files
 mod c;
 /// Module for analyzing C++ files
 mod cpp;
+/// Module for analyzing C# files
+mod csharp;
+/// Module for analyzing stylesheets and Tailwind configuration
+mod css;
+/// Module for comparing dependency versions across manifests and lockfiles
+mod dependencies;
+/// Module for analyzing Dockerfiles
+mod dockerfile;
+/// Module for analyzing Go files
+mod go;
 /// Module for analyzing Gradle files
 mod gradle;
+/// Module for analyzing GraphQL files
+mod graphql;
+/// Module for analyzing HTML files and templates
+mod html;
 /// Module for analyzing Java files
 mod java;
 /// Module for analyzing JavaScript files
@@ -21,38 +37,190 @@ mod javascript;
 mod json;
 /// Module for analyzing Kotlin files
 mod kotlin;
+/// Module for analyzing Kubernetes manifests and Helm charts
+mod kubernetes;
 /// Module for analyzing Markdown files
 mod markdown;
+/// Module for analyzing npm manifests and lockfiles
+mod npm;
+/// Module for analyzing PHP files
+mod php;
+/// Module for analyzing Protocol Buffers files
+mod protobuf;
 /// Module for analyzing Python files
 mod python;
+/// Module for analyzing Ruby files
+mod ruby;
 /// Module for analyzing Rust files
 mod rust;
+/// Module for analyzing Vue and Svelte components
+mod sfc;
+/// Module for analyzing SQL files and database migrations
+mod sql;
+/// Module for analyzing Swift files
+mod swift;
+/// Module for comparing functions and types parsed with tree-sitter
+#[cfg(feature = "tree-sitter")]
+mod syntax;
+/// Module for analyzing TOML files
+mod toml;
+/// Module for analyzing CI pipeline files
+mod workflow;
 /// Module for analyzing YAML files
 mod yaml;
 
+/// Whether a file is a lockfile whose analysis summarizes it even when its diff is left out
+/// of prompts
+pub fn is_lockfile(file: &str) -> bool {
+    npm::is_lockfile(file) || file.rsplit('/').next() == Some("Cargo.lock")
+}
+
+/// Whether a file is analyzed by comparing its whole contents before and after a change,
+/// because its diff alone can't show what changed
+pub fn analyzes_contents(file: &str) -> bool {
+    toml::is_cargo_file(file) || binary::is_svg(file) || parses_symbols(file)
+}
+
+/// Analyze a change from the contents of a file before and after it, where `None` means the
+/// file didn't exist; falls back to analyzing the diff when the contents can't be parsed
+pub fn analyze_contents(
+    staged_file: &StagedFile,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> Vec<String> {
+    toml::analyze_contents(&staged_file.path, old, new)
+        .or_else(|| binary::is_svg(&staged_file.path).then(|| binary::analyze_svg(old, new)))
+        .or_else(|| analyze_symbols(staged_file, old, new))
+        .unwrap_or_else(|| get_analyzer(&staged_file.path).analyze(&staged_file.path, staged_file))
+}
+
+/// Describe a change to a binary file, such as an image's dimensions or the number of entries
+/// in an archive, from its bytes before and after, where `None` means the file didn't exist
+pub fn analyze_binary(old: Option<&[u8]>, new: Option<&[u8]>) -> Vec<String> {
+    binary::analyze(old, new)
+}
+
+/// Whether a file's functions and types are compared by parsing it, which needs the
+/// `tree-sitter` feature
+#[cfg(feature = "tree-sitter")]
+fn parses_symbols(file: &str) -> bool {
+    syntax::supports(file)
+}
+
+#[cfg(not(feature = "tree-sitter"))]
+fn parses_symbols(_file: &str) -> bool {
+    false
+}
+
+/// The functions and types a change adds, removes, and modifies, followed by what the file's
+/// analyzer finds besides them, such as import changes
+#[cfg(feature = "tree-sitter")]
+fn analyze_symbols(
+    staged_file: &StagedFile,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> Option<Vec<String>> {
+    let mut analysis = syntax::analyze_contents(&staged_file.path, old, new)?;
+    analysis.extend(
+        get_analyzer(&staged_file.path)
+            .analyze(&staged_file.path, staged_file)
+            .into_iter()
+            .filter(|line| !syntax::SUPERSEDED.iter().any(|prefix| line.starts_with(prefix))),
+    );
+    Some(analysis)
+}
+
+#[cfg(not(feature = "tree-sitter"))]
+fn analyze_symbols(
+    _staged_file: &StagedFile,
+    _old: Option<&str>,
+    _new: Option<&str>,
+) -> Option<Vec<String>> {
+    None
+}
+
 /// Get the appropriate file analyzer based on the file extension
 pub fn get_analyzer(file: &str) -> Box<dyn FileAnalyzer> {
-    if file.ends_with(".c") || file == "Makefile" {
+    if file.ends_with(".c") || file.ends_with(".h") || file == "Makefile" || file.ends_with("/Makefile")
+    {
         Box::new(c::CAnalyzer)
-    } else if file.ends_with(".cpp") || file.ends_with(".cc") || file.ends_with(".cxx") ||  file == "CMakeLists.txt" {
+    } else if [".cpp", ".cc", ".cxx", ".hpp", ".hh", ".hxx", ".cmake"]
+        .iter()
+        .any(|extension| file.ends_with(extension))
+        || file == "CMakeLists.txt"
+        || file.ends_with("/CMakeLists.txt")
+    {
         Box::new(cpp::CppAnalyzer)
+    } else if file.ends_with(".cs") || file.ends_with(".csproj") {
+        Box::new(csharp::CSharpAnalyzer)
+    } else if dockerfile::is_dockerfile(file) {
+        Box::new(dockerfile::DockerfileAnalyzer)
+    } else if file.ends_with(".go") || file == "go.mod" || file.ends_with("/go.mod") {
+        Box::new(go::GoAnalyzer)
+    } else if file.ends_with(".rb")
+        || file.ends_with(".gemspec")
+        || ["Gemfile", "Gemfile.lock"]
+            .iter()
+            .any(|name| file == *name || file.ends_with(&format!("/{}", name)))
+    {
+        Box::new(ruby::RubyAnalyzer)
     } else if file.ends_with(".rs") {
         Box::new(rust::RustAnalyzer)
-    } else if file.ends_with(".js") || file.ends_with(".ts") {
+    } else if [".css", ".scss", ".less"]
+        .iter()
+        .any(|extension| file.ends_with(extension))
+        || css::is_tailwind_config(file)
+    {
+        Box::new(css::CssAnalyzer)
+    } else if html::EXTENSIONS
+        .iter()
+        .any(|extension| file.ends_with(extension))
+    {
+        Box::new(html::HtmlAnalyzer)
+    } else if npm::is_npm_file(file) {
+        Box::new(npm::NpmAnalyzer)
+    } else if file.ends_with(".toml") || toml::is_cargo_file(file) {
+        Box::new(toml::TomlAnalyzer)
+    } else if file.ends_with(".vue") || file.ends_with(".svelte") {
+        Box::new(sfc::SfcAnalyzer)
+    } else if [".js", ".jsx", ".mjs", ".cjs", ".ts", ".tsx", ".mts", ".cts"]
+        .iter()
+        .any(|extension| file.ends_with(extension))
+    {
         Box::new(javascript::JavaScriptAnalyzer)
+    } else if file.ends_with(".sql")
+        || (file.ends_with(".py") && file.contains("migrations/") && !file.ends_with("__init__.py"))
+    {
+        Box::new(sql::SqlAnalyzer)
     } else if file.ends_with(".py") {
         Box::new(python::PythonAnalyzer)
+    } else if file.ends_with(".graphql")
+        || file.ends_with(".graphqls")
+        || file.ends_with(".gql")
+    {
+        Box::new(graphql::GraphQlAnalyzer)
+    } else if file.ends_with(".proto") {
+        Box::new(protobuf::ProtobufAnalyzer)
+    } else if file.ends_with(".swift") || file.ends_with(".pbxproj") {
+        Box::new(swift::SwiftAnalyzer)
+    } else if workflow::is_github_workflow(file) || workflow::is_gitlab_ci(file) {
+        Box::new(workflow::WorkflowAnalyzer)
+    } else if kubernetes::is_helm_file(file) {
+        Box::new(kubernetes::HelmAnalyzer)
     } else if file.ends_with(".yaml") || file.ends_with(".yml") {
         Box::new(yaml::YamlAnalyzer)
+    } else if file.ends_with(".php") || file == "composer.json" || file.ends_with("/composer.json")
+    {
+        Box::new(php::PhpAnalyzer)
     } else if file.ends_with(".json") {
         Box::new(json::JsonAnalyzer)
     } else if file.ends_with(".md") {
         Box::new(markdown::MarkdownAnalyzer)
-    } else if file.ends_with(".java") {
+    } else if file.ends_with(".java") || file == "pom.xml" || file.ends_with("/pom.xml") {
         Box::new(java::JavaAnalyzer)
-    } else if file.ends_with(".kt") {
+    } else if file.ends_with(".kt") || file.ends_with(".kts") {
         Box::new(kotlin::KotlinAnalyzer)
-    } else if file.ends_with(".gradle") || file.ends_with(".gradle.kts") {
+    } else if file.ends_with(".gradle") {
         Box::new(gradle::GradleAnalyzer)
     } else {
         Box::new(DefaultAnalyzer)
@@ -0,0 +1,258 @@
+use super::dependencies::DependencyChanges;
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Analyzer for `package.json` and the npm, Yarn, and pnpm lockfiles
+pub struct NpmAnalyzer;
+
+/// Sections of `package.json` that list dependencies, with the label shown for each
+const DEPENDENCY_SECTIONS: &[(&str, &str)] = &[
+    ("dependencies", ""),
+    ("devDependencies", " (dev)"),
+    ("peerDependencies", " (peer)"),
+    ("optionalDependencies", " (optional)"),
+];
+
+impl FileAnalyzer for NpmAnalyzer {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let parse: fn(&str, char) -> BTreeMap<String, String> = match file_name(file) {
+            "package.json" => manifest_dependencies,
+            "yarn.lock" => yarn_lock_packages,
+            "pnpm-lock.yaml" => pnpm_lock_packages,
+            _ => npm_lock_packages,
+        };
+        let before = parse(&staged_file.diff, '-');
+        let after = parse(&staged_file.diff, '+');
+        DependencyChanges::between(&before, &after).describe()
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "npm package manifest or lockfile"
+    }
+
+    fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
+        let build_system = match file_name(file) {
+            "yarn.lock" => "Yarn",
+            "pnpm-lock.yaml" => "pnpm",
+            _ => "npm",
+        };
+        let mut metadata = ProjectMetadata {
+            language: Some("JavaScript".to_string()),
+            build_system: Some(build_system.to_string()),
+            ..Default::default()
+        };
+        if file_name(file) == "package.json" {
+            self.extract_package_json_metadata(content, &mut metadata);
+        }
+        metadata
+    }
+}
+
+impl NpmAnalyzer {
+    fn extract_package_json_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(content) {
+            if let Some(version) = json["version"].as_str() {
+                metadata.version = Some(version.to_string());
+            }
+
+            if let Some(dependencies) = json["dependencies"].as_object() {
+                for dep in dependencies.keys() {
+                    metadata.dependencies.push(dep.to_string());
+                }
+            }
+
+            if let Some(dev_dependencies) = json["devDependencies"].as_object() {
+                for dep in dev_dependencies.keys() {
+                    metadata.dependencies.push(dep.to_string());
+                }
+            }
+
+            // Detect framework
+            if json["dependencies"].get("react").is_some() {
+                metadata.framework = Some("React".to_string());
+            } else if json["dependencies"].get("vue").is_some() {
+                metadata.framework = Some("Vue".to_string());
+            } else if json["dependencies"].get("@angular/core").is_some() {
+                metadata.framework = Some("Angular".to_string());
+            }
+
+            // Detect test framework
+            if json["devDependencies"].get("jest").is_some() {
+                metadata.test_framework = Some("Jest".to_string());
+            } else if json["devDependencies"].get("mocha").is_some() {
+                metadata.test_framework = Some("Mocha".to_string());
+            }
+        }
+    }
+}
+
+pub(super) fn is_npm_file(file: &str) -> bool {
+    matches!(
+        file_name(file),
+        "package.json"
+            | "package-lock.json"
+            | "npm-shrinkwrap.json"
+            | "yarn.lock"
+            | "pnpm-lock.yaml"
+    )
+}
+
+/// Whether a file is a lockfile this analyzer summarizes even when its diff is left out
+pub(super) fn is_lockfile(file: &str) -> bool {
+    is_npm_file(file) && file_name(file) != "package.json"
+}
+
+fn file_name(file: &str) -> &str {
+    file.rsplit('/').next().unwrap_or(file)
+}
+
+/// Lines of the diff on one side, counting context lines on both, with hunk headers
+fn side_lines(diff: &str, sign: char) -> impl Iterator<Item = &str> {
+    diff.lines()
+        .filter(move |line| line.starts_with(' ') || line.starts_with(sign))
+        .map(|line| &line[1..])
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Dependencies in `package.json`, labeled by the section they are in
+///
+/// A hunk that starts inside a section doesn't show its name; dependencies there are nested
+/// deeper than the top-level settings and count as regular ones.
+fn manifest_dependencies(diff: &str, sign: char) -> BTreeMap<String, String> {
+    let section_re = Regex::new(r#"^\s*"(\w+)"\s*:\s*\{"#).unwrap();
+    let entry_re = Regex::new(r#"^\s*"([^"]+)"\s*:\s*"([^"]*)""#).unwrap();
+    let range_re = Regex::new(
+        r"^(?:[\^~<>=v\s]*\d|\*|x$|latest|next|workspace:|npm:|file:|link:|git|https?:|github:)",
+    )
+    .unwrap();
+
+    let mut dependencies = BTreeMap::new();
+    // The label of the section a line is in, `None` inside other settings, or unknown
+    let mut section: Option<Option<&str>> = None;
+    for line in side_lines(diff, sign) {
+        if line.trim_start().starts_with("@@") {
+            section = None;
+            continue;
+        }
+        if let Some(cap) = section_re.captures(line) {
+            section = Some(
+                DEPENDENCY_SECTIONS
+                    .iter()
+                    .find(|(name, _)| *name == &cap[1])
+                    .map(|(_, label)| *label),
+            );
+            continue;
+        }
+        if line.trim_start().starts_with('}') {
+            section = Some(None);
+            continue;
+        }
+        let Some(cap) = entry_re.captures(line) else {
+            continue;
+        };
+        let label = match section {
+            Some(Some(label)) => label,
+            Some(None) => continue,
+            None if indentation(line) > 2 && range_re.is_match(&cap[2]) => "",
+            None => continue,
+        };
+        dependencies.insert(format!("{}{}", &cap[1], label), cap[2].to_string());
+    }
+    dependencies
+}
+
+/// Package versions in `package-lock.json` or `npm-shrinkwrap.json`, from the `packages`
+/// entries of lockfile version 2 and later or the nested `dependencies` of version 1
+fn npm_lock_packages(diff: &str, sign: char) -> BTreeMap<String, String> {
+    let block_re = Regex::new(r#"^\s*"([^"]*)"\s*:\s*\{"#).unwrap();
+    let version_re = Regex::new(r#"^\s*"version"\s*:\s*"([^"]+)""#).unwrap();
+
+    let mut packages = BTreeMap::new();
+    // Keys of the objects enclosing the current line, with their indentation
+    let mut blocks: Vec<(usize, String)> = Vec::new();
+    for line in side_lines(diff, sign) {
+        if line.trim_start().starts_with("@@") {
+            blocks.clear();
+            continue;
+        }
+        let indent = indentation(line);
+        while blocks
+            .last()
+            .is_some_and(|(block_indent, _)| *block_indent >= indent)
+        {
+            blocks.pop();
+        }
+        if let Some(cap) = block_re.captures(line) {
+            blocks.push((indent, cap[1].to_string()));
+        } else if let Some(cap) = version_re.captures(line) {
+            let Some((_, key)) = blocks.last() else {
+                continue;
+            };
+            let name = key.rsplit("node_modules/").next().unwrap_or(key);
+            if !name.is_empty() {
+                add_version(&mut packages, name, &cap[1]);
+            }
+        }
+    }
+    packages
+}
+
+/// Package versions in `yarn.lock`, in both the classic and the Berry format
+fn yarn_lock_packages(diff: &str, sign: char) -> BTreeMap<String, String> {
+    let version_re = Regex::new(r#"^\s+version:?\s+"?([^"\s]+)"?"#).unwrap();
+
+    let mut packages = BTreeMap::new();
+    let mut name: Option<String> = None;
+    for line in side_lines(diff, sign) {
+        if let Some(header) = line.trim_start().strip_prefix("@@") {
+            // Git shows the entry a hunk starts in after the range
+            let context = header.rsplit("@@").next().unwrap_or("").trim();
+            name = context.strip_suffix(':').and_then(yarn_entry_name);
+            continue;
+        }
+        if !line.starts_with(' ') && !line.is_empty() && !line.starts_with('#') {
+            name = line.trim_end().strip_suffix(':').and_then(yarn_entry_name);
+        } else if let (Some(name), Some(cap)) = (&name, version_re.captures(line)) {
+            add_version(&mut packages, name, &cap[1]);
+        }
+    }
+    packages
+}
+
+/// The package name of an entry such as `"@babel/core@^7.0.0", "@babel/core@^7.2.0"`
+fn yarn_entry_name(header: &str) -> Option<String> {
+    let first = header.split(',').next()?.trim().trim_matches('"');
+    let at = first.rfind('@').filter(|at| *at > 0)?;
+    Some(first[..at].to_string())
+}
+
+/// Package versions in `pnpm-lock.yaml`, from its `packages` keys such as `/react@18.2.0`,
+/// `react@18.2.0(react-dom@18.2.0)`, or `/react/18.2.0` in older lockfiles
+fn pnpm_lock_packages(diff: &str, sign: char) -> BTreeMap<String, String> {
+    let key_re =
+        Regex::new(r"^  '?/?((?:@[\w.-]+/)?[\w.-]+)[@/](\d[^(:'\s]*)[^:]*'?:\s*$").unwrap();
+
+    let mut packages = BTreeMap::new();
+    for line in side_lines(diff, sign) {
+        if let Some(cap) = key_re.captures(line) {
+            add_version(&mut packages, &cap[1], &cap[2]);
+        }
+    }
+    packages
+}
+
+/// Record a version of a package; lockfiles can hold several versions of one package
+fn add_version(packages: &mut BTreeMap<String, String>, name: &str, version: &str) {
+    let versions = packages.entry(name.to_string()).or_default();
+    if versions.is_empty() {
+        versions.push_str(version);
+    } else if !versions.split(", ").any(|existing| existing == version) {
+        versions.push_str(", ");
+        versions.push_str(version);
+    }
+}
@@ -0,0 +1,195 @@
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for PHP sources and `composer.json`
+pub struct PhpAnalyzer;
+
+impl FileAnalyzer for PhpAnalyzer {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let mut analysis = Vec::new();
+
+        if is_composer_file(file) {
+            if let Some(packages) = extract_modified_packages(&staged_file.diff) {
+                analysis.push(format!("Modified dependencies: {}", packages.join(", ")));
+            }
+            if has_php_requirement_change(&staged_file.diff) {
+                analysis.push("PHP version requirement has been modified".to_string());
+            }
+            return analysis;
+        }
+
+        match extract_namespace_move(&staged_file.diff) {
+            Some((from, to)) => analysis.push(format!("Namespace moved: {} → {}", from, to)),
+            None => {
+                if let Some(namespaces) = extract_modified_namespaces(&staged_file.diff) {
+                    analysis.push(format!("Modified namespaces: {}", namespaces.join(", ")));
+                }
+            }
+        }
+
+        if let Some(classes) = extract_modified_classes(&staged_file.diff) {
+            analysis.push(format!("Modified classes: {}", classes.join(", ")));
+        }
+
+        if let Some(functions) = extract_modified_functions(&staged_file.diff) {
+            analysis.push(format!("Modified functions: {}", functions.join(", ")));
+        }
+
+        if has_use_changes(&staged_file.diff) {
+            analysis.push("Use statements have been modified".to_string());
+        }
+
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "PHP source file"
+    }
+
+    fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
+        let mut metadata = ProjectMetadata {
+            language: Some("PHP".to_string()),
+            ..Default::default()
+        };
+
+        if is_composer_file(file) {
+            self.extract_composer_metadata(content, &mut metadata);
+        } else {
+            self.extract_php_file_metadata(content, &mut metadata);
+        }
+
+        metadata
+    }
+}
+
+impl PhpAnalyzer {
+    fn extract_composer_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
+        metadata.build_system = Some("Composer".to_string());
+
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else {
+            return;
+        };
+        if let Some(version) = json["version"].as_str() {
+            metadata.version = Some(version.to_string());
+        }
+        for section in ["require", "require-dev"] {
+            if let Some(packages) = json[section].as_object() {
+                // Platform requirements such as `php` and `ext-json` aren't packages
+                metadata
+                    .dependencies
+                    .extend(packages.keys().filter(|name| name.contains('/')).cloned());
+            }
+        }
+
+        let has_package = |name: &str| metadata.dependencies.iter().any(|dep| dep == name);
+        let framework = if has_package("laravel/framework") {
+            Some("Laravel")
+        } else if metadata
+            .dependencies
+            .iter()
+            .any(|dep| dep == "symfony/framework-bundle" || dep == "symfony/symfony")
+        {
+            Some("Symfony")
+        } else {
+            None
+        };
+        let test_framework = if has_package("pestphp/pest") {
+            Some("Pest")
+        } else if has_package("phpunit/phpunit") {
+            Some("PHPUnit")
+        } else {
+            None
+        };
+        metadata.framework = framework.map(str::to_string);
+        metadata.test_framework = test_framework.map(str::to_string);
+    }
+
+    fn extract_php_file_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
+        if content.contains("use Illuminate\\") {
+            metadata.framework = Some("Laravel".to_string());
+        } else if content.contains("use Symfony\\") {
+            metadata.framework = Some("Symfony".to_string());
+        }
+
+        if content.contains("PHPUnit\\Framework") {
+            metadata.test_framework = Some("PHPUnit".to_string());
+        }
+    }
+}
+
+fn is_composer_file(file: &str) -> bool {
+    file == "composer.json" || file.ends_with("/composer.json")
+}
+
+fn extract_modified_packages(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r#"(?m)^[+-]\s*"([\w.-]+/[\w.-]+)"\s*:\s*""#).unwrap();
+    unique_names(&re, diff)
+}
+
+fn has_php_requirement_change(diff: &str) -> bool {
+    let re = Regex::new(r#"(?m)^[+-]\s*"php"\s*:"#).unwrap();
+    re.is_match(diff)
+}
+
+/// The namespace a file's declaration moved from and to, when exactly one was removed and
+/// one was added
+fn extract_namespace_move(diff: &str) -> Option<(String, String)> {
+    let re = Regex::new(r"(?m)^([+-])\s*namespace\s+([\w\\]+)\s*;").unwrap();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    for cap in re.captures_iter(diff) {
+        if &cap[1] == "-" {
+            removed.push(cap[2].to_string());
+        } else {
+            added.push(cap[2].to_string());
+        }
+    }
+
+    match (removed.as_slice(), added.as_slice()) {
+        ([from], [to]) if from != to => Some((from.clone(), to.clone())),
+        _ => None,
+    }
+}
+
+fn extract_modified_namespaces(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r"(?m)^[+-]\s*namespace\s+([\w\\]+)").unwrap();
+    unique_names(&re, diff)
+}
+
+fn extract_modified_classes(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r"(?m)^[+-]\s*(?:(?:abstract|final|readonly)\s+)*(?:class|interface|trait|enum)\s+(\w+)",
+    )
+    .unwrap();
+    unique_names(&re, diff)
+}
+
+fn extract_modified_functions(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r"(?m)^[+-]\s*(?:(?:public|protected|private|static|abstract|final)\s+)*function\s+&?(\w+)\s*\(",
+    )
+    .unwrap();
+    unique_names(&re, diff)
+}
+
+fn unique_names(re: &Regex, diff: &str) -> Option<Vec<String>> {
+    let mut names: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let name = cap[1].to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    if names.is_empty() {
+        None
+    } else {
+        Some(names)
+    }
+}
+
+fn has_use_changes(diff: &str) -> bool {
+    let re = Regex::new(r"(?m)^[+-]\s*use\s+[\w\\]+").unwrap();
+    re.is_match(diff)
+}
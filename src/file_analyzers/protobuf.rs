@@ -0,0 +1,322 @@
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for Protocol Buffers schemas and their gRPC services
+pub struct ProtobufAnalyzer;
+
+impl FileAnalyzer for ProtobufAnalyzer {
+    fn analyze(&self, _file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let before = Schema::parse(&staged_file.diff, '-');
+        let after = Schema::parse(&staged_file.diff, '+');
+        let mut analysis = Vec::new();
+
+        let risks = wire_compatibility_risks(&before, &after);
+        if !risks.is_empty() {
+            analysis.push(format!("Wire-compatibility risks: {}", risks.join(", ")));
+        }
+
+        let report = |analysis: &mut Vec<String>, label: &str, names: Vec<String>| {
+            if !names.is_empty() {
+                analysis.push(format!("{}: {}", label, names.join(", ")));
+            }
+        };
+        let (added, removed) = difference(&before.messages, &after.messages);
+        report(&mut analysis, "Messages added", added);
+        report(&mut analysis, "Messages removed", removed);
+
+        let field_names = |schema: &Schema| -> Vec<String> {
+            schema
+                .fields
+                .iter()
+                .map(|field| format!("{}.{}", field.message, field.name))
+                .collect()
+        };
+        let (added, removed) = difference(&field_names(&before), &field_names(&after));
+        report(&mut analysis, "Fields added", added);
+        report(&mut analysis, "Fields removed", removed);
+
+        let (added, removed) = difference(&before.enum_values, &after.enum_values);
+        report(&mut analysis, "Enum values added", added);
+        report(&mut analysis, "Enum values removed", removed);
+
+        let rpc_names = |schema: &Schema| -> Vec<String> {
+            schema.rpcs.iter().map(|rpc| rpc.name.clone()).collect()
+        };
+        let (added, removed) = difference(&rpc_names(&before), &rpc_names(&after));
+        report(&mut analysis, "RPCs added", added);
+        report(&mut analysis, "RPCs removed", removed);
+
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "Protocol Buffers schema"
+    }
+
+    fn extract_metadata(&self, _file: &str, content: &str) -> ProjectMetadata {
+        let import_re = Regex::new(r#"(?m)^\s*import\s+(?:public\s+|weak\s+)?"([^"]+)""#).unwrap();
+        let service_re = Regex::new(r"(?m)^\s*service\s+\w+").unwrap();
+        ProjectMetadata {
+            language: Some("Protocol Buffers".to_string()),
+            framework: service_re.is_match(content).then(|| "gRPC".to_string()),
+            dependencies: import_re
+                .captures_iter(content)
+                .map(|cap| cap[1].to_string())
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+struct Field {
+    message: String,
+    name: String,
+    /// The type with its label, such as `repeated string`
+    field_type: String,
+    number: u32,
+}
+
+struct Rpc {
+    /// `Service.Method`
+    name: String,
+    /// Request and response types, such as `(GetOrderRequest) returns (stream Order)`
+    signature: String,
+}
+
+/// What one side of a diff shows of a schema
+#[derive(Default)]
+struct Schema {
+    messages: Vec<String>,
+    fields: Vec<Field>,
+    /// `Enum.VALUE`
+    enum_values: Vec<String>,
+    rpcs: Vec<Rpc>,
+    /// Field numbers and names reserved in each message, as `Message.3` or `Message.name`
+    reserved: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ScopeKind {
+    Message,
+    Enum,
+    Service,
+    /// A `oneof` or option block, whose fields belong to the enclosing message
+    Block,
+}
+
+impl Schema {
+    /// Parse the context lines and the lines on one side of a diff
+    ///
+    /// A hunk that starts inside a definition takes it from the hunk header, where Git
+    /// shows the line that opened it.
+    fn parse(diff: &str, sign: char) -> Self {
+        let open_re = Regex::new(r"^(message|enum|service|oneof|extend)\s+([\w.]+)\s*\{").unwrap();
+        let field_re = Regex::new(
+            r"^((?:optional|repeated|required)\s+)?(map\s*<[^>]+>|[\w.]+)\s+(\w+)\s*=\s*(\d+)",
+        )
+        .unwrap();
+        let enum_value_re = Regex::new(r"^(\w+)\s*=\s*-?\d+").unwrap();
+        let rpc_re = Regex::new(r"^rpc\s+(\w+)\s*(\(.*?\)\s*returns\s*\(.*?\))").unwrap();
+        let reserved_re = Regex::new(r"^reserved\s+(.+?);").unwrap();
+
+        let mut schema = Schema::default();
+        let mut scopes: Vec<(ScopeKind, String)> = Vec::new();
+        for line in diff.lines() {
+            if !(line.starts_with(' ') || line.starts_with(sign)) {
+                continue;
+            }
+            let content = line[1..].trim();
+            if let Some(header) = content.strip_prefix("@@") {
+                scopes.clear();
+                let context = header.rsplit("@@").next().unwrap_or("").trim();
+                if let Some(cap) = open_re.captures(context) {
+                    scopes.push((scope_kind(&cap[1]), cap[2].to_string()));
+                }
+                continue;
+            }
+            let content = content.split("//").next().unwrap_or("").trim();
+
+            if let Some(cap) = open_re.captures(content) {
+                let kind = scope_kind(&cap[1]);
+                let name = match kind {
+                    ScopeKind::Block => current(&scopes, ScopeKind::Message).unwrap_or_default(),
+                    _ => nested_name(&scopes, &cap[2]),
+                };
+                if kind == ScopeKind::Message {
+                    push_unique(&mut schema.messages, name.clone());
+                }
+                if !content.ends_with('}') {
+                    scopes.push((kind, name));
+                }
+                continue;
+            }
+            if content.starts_with('}') {
+                scopes.pop();
+                continue;
+            }
+
+            match scopes.last().map(|(kind, _)| *kind) {
+                Some(ScopeKind::Message) | Some(ScopeKind::Block) => {
+                    let message = current(&scopes, ScopeKind::Message).unwrap_or_default();
+                    if let Some(cap) = reserved_re.captures(content) {
+                        for item in cap[1].split(',') {
+                            let item = item.trim().trim_matches('"');
+                            schema.reserved.push(format!("{}.{}", message, item));
+                        }
+                    } else if let Some(cap) = field_re.captures(content) {
+                        if matches!(&cap[2], "option" | "reserved" | "extensions") {
+                            continue;
+                        }
+                        schema.fields.push(Field {
+                            message,
+                            name: cap[3].to_string(),
+                            field_type: format!(
+                                "{}{}",
+                                cap.get(1).map_or("", |m| m.as_str()),
+                                &cap[2]
+                            ),
+                            number: cap[4].parse().unwrap_or_default(),
+                        });
+                    }
+                }
+                Some(ScopeKind::Enum) => {
+                    if let Some(cap) = enum_value_re.captures(content) {
+                        let name = scopes.last().map(|(_, name)| name.as_str()).unwrap_or("");
+                        push_unique(&mut schema.enum_values, format!("{}.{}", name, &cap[1]));
+                    }
+                }
+                Some(ScopeKind::Service) => {
+                    if let Some(cap) = rpc_re.captures(content) {
+                        let service = scopes.last().map(|(_, name)| name.as_str()).unwrap_or("");
+                        schema.rpcs.push(Rpc {
+                            name: format!("{}.{}", service, &cap[1]),
+                            signature: cap[2].split_whitespace().collect::<Vec<_>>().join(" "),
+                        });
+                    }
+                }
+                None => {}
+            }
+        }
+        schema
+    }
+}
+
+fn scope_kind(keyword: &str) -> ScopeKind {
+    match keyword {
+        "message" => ScopeKind::Message,
+        "enum" => ScopeKind::Enum,
+        "service" => ScopeKind::Service,
+        _ => ScopeKind::Block,
+    }
+}
+
+/// The innermost enclosing definition of a kind
+fn current(scopes: &[(ScopeKind, String)], kind: ScopeKind) -> Option<String> {
+    scopes
+        .iter()
+        .rev()
+        .find(|(scope_kind, _)| *scope_kind == kind)
+        .map(|(_, name)| name.clone())
+}
+
+/// The name of a definition nested in a message, such as `Order.Item`
+fn nested_name(scopes: &[(ScopeKind, String)], name: &str) -> String {
+    match current(scopes, ScopeKind::Message) {
+        Some(parent) => format!("{}.{}", parent, name),
+        None => name.to_string(),
+    }
+}
+
+fn push_unique(names: &mut Vec<String>, name: String) {
+    if !names.contains(&name) {
+        names.push(name);
+    }
+}
+
+fn difference(before: &[String], after: &[String]) -> (Vec<String>, Vec<String>) {
+    let added = after
+        .iter()
+        .filter(|name| !before.contains(name))
+        .cloned()
+        .collect();
+    let removed = before
+        .iter()
+        .filter(|name| !after.contains(name))
+        .cloned()
+        .collect();
+    (added, removed)
+}
+
+/// Changes that break clients or stored data encoded with the old schema
+fn wire_compatibility_risks(before: &Schema, after: &Schema) -> Vec<String> {
+    let mut risks = Vec::new();
+
+    for old in &before.fields {
+        let same_number = after
+            .fields
+            .iter()
+            .find(|new| new.message == old.message && new.number == old.number);
+        match same_number {
+            Some(new) if new.name != old.name => risks.push(format!(
+                "{} field number {} reassigned ({} → {})",
+                old.message, old.number, old.name, new.name
+            )),
+            Some(new) if new.field_type != old.field_type => risks.push(format!(
+                "{}.{} type changed ({} → {})",
+                old.message, old.name, old.field_type, new.field_type
+            )),
+            Some(_) => {}
+            None => {
+                let reserved = after.reserved.iter().any(|reserved| {
+                    *reserved == format!("{}.{}", old.message, old.number)
+                        || reserved_range_contains(reserved, &old.message, old.number)
+                });
+                let moved = after
+                    .fields
+                    .iter()
+                    .any(|new| new.message == old.message && new.name == old.name);
+                if !reserved && !moved {
+                    risks.push(format!(
+                        "{}.{} removed without reserving field number {}",
+                        old.message, old.name, old.number
+                    ));
+                } else if moved {
+                    risks.push(format!(
+                        "{}.{} renumbered from {}",
+                        old.message, old.name, old.number
+                    ));
+                }
+            }
+        }
+    }
+
+    for old in &before.rpcs {
+        match after.rpcs.iter().find(|new| new.name == old.name) {
+            Some(new) if new.signature != old.signature => risks.push(format!(
+                "{} signature changed ({} → {})",
+                old.name, old.signature, new.signature
+            )),
+            Some(_) => {}
+            None => risks.push(format!("{} removed", old.name)),
+        }
+    }
+
+    risks
+}
+
+/// Whether a `reserved` entry such as `Order.5 to 9` covers a field number
+fn reserved_range_contains(reserved: &str, message: &str, number: u32) -> bool {
+    let Some(range) = reserved.strip_prefix(&format!("{}.", message)) else {
+        return false;
+    };
+    let Some((start, end)) = range.split_once(" to ") else {
+        return false;
+    };
+    let start: u32 = start.trim().parse().unwrap_or(u32::MAX);
+    let end: u32 = match end.trim() {
+        "max" => u32::MAX,
+        end => end.parse().unwrap_or(0),
+    };
+    (start..=end).contains(&number)
+}
@@ -0,0 +1,275 @@
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for Ruby sources, gemspecs, `Gemfile`, and `Gemfile.lock`
+pub struct RubyAnalyzer;
+
+impl FileAnalyzer for RubyAnalyzer {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let mut analysis = Vec::new();
+
+        if is_named(file, "Gemfile") || file.ends_with(".gemspec") {
+            if let Some(gems) = extract_modified_gems(&staged_file.diff) {
+                analysis.push(format!("Modified gems: {}", gems.join(", ")));
+            }
+            if file.ends_with(".gemspec") && has_gemspec_version_change(&staged_file.diff) {
+                analysis.push("Gem version has been modified".to_string());
+            }
+            return analysis;
+        }
+
+        if is_named(file, "Gemfile.lock") {
+            if let Some(gems) = extract_modified_locked_gems(&staged_file.diff) {
+                analysis.push(format!("Modified locked gems: {}", gems.join(", ")));
+            }
+            return analysis;
+        }
+
+        if is_migration(file) {
+            analysis.push("Rails database migration has been modified".to_string());
+            if let Some(operations) = extract_schema_operations(&staged_file.diff) {
+                let destructive: Vec<&str> = operations
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|operation| {
+                        ["drop_table", "remove_column", "remove_reference"]
+                            .iter()
+                            .any(|prefix| operation.starts_with(prefix))
+                    })
+                    .collect();
+                if !destructive.is_empty() {
+                    analysis.insert(
+                        0,
+                        format!(
+                            "Destructive operations (data may be lost): {}",
+                            destructive.join(", ")
+                        ),
+                    );
+                }
+                analysis.push(format!("Schema changes: {}", operations.join(", ")));
+            }
+        }
+
+        if let Some(classes) = extract_modified_definitions(&staged_file.diff, "class") {
+            analysis.push(format!("Modified classes: {}", classes.join(", ")));
+        }
+
+        if let Some(modules) = extract_modified_definitions(&staged_file.diff, "module") {
+            analysis.push(format!("Modified modules: {}", modules.join(", ")));
+        }
+
+        if let Some(methods) = extract_modified_methods(&staged_file.diff) {
+            analysis.push(format!("Modified methods: {}", methods.join(", ")));
+        }
+
+        if file.ends_with("_spec.rb") {
+            if let Some(examples) = extract_modified_specs(&staged_file.diff) {
+                analysis.push(format!("Modified specs: {}", examples.join(", ")));
+            }
+        }
+
+        if has_require_changes(&staged_file.diff) {
+            analysis.push("Require statements have been modified".to_string());
+        }
+
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "Ruby source file"
+    }
+
+    fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
+        let mut metadata = ProjectMetadata {
+            language: Some("Ruby".to_string()),
+            ..Default::default()
+        };
+
+        if is_named(file, "Gemfile") || is_named(file, "Gemfile.lock") {
+            self.extract_gemfile_metadata(file, content, &mut metadata);
+        } else if file.ends_with(".gemspec") {
+            self.extract_gemspec_metadata(content, &mut metadata);
+        } else {
+            self.extract_ruby_file_metadata(content, &mut metadata);
+        }
+
+        metadata
+    }
+}
+
+impl RubyAnalyzer {
+    fn extract_gemfile_metadata(&self, file: &str, content: &str, metadata: &mut ProjectMetadata) {
+        metadata.build_system = Some("Bundler".to_string());
+
+        let gem_re = if is_named(file, "Gemfile.lock") {
+            Regex::new(r"(?m)^    ([\w.-]+) \(").unwrap()
+        } else {
+            Regex::new(r#"(?m)^\s*gem\s+['"]([^'"]+)['"]"#).unwrap()
+        };
+        metadata.dependencies = gem_re
+            .captures_iter(content)
+            .map(|cap| cap[1].to_string())
+            .collect();
+
+        self.detect_frameworks(&metadata.dependencies.join("\n"), metadata);
+    }
+
+    fn extract_gemspec_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
+        metadata.build_system = Some("RubyGems".to_string());
+
+        let version_re = Regex::new(r#"\.version\s*=\s*['"]([^'"]+)['"]"#).unwrap();
+        if let Some(cap) = version_re.captures(content) {
+            metadata.version = Some(cap[1].to_string());
+        }
+
+        let dependency_re =
+            Regex::new(r#"add_(?:runtime_|development_)?dependency\s*\(?\s*['"]([^'"]+)['"]"#)
+                .unwrap();
+        metadata.dependencies = dependency_re
+            .captures_iter(content)
+            .map(|cap| cap[1].to_string())
+            .collect();
+
+        self.detect_frameworks(&metadata.dependencies.join("\n"), metadata);
+    }
+
+    fn extract_ruby_file_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
+        if content.contains("ApplicationRecord")
+            || content.contains("ActiveRecord::")
+            || content.contains("Rails.")
+        {
+            metadata.framework = Some("Rails".to_string());
+        } else if content.contains("require 'sinatra'") || content.contains("require \"sinatra\"")
+        {
+            metadata.framework = Some("Sinatra".to_string());
+        }
+
+        if content.contains("RSpec.") || content.contains("require 'rails_helper'") {
+            metadata.test_framework = Some("RSpec".to_string());
+        } else if content.contains("Minitest::") {
+            metadata.test_framework = Some("Minitest".to_string());
+        }
+    }
+
+    /// Frameworks among gem names, one per line
+    fn detect_frameworks(&self, gems: &str, metadata: &mut ProjectMetadata) {
+        let has_gem = |name: &str| gems.lines().any(|gem| gem == name);
+        if has_gem("rails") {
+            metadata.framework = Some("Rails".to_string());
+        } else if has_gem("sinatra") {
+            metadata.framework = Some("Sinatra".to_string());
+        } else if has_gem("hanami") {
+            metadata.framework = Some("Hanami".to_string());
+        }
+
+        if has_gem("rspec") || has_gem("rspec-rails") {
+            metadata.test_framework = Some("RSpec".to_string());
+        } else if has_gem("minitest") {
+            metadata.test_framework = Some("Minitest".to_string());
+        }
+    }
+}
+
+fn is_named(file: &str, name: &str) -> bool {
+    file == name || file.ends_with(&format!("/{}", name))
+}
+
+fn is_migration(file: &str) -> bool {
+    file.starts_with("db/migrate/") || file.contains("/db/migrate/")
+}
+
+fn extract_modified_gems(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r#"(?m)^[+-]\s*(?:gem|\w+\.add_(?:runtime_|development_)?dependency)\s*\(?\s*['"]([^'"]+)['"]"#,
+    )
+    .unwrap();
+    unique_names(&re, diff)
+}
+
+fn has_gemspec_version_change(diff: &str) -> bool {
+    let re = Regex::new(r"(?m)^[+-]\s*\w+\.version\s*=").unwrap();
+    re.is_match(diff)
+}
+
+/// Gems whose resolved version changed, from the top-level entries of a `specs:` section
+fn extract_modified_locked_gems(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r"(?m)^[+-]    ([\w.-]+) \(").unwrap();
+    unique_names(&re, diff)
+}
+
+/// Schema statements in a migration, such as `create_table orders`
+fn extract_schema_operations(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r"(?m)^[+-]\s*((?:create|drop|rename|change)_table|(?:add|remove|rename|change)_(?:column|index|reference|foreign_key))\s*\(?\s*:(\w+)",
+    )
+    .unwrap();
+    let mut operations: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let operation = format!("{} {}", &cap[1], &cap[2]);
+        if !operations.contains(&operation) {
+            operations.push(operation);
+        }
+    }
+
+    if operations.is_empty() {
+        None
+    } else {
+        Some(operations)
+    }
+}
+
+fn extract_modified_definitions(diff: &str, keyword: &str) -> Option<Vec<String>> {
+    let re = Regex::new(&format!(r"(?m)^[+-]\s*{}\s+([A-Z][\w:]*)", keyword)).unwrap();
+    unique_names(&re, diff)
+}
+
+fn extract_modified_methods(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r"(?m)^[+-]\s*def\s+((?:self\.)?\w+[?!=]?)").unwrap();
+    unique_names(&re, diff)
+}
+
+/// Descriptions of the example groups and examples on changed lines of an RSpec file
+fn extract_modified_specs(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r#"(?m)^[+-]\s*(?:RSpec\.)?(?:describe|context|it|specify)\s*\(?\s*(?:['"]([^'"]+)['"]|([A-Z][\w:]*))"#,
+    )
+    .unwrap();
+    let mut examples: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let Some(example) = cap.get(1).or_else(|| cap.get(2)) else {
+            continue;
+        };
+        let example = example.as_str().to_string();
+        if !examples.contains(&example) {
+            examples.push(example);
+        }
+    }
+
+    if examples.is_empty() {
+        None
+    } else {
+        Some(examples)
+    }
+}
+
+fn unique_names(re: &Regex, diff: &str) -> Option<Vec<String>> {
+    let mut names: Vec<String> = Vec::new();
+    for cap in re.captures_iter(diff) {
+        let name = cap[1].to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    if names.is_empty() {
+        None
+    } else {
+        Some(names)
+    }
+}
+
+fn has_require_changes(diff: &str) -> bool {
+    let re = Regex::new(r"(?m)^[+-]\s*require(?:_relative)?\s").unwrap();
+    re.is_match(diff)
+}
@@ -0,0 +1,369 @@
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for Vue and Svelte single-file components
+pub struct SfcAnalyzer;
+
+/// Svelte runes, which look like store subscriptions
+const RUNES: &[&str] = &[
+    "props", "state", "derived", "effect", "bindable", "inspect", "host",
+];
+
+impl FileAnalyzer for SfcAnalyzer {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let svelte = file.ends_with(".svelte");
+        let lines = classify_lines(&staged_file.diff, svelte);
+        let mut analysis = Vec::new();
+
+        let mut blocks: Vec<&str> = Vec::new();
+        for line in lines.iter().filter(|line| line.changed()) {
+            if !blocks.contains(&line.block.name()) {
+                blocks.push(line.block.name());
+            }
+        }
+        if !blocks.is_empty() {
+            analysis.push(format!("Modified blocks: {}", blocks.join(", ")));
+        }
+
+        let (before, after) = (props(&lines, '-', svelte), props(&lines, '+', svelte));
+        push_difference(&mut analysis, "props", &before, &after);
+
+        let (before, after) = (events(&lines, '-'), events(&lines, '+'));
+        push_difference(&mut analysis, "emitted events", &before, &after);
+
+        if let Some(stores) = extract_store_usage(&lines, svelte) {
+            analysis.push(format!("Modified store usage: {}", stores.join(", ")));
+        }
+
+        if let Some(components) = extract_child_components(&lines) {
+            analysis.push(format!(
+                "Modified child components: {}",
+                components.join(", ")
+            ));
+        }
+
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "Single-file component"
+    }
+
+    fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
+        let typescript_re = Regex::new(r#"<script\b[^>]*\blang=["']ts["']"#).unwrap();
+        let framework = if file.ends_with(".svelte") {
+            "Svelte"
+        } else {
+            "Vue"
+        };
+        let language = if typescript_re.is_match(content) {
+            "TypeScript"
+        } else {
+            "JavaScript"
+        };
+        ProjectMetadata {
+            language: Some(language.to_string()),
+            framework: Some(framework.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Block {
+    Script,
+    /// A Vue `<template>`, or the markup outside the script and style of a Svelte component
+    Template {
+        svelte: bool,
+    },
+    Style,
+}
+
+impl Block {
+    fn name(self) -> &'static str {
+        match self {
+            Block::Script => "script",
+            Block::Template { svelte: false } => "template",
+            Block::Template { svelte: true } => "markup",
+            Block::Style => "style",
+        }
+    }
+}
+
+/// A line of the diff with its sign and the block it belongs to
+struct Line<'a> {
+    sign: char,
+    block: Block,
+    content: &'a str,
+}
+
+impl Line<'_> {
+    fn changed(&self) -> bool {
+        self.sign == '+' || self.sign == '-'
+    }
+
+    /// Whether the line is on a side of the diff, counting context lines on both
+    fn on_side(&self, sign: char) -> bool {
+        self.sign == ' ' || self.sign == sign
+    }
+}
+
+/// Assign each line of the diff to a block
+///
+/// A hunk that starts inside a block is placed by the first block tag it contains.
+fn classify_lines(diff: &str, svelte: bool) -> Vec<Line<'_>> {
+    let outside = if svelte {
+        Some(Block::Template { svelte: true })
+    } else {
+        None
+    };
+    let mut lines = Vec::new();
+    let mut block = outside;
+    let mut hunk_start = true;
+    for (index, line) in diff.lines().enumerate() {
+        let Some(sign) = line.chars().next().filter(|c| matches!(c, '+' | '-' | ' ')) else {
+            continue;
+        };
+        let content = &line[1..];
+        if content.trim_start().starts_with("@@") {
+            hunk_start = true;
+            continue;
+        }
+        if hunk_start {
+            block = guess_block(diff.lines().skip(index), svelte).or(outside);
+            hunk_start = false;
+        }
+
+        if let Some(opened) = opening_tag(content, svelte) {
+            block = Some(opened);
+        }
+        if let Some(block) = block {
+            lines.push(Line {
+                sign,
+                block,
+                content,
+            });
+        }
+        if closes_block(content) {
+            block = outside;
+        }
+    }
+    lines
+}
+
+fn opening_tag(content: &str, svelte: bool) -> Option<Block> {
+    if content.starts_with("<script") {
+        Some(Block::Script)
+    } else if content.starts_with("<style") {
+        Some(Block::Style)
+    } else if content.starts_with("<template") && !svelte {
+        Some(Block::Template { svelte })
+    } else {
+        None
+    }
+}
+
+/// Whether a line ends a top-level block; nested Vue `<template>` tags are indented
+fn closes_block(content: &str) -> bool {
+    ["</script>", "</style>", "</template>"]
+        .iter()
+        .any(|tag| content.starts_with(tag))
+}
+
+/// The block a hunk starts in, judging from the first top-level tag in it
+fn guess_block<'a>(mut lines: impl Iterator<Item = &'a str>, svelte: bool) -> Option<Block> {
+    lines.find_map(|line| {
+        let content = line.get(1..)?;
+        if content.starts_with("</script>") {
+            Some(Some(Block::Script))
+        } else if content.starts_with("</style>") {
+            Some(Some(Block::Style))
+        } else if content.starts_with("</template>") {
+            Some(Some(Block::Template { svelte }))
+        } else if opening_tag(content, svelte).is_some() {
+            Some(None)
+        } else {
+            None
+        }
+    })?
+}
+
+/// Props declared on one side of the diff: `defineProps` and the `props` option in Vue,
+/// and `export let` and `$props()` in Svelte
+fn props(lines: &[Line], sign: char, svelte: bool) -> Vec<String> {
+    let export_re = Regex::new(r"^\s*export\s+let\s+(\w+)").unwrap();
+    let runes_re = Regex::new(r"let\s*\{([^}]*)\}[^=]*=\s*\$props\(\)").unwrap();
+    let declaration_re = Regex::new(r"defineProps\b|^\s*props\s*:").unwrap();
+    let array_re = Regex::new(r"\[([^\]]*)\]").unwrap();
+    let key_re = Regex::new(r#"^\s*['"]?(\w+)['"]?\??\s*[:(,]"#).unwrap();
+    let string_re = Regex::new(r#"['"](\w+)['"]"#).unwrap();
+
+    let mut props = Vec::new();
+    // Nesting depth inside a multi-line props declaration, where depth 1 holds the prop names
+    let mut depth = 0i32;
+    for line in lines
+        .iter()
+        .filter(|line| line.block == Block::Script && line.on_side(sign))
+    {
+        let content = line.content;
+        if depth > 0 {
+            if depth == 1 {
+                if let Some(cap) = key_re.captures(content) {
+                    props.push(cap[1].to_string());
+                }
+            }
+            depth += nesting(content);
+            continue;
+        }
+
+        if svelte {
+            if let Some(cap) = export_re.captures(content) {
+                props.push(cap[1].to_string());
+            } else if let Some(cap) = runes_re.captures(content) {
+                props.extend(cap[1].split(',').filter_map(|prop| {
+                    let name = prop.split([':', '=']).next()?.trim();
+                    (!name.is_empty() && !name.starts_with("...")).then(|| name.to_string())
+                }));
+            }
+        } else if declaration_re.is_match(content) {
+            match array_re.captures(content) {
+                Some(cap) => props.extend(
+                    string_re
+                        .captures_iter(&cap[1])
+                        .map(|cap| cap[1].to_string()),
+                ),
+                None => depth = nesting(content).max(0),
+            }
+        }
+    }
+    props
+}
+
+/// Change in brace and bracket depth over a line
+fn nesting(content: &str) -> i32 {
+    content
+        .chars()
+        .map(|c| match c {
+            '{' | '[' => 1,
+            '}' | ']' => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Events declared or emitted on one side of the diff
+fn events(lines: &[Line], sign: char) -> Vec<String> {
+    let emit_re = Regex::new(r#"(?:\$?emit|dispatch)\(\s*['"]([\w:-]+)['"]"#).unwrap();
+    let declared_re =
+        Regex::new(r#"defineEmits\(\s*\[([^\]]*)\]|^\s*\(e:\s*['"]([\w:-]+)['"]"#).unwrap();
+    let string_re = Regex::new(r#"['"]([\w:-]+)['"]"#).unwrap();
+
+    let mut events: Vec<String> = Vec::new();
+    for line in lines
+        .iter()
+        .filter(|line| line.block != Block::Style && line.on_side(sign))
+    {
+        let mut names: Vec<String> = emit_re
+            .captures_iter(line.content)
+            .map(|cap| cap[1].to_string())
+            .collect();
+        if let Some(cap) = declared_re.captures(line.content) {
+            match (cap.get(1), cap.get(2)) {
+                (Some(list), _) => names.extend(
+                    string_re
+                        .captures_iter(list.as_str())
+                        .map(|cap| cap[1].to_string()),
+                ),
+                (None, Some(name)) => names.push(name.as_str().to_string()),
+                (None, None) => {}
+            }
+        }
+        for name in names {
+            if !events.contains(&name) {
+                events.push(name);
+            }
+        }
+    }
+    events
+}
+
+fn push_difference(analysis: &mut Vec<String>, label: &str, before: &[String], after: &[String]) {
+    let added: Vec<&str> = after
+        .iter()
+        .filter(|name| !before.contains(name))
+        .map(String::as_str)
+        .collect();
+    let removed: Vec<&str> = before
+        .iter()
+        .filter(|name| !after.contains(name))
+        .map(String::as_str)
+        .collect();
+    if !added.is_empty() {
+        analysis.push(format!("Added {}: {}", label, added.join(", ")));
+    }
+    if !removed.is_empty() {
+        analysis.push(format!("Removed {}: {}", label, removed.join(", ")));
+    }
+}
+
+/// Pinia and Vuex stores, and Svelte stores read through `$store` subscriptions
+fn extract_store_usage(lines: &[Line], svelte: bool) -> Option<Vec<String>> {
+    let vue_re =
+        Regex::new(r"\b(use\w*Store)\(|\b(map(?:State|Getters|Actions|Mutations))\(|(\$store)\b")
+            .unwrap();
+    let svelte_re = Regex::new(r"(?:^|[^\w.$])\$([a-zA-Z]\w*)\b").unwrap();
+
+    let mut stores: Vec<String> = Vec::new();
+    for line in lines
+        .iter()
+        .filter(|line| line.changed() && line.block != Block::Style)
+    {
+        let vue = vue_re.captures_iter(line.content).filter_map(|cap| {
+            cap.iter()
+                .skip(1)
+                .flatten()
+                .next()
+                .map(|name| name.as_str().to_string())
+        });
+        let subscriptions = svelte_re
+            .captures_iter(line.content)
+            .filter(|cap| svelte && !RUNES.contains(&&cap[1]))
+            .map(|cap| format!("${}", &cap[1]));
+        for store in vue.chain(subscriptions) {
+            if !stores.contains(&store) {
+                stores.push(store);
+            }
+        }
+    }
+
+    if stores.is_empty() {
+        None
+    } else {
+        Some(stores)
+    }
+}
+
+/// Components used in the template, which start with an upper-case letter
+fn extract_child_components(lines: &[Line]) -> Option<Vec<String>> {
+    let re = Regex::new(r"</?([A-Z][\w.]*)").unwrap();
+
+    let mut components: Vec<String> = Vec::new();
+    for line in lines
+        .iter()
+        .filter(|line| line.changed() && matches!(line.block, Block::Template { .. }))
+    {
+        for cap in re.captures_iter(line.content) {
+            let name = cap[1].to_string();
+            if !components.contains(&name) {
+                components.push(name);
+            }
+        }
+    }
+
+    if components.is_empty() {
+        None
+    } else {
+        Some(components)
+    }
+}
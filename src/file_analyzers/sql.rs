@@ -0,0 +1,331 @@
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for SQL files and Django migrations, summarizing the schema changes they make
+pub struct SqlAnalyzer;
+
+impl FileAnalyzer for SqlAnalyzer {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let mut analysis = Vec::new();
+
+        // Only added lines run against the database; removed ones no longer will
+        let added = added_text(&staged_file.diff);
+        let changes = if is_django_migration(file) {
+            django_schema_changes(&added)
+        } else {
+            sql_schema_changes(&added)
+        };
+
+        if !changes.destructive.is_empty() {
+            analysis.push(format!(
+                "Destructive operations (data may be lost): {}",
+                changes.destructive.join(", ")
+            ));
+        }
+        if let Some(tool) = migration_tool(file) {
+            analysis.push(format!("Database migration ({})", tool));
+        }
+        for (label, names) in [
+            ("Tables added", &changes.tables_added),
+            ("Tables dropped", &changes.tables_dropped),
+            ("Tables altered", &changes.tables_altered),
+            ("Columns added", &changes.columns_added),
+            ("Columns dropped", &changes.columns_dropped),
+            ("Columns altered", &changes.columns_altered),
+            ("Indexes added", &changes.indexes_added),
+            ("Indexes dropped", &changes.indexes_dropped),
+        ] {
+            if !names.is_empty() {
+                analysis.push(format!("{}: {}", label, names.join(", ")));
+            }
+        }
+
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "SQL or migration file"
+    }
+
+    fn extract_metadata(&self, file: &str, _content: &str) -> ProjectMetadata {
+        if is_django_migration(file) {
+            ProjectMetadata {
+                language: Some("Python".to_string()),
+                framework: Some("Django".to_string()),
+                ..Default::default()
+            }
+        } else {
+            ProjectMetadata {
+                language: Some("SQL".to_string()),
+                build_system: migration_tool(file).map(str::to_string),
+                ..Default::default()
+            }
+        }
+    }
+}
+
+/// What a migration or SQL script does to the schema; columns are written `table.column`
+#[derive(Default)]
+struct SchemaChanges {
+    tables_added: Vec<String>,
+    tables_dropped: Vec<String>,
+    tables_altered: Vec<String>,
+    columns_added: Vec<String>,
+    columns_dropped: Vec<String>,
+    columns_altered: Vec<String>,
+    indexes_added: Vec<String>,
+    indexes_dropped: Vec<String>,
+    /// Statements that drop or delete data, such as `DROP TABLE orders`
+    destructive: Vec<String>,
+}
+
+fn push_unique(names: &mut Vec<String>, name: String) {
+    if !names.contains(&name) {
+        names.push(name);
+    }
+}
+
+fn is_django_migration(file: &str) -> bool {
+    file.ends_with(".py") && file.contains("migrations/") && !file.ends_with("__init__.py")
+}
+
+/// The migration tool a file belongs to, going by the naming conventions of each
+fn migration_tool(file: &str) -> Option<&'static str> {
+    let name = file.rsplit('/').next().unwrap_or(file);
+    let flyway_re = Regex::new(r"^[VUR]\d+(?:[._]\d+)*__\w+\.sql$").unwrap();
+    let diesel_re = Regex::new(r"(?:^|/)migrations/[\d_-]+\w*/(?:up|down)\.sql$").unwrap();
+    let sqlx_re = Regex::new(r"(?:^|/)migrations/\d+_\w+?(?:\.up|\.down)?\.sql$").unwrap();
+
+    if is_django_migration(file) {
+        Some("Django")
+    } else if flyway_re.is_match(name) {
+        Some("Flyway")
+    } else if diesel_re.is_match(file) {
+        Some("Diesel")
+    } else if sqlx_re.is_match(file) {
+        Some("sqlx")
+    } else {
+        None
+    }
+}
+
+/// The added lines of a diff without their `+`, so that statements spanning several lines
+/// can be matched
+fn added_text(diff: &str) -> String {
+    diff.lines()
+        .filter(|line| line.starts_with('+') && !line.starts_with("+++"))
+        .map(|line| &line[1..])
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// An identifier without its quotes or brackets
+fn unquote(name: &str) -> String {
+    name.trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'))
+        .replace(['"', '`', '[', ']'], "")
+}
+
+const IDENTIFIER: &str = r#"[\w."`\[\]]+"#;
+
+fn sql_schema_changes(sql: &str) -> SchemaChanges {
+    let mut changes = SchemaChanges::default();
+
+    let create_table_re = Regex::new(&format!(
+        r"(?i)\bCREATE\s+(?:TEMP(?:ORARY)?\s+)?TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?({})",
+        IDENTIFIER
+    ))
+    .unwrap();
+    for cap in create_table_re.captures_iter(sql) {
+        push_unique(&mut changes.tables_added, unquote(&cap[1]));
+    }
+
+    let drop_table_re = Regex::new(&format!(
+        r"(?i)\bDROP\s+TABLE\s+(?:IF\s+EXISTS\s+)?({})",
+        IDENTIFIER
+    ))
+    .unwrap();
+    for cap in drop_table_re.captures_iter(sql) {
+        let table = unquote(&cap[1]);
+        push_unique(&mut changes.destructive, format!("DROP TABLE {}", table));
+        push_unique(&mut changes.tables_dropped, table);
+    }
+
+    let truncate_re =
+        Regex::new(&format!(r"(?i)\bTRUNCATE\s+(?:TABLE\s+)?({})", IDENTIFIER)).unwrap();
+    for cap in truncate_re.captures_iter(sql) {
+        push_unique(
+            &mut changes.destructive,
+            format!("TRUNCATE {}", unquote(&cap[1])),
+        );
+    }
+
+    let drop_schema_re = Regex::new(&format!(
+        r"(?i)\bDROP\s+(SCHEMA|DATABASE)\s+(?:IF\s+EXISTS\s+)?({})",
+        IDENTIFIER
+    ))
+    .unwrap();
+    for cap in drop_schema_re.captures_iter(sql) {
+        push_unique(
+            &mut changes.destructive,
+            format!("DROP {} {}", cap[1].to_uppercase(), unquote(&cap[2])),
+        );
+    }
+
+    let alter_table_re = Regex::new(&format!(
+        r"(?is)\bALTER\s+TABLE\s+(?:IF\s+EXISTS\s+)?(?:ONLY\s+)?({})\s+([^;]*)",
+        IDENTIFIER
+    ))
+    .unwrap();
+    for cap in alter_table_re.captures_iter(sql) {
+        alter_table_changes(&unquote(&cap[1]), &cap[2], &mut changes);
+    }
+
+    let create_index_re = Regex::new(&format!(
+        r"(?i)\bCREATE\s+(?:UNIQUE\s+)?INDEX\s+(?:CONCURRENTLY\s+)?(?:IF\s+NOT\s+EXISTS\s+)?({})\s+ON\s+(?:ONLY\s+)?({})",
+        IDENTIFIER, IDENTIFIER
+    ))
+    .unwrap();
+    for cap in create_index_re.captures_iter(sql) {
+        let index = format!("{} on {}", unquote(&cap[1]), unquote(&cap[2]));
+        push_unique(&mut changes.indexes_added, index);
+    }
+
+    let drop_index_re = Regex::new(&format!(
+        r"(?i)\bDROP\s+INDEX\s+(?:CONCURRENTLY\s+)?(?:IF\s+EXISTS\s+)?({})",
+        IDENTIFIER
+    ))
+    .unwrap();
+    for cap in drop_index_re.captures_iter(sql) {
+        push_unique(&mut changes.indexes_dropped, unquote(&cap[1]));
+    }
+
+    changes
+}
+
+/// The clauses of one `ALTER TABLE` statement
+fn alter_table_changes(table: &str, clauses: &str, changes: &mut SchemaChanges) {
+    let add_re = Regex::new(&format!(
+        r"(?i)\bADD\s+(?:COLUMN\s+)?(?:IF\s+NOT\s+EXISTS\s+)?({})",
+        IDENTIFIER
+    ))
+    .unwrap();
+    let drop_re = Regex::new(&format!(
+        r"(?i)\bDROP\s+(?:COLUMN\s+)?(?:IF\s+EXISTS\s+)?({})",
+        IDENTIFIER
+    ))
+    .unwrap();
+    let alter_re = Regex::new(&format!(
+        r"(?i)\b(?:ALTER|MODIFY|CHANGE)\s+(?:COLUMN\s+)?({})",
+        IDENTIFIER
+    ))
+    .unwrap();
+    let rename_column_re = Regex::new(&format!(
+        r"(?i)\bRENAME\s+(?:COLUMN\s+)?({})\s+TO\s+({})",
+        IDENTIFIER, IDENTIFIER
+    ))
+    .unwrap();
+    let rename_table_re = Regex::new(&format!(r"(?i)\bRENAME\s+TO\s+({})", IDENTIFIER)).unwrap();
+    // Constraints and keys are added and dropped with the same keywords as columns
+    let is_keyword = |name: &str| {
+        matches!(
+            name.to_uppercase().as_str(),
+            "CONSTRAINT"
+                | "PRIMARY"
+                | "FOREIGN"
+                | "UNIQUE"
+                | "CHECK"
+                | "INDEX"
+                | "KEY"
+                | "NOT"
+                | "DEFAULT"
+                | "TO"
+        )
+    };
+
+    let mut found = false;
+    for cap in add_re.captures_iter(clauses) {
+        if !is_keyword(&cap[1]) {
+            push_unique(
+                &mut changes.columns_added,
+                format!("{}.{}", table, unquote(&cap[1])),
+            );
+            found = true;
+        }
+    }
+    for cap in drop_re.captures_iter(clauses) {
+        if !is_keyword(&cap[1]) {
+            let column = format!("{}.{}", table, unquote(&cap[1]));
+            push_unique(&mut changes.destructive, format!("DROP COLUMN {}", column));
+            push_unique(&mut changes.columns_dropped, column);
+            found = true;
+        }
+    }
+    for cap in alter_re.captures_iter(clauses) {
+        if !is_keyword(&cap[1]) {
+            push_unique(
+                &mut changes.columns_altered,
+                format!("{}.{}", table, unquote(&cap[1])),
+            );
+            found = true;
+        }
+    }
+    if let Some(cap) = rename_table_re.captures(clauses) {
+        push_unique(
+            &mut changes.tables_altered,
+            format!("{} (renamed to {})", table, unquote(&cap[1])),
+        );
+        return;
+    }
+    for cap in rename_column_re.captures_iter(clauses) {
+        push_unique(
+            &mut changes.columns_altered,
+            format!(
+                "{}.{} (renamed to {})",
+                table,
+                unquote(&cap[1]),
+                unquote(&cap[2])
+            ),
+        );
+        found = true;
+    }
+    if !found {
+        push_unique(&mut changes.tables_altered, table.to_string());
+    }
+}
+
+fn django_schema_changes(source: &str) -> SchemaChanges {
+    let mut changes = SchemaChanges::default();
+    let operation_re = Regex::new(
+        r#"migrations\.(\w+)\(\s*(?:model_name|name|old_name)\s*=\s*['"](\w+)['"](?:\s*,\s*(?:name|old_name|new_name)\s*=\s*['"](\w+)['"])?"#,
+    )
+    .unwrap();
+
+    for cap in operation_re.captures_iter(source) {
+        let model = cap[2].to_string();
+        let field = cap.get(3).map(|m| format!("{}.{}", model, m.as_str()));
+        match (&cap[1], field) {
+            ("CreateModel", _) => push_unique(&mut changes.tables_added, model),
+            ("DeleteModel", _) => {
+                push_unique(&mut changes.destructive, format!("DeleteModel {}", model));
+                push_unique(&mut changes.tables_dropped, model);
+            }
+            ("RenameModel", _) | ("AlterModelTable", _) | ("AlterModelOptions", _) => {
+                push_unique(&mut changes.tables_altered, model)
+            }
+            ("AddField", Some(field)) => push_unique(&mut changes.columns_added, field),
+            ("RemoveField", Some(field)) => {
+                push_unique(&mut changes.destructive, format!("RemoveField {}", field));
+                push_unique(&mut changes.columns_dropped, field);
+            }
+            ("AlterField", Some(field)) | ("RenameField", Some(field)) => {
+                push_unique(&mut changes.columns_altered, field)
+            }
+            ("AddIndex", _) => push_unique(&mut changes.indexes_added, model),
+            ("RemoveIndex", Some(field)) => push_unique(&mut changes.indexes_dropped, field),
+            _ => {}
+        }
+    }
+
+    changes
+}
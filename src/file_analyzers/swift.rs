@@ -0,0 +1,277 @@
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for Swift sources, `Package.swift` manifests, and Xcode project files
+pub struct SwiftAnalyzer;
+
+impl FileAnalyzer for SwiftAnalyzer {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let mut analysis = Vec::new();
+
+        if is_package_manifest(file) {
+            if let Some(packages) = extract_modified_packages(&staged_file.diff) {
+                analysis.push(format!(
+                    "Modified package dependencies: {}",
+                    packages.join(", ")
+                ));
+            }
+            if let Some(targets) = extract_modified_targets(&staged_file.diff) {
+                analysis.push(format!("Modified targets: {}", targets.join(", ")));
+            }
+            if has_tools_version_change(&staged_file.diff) {
+                analysis.push("Swift tools version has been modified".to_string());
+            }
+            return analysis;
+        }
+
+        if is_xcode_project(file) {
+            if let Some(packages) = extract_modified_project_packages(&staged_file.diff) {
+                analysis.push(format!(
+                    "Modified package dependencies: {}",
+                    packages.join(", ")
+                ));
+            }
+            if let Some(settings) = extract_modified_build_settings(&staged_file.diff) {
+                analysis.push(format!("Modified build settings: {}", settings.join(", ")));
+            }
+            if has_file_reference_changes(&staged_file.diff) {
+                analysis.push("Project file references have been modified".to_string());
+            }
+            return analysis;
+        }
+
+        if let Some(types) = extract_modified_types(&staged_file.diff) {
+            analysis.push(format!("Modified types: {}", types.join(", ")));
+        }
+
+        if let Some(conformances) = extract_modified_conformances(&staged_file.diff) {
+            analysis.push(format!(
+                "Modified protocol conformances: {}",
+                conformances.join(", ")
+            ));
+        }
+
+        if let Some(views) = extract_modified_views(&staged_file.diff) {
+            analysis.push(format!("Modified SwiftUI views: {}", views.join(", ")));
+        } else if has_view_body_changes(&staged_file.diff) {
+            analysis.push("SwiftUI view bodies have been modified".to_string());
+        }
+
+        if let Some(functions) = extract_modified_functions(&staged_file.diff) {
+            analysis.push(format!("Modified functions: {}", functions.join(", ")));
+        }
+
+        if has_import_changes(&staged_file.diff) {
+            analysis.push("Import statements have been modified".to_string());
+        }
+
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "Swift source file"
+    }
+
+    fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
+        let mut metadata = ProjectMetadata {
+            language: Some("Swift".to_string()),
+            ..Default::default()
+        };
+
+        if is_package_manifest(file) {
+            self.extract_manifest_metadata(content, &mut metadata);
+        } else if is_xcode_project(file) {
+            metadata.build_system = Some("Xcode".to_string());
+            let package_re = Regex::new(r#"XCRemoteSwiftPackageReference "([^"]+)""#).unwrap();
+            for cap in package_re.captures_iter(content) {
+                if !metadata.dependencies.contains(&cap[1].to_string()) {
+                    metadata.dependencies.push(cap[1].to_string());
+                }
+            }
+        } else {
+            self.extract_swift_file_metadata(content, &mut metadata);
+        }
+
+        metadata
+    }
+}
+
+impl SwiftAnalyzer {
+    fn extract_manifest_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
+        metadata.build_system = Some("Swift Package Manager".to_string());
+
+        let package_re =
+            Regex::new(r#"\.package\(\s*(?:name:\s*"[^"]*",\s*)?url:\s*"([^"]+)""#).unwrap();
+        metadata.dependencies = package_re
+            .captures_iter(content)
+            .map(|cap| package_name(&cap[1]))
+            .collect();
+
+        if metadata.dependencies.iter().any(|dep| dep == "vapor") {
+            metadata.framework = Some("Vapor".to_string());
+        }
+        if content.contains(".testTarget(") {
+            metadata.test_framework = Some("XCTest".to_string());
+        }
+    }
+
+    fn extract_swift_file_metadata(&self, content: &str, metadata: &mut ProjectMetadata) {
+        if content.contains("import SwiftUI") {
+            metadata.framework = Some("SwiftUI".to_string());
+        } else if content.contains("import UIKit") {
+            metadata.framework = Some("UIKit".to_string());
+        } else if content.contains("import Vapor") {
+            metadata.framework = Some("Vapor".to_string());
+        }
+
+        if content.contains("import XCTest") {
+            metadata.test_framework = Some("XCTest".to_string());
+        } else if content.contains("import Testing") {
+            metadata.test_framework = Some("Swift Testing".to_string());
+        }
+    }
+}
+
+fn is_package_manifest(file: &str) -> bool {
+    file == "Package.swift" || file.ends_with("/Package.swift")
+}
+
+fn is_xcode_project(file: &str) -> bool {
+    file.ends_with(".pbxproj")
+}
+
+/// The repository name at the end of a package URL
+fn package_name(url: &str) -> String {
+    let name = url.trim_end_matches('/').rsplit('/').next().unwrap_or(url);
+    name.trim_end_matches(".git").to_string()
+}
+
+fn extract_modified_packages(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r#"(?m)^[+-]\s*\.package\(\s*(?:name:\s*"[^"]*",\s*)?url:\s*"([^"]+)""#)
+        .unwrap();
+    unique(re.captures_iter(diff).map(|cap| package_name(&cap[1])))
+}
+
+fn extract_modified_targets(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r#"(?m)^[+-]\s*\.(?:target|executableTarget|testTarget|binaryTarget|macro)\(\s*name:\s*"([^"]+)""#,
+    )
+    .unwrap();
+    unique(re.captures_iter(diff).map(|cap| cap[1].to_string()))
+}
+
+fn has_tools_version_change(diff: &str) -> bool {
+    let re = Regex::new(r"(?m)^[+-]\s*// swift-tools-version").unwrap();
+    re.is_match(diff)
+}
+
+fn extract_modified_project_packages(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r#"(?m)^[+-].*XCRemoteSwiftPackageReference "([^"]+)""#).unwrap();
+    unique(re.captures_iter(diff).map(|cap| cap[1].to_string()))
+}
+
+fn extract_modified_build_settings(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(r"(?m)^[+-]\s*([A-Z][A-Z0-9_]+)\s*=").unwrap();
+    unique(re.captures_iter(diff).map(|cap| cap[1].to_string()))
+}
+
+fn has_file_reference_changes(diff: &str) -> bool {
+    let re = Regex::new(r"(?m)^[+-].*isa = PBX(?:FileReference|BuildFile)").unwrap();
+    re.is_match(diff)
+}
+
+/// A type or extension declared on a changed line
+struct TypeDeclaration {
+    keyword: String,
+    name: String,
+    /// Protocols the type conforms to, as written after the colon
+    protocols: Option<String>,
+}
+
+fn extract_type_declarations(diff: &str) -> Vec<TypeDeclaration> {
+    let re = Regex::new(
+        r"(?m)^[+-]\s*(?:(?:public|private|fileprivate|internal|open|final|indirect)\s+)*(class|struct|enum|protocol|actor|extension)\s+([\w.]+)(?:<[^>\n]*>)?(?:\s*:\s*([^{\n]+))?",
+    )
+    .unwrap();
+    re.captures_iter(diff)
+        // `class func` and `class var` declare members rather than a class
+        .filter(|cap| !matches!(&cap[2], "func" | "var" | "let"))
+        .map(|cap| TypeDeclaration {
+            keyword: cap[1].to_string(),
+            name: cap[2].to_string(),
+            protocols: cap.get(3).and_then(|protocols| {
+                let protocols = protocols.as_str().split(" where ").next()?.trim();
+                (!protocols.is_empty()).then(|| protocols.to_string())
+            }),
+        })
+        .collect()
+}
+
+fn extract_modified_types(diff: &str) -> Option<Vec<String>> {
+    unique(
+        extract_type_declarations(diff)
+            .into_iter()
+            .filter(|declaration| declaration.keyword != "extension")
+            .map(|declaration| declaration.name),
+    )
+}
+
+fn extract_modified_conformances(diff: &str) -> Option<Vec<String>> {
+    unique(
+        extract_type_declarations(diff)
+            .into_iter()
+            .filter_map(|declaration| {
+                Some(format!("{} ({})", declaration.name, declaration.protocols?))
+            }),
+    )
+}
+
+/// Types on changed lines that conform to `View`
+fn extract_modified_views(diff: &str) -> Option<Vec<String>> {
+    unique(
+        extract_type_declarations(diff)
+            .into_iter()
+            .filter(|declaration| {
+                declaration.protocols.as_deref().is_some_and(|protocols| {
+                    protocols
+                        .split(',')
+                        .any(|protocol| protocol.trim() == "View")
+                })
+            })
+            .map(|declaration| declaration.name),
+    )
+}
+
+fn has_view_body_changes(diff: &str) -> bool {
+    let re = Regex::new(r"(?m)^[+-].*\bsome View\b").unwrap();
+    re.is_match(diff)
+}
+
+fn extract_modified_functions(diff: &str) -> Option<Vec<String>> {
+    let re = Regex::new(
+        r"(?m)^[+-]\s*(?:@\w+\s+)*(?:(?:public|private|fileprivate|internal|open|static|class|override|mutating|final)\s+)*func\s+(\w+)",
+    )
+    .unwrap();
+    unique(re.captures_iter(diff).map(|cap| cap[1].to_string()))
+}
+
+fn unique(names: impl Iterator<Item = String>) -> Option<Vec<String>> {
+    let mut unique: Vec<String> = Vec::new();
+    for name in names {
+        if !unique.contains(&name) {
+            unique.push(name);
+        }
+    }
+
+    if unique.is_empty() {
+        None
+    } else {
+        Some(unique)
+    }
+}
+
+fn has_import_changes(diff: &str) -> bool {
+    let re = Regex::new(r"(?m)^[+-]\s*import\s+\w+").unwrap();
+    re.is_match(diff)
+}
@@ -0,0 +1,341 @@
+use std::collections::BTreeMap;
+use tree_sitter::{Language, Node, Parser};
+
+/// Lines of the regex analyzers that guess at the symbols a diff touches, which are left out
+/// when the symbols are compared precisely
+pub(super) const SUPERSEDED: &[&str] = &[
+    "Modified functions:",
+    "Modified exported functions:",
+    "Added exported functions:",
+    "Modified classes:",
+    "Modified structs:",
+    "Modified exported structs:",
+    "Modified exported interfaces:",
+    "Modified traits:",
+    "Modified types and interfaces:",
+    "Modified custom hooks:",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SymbolKind {
+    Function,
+    Type,
+}
+
+/// What a syntax node means for the symbols of a file
+#[derive(Debug, Clone, Copy)]
+enum Role {
+    /// Defines a symbol
+    Symbol(SymbolKind),
+    /// Groups symbols under its name without being one, like a Rust `impl` block
+    Container,
+    /// Defines a symbol that has members of its own, like a class
+    SymbolContainer(SymbolKind),
+}
+
+/// A language tree-sitter parses, with the nodes that define symbols in it
+struct Grammar {
+    language: fn() -> Language,
+    roles: &'static [(&'static str, Role)],
+    /// Separator between the name of a container and those of its members
+    separator: &'static str,
+}
+
+const FUNCTION: Role = Role::Symbol(SymbolKind::Function);
+const TYPE: Role = Role::Symbol(SymbolKind::Type);
+const CLASS: Role = Role::SymbolContainer(SymbolKind::Type);
+
+const RUST: Grammar = Grammar {
+    language: || tree_sitter_rust::LANGUAGE.into(),
+    roles: &[
+        ("function_item", FUNCTION),
+        ("function_signature_item", FUNCTION),
+        ("struct_item", TYPE),
+        ("enum_item", TYPE),
+        ("union_item", TYPE),
+        ("type_item", TYPE),
+        ("trait_item", CLASS),
+        ("impl_item", Role::Container),
+        ("mod_item", Role::Container),
+    ],
+    separator: "::",
+};
+
+const PYTHON: Grammar = Grammar {
+    language: || tree_sitter_python::LANGUAGE.into(),
+    roles: &[
+        ("function_definition", FUNCTION),
+        ("class_definition", CLASS),
+    ],
+    separator: ".",
+};
+
+const JAVASCRIPT_ROLES: &[(&str, Role)] = &[
+    ("function_declaration", FUNCTION),
+    ("generator_function_declaration", FUNCTION),
+    ("method_definition", FUNCTION),
+    ("variable_declarator", FUNCTION),
+    ("class_declaration", CLASS),
+];
+
+const JAVASCRIPT: Grammar = Grammar {
+    language: || tree_sitter_javascript::LANGUAGE.into(),
+    roles: JAVASCRIPT_ROLES,
+    separator: ".",
+};
+
+const TYPESCRIPT_ROLES: &[(&str, Role)] = &[
+    ("function_declaration", FUNCTION),
+    ("generator_function_declaration", FUNCTION),
+    ("function_signature", FUNCTION),
+    ("method_definition", FUNCTION),
+    ("variable_declarator", FUNCTION),
+    ("class_declaration", CLASS),
+    ("abstract_class_declaration", CLASS),
+    ("interface_declaration", TYPE),
+    ("type_alias_declaration", TYPE),
+    ("enum_declaration", TYPE),
+];
+
+const TYPESCRIPT: Grammar = Grammar {
+    language: || tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+    roles: TYPESCRIPT_ROLES,
+    separator: ".",
+};
+
+const TSX: Grammar = Grammar {
+    language: || tree_sitter_typescript::LANGUAGE_TSX.into(),
+    roles: TYPESCRIPT_ROLES,
+    separator: ".",
+};
+
+const GO: Grammar = Grammar {
+    language: || tree_sitter_go::LANGUAGE.into(),
+    roles: &[
+        ("function_declaration", FUNCTION),
+        ("method_declaration", FUNCTION),
+        ("type_spec", TYPE),
+        ("type_alias", TYPE),
+    ],
+    separator: ".",
+};
+
+impl Grammar {
+    fn role(&self, kind: &str) -> Option<Role> {
+        self.roles
+            .iter()
+            .find(|(node, _)| *node == kind)
+            .map(|(_, role)| *role)
+    }
+}
+
+/// The grammar for a file, if tree-sitter parses its language
+fn grammar_for(file: &str) -> Option<&'static Grammar> {
+    let extension = file.rsplit('.').next()?;
+    match extension {
+        "rs" => Some(&RUST),
+        // Python migrations are read by the SQL analyzer instead
+        "py" if !file.contains("migrations/") => Some(&PYTHON),
+        "js" | "jsx" | "mjs" | "cjs" => Some(&JAVASCRIPT),
+        "ts" | "mts" | "cts" => Some(&TYPESCRIPT),
+        "tsx" => Some(&TSX),
+        "go" => Some(&GO),
+        _ => None,
+    }
+}
+
+/// Whether a file's symbols are compared by parsing it
+pub(super) fn supports(file: &str) -> bool {
+    grammar_for(file).is_some()
+}
+
+/// A function or type, with its text collapsed so reformatting alone doesn't change it
+struct Symbol {
+    kind: SymbolKind,
+    signature: String,
+    text: String,
+}
+
+/// Compare the functions and types defined before and after a change, or `None` when either
+/// side doesn't parse cleanly
+pub(super) fn analyze_contents(
+    file: &str,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> Option<Vec<String>> {
+    let grammar = grammar_for(file)?;
+    let before = parse_symbols(grammar, old.unwrap_or_default())?;
+    let after = parse_symbols(grammar, new.unwrap_or_default())?;
+
+    let mut analysis = Vec::new();
+    let report = |analysis: &mut Vec<String>, label: &str, names: Vec<String>| {
+        if !names.is_empty() {
+            analysis.push(format!("{}: {}", label, names.join(", ")));
+        }
+    };
+
+    for (kind, plural) in [
+        (SymbolKind::Function, "Functions"),
+        (SymbolKind::Type, "Types"),
+    ] {
+        let names = |symbols: &BTreeMap<String, Symbol>, others: &BTreeMap<String, Symbol>| {
+            symbols
+                .iter()
+                .filter(|(name, symbol)| symbol.kind == kind && !others.contains_key(*name))
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>()
+        };
+        report(
+            &mut analysis,
+            &format!("{} added", plural),
+            names(&after, &before),
+        );
+        report(
+            &mut analysis,
+            &format!("{} removed", plural),
+            names(&before, &after),
+        );
+
+        let mut signatures = Vec::new();
+        let mut modified = Vec::new();
+        for (name, old) in before.iter().filter(|(_, symbol)| symbol.kind == kind) {
+            let Some(new) = after.get(name).filter(|symbol| symbol.kind == kind) else {
+                continue;
+            };
+            if kind == SymbolKind::Function && old.signature != new.signature {
+                signatures.push(format!("{} → {}", old.signature, new.signature));
+            } else if old.text != new.text {
+                modified.push(name.clone());
+            }
+        }
+        if kind == SymbolKind::Function && !signatures.is_empty() {
+            analysis.push(format!("Signatures changed: {}", signatures.join("; ")));
+        }
+        report(&mut analysis, &format!("{} modified", plural), modified);
+    }
+
+    Some(analysis)
+}
+
+/// The symbols defined in a source file, keyed by their names qualified with those of the
+/// types or modules they belong to
+fn parse_symbols(grammar: &Grammar, source: &str) -> Option<BTreeMap<String, Symbol>> {
+    let mut parser = Parser::new();
+    parser.set_language(&(grammar.language)()).ok()?;
+    let tree = parser.parse(source, None)?;
+    let root = tree.root_node();
+    if root.has_error() {
+        return None;
+    }
+
+    let mut symbols = BTreeMap::new();
+    collect_symbols(grammar, root, source, None, &mut symbols);
+    Some(symbols)
+}
+
+fn collect_symbols(
+    grammar: &Grammar,
+    node: Node,
+    source: &str,
+    scope: Option<&str>,
+    symbols: &mut BTreeMap<String, Symbol>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let Some(role) = grammar.role(child.kind()) else {
+            collect_symbols(grammar, child, source, scope, symbols);
+            continue;
+        };
+        let Some(name) = symbol_name(child, source) else {
+            if matches!(role, Role::Container) {
+                collect_symbols(grammar, child, source, scope, symbols);
+            }
+            continue;
+        };
+        let qualified = match scope {
+            Some(scope) => format!("{}{}{}", scope, grammar.separator, name),
+            None => name,
+        };
+
+        if let Role::Symbol(kind) | Role::SymbolContainer(kind) = role {
+            // A name defined twice, such as under a condition, keeps its first definition
+            symbols.entry(qualified.clone()).or_insert_with(|| Symbol {
+                kind,
+                signature: signature(child, source),
+                text: collapse(text(child, source)),
+            });
+        }
+        if let Role::Container | Role::SymbolContainer(_) = role {
+            collect_symbols(grammar, child, source, Some(qualified.as_str()), symbols);
+        }
+    }
+}
+
+/// The name a node defines, or `None` for anonymous definitions and variables that don't
+/// hold functions
+fn symbol_name(node: Node, source: &str) -> Option<String> {
+    let field = |name: &str| {
+        node.child_by_field_name(name)
+            .map(|child| text(child, source))
+    };
+    match node.kind() {
+        // `impl Display for Config` names its members `<Config as Display>::fmt`
+        "impl_item" => {
+            let implemented = field("type")?;
+            Some(match field("trait") {
+                Some(implemented_trait) => format!("<{} as {}>", implemented, implemented_trait),
+                None => implemented.to_string(),
+            })
+        }
+        // Go methods are named after their receiver's type, as in `Server.Start`
+        "method_declaration" => {
+            let receiver = node
+                .child_by_field_name("receiver")
+                .and_then(|receiver| receiver.named_child(0))
+                .and_then(|parameter| parameter.child_by_field_name("type"))
+                .map(|receiver_type| text(receiver_type, source))?;
+            let receiver = receiver.trim_start_matches('*');
+            let receiver = receiver.split('[').next().unwrap_or(receiver);
+            Some(format!("{}.{}", receiver, field("name")?))
+        }
+        "variable_declarator" => {
+            let value = node.child_by_field_name("value")?;
+            if !matches!(
+                value.kind(),
+                "arrow_function" | "function" | "function_expression" | "generator_function"
+            ) {
+                return None;
+            }
+            field("name").map(str::to_string)
+        }
+        _ => field("name").map(str::to_string),
+    }
+}
+
+/// The declaration of a symbol without its body, as in `fn parse(input: &str) -> Ast`
+fn signature(node: Node, source: &str) -> String {
+    let body = match node.kind() {
+        "variable_declarator" => node
+            .child_by_field_name("value")
+            .and_then(|value| value.child_by_field_name("body")),
+        _ => node.child_by_field_name("body"),
+    };
+    let end = body.map_or(node.end_byte(), |body| body.start_byte());
+    let declaration = collapse(&source[node.start_byte()..end]);
+    let declaration = declaration.trim_end();
+    let declaration = declaration.strip_suffix("=>").unwrap_or(declaration);
+    declaration
+        .trim_end()
+        .trim_end_matches([':', ';'])
+        .trim_end()
+        .to_string()
+}
+
+fn text<'a>(node: Node, source: &'a str) -> &'a str {
+    &source[node.start_byte()..node.end_byte()]
+}
+
+/// Text with each run of whitespace made a single space
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -1,27 +1,451 @@
-use super::FileAnalyzer;
-use crate::git::FileChange;
+use super::dependencies::DependencyChanges;
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+use std::collections::BTreeMap;
 
-/// Analyzer for TOML configuration files
+/// Analyzer for TOML configuration files, with `Cargo.toml` and `Cargo.lock` compared in full
 pub struct TomlAnalyzer;
 
-impl FileAnalyzer for TomlAnalyzer {
-    fn analyze(&self, file: &str, change: &FileChange) -> Vec<String> {
-        let mut analysis = Vec::new();
+/// Dependency tables of a Cargo manifest, with the label shown for each
+const DEPENDENCY_TABLES: &[(&str, &str)] = &[
+    ("dependencies", ""),
+    ("dev-dependencies", " (dev)"),
+    ("build-dependencies", " (build)"),
+];
 
-        // Check for dependency changes in Cargo.toml
-        if file.ends_with("Cargo.toml") && has_dependency_changes(&change.diff) {
-            analysis.push("Dependencies updated".to_string());
+impl FileAnalyzer for TomlAnalyzer {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let diff = &staged_file.diff;
+        match file_name(file) {
+            "Cargo.toml" => {
+                let changes = DependencyChanges::between(
+                    &diff_dependencies(diff, '-'),
+                    &diff_dependencies(diff, '+'),
+                );
+                changes.describe()
+            }
+            "Cargo.lock" => DependencyChanges::between(
+                &diff_lock_packages(diff, '-'),
+                &diff_lock_packages(diff, '+'),
+            )
+            .describe(),
+            _ => {
+                let mut analysis = Vec::new();
+                if let Some(tables) = extract_modified_tables(diff) {
+                    analysis.push(format!("Modified tables: {}", tables.join(", ")));
+                }
+                analysis
+            }
         }
-
-        analysis
     }
 
     fn get_file_type(&self) -> &'static str {
         "TOML configuration file"
     }
+
+    fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
+        if file_name(file) != "Cargo.toml" {
+            return ProjectMetadata::default();
+        }
+        let mut metadata = ProjectMetadata {
+            language: Some("Rust".to_string()),
+            build_system: Some("Cargo".to_string()),
+            ..Default::default()
+        };
+        let Ok(manifest) = content.parse::<toml::Table>() else {
+            return metadata;
+        };
+
+        metadata.version = manifest
+            .get("package")
+            .and_then(|package| package.get("version"))
+            .and_then(toml::Value::as_str)
+            .map(str::to_string);
+        let dependencies: Vec<String> = manifest
+            .get("dependencies")
+            .and_then(toml::Value::as_table)
+            .map(|table| table.keys().cloned().collect())
+            .unwrap_or_default();
+        metadata.framework = ["rocket", "actix-web", "axum", "warp", "tauri", "bevy"]
+            .iter()
+            .find(|framework| dependencies.iter().any(|name| name == *framework))
+            .map(|framework| framework.to_string());
+        metadata.dependencies = dependencies;
+        metadata
+    }
+}
+
+fn file_name(file: &str) -> &str {
+    file.rsplit('/').next().unwrap_or(file)
+}
+
+/// Whether a file is compared in full by `analyze_contents`
+pub(super) fn is_cargo_file(file: &str) -> bool {
+    matches!(file_name(file), "Cargo.toml" | "Cargo.lock")
 }
 
-/// Check if the diff contains dependency changes
-fn has_dependency_changes(diff: &str) -> bool {
-    diff.contains("[dependencies]") || diff.contains("version =")
+/// Compare a Cargo manifest or lockfile before and after a change, or `None` when either
+/// side isn't valid TOML
+pub(super) fn analyze_contents(
+    file: &str,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> Option<Vec<String>> {
+    let parse = |content: Option<&str>| -> Option<toml::Table> {
+        content.map_or(Some(toml::Table::new()), |content| content.parse().ok())
+    };
+    let (old, new) = (parse(old)?, parse(new)?);
+
+    if file_name(file) == "Cargo.lock" {
+        return Some(
+            DependencyChanges::between(&lock_packages(&old), &lock_packages(&new)).describe(),
+        );
+    }
+
+    let mut analysis = Vec::new();
+    for (label, key) in [
+        ("Package version", "version"),
+        ("Edition", "edition"),
+        ("Minimum supported Rust version", "rust-version"),
+    ] {
+        let (before, after) = (package_setting(&old, key), package_setting(&new, key));
+        if before != after {
+            analysis.push(format!(
+                "{} changed: {} → {}",
+                label,
+                before.as_deref().unwrap_or("unset"),
+                after.as_deref().unwrap_or("unset")
+            ));
+        }
+    }
+
+    let (old_dependencies, new_dependencies) = (dependencies(&old), dependencies(&new));
+    let versions = |dependencies: &BTreeMap<String, toml::Value>| -> BTreeMap<String, String> {
+        dependencies
+            .iter()
+            .map(|(name, spec)| (name.clone(), source(spec)))
+            .collect()
+    };
+    analysis.extend(
+        DependencyChanges::between(&versions(&old_dependencies), &versions(&new_dependencies))
+            .describe(),
+    );
+
+    let feature_changes: Vec<String> = new_dependencies
+        .iter()
+        .filter_map(|(name, spec)| {
+            let old_spec = old_dependencies.get(name)?;
+            describe_feature_change(name, old_spec, spec)
+        })
+        .collect();
+    if !feature_changes.is_empty() {
+        analysis.push(format!(
+            "Dependency features changed: {}",
+            feature_changes.join(", ")
+        ));
+    }
+
+    let (old_features, new_features) = (features(&old), features(&new));
+    let (mut added, mut removed, mut modified) = (Vec::new(), Vec::new(), Vec::new());
+    for (name, enables) in &new_features {
+        match old_features.get(name) {
+            None => added.push(name.as_str()),
+            Some(old_enables) if old_enables != enables => modified.push(name.as_str()),
+            Some(_) => {}
+        }
+    }
+    for name in old_features.keys() {
+        if !new_features.contains_key(name) {
+            removed.push(name.as_str());
+        }
+    }
+    for (label, names) in [
+        ("Added features", added),
+        ("Removed features", removed),
+        ("Modified features", modified),
+    ] {
+        if !names.is_empty() {
+            analysis.push(format!("{}: {}", label, names.join(", ")));
+        }
+    }
+
+    Some(analysis)
+}
+
+/// A setting of `[package]`, or of `[workspace.package]` in a workspace root
+fn package_setting(manifest: &toml::Table, key: &str) -> Option<String> {
+    let value = manifest
+        .get("package")
+        .and_then(|package| package.get(key))
+        .or_else(|| {
+            manifest
+                .get("workspace")
+                .and_then(|workspace| workspace.get("package"))
+                .and_then(|package| package.get(key))
+        })?;
+    match value {
+        toml::Value::String(value) => Some(value.clone()),
+        // `edition.workspace = true` takes the setting from the workspace root
+        toml::Value::Table(_) => Some("inherited from workspace".to_string()),
+        value => Some(value.to_string()),
+    }
+}
+
+/// Every dependency of a manifest by name, labeled with its table, including
+/// platform-specific and workspace dependencies
+fn dependencies(manifest: &toml::Table) -> BTreeMap<String, toml::Value> {
+    let mut dependencies = BTreeMap::new();
+    let mut add_tables = |table: &toml::Table, scope: &str| {
+        for (key, label) in DEPENDENCY_TABLES {
+            let Some(entries) = table.get(*key).and_then(toml::Value::as_table) else {
+                continue;
+            };
+            for (name, spec) in entries {
+                dependencies.insert(format!("{}{}{}", name, label, scope), spec.clone());
+            }
+        }
+    };
+
+    add_tables(manifest, "");
+    if let Some(targets) = manifest.get("target").and_then(toml::Value::as_table) {
+        for (platform, table) in targets {
+            if let Some(table) = table.as_table() {
+                add_tables(table, &format!(" (for {})", platform));
+            }
+        }
+    }
+    if let Some(workspace) = manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("dependencies"))
+        .and_then(toml::Value::as_table)
+    {
+        for (name, spec) in workspace {
+            dependencies.insert(format!("{} (workspace)", name), spec.clone());
+        }
+    }
+    dependencies
+}
+
+/// Where a dependency comes from: its version requirement, or its path or Git repository
+fn source(spec: &toml::Value) -> String {
+    let Some(table) = spec.as_table() else {
+        return spec.as_str().unwrap_or_default().to_string();
+    };
+    let setting = |key: &str| table.get(key).and_then(toml::Value::as_str);
+    if let Some(version) = setting("version") {
+        version.to_string()
+    } else if let Some(path) = setting("path") {
+        format!("path {}", path)
+    } else if let Some(git) = setting("git") {
+        match setting("tag").or(setting("rev")).or(setting("branch")) {
+            Some(reference) => format!("git {}#{}", git, reference),
+            None => format!("git {}", git),
+        }
+    } else if table.get("workspace").and_then(toml::Value::as_bool) == Some(true) {
+        "workspace".to_string()
+    } else {
+        "*".to_string()
+    }
+}
+
+/// The features enabled on a dependency, and whether its default features are
+fn enabled_features(spec: &toml::Value) -> (Vec<String>, bool) {
+    let features = spec
+        .get("features")
+        .and_then(toml::Value::as_array)
+        .map(|features| {
+            features
+                .iter()
+                .filter_map(|feature| feature.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    let default_features = spec
+        .get("default-features")
+        .or_else(|| spec.get("default_features"))
+        .and_then(toml::Value::as_bool)
+        .unwrap_or(true);
+    (features, default_features)
+}
+
+/// A change in the features enabled on a dependency, such as `tokio (+macros, -rt)`
+fn describe_feature_change(name: &str, old: &toml::Value, new: &toml::Value) -> Option<String> {
+    let (old_features, old_default) = enabled_features(old);
+    let (new_features, new_default) = enabled_features(new);
+
+    let mut changes: Vec<String> = new_features
+        .iter()
+        .filter(|feature| !old_features.contains(feature))
+        .map(|feature| format!("+{}", feature))
+        .collect();
+    changes.extend(
+        old_features
+            .iter()
+            .filter(|feature| !new_features.contains(feature))
+            .map(|feature| format!("-{}", feature)),
+    );
+    if old_default != new_default {
+        changes.push(
+            if new_default {
+                "default features on"
+            } else {
+                "default features off"
+            }
+            .to_string(),
+        );
+    }
+
+    if changes.is_empty() {
+        None
+    } else {
+        Some(format!("{} ({})", name, changes.join(", ")))
+    }
+}
+
+/// The crate's own features and what each one enables
+fn features(manifest: &toml::Table) -> BTreeMap<String, toml::Value> {
+    manifest
+        .get("features")
+        .and_then(toml::Value::as_table)
+        .map(|features| {
+            features
+                .iter()
+                .map(|(name, enables)| (name.clone(), enables.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The locked version of each package; a lockfile can hold several versions of one package
+fn lock_packages(lockfile: &toml::Table) -> BTreeMap<String, String> {
+    let mut packages: BTreeMap<String, String> = BTreeMap::new();
+    let entries = lockfile
+        .get("package")
+        .and_then(toml::Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for entry in entries {
+        let setting = |key: &str| entry.get(key).and_then(toml::Value::as_str);
+        if let (Some(name), Some(version)) = (setting("name"), setting("version")) {
+            add_version(&mut packages, name, version);
+        }
+    }
+    packages
+}
+
+fn add_version(packages: &mut BTreeMap<String, String>, name: &str, version: &str) {
+    let versions = packages.entry(name.to_string()).or_default();
+    if versions.is_empty() {
+        versions.push_str(version);
+    } else if !versions.split(", ").any(|existing| existing == version) {
+        versions.push_str(", ");
+        versions.push_str(version);
+    }
+}
+
+/// Lines of the diff on one side, counting context lines on both
+fn side_lines(diff: &str, sign: char) -> impl Iterator<Item = &str> {
+    diff.lines()
+        .filter(move |line| line.starts_with(' ') || line.starts_with(sign))
+        .map(|line| &line[1..])
+}
+
+/// Dependencies on one side of a `Cargo.toml` diff, for when the whole file isn't available
+///
+/// A hunk that starts inside a table doesn't show its header; entries there count as
+/// dependencies when they name a version.
+fn diff_dependencies(diff: &str, sign: char) -> BTreeMap<String, String> {
+    let header_re = Regex::new(r"^\s*\[([^\]]+)\]").unwrap();
+    let entry_re = Regex::new(
+        r#"^\s*([\w-]+)\s*=\s*(?:"([^"]*)"|\{.*?\bversion\s*=\s*"([^"]*)"|\{.*?\bpath\s*=\s*"([^"]*)")"#,
+    )
+    .unwrap();
+
+    let mut dependencies = BTreeMap::new();
+    // The label of the table a line is in, `None` inside other tables, or unknown
+    let mut table: Option<Option<&str>> = None;
+    for line in side_lines(diff, sign) {
+        if line.trim_start().starts_with("@@") {
+            table = None;
+            continue;
+        }
+        if let Some(cap) = header_re.captures(line) {
+            let name = cap[1].trim();
+            table = Some(
+                DEPENDENCY_TABLES
+                    .iter()
+                    .find(|(key, _)| name == *key || name.ends_with(&format!(".{}", key)))
+                    .map(|(_, label)| *label),
+            );
+            continue;
+        }
+        let Some(cap) = entry_re.captures(line) else {
+            continue;
+        };
+        let label = match table {
+            Some(Some(label)) => label,
+            Some(None) => continue,
+            None if cap.get(2).is_none() => "",
+            None => continue,
+        };
+        let version = match (cap.get(2), cap.get(3), cap.get(4)) {
+            (Some(version), _, _) | (None, Some(version), _) => version.as_str().to_string(),
+            (None, None, Some(path)) => format!("path {}", path.as_str()),
+            _ => continue,
+        };
+        dependencies.insert(format!("{}{}", &cap[1], label), version);
+    }
+    dependencies
+}
+
+/// Packages on one side of a `Cargo.lock` diff, for when the whole file isn't available
+fn diff_lock_packages(diff: &str, sign: char) -> BTreeMap<String, String> {
+    let name_re = Regex::new(r#"^name\s*=\s*"([^"]+)""#).unwrap();
+    let version_re = Regex::new(r#"^version\s*=\s*"([^"]+)""#).unwrap();
+
+    let mut packages = BTreeMap::new();
+    let mut name: Option<String> = None;
+    for line in side_lines(diff, sign) {
+        if line.starts_with("[[package]]") || line.trim_start().starts_with("@@") {
+            name = None;
+        } else if let Some(cap) = name_re.captures(line) {
+            name = Some(cap[1].to_string());
+        } else if let (Some(name), Some(cap)) = (&name, version_re.captures(line)) {
+            add_version(&mut packages, name, &cap[1]);
+        }
+    }
+    packages
+}
+
+fn extract_modified_tables(diff: &str) -> Option<Vec<String>> {
+    let header_re = Regex::new(r"^\s*\[+([^\]]+)\]+").unwrap();
+
+    let mut tables: Vec<String> = Vec::new();
+    let mut current: Option<String> = None;
+    for line in diff.lines() {
+        let Some(content) = line.get(1..) else {
+            continue;
+        };
+        if content.trim_start().starts_with("@@") {
+            current = None;
+            continue;
+        }
+        if let Some(cap) = header_re.captures(content) {
+            current = Some(cap[1].trim().to_string());
+        }
+        let changed = (line.starts_with('+') || line.starts_with('-')) && !content.trim().is_empty();
+        if changed {
+            let table = current.clone().unwrap_or_else(|| "(top level)".to_string());
+            if !tables.contains(&table) {
+                tables.push(table);
+            }
+        }
+    }
+
+    if tables.is_empty() {
+        None
+    } else {
+        Some(tables)
+    }
 }
@@ -0,0 +1,296 @@
+use super::yaml::{yaml_entries, YamlEntry};
+use super::{FileAnalyzer, ProjectMetadata};
+use crate::context::StagedFile;
+use regex::Regex;
+
+/// Analyzer for CI pipelines: GitHub Actions workflows and GitLab CI files
+pub struct WorkflowAnalyzer;
+
+/// Top-level keys of a GitLab CI file that configure the pipeline rather than name a job
+const GITLAB_KEYWORDS: &[&str] = &[
+    "stages",
+    "variables",
+    "default",
+    "include",
+    "workflow",
+    "image",
+    "services",
+    "before_script",
+    "after_script",
+    "cache",
+];
+
+/// Events that start a GitHub workflow, which look like jobs when a hunk doesn't show the
+/// `on` key above them
+const GITHUB_EVENTS: &[&str] = &[
+    "push",
+    "pull_request",
+    "pull_request_target",
+    "workflow_dispatch",
+    "workflow_call",
+    "workflow_run",
+    "schedule",
+    "merge_group",
+];
+
+impl FileAnalyzer for WorkflowAnalyzer {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
+        let diff = &staged_file.diff;
+        let github = is_github_workflow(file);
+        let orphan_parent = if github { "jobs" } else { "" };
+        let before = yaml_entries(diff, '-', orphan_parent);
+        let after = yaml_entries(diff, '+', orphan_parent);
+        let mut analysis = Vec::new();
+
+        let (jobs_added, jobs_removed) = difference(&jobs(&before, github), &jobs(&after, github));
+        if !jobs_added.is_empty() {
+            analysis.push(format!("Jobs added: {}", jobs_added.join(", ")));
+        }
+        if !jobs_removed.is_empty() {
+            analysis.push(format!("Jobs removed: {}", jobs_removed.join(", ")));
+        }
+
+        let versions = version_changes(&references(&before, github), &references(&after, github));
+        if !versions.is_empty() {
+            let label = if github { "Action and image" } else { "Image" };
+            analysis.push(format!(
+                "{} version changes: {}",
+                label,
+                versions.join(", ")
+            ));
+        }
+
+        if github {
+            let (added, removed) = difference(&triggers(&before), &triggers(&after));
+            if !added.is_empty() {
+                analysis.push(format!("New triggers: {}", added.join(", ")));
+            }
+            if !removed.is_empty() {
+                analysis.push(format!("Triggers removed: {}", removed.join(", ")));
+            }
+
+            let (added, removed) = difference(&permissions(&before), &permissions(&after));
+            if !added.is_empty() || !removed.is_empty() {
+                let permissions = if added.is_empty() { removed } else { added };
+                analysis.push(format!("Permissions changed: {}", permissions.join(", ")));
+            }
+        } else if has_rule_changes(diff) {
+            analysis.push("Job rules or triggers have been modified".to_string());
+        }
+
+        let (added, removed) = difference(
+            &secrets(&side_text(diff, '-'), github),
+            &secrets(&side_text(diff, '+'), github),
+        );
+        let kind = if github { "secrets" } else { "variables" };
+        if !added.is_empty() {
+            analysis.push(format!("New {} used: {}", kind, added.join(", ")));
+        }
+        if !removed.is_empty() {
+            analysis.push(format!(
+                "{} no longer used: {}",
+                capitalize(kind),
+                removed.join(", ")
+            ));
+        }
+
+        analysis
+    }
+
+    fn get_file_type(&self) -> &'static str {
+        "CI pipeline file"
+    }
+
+    fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
+        let github = is_github_workflow(file);
+        let diff: String = content.lines().map(|line| format!(" {}\n", line)).collect();
+        let entries = yaml_entries(&diff, ' ', "");
+        ProjectMetadata {
+            build_system: Some(
+                if github {
+                    "GitHub Actions"
+                } else {
+                    "GitLab CI"
+                }
+                .to_string(),
+            ),
+            dependencies: references(&entries, github)
+                .into_iter()
+                .map(|(name, version)| format!("{}@{}", name, version))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+pub(super) fn is_github_workflow(file: &str) -> bool {
+    file.contains(".github/workflows/") && (file.ends_with(".yml") || file.ends_with(".yaml"))
+}
+
+pub(super) fn is_gitlab_ci(file: &str) -> bool {
+    let name = file.rsplit('/').next().unwrap_or(file);
+    name.ends_with(".gitlab-ci.yml") || name == ".gitlab-ci.yml"
+}
+
+fn difference(before: &[String], after: &[String]) -> (Vec<String>, Vec<String>) {
+    let added = after
+        .iter()
+        .filter(|name| !before.contains(name))
+        .cloned()
+        .collect();
+    let removed = before
+        .iter()
+        .filter(|name| !after.contains(name))
+        .cloned()
+        .collect();
+    (added, removed)
+}
+
+fn push_unique(names: &mut Vec<String>, name: String) {
+    if !names.contains(&name) {
+        names.push(name);
+    }
+}
+
+fn jobs(entries: &[YamlEntry], github: bool) -> Vec<String> {
+    let mut jobs = Vec::new();
+    for entry in entries {
+        let is_job = if github {
+            entry.path.len() == 2
+                && entry.path[0] == "jobs"
+                && !GITHUB_EVENTS.contains(&entry.path[1].as_str())
+        } else {
+            // Jobs are top-level mappings; hidden ones starting with a dot are templates
+            entry.path.len() == 1
+                && entry.value.is_none()
+                && !entry.path[0].starts_with('.')
+                && !GITLAB_KEYWORDS.contains(&entry.path[0].as_str())
+        };
+        if is_job {
+            push_unique(&mut jobs, entry.path.last().unwrap().clone());
+        }
+    }
+    jobs
+}
+
+/// Actions and container images with their versions, as `(name, version)` pairs
+fn references(entries: &[YamlEntry], github: bool) -> Vec<(String, String)> {
+    let mut references = Vec::new();
+    for entry in entries {
+        let Some(key) = entry.path.last() else {
+            continue;
+        };
+        let Some(value) = &entry.value else {
+            continue;
+        };
+        let reference = match key.as_str() {
+            "uses" if github => value
+                .split_once('@')
+                .map(|(name, version)| (name.to_string(), version.to_string())),
+            "image" | "container" => Some(match value.rsplit_once(':') {
+                Some((name, tag)) if !tag.contains('/') => (name.to_string(), tag.to_string()),
+                _ => (value.clone(), "latest".to_string()),
+            }),
+            _ => None,
+        };
+        if let Some(reference) = reference.filter(|reference| !references.contains(reference)) {
+            references.push(reference);
+        }
+    }
+    references
+}
+
+fn version_changes(before: &[(String, String)], after: &[(String, String)]) -> Vec<String> {
+    let mut changes = Vec::new();
+    for (name, old_version) in before {
+        let new_versions: Vec<&String> = after
+            .iter()
+            .filter(|(new_name, _)| new_name == name)
+            .map(|(_, version)| version)
+            .collect();
+        if !new_versions.is_empty() && !new_versions.contains(&old_version) {
+            let change = format!("{} ({} → {})", name, old_version, new_versions[0]);
+            push_unique(&mut changes, change);
+        }
+    }
+    changes
+}
+
+/// The events under `on`, whether written as a list, a single event, or a mapping
+fn triggers(entries: &[YamlEntry]) -> Vec<String> {
+    let mut triggers = Vec::new();
+    for entry in entries {
+        if entry.path.first().map(String::as_str) != Some("on") {
+            continue;
+        }
+        match (entry.path.len(), &entry.value) {
+            (1, Some(value)) => {
+                for event in value.trim_matches(|c| c == '[' || c == ']').split(',') {
+                    push_unique(&mut triggers, event.trim().to_string());
+                }
+            }
+            (2, _) if entry.path[1] != "on" => push_unique(&mut triggers, entry.path[1].clone()),
+            _ => {}
+        }
+    }
+    triggers.retain(|trigger| !trigger.is_empty());
+    triggers
+}
+
+/// Permission grants anywhere in the workflow, written `scope: access`
+fn permissions(entries: &[YamlEntry]) -> Vec<String> {
+    let mut permissions = Vec::new();
+    for entry in entries {
+        let Some(position) = entry.path.iter().position(|key| key == "permissions") else {
+            continue;
+        };
+        let Some(value) = &entry.value else {
+            continue;
+        };
+        let permission = match entry.path.get(position + 1) {
+            Some(scope) => format!("{}: {}", scope, value),
+            None => value.clone(),
+        };
+        push_unique(&mut permissions, permission);
+    }
+    permissions
+}
+
+fn has_rule_changes(diff: &str) -> bool {
+    let re = Regex::new(r"(?m)^[+-]\s*(?:-\s*)?(?:rules|only|except|if|when|changes):").unwrap();
+    re.is_match(diff)
+}
+
+/// Context lines and the lines on one side of a diff
+fn side_text(diff: &str, sign: char) -> String {
+    diff.lines()
+        .filter(|line| line.starts_with(' ') || line.starts_with(sign))
+        .map(|line| &line[1..])
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Secrets a GitHub workflow reads, or the variables a GitLab pipeline reads other than the
+/// predefined `CI_` ones
+fn secrets(text: &str, github: bool) -> Vec<String> {
+    let re = if github {
+        Regex::new(r"\bsecrets\.(\w+)").unwrap()
+    } else {
+        Regex::new(r"\$\{?([A-Z][A-Z0-9_]*)").unwrap()
+    };
+    let mut names = Vec::new();
+    for cap in re.captures_iter(text) {
+        if github || !cap[1].starts_with("CI_") {
+            push_unique(&mut names, cap[1].to_string());
+        }
+    }
+    names
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
@@ -1,4 +1,4 @@
-use super::{FileAnalyzer, ProjectMetadata};
+use super::{kubernetes, FileAnalyzer, ProjectMetadata};
 use crate::context::StagedFile;
 use regex::Regex;
 use std::collections::HashSet;
@@ -6,7 +6,14 @@ use std::collections::HashSet;
 pub struct YamlAnalyzer;
 
 impl FileAnalyzer for YamlAnalyzer {
-    fn analyze(&self, _file: &str, staged_file: &StagedFile) -> Vec<String> {
+    fn analyze(&self, file: &str, staged_file: &StagedFile) -> Vec<String> {
+        if is_compose_file(file) {
+            return analyze_compose(&staged_file.diff);
+        }
+        if kubernetes::is_manifest(&staged_file.diff) {
+            return kubernetes::analyze_manifest(&staged_file.diff);
+        }
+
         let mut analysis = Vec::new();
 
         if let Some(keys) = extract_modified_top_level_keys(&staged_file.diff) {
@@ -31,10 +38,13 @@ impl FileAnalyzer for YamlAnalyzer {
     fn extract_metadata(&self, file: &str, content: &str) -> ProjectMetadata {
         let mut metadata = ProjectMetadata::default();
 
-        if file == "docker-compose.yml" || file == "docker-compose.yaml" {
+        if is_compose_file(file) {
             metadata.build_system = Some("Docker Compose".to_string());
-        } else if file.ends_with(".github/workflows/ci.yml") || file.ends_with(".github/workflows/ci.yaml") {
-            metadata.build_system = Some("GitHub Actions".to_string());
+            let image_re = Regex::new(r#"(?m)^\s+image:\s*['"]?([^'"\s]+)"#).unwrap();
+            metadata.dependencies = image_re
+                .captures_iter(content)
+                .map(|cap| cap[1].to_string())
+                .collect();
         } else if file == ".travis.yml" {
             metadata.build_system = Some("Travis CI".to_string());
         }
@@ -79,3 +89,195 @@ fn has_nested_changes(diff: &str) -> bool {
     let re = Regex::new(r"(?m)^[+-]\s+\w+:").unwrap();
     re.is_match(diff)
 }
+
+/// Whether a file is a Docker Compose file, such as `docker-compose.yml` or
+/// `compose.prod.yaml`
+fn is_compose_file(file: &str) -> bool {
+    let name = file.rsplit('/').next().unwrap_or(file);
+    (name.starts_with("docker-compose") || name.starts_with("compose."))
+        && (name.ends_with(".yml") || name.ends_with(".yaml"))
+}
+
+/// A key or list item in one side of a YAML diff
+pub(super) struct YamlEntry {
+    /// Keys from the top of the document down to this entry, as far as the diff shows them
+    pub path: Vec<String>,
+    /// The scalar after the colon, or the list item itself
+    pub value: Option<String>,
+}
+
+/// The entries of a YAML file as it was (`-`) or is (`+`), from the context lines and the
+/// lines on that side of the diff
+///
+/// Keys are placed by their indentation. A key indented by two spaces with no parent in
+/// view is assumed to be under `orphan_parent`, such as `services` in a Compose file;
+/// deeper keys with no parent in view are left out.
+pub(super) fn yaml_entries(diff: &str, sign: char, orphan_parent: &str) -> Vec<YamlEntry> {
+    let key_re = Regex::new(r#"^['"]?([\w.\-/]+)['"]?\s*:(?:\s+(.*))?$"#).unwrap();
+    let mut entries = Vec::new();
+    let mut stack: Vec<(usize, String)> = Vec::new();
+
+    for line in diff.lines() {
+        if !(line.starts_with(' ') || line.starts_with(sign)) {
+            continue;
+        }
+        let body = &line[1..];
+        if body.starts_with("@@") {
+            stack.clear();
+            continue;
+        }
+        let content = body.trim_start();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        let indent = body.len() - content.len();
+        let (is_list_item, content) = match content.strip_prefix("- ") {
+            Some(item) => (true, item.trim()),
+            None => (false, content),
+        };
+
+        // A list may sit at the same indentation as its key
+        while stack.last().is_some_and(|(parent_indent, _)| {
+            *parent_indent > indent || (*parent_indent == indent && !is_list_item)
+        }) {
+            stack.pop();
+        }
+        if stack.is_empty() && indent > 0 {
+            if indent != 2 || orphan_parent.is_empty() {
+                continue;
+            }
+            stack.push((0, orphan_parent.to_string()));
+        }
+
+        let mut path: Vec<String> = stack.iter().map(|(_, key)| key.clone()).collect();
+        match key_re.captures(content) {
+            Some(cap) => {
+                let value = cap.get(2).map(|m| unquote(m.as_str())).filter(|v| !v.is_empty());
+                path.push(cap[1].to_string());
+                if value.is_none() && !is_list_item {
+                    stack.push((indent, cap[1].to_string()));
+                }
+                entries.push(YamlEntry { path, value });
+            }
+            None if is_list_item => entries.push(YamlEntry {
+                path,
+                value: Some(unquote(content)),
+            }),
+            None => {}
+        }
+    }
+
+    entries
+}
+
+fn unquote(value: &str) -> String {
+    value.trim().trim_matches(|c| c == '"' || c == '\'').to_string()
+}
+
+/// Compose settings of each service, as `(service, value)` pairs
+fn service_values(entries: &[YamlEntry], setting: &str) -> Vec<(String, String)> {
+    entries
+        .iter()
+        .filter(|entry| entry.path.len() >= 3 && entry.path[0] == "services")
+        .filter(|entry| entry.path[2] == setting)
+        .filter_map(|entry| {
+            let value = match (entry.path.len(), &entry.value) {
+                (3, Some(value)) => value.clone(),
+                // Mappings such as `environment:` with one key per variable
+                (4, _) => entry.path[3].clone(),
+                _ => return None,
+            };
+            Some((entry.path[1].clone(), value))
+        })
+        .collect()
+}
+
+fn analyze_compose(diff: &str) -> Vec<String> {
+    let before = yaml_entries(diff, '-', "services");
+    let after = yaml_entries(diff, '+', "services");
+    let mut analysis = Vec::new();
+
+    let names = |entries: &[YamlEntry], section: &str| -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for entry in entries {
+            if entry.path.len() == 2 && entry.path[0] == section && !names.contains(&entry.path[1])
+            {
+                names.push(entry.path[1].clone());
+            }
+        }
+        names
+    };
+    let services_before = names(&before, "services");
+    let services_after = names(&after, "services");
+    let added: Vec<&String> = services_after
+        .iter()
+        .filter(|service| !services_before.contains(service))
+        .collect();
+    let removed: Vec<&String> = services_before
+        .iter()
+        .filter(|service| !services_after.contains(service))
+        .collect();
+    if !added.is_empty() {
+        analysis.push(format!("Services added: {}", join(&added)));
+    }
+    if !removed.is_empty() {
+        analysis.push(format!("Services removed: {}", join(&removed)));
+    }
+
+    let images_before = service_values(&before, "image");
+    let image_changes: Vec<String> = service_values(&after, "image")
+        .into_iter()
+        .filter_map(|(service, image)| {
+            let (_, old_image) = images_before.iter().find(|(name, _)| *name == service)?;
+            (*old_image != image).then(|| format!("{} ({} → {})", service, old_image, image))
+        })
+        .collect();
+    if !image_changes.is_empty() {
+        analysis.push(format!("Image changes: {}", image_changes.join(", ")));
+    }
+
+    for (setting, label) in [
+        ("ports", "New ports"),
+        ("volumes", "New volumes"),
+        ("environment", "New environment variables"),
+    ] {
+        let old_values = service_values(&before, setting);
+        let new_values: Vec<String> = service_values(&after, setting)
+            .into_iter()
+            // Only the service's own additions; a new service is reported as a whole
+            .filter(|(service, _)| !added.contains(&service))
+            .filter(|pair| !old_values.contains(pair))
+            .map(|(service, value)| {
+                let value = if setting == "environment" {
+                    value.split('=').next().unwrap_or(&value).to_string()
+                } else {
+                    value
+                };
+                format!("{} {}", service, value)
+            })
+            .collect();
+        if !new_values.is_empty() {
+            analysis.push(format!("{}: {}", label, new_values.join(", ")));
+        }
+    }
+
+    let volumes_before = names(&before, "volumes");
+    let volumes_after = names(&after, "volumes");
+    let named_volumes: Vec<&String> = volumes_after
+        .iter()
+        .filter(|volume| !volumes_before.contains(volume))
+        .collect();
+    if !named_volumes.is_empty() {
+        analysis.push(format!("Named volumes added: {}", join(&named_volumes)));
+    }
+
+    analysis
+}
+
+fn join(names: &[&String]) -> String {
+    names
+        .iter()
+        .map(|name| name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,182 @@
+use crate::split::{collect_staged_hunks, StagedHunk};
+use anyhow::{anyhow, Result};
+use git2::{BlameOptions, Oid, Repository};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Number of recent commits considered as fixup targets by default
+pub const DEFAULT_MAX_COMMITS: usize = 20;
+
+/// Prefix Git's `--autosquash` looks for in fixup commit subjects
+pub const FIXUP_PREFIX: &str = "fixup! ";
+
+/// A recent commit the staged changes may belong to
+#[derive(Debug, Clone)]
+pub struct FixupCandidate {
+    pub commit_id: String,
+    pub subject: String,
+    /// Number of lines touched by the staged changes that this commit last changed
+    pub blamed_lines: usize,
+    /// Files changed both by this commit and by the staged changes
+    pub shared_files: Vec<String>,
+}
+
+impl FixupCandidate {
+    /// Lines the commit last changed weigh more than files it merely shares
+    pub fn score(&self) -> usize {
+        self.blamed_lines * 2 + self.shared_files.len()
+    }
+
+    /// Message for a commit that Git's `--autosquash` folds into this one
+    pub fn fixup_message(&self) -> String {
+        format!("{}{}", FIXUP_PREFIX, self.subject)
+    }
+}
+
+/// Rank the most recent commits by how likely the staged changes belong to them
+///
+/// Each staged hunk is traced back with `git blame` to the commits that last changed the lines it
+/// removes, and the lines around the lines it adds. Commits that only touched the same files
+/// rank below those. Merge commits and commits without any overlap are left out.
+pub fn suggest_fixup_targets(repo_path: &Path, max_commits: usize) -> Result<Vec<FixupCandidate>> {
+    let repo = Repository::open(repo_path)?;
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|_| anyhow!("There are no commits to fix up yet"))?;
+
+    let hunks = collect_staged_hunks(repo_path)?;
+    if hunks.is_empty() {
+        return Err(anyhow!("No staged changes"));
+    }
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head.id())?;
+    let mut candidates: HashMap<Oid, FixupCandidate> = HashMap::new();
+    let mut order: Vec<Oid> = Vec::new();
+    for oid in revwalk.take(max_commits) {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        order.push(commit.id());
+        candidates.insert(
+            commit.id(),
+            FixupCandidate {
+                commit_id: commit.id().to_string(),
+                subject: commit.summary().unwrap_or_default().to_string(),
+                blamed_lines: 0,
+                shared_files: Vec::new(),
+            },
+        );
+    }
+
+    let staged_paths: Vec<&str> = unique_paths(&hunks);
+    for path in &staged_paths {
+        let file_hunks: Vec<&StagedHunk> = hunks.iter().filter(|h| h.path == *path).collect();
+        for oid in blamed_commits(&repo, head.id(), path, &file_hunks) {
+            if let Some(candidate) = candidates.get_mut(&oid) {
+                candidate.blamed_lines += 1;
+            }
+        }
+    }
+
+    for oid in &order {
+        let commit = repo.find_commit(*oid)?;
+        let changed = changed_paths(&repo, &commit)?;
+        if let Some(candidate) = candidates.get_mut(oid) {
+            candidate.shared_files = staged_paths
+                .iter()
+                .filter(|path| changed.contains(**path))
+                .map(|path| path.to_string())
+                .collect();
+        }
+    }
+
+    // Most likely first; among equals, the most recent commit wins
+    let mut ranked: Vec<FixupCandidate> = order
+        .iter()
+        .filter_map(|oid| candidates.remove(oid))
+        .filter(|candidate| candidate.score() > 0)
+        .collect();
+    ranked.sort_by_key(|candidate| std::cmp::Reverse(candidate.score()));
+    Ok(ranked)
+}
+
+fn unique_paths(hunks: &[StagedHunk]) -> Vec<&str> {
+    let mut paths: Vec<&str> = Vec::new();
+    for hunk in hunks {
+        if !paths.contains(&hunk.path.as_str()) {
+            paths.push(&hunk.path);
+        }
+    }
+    paths
+}
+
+/// The commit that last changed each HEAD line the hunks touch, one entry per line
+fn blamed_commits(repo: &Repository, head: Oid, path: &str, hunks: &[&StagedHunk]) -> Vec<Oid> {
+    let mut options = BlameOptions::new();
+    options.newest_commit(head);
+    // New files have no history to blame
+    let Ok(blame) = repo.blame_file(Path::new(path), Some(&mut options)) else {
+        return Vec::new();
+    };
+
+    let mut lines: HashSet<usize> = HashSet::new();
+    for hunk in hunks {
+        if hunk.file_hunk_index.is_some() {
+            lines.extend(touched_lines(hunk));
+        }
+    }
+
+    lines
+        .into_iter()
+        .filter(|line| *line > 0)
+        .filter_map(|line| blame.get_line(line).map(|hunk| hunk.final_commit_id()))
+        .collect()
+}
+
+/// HEAD line numbers a hunk removes, or the lines on either side of a block it only adds
+fn touched_lines(hunk: &StagedHunk) -> Vec<usize> {
+    let mut lines = Vec::new();
+    let mut old_line = hunk.old_start;
+    // For a pure addition, `old_start` is the line the new lines go after
+    if !hunk.content.lines().any(|line| line.starts_with([' ', '-'])) {
+        old_line += 1;
+    }
+
+    // Whether the current block of changed lines removes anything, and whether it adds anything
+    let mut removes = false;
+    let mut adds = false;
+    for line in hunk.content.lines().chain(std::iter::once(" ")) {
+        match line.chars().next() {
+            Some('-') => {
+                lines.push(old_line);
+                old_line += 1;
+                removes = true;
+            }
+            Some('+') => adds = true,
+            Some(' ') => {
+                if adds && !removes {
+                    lines.push(old_line.saturating_sub(1));
+                    lines.push(old_line);
+                }
+                removes = false;
+                adds = false;
+                old_line += 1;
+            }
+            _ => {}
+        }
+    }
+    lines
+}
+
+fn changed_paths(repo: &Repository, commit: &git2::Commit) -> Result<HashSet<String>> {
+    let parent_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    Ok(diff
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+        .map(|path| path.to_string_lossy().to_string())
+        .collect())
+}
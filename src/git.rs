@@ -1,31 +1,270 @@
 use crate::change_analyzer::{AnalyzedChange, ChangeAnalyzer};
-use crate::config::Config;
-use crate::context::{ChangeType, CommitContext, ProjectMetadata, RecentCommit, StagedFile};
+use crate::config::{Config, RecentCommitsConfig};
+use crate::context::{
+    ChangeScope, ChangeType, CommitContext, ProjectMetadata, RecentCommit, StageStatus, StagedFile,
+};
+use crate::exclusions::PromptFilter;
 use crate::file_analyzers;
+use crate::workspace::WorkspaceGraph;
 use anyhow::{anyhow, Result};
-use git2::{DiffOptions, Repository, StatusOptions};
-use regex::Regex;
+use git2::{DescribeFormatOptions, DescribeOptions, DiffOptions, Repository, StatusOptions};
 use std::fs;
 use std::path::Path;
 use walkdir::WalkDir;
 
-pub fn get_git_info(repo_path: &Path, _config: &Config) -> Result<CommitContext> {
+pub fn get_git_info(repo_path: &Path, config: &Config) -> Result<CommitContext> {
+    get_git_info_with_scope(repo_path, config, ChangeScope::Staged)
+}
+
+/// Build a commit context from the changes in the given scope
+///
+/// Beyond the index, working-tree changes to tracked files can be included, each file labeled
+/// with where its change lives. Untracked files are always listed as unstaged files only.
+/// Files matching the built-in or the configured `prompt_exclude` patterns are listed without
+/// their contents, unless a `prompt_include` pattern matches them.
+pub fn get_git_info_with_scope(
+    repo_path: &Path,
+    config: &Config,
+    scope: ChangeScope,
+) -> Result<CommitContext> {
     let repo = Repository::open(repo_path)?;
 
     let branch = get_current_branch(&repo)?;
-    let recent_commits = get_recent_commits(&repo, 5)?;
-    let (staged_files, unstaged_files) = get_file_statuses(&repo)?;
+    let recent_commits = get_recent_commits(&repo, &config.recent_commits)?;
+    let filter = PromptFilter::from_config(config);
+    let (staged_files, unstaged_files) = match scope {
+        ChangeScope::Staged => get_file_statuses(&repo, &filter)?,
+        ChangeScope::All | ChangeScope::Unstaged => {
+            get_working_tree_files(&repo, scope, &filter)?
+        }
+    };
     let project_metadata = get_project_metadata(repo_path)?;
+    let workspace_impact = WorkspaceGraph::load(repo_path)
+        .map(|graph| graph.impact_of(staged_files.iter().map(|f| f.path.as_str())))
+        .unwrap_or_default();
 
-    let context = CommitContext::new(
+    Ok(CommitContext::new(
         branch,
         recent_commits,
         staged_files,
         unstaged_files,
         project_metadata,
-    );
+        workspace_impact,
+    ))
+}
+
+/// Build a commit context from an existing commit, treating its changes as the staged files
+///
+/// The recent commits are the ones before it.
+pub fn get_commit_info(
+    repo_path: &Path,
+    reference: &str,
+    recent: &RecentCommitsConfig,
+    filter: &PromptFilter,
+) -> Result<CommitContext> {
+    let repo = Repository::open(repo_path)?;
+    let commit = repo
+        .revparse_single(reference)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|_| anyhow!("Commit '{}' not found", reference))?;
+
+    let branch = get_current_branch(&repo)?;
+    let recent_commits = match commit.parent(0) {
+        Ok(parent) => get_recent_commits_from(&repo, parent.id(), recent)?,
+        Err(_) => Vec::new(),
+    };
+    let staged_files = get_commit_files(&repo, &commit, filter)?;
+    let project_metadata = get_project_metadata(repo_path)?;
+    let workspace_impact = WorkspaceGraph::load(repo_path)
+        .map(|graph| graph.impact_of(staged_files.iter().map(|f| f.path.as_str())))
+        .unwrap_or_default();
+
+    Ok(CommitContext::new(
+        branch,
+        recent_commits,
+        staged_files,
+        Vec::new(),
+        project_metadata,
+        workspace_impact,
+    ))
+}
+
+/// Build a commit context from every uncommitted change, staged or not, as `git stash` would save it
+pub fn get_working_tree_info(
+    repo_path: &Path,
+    include_untracked: bool,
+    filter: &PromptFilter,
+) -> Result<CommitContext> {
+    let repo = Repository::open(repo_path)?;
+
+    let branch = get_current_branch(&repo)?;
+    let recent_commits = get_recent_commits(&repo, &RecentCommitsConfig::default())?;
+
+    let head_tree = repo.head()?.peel_to_tree()?;
+    let mut diff_options = DiffOptions::new();
+    diff_options
+        .include_untracked(include_untracked)
+        .recurse_untracked_dirs(include_untracked)
+        .show_untracked_content(include_untracked);
+    let diff = repo.diff_tree_to_workdir_with_index(Some(&head_tree), Some(&mut diff_options))?;
+    let changed_files = get_diff_files(&repo, &diff, filter)?;
+
+    let project_metadata = get_project_metadata(repo_path)?;
+    let workspace_impact = WorkspaceGraph::load(repo_path)
+        .map(|graph| graph.impact_of(changed_files.iter().map(|f| f.path.as_str())))
+        .unwrap_or_default();
+
+    Ok(CommitContext::new(
+        branch,
+        recent_commits,
+        changed_files,
+        Vec::new(),
+        project_metadata,
+        workspace_impact,
+    ))
+}
+
+/// Collect the files changed between two references as a single diff, with their analysis
+pub fn get_range_files(
+    repo_path: &Path,
+    from: &str,
+    to: &str,
+    filter: &PromptFilter,
+) -> Result<Vec<StagedFile>> {
+    let repo = Repository::open(repo_path)?;
+    let tree_of = |reference: &str| {
+        repo.revparse_single(reference)
+            .and_then(|obj| obj.peel_to_tree())
+            .map_err(|_| anyhow!("Reference '{}' not found", reference))
+    };
+    let diff = repo.diff_tree_to_tree(Some(&tree_of(from)?), Some(&tree_of(to)?), None)?;
+    get_diff_files(&repo, &diff, filter)
+}
+
+fn get_commit_files(
+    repo: &Repository,
+    commit: &git2::Commit,
+    filter: &PromptFilter,
+) -> Result<Vec<StagedFile>> {
+    let parent_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    get_diff_files(repo, &diff, filter)
+}
+
+fn get_diff_files(
+    repo: &Repository,
+    diff: &git2::Diff,
+    filter: &PromptFilter,
+) -> Result<Vec<StagedFile>> {
+    let mut files: Vec<StagedFile> = Vec::new();
+    // The blobs each file changed between, to compare files analyzed from their contents
+    let mut blobs: Vec<(git2::Oid, git2::Oid)> = Vec::new();
+    diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        if files.last().map(|f| f.path.as_str()) != Some(path.as_str()) {
+            let change_type = match delta.status() {
+                git2::Delta::Added | git2::Delta::Untracked => ChangeType::Added,
+                git2::Delta::Deleted => ChangeType::Deleted,
+                _ => ChangeType::Modified,
+            };
+            blobs.push((delta.old_file().id(), delta.new_file().id()));
+            files.push(StagedFile {
+                content_excluded: filter.is_excluded(&path),
+                stage_status: StageStatus::Staged,
+                path,
+                change_type,
+                diff: String::new(),
+                analysis: Vec::new(),
+            });
+        }
+
+        let origin = match line.origin() {
+            '+' | '-' | ' ' => line.origin(),
+            _ => ' ',
+        };
+        if let Some(file) = files.last_mut() {
+            file.diff.push(origin);
+            file.diff.push_str(&String::from_utf8_lossy(line.content()));
+        }
+        true
+    })?;
+
+    for (file, (old_blob, new_blob)) in files.iter_mut().zip(blobs) {
+        let contents = file_analyzers::analyzes_contents(&file.path).then(|| {
+            let new = new_blob_bytes(repo, new_blob, &file.path)
+                .and_then(|bytes| String::from_utf8(bytes).ok());
+            (blob_text(repo, old_blob), new)
+        });
+        if file.content_excluded {
+            file.analysis = excluded_analysis(file, contents);
+            file.diff = String::from("[Content excluded]");
+        } else if is_binary_diff(&file.diff) {
+            file.diff = BINARY_DIFF.to_string();
+            file.analysis = file_analyzers::analyze_binary(
+                blob_bytes(repo, old_blob).as_deref(),
+                new_blob_bytes(repo, new_blob, &file.path).as_deref(),
+            );
+        } else {
+            file.analysis = analyze_file(file, contents);
+        }
+    }
+
+    Ok(files)
+}
+
+/// What the diff of a binary file is replaced with; its analysis describes the change instead
+const BINARY_DIFF: &str = "[Binary file changed]";
+
+/// The contents of a file before and after a change, for files analyzed by comparing them
+type Contents = (Option<String>, Option<String>);
+
+/// Analyze a file from its contents when they were loaded, or from its diff otherwise
+fn analyze_file(file: &StagedFile, contents: Option<Contents>) -> Vec<String> {
+    match contents {
+        Some((old, new)) => file_analyzers::analyze_contents(file, old.as_deref(), new.as_deref()),
+        None => file_analyzers::get_analyzer(&file.path).analyze(&file.path, file),
+    }
+}
+
+/// The analysis of a file whose diff is left out of prompts: a summary for lockfiles, which
+/// is far shorter than their diff, and nothing for anything else
+fn excluded_analysis(file: &StagedFile, contents: Option<Contents>) -> Vec<String> {
+    let summary = if file_analyzers::is_lockfile(&file.path) {
+        analyze_file(file, contents)
+    } else {
+        Vec::new()
+    };
+    if summary.is_empty() {
+        vec!["[Analysis excluded]".to_string()]
+    } else {
+        summary
+    }
+}
+
+/// The bytes of a blob, or `None` for a file missing on that side of a diff
+fn blob_bytes(repo: &Repository, id: git2::Oid) -> Option<Vec<u8>> {
+    if id.is_zero() {
+        return None;
+    }
+    repo.find_blob(id).ok().map(|blob| blob.content().to_vec())
+}
+
+/// The text of a blob, or `None` for a file missing on that side of a diff or not UTF-8
+fn blob_text(repo: &Repository, id: git2::Oid) -> Option<String> {
+    String::from_utf8(blob_bytes(repo, id)?).ok()
+}
 
-    Ok(context)
+/// The bytes of the new side of a change, read from the working tree when the diff didn't
+/// record a blob for it, as diffs against the working tree do for changed files
+fn new_blob_bytes(repo: &Repository, id: git2::Oid, path: &str) -> Option<Vec<u8>> {
+    blob_bytes(repo, id)
+        .or_else(|| repo.workdir().and_then(|workdir| fs::read(workdir.join(path)).ok()))
 }
 
 fn get_current_branch(repo: &Repository) -> Result<String> {
@@ -33,19 +272,42 @@ fn get_current_branch(repo: &Repository) -> Result<String> {
     Ok(head.shorthand().unwrap_or("HEAD detached").to_string())
 }
 
-fn get_recent_commits(repo: &Repository, count: usize) -> Result<Vec<RecentCommit>> {
+pub fn get_current_branch_name(repo_path: &Path) -> Result<String> {
+    let repo = Repository::open(repo_path)?;
+    get_current_branch(&repo)
+}
+
+fn get_recent_commits(repo: &Repository, recent: &RecentCommitsConfig) -> Result<Vec<RecentCommit>> {
+    let head = repo.head()?.peel_to_commit()?;
+    get_recent_commits_from(repo, head.id(), recent)
+}
+
+/// The commits leading up to `start`, with only their subjects unless bodies are configured
+fn get_recent_commits_from(
+    repo: &Repository,
+    start: git2::Oid,
+    recent: &RecentCommitsConfig,
+) -> Result<Vec<RecentCommit>> {
+    if recent.count == 0 {
+        return Ok(Vec::new());
+    }
     let mut revwalk = repo.revwalk()?;
-    revwalk.push_head()?;
+    revwalk.push(start)?;
 
     let commits = revwalk
-        .take(count)
+        .take(recent.count)
         .map(|oid| {
             let oid = oid?;
             let commit = repo.find_commit(oid)?;
             let author = commit.author();
+            let message = if recent.bodies {
+                commit.message().unwrap_or_default().to_string()
+            } else {
+                commit.summary().unwrap_or_default().to_string()
+            };
             Ok(RecentCommit {
                 hash: oid.to_string(),
-                message: commit.message().unwrap_or_default().to_string(),
+                message,
                 author: author.name().unwrap_or_default().to_string(),
                 timestamp: commit.time().seconds().to_string(),
             })
@@ -55,9 +317,26 @@ fn get_recent_commits(repo: &Repository, count: usize) -> Result<Vec<RecentCommi
     Ok(commits)
 }
 
-pub fn get_commits_between(repo_path: &Path, from: &str, to: &str) -> Result<Vec<AnalyzedChange>> {
+pub fn get_commits_between(
+    repo_path: &Path,
+    from: &str,
+    to: &str,
+    filter: &PromptFilter,
+) -> Result<Vec<AnalyzedChange>> {
+    get_commits_between_with_progress(repo_path, from, to, filter, |_, _| {})
+}
+
+/// Analyze the commits between two references, calling `on_progress` with the number of
+/// commits analyzed so far and the total after each one
+pub fn get_commits_between_with_progress(
+    repo_path: &Path,
+    from: &str,
+    to: &str,
+    filter: &PromptFilter,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<Vec<AnalyzedChange>> {
     let repo = Repository::open(repo_path)?;
-    let analyzer = ChangeAnalyzer::new(&repo);
+    let analyzer = ChangeAnalyzer::new(&repo).with_filter(filter);
 
     let from_commit = repo.revparse_single(from)?.peel_to_commit()?;
     let to_commit = repo.revparse_single(to)?.peel_to_commit()?;
@@ -65,6 +344,85 @@ pub fn get_commits_between(repo_path: &Path, from: &str, to: &str) -> Result<Vec
     let mut revwalk = repo.revwalk()?;
     revwalk.push(to_commit.id())?;
     revwalk.hide(from_commit.id())?;
+    let commit_ids: Vec<git2::Oid> = revwalk.filter_map(|id| id.ok()).collect();
+
+    let total = commit_ids.len();
+    let mut analyzed_commits = Vec::with_capacity(total);
+    for (index, id) in commit_ids.into_iter().enumerate() {
+        if let Some(change) = repo
+            .find_commit(id)
+            .ok()
+            .and_then(|commit| analyzer.analyze_commit(&commit).ok())
+        {
+            analyzed_commits.push(change);
+        }
+        on_progress(index + 1, total);
+    }
+
+    Ok(analyzed_commits)
+}
+
+/// Analyze a single commit, given by hash or reference
+pub fn analyze_commit(
+    repo_path: &Path,
+    reference: &str,
+    filter: &PromptFilter,
+) -> Result<AnalyzedChange> {
+    let repo = Repository::open(repo_path)?;
+    let commit = repo
+        .revparse_single(reference)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|_| anyhow!("Commit '{}' not found", reference))?;
+    ChangeAnalyzer::new(&repo)
+        .with_filter(filter)
+        .analyze_commit(&commit)
+}
+
+/// Get the unified diff a commit introduces relative to its first parent, with the changes to
+/// files the filter excludes left out
+pub fn get_commit_patch(
+    repo_path: &Path,
+    reference: &str,
+    filter: &PromptFilter,
+) -> Result<String> {
+    let repo = Repository::open(repo_path)?;
+    let commit = repo.revparse_single(reference)?.peel_to_commit()?;
+    let parent_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+
+    let mut patch = String::new();
+    diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
+        let path = delta.new_file().path().or_else(|| delta.old_file().path());
+        if path.is_some_and(|path| filter.is_excluded(&path.to_string_lossy())) {
+            // Keep the file header so the patch still shows which files changed
+            if line.origin() == 'F' {
+                patch.push_str(&String::from_utf8_lossy(line.content()));
+                patch.push_str("[Content excluded]\n");
+            }
+            return true;
+        }
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin());
+        }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })?;
+
+    Ok(patch)
+}
+
+/// Analyze every commit reachable from the given reference
+pub fn get_all_commits(
+    repo_path: &Path,
+    to: &str,
+    filter: &PromptFilter,
+) -> Result<Vec<AnalyzedChange>> {
+    let repo = Repository::open(repo_path)?;
+    let analyzer = ChangeAnalyzer::new(&repo).with_filter(filter);
+
+    let to_commit = repo.revparse_single(to)?.peel_to_commit()?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(to_commit.id())?;
 
     let analyzed_commits = revwalk
         .filter_map(|id| id.ok())
@@ -75,39 +433,40 @@ pub fn get_commits_between(repo_path: &Path, from: &str, to: &str) -> Result<Vec
     Ok(analyzed_commits)
 }
 
-fn should_exclude_file(path: &str) -> bool {
-    let exclude_patterns = vec![
-        String::from(r"\.git"),
-        String::from(r"\.svn"),
-        String::from(r"\.hg"),
-        String::from(r"\.DS_Store"),
-        String::from(r"node_modules"),
-        String::from(r"target"),
-        String::from(r"build"),
-        String::from(r"dist"),
-        String::from(r"\.vscode"),
-        String::from(r"\.idea"),
-        String::from(r"\.vs"),
-        String::from(r"package-lock\.json"),
-        String::from(r"\.lock"),
-        String::from(r"\.log"),
-        String::from(r"\.tmp"),
-        String::from(r"\.temp"),
-        String::from(r"\.swp"),
-        String::from(r"\.min\.js"),
-        // Add more patterns as needed
-    ];
-
-    for pattern in exclude_patterns {
-        let re = Regex::new(&pattern).unwrap();
-        if re.is_match(path) {
-            return true;
-        }
-    }
-    false
+/// Get the URL of the named remote
+pub fn get_remote_url(repo_path: &Path, remote_name: &str) -> Result<String> {
+    let repo = Repository::open(repo_path)?;
+    let remote = repo
+        .find_remote(remote_name)
+        .map_err(|_| anyhow!("Remote '{}' not found", remote_name))?;
+    remote
+        .url()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("Remote '{}' has no URL", remote_name))
 }
 
-fn get_file_statuses(repo: &Repository) -> Result<(Vec<StagedFile>, Vec<String>)> {
+/// Get the analyzed commits on the current branch since it diverged from `base`
+pub fn get_branch_commits(
+    repo_path: &Path,
+    base: &str,
+    filter: &PromptFilter,
+) -> Result<Vec<AnalyzedChange>> {
+    let repo = Repository::open(repo_path)?;
+
+    let base_commit = repo
+        .revparse_single(base)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|_| anyhow!("Base reference '{}' not found", base))?;
+    let head_commit = repo.head()?.peel_to_commit()?;
+    let merge_base = repo.merge_base(base_commit.id(), head_commit.id())?;
+
+    get_commits_between(repo_path, &merge_base.to_string(), "HEAD", filter)
+}
+
+fn get_file_statuses(
+    repo: &Repository,
+    filter: &PromptFilter,
+) -> Result<(Vec<StagedFile>, Vec<String>)> {
     let mut staged_files = Vec::new();
     let mut unstaged_files = Vec::new();
 
@@ -128,25 +487,35 @@ fn get_file_statuses(repo: &Repository) -> Result<(Vec<StagedFile>, Vec<String>)
                 ChangeType::Deleted
             };
 
-            let should_exclude = should_exclude_file(path);
-            let diff = if should_exclude {
+            let should_exclude = filter.is_excluded(path);
+            let diff = if should_exclude && !file_analyzers::is_lockfile(path) {
                 String::from("[Content excluded]")
             } else {
                 get_diff_for_file(repo, path, true)?
             };
 
-            let analyzer = file_analyzers::get_analyzer(path);
             let staged_file = StagedFile {
                 path: path.to_string(),
                 change_type: change_type.clone(),
                 diff: diff.clone(),
                 analysis: Vec::new(),
                 content_excluded: should_exclude,
+                stage_status: StageStatus::Staged,
             };
+            let contents = file_analyzers::analyzes_contents(path)
+                .then(|| staged_contents(repo, path));
             let analysis = if should_exclude {
-                vec!["[Analysis excluded]".to_string()]
+                excluded_analysis(&staged_file, contents)
+            } else if diff == BINARY_DIFF {
+                let (old, new) = staged_bytes(repo, path);
+                file_analyzers::analyze_binary(old.as_deref(), new.as_deref())
             } else {
-                analyzer.analyze(path, &staged_file)
+                analyze_file(&staged_file, contents)
+            };
+            let diff = if should_exclude {
+                String::from("[Content excluded]")
+            } else {
+                diff
             };
 
             staged_files.push(StagedFile {
@@ -155,6 +524,7 @@ fn get_file_statuses(repo: &Repository) -> Result<(Vec<StagedFile>, Vec<String>)
                 diff,
                 analysis,
                 content_excluded: should_exclude,
+                stage_status: StageStatus::Staged,
             });
         } else if status.is_wt_modified() || status.is_wt_new() || status.is_wt_deleted() {
             unstaged_files.push(path.to_string());
@@ -164,18 +534,91 @@ fn get_file_statuses(repo: &Repository) -> Result<(Vec<StagedFile>, Vec<String>)
     Ok((staged_files, unstaged_files))
 }
 
+/// The contents of a file in `HEAD` and in the index
+fn staged_contents(repo: &Repository, path: &str) -> Contents {
+    let (old, new) = staged_bytes(repo, path);
+    let text = |bytes: Option<Vec<u8>>| bytes.and_then(|bytes| String::from_utf8(bytes).ok());
+    (text(old), text(new))
+}
+
+/// The bytes of a file in `HEAD` and in the index
+fn staged_bytes(repo: &Repository, path: &str) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+    let old = repo
+        .head()
+        .and_then(|head| head.peel_to_tree())
+        .and_then(|tree| tree.get_path(Path::new(path)))
+        .ok()
+        .and_then(|entry| blob_bytes(repo, entry.id()));
+    let new = repo
+        .index()
+        .ok()
+        .and_then(|index| index.get_path(Path::new(path), 0))
+        .and_then(|entry| blob_bytes(repo, entry.id));
+    (old, new)
+}
+
+/// Collect working-tree changes to tracked files, plus staged ones for `ChangeScope::All`
+fn get_working_tree_files(
+    repo: &Repository,
+    scope: ChangeScope,
+    filter: &PromptFilter,
+) -> Result<(Vec<StagedFile>, Vec<String>)> {
+    let diff = match scope {
+        ChangeScope::Unstaged => repo.diff_index_to_workdir(None, None)?,
+        _ => {
+            let head_tree = repo.head()?.peel_to_tree()?;
+            repo.diff_tree_to_workdir_with_index(Some(&head_tree), None)?
+        }
+    };
+    let mut files = get_diff_files(repo, &diff, filter)?;
+
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true);
+    let statuses = repo.statuses(Some(&mut opts))?;
+
+    let mut untracked_files = Vec::new();
+    for entry in statuses.iter() {
+        let Some(path) = entry.path() else { continue };
+        let status = entry.status();
+        if status.is_wt_new() {
+            untracked_files.push(path.to_string());
+            continue;
+        }
+
+        let in_index = status.is_index_new()
+            || status.is_index_modified()
+            || status.is_index_deleted()
+            || status.is_index_renamed()
+            || status.is_index_typechange();
+        let in_worktree = status.is_wt_modified()
+            || status.is_wt_deleted()
+            || status.is_wt_renamed()
+            || status.is_wt_typechange();
+        let stage_status = match (in_index, in_worktree) {
+            (true, true) => StageStatus::PartiallyStaged,
+            (false, true) => StageStatus::Unstaged,
+            _ => StageStatus::Staged,
+        };
+        if let Some(file) = files.iter_mut().find(|f| f.path == path) {
+            file.stage_status = stage_status;
+        }
+    }
+
+    Ok((files, untracked_files))
+}
+
 fn get_diff_for_file(repo: &Repository, path: &str, staged: bool) -> Result<String> {
     let mut diff_options = DiffOptions::new();
     diff_options.pathspec(path);
 
-    let tree = if staged {
-        Some(repo.head()?.peel_to_tree()?)
+    // Staged diffs stop at the index, so edits made after staging don't leak into them
+    let diff = if staged {
+        let tree = repo.head()?.peel_to_tree()?;
+        repo.diff_tree_to_index(Some(&tree), None, Some(&mut diff_options))?
     } else {
-        None
+        repo.diff_index_to_workdir(None, Some(&mut diff_options))?
     };
 
-    let diff = repo.diff_tree_to_workdir_with_index(tree.as_ref(), Some(&mut diff_options))?;
-
     let mut diff_string = String::new();
     diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
         let origin = match line.origin() {
@@ -188,7 +631,7 @@ fn get_diff_for_file(repo: &Repository, path: &str, staged: bool) -> Result<Stri
     })?;
 
     if is_binary_diff(&diff_string) {
-        Ok("[Binary file changed]".to_string())
+        Ok(BINARY_DIFF.to_string())
     } else {
         Ok(diff_string)
     }
@@ -278,6 +721,189 @@ pub fn commit(repo_path: &Path, message: &str) -> Result<()> {
     Ok(())
 }
 
+/// Write the index as a tree and return its ID, capturing exactly what is staged
+pub fn write_index_tree(repo_path: &Path) -> Result<String> {
+    let repo = Repository::open(repo_path)?;
+    let tree_id = repo.index()?.write_tree()?;
+    Ok(tree_id.to_string())
+}
+
+/// Stage every change to tracked files and commit, like `git commit -a`
+pub fn commit_all(repo_path: &Path, message: &str) -> Result<()> {
+    stage_all(repo_path, false)?;
+    commit(repo_path, message)
+}
+
+/// Stage every change to tracked files, and new files too when `include_untracked` is set
+pub fn stage_all(repo_path: &Path, include_untracked: bool) -> Result<()> {
+    let repo = Repository::open(repo_path)?;
+    let mut index = repo.index()?;
+    if include_untracked {
+        // Ignored files are skipped, as with `git add -A`
+        index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
+    }
+    index.update_all(["*"].iter(), None)?;
+    index.write()?;
+    Ok(())
+}
+
+/// Check whether the index holds any staged changes
+pub fn has_staged_changes(repo_path: &Path) -> Result<bool> {
+    let repo = Repository::open(repo_path)?;
+    let statuses = repo.statuses(None)?;
+    Ok(statuses.iter().any(|entry| {
+        let status = entry.status();
+        status.is_index_new()
+            || status.is_index_modified()
+            || status.is_index_deleted()
+            || status.is_index_renamed()
+            || status.is_index_typechange()
+    }))
+}
+
+/// Check whether a tag with the given name exists
+pub fn tag_exists(repo_path: &Path, name: &str) -> Result<bool> {
+    let repo = Repository::open(repo_path)?;
+    let exists = repo.find_reference(&format!("refs/tags/{}", name)).is_ok();
+    Ok(exists)
+}
+
+/// Stage the given files, relative to the repository root, and commit them
+pub fn commit_paths(repo_path: &Path, paths: &[&Path], message: &str) -> Result<()> {
+    let repo = Repository::open(repo_path)?;
+    let mut index = repo.index()?;
+    for path in paths {
+        index.add_path(path)?;
+    }
+    index.write()?;
+    commit(repo_path, message)
+}
+
+/// Push a tag to the given remote with the `git` command, so the user's credentials are used
+pub fn push_tag(repo_path: &Path, remote: &str, tag: &str) -> Result<()> {
+    let output = std::process::Command::new("git")
+        .arg("push")
+        .arg(remote)
+        .arg(format!("refs/tags/{}", tag))
+        .current_dir(repo_path)
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Failed to push tag '{}' to '{}': {}",
+            tag,
+            remote,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Replace the message of the HEAD commit, keeping its tree, author, and parents
+pub fn amend_head_message(repo_path: &Path, message: &str) -> Result<()> {
+    let repo = Repository::open(repo_path)?;
+    let head = repo.head()?.peel_to_commit()?;
+    head.amend(Some("HEAD"), None, None, None, Some(message), None)?;
+    Ok(())
+}
+
+/// Get the full message of the given commit
+pub fn get_commit_message(repo_path: &Path, reference: &str) -> Result<String> {
+    let repo = Repository::open(repo_path)?;
+    let commit = repo.revparse_single(reference)?.peel_to_commit()?;
+    Ok(commit.message().unwrap_or_default().to_string())
+}
+
+/// Get the character that starts comment lines in commit messages, from `core.commentChar`
+///
+/// Falls back to `#` when the setting is missing or set to `auto`.
+pub fn get_comment_char(repo_path: &Path) -> char {
+    Repository::open(repo_path)
+        .and_then(|repo| repo.config())
+        .and_then(|git_config| git_config.get_string("core.commentChar"))
+        .ok()
+        .filter(|value| value != "auto")
+        .and_then(|value| value.chars().next())
+        .unwrap_or('#')
+}
+
+/// Write a message to `.git/SQUASH_MSG`, where `git commit` picks it up after `git merge --squash`
+pub fn write_squash_msg(repo_path: &Path, message: &str) -> Result<std::path::PathBuf> {
+    let repo = Repository::open(repo_path)?;
+    let path = repo.path().join("SQUASH_MSG");
+    fs::write(&path, format!("{}\n", message.trim_end()))?;
+    Ok(path)
+}
+
+/// Create a branch at HEAD and switch to it, keeping any uncommitted changes
+pub fn create_and_checkout_branch(repo_path: &Path, name: &str) -> Result<()> {
+    let repo = Repository::open(repo_path)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let branch = repo
+        .branch(name, &head, false)
+        .map_err(|e| anyhow!("Failed to create branch '{}': {}", name, e.message()))?;
+    let reference = branch
+        .get()
+        .name()
+        .ok_or_else(|| anyhow!("Invalid branch name '{}'", name))?;
+    // The new branch points at the current commit, so the working tree stays as it is
+    repo.set_head(reference)?;
+    Ok(())
+}
+
+/// Stash all uncommitted changes with the given message, like `git stash push -m`
+pub fn stash_push(repo_path: &Path, message: &str, include_untracked: bool) -> Result<()> {
+    let mut repo = Repository::open(repo_path)?;
+    let signature = repo.signature()?;
+    let flags = if include_untracked {
+        git2::StashFlags::INCLUDE_UNTRACKED
+    } else {
+        git2::StashFlags::DEFAULT
+    };
+    repo.stash_save(&signature, message, Some(flags))
+        .map_err(|e| anyhow!("Failed to stash changes: {}", e.message()))?;
+    Ok(())
+}
+
+/// Resolve a reference to the full hash of the commit it points to
+pub fn resolve_commit_id(repo_path: &Path, reference: &str) -> Result<String> {
+    let repo = Repository::open(repo_path)?;
+    let commit = repo.revparse_single(reference)?.peel_to_commit()?;
+    Ok(commit.id().to_string())
+}
+
+/// Get the most recent tag reachable from the given reference, if any
+pub fn get_latest_tag(repo_path: &Path, reference: &str) -> Result<Option<String>> {
+    let repo = Repository::open(repo_path)?;
+    let commit = repo.revparse_single(reference)?.peel_to_commit()?;
+
+    let mut describe_options = DescribeOptions::new();
+    describe_options.describe_tags();
+    let describe = match commit.as_object().describe(&describe_options) {
+        Ok(describe) => describe,
+        Err(_) => return Ok(None),
+    };
+
+    let mut format_options = DescribeFormatOptions::new();
+    format_options.abbreviated_size(0);
+    Ok(Some(describe.format(Some(&format_options))?))
+}
+
+/// Create an annotated tag pointing at the given reference
+pub fn create_annotated_tag(
+    repo_path: &Path,
+    name: &str,
+    target: &str,
+    message: &str,
+) -> Result<()> {
+    let repo = Repository::open(repo_path)?;
+    let signature = repo.signature()?;
+    let target = repo.revparse_single(target)?.peel_to_commit()?;
+
+    repo.tag(name, target.as_object(), &signature, message, false)
+        .map_err(|e| anyhow!("Failed to create tag '{}': {}", name, e.message()))?;
+    Ok(())
+}
+
 pub fn find_and_read_readme(repo_path: &Path) -> Result<Option<String>> {
     let readme_patterns = ["README.md", "README.txt", "README", "Readme.md"];
 
@@ -1,65 +1,404 @@
+use crate::log_debug;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{PoisonError, RwLock};
 
-fn create_gitmoji_map() -> HashMap<&'static str, (&'static str, &'static str)> {
-    let mut m = HashMap::new();
-
-    m.insert("feat", ("✨", "Introduce new features"));
-    m.insert("fix", ("🐛", "Fix a bug"));
-    m.insert("docs", ("📝", "Add or update documentation"));
-    m.insert("style", ("💄", "Add or update the UI and style files"));
-    m.insert("refactor", ("♻️", "Refactor code"));
-    m.insert("perf", ("⚡️", "Improve performance"));
-    m.insert("test", ("✅", "Add or update tests"));
-    m.insert("build", ("👷", "Add or update build scripts"));
-    m.insert("ci", ("🔧", "Add or update CI configuration"));
-    m.insert("chore", ("🔨", "Other changes that don't modify src or test files"));
-    m.insert("revert", ("⏪️", "Revert changes"));
-    m.insert("wip", ("🚧", "Work in progress"));
-    m.insert("dependencies", ("⬆️", "Update dependencies"));
-    m.insert("remove", ("🔥", "Remove code or files"));
-    m.insert("i18n", ("🌐", "Internationalization and localization"));
-    m.insert("security", ("🔒️", "Fix security issues"));
-    m.insert("debug", ("🐛", "Add or update debugging code"));
-    m.insert("deployment", ("🚀", "Deploy stuff"));
-    m.insert("hotfix", ("🚑", "Critical hotfix"));
-    m.insert("accessibility", ("♿", "Improve accessibility"));
-    m.insert("analytics", ("📈", "Add or update analytics"));
-    m.insert("seo", ("🔍️", "Improve SEO"));
-    m.insert("config", ("🔧", "Add or update configuration files"));
-    m.insert("tracking", ("📈", "Add or update tracking code"));
-    m.insert("design", ("🎨", "Improve structure / format of the code"));
-    m.insert("error", ("🚨", "Fix compiler / linter warnings"));
-    m.insert("test_failure", ("💥", "Fix tests or CI failures"));
-    m.insert("data", ("📊", "Add or update data"));
-    m.insert("content", ("📝", "Add or update content"));
-    m.insert("linter", ("👕", "Add or update linters"));
-    m.insert("initial", ("🎉", "Begin a project"));
-
-    m
+/// Built-in gitmojis: type, emoji, shortcode, and description
+const BUILT_IN_GITMOJIS: &[(&str, &str, &str, &str)] = &[
+    ("feat", "✨", "sparkles", "Introduce new features"),
+    ("fix", "🐛", "bug", "Fix a bug"),
+    ("docs", "📝", "memo", "Add or update documentation"),
+    ("style", "💄", "lipstick", "Add or update the UI and style files"),
+    ("refactor", "♻️", "recycle", "Refactor code"),
+    ("perf", "⚡️", "zap", "Improve performance"),
+    ("test", "✅", "white_check_mark", "Add or update tests"),
+    ("build", "👷", "construction_worker", "Add or update build scripts"),
+    ("ci", "🔧", "wrench", "Add or update CI configuration"),
+    ("chore", "🔨", "hammer", "Other changes that don't modify src or test files"),
+    ("revert", "⏪️", "rewind", "Revert changes"),
+    ("wip", "🚧", "construction", "Work in progress"),
+    ("dependencies", "⬆️", "arrow_up", "Update dependencies"),
+    ("remove", "🔥", "fire", "Remove code or files"),
+    ("i18n", "🌐", "globe_with_meridians", "Internationalization and localization"),
+    ("security", "🔒️", "lock", "Fix security issues"),
+    ("debug", "🐛", "bug", "Add or update debugging code"),
+    ("deployment", "🚀", "rocket", "Deploy stuff"),
+    ("hotfix", "🚑", "ambulance", "Critical hotfix"),
+    ("accessibility", "♿", "wheelchair", "Improve accessibility"),
+    ("analytics", "📈", "chart_with_upwards_trend", "Add or update analytics"),
+    ("seo", "🔍️", "mag", "Improve SEO"),
+    ("config", "🔧", "wrench", "Add or update configuration files"),
+    ("tracking", "📈", "chart_with_upwards_trend", "Add or update tracking code"),
+    ("design", "🎨", "art", "Improve structure / format of the code"),
+    ("error", "🚨", "rotating_light", "Fix compiler / linter warnings"),
+    ("test_failure", "💥", "boom", "Fix tests or CI failures"),
+    ("data", "📊", "bar_chart", "Add or update data"),
+    ("content", "📝", "memo", "Add or update content"),
+    ("linter", "👕", "shirt", "Add or update linters"),
+    ("initial", "🎉", "tada", "Begin a project"),
+];
+
+/// Other gitmoji.dev shortcodes a mapping file may use, with their emoji
+const OTHER_SHORTCODES: &[(&str, &str)] = &[
+    ("adhesive_bandage", "🩹"),
+    ("alembic", "⚗️"),
+    ("alien", "👽️"),
+    ("arrow_down", "⬇️"),
+    ("bento", "🍱"),
+    ("bookmark", "🔖"),
+    ("bricks", "🧱"),
+    ("building_construction", "🏗️"),
+    ("card_file_box", "🗃️"),
+    ("children_crossing", "🚸"),
+    ("closed_lock_with_key", "🔐"),
+    ("coffin", "⚰️"),
+    ("dizzy", "💫"),
+    ("goal_net", "🥅"),
+    ("green_heart", "💚"),
+    ("heavy_minus_sign", "➖"),
+    ("heavy_plus_sign", "➕"),
+    ("label", "🏷️"),
+    ("loud_sound", "🔊"),
+    ("monocle_face", "🧐"),
+    ("mute", "🔇"),
+    ("necktie", "👔"),
+    ("package", "📦️"),
+    ("page_facing_up", "📄"),
+    ("passport_control", "🛂"),
+    ("pencil2", "✏️"),
+    ("pushpin", "📌"),
+    ("see_no_evil", "🙈"),
+    ("seedling", "🌱"),
+    ("speech_balloon", "💬"),
+    ("technologist", "🧑‍💻"),
+    ("test_tube", "🧪"),
+    ("triangular_flag_on_post", "🚩"),
+    ("truck", "🚚"),
+    ("wastebasket", "🗑️"),
+];
+
+/// How gitmojis are written in commit messages
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GitmojiStyle {
+    /// The emoji itself, e.g. ✨
+    #[default]
+    Unicode,
+    /// A shortcode such as `:sparkles:`, as written by devmoji and rendered by GitHub
+    Shortcode,
+}
+
+impl FromStr for GitmojiStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "unicode" | "emoji" => Ok(GitmojiStyle::Unicode),
+            "shortcode" | "code" => Ok(GitmojiStyle::Shortcode),
+            _ => Err(anyhow!("Invalid gitmoji style: {}. Use unicode or shortcode", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for GitmojiStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitmojiStyle::Unicode => write!(f, "unicode"),
+            GitmojiStyle::Shortcode => write!(f, "shortcode"),
+        }
+    }
+}
+
+/// A gitmoji with its name and description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gitmoji {
+    /// The type of change it stands for, e.g. `feat`
+    pub name: String,
+    /// The emoji, or its shortcode with colons if there is no known emoji for it
+    pub emoji: String,
+    /// The shortcode without colons, e.g. `sparkles`
+    pub code: Option<String>,
+    pub description: String,
+}
+
+impl Gitmoji {
+    /// How the gitmoji is written in messages with the active style
+    pub fn text(&self) -> String {
+        self.text_in(gitmoji_style())
+    }
+
+    fn text_in(&self, style: GitmojiStyle) -> String {
+        match (style, &self.code) {
+            (GitmojiStyle::Shortcode, Some(code)) => format!(":{}:", code),
+            _ => self.emoji.clone(),
+        }
+    }
+
+    /// Whether a word from a message is this gitmoji, written in either style
+    pub fn is_written_as(&self, word: &str) -> bool {
+        without_variation_selectors(word) == without_variation_selectors(&self.emoji)
+            || self.code.as_deref().is_some_and(|code| {
+                word.strip_prefix(':').and_then(|word| word.strip_suffix(':')) == Some(code)
+            })
+    }
+}
+
+/// The gitmojis in use and how they're written
+struct GitmojiSet {
+    gitmojis: Vec<Gitmoji>,
+    style: GitmojiStyle,
 }
 
 lazy_static::lazy_static! {
-    static ref GITMOJI_MAP: HashMap<&'static str, (&'static str, &'static str)> = create_gitmoji_map();
+    static ref ACTIVE: RwLock<GitmojiSet> = RwLock::new(GitmojiSet {
+        gitmojis: built_in_gitmojis(),
+        style: GitmojiStyle::default(),
+    });
+}
+
+/// The built-in gitmojis, sorted by name
+pub fn built_in_gitmojis() -> Vec<Gitmoji> {
+    let mut gitmojis: Vec<Gitmoji> = BUILT_IN_GITMOJIS
+        .iter()
+        .map(|&(name, emoji, code, description)| Gitmoji {
+            name: name.to_string(),
+            emoji: emoji.to_string(),
+            code: Some(code.to_string()),
+            description: description.to_string(),
+        })
+        .collect();
+    gitmojis.sort_by(|a, b| a.name.cmp(&b.name));
+    gitmojis
+}
+
+/// Use these gitmojis and style instead of the built-in ones for the rest of the process
+pub fn set_gitmojis(mut gitmojis: Vec<Gitmoji>, style: GitmojiStyle) {
+    gitmojis.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut active = ACTIVE.write().unwrap_or_else(PoisonError::into_inner);
+    *active = GitmojiSet { gitmojis, style };
+}
+
+/// Use the gitmojis from a mapping file, if one is configured, written in the given style
+///
+/// If the file can't be used, the built-in gitmojis are used and the error is returned so the
+/// caller can report it.
+pub fn configure(mapping_file: Option<&Path>, style: GitmojiStyle) -> Result<()> {
+    let (gitmojis, result) = match mapping_file {
+        Some(path) => match load_mapping(path) {
+            Ok(gitmojis) => {
+                log_debug!("Using the gitmojis from {}", path.display());
+                (gitmojis, Ok(()))
+            }
+            Err(e) => (built_in_gitmojis(), Err(e)),
+        },
+        None => (built_in_gitmojis(), Ok(())),
+    };
+    set_gitmojis(gitmojis, style);
+    result
+}
+
+/// The style gitmojis are written in
+pub fn gitmoji_style() -> GitmojiStyle {
+    ACTIVE.read().unwrap_or_else(PoisonError::into_inner).style
+}
+
+#[derive(Deserialize)]
+struct MappingFile {
+    /// Use only the file's gitmojis instead of adding them to the built-in ones
+    #[serde(default)]
+    replace: bool,
+    #[serde(default)]
+    types: HashMap<String, MappingEntry>,
+}
+
+#[derive(Deserialize)]
+struct MappingEntry {
+    emoji: String,
+    description: Option<String>,
 }
 
-pub fn get_gitmoji(commit_type: &str) -> Option<&'static str> {
-    GITMOJI_MAP.get(commit_type).map(|&(emoji, _)| emoji)
+/// Read a gitmoji mapping file
+pub fn load_mapping(path: &Path) -> Result<Vec<Gitmoji>> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Unable to read the gitmoji file {}: {}", path.display(), e))?;
+    parse_mapping(&content)
+        .map_err(|e| anyhow!("Invalid gitmoji file {}: {}", path.display(), e))
+}
+
+/// Parse a gitmoji mapping: a `[types.<name>]` table for each type with its `emoji`, either the
+/// emoji itself or a `:shortcode:`, and a `description`
+///
+/// Types are added to the built-in gitmojis, replacing those with the same name, unless
+/// `replace = true` is set. Descriptions can be left out for built-in types.
+pub fn parse_mapping(content: &str) -> Result<Vec<Gitmoji>> {
+    let file: MappingFile = toml::from_str(content)?;
+    let built_in = built_in_gitmojis();
+    let mut gitmojis = if file.replace {
+        Vec::new()
+    } else {
+        built_in.clone()
+    };
+    for (name, entry) in file.types {
+        let written = entry.emoji.trim();
+        if written.is_empty() {
+            return Err(anyhow!("'{}' has no emoji", name));
+        }
+        let (emoji, code) = match written.strip_prefix(':').and_then(|w| w.strip_suffix(':')) {
+            Some(code) => (
+                emoji_for_code(code).unwrap_or(written).to_string(),
+                Some(code.to_string()),
+            ),
+            None => (written.to_string(), code_for_emoji(written).map(str::to_string)),
+        };
+        let description = entry
+            .description
+            .or_else(|| {
+                built_in
+                    .iter()
+                    .find(|gitmoji| gitmoji.name == name)
+                    .map(|gitmoji| gitmoji.description.clone())
+            })
+            .ok_or_else(|| anyhow!("'{}' needs a description", name))?;
+        gitmojis.retain(|gitmoji| gitmoji.name != name);
+        gitmojis.push(Gitmoji {
+            name,
+            emoji,
+            code,
+            description,
+        });
+    }
+    if gitmojis.is_empty() {
+        return Err(anyhow!("no gitmojis are defined"));
+    }
+    gitmojis.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(gitmojis)
+}
+
+fn emoji_for_code(code: &str) -> Option<&'static str> {
+    BUILT_IN_GITMOJIS
+        .iter()
+        .map(|&(_, emoji, known, _)| (known, emoji))
+        .chain(OTHER_SHORTCODES.iter().copied())
+        .find(|(known, _)| *known == code)
+        .map(|(_, emoji)| emoji)
+}
+
+fn code_for_emoji(emoji: &str) -> Option<&'static str> {
+    let emoji = without_variation_selectors(emoji);
+    BUILT_IN_GITMOJIS
+        .iter()
+        .map(|&(_, known, code, _)| (code, known))
+        .chain(OTHER_SHORTCODES.iter().copied())
+        .find(|(_, known)| without_variation_selectors(known) == emoji)
+        .map(|(code, _)| code)
+}
+
+/// Models often leave out or add the variation selector that asks for emoji presentation
+fn without_variation_selectors(text: &str) -> String {
+    text.chars().filter(|c| *c != '\u{fe0f}').collect()
+}
+
+/// The gitmoji for a type of change, written in the active style
+pub fn get_gitmoji(commit_type: &str) -> Option<String> {
+    let active = ACTIVE.read().unwrap_or_else(PoisonError::into_inner);
+    active
+        .gitmojis
+        .iter()
+        .find(|gitmoji| gitmoji.name == commit_type)
+        .map(|gitmoji| gitmoji.text_in(active.style))
+}
+
+/// Get every way a gitmoji in use can be written: its emoji and its `:shortcode:`
+pub fn get_gitmoji_emojis() -> Vec<String> {
+    let active = ACTIVE.read().unwrap_or_else(PoisonError::into_inner);
+    let mut emojis = Vec::new();
+    for gitmoji in &active.gitmojis {
+        emojis.push(gitmoji.emoji.clone());
+        if let Some(code) = &gitmoji.code {
+            emojis.push(format!(":{}:", code));
+        }
+    }
+    emojis
+}
+
+/// Every gitmoji in use, sorted by name
+pub fn all_gitmojis() -> Vec<Gitmoji> {
+    ACTIVE
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .gitmojis
+        .clone()
+}
+
+/// Gitmojis matching a search, best matches first
+///
+/// Names starting with the query rank first, then names or descriptions containing it, then
+/// names whose letters contain the query's letters in order, so "rfc" finds "refactor".
+pub fn search_gitmojis(query: &str) -> Vec<Gitmoji> {
+    let query = query.trim().to_lowercase();
+    let mut ranked: Vec<(u8, Gitmoji)> = all_gitmojis()
+        .into_iter()
+        .filter_map(|gitmoji| {
+            let description = gitmoji.description.to_lowercase();
+            let rank = if gitmoji.name.starts_with(&query) {
+                0
+            } else if gitmoji.name.contains(&query) || description.contains(&query) {
+                1
+            } else if is_subsequence(&query, &gitmoji.name) {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, gitmoji))
+        })
+        .collect();
+    ranked.sort_by(|(rank_a, a), (rank_b, b)| (rank_a, &a.name).cmp(&(rank_b, &b.name)));
+    ranked.into_iter().map(|(_, gitmoji)| gitmoji).collect()
+}
+
+fn is_subsequence(query: &str, text: &str) -> bool {
+    let mut chars = text.chars();
+    query.chars().all(|c| chars.any(|t| t == c))
 }
 
 pub fn apply_gitmoji(commit_message: &str) -> String {
     let parts: Vec<&str> = commit_message.splitn(2, ':').collect();
     if parts.len() == 2 {
-        if let Some((gitmoji, _)) = GITMOJI_MAP.get(parts[0].trim()) {
+        if let Some(gitmoji) = get_gitmoji(parts[0].trim()) {
             return format!("{} {}: {}", gitmoji, parts[0].trim(), parts[1].trim());
         }
     }
     commit_message.to_string()
 }
 
+/// Rewrite the gitmoji leading a message's subject line in the active style, so a model that
+/// answered with `:sparkles:` instead of ✨, or the other way around, still matches it
+pub fn render_gitmoji(message: &str) -> String {
+    let message = message.trim_start();
+    let first = &message[..message.find(char::is_whitespace).unwrap_or(message.len())];
+    if first.is_empty() {
+        return message.to_string();
+    }
+    let active = ACTIVE.read().unwrap_or_else(PoisonError::into_inner);
+    match active.gitmojis.iter().find(|gitmoji| gitmoji.is_written_as(first)) {
+        Some(gitmoji) => format!("{}{}", gitmoji.text_in(active.style), &message[first.len()..]),
+        None => message.to_string(),
+    }
+}
+
 pub fn get_gitmoji_list() -> String {
-    GITMOJI_MAP
+    let active = ACTIVE.read().unwrap_or_else(PoisonError::into_inner);
+    active
+        .gitmojis
         .iter()
-        .map(|(key, (emoji, description))| format!("{} - :{}: - {}", emoji, key, description))
+        .map(|gitmoji| {
+            let text = gitmoji.text_in(active.style);
+            format!("{} - :{}: - {}", text, gitmoji.name, gitmoji.description)
+        })
         .collect::<Vec<String>>()
         .join("\n")
 }
@@ -0,0 +1,51 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Instructions asking the model to use the project's own terms, or an empty string if the
+/// glossary is empty
+///
+/// Meanings that look like a path in the repository are described as the code a term refers
+/// to, so that changes there are called by the project's name for them.
+pub fn glossary_instructions(glossary: &HashMap<String, String>) -> String {
+    let mut entries: Vec<(&String, &String)> = glossary
+        .iter()
+        .filter(|(term, meaning)| !term.trim().is_empty() && !meaning.trim().is_empty())
+        .collect();
+    if entries.is_empty() {
+        return String::new();
+    }
+    entries.sort_by_key(|(term, _)| term.to_lowercase());
+
+    let mut instructions = String::from(
+        "Use the project's established terminology instead of generic phrasing. \
+        Glossary:\n",
+    );
+    for (term, meaning) in entries {
+        let meaning = meaning.trim();
+        if is_path(meaning) {
+            let _ = writeln!(
+                instructions,
+                "- \"{}\": the code in {}; call changes there by this name",
+                term.trim(),
+                meaning
+            );
+        } else {
+            let _ = writeln!(instructions, "- \"{}\": {}", term.trim(), meaning);
+        }
+    }
+    instructions.trim_end().to_string()
+}
+
+/// Parse a `TERM=MEANING` glossary entry; an empty meaning removes the term
+pub fn parse_entry(entry: &str) -> Result<(String, String)> {
+    entry
+        .split_once('=')
+        .map(|(term, meaning)| (term.trim().to_string(), meaning.trim().to_string()))
+        .filter(|(term, _)| !term.is_empty())
+        .ok_or_else(|| anyhow!("Invalid glossary entry '{}'. Use TERM=MEANING", entry))
+}
+
+fn is_path(meaning: &str) -> bool {
+    meaning.contains('/') && !meaning.contains(char::is_whitespace) && !meaning.contains("://")
+}
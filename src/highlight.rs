@@ -0,0 +1,193 @@
+use std::path::Path;
+
+/// Languages the diff view knows how to highlight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    Go,
+    /// C, C++, Java, C#, Kotlin, Swift, and others with C-style syntax
+    CLike,
+    Shell,
+    /// TOML, YAML, and INI files
+    Config,
+    Plain,
+}
+
+/// What a piece of highlighted code is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Keyword,
+    String,
+    Comment,
+    Number,
+    Plain,
+}
+
+impl Language {
+    /// Pick the language from a file's extension or name
+    pub fn from_path(path: &str) -> Self {
+        let path = Path::new(path);
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        match extension.as_str() {
+            "rs" => Language::Rust,
+            "py" | "pyi" => Language::Python,
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => Language::JavaScript,
+            "go" => Language::Go,
+            "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "java" | "cs" | "kt" | "kts" | "swift"
+            | "scala" | "dart" => Language::CLike,
+            "sh" | "bash" | "zsh" | "fish" => Language::Shell,
+            "toml" | "yaml" | "yml" | "ini" | "cfg" | "conf" => Language::Config,
+            _ => match path.file_name().and_then(|name| name.to_str()) {
+                Some("Makefile" | "Dockerfile" | ".bashrc" | ".zshrc") => Language::Shell,
+                _ => Language::Plain,
+            },
+        }
+    }
+
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &[
+                "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else",
+                "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop",
+                "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static",
+                "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+            ],
+            Language::Python => &[
+                "and", "as", "assert", "async", "await", "break", "class", "continue", "def",
+                "del", "elif", "else", "except", "False", "finally", "for", "from", "global",
+                "if", "import", "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass",
+                "raise", "return", "True", "try", "while", "with", "yield",
+            ],
+            Language::JavaScript => &[
+                "async", "await", "break", "case", "catch", "class", "const", "continue",
+                "default", "delete", "else", "export", "extends", "false", "finally", "for",
+                "function", "if", "import", "in", "instanceof", "interface", "let", "new",
+                "null", "return", "switch", "this", "throw", "true", "try", "type", "typeof",
+                "undefined", "var", "void", "while", "yield",
+            ],
+            Language::Go => &[
+                "break", "case", "chan", "const", "continue", "default", "defer", "else",
+                "fallthrough", "false", "for", "func", "go", "goto", "if", "import",
+                "interface", "map", "nil", "package", "range", "return", "select", "struct",
+                "switch", "true", "type", "var",
+            ],
+            Language::CLike => &[
+                "break", "case", "catch", "class", "const", "continue", "default", "delete",
+                "do", "else", "enum", "extends", "false", "final", "for", "fun", "func", "if",
+                "implements", "import", "include", "interface", "let", "namespace", "new",
+                "null", "nullptr", "override", "package", "private", "protected", "public",
+                "return", "static", "struct", "switch", "this", "throw", "true", "try",
+                "typedef", "val", "var", "virtual", "void", "while",
+            ],
+            Language::Shell => &[
+                "case", "do", "done", "elif", "else", "esac", "export", "fi", "for",
+                "function", "if", "in", "local", "return", "then", "until", "while",
+            ],
+            Language::Config => &["false", "true", "null", "yes", "no"],
+            Language::Plain => &[],
+        }
+    }
+
+    fn line_comment(self) -> Option<&'static str> {
+        match self {
+            Language::Rust | Language::JavaScript | Language::Go | Language::CLike => Some("//"),
+            Language::Python | Language::Shell | Language::Config => Some("#"),
+            Language::Plain => None,
+        }
+    }
+
+    fn has_block_comments(self) -> bool {
+        matches!(
+            self,
+            Language::Rust | Language::JavaScript | Language::Go | Language::CLike
+        )
+    }
+
+    /// Quote characters that start a string; Rust leaves out `'` because of lifetimes
+    fn quotes(self) -> &'static [char] {
+        match self {
+            Language::Rust => &['"'],
+            Language::Go | Language::JavaScript => &['"', '\'', '`'],
+            Language::Plain => &[],
+            _ => &['"', '\''],
+        }
+    }
+}
+
+/// Split a line of code into highlighted tokens
+///
+/// Each line is highlighted on its own, so comments and strings spanning several lines are
+/// only recognized on the line they start.
+pub fn tokenize(code: &str, language: Language) -> Vec<(TokenKind, String)> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut tokens: Vec<(TokenKind, String)> = Vec::new();
+    let mut push = |kind: TokenKind, text: String| match tokens.last_mut() {
+        Some((last_kind, last_text)) if *last_kind == kind => last_text.push_str(&text),
+        _ => tokens.push((kind, text)),
+    };
+
+    let mut index = 0;
+    while index < chars.len() {
+        let rest: String = chars[index..].iter().collect();
+        let c = chars[index];
+
+        if language.line_comment().is_some_and(|marker| rest.starts_with(marker)) {
+            push(TokenKind::Comment, rest);
+            break;
+        }
+        if language.has_block_comments() && rest.starts_with("/*") {
+            let length = rest[2..].find("*/").map_or(rest.len(), |end| end + 4);
+            let comment: String = rest[..length].to_string();
+            index += comment.chars().count();
+            push(TokenKind::Comment, comment);
+            continue;
+        }
+        if language.quotes().contains(&c) {
+            let mut end = index + 1;
+            while end < chars.len() && chars[end] != c {
+                if chars[end] == '\\' {
+                    end += 1;
+                }
+                end += 1;
+            }
+            let end = (end + 1).min(chars.len());
+            push(TokenKind::String, chars[index..end].iter().collect());
+            index = end;
+            continue;
+        }
+        if c.is_ascii_digit() {
+            let end = word_end(&chars, index);
+            push(TokenKind::Number, chars[index..end].iter().collect());
+            index = end;
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            let end = word_end(&chars, index);
+            let word: String = chars[index..end].iter().collect();
+            let kind = if language.keywords().contains(&word.as_str()) {
+                TokenKind::Keyword
+            } else {
+                TokenKind::Plain
+            };
+            push(kind, word);
+            index = end;
+            continue;
+        }
+        push(TokenKind::Plain, c.to_string());
+        index += 1;
+    }
+    tokens
+}
+
+fn word_end(chars: &[char], start: usize) -> usize {
+    chars[start..]
+        .iter()
+        .position(|c| !(c.is_alphanumeric() || *c == '_'))
+        .map_or(chars.len(), |offset| start + offset)
+}
@@ -0,0 +1,117 @@
+use crate::context::StagedFile;
+use crate::log_debug;
+use crate::session::session_key;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const HISTORY_FILE: &str = "history.json";
+
+/// Most messages kept per repository; older ones are dropped
+pub const MAX_HISTORY_ENTRIES: usize = 100;
+
+/// A commit message generated earlier
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    /// When the message was generated, in RFC 3339 format
+    pub timestamp: String,
+    /// Hash of the changes the message was generated for
+    pub diff_hash: String,
+    pub message: String,
+}
+
+impl HistoryEntry {
+    /// The timestamp in local time, for display
+    pub fn local_time(&self) -> String {
+        DateTime::parse_from_rfc3339(&self.timestamp)
+            .map(|time| time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|_| self.timestamp.clone())
+    }
+}
+
+/// Every commit message generated for a repository, oldest first
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GenerationHistory {
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl GenerationHistory {
+    pub fn load(repo_path: &Path) -> Self {
+        history_path(repo_path)
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, repo_path: &Path) -> Result<()> {
+        fs::write(history_path(repo_path)?, serde_json::to_string_pretty(self)?)?;
+        log_debug!("Generation history saved ({} entries)", self.entries.len());
+        Ok(())
+    }
+
+    /// Add a message, unless it repeats the newest one for the same changes
+    pub fn push(&mut self, message: &str, diff_hash: &str, time: DateTime<Local>) {
+        let message = message.trim();
+        if self
+            .entries
+            .last()
+            .is_some_and(|last| last.message == message && last.diff_hash == diff_hash)
+        {
+            return;
+        }
+        self.entries.push(HistoryEntry {
+            timestamp: time.to_rfc3339(),
+            diff_hash: diff_hash.to_string(),
+            message: message.to_string(),
+        });
+        let excess = self.entries.len().saturating_sub(MAX_HISTORY_ENTRIES);
+        self.entries.drain(..excess);
+    }
+
+    /// The entry with the given number, where 1 is the newest
+    pub fn get(&self, number: usize) -> Result<&HistoryEntry> {
+        if number == 0 || number > self.entries.len() {
+            return Err(anyhow!(
+                "No message #{} in the history ({} saved)",
+                number,
+                self.entries.len()
+            ));
+        }
+        Ok(&self.entries[self.entries.len() - number])
+    }
+
+    /// Entries with their numbers, newest first
+    pub fn newest_first(&self) -> impl Iterator<Item = (usize, &HistoryEntry)> {
+        self.entries.iter().rev().enumerate().map(|(index, entry)| (index + 1, entry))
+    }
+}
+
+/// Save a generated message to the repository's history
+pub fn record_generation(repo_path: &Path, message: &str, diff_hash: &str) {
+    let mut history = GenerationHistory::load(repo_path);
+    history.push(message, diff_hash, Local::now());
+    if let Err(e) = history.save(repo_path) {
+        log_debug!("Failed to save the generation history: {}", e);
+    }
+}
+
+/// Identify a set of changes, so messages can be matched to the changes they describe
+pub fn changes_hash(files: &[StagedFile]) -> String {
+    let parts: Vec<&str> = files
+        .iter()
+        .flat_map(|file| [file.path.as_str(), file.diff.as_str()])
+        .collect();
+    session_key(&parts)
+}
+
+/// The history lives inside the repository's `.git` directory so it never shows up as a change
+fn history_path(repo_path: &Path) -> Result<PathBuf> {
+    let repo = Repository::open(repo_path)?;
+    let dir = repo.path().join("git-iris");
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(HISTORY_FILE))
+}
@@ -0,0 +1,196 @@
+use crate::cherry_pick;
+use crate::git;
+use anyhow::{anyhow, Result};
+use git2::Repository;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the hook Git-Iris installs
+pub const HOOK_NAME: &str = "prepare-commit-msg";
+
+/// Name of the hook that lints commit messages
+pub const LINT_HOOK_NAME: &str = "commit-msg";
+
+/// Marker that identifies hook scripts written by Git-Iris
+const HOOK_MARKER: &str = "# Installed by git-iris";
+
+/// Suffix of the backup kept when replacing an existing hook
+const BACKUP_SUFFIX: &str = ".git-iris-backup";
+
+/// What the hook should do for a given commit message source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookAction {
+    /// Generate a commit message from the staged changes
+    CommitMessage,
+    /// Generate a merge commit message
+    MergeMessage,
+    /// Adapt the message of a cherry-picked commit to the current branch
+    CherryPickMessage,
+    /// Leave the message Git prepared untouched
+    Skip,
+}
+
+/// Decide what to do from the source argument Git passes to prepare-commit-msg
+pub fn hook_action(source: Option<&str>) -> HookAction {
+    match source.unwrap_or("") {
+        "" => HookAction::CommitMessage,
+        "merge" => HookAction::MergeMessage,
+        // message (-m/-F), template, squash, and commit (-c/-C/--amend) already have a message
+        _ => HookAction::Skip,
+    }
+}
+
+/// Decide what to do for a commit in the repository
+///
+/// During a cherry-pick, Git passes `merge` or `message` as the source. The picked commit's message is
+/// adapted only while the message file still holds it unchanged, so messages given with `-m` are kept.
+pub fn hook_action_in_repo(
+    repo_path: &Path,
+    message_file: &Path,
+    source: Option<&str>,
+) -> HookAction {
+    if matches!(source, Some("merge" | "message"))
+        && cherry_pick::is_cherry_pick_in_progress(repo_path)
+    {
+        let comment_char = git::get_comment_char(repo_path);
+        if cherry_pick::has_default_cherry_pick_message(repo_path, message_file, comment_char) {
+            return HookAction::CherryPickMessage;
+        }
+        return HookAction::Skip;
+    }
+    hook_action(source)
+}
+
+/// The script written to the hooks directory
+pub fn hook_script() -> String {
+    format!(
+        "#!/bin/sh\n{}\n# Generates commit messages with AI. Remove with: git-iris hooks uninstall\nexec git-iris hooks run \"$1\" \"$2\" \"$3\"\n",
+        HOOK_MARKER
+    )
+}
+
+/// The commit-msg script that lints the message before the commit is created
+pub fn lint_hook_script() -> String {
+    format!(
+        "#!/bin/sh\n{}\n# Lints commit messages. Remove with: git-iris hooks uninstall\nexec git-iris lint --message-file \"$1\"\n",
+        HOOK_MARKER
+    )
+}
+
+/// Install the prepare-commit-msg hook, returning its path.
+/// An existing hook that was not written by Git-Iris is only replaced with `force`, and is backed up.
+pub fn install_hook(repo_path: &Path, force: bool) -> Result<PathBuf> {
+    install_script(repo_path, HOOK_NAME, &hook_script(), force)
+}
+
+/// Install the commit-msg hook that lints commit messages, returning its path
+pub fn install_lint_hook(repo_path: &Path, force: bool) -> Result<PathBuf> {
+    install_script(repo_path, LINT_HOOK_NAME, &lint_hook_script(), force)
+}
+
+/// Remove the hook if Git-Iris installed it, restoring any backed-up hook.
+/// Returns whether a hook was removed.
+pub fn uninstall_hook(repo_path: &Path) -> Result<bool> {
+    remove_script(repo_path, HOOK_NAME)
+}
+
+/// Remove the commit-msg hook if Git-Iris installed it, restoring any backed-up hook
+pub fn uninstall_lint_hook(repo_path: &Path) -> Result<bool> {
+    remove_script(repo_path, LINT_HOOK_NAME)
+}
+
+fn install_script(repo_path: &Path, name: &str, script: &str, force: bool) -> Result<PathBuf> {
+    let hook_path = hooks_dir(repo_path)?.join(name);
+
+    if hook_path.exists() && !is_git_iris_hook(&hook_path) {
+        if !force {
+            return Err(anyhow!(
+                "A {} hook already exists at {}. Use --force to replace it (a backup will be kept)",
+                name,
+                hook_path.display()
+            ));
+        }
+        fs::rename(&hook_path, backup_path(&hook_path))?;
+    }
+
+    fs::write(&hook_path, script)?;
+    make_executable(&hook_path)?;
+    Ok(hook_path)
+}
+
+fn remove_script(repo_path: &Path, name: &str) -> Result<bool> {
+    let hook_path = hooks_dir(repo_path)?.join(name);
+
+    if !hook_path.exists() {
+        return Ok(false);
+    }
+    if !is_git_iris_hook(&hook_path) {
+        return Err(anyhow!(
+            "The {} hook at {} was not installed by git-iris; leaving it in place",
+            name,
+            hook_path.display()
+        ));
+    }
+
+    fs::remove_file(&hook_path)?;
+    let backup = backup_path(&hook_path);
+    if backup.exists() {
+        fs::rename(backup, &hook_path)?;
+    }
+    Ok(true)
+}
+
+/// Check whether a prepare-commit-msg hook exists that was not installed by Git-Iris
+pub fn has_other_hook(repo_path: &Path) -> bool {
+    hooks_dir(repo_path)
+        .map(|dir| {
+            let path = dir.join(HOOK_NAME);
+            path.exists() && !is_git_iris_hook(&path)
+        })
+        .unwrap_or(false)
+}
+
+/// Whether the Git-Iris hook is installed in the repository
+pub fn is_hook_installed(repo_path: &Path) -> bool {
+    hooks_dir(repo_path)
+        .map(|dir| is_git_iris_hook(&dir.join(HOOK_NAME)))
+        .unwrap_or(false)
+}
+
+/// The hooks directory, honoring `core.hooksPath`
+fn hooks_dir(repo_path: &Path) -> Result<PathBuf> {
+    let repo = Repository::open(repo_path)?;
+    let dir = match repo.config()?.get_path("core.hooksPath") {
+        Ok(path) if path.is_absolute() => path,
+        Ok(path) => repo.workdir().unwrap_or_else(|| repo.path()).join(path),
+        Err(_) => repo.path().join("hooks"),
+    };
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn is_git_iris_hook(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .map(|content| content.contains(HOOK_MARKER))
+        .unwrap_or(false)
+}
+
+fn backup_path(hook_path: &Path) -> PathBuf {
+    let mut name = hook_path.as_os_str().to_owned();
+    name.push(BACKUP_SUFFIX);
+    PathBuf::from(name)
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_mode(0o755);
+    fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
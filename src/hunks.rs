@@ -0,0 +1,132 @@
+use anyhow::{anyhow, Result};
+use git2::{ApplyLocation, ApplyOptions, Diff, DiffOptions, Patch, Repository};
+use std::cell::Cell;
+use std::path::Path;
+
+/// Whether a hunk is in the index or only in the working tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HunkSide {
+    Staged,
+    Unstaged,
+}
+
+/// A single hunk of a staged or unstaged change to a tracked file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    pub path: String,
+    pub side: HunkSide,
+    /// Position of the hunk among the file's hunks on the same side
+    pub index: usize,
+    pub header: String,
+    /// Lines of the hunk, each prefixed with '+', '-', or ' '
+    pub lines: Vec<String>,
+}
+
+impl Hunk {
+    pub fn is_staged(&self) -> bool {
+        self.side == HunkSide::Staged
+    }
+}
+
+/// List the hunks of staged changes, followed by those of unstaged changes to tracked files
+///
+/// Binary files have no hunks and are left out; stage them with `git add`.
+pub fn list_hunks(repo_path: &Path) -> Result<Vec<Hunk>> {
+    let repo = Repository::open(repo_path)?;
+    let mut hunks = collect_hunks(&side_diff(&repo, HunkSide::Staged, false)?, HunkSide::Staged)?;
+    hunks.extend(collect_hunks(
+        &side_diff(&repo, HunkSide::Unstaged, false)?,
+        HunkSide::Unstaged,
+    )?);
+    Ok(hunks)
+}
+
+/// Stage an unstaged hunk, or unstage a staged one, leaving the working tree untouched
+///
+/// Hunk positions shift once the index changes, so list the hunks again after each toggle.
+pub fn toggle_hunk(repo_path: &Path, hunk: &Hunk) -> Result<()> {
+    let repo = Repository::open(repo_path)?;
+    // Unstaging applies the staged change in reverse
+    let diff = side_diff(&repo, hunk.side, hunk.is_staged())?;
+
+    let in_file = Cell::new(false);
+    let position = Cell::new(0);
+    let found = Cell::new(false);
+    let mut options = ApplyOptions::new();
+    options.delta_callback(|delta| {
+        let matches = delta.is_some_and(|delta| delta_path(&delta) == hunk.path);
+        in_file.set(matches);
+        position.set(0);
+        matches
+    });
+    options.hunk_callback(|_| {
+        if !in_file.get() {
+            return false;
+        }
+        let selected = position.get() == hunk.index;
+        position.set(position.get() + 1);
+        if selected {
+            found.set(true);
+        }
+        selected
+    });
+
+    repo.apply(&diff, ApplyLocation::Index, Some(&mut options))?;
+    if !found.get() {
+        return Err(anyhow!(
+            "Hunk {} of '{}' is no longer there; the changes were modified",
+            hunk.index + 1,
+            hunk.path
+        ));
+    }
+    Ok(())
+}
+
+fn side_diff(repo: &Repository, side: HunkSide, reverse: bool) -> Result<Diff<'_>> {
+    let mut options = DiffOptions::new();
+    options.reverse(reverse);
+    let diff = match side {
+        HunkSide::Staged => {
+            let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+            repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut options))?
+        }
+        HunkSide::Unstaged => repo.diff_index_to_workdir(None, Some(&mut options))?,
+    };
+    Ok(diff)
+}
+
+fn collect_hunks(diff: &Diff, side: HunkSide) -> Result<Vec<Hunk>> {
+    let mut hunks = Vec::new();
+    for delta_index in 0..diff.deltas().len() {
+        let Some(patch) = Patch::from_diff(diff, delta_index)? else {
+            continue;
+        };
+        let path = delta_path(&patch.delta());
+        for hunk_index in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(hunk_index)?;
+            let mut lines = Vec::with_capacity(line_count);
+            for line_index in 0..line_count {
+                let line = patch.line_in_hunk(hunk_index, line_index)?;
+                let content = String::from_utf8_lossy(line.content());
+                lines.push(format!("{}{}", line.origin(), content.trim_end_matches('\n')));
+            }
+            hunks.push(Hunk {
+                path: path.clone(),
+                side,
+                index: hunk_index,
+                header: String::from_utf8_lossy(hunk.header()).trim_end().to_string(),
+                lines,
+            });
+        }
+    }
+    Ok(hunks)
+}
+
+fn delta_path(delta: &git2::DiffDelta) -> String {
+    delta
+        .new_file()
+        .path()
+        .or_else(|| delta.old_file().path())
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
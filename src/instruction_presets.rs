@@ -1,5 +1,12 @@
+use crate::log_debug;
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Directory of a repository's own presets, relative to its root
+pub const REPO_PRESETS_DIR: &str = ".git-iris/presets";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct InstructionPreset {
@@ -180,8 +187,86 @@ impl InstructionPresetLibrary {
     pub fn list_presets(&self) -> Vec<(&String, &InstructionPreset)> {
         self.presets.iter().collect()
     }
+
+    /// Add a preset, replacing any existing preset with the same key
+    pub fn add_preset(&mut self, key: String, preset: InstructionPreset) {
+        self.presets.insert(key, preset);
+    }
+}
+
+/// Check whether a key names one of the built-in presets
+pub fn is_builtin_preset(key: &str) -> bool {
+    InstructionPresetLibrary::new().get_preset(key).is_some()
 }
 
 pub fn get_instruction_preset_library() -> InstructionPresetLibrary {
     InstructionPresetLibrary::new()
 }
+
+/// A preset file in a repository's preset directory, as written in TOML
+#[derive(Deserialize)]
+struct PresetFile {
+    name: Option<String>,
+    description: Option<String>,
+    instructions: String,
+}
+
+/// Read a preset from a repository's preset directory, keyed by its file name
+///
+/// A `.toml` file holds `instructions` and optionally a `name` and `description`, while the
+/// whole of a `.md` or `.txt` file is the instructions. Returns `None` for other files.
+pub fn read_repo_preset(path: &Path) -> Result<Option<(String, InstructionPreset)>> {
+    let (Some(key), Some(extension)) = (
+        path.file_stem().and_then(|stem| stem.to_str()),
+        path.extension().and_then(|extension| extension.to_str()),
+    ) else {
+        return Ok(None);
+    };
+    let content = match extension {
+        "toml" | "md" | "txt" => fs::read_to_string(path)
+            .map_err(|e| anyhow!("Unable to read {}: {}", path.display(), e))?,
+        _ => return Ok(None),
+    };
+
+    let preset = if extension == "toml" {
+        let file: PresetFile = toml::from_str(&content)
+            .map_err(|e| anyhow!("Invalid preset in {}: {}", path.display(), e))?;
+        InstructionPreset {
+            name: file.name.unwrap_or_else(|| key.to_string()),
+            description: file.description.unwrap_or_else(|| "Repository preset".to_string()),
+            instructions: file.instructions.trim().to_string(),
+        }
+    } else {
+        InstructionPreset {
+            name: key.to_string(),
+            description: "Repository preset".to_string(),
+            instructions: content.trim().to_string(),
+        }
+    };
+    if preset.instructions.is_empty() {
+        return Err(anyhow!("The preset in {} has no instructions", path.display()));
+    }
+    Ok(Some((key.to_string(), preset)))
+}
+
+/// Read every preset in a repository's preset directory, skipping broken files and files
+/// named after a built-in preset
+pub fn load_repo_presets(dir: &Path) -> HashMap<String, InstructionPreset> {
+    let mut presets = HashMap::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return presets;
+    };
+    for entry in entries.flatten() {
+        match read_repo_preset(&entry.path()) {
+            Ok(Some((key, _))) if is_builtin_preset(&key) => {
+                log_debug!("Ignoring the repository preset '{}', a built-in preset", key);
+            }
+            Ok(Some((key, preset))) => {
+                presets.insert(key, preset);
+            }
+            Ok(None) => {}
+            Err(e) => log_debug!("Skipping a repository preset: {}", e),
+        }
+    }
+    presets
+}
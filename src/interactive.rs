@@ -1,4 +1,9 @@
+use crate::commit_message::{candidate_diff, replace_gitmoji, split_leading_emoji, subject_line};
+use crate::config::LintConfig;
 use crate::git;
+use crate::gitmoji::{search_gitmojis, Gitmoji};
+use crate::hunks::{self, Hunk};
+use crate::lint::lint_message;
 use crate::log_debug;
 use crate::ui;
 use anyhow::Result;
@@ -9,6 +14,22 @@ use std::process::Command;
 use textwrap;
 use unicode_width::UnicodeWidthStr;
 
+/// Most changed lines shown when comparing two candidates
+const MAX_CANDIDATE_DIFF_LINES: usize = 6;
+
+/// Most hunks listed at once when staging hunks
+const MAX_LISTED_HUNKS: usize = 10;
+
+/// Most gitmojis listed at once in the picker
+const MAX_LISTED_GITMOJIS: usize = 10;
+
+/// Feedback on a generated message, for the model to revise it rather than start from scratch
+#[derive(Debug, Clone)]
+pub struct Refinement {
+    pub previous_message: String,
+    pub feedback: String,
+}
+
 pub struct InteractiveCommit {
     messages: Vec<String>,
     current_index: usize,
@@ -16,6 +37,11 @@ pub struct InteractiveCommit {
     combined_instructions: String,
     program_name: String,
     program_version: String,
+    amend: bool,
+    commit_all: bool,
+    hunk_staging: bool,
+    use_gitmoji: bool,
+    lint: LintConfig,
 }
 
 impl InteractiveCommit {
@@ -32,12 +58,65 @@ impl InteractiveCommit {
             combined_instructions,
             program_name,
             program_version,
+            amend: false,
+            commit_all: false,
+            hunk_staging: false,
+            use_gitmoji: false,
+            lint: LintConfig::default(),
         }
     }
 
+    /// Replace the message of the HEAD commit instead of creating a new commit
+    pub fn set_amend(&mut self, amend: bool) {
+        self.amend = amend;
+    }
+
+    /// Stage every change to tracked files before committing, like `git commit -a`
+    pub fn set_commit_all(&mut self, commit_all: bool) {
+        self.commit_all = commit_all;
+    }
+
+    /// Allow staging and unstaging individual hunks, regenerating the message afterwards
+    pub fn set_hunk_staging(&mut self, hunk_staging: bool) {
+        self.hunk_staging = hunk_staging;
+    }
+
+    /// Offer the gitmoji picker, for messages generated with gitmoji
+    pub fn set_gitmoji(&mut self, use_gitmoji: bool) {
+        self.use_gitmoji = use_gitmoji;
+    }
+
+    /// Add another generated message to page through
+    pub fn add_candidate(&mut self, message: String) {
+        self.messages.push(message);
+    }
+
+    /// The message currently shown, which is the one committed
+    pub fn selected_message(&self) -> &str {
+        &self.messages[self.current_index]
+    }
+
+    /// The other candidates that differ from the selected message, in the order they were made
+    pub fn rejected_candidates(&self) -> Vec<String> {
+        let selected = self.selected_message().trim();
+        let mut rejected: Vec<String> = Vec::new();
+        for message in &self.messages {
+            let message = message.trim();
+            if message != selected && !rejected.iter().any(|other| other == message) {
+                rejected.push(message.to_string());
+            }
+        }
+        rejected
+    }
+
+    /// Rules each message is checked against before it is shown
+    pub fn set_lint_config(&mut self, lint: LintConfig) {
+        self.lint = lint;
+    }
+
     pub async fn run<F, Fut>(&mut self, generate_message: F) -> Result<bool>
     where
-        F: Fn(&str) -> Fut,
+        F: Fn(&str, Option<Refinement>) -> Fut,
         Fut: std::future::Future<Output = Result<String>>,
     {
         let mut term = Term::stdout();
@@ -68,6 +147,28 @@ impl InteractiveCommit {
                         self.edit_instructions(&generate_message).await?;
                     }
                 }
+                Key::Char('f') | Key::Char('F') => {
+                    if !self.generating {
+                        self.generating = true;
+                        self.refine_message(&mut term, &generate_message).await?;
+                        self.generating = false;
+                    }
+                }
+                Key::Char('h') | Key::Char('H') if !self.generating && self.hunk_staging => {
+                    let index_changed = self.stage_hunks(&mut term)?;
+                    if index_changed {
+                        self.generating = true;
+                        self.regenerate_message(&generate_message).await?;
+                        self.generating = false;
+                    }
+                }
+                Key::Char('g') | Key::Char('G') if !self.generating && self.use_gitmoji => {
+                    let picked = self.pick_gitmoji(&mut term)?;
+                    if let Some(gitmoji) = picked {
+                        let message = &self.messages[self.current_index];
+                        self.messages[self.current_index] = replace_gitmoji(message, &gitmoji.text());
+                    }
+                }
                 Key::Char('r') | Key::Char('R') => {
                     if !self.generating {
                         self.generating = true;
@@ -107,6 +208,18 @@ impl InteractiveCommit {
 
         self.display_message_box(term, &self.messages[self.current_index], term_width)?;
 
+        let current_message = &self.messages[self.current_index];
+        if self.messages.len() > 1 {
+            self.display_candidate_diff(term)?;
+        }
+        for violation in lint_message(current_message, &self.lint) {
+            writeln!(
+                term,
+                "{}",
+                format!("⚠️  {}", violation.message).yellow().bold()
+            )?;
+        }
+
         writeln!(term)?;
 
         if !self.combined_instructions.trim().is_empty() {
@@ -128,6 +241,45 @@ impl InteractiveCommit {
         Ok(())
     }
 
+    /// Show how the current candidate differs from the one before it (or after, for the first)
+    fn display_candidate_diff(&self, term: &mut Term) -> Result<()> {
+        let other = if self.current_index > 0 {
+            self.current_index - 1
+        } else {
+            1
+        };
+        let changes = candidate_diff(
+            &self.messages[other],
+            &self.messages[self.current_index],
+            MAX_CANDIDATE_DIFF_LINES,
+        );
+        if changes.is_empty() {
+            writeln!(
+                term,
+                "{}",
+                format!("Same as candidate {}", other + 1).dimmed()
+            )?;
+            return Ok(());
+        }
+
+        writeln!(
+            term,
+            "{}",
+            format!("Compared with candidate {}:", other + 1).dimmed()
+        )?;
+        for change in changes {
+            let styled = if change.starts_with("+ ") {
+                change.green()
+            } else if change.starts_with("- ") {
+                change.red()
+            } else {
+                change.dimmed()
+            };
+            writeln!(term, "  {}", styled)?;
+        }
+        Ok(())
+    }
+
     fn display_header(&self, term: &mut Term, term_width: usize) -> Result<()> {
         let logo = ui::create_gradient_text(&format!(
             "🔮 {} v{} 🔮",
@@ -239,14 +391,21 @@ impl InteractiveCommit {
     }
 
     fn display_navigation_hints(&self, term: &mut Term) -> Result<()> {
-        let hints = vec![
+        let mut hints = vec![
             ("←→", "Navigate", (147, 112, 219), "🔮"),
             ("e", "Edit", (0, 255, 255), "✏️"),
             ("i", "Instructions", (138, 43, 226), "📜"),
+            ("f", "Feedback", (64, 224, 208), "💬"),
             ("r", "Regenerate", (0, 191, 255), "✨"),
             ("Enter", "Commit", (123, 104, 238), "💫"),
             ("Esc", "Cancel", (255, 20, 147), "🌠"),
         ];
+        if self.use_gitmoji {
+            hints.insert(1, ("g", "Gitmoji", (255, 215, 0), "😀"));
+        }
+        if self.hunk_staging {
+            hints.insert(hints.len() - 2, ("h", "Hunks", (72, 209, 204), "🧩"));
+        }
 
         let mut hint_line = String::new();
         for (i, (key, action, color, emoji)) in hints.iter().enumerate() {
@@ -263,22 +422,185 @@ impl InteractiveCommit {
             ));
         }
 
+        // Center by display width so ANSI codes and wide emoji don't skew the padding
         let term_width = term.size().1 as usize;
-        let padded_hint_line = format!("{:^width$}", hint_line, width = term_width);
+        let hint_width = console::strip_ansi_codes(&hint_line).width();
+        let padding = term_width.saturating_sub(hint_width) / 2;
 
-        writeln!(term, "{}", padded_hint_line)?;
+        writeln!(term, "{}{}", " ".repeat(padding), hint_line)?;
 
         Ok(())
     }
 
+    /// Let the user stage and unstage hunks one at a time. Returns whether the index changed.
+    fn stage_hunks(&self, term: &mut Term) -> Result<bool> {
+        let repo_path = std::env::current_dir()?;
+        let mut selected = 0;
+        let mut changed = false;
+        let mut status = String::new();
+        loop {
+            let hunks = hunks::list_hunks(&repo_path)?;
+            if hunks.is_empty() {
+                return Ok(changed);
+            }
+            selected = selected.min(hunks.len() - 1);
+
+            term.clear_screen()?;
+            let (height, width) = term.size();
+            let term_width = (width - 1) as usize;
+            self.display_title(term, "Hunks", '✦', term_width)?;
+            self.display_hunk_list(term, &hunks, selected)?;
+            writeln!(term)?;
+            let shown_lines = (height as usize).saturating_sub(MAX_LISTED_HUNKS + 8).max(3);
+            display_hunk_lines(term, &hunks[selected], shown_lines, term_width)?;
+            writeln!(term)?;
+            if !status.is_empty() {
+                writeln!(term, "{}", status.red())?;
+            }
+            writeln!(
+                term,
+                "{}  {}  {}",
+                format!("{} : Move", "↑↓".bold()).truecolor(147, 112, 219),
+                format!("{} : Stage/Unstage", "Space".bold()).truecolor(72, 209, 204),
+                format!("{} : Back", "Esc".bold()).truecolor(255, 20, 147)
+            )?;
+
+            status.clear();
+            match term.read_key()? {
+                Key::ArrowUp | Key::Char('k') => selected = selected.saturating_sub(1),
+                Key::ArrowDown | Key::Char('j') => selected += 1,
+                Key::Char(' ') => match hunks::toggle_hunk(&repo_path, &hunks[selected]) {
+                    Ok(()) => changed = true,
+                    Err(e) => status = format!("Could not update the index: {}", e),
+                },
+                Key::Escape | Key::Char('q') => return Ok(changed),
+                _ => {}
+            }
+        }
+    }
+
+    fn display_hunk_list(&self, term: &mut Term, hunks: &[Hunk], selected: usize) -> Result<()> {
+        let start = selected.saturating_sub(MAX_LISTED_HUNKS - 1);
+        for (index, hunk) in hunks.iter().enumerate().skip(start).take(MAX_LISTED_HUNKS) {
+            let marker = if hunk.is_staged() { "[x]" } else { "[ ]" };
+            let line = format!("{} {} {}", marker, hunk.path, hunk.header.dimmed());
+            if index == selected {
+                writeln!(term, "{} {}", "❯".bright_purple().bold(), line.bold())?;
+            } else {
+                writeln!(term, "  {}", line)?;
+            }
+        }
+        if hunks.len() > start + MAX_LISTED_HUNKS {
+            writeln!(
+                term,
+                "  {}",
+                format!("… {} more", hunks.len() - start - MAX_LISTED_HUNKS).dimmed()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Let the user search for another gitmoji, previewing the subject line with the one selected
+    fn pick_gitmoji(&self, term: &mut Term) -> Result<Option<Gitmoji>> {
+        let message = &self.messages[self.current_index];
+        let current = split_leading_emoji(subject_line(message)).0;
+        let mut query = String::new();
+        let mut selected = 0;
+        loop {
+            let matches = search_gitmojis(&query);
+            selected = selected.min(matches.len().saturating_sub(1));
+
+            term.clear_screen()?;
+            let term_width = (term.size().1 - 1) as usize;
+            self.display_title(term, "Gitmoji", '✦', term_width)?;
+            let preview = match matches.get(selected) {
+                Some(gitmoji) => replace_gitmoji(message, &gitmoji.text()),
+                None => message.clone(),
+            };
+            writeln!(term, "{}", subject_line(&preview).bold())?;
+            writeln!(term)?;
+            writeln!(term, "{} {}", "🔍".bright_purple(), query)?;
+
+            let start = selected.saturating_sub(MAX_LISTED_GITMOJIS - 1);
+            let listed = matches.iter().enumerate().skip(start).take(MAX_LISTED_GITMOJIS);
+            for (index, gitmoji) in listed {
+                let mut line = format!(
+                    "{} {} {}",
+                    gitmoji.emoji,
+                    format!(":{}:", gitmoji.name).cyan(),
+                    gitmoji.description.dimmed()
+                );
+                if current.is_some_and(|current| gitmoji.is_written_as(current)) {
+                    line.push_str(&format!(" {}", "(current)".dimmed()));
+                }
+                if index == selected {
+                    writeln!(term, "{} {}", "❯".bright_purple().bold(), line)?;
+                } else {
+                    writeln!(term, "  {}", line)?;
+                }
+            }
+            if matches.is_empty() {
+                writeln!(term, "  {}", "No matching gitmoji".dimmed())?;
+            }
+            writeln!(term)?;
+            writeln!(
+                term,
+                "{}",
+                "Type to search  ↑↓ cycle  Enter apply  Esc cancel".dimmed()
+            )?;
+
+            match term.read_key()? {
+                Key::ArrowUp => selected = selected.saturating_sub(1),
+                Key::ArrowDown => selected += 1,
+                Key::Backspace => {
+                    query.pop();
+                    selected = 0;
+                }
+                Key::Char(c) if !c.is_control() => {
+                    query.push(c);
+                    selected = 0;
+                }
+                Key::Enter => return Ok(matches.get(selected).cloned()),
+                Key::Escape => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+
     async fn regenerate_message<F, Fut>(&mut self, generate_message: &F) -> Result<()>
     where
-        F: Fn(&str) -> Fut,
+        F: Fn(&str, Option<Refinement>) -> Fut,
+        Fut: std::future::Future<Output = Result<String>>,
+    {
+        let spinner = ui::create_spinner(&crate::messages::get_random_message());
+
+        let new_message = generate_message(&self.combined_instructions, None).await?;
+        self.messages.push(new_message);
+        self.current_index = self.messages.len() - 1;
+
+        spinner.finish_and_clear();
+        Ok(())
+    }
+
+    async fn refine_message<F, Fut>(&mut self, term: &mut Term, generate_message: &F) -> Result<()>
+    where
+        F: Fn(&str, Option<Refinement>) -> Fut,
         Fut: std::future::Future<Output = Result<String>>,
     {
+        write!(term, "{} ", "💬 What should change?".cyan().bold())?;
+        let feedback = term.read_line()?;
+        if feedback.trim().is_empty() {
+            return Ok(());
+        }
+
         let spinner = ui::create_spinner(&crate::messages::get_random_message());
 
-        let new_message = generate_message(&self.combined_instructions).await?;
+        let refinement = Refinement {
+            previous_message: self.messages[self.current_index].clone(),
+            feedback: feedback.trim().to_string(),
+        };
+        log_debug!("💬 Refining message with feedback: {}", refinement.feedback);
+        let new_message = generate_message(&self.combined_instructions, Some(refinement)).await?;
         self.messages.push(new_message);
         self.current_index = self.messages.len() - 1;
 
@@ -307,7 +629,7 @@ impl InteractiveCommit {
 
     async fn edit_instructions<F, Fut>(&mut self, generate_message: &F) -> Result<()>
     where
-        F: Fn(&str) -> Fut,
+        F: Fn(&str, Option<Refinement>) -> Fut,
         Fut: std::future::Future<Output = Result<String>>,
     {
         let mut file = tempfile::NamedTempFile::new()?;
@@ -335,7 +657,13 @@ impl InteractiveCommit {
 
         let commit_message = &self.messages[self.current_index];
         let repo_path = std::env::current_dir()?;
-        let result = git::commit(&repo_path, commit_message);
+        let result = if self.amend {
+            git::amend_head_message(&repo_path, commit_message)
+        } else if self.commit_all {
+            git::commit_all(&repo_path, commit_message)
+        } else {
+            git::commit(&repo_path, commit_message)
+        };
 
         spinner.finish_and_clear();
 
@@ -353,3 +681,26 @@ impl InteractiveCommit {
         }
     }
 }
+
+/// Show the lines of a hunk, colored like a diff
+fn display_hunk_lines(term: &mut Term, hunk: &Hunk, max_lines: usize, width: usize) -> Result<()> {
+    for line in hunk.lines.iter().take(max_lines) {
+        let line: String = console::truncate_str(line, width, "…").into_owned();
+        let styled = if line.starts_with('+') {
+            line.green()
+        } else if line.starts_with('-') {
+            line.red()
+        } else {
+            line.normal()
+        };
+        writeln!(term, "{}", styled)?;
+    }
+    if hunk.lines.len() > max_lines {
+        writeln!(
+            term,
+            "{}",
+            format!("… {} more lines", hunk.lines.len() - max_lines).dimmed()
+        )?;
+    }
+    Ok(())
+}
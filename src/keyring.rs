@@ -0,0 +1,93 @@
+use crate::log_debug;
+use anyhow::{anyhow, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Service name the API keys are stored under
+const SERVICE: &str = "git-iris";
+
+/// Whether the operating system's keyring can be used: `security` on macOS, and
+/// `secret-tool` from libsecret elsewhere
+pub fn is_available() -> bool {
+    let tool = if cfg!(target_os = "macos") {
+        "security"
+    } else if cfg!(windows) {
+        return false;
+    } else {
+        "secret-tool"
+    };
+    Command::new(tool)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+}
+
+/// Store a provider's API key in the keyring, replacing any key stored before
+///
+/// The key is written to the tool's stdin rather than passed as an argument, which keeps it
+/// out of the process list.
+pub fn store_api_key(provider: &str, api_key: &str) -> Result<()> {
+    let (mut command, input) = if cfg!(target_os = "macos") {
+        // In interactive mode, security reads its commands from stdin
+        let mut command = Command::new("security");
+        command.arg("-i");
+        let input = format!(
+            "add-generic-password -U -s {} -a {} -w {}\n",
+            quote(SERVICE),
+            quote(provider),
+            quote(api_key)
+        );
+        (command, input)
+    } else {
+        let mut command = Command::new("secret-tool");
+        command
+            .args(["store", "--label", &format!("Git-Iris {} API key", provider)])
+            .args(["service", SERVICE, "account", provider]);
+        (command, api_key.to_string())
+    };
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let status = child.wait()?;
+    // security -i exits successfully even when its command fails, so read the key back
+    let stored = status.success()
+        && (!cfg!(target_os = "macos")
+            || load_api_key(provider)?.as_deref() == Some(api_key.trim()));
+    if !stored {
+        return Err(anyhow!(
+            "Could not store the {} API key in the system keyring",
+            provider
+        ));
+    }
+    log_debug!("Stored the {} API key in the system keyring", provider);
+    Ok(())
+}
+
+/// Quote an argument for a command given to `security -i`
+fn quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Read a provider's API key from the keyring, or `None` if none is stored
+pub fn load_api_key(provider: &str) -> Result<Option<String>> {
+    let output = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args(["find-generic-password", "-s", SERVICE, "-a", provider, "-w"])
+            .output()?
+    } else {
+        Command::new("secret-tool")
+            .args(["lookup", "service", SERVICE, "account", provider])
+            .output()?
+    };
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let api_key = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(Some(api_key).filter(|api_key| !api_key.is_empty()))
+}
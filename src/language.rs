@@ -0,0 +1,158 @@
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Language generated text is written in when none is configured
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// A natural language generated text can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Language {
+    /// ISO 639-1 code, as set with `language = "de"`
+    pub code: &'static str,
+    /// English name, as used in prompts
+    pub name: &'static str,
+    /// The verb form subjects and changelog entries start with, in place of the English
+    /// imperative mood
+    mood: &'static str,
+}
+
+const LANGUAGES: &[Language] = &[
+    Language {
+        code: "en",
+        name: "English",
+        mood: "the imperative mood (e.g., 'Add feature' not 'Added feature')",
+    },
+    Language {
+        code: "de",
+        name: "German",
+        mood: "the imperative (e.g., 'Füge Export hinzu' not 'Export hinzugefügt')",
+    },
+    Language {
+        code: "fr",
+        name: "French",
+        mood: "the infinitive (e.g., 'Ajouter l'export' not 'Ajout de l'export')",
+    },
+    Language {
+        code: "es",
+        name: "Spanish",
+        mood: "the infinitive (e.g., 'Añadir exportación' not 'Añadida exportación')",
+    },
+    Language {
+        code: "it",
+        name: "Italian",
+        mood: "the imperative (e.g., 'Aggiungi esportazione' not 'Aggiunta esportazione')",
+    },
+    Language {
+        code: "pt",
+        name: "Portuguese",
+        mood: "the infinitive (e.g., 'Adicionar exportação' not 'Adicionada exportação')",
+    },
+    Language {
+        code: "nl",
+        name: "Dutch",
+        mood: "the imperative (e.g., 'Voeg export toe' not 'Export toegevoegd')",
+    },
+    Language {
+        code: "sv",
+        name: "Swedish",
+        mood: "the imperative (e.g., 'Lägg till export' not 'Lade till export')",
+    },
+    Language {
+        code: "pl",
+        name: "Polish",
+        mood: "the imperative (e.g., 'Dodaj eksport' not 'Dodano eksport')",
+    },
+    Language {
+        code: "ru",
+        name: "Russian",
+        mood: "the infinitive (e.g., 'Добавить экспорт' not 'Добавлен экспорт')",
+    },
+    Language {
+        code: "ja",
+        name: "Japanese",
+        mood: "a short phrase ending in the plain verb (e.g., 'エクスポートを追加')",
+    },
+    Language {
+        code: "zh",
+        name: "Chinese",
+        mood: "a phrase starting with the verb (e.g., '添加导出功能')",
+    },
+    Language {
+        code: "ko",
+        name: "Korean",
+        mood: "a short phrase ending in the noun form of the verb (e.g., '내보내기 추가')",
+    },
+];
+
+impl Language {
+    /// Every supported language, English first
+    pub fn all() -> &'static [Language] {
+        LANGUAGES
+    }
+
+    pub fn is_english(&self) -> bool {
+        self.code == DEFAULT_LANGUAGE
+    }
+
+    /// The rule for commit subject lines, replacing the English imperative mood rule
+    pub fn subject_rule(&self) -> String {
+        format!("Use {} in the subject line.", self.mood)
+    }
+
+    /// The rule for changelog and release note entries
+    pub fn entry_rule(&self) -> String {
+        format!("Use the present tense and {}.", self.mood)
+    }
+
+    /// Instructions to write all generated text in this language, or an empty string for
+    /// English
+    pub fn instructions(&self) -> String {
+        if self.is_english() {
+            return String::new();
+        }
+        format!(
+            "Write all generated text in {}. Keep code identifiers, file paths, commit hashes, \
+            gitmojis, and Conventional Commits types unchanged. Where these instructions ask \
+            for the imperative mood, use {} instead.",
+            self.name, self.mood
+        )
+    }
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        LANGUAGES[0]
+    }
+}
+
+impl FromStr for Language {
+    type Err = anyhow::Error;
+
+    /// Look up a language by code or English name, ignoring case and any region, as in `pt-BR`
+    fn from_str(s: &str) -> Result<Self> {
+        let wanted = s.trim().to_lowercase();
+        let code = wanted.split(['-', '_']).next().unwrap_or_default();
+        LANGUAGES
+            .iter()
+            .find(|language| language.code == code || language.name.to_lowercase() == wanted)
+            .copied()
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unsupported language: {}. Use one of {}",
+                    s,
+                    LANGUAGES
+                        .iter()
+                        .map(|language| language.code)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.code)
+    }
+}
@@ -1,24 +1,75 @@
+pub mod blame;
+pub mod branch;
 pub mod change_analyzer;
 pub mod changelog;
 pub mod changelog_prompts;
+pub mod changelog_review;
+pub mod cherry_pick;
+pub mod ci;
+pub mod classify;
 pub mod cli;
+pub mod commit_message;
 pub mod commands;
 pub mod config;
+pub mod config_bundle;
+pub mod config_migration;
+pub mod config_validation;
+pub mod conflicts;
 pub mod context;
+pub mod conventional;
+pub mod doctor;
+pub mod exclusions;
+pub mod explain;
+pub mod explain_prompts;
 pub mod file_analyzers;
+pub mod fixup;
 pub mod git;
 pub mod gitmoji;
+pub mod glossary;
+pub mod highlight;
+pub mod history;
+pub mod hooks;
+pub mod hunks;
 pub mod instruction_presets;
 pub mod interactive;
+pub mod keyring;
+pub mod language;
+pub mod lint;
 pub mod llm;
 pub mod llm_providers;
 pub mod logger;
+pub mod mcp;
+pub mod merge;
+pub mod merge_prompts;
 pub mod messages;
+pub mod migration;
+pub mod notes;
+pub mod pr;
+pub mod pr_prompts;
 pub mod prompt;
 pub mod readme_reader;
+pub mod redaction;
+pub mod release;
 pub mod relevance;
+pub mod report;
+pub mod revert;
+pub mod rewrite;
+pub mod risk;
+pub mod secrets;
+pub mod semver;
+pub mod session;
+pub mod split;
+pub mod stash;
+pub mod stats;
+pub mod summary;
+pub mod templates;
+pub mod todo;
 pub mod token_optimizer;
+pub mod tui;
 pub mod ui;
+pub mod undo;
+pub mod wip;
+pub mod workspace;
 
 // Re-export important structs and functions for easier testing
 pub use config::Config;
@@ -0,0 +1,224 @@
+use crate::commit_message::{split_leading_emoji, subject_line};
+use crate::config::LintConfig;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+use unicode_width::UnicodeWidthStr;
+
+lazy_static! {
+    // Conventional commit subject: type(scope)!: description
+    static ref CONVENTIONAL_SUBJECT: Regex =
+        Regex::new(r"^(?P<type>[a-z]+)(\([^()\s]+\))?!?: (?P<description>\S.*)$").unwrap();
+}
+
+/// Commit types accepted by the conventional rule
+pub const CONVENTIONAL_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// Verbs commonly used in subject lines, used to recognize their non-imperative forms
+const COMMON_VERBS: &[&str] = &[
+    "add", "adjust", "allow", "avoid", "bump", "change", "clean", "convert", "correct", "create",
+    "delete", "deprecate", "disable", "document", "drop", "enable", "ensure", "extract", "fix",
+    "handle", "hide", "implement", "improve", "introduce", "make", "merge", "move", "optimize",
+    "prevent", "refactor", "remove", "rename", "replace", "restore", "return", "revert", "rewrite",
+    "set", "show", "simplify", "split", "support", "switch", "update", "upgrade", "use", "write",
+];
+
+/// Words ending in "ed" or "ing" that are not past tense or gerunds
+const NON_VERB_FORMS: &[&str] = &[
+    "bleed", "breed", "embed", "exceed", "feed", "need", "proceed", "seed", "shed", "speed",
+    "succeed", "bring", "ping", "ring", "sing", "spring", "string", "swing", "thing", "wing",
+];
+
+/// Subjects Git writes itself, which are left alone
+const GIT_GENERATED_PREFIXES: &[&str] =
+    &["fixup! ", "squash! ", "amend! ", "Merge ", "Revert \""];
+
+/// A rule a commit message is checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintRule {
+    Empty,
+    Conventional,
+    SubjectLength,
+    ImperativeMood,
+    BodySeparator,
+    BodyWrap,
+}
+
+impl LintRule {
+    /// Name of the rule as shown in lint output
+    pub fn key(&self) -> &'static str {
+        match self {
+            LintRule::Empty => "empty",
+            LintRule::Conventional => "conventional",
+            LintRule::SubjectLength => "subject-length",
+            LintRule::ImperativeMood => "imperative-mood",
+            LintRule::BodySeparator => "body-separator",
+            LintRule::BodyWrap => "body-wrap",
+        }
+    }
+}
+
+/// A problem found in a commit message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintViolation {
+    pub rule: LintRule,
+    /// Line of the message the problem is on, starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for LintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {} [{}]", self.line, self.message, self.rule.key())
+    }
+}
+
+/// Check a commit message against the configured rules
+pub fn lint_message(message: &str, rules: &LintConfig) -> Vec<LintViolation> {
+    let message = message.trim();
+    let mut violations = Vec::new();
+    if message.is_empty() {
+        violations.push(violation(LintRule::Empty, 1, "Commit message is empty".to_string()));
+        return violations;
+    }
+
+    let subject = subject_line(message);
+    if GIT_GENERATED_PREFIXES
+        .iter()
+        .any(|prefix| subject.starts_with(prefix))
+    {
+        return violations;
+    }
+
+    let width = subject.width();
+    if rules.max_subject_length > 0 && width > rules.max_subject_length {
+        violations.push(violation(
+            LintRule::SubjectLength,
+            1,
+            format!(
+                "Subject line is {} columns wide (limit {})",
+                width, rules.max_subject_length
+            ),
+        ));
+    }
+
+    let (_, subject_text) = split_leading_emoji(subject);
+    let mut description = subject_text;
+    if rules.conventional {
+        match CONVENTIONAL_SUBJECT.captures(subject_text) {
+            Some(captures) => {
+                let commit_type = captures.name("type").map_or("", |m| m.as_str());
+                if !CONVENTIONAL_TYPES.contains(&commit_type) {
+                    violations.push(violation(
+                        LintRule::Conventional,
+                        1,
+                        format!(
+                            "Unknown commit type '{}' (expected one of {})",
+                            commit_type,
+                            CONVENTIONAL_TYPES.join(", ")
+                        ),
+                    ));
+                }
+                description = captures.name("description").map_or("", |m| m.as_str());
+            }
+            None => violations.push(violation(
+                LintRule::Conventional,
+                1,
+                "Subject does not follow the 'type(scope): description' format".to_string(),
+            )),
+        }
+    } else if let Some(captures) = CONVENTIONAL_SUBJECT.captures(subject_text) {
+        description = captures.name("description").map_or("", |m| m.as_str());
+    }
+
+    if rules.imperative_mood {
+        if let Some(word) = non_imperative_word(description) {
+            violations.push(violation(
+                LintRule::ImperativeMood,
+                1,
+                format!(
+                    "Subject should use the imperative mood ('{}' is not an instruction)",
+                    word
+                ),
+            ));
+        }
+    }
+
+    let lines: Vec<&str> = message.lines().collect();
+    if lines.len() > 1 && !lines[1].trim().is_empty() {
+        violations.push(violation(
+            LintRule::BodySeparator,
+            2,
+            "Separate the subject from the body with a blank line".to_string(),
+        ));
+    }
+
+    if rules.max_body_line_length > 0 {
+        for (index, line) in lines.iter().enumerate().skip(1) {
+            let width = line.width();
+            if width > rules.max_body_line_length && !is_unwrappable(line) {
+                violations.push(violation(
+                    LintRule::BodyWrap,
+                    index + 1,
+                    format!(
+                        "Body line is {} columns wide (wrap at {})",
+                        width, rules.max_body_line_length
+                    ),
+                ));
+            }
+        }
+    }
+
+    violations
+}
+
+/// Remove the comment lines Git adds to a message file, and everything below a scissors line
+pub fn strip_comments(content: &str, comment_char: char) -> String {
+    let scissors = format!("{} ------------------------ >8 ------------------------", comment_char);
+    content
+        .lines()
+        .take_while(|line| *line != scissors)
+        .filter(|line| !line.starts_with(comment_char))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn violation(rule: LintRule, line: usize, message: String) -> LintViolation {
+    LintViolation {
+        rule,
+        line,
+        message,
+    }
+}
+
+/// The first word of a description if it is clearly past tense, a gerund, or third person
+fn non_imperative_word(description: &str) -> Option<&str> {
+    let word = description
+        .split_whitespace()
+        .next()?
+        .trim_end_matches([',', ':', '.']);
+    let lower = word.to_lowercase();
+    if !lower.chars().all(|c| c.is_ascii_alphabetic()) || NON_VERB_FORMS.contains(&lower.as_str())
+    {
+        return None;
+    }
+
+    let past_tense = lower.len() > 4 && lower.ends_with("ed");
+    let gerund = lower.len() > 5 && lower.ends_with("ing");
+    let third_person = ["es", "s"].iter().any(|suffix| {
+        lower
+            .strip_suffix(suffix)
+            .is_some_and(|stem| COMMON_VERBS.contains(&stem))
+    });
+    (past_tense || gerund || third_person).then_some(word)
+}
+
+/// Lines that can't be wrapped, such as long URLs and indented code
+fn is_unwrappable(line: &str) -> bool {
+    line.starts_with("    ")
+        || line.starts_with('\t')
+        || line.contains("://")
+        || !line.trim().contains(' ')
+}
@@ -1,10 +1,80 @@
+use crate::ci::{self, FailureKind};
 use crate::config::{Config, ProviderConfig};
 use crate::llm_providers::{
     create_provider, get_available_providers, get_provider_metadata, LLMProviderConfig,
     LLMProviderType,
 };
 use crate::log_debug;
+use crate::redaction::{format_redaction_report, Redactor};
+use crate::token_optimizer::TokenOptimizer;
 use anyhow::{anyhow, Result};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Flag to control whether prompts are printed instead of sent to the provider
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Flag to control whether what would be redacted is printed instead of sending the prompts
+static SHOW_REDACTIONS: AtomicBool = AtomicBool::new(false);
+
+/// Print prompts instead of sending them to the provider
+pub fn enable_dry_run() {
+    DRY_RUN.store(true, Ordering::SeqCst);
+}
+
+/// Check whether prompts are printed instead of sent to the provider
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::SeqCst)
+}
+
+/// Print what would be redacted from the prompts instead of sending them to the provider
+pub fn enable_show_redactions() {
+    SHOW_REDACTIONS.store(true, Ordering::SeqCst);
+}
+
+/// Check whether what would be redacted is printed instead of sending the prompts
+pub fn is_showing_redactions() -> bool {
+    SHOW_REDACTIONS.load(Ordering::SeqCst)
+}
+
+/// Error returned in place of a response during a dry run, after the prompts were printed
+#[derive(Debug)]
+pub struct DryRunComplete;
+
+impl fmt::Display for DryRunComplete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Dry run: no request was sent to the provider")
+    }
+}
+
+impl std::error::Error for DryRunComplete {}
+
+/// Format the prompts that would be sent to the provider, with an estimated token count
+pub fn format_dry_run_report(
+    provider_type: &LLMProviderType,
+    system_prompt: &str,
+    user_prompt: &str,
+) -> String {
+    let metadata = get_provider_metadata(provider_type);
+    let optimizer = TokenOptimizer::new(metadata.default_token_limit);
+    let system_tokens = optimizer.count_tokens(system_prompt);
+    let user_tokens = optimizer.count_tokens(user_prompt);
+
+    format!(
+        "Provider: {}\n\
+        Estimated tokens: {} (system: {}, user: {}, limit: {})\n\n\
+        === System prompt ===\n{}\n\n\
+        === User prompt ===\n{}",
+        provider_type,
+        system_tokens + user_tokens,
+        system_tokens,
+        user_tokens,
+        metadata.default_token_limit,
+        system_prompt,
+        user_prompt
+    )
+}
 
 /// Generates a message using the given configuration
 pub async fn get_refined_message(
@@ -14,16 +84,79 @@ pub async fn get_refined_message(
     user_prompt: &str,
     custom_instructions: Option<&str>,
 ) -> Result<String> {
+    config
+        .policy
+        .check_provider(provider_type.as_ref())
+        .map_err(|e| ci::failure(FailureKind::ConfigMissing, e))?;
+
+    // Append custom instructions to the user prompt if provided
+    let final_system_prompt = match custom_instructions {
+        Some(instructions) => format!(
+            "{}\n\nAdditional instructions: {}",
+            system_prompt, instructions
+        ),
+        None => system_prompt.to_string(),
+    };
+
+    // Redact before anything leaves the machine; an invalid pattern stops the request
+    let redactor = Redactor::new(&config.redaction)?;
+    let (final_system_prompt, system_redactions) = redactor.redact(&final_system_prompt);
+    let (user_prompt, user_redactions) = redactor.redact(user_prompt);
+    let user_prompt = user_prompt.as_str();
+    if !system_redactions.is_empty() || !user_redactions.is_empty() {
+        log_debug!(
+            "Redacted {} match(es) from the prompts",
+            system_redactions.len() + user_redactions.len()
+        );
+    }
+
+    if is_showing_redactions() {
+        println!(
+            "{}",
+            format_redaction_report(
+                &[
+                    ("System prompt", &system_redactions),
+                    ("User prompt", &user_redactions),
+                ],
+                &config.redaction.replacement
+            )
+        );
+        return Err(DryRunComplete.into());
+    }
+
+    if is_dry_run() {
+        println!(
+            "{}",
+            format_dry_run_report(provider_type, &final_system_prompt, user_prompt)
+        );
+        return Err(DryRunComplete.into());
+    }
+
     let provider_metadata = get_provider_metadata(provider_type);
 
     let provider_config = if provider_metadata.requires_api_key {
-        config
+        let provider_config = config
             .get_provider_config(&provider_type.to_string())
-            .ok_or_else(|| anyhow!("Provider '{}' not found in configuration", provider_type))?
-            .clone()
+            .ok_or_else(|| {
+                ci::failure(
+                    FailureKind::ConfigMissing,
+                    anyhow!("Provider '{}' not found in configuration", provider_type),
+                )
+            })?
+            .clone();
+        if provider_config.api_key.is_empty() {
+            return Err(ci::failure(
+                FailureKind::ConfigMissing,
+                anyhow!("API key required for provider: {}", provider_type),
+            ));
+        }
+        provider_config
     } else {
-        // Use default configuration for providers that don't require an API key
-        ProviderConfig::default_for(&provider_type.to_string())
+        // Providers that don't require an API key can still have network settings
+        config
+            .get_provider_config(provider_type.as_ref())
+            .cloned()
+            .unwrap_or_else(|| ProviderConfig::default_for(provider_type.as_ref()))
     };
 
     // Create the LLM provider instance using the provided function
@@ -32,15 +165,6 @@ pub async fn get_refined_message(
         provider_config.to_llm_provider_config(),
     )?;
 
-    // Append custom instructions to the user prompt if provided
-    let final_system_prompt = match custom_instructions {
-        Some(instructions) => format!(
-            "{}\n\nAdditional instructions: {}",
-            system_prompt, instructions
-        ),
-        None => system_prompt.to_string(),
-    };
-
     log_debug!(
         "Generating refined message using provider: {}",
         provider_type
@@ -51,13 +175,35 @@ pub async fn get_refined_message(
     // Generate the message using the LLM provider
     let refined_message = llm_provider
         .generate_message(&final_system_prompt, user_prompt)
-        .await?;
+        .await
+        .map_err(|e| ci::failure(FailureKind::ProviderFailure, e))?;
 
     log_debug!("Refined message: {}", refined_message);
     
     Ok(refined_message)
 }
 
+/// Pings the given provider and returns the round-trip latency
+pub async fn ping_provider(config: &Config, provider_type: &LLMProviderType) -> Result<Duration> {
+    config.policy.check_provider(provider_type.as_ref())?;
+    validate_provider_config(config, provider_type)?;
+
+    let provider_config = config
+        .get_provider_config(&provider_type.to_string())
+        .cloned()
+        .unwrap_or_else(|| ProviderConfig::default_for(&provider_type.to_string()));
+
+    let llm_provider = create_provider(*provider_type, provider_config.to_llm_provider_config())?;
+
+    log_debug!("Pinging provider: {}", provider_type);
+    let start = Instant::now();
+    llm_provider
+        .ping()
+        .await
+        .map_err(|e| ci::failure(FailureKind::ProviderFailure, e))?;
+    Ok(start.elapsed())
+}
+
 /// Returns a list of available LLM providers as strings
 pub fn get_available_provider_names() -> Vec<String> {
     get_available_providers()
@@ -88,10 +234,18 @@ pub fn validate_provider_config(config: &Config, provider_type: &LLMProviderType
     if metadata.requires_api_key {
         let provider_config = config
             .get_provider_config(&provider_type.to_string())
-            .ok_or_else(|| anyhow!("Provider '{}' not found in configuration", provider_type))?;
+            .ok_or_else(|| {
+                ci::failure(
+                    FailureKind::ConfigMissing,
+                    anyhow!("Provider '{}' not found in configuration", provider_type),
+                )
+            })?;
 
         if provider_config.api_key.is_empty() {
-            return Err(anyhow!("API key required for provider: {}", provider_type));
+            return Err(ci::failure(
+                FailureKind::ConfigMissing,
+                anyhow!("API key required for provider: {}", provider_type),
+            ));
         }
     }
 
@@ -110,12 +264,15 @@ pub fn get_combined_config(
             .unwrap()
             .to_string(),
         additional_params: Default::default(),
+        network: Default::default(),
     };
 
     let saved_config = config
         .get_provider_config(&provider_type.to_string())
         .cloned()
         .unwrap_or_default();
+    // Network settings only come from the configuration
+    let network = saved_config.network_settings();
 
     LLMProviderConfig {
         api_key: if !command_line_args.api_key.is_empty() {
@@ -139,5 +296,6 @@ pub fn get_combined_config(
         } else {
             default_config.additional_params
         },
+        network,
     }
 }
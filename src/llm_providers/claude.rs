@@ -1,4 +1,4 @@
-use super::{LLMProvider, LLMProviderConfig, ProviderMetadata};
+use super::{ping_error, LLMProvider, LLMProviderConfig, ProviderMetadata};
 use anyhow::Result;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -13,10 +13,8 @@ pub struct ClaudeProvider {
 impl ClaudeProvider {
     /// Creates a new instance of ClaudeProvider with the given configuration
     pub fn new(config: LLMProviderConfig) -> Result<Self> {
-        Ok(Self {
-            config,
-            client: Client::new(),
-        })
+        let client = config.network.client()?;
+        Ok(Self { config, client })
     }
 }
 
@@ -39,15 +37,14 @@ impl LLMProvider for ClaudeProvider {
         }
 
         // Make the API request
-        let response = self
+        let request = self
             .client
             .post("https://api.anthropic.com/v1/messages")
             .header("x-api-key", &self.config.api_key)
             .header("anthropic-version", "2023-06-01")
             .header("Content-Type", "application/json")
-            .json(&request_body)
-            .send()
-            .await?;
+            .json(&request_body);
+        let response = self.config.network.send(request).await?;
 
         // Check for successful response
         if !response.status().is_success() {
@@ -75,6 +72,27 @@ impl LLMProvider for ClaudeProvider {
 
         Ok(message)
     }
+
+    /// Looks up the configured model through the Claude models endpoint
+    async fn ping(&self) -> Result<()> {
+        let request = self
+            .client
+            .get(format!(
+                "https://api.anthropic.com/v1/models/{}",
+                self.config.model
+            ))
+            .header("x-api-key", &self.config.api_key)
+            .header("anthropic-version", "2023-06-01");
+        let response = self.config.network.send(request).await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let text = response.text().await?;
+        Err(ping_error("Claude", &self.config.model, status, &text))
+    }
 }
 
 pub(super) fn get_metadata() -> ProviderMetadata {
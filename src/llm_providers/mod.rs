@@ -1,15 +1,19 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use reqwest::StatusCode;
 use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 use strum::IntoEnumIterator;
 use strum_macros::{AsRefStr, EnumIter};
 mod claude;
+mod network;
 mod ollama;
 mod openai;
 mod test;
 
+pub use network::{is_retryable_status, NetworkSettings};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, AsRefStr)]
 #[strum(serialize_all = "lowercase")]
 pub enum LLMProviderType {
@@ -42,6 +46,9 @@ impl FromStr for LLMProviderType {
 #[async_trait]
 pub trait LLMProvider: Send + Sync {
     async fn generate_message(&self, system_prompt: &str, user_prompt: &str) -> Result<String>;
+
+    /// Checks that the provider is reachable, the credentials are valid, and the model exists
+    async fn ping(&self) -> Result<()>;
 }
 
 pub struct ProviderMetadata {
@@ -56,6 +63,7 @@ pub struct LLMProviderConfig {
     pub api_key: String,
     pub model: String,
     pub additional_params: HashMap<String, String>,
+    pub network: NetworkSettings,
 }
 
 pub fn create_provider(
@@ -83,3 +91,25 @@ pub fn get_available_providers() -> Vec<LLMProviderType> {
     LLMProviderType::iter().collect()
 }
 
+/// Turns a failed ping response into an actionable error message
+pub(crate) fn ping_error(provider: &str, model: &str, status: StatusCode, body: &str) -> anyhow::Error {
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => anyhow::anyhow!(
+            "API key for {} was rejected. Run 'git-iris config --provider {} --api-key YOUR_API_KEY' to update it.",
+            provider,
+            provider.to_lowercase()
+        ),
+        StatusCode::NOT_FOUND => anyhow::anyhow!(
+            "Model '{}' not found for your account. Run 'git-iris config --provider {} --model MODEL' to choose another.",
+            model,
+            provider.to_lowercase()
+        ),
+        _ => anyhow::anyhow!(
+            "{} API request failed with status {}: {}",
+            provider,
+            status,
+            body
+        ),
+    }
+}
+
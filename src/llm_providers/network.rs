@@ -0,0 +1,86 @@
+use crate::log_debug;
+use anyhow::{anyhow, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, Proxy, RequestBuilder, Response, StatusCode};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Delay before the first retry, doubled for each one after it
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest delay between retries
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// How requests to a provider are sent: the same for every HTTP-based provider
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NetworkSettings {
+    /// Time to wait for a whole request, or `None` to wait indefinitely
+    pub timeout: Option<Duration>,
+    /// Times a request is retried after a timeout, a connection error, a rate limit, or a
+    /// server error
+    pub max_retries: u32,
+    /// Proxy URL for requests to the provider, such as `http://proxy.example.com:8080`
+    pub proxy: Option<String>,
+    /// Headers added to every request, such as the authentication header of a gateway
+    pub headers: HashMap<String, String>,
+}
+
+impl NetworkSettings {
+    /// Build a client that applies the timeout, proxy, and headers to every request
+    pub fn client(&self) -> Result<Client> {
+        let mut builder = Client::builder().default_headers(self.header_map()?);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = self.proxy.as_deref().filter(|proxy| !proxy.is_empty()) {
+            let proxy =
+                Proxy::all(proxy).map_err(|e| anyhow!("Invalid proxy URL '{}': {}", proxy, e))?;
+            builder = builder.proxy(proxy);
+        }
+        builder
+            .build()
+            .map_err(|e| anyhow!("Failed to set up the HTTP client: {}", e))
+    }
+
+    /// The extra headers, failing on the first name or value that isn't valid in HTTP
+    pub fn header_map(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| anyhow!("Invalid header name '{}'", name))?;
+            let header_value = HeaderValue::from_str(value)
+                .map_err(|_| anyhow!("Invalid value for header '{}'", name))?;
+            headers.insert(header_name, header_value);
+        }
+        Ok(headers)
+    }
+
+    /// Send a request, retrying it up to `max_retries` times when the failure is likely to
+    /// be temporary
+    pub async fn send(&self, request: RequestBuilder) -> reqwest::Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let Some(this_attempt) = request.try_clone().filter(|_| attempt < self.max_retries)
+            else {
+                return request.send().await;
+            };
+            match this_attempt.send().await {
+                Ok(response) if is_retryable_status(response.status()) => {
+                    log_debug!("Retrying after status {}", response.status());
+                }
+                Err(e) if e.is_timeout() || e.is_connect() => {
+                    log_debug!("Retrying after error: {}", e);
+                }
+                result => return result,
+            }
+            attempt += 1;
+            let delay = RETRY_DELAY.saturating_mul(2u32.saturating_pow(attempt - 1));
+            tokio::time::sleep(delay.min(MAX_RETRY_DELAY)).await;
+        }
+    }
+}
+
+/// Whether a response status means the same request may succeed later
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
@@ -1,4 +1,4 @@
-use super::{LLMProvider, LLMProviderConfig, ProviderMetadata};
+use super::{ping_error, LLMProvider, LLMProviderConfig, ProviderMetadata};
 use anyhow::Result;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -13,10 +13,8 @@ pub struct OllamaProvider {
 impl OllamaProvider {
     /// Creates a new instance of OllamaProvider with the given configuration
     pub fn new(config: LLMProviderConfig) -> Result<Self> {
-        Ok(Self {
-            config,
-            client: Client::new(),
-        })
+        let client = config.network.client()?;
+        Ok(Self { config, client })
     }
 }
 
@@ -31,12 +29,11 @@ impl LLMProvider for OllamaProvider {
         });
 
         // Make the API request
-        let response = self
+        let request = self
             .client
             .post("http://localhost:11434/api/generate")
-            .json(&request_body)
-            .send()
-            .await?;
+            .json(&request_body);
+        let response = self.config.network.send(request).await?;
 
         // Check for successful response
         if !response.status().is_success() {
@@ -57,6 +54,35 @@ impl LLMProvider for OllamaProvider {
 
         Ok(content.to_string())
     }
+
+    /// Checks that the local Ollama server is running and has the configured model pulled
+    async fn ping(&self) -> Result<()> {
+        let request = self
+            .client
+            .post("http://localhost:11434/api/show")
+            .json(&json!({ "name": self.config.model }));
+        let response = self.config.network.send(request).await.map_err(|_| {
+            anyhow::anyhow!(
+                "Could not reach Ollama at http://localhost:11434. Is 'ollama serve' running?"
+            )
+        })?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(anyhow::anyhow!(
+                "Model '{}' is not available locally. Run 'ollama pull {}' to download it.",
+                self.config.model,
+                self.config.model
+            ));
+        }
+
+        let text = response.text().await?;
+        Err(ping_error("Ollama", &self.config.model, status, &text))
+    }
 }
 
 pub(super) fn get_metadata() -> ProviderMetadata {
@@ -1,4 +1,4 @@
-use super::{LLMProvider, LLMProviderConfig, ProviderMetadata};
+use super::{ping_error, LLMProvider, LLMProviderConfig, ProviderMetadata};
 use anyhow::Result;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -13,10 +13,8 @@ pub struct OpenAIProvider {
 impl OpenAIProvider {
     /// Creates a new instance of OpenAIProvider with the given configuration
     pub fn new(config: LLMProviderConfig) -> Result<Self> {
-        Ok(Self {
-            config,
-            client: Client::new(),
-        })
+        let client = config.network.client()?;
+        Ok(Self { config, client })
     }
 }
 
@@ -38,14 +36,13 @@ impl LLMProvider for OpenAIProvider {
         }
 
         // Make the API request
-        let response = self
+        let request = self
             .client
             .post("https://api.openai.com/v1/chat/completions")
             .header("Authorization", format!("Bearer {}", self.config.api_key))
             .header("Content-Type", "application/json")
-            .json(&request_body)
-            .send()
-            .await?;
+            .json(&request_body);
+        let response = self.config.network.send(request).await?;
 
         // Check for successful response
         if !response.status().is_success() {
@@ -66,6 +63,26 @@ impl LLMProvider for OpenAIProvider {
 
         Ok(content.to_string())
     }
+
+    /// Looks up the configured model through the OpenAI models endpoint
+    async fn ping(&self) -> Result<()> {
+        let request = self
+            .client
+            .get(format!(
+                "https://api.openai.com/v1/models/{}",
+                self.config.model
+            ))
+            .header("Authorization", format!("Bearer {}", self.config.api_key));
+        let response = self.config.network.send(request).await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let text = response.text().await?;
+        Err(ping_error("OpenAI", &self.config.model, status, &text))
+    }
 }
 
 pub(super) fn get_metadata() -> ProviderMetadata {
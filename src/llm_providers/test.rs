@@ -23,6 +23,11 @@ impl LLMProvider for TestLLMProvider {
             self.config.model, system_prompt, user_prompt
         ))
     }
+
+    /// The test provider is always reachable
+    async fn ping(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 pub(super) fn get_metadata() -> ProviderMetadata {
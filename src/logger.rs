@@ -2,15 +2,19 @@ use chrono::Local;
 use once_cell::sync::Lazy;
 use std::fs::OpenOptions;
 use std::io::Write;
+use std::path::PathBuf;
 use std::sync::Mutex;
 
+/// Name of the debug log file, written to the current directory
+pub const LOG_FILE_NAME: &str = "git-iris-debug.log";
+
 /// Static mutex-protected log file handle
 static LOG_FILE: Lazy<Mutex<std::fs::File>> = Lazy::new(|| {
     Mutex::new(
         OpenOptions::new()
             .create(true)
             .append(true)
-            .open("git-iris-debug.log")
+            .open(LOG_FILE_NAME)
             .expect("Failed to open log file"),
     )
 });
@@ -30,6 +34,19 @@ pub fn disable_logging() {
     *logging_enabled = false;
 }
 
+/// Check that the debug log file can be written, returning its path
+pub fn check_log_file() -> std::io::Result<PathBuf> {
+    let path = std::env::current_dir()?.join(LOG_FILE_NAME);
+    if path.exists() {
+        OpenOptions::new().append(true).open(&path)?;
+    } else {
+        // Don't leave an empty log file behind
+        OpenOptions::new().create_new(true).write(true).open(&path)?;
+        std::fs::remove_file(&path)?;
+    }
+    Ok(path)
+}
+
 /// Log a message with the given level
 pub fn log(level: &str, message: &str) {
     let logging_enabled = LOGGING_ENABLED.lock().unwrap();
@@ -1,8 +1,10 @@
-use anyhow::Result;
-use git_iris::cli;
+use git_iris::{ci, cli};
 
 /// Main entry point for the application
 #[tokio::main]
-async fn main() -> Result<()> {
-    cli::main().await
+async fn main() {
+    if let Err(error) = cli::main().await {
+        ci::report_error(&error);
+        std::process::exit(ci::exit_code(&error));
+    }
 }
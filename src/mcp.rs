@@ -0,0 +1,265 @@
+use crate::changelog::{ChangelogGenerator, DetailLevel, ReleaseNotesGenerator};
+use crate::commands::{generate_staged_commit_message, generate_staged_review, StagedReview};
+use crate::config::Config;
+use crate::llm::validate_provider_config;
+use crate::llm_providers::LLMProviderType;
+use crate::log_debug;
+use anyhow::{anyhow, Result};
+use clap::crate_version;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::str::FromStr;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Model Context Protocol revision implemented by the server
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Serves Git-Iris tools over the Model Context Protocol on stdin and stdout
+pub struct McpServer {
+    repo_path: PathBuf,
+}
+
+impl McpServer {
+    /// Create a server whose tools default to the repository at `repo_path`
+    pub fn new(repo_path: PathBuf) -> Self {
+        Self { repo_path }
+    }
+
+    /// Serve newline-delimited JSON-RPC messages from stdin until it closes
+    pub async fn run(&self) -> Result<()> {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        let mut stdout = tokio::io::stdout();
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_message(&line).await {
+                stdout.write_all(format!("{}\n", response).as_bytes()).await?;
+                stdout.flush().await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a single JSON-RPC message, returning the response to send, if any
+    pub async fn handle_message(&self, message: &str) -> Option<Value> {
+        let request: Value = match serde_json::from_str(message) {
+            Ok(request) => request,
+            Err(e) => {
+                return Some(error_response(
+                    Value::Null,
+                    PARSE_ERROR,
+                    &format!("Parse error: {}", e),
+                ))
+            }
+        };
+
+        let method = request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        log_debug!("MCP request: {}", method);
+
+        // Notifications carry no id and get no response
+        let id = request.get("id").cloned()?;
+        let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "git-iris", "version": crate_version!() },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(&params).await,
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    async fn call_tool(&self, params: &Value) -> std::result::Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| (INVALID_PARAMS, "Missing tool name".to_string()))?;
+        if !tool_definitions()
+            .iter()
+            .any(|tool| tool["name"].as_str() == Some(name))
+        {
+            return Err((INVALID_PARAMS, format!("Unknown tool: {}", name)));
+        }
+
+        let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+
+        // Tool failures are reported to the model as results, not protocol errors
+        Ok(match self.run_tool(name, &arguments).await {
+            Ok(text) => json!({ "content": [{ "type": "text", "text": text }], "isError": false }),
+            Err(e) => json!({
+                "content": [{ "type": "text", "text": format!("{:#}", e) }],
+                "isError": true,
+            }),
+        })
+    }
+
+    async fn run_tool(&self, name: &str, arguments: &Value) -> Result<String> {
+        let repo_path = string_argument(arguments, "repository")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.repo_path.clone());
+
+        let mut config = Config::load()?;
+        config.set_temp_instructions(string_argument(arguments, "instructions"));
+        config.set_temp_preset(string_argument(arguments, "preset"));
+
+        match name {
+            "generate_commit_message" => {
+                if let Some(use_gitmoji) = arguments.get("gitmoji").and_then(Value::as_bool) {
+                    config.use_gitmoji = use_gitmoji;
+                }
+                generate_staged_commit_message(&repo_path, &config)
+                    .await?
+                    .ok_or_else(|| anyhow!("No staged changes. Stage changes with 'git add' first"))
+            }
+            "generate_changelog" | "generate_release_notes" => {
+                let from = string_argument(arguments, "from")
+                    .ok_or_else(|| anyhow!("Missing required argument 'from'"))?;
+                let to = string_argument(arguments, "to").unwrap_or_else(|| "HEAD".to_string());
+                let detail_level = DetailLevel::from_str(
+                    &string_argument(arguments, "detail_level")
+                        .unwrap_or_else(|| "standard".to_string()),
+                )?;
+
+                if name == "generate_changelog" {
+                    ChangelogGenerator::generate(&repo_path, &from, &to, &config, detail_level).await
+                } else {
+                    ReleaseNotesGenerator::generate(&repo_path, &from, &to, &config, detail_level)
+                        .await
+                }
+            }
+            "review_changes" => {
+                let provider_type = LLMProviderType::from_str(&config.default_provider)?;
+                validate_provider_config(&config, &provider_type)?;
+
+                let StagedReview { review, file_risks } =
+                    generate_staged_review(&repo_path, &config, &provider_type)
+                        .await?
+                        .ok_or_else(|| {
+                            anyhow!("No staged changes. Stage the changes to review with 'git add' first")
+                        })?;
+
+                let mut text = String::new();
+                for (path, labels) in &file_risks {
+                    let badges: Vec<String> = labels
+                        .iter()
+                        .map(|label| format!("[{}]", label.badge()))
+                        .collect();
+                    text.push_str(&format!("{} {}\n", badges.join(" "), path));
+                }
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str(&review);
+                Ok(text)
+            }
+            _ => Err(anyhow!("Unknown tool: {}", name)),
+        }
+    }
+}
+
+/// Definitions of the tools the server exposes, with their JSON input schemas
+pub fn tool_definitions() -> Vec<Value> {
+    let repository = json!({
+        "type": "string",
+        "description": "Path to the Git repository. Defaults to the directory the server was started in."
+    });
+    let instructions = json!({
+        "type": "string",
+        "description": "Custom instructions for the generated text"
+    });
+    let preset = json!({
+        "type": "string",
+        "description": "Instruction preset to use, as listed by 'git-iris list-presets'"
+    });
+    let range = json!({
+        "from": { "type": "string", "description": "Starting Git reference (commit hash, tag, or branch name)" },
+        "to": { "type": "string", "description": "Ending Git reference. Defaults to HEAD." },
+        "detail_level": {
+            "type": "string",
+            "enum": ["minimal", "standard", "detailed"],
+            "description": "Level of detail. Defaults to standard."
+        },
+    });
+
+    let with_common = |mut properties: Value| {
+        properties["repository"] = repository.clone();
+        properties["instructions"] = instructions.clone();
+        properties["preset"] = preset.clone();
+        properties
+    };
+
+    vec![
+        json!({
+            "name": "generate_commit_message",
+            "description": "Generate a commit message for the staged changes in the repository",
+            "inputSchema": {
+                "type": "object",
+                "properties": with_common(json!({
+                    "gitmoji": { "type": "boolean", "description": "Whether to use Gitmoji. Defaults to the user's configuration." }
+                })),
+            },
+        }),
+        json!({
+            "name": "generate_changelog",
+            "description": "Generate a changelog for the commits between two Git references",
+            "inputSchema": {
+                "type": "object",
+                "properties": with_common(range.clone()),
+                "required": ["from"],
+            },
+        }),
+        json!({
+            "name": "generate_release_notes",
+            "description": "Generate release notes for the commits between two Git references",
+            "inputSchema": {
+                "type": "object",
+                "properties": with_common(range.clone()),
+                "required": ["from"],
+            },
+        }),
+        json!({
+            "name": "review_changes",
+            "description": "Review the staged changes for bugs, security issues, missing tests, and style problems",
+            "inputSchema": {
+                "type": "object",
+                "properties": with_common(json!({})),
+            },
+        }),
+    ]
+}
+
+fn string_argument(arguments: &Value, name: &str) -> Option<String> {
+    arguments
+        .get(name)
+        .and_then(Value::as_str)
+        .filter(|value| !value.trim().is_empty())
+        .map(str::to_string)
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
@@ -0,0 +1,179 @@
+use crate::change_analyzer::AnalyzedChange;
+use crate::config::Config;
+use crate::exclusions::PromptFilter;
+use crate::git;
+use crate::llm;
+use crate::llm_providers::LLMProviderType;
+use crate::merge_prompts;
+use anyhow::{anyhow, Context, Result};
+use git2::Repository;
+use std::fs;
+use std::path::Path;
+
+/// Everything a merge brings into the target branch
+pub struct MergeContext {
+    pub source: String,
+    pub target: String,
+    pub merge_base: String,
+    pub incoming: Vec<AnalyzedChange>,
+    pub conflicts: Vec<String>,
+}
+
+impl MergeContext {
+    /// Collect the merge context for merging `branch` into the current branch.
+    /// Without a branch, the merge in progress (`MERGE_HEAD`) is used.
+    pub fn load(repo_path: &Path, branch: Option<&str>, filter: &PromptFilter) -> Result<Self> {
+        let repo = Repository::open(repo_path)?;
+        let target = git::get_current_branch_name(repo_path)?;
+
+        let (source, source_ref) = match branch {
+            Some(branch) => (branch.to_string(), branch.to_string()),
+            None => {
+                let merge_head = read_merge_head(&repo).ok_or_else(|| {
+                    anyhow!("No merge in progress. Specify the branch to merge")
+                })?;
+                let source = read_merge_msg(&repo)
+                    .and_then(|msg| merge_source_from_message(&msg))
+                    .unwrap_or_else(|| merge_head[..7].to_string());
+                (source, merge_head)
+            }
+        };
+
+        let head = repo.head()?.peel_to_commit()?;
+        let incoming_commit = repo
+            .revparse_single(&source_ref)
+            .and_then(|obj| obj.peel_to_commit())
+            .map_err(|_| anyhow!("Branch '{}' not found", source_ref))?;
+        let merge_base = repo.merge_base(head.id(), incoming_commit.id())?.to_string();
+
+        let incoming = git::get_commits_between(repo_path, &merge_base, &source_ref, filter)?;
+        let conflicts = find_conflicts(&repo);
+
+        Ok(MergeContext {
+            source,
+            target,
+            merge_base,
+            incoming,
+            conflicts,
+        })
+    }
+}
+
+pub struct MergeMessageGenerator;
+
+impl MergeMessageGenerator {
+    pub async fn generate(context: &MergeContext, config: &Config) -> Result<String> {
+        if context.incoming.is_empty() {
+            return Err(anyhow!(
+                "'{}' has no commits that are not already in '{}'",
+                context.source,
+                context.target
+            ));
+        }
+
+        let mut system_prompt = merge_prompts::create_merge_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let user_prompt = merge_prompts::create_merge_user_prompt(context);
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+            .await
+            .context("Failed to generate merge commit message")
+    }
+}
+
+fn read_merge_head(repo: &Repository) -> Option<String> {
+    let content = fs::read_to_string(repo.path().join("MERGE_HEAD")).ok()?;
+    content.lines().next().map(|line| line.trim().to_string())
+}
+
+fn read_merge_msg(repo: &Repository) -> Option<String> {
+    fs::read_to_string(repo.path().join("MERGE_MSG")).ok()
+}
+
+/// Extract the branch name from Git's default "Merge branch 'x'" message
+pub fn merge_source_from_message(message: &str) -> Option<String> {
+    let subject = message.lines().next()?;
+    let rest = subject
+        .strip_prefix("Merge branch '")
+        .or_else(|| subject.strip_prefix("Merge remote-tracking branch '"))?;
+    rest.split('\'').next().map(str::to_string)
+}
+
+/// Conflicted files, either still unresolved in the index or listed by Git in MERGE_MSG
+fn find_conflicts(repo: &Repository) -> Vec<String> {
+    let mut conflicts = Vec::new();
+
+    if let Ok(index) = repo.index() {
+        if let Ok(index_conflicts) = index.conflicts() {
+            conflicts.extend(
+                index_conflicts
+                    .filter_map(|conflict| conflict.ok())
+                    .filter_map(|conflict| conflict.our.or(conflict.their))
+                    .filter_map(|entry| String::from_utf8(entry.path).ok()),
+            );
+        }
+    }
+
+    if let Some(message) = read_merge_msg(repo) {
+        conflicts.extend(conflicts_from_message(&message));
+    }
+
+    conflicts.sort();
+    conflicts.dedup();
+    conflicts
+}
+
+/// Parse the "# Conflicts:" section Git adds to MERGE_MSG after a conflicted merge
+pub fn conflicts_from_message(message: &str) -> Vec<String> {
+    message
+        .lines()
+        .skip_while(|line| line.trim() != "# Conflicts:")
+        .skip(1)
+        .map_while(|line| line.strip_prefix("#\t"))
+        .map(|path| path.trim().to_string())
+        .collect()
+}
+
+/// Replace the message in a commit message file, keeping Git's comment lines
+pub fn write_message_file(path: &Path, message: &str, comment_char: char) -> Result<()> {
+    let existing = fs::read_to_string(path).unwrap_or_default();
+    fs::write(path, format_message_file(&existing, message, comment_char))?;
+    Ok(())
+}
+
+/// Build the contents of a commit message file from a message and the file's existing contents
+///
+/// Everything from the first comment line on is kept, including the diff below the scissors
+/// line that `git commit -v` adds. The message ends with exactly one newline, followed by a
+/// blank line before the comments.
+pub fn format_message_file(existing: &str, message: &str, comment_char: char) -> String {
+    let message = message.trim_start_matches(['\r', '\n']).trim_end();
+    let mut content = format!("{}\n", message);
+
+    let mut offset = 0;
+    for line in existing.split_inclusive('\n') {
+        if line.starts_with(comment_char) {
+            content.push('\n');
+            content.push_str(&existing[offset..]);
+            if !content.ends_with('\n') {
+                content.push('\n');
+            }
+            break;
+        }
+        offset += line.len();
+    }
+
+    content
+}
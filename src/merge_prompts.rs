@@ -0,0 +1,62 @@
+use crate::merge::MergeContext;
+
+pub fn create_merge_system_prompt() -> String {
+    String::from(
+        "You are an AI assistant specialized in writing merge commit messages. \
+        Your task is to describe what a merge brings into the target branch, based on the incoming commits and any conflicts. \
+        Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Start the subject line with 'Merge' and name the source and target branches, e.g. 'Merge feature-x into main: add OAuth login'.
+        2. After the branch names, summarize the net effect of the incoming commits in a few words.
+        3. Keep the subject line under 72 characters if possible.
+        4. Separate the subject from the body with a blank line and wrap the body at 72 characters.
+        5. In the body, list the notable incoming changes as bullet points, describing the combined effect rather than each commit.
+        6. If there were conflicts, add a 'Conflicts resolved:' section listing the conflicted files.
+        7. Do not describe how conflicts were resolved unless the context shows it.
+        8. Avoid common cliché words (like 'enhance', 'streamline', 'leverage', etc) and phrases.
+        9. Do not speculate about the purpose of a change or add any information not directly supported by the context.
+        10. NO YAPPING!
+
+        Generate only the merge commit message, without any explanations, quotes, or code fences."
+    )
+}
+
+pub fn create_merge_user_prompt(context: &MergeContext) -> String {
+    let mut prompt = format!(
+        "Based on the following information, generate a merge commit message:\n\n\
+        Source: {}\n\
+        Target: {}\n\
+        Merge base: {}\n\
+        Incoming commits: {}\n\n",
+        context.source,
+        context.target,
+        &context.merge_base[..7.min(context.merge_base.len())],
+        context.incoming.len()
+    );
+
+    for change in &context.incoming {
+        prompt.push_str(&format!("Commit: {}\n", &change.commit_hash[..7]));
+        prompt.push_str(&format!("Message: {}\n", change.commit_message.trim()));
+        prompt.push_str("Files changed:\n");
+        for file_change in &change.file_changes {
+            prompt.push_str(&format!(
+                "  - {} ({})\n",
+                file_change.new_path, file_change.change_type
+            ));
+        }
+        prompt.push('\n');
+    }
+
+    if context.conflicts.is_empty() {
+        prompt.push_str("Conflicts: none\n");
+    } else {
+        prompt.push_str("Conflicts:\n");
+        for path in &context.conflicts {
+            prompt.push_str(&format!("  - {}\n", path));
+        }
+    }
+
+    prompt
+}
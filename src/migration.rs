@@ -0,0 +1,372 @@
+use crate::config::Config;
+use crate::context::StagedFile;
+use crate::exclusions::PromptFilter;
+use crate::git;
+use crate::llm;
+use crate::llm_providers::{get_provider_metadata, LLMProviderType};
+use crate::semver::{classify_commit, Bump};
+use crate::token_optimizer::TokenOptimizer;
+use anyhow::{anyhow, Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+lazy_static! {
+    // Public items, per language; the captures are the item kind and its name
+    static ref RUST_PUBLIC_ITEM: Regex = Regex::new(
+        r"^\s*pub\s+(?:async\s+)?(?:unsafe\s+)?(fn|struct|enum|trait|type|const|static|mod)\s+(\w+)"
+    )
+    .unwrap();
+    static ref JS_EXPORT: Regex = Regex::new(
+        r"^\s*export\s+(?:default\s+)?(?:async\s+)?(function|class|const|let|var|interface|type|enum)\s+(\w+)"
+    )
+    .unwrap();
+    static ref PYTHON_DEFINITION: Regex = Regex::new(r"^(def|class)\s+([A-Za-z]\w*)").unwrap();
+    static ref JAVA_PUBLIC_TYPE: Regex =
+        Regex::new(r"^\s*public\s+(?:(?:static|final|abstract|sealed)\s+)*(class|interface|enum|record)\s+(\w+)")
+            .unwrap();
+    static ref JAVA_PUBLIC_METHOD: Regex =
+        Regex::new(r"^\s*public\s+(?:[\w<>\[\],?]+\s+)+(\w+)\s*\(").unwrap();
+    static ref KOTLIN_DECLARATION: Regex = Regex::new(
+        r"^\s*(?:public\s+)?(?:(?:data|sealed|open|abstract|enum|suspend|inline)\s+)*(fun|class|interface|object)\s+(\w+)"
+    )
+    .unwrap();
+}
+
+/// File extensions of configuration files
+const CONFIG_EXTENSIONS: &[&str] = &[
+    ".toml", ".yaml", ".yml", ".json", ".ini", ".cfg", ".conf", ".properties", ".env",
+];
+
+/// How a public API item changed between two releases
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiChangeKind {
+    Removed,
+    Added,
+    /// The declaration changed, such as a function's parameters
+    Changed,
+    /// The item disappeared from one file and appeared in another
+    Moved,
+}
+
+impl fmt::Display for ApiChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiChangeKind::Removed => write!(f, "Removed"),
+            ApiChangeKind::Added => write!(f, "Added"),
+            ApiChangeKind::Changed => write!(f, "Changed"),
+            ApiChangeKind::Moved => write!(f, "Moved"),
+        }
+    }
+}
+
+/// A change to a public function, type, or other item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiChange {
+    pub kind: ApiChangeKind,
+    /// Kind of item, such as `fn`, `class`, or `method`
+    pub item: String,
+    pub name: String,
+    pub path: String,
+    /// Where a moved item used to be
+    pub previous_path: Option<String>,
+    pub old_declaration: Option<String>,
+    pub new_declaration: Option<String>,
+}
+
+/// Everything an upgrade from one release to another involves
+pub struct MigrationContext {
+    pub from: String,
+    pub to: String,
+    /// Messages of commits marked as breaking, with `!` or a `BREAKING CHANGE` footer
+    pub breaking_commits: Vec<String>,
+    /// Subjects of all commits in the range
+    pub commit_subjects: Vec<String>,
+    pub api_changes: Vec<ApiChange>,
+    /// Changed configuration files, with their diffs and analysis
+    pub config_changes: Vec<StagedFile>,
+    /// File analysis of the source files with public API changes
+    pub file_analysis: Vec<(String, Vec<String>)>,
+}
+
+impl MigrationContext {
+    pub fn load(repo_path: &Path, from: &str, to: &str, filter: &PromptFilter) -> Result<Self> {
+        let commits = git::get_commits_between(repo_path, from, to, filter)?;
+        let breaking_commits = commits
+            .iter()
+            .filter(|change| classify_commit(&change.commit_message) == Bump::Major)
+            .map(|change| change.commit_message.trim().to_string())
+            .collect();
+        let commit_subjects = commits
+            .iter()
+            .map(|change| change.commit_message.lines().next().unwrap_or("").trim().to_string())
+            .collect();
+
+        let files = git::get_range_files(repo_path, from, to, filter)?;
+        let api_changes = detect_api_changes(&files);
+        let file_analysis = files
+            .iter()
+            .filter(|file| api_changes.iter().any(|change| change.path == file.path))
+            .filter(|file| !file.analysis.is_empty())
+            .map(|file| (file.path.clone(), file.analysis.clone()))
+            .collect();
+        let config_changes = files
+            .into_iter()
+            .filter(|file| is_config_file(&file.path) && !file.content_excluded)
+            .collect();
+
+        Ok(MigrationContext {
+            from: from.to_string(),
+            to: to.to_string(),
+            breaking_commits,
+            commit_subjects,
+            api_changes,
+            config_changes,
+            file_analysis,
+        })
+    }
+}
+
+pub struct MigrationGuideGenerator;
+
+impl MigrationGuideGenerator {
+    /// Write an upgrade guide for moving from one release to another
+    pub async fn generate(context: &MigrationContext, config: &Config) -> Result<String> {
+        if context.commit_subjects.is_empty() {
+            return Err(anyhow!(
+                "There are no commits between {} and {}",
+                context.from,
+                context.to
+            ));
+        }
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let mut system_prompt = create_migration_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        // Keep large upgrades within the provider's context window
+        let token_limit = config
+            .providers
+            .get(&config.default_provider)
+            .and_then(|provider| provider.token_limit)
+            .unwrap_or_else(|| get_provider_metadata(&provider_type).default_token_limit);
+        let user_prompt = TokenOptimizer::new(token_limit)
+            .truncate_string(&create_migration_user_prompt(context), token_limit / 2);
+
+        llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+            .await
+            .context("Failed to generate migration guide")
+    }
+}
+
+pub fn create_migration_system_prompt() -> String {
+    String::from(
+        "You are an AI assistant specialized in writing upgrade guides for software libraries and applications. \
+        Your task is to tell users what they must change in their own code and configuration to move from one release to the next. \
+        Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Write the guide in Markdown with these sections, leaving out any that would be empty: '## Overview', '## Breaking Changes', '## Removed and Renamed APIs', '## Configuration Changes', and '## Upgrade Steps'.
+        2. In 'Overview', say in two or three sentences how much work the upgrade is and who is affected.
+        3. In 'Breaking Changes', describe each breaking change, why it breaks existing users, and what to do instead.
+        4. In 'Removed and Renamed APIs', cover every removed, changed, or moved public item, with a short before and after code snippet when the declarations are available.
+        5. When an item was removed and a similar one added, present it as a likely rename and say that it is likely.
+        6. In 'Configuration Changes', list renamed, removed, and new settings and changed defaults, with before and after examples.
+        7. In 'Upgrade Steps', give a numbered checklist a user can follow in order.
+        8. Leave out additions and internal changes that need no action from users, except where they replace something removed.
+        9. Do not describe changes as features or fixes; this is not a changelog.
+        10. Avoid common cliché words (like 'enhance', 'streamline', 'leverage', etc) and phrases.
+        11. Do not speculate or add any information not directly supported by the context.
+        12. NO YAPPING!
+
+        Generate only the guide, without any introduction or closing remarks.",
+    )
+}
+
+pub fn create_migration_user_prompt(context: &MigrationContext) -> String {
+    let mut prompt = format!(
+        "Based on the following changes from {} to {}, write a migration guide:\n\n",
+        context.from, context.to
+    );
+
+    prompt.push_str("Breaking commits:\n");
+    if context.breaking_commits.is_empty() {
+        prompt.push_str("None marked as breaking\n");
+    }
+    for message in &context.breaking_commits {
+        prompt.push_str(&format!("- {}\n", message.replace('\n', "\n  ")));
+    }
+
+    prompt.push_str("\nPublic API changes:\n");
+    if context.api_changes.is_empty() {
+        prompt.push_str("None detected\n");
+    }
+    for change in &context.api_changes {
+        let location = match &change.previous_path {
+            Some(previous) => format!("{} -> {}", previous, change.path),
+            None => change.path.clone(),
+        };
+        prompt.push_str(&format!(
+            "- {} {} {} ({})\n",
+            change.kind, change.item, change.name, location
+        ));
+        if let Some(old) = &change.old_declaration {
+            prompt.push_str(&format!("  Before: {}\n", old));
+        }
+        if let Some(new) = &change.new_declaration {
+            prompt.push_str(&format!("  After: {}\n", new));
+        }
+    }
+
+    if !context.file_analysis.is_empty() {
+        prompt.push_str("\nFile analysis:\n");
+        for (path, analysis) in &context.file_analysis {
+            prompt.push_str(&format!("- {}\n", path));
+            for line in analysis {
+                prompt.push_str(&format!("  - {}\n", line));
+            }
+        }
+    }
+
+    prompt.push_str("\nConfiguration changes:\n");
+    if context.config_changes.is_empty() {
+        prompt.push_str("None\n");
+    }
+    for file in &context.config_changes {
+        prompt.push_str(&format!("File: {} ({})\n", file.path, file.change_type));
+        for analysis in &file.analysis {
+            prompt.push_str(&format!("  - {}\n", analysis));
+        }
+        prompt.push_str(&format!("{}\n", file.diff));
+    }
+
+    prompt.push_str("\nAll commits:\n");
+    for subject in &context.commit_subjects {
+        prompt.push_str(&format!("- {}\n", subject));
+    }
+
+    prompt
+}
+
+/// Find public items that were removed, added, changed, or moved in the diffs
+pub fn detect_api_changes(files: &[StagedFile]) -> Vec<ApiChange> {
+    // (item, name) -> (path, declaration) for each side
+    let mut removed: BTreeMap<(String, String), (String, String)> = BTreeMap::new();
+    let mut added: BTreeMap<(String, String), (String, String)> = BTreeMap::new();
+
+    for file in files {
+        for line in file.diff.lines() {
+            let (side, code) = if let Some(code) = line.strip_prefix('-') {
+                (&mut removed, code)
+            } else if let Some(code) = line.strip_prefix('+') {
+                (&mut added, code)
+            } else {
+                continue;
+            };
+            if let Some((item, name)) = public_item(&file.path, code) {
+                side.entry((item, name))
+                    .or_insert_with(|| (file.path.clone(), code.trim().to_string()));
+            }
+        }
+    }
+
+    let mut changes = Vec::new();
+    for ((item, name), (old_path, old_declaration)) in &removed {
+        let change = match added.remove(&(item.clone(), name.clone())) {
+            Some((path, new_declaration)) if path != *old_path => ApiChange {
+                kind: ApiChangeKind::Moved,
+                item: item.clone(),
+                name: name.clone(),
+                path,
+                previous_path: Some(old_path.clone()),
+                old_declaration: Some(old_declaration.clone()),
+                new_declaration: Some(new_declaration),
+            },
+            // Same declaration on both sides: the item only moved within the file
+            Some((_, new_declaration)) if new_declaration == *old_declaration => continue,
+            Some((path, new_declaration)) => ApiChange {
+                kind: ApiChangeKind::Changed,
+                item: item.clone(),
+                name: name.clone(),
+                path,
+                previous_path: None,
+                old_declaration: Some(old_declaration.clone()),
+                new_declaration: Some(new_declaration),
+            },
+            None => ApiChange {
+                kind: ApiChangeKind::Removed,
+                item: item.clone(),
+                name: name.clone(),
+                path: old_path.clone(),
+                previous_path: None,
+                old_declaration: Some(old_declaration.clone()),
+                new_declaration: None,
+            },
+        };
+        changes.push(change);
+    }
+    for ((item, name), (path, new_declaration)) in added {
+        changes.push(ApiChange {
+            kind: ApiChangeKind::Added,
+            item,
+            name,
+            path,
+            previous_path: None,
+            old_declaration: None,
+            new_declaration: Some(new_declaration),
+        });
+    }
+
+    changes
+}
+
+/// The kind and name of a public item declared on a line of code, if any
+fn public_item(path: &str, code: &str) -> Option<(String, String)> {
+    let captures_item = |regex: &Regex| {
+        regex
+            .captures(code)
+            .map(|captures| (captures[1].to_string(), captures[2].to_string()))
+    };
+
+    if path.ends_with(".rs") {
+        captures_item(&RUST_PUBLIC_ITEM)
+    } else if path.ends_with(".js") || path.ends_with(".ts") {
+        captures_item(&JS_EXPORT)
+    } else if path.ends_with(".py") {
+        // Only top-level definitions without a leading underscore are public
+        captures_item(&PYTHON_DEFINITION)
+    } else if path.ends_with(".java") {
+        captures_item(&JAVA_PUBLIC_TYPE).or_else(|| {
+            JAVA_PUBLIC_METHOD
+                .captures(code)
+                .map(|captures| ("method".to_string(), captures[1].to_string()))
+        })
+    } else if path.ends_with(".kt") {
+        captures_item(&KOTLIN_DECLARATION)
+    } else {
+        None
+    }
+}
+
+/// Whether a path looks like a configuration file; lock files are not configuration
+pub fn is_config_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path).to_lowercase();
+    if name.ends_with(".lock") || name == "package-lock.json" {
+        return false;
+    }
+    CONFIG_EXTENSIONS.iter().any(|extension| name.ends_with(extension))
+        || name.starts_with(".env")
+}
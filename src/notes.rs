@@ -0,0 +1,98 @@
+use crate::context::CommitContext;
+use crate::workspace::format_workspace_impact;
+use anyhow::{anyhow, Result};
+use git2::{Oid, Repository};
+use std::path::Path;
+
+/// Notes reference that Git-Iris writes to
+pub const NOTES_REF: &str = "refs/notes/iris";
+
+/// Resolve a commit or `from..to` range to commit hashes, oldest first
+pub fn commits_in_range(repo_path: &Path, range: &str) -> Result<Vec<String>> {
+    let repo = Repository::open(repo_path)?;
+
+    if !range.contains("..") {
+        let commit = repo
+            .revparse_single(range)
+            .and_then(|obj| obj.peel_to_commit())
+            .map_err(|_| anyhow!("Commit '{}' not found", range))?;
+        return Ok(vec![commit.id().to_string()]);
+    }
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk
+        .push_range(range)
+        .map_err(|e| anyhow!("Invalid range '{}': {}", range, e.message()))?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+
+    let commits = revwalk
+        .map(|oid| Ok(oid?.to_string()))
+        .collect::<Result<Vec<_>>>()?;
+    if commits.is_empty() {
+        return Err(anyhow!("No commits found in '{}'", range));
+    }
+    Ok(commits)
+}
+
+/// Read the Git-Iris note attached to a commit, if there is one
+pub fn read_note(repo_path: &Path, commit: &str) -> Result<Option<String>> {
+    let repo = Repository::open(repo_path)?;
+    let oid = repo.revparse_single(commit)?.peel_to_commit()?.id();
+    let message = match repo.find_note(Some(NOTES_REF), oid) {
+        Ok(note) => note.message().map(str::to_string),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    Ok(message)
+}
+
+/// Attach a note to a commit under `refs/notes/iris`, replacing any existing Git-Iris note
+pub fn write_note(repo_path: &Path, commit: &str, note: &str) -> Result<()> {
+    let repo = Repository::open(repo_path)?;
+    let oid: Oid = repo.revparse_single(commit)?.peel_to_commit()?.id();
+    let signature = repo.signature()?;
+    repo.note(
+        &signature,
+        &signature,
+        Some(NOTES_REF),
+        oid,
+        &format!("{}\n", note.trim_end()),
+        true,
+    )?;
+    Ok(())
+}
+
+/// Summarize the context a commit message was generated from, for recording as a note
+pub fn format_context_note(context: &CommitContext, provider: &str, instructions: &str) -> String {
+    let mut note = String::from("Git-Iris context summary\n\n");
+
+    note.push_str(&format!("Branch: {}\n", context.branch));
+    note.push_str(&format!("Provider: {}\n", provider));
+    if let Some(language) = &context.project_metadata.language {
+        note.push_str(&format!("Language: {}\n", language));
+    }
+    if let Some(framework) = &context.project_metadata.framework {
+        note.push_str(&format!("Framework: {}\n", framework));
+    }
+
+    note.push_str("\nChanges:\n");
+    for file in &context.staged_files {
+        note.push_str(&format!("- {} ({})\n", file.path, file.change_type));
+        for analysis in &file.analysis {
+            note.push_str(&format!("  - {}\n", analysis));
+        }
+    }
+
+    if !context.workspace_impact.is_empty() {
+        note.push_str(&format!(
+            "\nWorkspace impact:\n{}\n",
+            format_workspace_impact(&context.workspace_impact)
+        ));
+    }
+
+    if !instructions.trim().is_empty() {
+        note.push_str(&format!("\nInstructions:\n{}\n", instructions.trim()));
+    }
+
+    note
+}
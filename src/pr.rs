@@ -0,0 +1,336 @@
+use crate::config::Config;
+use crate::exclusions::PromptFilter;
+use crate::git;
+use crate::llm;
+use crate::llm_providers::LLMProviderType;
+use crate::pr_prompts;
+use anyhow::{anyhow, Context, Result};
+use reqwest::Client;
+use serde_json::json;
+use std::env;
+use std::path::Path;
+
+/// A squash-merge commit message, split into its subject and optional body
+#[derive(Debug, Clone, PartialEq)]
+pub struct SquashMessage {
+    pub title: String,
+    pub body: Option<String>,
+}
+
+pub struct SquashTitleGenerator;
+
+impl SquashTitleGenerator {
+    pub async fn generate(
+        repo_path: &Path,
+        base: &str,
+        config: &Config,
+        include_body: bool,
+    ) -> Result<SquashMessage> {
+        let filter = PromptFilter::from_config(config);
+        let changes = git::get_branch_commits(repo_path, base, &filter)?;
+        if changes.is_empty() {
+            return Err(anyhow!(
+                "No commits found on the current branch since it diverged from '{}'",
+                base
+            ));
+        }
+        let branch = git::get_current_branch_name(repo_path)?;
+
+        let mut system_prompt = pr_prompts::create_squash_title_system_prompt(include_body);
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let user_prompt = pr_prompts::create_squash_title_user_prompt(&changes, &branch, base);
+
+        Self::request(config, &system_prompt, &user_prompt, include_body).await
+    }
+
+    /// Generate one consolidated message for the commits between `from` and `to`
+    pub async fn generate_for_range(
+        repo_path: &Path,
+        from: &str,
+        to: &str,
+        config: &Config,
+    ) -> Result<SquashMessage> {
+        let filter = PromptFilter::from_config(config);
+        let changes = git::get_commits_between(repo_path, from, to, &filter)?;
+        if changes.is_empty() {
+            return Err(anyhow!("No commits found between '{}' and '{}'", from, to));
+        }
+
+        let mut system_prompt = pr_prompts::create_squash_title_system_prompt(true);
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let user_prompt = pr_prompts::create_squash_range_user_prompt(&changes, from, to);
+
+        Self::request(config, &system_prompt, &user_prompt, true).await
+    }
+
+    async fn request(
+        config: &Config,
+        system_prompt: &str,
+        user_prompt: &str,
+        include_body: bool,
+    ) -> Result<SquashMessage> {
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let response =
+            llm::get_refined_message(config, &provider_type, system_prompt, user_prompt, None)
+                .await
+                .context("Failed to generate squash-merge message")?;
+
+        Ok(parse_squash_message(&response, include_body))
+    }
+}
+
+impl SquashMessage {
+    /// Join the title and body into a full commit message
+    pub fn to_commit_message(&self) -> String {
+        match &self.body {
+            Some(body) => format!("{}\n\n{}", self.title, body),
+            None => self.title.clone(),
+        }
+    }
+}
+
+/// A generated pull request title and Markdown description
+#[derive(Debug, Clone, PartialEq)]
+pub struct PullRequestDescription {
+    pub title: String,
+    pub body: String,
+}
+
+pub struct PullRequestGenerator;
+
+impl PullRequestGenerator {
+    pub async fn generate(
+        repo_path: &Path,
+        base: &str,
+        config: &Config,
+    ) -> Result<PullRequestDescription> {
+        let filter = PromptFilter::from_config(config);
+        let changes = git::get_branch_commits(repo_path, base, &filter)?;
+        if changes.is_empty() {
+            return Err(anyhow!(
+                "No commits found on the current branch since it diverged from '{}'",
+                base
+            ));
+        }
+        let branch = git::get_current_branch_name(repo_path)?;
+
+        let mut system_prompt = pr_prompts::create_pr_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let user_prompt = pr_prompts::create_pr_user_prompt(&changes, &branch, base);
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let response =
+            llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+                .await
+                .context("Failed to generate pull request description")?;
+
+        let message = parse_squash_message(&response, true);
+        Ok(PullRequestDescription {
+            title: message.title,
+            body: message.body.unwrap_or_default(),
+        })
+    }
+}
+
+/// The hosting service behind a Git remote
+#[derive(Debug, Clone, PartialEq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+}
+
+/// A repository on GitHub or GitLab, parsed from a remote URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteRepository {
+    pub forge: Forge,
+    pub host: String,
+    /// Repository path such as `owner/repo` (GitLab paths may include subgroups)
+    pub path: String,
+}
+
+/// Parse an SSH or HTTPS remote URL pointing at GitHub or GitLab
+pub fn parse_remote_url(url: &str) -> Option<RemoteRepository> {
+    let url = url.trim();
+    let (host, path) = if let Some(rest) = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .or_else(|| url.strip_prefix("ssh://"))
+    {
+        let rest = rest.rsplit_once('@').map_or(rest, |(_, r)| r);
+        rest.split_once('/')?
+    } else {
+        // scp-like syntax: git@github.com:owner/repo.git
+        let rest = url.split_once('@').map_or(url, |(_, r)| r);
+        rest.split_once(':')?
+    };
+
+    let host = host.split(':').next()?.to_lowercase();
+    let path = path.trim_end_matches('/').trim_end_matches(".git").to_string();
+    if path.is_empty() {
+        return None;
+    }
+
+    let forge = if host.contains("github") {
+        Forge::GitHub
+    } else if host.contains("gitlab") {
+        Forge::GitLab
+    } else {
+        return None;
+    };
+
+    Some(RemoteRepository { forge, host, path })
+}
+
+/// Open a pull request (or GitLab merge request) for the current branch and return its URL
+///
+/// Requires `GITHUB_TOKEN` or `GITLAB_TOKEN` to be set in the environment.
+pub async fn publish_pull_request(
+    repo_path: &Path,
+    base: &str,
+    description: &PullRequestDescription,
+) -> Result<String> {
+    let remote_url = git::get_remote_url(repo_path, "origin")?;
+    let remote = parse_remote_url(&remote_url).ok_or_else(|| {
+        anyhow!(
+            "Remote 'origin' ({}) is not a GitHub or GitLab repository",
+            remote_url
+        )
+    })?;
+    let branch = git::get_current_branch_name(repo_path)?;
+    let client = Client::new();
+
+    match remote.forge {
+        Forge::GitHub => {
+            let token = env::var("GITHUB_TOKEN")
+                .map_err(|_| anyhow!("GITHUB_TOKEN must be set to publish a pull request"))?;
+            let api_base = if remote.host == "github.com" {
+                "https://api.github.com".to_string()
+            } else {
+                format!("https://{}/api/v3", remote.host)
+            };
+
+            let response = client
+                .post(format!("{}/repos/{}/pulls", api_base, remote.path))
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Accept", "application/vnd.github+json")
+                .header("User-Agent", "git-iris")
+                .json(&json!({
+                    "title": description.title,
+                    "body": description.body,
+                    "head": branch,
+                    "base": base,
+                }))
+                .send()
+                .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await?;
+                return Err(anyhow!(
+                    "GitHub API request failed with status {}: {}",
+                    status,
+                    text
+                ));
+            }
+
+            let response_body: serde_json::Value = response.json().await?;
+            response_body["html_url"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("Failed to extract pull request URL from GitHub API response"))
+        }
+        Forge::GitLab => {
+            let token = env::var("GITLAB_TOKEN")
+                .map_err(|_| anyhow!("GITLAB_TOKEN must be set to publish a merge request"))?;
+            let project = remote.path.replace('/', "%2F");
+
+            let response = client
+                .post(format!(
+                    "https://{}/api/v4/projects/{}/merge_requests",
+                    remote.host, project
+                ))
+                .header("PRIVATE-TOKEN", token)
+                .json(&json!({
+                    "title": description.title,
+                    "description": description.body,
+                    "source_branch": branch,
+                    "target_branch": base,
+                }))
+                .send()
+                .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await?;
+                return Err(anyhow!(
+                    "GitLab API request failed with status {}: {}",
+                    status,
+                    text
+                ));
+            }
+
+            let response_body: serde_json::Value = response.json().await?;
+            response_body["web_url"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("Failed to extract merge request URL from GitLab API response"))
+        }
+    }
+}
+
+/// Split an LLM response into a title and body, dropping code fences and surrounding quotes
+pub fn parse_squash_message(response: &str, include_body: bool) -> SquashMessage {
+    let lines: Vec<&str> = response
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .collect();
+
+    let title_index = lines.iter().position(|line| !line.trim().is_empty());
+    let title = title_index
+        .map(|i| {
+            lines[i]
+                .trim()
+                .trim_matches(|c| c == '"' || c == '\'' || c == '`')
+                .to_string()
+        })
+        .unwrap_or_default();
+
+    let body = if include_body {
+        title_index
+            .map(|i| lines[i + 1..].join("\n").trim().to_string())
+            .filter(|body| !body.is_empty())
+    } else {
+        None
+    };
+
+    SquashMessage { title, body }
+}
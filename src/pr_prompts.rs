@@ -0,0 +1,157 @@
+use crate::classify::{classify_file, test_coverage_signals};
+use crate::change_analyzer::AnalyzedChange;
+
+pub fn create_squash_title_system_prompt(include_body: bool) -> String {
+    let mut prompt = String::from(
+        "You are an AI assistant specialized in writing squash-merge commit messages for pull requests. \
+        Your task is to summarize all commits on a branch into the single commit that will land on the main branch. \
+        Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Follow the Conventional Commits format for the title: type(scope): description.
+        2. Use one of these types: feat, fix, docs, style, refactor, perf, test, build, ci, chore, revert.
+        3. Only include a scope if the changes are clearly limited to one area of the codebase.
+        4. Use the imperative mood and a lowercase description without a trailing period.
+        5. Keep the title under 72 characters.
+        6. Describe the net effect of the branch as a whole, not the individual commits.
+        7. Ignore commits that only fix up or revert earlier commits on the same branch.
+        8. Mark breaking changes with '!' after the type or scope.
+        9. Avoid common cliché words (like 'enhance', 'streamline', 'leverage', etc) and phrases.
+        10. Do not speculate about the purpose of a change or add any information not directly supported by the context.
+        11. NO YAPPING!"
+    );
+
+    if include_body {
+        prompt.push_str(
+            "\n\nAfter the title, add a blank line followed by a short body that lists the notable changes as bullet points. \
+            Wrap the body at 72 characters. \
+            If any change is breaking, end the body with a 'BREAKING CHANGE:' footer describing it.",
+        );
+    } else {
+        prompt.push_str("\n\nOutput only the title, on a single line.");
+    }
+
+    prompt.push_str(
+        "\n\nGenerate only the requested text, without any explanations, quotes, or code fences.",
+    );
+
+    prompt
+}
+
+pub fn create_squash_title_user_prompt(
+    changes: &[AnalyzedChange],
+    branch: &str,
+    base: &str,
+) -> String {
+    let prompt = format!(
+        "Based on the following commits on branch '{}' since it diverged from '{}', generate a squash-merge commit message:\n\n",
+        branch, base
+    );
+
+    format_squash_commits(prompt, changes)
+}
+
+pub fn create_squash_range_user_prompt(changes: &[AnalyzedChange], from: &str, to: &str) -> String {
+    let prompt = format!(
+        "Based on the following commits from {} to {}, generate a single consolidated commit message that replaces all of them:\n\n",
+        from, to
+    );
+
+    format_squash_commits(prompt, changes)
+}
+
+fn format_squash_commits(mut prompt: String, changes: &[AnalyzedChange]) -> String {
+    for change in changes {
+        prompt.push_str(&format!("Commit: {}\n", &change.commit_hash[..7]));
+        prompt.push_str(&format!("Message: {}\n", change.commit_message.trim()));
+        prompt.push_str("Files changed:\n");
+        for file_change in &change.file_changes {
+            prompt.push_str(&format!(
+                "  - {} ({})\n",
+                file_change.new_path, file_change.change_type
+            ));
+        }
+        prompt.push('\n');
+    }
+
+    prompt
+}
+
+pub fn create_pr_system_prompt() -> String {
+    String::from(
+        "You are an AI assistant specialized in writing pull request descriptions. \
+        Your task is to create a title and a structured description for a pull request based on the provided commits and analysis. \
+        Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Write the title on the first line, followed by a blank line and the description.
+        2. Keep the title under 72 characters, use the imperative mood, and do not end it with a period.
+        3. Write the description in Markdown with exactly these sections: '## Summary', '## Changes', and '## Testing'.
+        4. In 'Summary', explain in two or three sentences what the pull request does as a whole.
+        5. In 'Changes', list the notable changes as bullet points, grouped by area when that helps.
+        6. In 'Testing', describe the tests that were added or changed; if none are visible in the context, say what reviewers should verify manually.
+        7. Call out breaking changes, migrations, or new configuration in a bold 'Note:' line in the relevant section.
+        8. Describe the net effect of the branch, not the history of individual commits.
+        9. Avoid common cliché words (like 'enhance', 'streamline', 'leverage', etc) and phrases.
+        10. Do not speculate about the purpose of a change or add any information not directly supported by the context.
+        11. NO YAPPING!
+
+        Generate only the title and description, without any explanations or code fences around the whole response."
+    )
+}
+
+pub fn create_pr_user_prompt(changes: &[AnalyzedChange], branch: &str, base: &str) -> String {
+    let mut prompt = format!(
+        "Based on the following commits on branch '{}' since it diverged from '{}', generate a pull request title and description:\n\n",
+        branch, base
+    );
+
+    let files_changed: usize = changes.iter().map(|c| c.metrics.files_changed).sum();
+    let insertions: usize = changes.iter().map(|c| c.metrics.insertions).sum();
+    let deletions: usize = changes.iter().map(|c| c.metrics.deletions).sum();
+    prompt.push_str("Overall Changes:\n");
+    prompt.push_str(&format!("Total commits: {}\n", changes.len()));
+    prompt.push_str(&format!("Files changed: {}\n", files_changed));
+    prompt.push_str(&format!("Insertions: {}\n", insertions));
+    prompt.push_str(&format!("Deletions: {}\n\n", deletions));
+
+    // Only paths are known here, so tests are noted as changed rather than counted
+    let mut paths: Vec<&str> = Vec::new();
+    for file_change in changes.iter().flat_map(|change| &change.file_changes) {
+        if !paths.contains(&file_change.new_path.as_str()) {
+            paths.push(&file_change.new_path);
+        }
+    }
+    let files: Vec<(&str, &str)> = paths.into_iter().map(|path| (path, "")).collect();
+    let test_coverage = test_coverage_signals(&files);
+    if !test_coverage.is_empty() {
+        prompt.push_str("Test coverage:\n");
+        for signal in &test_coverage {
+            prompt.push_str(&format!("- {}\n", signal));
+        }
+        prompt.push('\n');
+    }
+
+    for change in changes {
+        prompt.push_str(&format!("Commit: {}\n", &change.commit_hash[..7]));
+        prompt.push_str(&format!("Message: {}\n", change.commit_message.trim()));
+        prompt.push_str(&format!("Impact score: {:.2}\n", change.impact_score));
+        prompt.push_str("File changes:\n");
+        for file_change in &change.file_changes {
+            prompt.push_str(&format!(
+                "  - {} ({}, {})\n",
+                file_change.new_path,
+                file_change.change_type,
+                classify_file(&file_change.new_path, "")
+            ));
+            for analysis in &file_change.analysis {
+                prompt.push_str(&format!("    * {}\n", analysis));
+            }
+        }
+        prompt.push('\n');
+    }
+
+    prompt
+}
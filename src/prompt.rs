@@ -1,13 +1,21 @@
-use crate::config::Config;
-use crate::context::{ChangeType, CommitContext, ProjectMetadata, RecentCommit, StagedFile};
+use crate::classify::{classify_file, test_coverage_signals};
+use crate::config::{Config, MessageFormatConfig};
+use crate::context::{
+    ChangeType, CommitContext, ProjectMetadata, RecentCommit, StageStatus, StagedFile,
+};
 use crate::gitmoji::{apply_gitmoji, get_gitmoji_list};
+use crate::language::Language;
 use crate::log_debug;
 use crate::relevance::RelevanceScorer;
+use crate::templates;
+use crate::workspace::format_workspace_impact;
 use anyhow::Result;
+use serde_json::json;
 use std::collections::HashMap;
 
 pub fn create_prompt(context: &CommitContext, config: &Config) -> Result<String> {
-    let system_prompt = create_system_prompt(config.use_gitmoji, &config.instructions);
+    let system_prompt =
+        create_system_prompt(config.use_gitmoji, &config.instructions, config.language());
     let user_prompt = create_user_prompt(context)?;
 
     let full_prompt = format!("{}\n\n{}", system_prompt, user_prompt);
@@ -15,15 +23,30 @@ pub fn create_prompt(context: &CommitContext, config: &Config) -> Result<String>
     Ok(full_prompt)
 }
 
-pub fn create_system_prompt(use_gitmoji: bool, combined_instructions: &str) -> String {
-    let mut prompt = String::from(
+pub fn create_system_prompt(
+    use_gitmoji: bool,
+    combined_instructions: &str,
+    language: Language,
+) -> String {
+    let variables = json!({
+        "use_gitmoji": use_gitmoji,
+        "gitmoji_list": get_gitmoji_list(),
+        "instructions": combined_instructions,
+        "language": language.name,
+    });
+    if let Some(prompt) = templates::render_override_or_warn(templates::COMMIT_SYSTEM, &variables)
+    {
+        return prompt;
+    }
+
+    let mut prompt = format!(
         "You are an AI assistant specializing in creating high-quality, professional Git commit messages. \
         Your task is to generate clear,concise, and informative commit messages based solely on the provided context. \
         Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
         
         Work step-by-step and follow these guidelines exactly:
 
-        1. Use the imperative mood in the subject line (e.g., 'Add feature' not 'Added feature').
+        1. {}
         2. Limit the subject line to 50 characters if possible, but never exceed 72 characters.
         3. Capitalize the subject line.
         4. Do not end the subject line with a period.
@@ -47,7 +70,8 @@ pub fn create_system_prompt(use_gitmoji: bool, combined_instructions: &str) -> S
 
         Generate only the commit message, without any explanations, questions, or additional text.
         The message should be based entirely on the information provided in the context,
-        without any speculation or assumptions."
+        without any speculation or assumptions.",
+        language.subject_rule()
     );
 
     if use_gitmoji {
@@ -68,12 +92,69 @@ pub fn create_system_prompt(use_gitmoji: bool, combined_instructions: &str) -> S
     prompt
 }
 
+/// Layout rules that replace the default subject and body limits in the system prompt
+pub fn format_instructions(format: &MessageFormatConfig) -> String {
+    let mut instructions = format!(
+        "These layout rules replace the ones above. \
+        Limit the subject line to {} characters if possible",
+        format.subject_length
+    );
+    if format.max_subject_length > 0 {
+        instructions.push_str(&format!(
+            ", but never exceed {} characters",
+            format.max_subject_length
+        ));
+    }
+    instructions.push('.');
+    if format.wrap_body_at > 0 {
+        instructions.push_str(&format!(" Wrap the body at {} characters.", format.wrap_body_at));
+    }
+    if !format.blank_line_after_subject {
+        instructions.push_str(" The body may follow the subject line directly.");
+    }
+    instructions
+}
+
 pub fn create_user_prompt(context: &CommitContext) -> Result<String> {
     let scorer = RelevanceScorer::new();
     let relevance_scores = scorer.score(context);
     let detailed_changes = format_detailed_changes(&context.staged_files, &relevance_scores);
+    let has_unstaged_changes = context
+        .staged_files
+        .iter()
+        .any(|file| file.stage_status != StageStatus::Staged);
+    let test_coverage = coverage_signals(&context.staged_files);
 
-    let prompt = format!(
+    let variables = json!({
+        "branch": context.branch,
+        "recent_commits": format_recent_commits(&context.recent_commits),
+        "staged_files": format_staged_files(&context.staged_files, &relevance_scores),
+        "unstaged_files": context.unstaged_files.join(", "),
+        "project_metadata": format_project_metadata(&context.project_metadata),
+        "detailed_changes": detailed_changes,
+        "has_unstaged_changes": has_unstaged_changes,
+        "workspace_impact": format_workspace_impact(&context.workspace_impact),
+        "test_coverage": format_test_coverage(&test_coverage),
+        "files": context.staged_files.iter().map(|file| json!({
+            "path": file.path,
+            "change_type": format_change_type(&file.change_type),
+            "role": classify_file(&file.path, &file.diff),
+            "stage_status": file.stage_status.to_string(),
+            "relevance": relevance_scores.get(&file.path).copied().unwrap_or(0.0),
+            "analysis": file.analysis,
+            "diff": file.diff,
+        })).collect::<Vec<_>>(),
+        "commits": context.recent_commits.iter().map(|commit| json!({
+            "hash": commit.hash,
+            "message": commit.message,
+        })).collect::<Vec<_>>(),
+    });
+    if let Some(prompt) = templates::render_override(templates::COMMIT_USER, &variables)? {
+        log_debug!("Detailed changes:\n{}", detailed_changes);
+        return Ok(prompt);
+    }
+
+    let mut prompt = format!(
         "Based on the following context, generate a Git commit message:\n\n\
         Branch: {}\n\n\
         Recent commits:\n{}\n\n\
@@ -89,11 +170,160 @@ pub fn create_user_prompt(context: &CommitContext) -> Result<String> {
         detailed_changes
     );
 
+    if has_unstaged_changes {
+        prompt.push_str(
+            "\n\nSome of the changes above are not staged yet and are labeled as unstaged. \
+            They will be committed together with the staged changes, as with `git commit -a`, \
+            so describe all of them in the commit message.",
+        );
+    }
+
+    if !context.workspace_impact.is_empty() {
+        prompt.push_str(&format!(
+            "\n\nWorkspace impact (changed members and the members that depend on them):\n{}\n\
+            When a changed member has dependents, mention the scope of the impact in the commit body.",
+            format_workspace_impact(&context.workspace_impact)
+        ));
+    }
+
+    if !test_coverage.is_empty() {
+        prompt.push_str(&format!(
+            "\n\nTest coverage:\n{}\n\
+            Only say the change is tested as far as these show; don't claim tests that aren't there.",
+            format_test_coverage(&test_coverage)
+        ));
+    }
+
     log_debug!("Detailed changes:\n{}", detailed_changes);
 
     Ok(prompt)
 }
 
+pub fn create_amend_user_prompt(context: &CommitContext, existing_message: &str) -> Result<String> {
+    let mut prompt = create_user_prompt(context)?;
+    prompt.push_str(&format!(
+        "\n\nExisting commit message:\n{}\n\n\
+        The changes above are already committed with the existing message. \
+        Write an improved message for them, keeping any details from the existing message that the changes support.",
+        existing_message.trim()
+    ));
+    Ok(prompt)
+}
+
+pub fn create_refine_user_prompt(
+    user_prompt: &str,
+    previous_message: &str,
+    earlier_feedback: &[String],
+    feedback: &str,
+) -> String {
+    let mut prompt = format!(
+        "{}\n\nYou previously wrote this commit message for the changes above:\n{}\n",
+        user_prompt,
+        previous_message.trim()
+    );
+    if !earlier_feedback.is_empty() {
+        prompt.push_str("\nIt already reflects this earlier feedback:\n");
+        for item in earlier_feedback {
+            prompt.push_str(&format!("- {}\n", item.trim()));
+        }
+    }
+    prompt.push_str(&format!(
+        "\nRevise the message based on the following feedback, keeping everything the feedback does not ask to change:\n{}",
+        feedback.trim()
+    ));
+    prompt
+}
+
+/// Tell the model which candidates the user passed over, so a revision doesn't drift back to them
+pub fn append_rejected_candidates(prompt: &mut String, rejected: &[String]) {
+    if rejected.is_empty() {
+        return;
+    }
+    prompt.push_str("\n\nThe user chose the previous message over these alternatives; keep the revision closer to the chosen style:\n");
+    for candidate in rejected {
+        prompt.push_str(&format!("---\n{}\n", candidate.trim()));
+    }
+    prompt.push_str("---");
+}
+
+pub fn create_review_system_prompt(combined_instructions: &str) -> String {
+    let mut prompt = String::from(
+        "You are an AI assistant specializing in reviewing code changes before they are committed. \
+        Your task is to review the staged changes in the provided context and report concrete, actionable findings. \
+        Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Group findings into these categories, in this order: Bugs, Security, Missing Tests, Style.
+        2. Use a Markdown heading for each category and omit categories without findings.
+        3. Start every finding with a file and line reference in the form `path:line`, using the line numbers from the diff hunk headers.
+        4. Describe the problem in one or two sentences, then suggest a fix.
+        5. Only report issues in lines that were added or modified in the diff.
+        6. Report missing tests only for new or changed behavior that has no corresponding test change.
+        7. Do not report style issues that an automatic formatter would fix.
+        8. Order findings within each category by severity, most severe first.
+        9. Do not speculate about code that is not shown in the context.
+        10. If there are no findings at all, reply with a single line saying the changes look good.
+        11. NO YAPPING!
+
+        Generate only the review, without any introduction or closing remarks."
+    );
+
+    if !combined_instructions.is_empty() {
+        prompt.push_str(&format!(
+            "\n\nAdditional instructions:\n{}\n\n",
+            combined_instructions
+        ));
+    }
+
+    prompt
+}
+
+pub fn create_review_user_prompt(context: &CommitContext) -> Result<String> {
+    let scorer = RelevanceScorer::new();
+    let relevance_scores = scorer.score(context);
+    let detailed_changes = format_detailed_changes(&context.staged_files, &relevance_scores);
+
+    let mut prompt = format!(
+        "Based on the following context, review the staged changes:\n\n\
+        Branch: {}\n\n\
+        Staged changes:\n{}\n\n\
+        Project metadata:\n{}\n\n\
+        Detailed changes:\n{}",
+        context.branch,
+        format_staged_files(&context.staged_files, &relevance_scores),
+        format_project_metadata(&context.project_metadata),
+        detailed_changes
+    );
+
+    let test_coverage = coverage_signals(&context.staged_files);
+    if !test_coverage.is_empty() {
+        prompt.push_str(&format!(
+            "\n\nTest coverage:\n{}",
+            format_test_coverage(&test_coverage)
+        ));
+    }
+
+    Ok(prompt)
+}
+
+/// How the tests in the changes relate to the code they change
+fn coverage_signals(files: &[StagedFile]) -> Vec<String> {
+    let files: Vec<(&str, &str)> = files
+        .iter()
+        .map(|file| (file.path.as_str(), file.diff.as_str()))
+        .collect();
+    test_coverage_signals(&files)
+}
+
+fn format_test_coverage(signals: &[String]) -> String {
+    signals
+        .iter()
+        .map(|signal| format!("- {}", signal))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 fn format_recent_commits(commits: &[RecentCommit]) -> String {
     commits
         .iter()
@@ -108,10 +338,12 @@ fn format_staged_files(files: &[StagedFile], relevance_scores: &HashMap<String,
         .map(|file| {
             let relevance = relevance_scores.get(&file.path).unwrap_or(&0.0);
             format!(
-                "{} ({:.2}) - {}",
+                "{} ({:.2}) - {}{} [{}]",
                 file.path,
                 relevance,
-                format_change_type(&file.change_type)
+                format_change_type(&file.change_type),
+                format_stage_label(file.stage_status),
+                classify_file(&file.path, &file.diff)
             )
         })
         .collect::<Vec<_>>()
@@ -136,10 +368,11 @@ fn format_detailed_changes(
         .map(|file| {
             let relevance = relevance_scores.get(&file.path).unwrap_or(&0.0);
             format!(
-                "File: {} (Relevance: {:.2})\nChange Type: {}\nAnalysis:\n{}\n\nDiff:\n{}",
+                "File: {} (Relevance: {:.2})\nChange Type: {}{}\nAnalysis:\n{}\n\nDiff:\n{}",
                 file.path,
                 relevance,
                 format_change_type(&file.change_type),
+                format_stage_label(file.stage_status),
                 file.analysis.join("\n"),
                 file.diff
             )
@@ -156,6 +389,14 @@ fn format_change_type(change_type: &ChangeType) -> &'static str {
     }
 }
 
+/// Label a file whose change is not only in the index, leaving staged files unlabeled
+fn format_stage_label(stage_status: StageStatus) -> String {
+    match stage_status {
+        StageStatus::Staged => String::new(),
+        _ => format!(" ({})", stage_status),
+    }
+}
+
 pub fn process_commit_message(message: String, use_gitmoji: bool) -> String {
     if use_gitmoji {
         apply_gitmoji(&message)
@@ -0,0 +1,150 @@
+use crate::config::RedactionConfig;
+use crate::secrets::{find_env_values, find_secrets, SecretKind};
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use std::fmt::Write;
+
+/// Prefix marking a redaction pattern as a glob rather than a regular expression
+pub const GLOB_PREFIX: &str = "glob:";
+
+/// Characters a glob's `*` and `?` can stand for: those found in words and hostnames
+const GLOB_WORD_CHARS: &str = r"[A-Za-z0-9_.\-]";
+
+/// Text replaced before a prompt was sent, for the `--show-redactions` report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redaction {
+    /// The pattern as configured, or the kind of secret that was detected
+    pub pattern: String,
+    /// The text that was replaced
+    pub text: String,
+    /// Line of the prompt it was found on, starting at 1
+    pub line: usize,
+}
+
+/// Replaces text matching the configured patterns, and likely secrets, in everything sent to
+/// a provider
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    rules: Vec<(String, Regex)>,
+    replacement: String,
+    detect_secrets: bool,
+}
+
+impl Redactor {
+    /// Compile the configured patterns, failing on the first invalid one
+    pub fn new(config: &RedactionConfig) -> Result<Self> {
+        let rules = config
+            .patterns
+            .iter()
+            .map(|pattern| Ok((pattern.clone(), compile_pattern(pattern)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            rules,
+            replacement: config.replacement.clone(),
+            detect_secrets: config.detect_secrets,
+        })
+    }
+
+    /// Replace every match of every pattern, returning the redacted text and what was replaced
+    pub fn redact(&self, text: &str) -> (String, Vec<Redaction>) {
+        let mut redacted = text.to_string();
+        let mut redactions = Vec::new();
+        for (pattern, regex) in &self.rules {
+            for found in regex.find_iter(&redacted) {
+                if found.as_str().is_empty() {
+                    continue;
+                }
+                redactions.push(Redaction {
+                    pattern: pattern.clone(),
+                    text: found.as_str().to_string(),
+                    line: redacted[..found.start()].matches('\n').count() + 1,
+                });
+            }
+            redacted = regex
+                .replace_all(&redacted, regex::NoExpand(&self.replacement))
+                .into_owned();
+        }
+        if self.detect_secrets {
+            for (kind, secret) in find_secrets(&redacted) {
+                let Some(start) = redacted.find(&secret) else {
+                    continue;
+                };
+                redactions.push(Redaction {
+                    pattern: format!("detected {}", kind),
+                    line: redacted[..start].matches('\n').count() + 1,
+                    text: secret.clone(),
+                });
+                redacted = redacted.replace(&secret, &self.replacement);
+            }
+            redacted = self.redact_env_values(&redacted, &mut redactions);
+        }
+        (redacted, redactions)
+    }
+
+    /// Replace the values set in `.env` files, on their own lines only, as short values such
+    /// as `true` would match all over the prompt
+    fn redact_env_values(&self, text: &str, redactions: &mut Vec<Redaction>) -> String {
+        let mut lines: Vec<String> = text.split('\n').map(ToString::to_string).collect();
+        for (index, value) in find_env_values(text) {
+            let line = &mut lines[index];
+            let Some(start) = line.find('=').and_then(|equals| {
+                line[equals..].find(&value).map(|offset| equals + offset)
+            }) else {
+                continue;
+            };
+            line.replace_range(start..start + value.len(), &self.replacement);
+            redactions.push(Redaction {
+                pattern: format!("detected {}", SecretKind::EnvValue),
+                text: value,
+                line: index + 1,
+            });
+        }
+        lines.join("\n")
+    }
+}
+
+/// Compile a redaction pattern: a regular expression, or a glob after `glob:`
+pub fn compile_pattern(pattern: &str) -> Result<Regex> {
+    let source = match pattern.strip_prefix(GLOB_PREFIX) {
+        Some(glob) => glob_to_regex(glob.trim()),
+        None => pattern.to_string(),
+    };
+    if source.is_empty() {
+        return Err(anyhow!("Empty redaction pattern"));
+    }
+    Regex::new(&source).map_err(|e| anyhow!("Invalid redaction pattern '{}': {}", pattern, e))
+}
+
+/// Turn a glob such as `*.corp.example.com` into a regular expression, where `*` stands for
+/// any run of word characters and `?` for one, so a match never spans whitespace
+pub fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::new();
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(&format!("{}*", GLOB_WORD_CHARS)),
+            '?' => regex.push_str(GLOB_WORD_CHARS),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex
+}
+
+/// Describe what was redacted from each prompt, for `--show-redactions`
+pub fn format_redaction_report(prompts: &[(&str, &[Redaction])], replacement: &str) -> String {
+    let mut report = String::new();
+    for (name, redactions) in prompts {
+        let _ = writeln!(report, "=== {} ===", name);
+        if redactions.is_empty() {
+            let _ = writeln!(report, "Nothing redacted");
+        }
+        for redaction in *redactions {
+            let _ = writeln!(
+                report,
+                "line {}: {} → {} (pattern: {})",
+                redaction.line, redaction.text, replacement, redaction.pattern
+            );
+        }
+        report.push('\n');
+    }
+    report.trim_end().to_string()
+}
@@ -0,0 +1,169 @@
+use crate::git;
+use crate::pr::{parse_remote_url, Forge};
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde_json::json;
+use std::env;
+use std::path::Path;
+
+/// Title written at the top of a new changelog file
+const CHANGELOG_TITLE: &str = "# Changelog";
+
+/// Pick the tag name for a release, following the `v` prefix convention of the previous tag
+pub fn release_tag_name(previous_tag: Option<&str>, version: &str) -> String {
+    let version = version.trim().trim_start_matches(['v', 'V']);
+    match previous_tag {
+        Some(tag) if !tag.starts_with(['v', 'V']) => version.to_string(),
+        _ => format!("v{}", version),
+    }
+}
+
+/// Insert a release section into the contents of a changelog file, newest release first
+pub fn insert_changelog_entry(existing: &str, version: &str, date: &str, changelog: &str) -> String {
+    let entry = format!(
+        "## [{}] - {}\n\n{}\n",
+        version,
+        date,
+        nest_headings(changelog).trim()
+    );
+    if existing.trim().is_empty() {
+        return format!("{}\n\n{}", CHANGELOG_TITLE, entry);
+    }
+
+    // Insert before the first release section, keeping any title and preamble on top
+    let mut offset = 0;
+    for line in existing.split_inclusive('\n') {
+        if line.starts_with("## ") {
+            return format!("{}{}\n{}", &existing[..offset], entry, &existing[offset..]);
+        }
+        offset += line.len();
+    }
+    format!("{}\n\n{}", existing.trim_end(), entry)
+}
+
+/// Drop a generated title and shift headings so the changelog's sections nest under the release heading
+fn nest_headings(changelog: &str) -> String {
+    let heading_level = |line: &str| {
+        let level = line.chars().take_while(|c| *c == '#').count();
+        (level > 0 && line[level..].starts_with(' ')).then_some(level)
+    };
+
+    let mut lines: Vec<&str> = changelog.trim().lines().collect();
+    if lines.first().and_then(|line| heading_level(line)) == Some(1) {
+        lines.remove(0);
+    }
+
+    let Some(top_level) = lines.iter().filter_map(|line| heading_level(line)).min() else {
+        return lines.join("\n");
+    };
+    let shift = "#".repeat(3usize.saturating_sub(top_level));
+    lines
+        .iter()
+        .map(|line| match heading_level(line) {
+            Some(_) => format!("{}{}", shift, line),
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Update the changelog file with a new release section, creating the file if needed
+pub fn update_changelog_file(path: &Path, version: &str, date: &str, changelog: &str) -> Result<()> {
+    let existing = if path.exists() {
+        std::fs::read_to_string(path)?
+    } else {
+        String::new()
+    };
+    std::fs::write(path, insert_changelog_entry(&existing, version, date, changelog))?;
+    Ok(())
+}
+
+/// Publish a GitHub or GitLab release for an already pushed tag and return its URL
+///
+/// Requires `GITHUB_TOKEN` or `GITLAB_TOKEN` to be set in the environment.
+pub async fn publish_release(repo_path: &Path, tag: &str, notes: &str) -> Result<String> {
+    let remote_url = git::get_remote_url(repo_path, "origin")?;
+    let remote = parse_remote_url(&remote_url).ok_or_else(|| {
+        anyhow!(
+            "Remote 'origin' ({}) is not a GitHub or GitLab repository",
+            remote_url
+        )
+    })?;
+    let client = Client::new();
+
+    match remote.forge {
+        Forge::GitHub => {
+            let token = env::var("GITHUB_TOKEN")
+                .map_err(|_| anyhow!("GITHUB_TOKEN must be set to publish a release"))?;
+            let api_base = if remote.host == "github.com" {
+                "https://api.github.com".to_string()
+            } else {
+                format!("https://{}/api/v3", remote.host)
+            };
+
+            let response = client
+                .post(format!("{}/repos/{}/releases", api_base, remote.path))
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Accept", "application/vnd.github+json")
+                .header("User-Agent", "git-iris")
+                .json(&json!({
+                    "tag_name": tag,
+                    "name": tag,
+                    "body": notes,
+                }))
+                .send()
+                .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await?;
+                return Err(anyhow!(
+                    "GitHub API request failed with status {}: {}",
+                    status,
+                    text
+                ));
+            }
+
+            let response_body: serde_json::Value = response.json().await?;
+            response_body["html_url"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("Failed to extract release URL from GitHub API response"))
+        }
+        Forge::GitLab => {
+            let token = env::var("GITLAB_TOKEN")
+                .map_err(|_| anyhow!("GITLAB_TOKEN must be set to publish a release"))?;
+            let project = remote.path.replace('/', "%2F");
+
+            let response = client
+                .post(format!(
+                    "https://{}/api/v4/projects/{}/releases",
+                    remote.host, project
+                ))
+                .header("PRIVATE-TOKEN", token)
+                .json(&json!({
+                    "tag_name": tag,
+                    "name": tag,
+                    "description": notes,
+                }))
+                .send()
+                .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await?;
+                return Err(anyhow!(
+                    "GitLab API request failed with status {}: {}",
+                    status,
+                    text
+                ));
+            }
+
+            let response_body: serde_json::Value = response.json().await?;
+            response_body["_links"]["self"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("Failed to extract release URL from GitLab API response"))
+        }
+    }
+}
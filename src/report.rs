@@ -0,0 +1,262 @@
+use crate::change_analyzer::AnalyzedChange;
+use crate::changelog::{append_workspace_impact, retain_enabled_risks, DetailLevel};
+use crate::changelog_prompts::{calculate_total_metrics, push_change_details};
+use crate::config::Config;
+use crate::llm;
+use crate::llm_providers::LLMProviderType;
+use crate::summary::{get_commits_since, group_by_area};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local};
+use std::collections::HashSet;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Number of areas listed as most active in the metrics section
+const TOP_AREAS: usize = 3;
+
+/// Output format of a progress report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl FromStr for ReportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "md" | "markdown" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            _ => Err(anyhow!("Invalid report format: {}. Use md or html", s)),
+        }
+    }
+}
+
+/// Figures computed locally for the metrics section of a report
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportMetrics {
+    pub commits: usize,
+    pub contributors: usize,
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// The areas with the most commits, with their commit counts
+    pub top_areas: Vec<(String, usize)>,
+}
+
+impl ReportMetrics {
+    pub fn from_changes(changes: &[AnalyzedChange]) -> Self {
+        let totals = calculate_total_metrics(changes);
+        let contributors: HashSet<&str> = changes.iter().map(|c| c.author.as_str()).collect();
+
+        let mut top_areas: Vec<(String, usize)> = group_by_area(changes)
+            .into_iter()
+            .map(|(area, commits)| (area, commits.len()))
+            .collect();
+        // Stable sort keeps areas with equal counts in alphabetical order
+        top_areas.sort_by_key(|(_, commits)| std::cmp::Reverse(*commits));
+        top_areas.truncate(TOP_AREAS);
+
+        ReportMetrics {
+            commits: changes.len(),
+            contributors: contributors.len(),
+            files_changed: totals.files_changed,
+            insertions: totals.insertions,
+            deletions: totals.deletions,
+            top_areas,
+        }
+    }
+}
+
+pub struct ReportGenerator;
+
+impl ReportGenerator {
+    /// Generate a progress report for the commits on HEAD since the given time
+    pub async fn generate(
+        repo_path: &Path,
+        since: DateTime<Local>,
+        author: Option<&str>,
+        format: ReportFormat,
+        config: &Config,
+    ) -> Result<String> {
+        let mut changes = get_commits_since(repo_path, author, since)?;
+        if changes.is_empty() {
+            return Err(anyhow!(
+                "No commits since {}",
+                since.format("%Y-%m-%d %H:%M")
+            ));
+        }
+        retain_enabled_risks(repo_path, config, &mut changes);
+
+        let title = report_title(since, Local::now(), author);
+
+        let mut system_prompt = create_report_system_prompt(format);
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let mut user_prompt = create_report_user_prompt(&changes, &title);
+        append_workspace_impact(&mut user_prompt, repo_path, &changes);
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let body =
+            llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+                .await
+                .context("Failed to generate report")?;
+
+        Ok(render_report(
+            format,
+            &title,
+            &body,
+            &ReportMetrics::from_changes(&changes),
+        ))
+    }
+}
+
+/// Title of a report covering the time from `since` to `until`
+pub fn report_title(
+    since: DateTime<Local>,
+    until: DateTime<Local>,
+    author: Option<&str>,
+) -> String {
+    let mut title = format!(
+        "Progress Report: {} to {}",
+        since.format("%Y-%m-%d"),
+        until.format("%Y-%m-%d")
+    );
+    if let Some(author) = author {
+        title.push_str(&format!(" ({})", author));
+    }
+    title
+}
+
+pub fn create_report_system_prompt(format: ReportFormat) -> String {
+    let mut prompt = String::from(
+        "You are an AI assistant specialized in writing progress reports about software projects for teammates and stakeholders. \
+        Your task is to turn the provided commits into a polished report of what was accomplished in a period. \
+        Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Write exactly these sections, in this order: 'Summary', 'Themes of Work', and 'Notable Changes'.
+        2. In 'Summary', write two to four sentences on the overall progress in the period.
+        3. In 'Themes of Work', group related commits into a few themes, each with a short title and a sentence or two on what was done.
+        4. In 'Notable Changes', list the most significant individual changes, using the impact scores to choose them, with the short commit hash in parentheses.
+        5. Mention user-visible outcomes before internal changes.
+        6. If any changes carry risk labels, mention them in 'Notable Changes'.
+        7. Do not add a title or a metrics section; they are added for you.
+        8. Write in the past tense and the third person.
+        9. Avoid common cliché words (like 'enhance', 'streamline', 'leverage', etc) and phrases.
+        10. Do not speculate about the purpose of a change or add any information not directly supported by the context.
+        11. NO YAPPING!",
+    );
+
+    match format {
+        ReportFormat::Markdown => prompt.push_str(
+            "\n\nFormat the report in Markdown, with a '## ' heading for each section. \
+            Generate only the report sections, without any introduction or closing remarks.",
+        ),
+        ReportFormat::Html => prompt.push_str(
+            "\n\nFormat the report as an HTML fragment, with an <h2> heading for each section and <p> and <ul> elements for the content. \
+            Do not include <html>, <head>, or <body> elements, styles, or code fences. \
+            Generate only the report sections, without any introduction or closing remarks.",
+        ),
+    }
+
+    prompt
+}
+
+pub fn create_report_user_prompt(changes: &[AnalyzedChange], title: &str) -> String {
+    let mut prompt = format!(
+        "Based on the following {} commits, write the sections of the report '{}':\n\n",
+        changes.len(),
+        title
+    );
+
+    for change in changes {
+        push_change_details(&mut prompt, change, DetailLevel::Standard);
+        prompt.push('\n');
+    }
+
+    prompt
+}
+
+/// Put the title, the generated sections, and the locally computed metrics together
+pub fn render_report(
+    format: ReportFormat,
+    title: &str,
+    body: &str,
+    metrics: &ReportMetrics,
+) -> String {
+    let areas = metrics
+        .top_areas
+        .iter()
+        .map(|(area, commits)| match commits {
+            1 => format!("{} (1 commit)", area),
+            _ => format!("{} ({} commits)", area, commits),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let rows = [
+        ("Commits", metrics.commits.to_string()),
+        ("Contributors", metrics.contributors.to_string()),
+        ("Files changed", metrics.files_changed.to_string()),
+        (
+            "Lines changed",
+            format!("+{} -{}", metrics.insertions, metrics.deletions),
+        ),
+        ("Most active areas", areas),
+    ];
+
+    match format {
+        ReportFormat::Markdown => {
+            let mut report = format!("# {}\n\n{}\n\n## Metrics\n\n", title, body.trim());
+            report.push_str("| Metric | Value |\n| --- | --- |\n");
+            for (name, value) in rows {
+                report.push_str(&format!("| {} | {} |\n", name, value));
+            }
+            report
+        }
+        ReportFormat::Html => {
+            let mut table = String::new();
+            for (name, value) in rows {
+                table.push_str(&format!(
+                    "<tr><th>{}</th><td>{}</td></tr>\n",
+                    name,
+                    escape_html(&value)
+                ));
+            }
+            format!(
+                "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+                <style>\n{style}\n</style>\n</head>\n<body>\n<h1>{title}</h1>\n{body}\n\
+                <h2>Metrics</h2>\n<table>\n{table}</table>\n</body>\n</html>\n",
+                title = escape_html(title),
+                style = HTML_STYLE,
+                body = body.trim(),
+                table = table,
+            )
+        }
+    }
+}
+
+const HTML_STYLE: &str = "body { font-family: system-ui, sans-serif; max-width: 48rem; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; color: #222; }
+h1 { border-bottom: 2px solid #7c3aed; padding-bottom: 0.3rem; }
+h2 { color: #7c3aed; }
+table { border-collapse: collapse; }
+th, td { text-align: left; padding: 0.3rem 1rem 0.3rem 0; border-bottom: 1px solid #ddd; }";
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,174 @@
+use crate::config::Config;
+use crate::exclusions::PromptFilter;
+use crate::context::CommitContext;
+use crate::git;
+use crate::llm;
+use crate::llm_providers::{get_provider_metadata, LLMProviderType};
+use crate::token_optimizer::TokenOptimizer;
+use anyhow::{anyhow, Context, Result};
+use git2::{Repository, RevertOptions};
+use std::fs;
+use std::path::Path;
+
+/// What happened when reverting a commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevertOutcome {
+    /// The revert was committed; holds the new commit's hash
+    Committed(String),
+    /// The revert left conflicts; holds the conflicted paths
+    Conflicts(Vec<String>),
+}
+
+pub struct RevertMessageGenerator;
+
+impl RevertMessageGenerator {
+    /// Generate a message for a commit that reverts the given one
+    pub async fn generate(repo_path: &Path, reference: &str, config: &Config) -> Result<String> {
+        let commit_id = git::resolve_commit_id(repo_path, reference)
+            .map_err(|_| anyhow!("Commit '{}' not found", reference))?;
+        let original_message = git::get_commit_message(repo_path, &commit_id)?;
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let mut context = git::get_commit_info(
+            repo_path,
+            &commit_id,
+            &config.recent_commits,
+            &PromptFilter::from_config(config),
+        )?;
+        let optimizer = TokenOptimizer::new(get_provider_metadata(&provider_type).default_token_limit);
+        optimizer.optimize_context(&mut context);
+
+        let mut system_prompt = create_revert_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let user_prompt = create_revert_user_prompt(&context, &commit_id, &original_message);
+
+        let message =
+            llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+                .await
+                .context("Failed to generate revert message")?;
+
+        Ok(ensure_revert_trailer(&message, &commit_id))
+    }
+}
+
+pub fn create_revert_system_prompt() -> String {
+    String::from(
+        "You are an AI assistant specialized in writing Git commit messages for reverts. \
+        Your task is to explain what a revert undoes, so readers of the history understand the effect without opening the original commit.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Start with the subject line Revert \"<original subject>\", using the original subject exactly.
+        2. Separate subject from body with a blank line, and wrap the body at 72 characters.
+        3. In the body, describe in plain words what behavior or code is being removed or restored.
+        4. Carry over the context from the original message that explains what the commit was for, such as the problem it addressed or the issue it referenced.
+        5. Mention notable side effects of undoing it, such as removed APIs or restored defaults, when the changes show them.
+        6. Do not invent a reason for the revert; if a reason is given in the instructions, state it.
+        7. End the body with the line: This reverts commit <full hash>.
+        8. NO YAPPING!
+
+        Generate only the commit message, without any explanations, quotes, or code fences.",
+    )
+}
+
+pub fn create_revert_user_prompt(
+    context: &CommitContext,
+    commit_id: &str,
+    original_message: &str,
+) -> String {
+    let mut prompt = String::from("Based on the following commit, write the message for a commit that reverts it:\n\n");
+
+    prompt.push_str(&format!("Commit: {}\n\n", commit_id));
+    prompt.push_str(&format!(
+        "Original message:\n{}\n\n",
+        original_message.trim()
+    ));
+
+    prompt.push_str("Changes being undone:\n");
+    for file in &context.staged_files {
+        prompt.push_str(&format!("- {} ({})\n", file.path, file.change_type));
+        for analysis in &file.analysis {
+            prompt.push_str(&format!("  - {}\n", analysis));
+        }
+    }
+
+    prompt.push_str("\nDiffs of the original commit:\n");
+    for file in &context.staged_files {
+        prompt.push_str(&format!("File: {}\n{}\n", file.path, file.diff));
+    }
+
+    prompt
+}
+
+/// Make sure the message ends with Git's "This reverts commit <hash>." line
+pub fn ensure_revert_trailer(message: &str, commit_id: &str) -> String {
+    let trailer = format!("This reverts commit {}.", commit_id);
+    let message = message.trim();
+    let kept: Vec<&str> = message
+        .lines()
+        .filter(|line| !line.trim().starts_with("This reverts commit"))
+        .collect();
+    format!("{}\n\n{}", kept.join("\n").trim_end(), trailer)
+}
+
+/// Revert a commit in the index and working tree, and commit the result with the given message
+///
+/// For a merge commit, `mainline` is the parent number, starting at 1, whose side is kept.
+/// When the revert conflicts, the message is left in `MERGE_MSG` for `git commit` to pick up.
+pub fn revert_commit(
+    repo_path: &Path,
+    reference: &str,
+    mainline: Option<u32>,
+    message: &str,
+) -> Result<RevertOutcome> {
+    let repo = Repository::open(repo_path)?;
+    let commit = repo
+        .revparse_single(reference)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|_| anyhow!("Commit '{}' not found", reference))?;
+    if commit.parent_count() > 1 && mainline.is_none() {
+        return Err(anyhow!(
+            "Commit '{}' is a merge; pass --mainline with the parent number to keep",
+            reference
+        ));
+    }
+    if git::has_staged_changes(repo_path)? {
+        return Err(anyhow!(
+            "The index has staged changes; commit or unstage them before reverting"
+        ));
+    }
+
+    let mut options = RevertOptions::new();
+    if let Some(mainline) = mainline {
+        options.mainline(mainline);
+    }
+    repo.revert(&commit, Some(&mut options))
+        .map_err(|e| anyhow!("Failed to revert '{}': {}", reference, e.message()))?;
+
+    let index = repo.index()?;
+    if index.has_conflicts() {
+        let conflicts = index
+            .conflicts()?
+            .filter_map(|conflict| conflict.ok())
+            .filter_map(|conflict| conflict.our.or(conflict.their))
+            .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+            .collect();
+        fs::write(repo.path().join("MERGE_MSG"), format!("{}\n", message.trim_end()))?;
+        return Ok(RevertOutcome::Conflicts(conflicts));
+    }
+
+    git::commit(repo_path, message)?;
+    repo.cleanup_state()?;
+    Ok(RevertOutcome::Committed(git::resolve_commit_id(repo_path, "HEAD")?))
+}
@@ -0,0 +1,214 @@
+use crate::commit_message::move_gitmoji_to_body;
+use crate::config::Config;
+use crate::conventional::{self, ConventionalMode};
+use crate::exclusions::PromptFilter;
+use crate::git;
+use crate::llm;
+use crate::llm_providers::{get_provider_metadata, LLMProviderType};
+use crate::prompt;
+use crate::token_optimizer::TokenOptimizer;
+use anyhow::{anyhow, Result};
+use git2::{BranchType, Oid, Repository};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A commit and the message that will replace its current one
+#[derive(Debug, Clone)]
+pub struct RewriteEntry {
+    pub commit_id: String,
+    pub original: String,
+    pub rewritten: String,
+}
+
+/// Collect the commits in `from..to`, oldest first.
+/// Fails if the range contains merge commits, which cannot be rewritten automatically.
+pub fn commits_to_rewrite(repo_path: &Path, from: &str, to: &str) -> Result<Vec<String>> {
+    let repo = Repository::open(repo_path)?;
+    let head = repo.head()?.peel_to_commit()?.id();
+    let to_id = repo.revparse_single(to)?.peel_to_commit()?.id();
+    if to_id != head && !repo.graph_descendant_of(head, to_id)? {
+        return Err(anyhow!("'{}' is not part of the current branch", to));
+    }
+
+    let range = walk_range(&repo, from, to)?;
+    if range.is_empty() {
+        return Err(anyhow!("No commits found between '{}' and '{}'", from, to));
+    }
+    Ok(range.iter().map(Oid::to_string).collect())
+}
+
+/// Fail if any commit in the range is already on a remote-tracking branch
+pub fn check_not_pushed(repo_path: &Path, commits: &[String]) -> Result<()> {
+    let repo = Repository::open(repo_path)?;
+    for branch in repo.branches(Some(BranchType::Remote))? {
+        let (branch, _) = branch?;
+        let Some(remote_tip) = branch.get().target() else {
+            continue;
+        };
+        for commit in commits {
+            let oid = Oid::from_str(commit)?;
+            if remote_tip == oid || repo.graph_descendant_of(remote_tip, oid)? {
+                return Err(anyhow!(
+                    "Commit {} is already on '{}'. Rewriting pushed history would require a force push; use --force to rewrite anyway",
+                    &commit[..7],
+                    branch.name()?.unwrap_or("a remote branch")
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Generate an improved message for each commit
+///
+/// In Conventional Commits mode, each message gets the scope inferred from its commit's changes,
+/// and a message in the wrong format is sent back to the model once to be corrected.
+pub async fn generate_rewrites(
+    repo_path: &Path,
+    commits: &[String],
+    config: &Config,
+    provider_type: &LLMProviderType,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<Vec<RewriteEntry>> {
+    let instructions = config.get_effective_instructions();
+    let base_prompt =
+        prompt::create_system_prompt(config.use_gitmoji, &instructions, config.language());
+    let optimizer = TokenOptimizer::new(get_provider_metadata(provider_type).default_token_limit);
+
+    let mut entries = Vec::with_capacity(commits.len());
+    for (i, commit) in commits.iter().enumerate() {
+        on_progress(i + 1, commits.len());
+
+        let mut context = git::get_commit_info(
+            repo_path,
+            commit,
+            &config.recent_commits,
+            &PromptFilter::from_config(config),
+        )?;
+        optimizer.optimize_context(&mut context);
+        let original = git::get_commit_message(repo_path, commit)?;
+        let user_prompt = prompt::create_amend_user_prompt(&context, &original)?;
+
+        let conventional = config
+            .conventional
+            .enabled
+            .then(|| ConventionalMode::for_changes(&context.staged_files, &config.conventional));
+        let system_prompt = match &conventional {
+            Some(mode) => format!("{}\n\n{}", base_prompt, mode.instructions()),
+            None => base_prompt.clone(),
+        };
+
+        let mut message =
+            llm::get_refined_message(config, provider_type, &system_prompt, &user_prompt, None)
+                .await?;
+        if let Some(mode) = &conventional {
+            if let Some(problem) = mode.check(&message) {
+                let correction = conventional::correction_prompt(&user_prompt, &message, &problem);
+                message = llm::get_refined_message(
+                    config,
+                    provider_type,
+                    &system_prompt,
+                    &correction,
+                    None,
+                )
+                .await?;
+            }
+            message = mode.apply_scope(&message);
+        }
+        let rewritten = if config.use_gitmoji && config.gitmoji_in_body {
+            move_gitmoji_to_body(&message)
+        } else {
+            message
+        };
+
+        entries.push(RewriteEntry {
+            commit_id: commit.clone(),
+            original,
+            rewritten,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Recreate the commits after `from` with the new messages and move the current branch to the result.
+/// Trees are unchanged, so this is a rebase that can never conflict. Returns the new HEAD.
+pub fn apply_rewrites(repo_path: &Path, from: &str, entries: &[RewriteEntry]) -> Result<String> {
+    let repo = Repository::open(repo_path)?;
+    let messages: HashMap<Oid, &str> = entries
+        .iter()
+        .map(|entry| -> Result<(Oid, &str)> {
+            Ok((Oid::from_str(&entry.commit_id)?, entry.rewritten.as_str()))
+        })
+        .collect::<Result<_>>()?;
+
+    // Replay everything up to HEAD, so commits after the rewritten range are kept
+    let mut rewritten: HashMap<Oid, Oid> = HashMap::new();
+    let mut new_head = None;
+    for oid in walk_range(&repo, from, "HEAD")? {
+        let commit = repo.find_commit(oid)?;
+        let parents = commit
+            .parent_ids()
+            .map(|id| repo.find_commit(*rewritten.get(&id).unwrap_or(&id)))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        let message = match messages.get(&oid) {
+            Some(message) => message.to_string(),
+            None => commit.message().unwrap_or_default().to_string(),
+        };
+
+        let new_oid = repo.commit(
+            None,
+            &commit.author(),
+            &commit.committer(),
+            &message,
+            &commit.tree()?,
+            &parent_refs,
+        )?;
+        rewritten.insert(oid, new_oid);
+        new_head = Some(new_oid);
+    }
+
+    let new_head = new_head.ok_or_else(|| anyhow!("Nothing to rewrite"))?;
+    let head = repo.head()?;
+    if head.is_branch() {
+        let name = head
+            .name()
+            .ok_or_else(|| anyhow!("Current branch name is not valid UTF-8"))?;
+        repo.reference(name, new_head, true, "git-iris rewrite: update commit messages")?;
+    } else {
+        repo.set_head_detached(new_head)?;
+    }
+
+    Ok(new_head.to_string())
+}
+
+/// Linear list of commits in `from..to`, oldest first
+fn walk_range(repo: &Repository, from: &str, to: &str) -> Result<Vec<Oid>> {
+    let from_commit = repo
+        .revparse_single(from)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|_| anyhow!("Reference '{}' not found", from))?;
+    let to_commit = repo
+        .revparse_single(to)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|_| anyhow!("Reference '{}' not found", to))?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(to_commit.id())?;
+    revwalk.hide(from_commit.id())?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+
+    let mut oids = Vec::new();
+    for oid in revwalk {
+        let oid = oid?;
+        if repo.find_commit(oid)?.parent_count() > 1 {
+            return Err(anyhow!(
+                "Commit {} is a merge; rewriting ranges with merges is not supported",
+                &oid.to_string()[..7]
+            ));
+        }
+        oids.push(oid);
+    }
+    Ok(oids)
+}
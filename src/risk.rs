@@ -0,0 +1,176 @@
+use crate::config::Config;
+use git2::Repository;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Git config key that overrides the enabled risk labels for a single repository
+const REPO_RISK_LABELS_KEY: &str = "iris.riskLabels";
+
+/// Categories of risky changes that deserve extra attention from reviewers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RiskLabel {
+    SchemaMigration,
+    AuthChange,
+    DependencyMajorBump,
+    ConcurrencySensitive,
+}
+
+impl RiskLabel {
+    pub fn all() -> Vec<RiskLabel> {
+        vec![
+            RiskLabel::SchemaMigration,
+            RiskLabel::AuthChange,
+            RiskLabel::DependencyMajorBump,
+            RiskLabel::ConcurrencySensitive,
+        ]
+    }
+
+    /// Short key used in configuration
+    pub fn key(&self) -> &'static str {
+        match self {
+            RiskLabel::SchemaMigration => "schema-migration",
+            RiskLabel::AuthChange => "auth-change",
+            RiskLabel::DependencyMajorBump => "dependency-major-bump",
+            RiskLabel::ConcurrencySensitive => "concurrency",
+        }
+    }
+
+    /// Badge shown in terminal output
+    pub fn badge(&self) -> &'static str {
+        match self {
+            RiskLabel::SchemaMigration => "🗃️ schema migration",
+            RiskLabel::AuthChange => "🔐 auth change",
+            RiskLabel::DependencyMajorBump => "⬆️ major dependency bump",
+            RiskLabel::ConcurrencySensitive => "🧵 concurrency-sensitive",
+        }
+    }
+}
+
+impl fmt::Display for RiskLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key())
+    }
+}
+
+impl FromStr for RiskLabel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RiskLabel::all()
+            .into_iter()
+            .find(|label| label.key() == s.trim().to_lowercase())
+            .ok_or_else(|| anyhow::anyhow!("Unknown risk label: {}", s))
+    }
+}
+
+/// Derive risk labels for a single file from its path, diff, and analyzer results
+pub fn detect_risks(path: &str, diff: &str, analysis: &[String]) -> Vec<RiskLabel> {
+    let mut labels = Vec::new();
+
+    if is_schema_migration(path, diff) {
+        labels.push(RiskLabel::SchemaMigration);
+    }
+    if is_auth_change(path, analysis) {
+        labels.push(RiskLabel::AuthChange);
+    }
+    if has_major_dependency_bump(path, diff) {
+        labels.push(RiskLabel::DependencyMajorBump);
+    }
+    if is_concurrency_sensitive(diff) {
+        labels.push(RiskLabel::ConcurrencySensitive);
+    }
+
+    labels
+}
+
+/// Get the risk labels enabled for a repository
+///
+/// A repository can override the global `risk_labels` setting with
+/// `git config iris.riskLabels "schema-migration,auth-change"`, or disable
+/// labels entirely with `git config iris.riskLabels none`.
+pub fn enabled_risk_labels(repo_path: &Path, config: &Config) -> Vec<RiskLabel> {
+    let repo_setting = Repository::open(repo_path)
+        .and_then(|repo| repo.config())
+        .and_then(|git_config| git_config.get_string(REPO_RISK_LABELS_KEY))
+        .ok();
+
+    let keys: Vec<String> = match repo_setting {
+        Some(setting) => setting.split(',').map(|s| s.trim().to_string()).collect(),
+        None => config.risk_labels.clone(),
+    };
+
+    keys.iter()
+        .filter_map(|key| RiskLabel::from_str(key).ok())
+        .collect()
+}
+
+fn is_schema_migration(path: &str, diff: &str) -> bool {
+    let path = path.to_lowercase();
+    if path.contains("migration") || path.contains("migrate/") || path.ends_with("schema.rb") {
+        return true;
+    }
+
+    let re = Regex::new(r"(?mi)^[+-]\s*(CREATE|ALTER|DROP)\s+(TABLE|INDEX|COLUMN)").unwrap();
+    path.ends_with(".sql") && re.is_match(diff)
+}
+
+fn is_auth_change(path: &str, analysis: &[String]) -> bool {
+    // `auth` must not be followed by `or`, so `author` doesn't count but `authorize` does
+    let re = Regex::new(
+        r"(?i)(?:^|[^a-z])auth(?:[^o]|or[iy]|$)|oauth|jwt|login|logout|password|passwd|credential|session|permission|access_?token|api_?key",
+    )
+    .unwrap();
+    re.is_match(path) || analysis.iter().any(|a| re.is_match(a))
+}
+
+fn has_major_dependency_bump(path: &str, diff: &str) -> bool {
+    let manifests = [
+        "Cargo.toml",
+        "package.json",
+        "pyproject.toml",
+        "requirements.txt",
+        "go.mod",
+        "Gemfile",
+        "composer.json",
+    ];
+    if !manifests.iter().any(|m| path.ends_with(m)) {
+        return false;
+    }
+
+    // Matches `name = "1.2"`, `"name": "^1.2"`, `name==1.2`, and `name v1.2` style entries
+    let re = Regex::new(
+        r#"(?m)^([+-])\s*"?([\w@/.\-]+)"?\s*(?:=|:|==|>=|~=|\s)\s*(?:\{[^}]*version\s*=\s*)?["'^~>=v ]*(\d+)\."#,
+    )
+    .unwrap();
+
+    let mut removed: HashMap<String, u64> = HashMap::new();
+    let mut added: HashMap<String, u64> = HashMap::new();
+    for cap in re.captures_iter(diff) {
+        let Ok(major) = cap[3].parse::<u64>() else {
+            continue;
+        };
+        let target = if &cap[1] == "-" {
+            &mut removed
+        } else {
+            &mut added
+        };
+        target.insert(cap[2].to_string(), major);
+    }
+
+    added.iter().any(|(name, new_major)| {
+        removed
+            .get(name)
+            .is_some_and(|old_major| new_major > old_major)
+    })
+}
+
+fn is_concurrency_sensitive(diff: &str) -> bool {
+    let re = Regex::new(
+        r"(?m)^[+-].*\b(Mutex|RwLock|Atomic\w*|Condvar|thread::spawn|tokio::spawn|spawn_blocking|synchronized|threading|asyncio\.Lock|sync\.WaitGroup|go func|Semaphore)\b",
+    )
+    .unwrap();
+    re.is_match(diff)
+}
@@ -0,0 +1,224 @@
+use crate::change_analyzer::AnalyzedChange;
+use crate::config::Config;
+use crate::exclusions::PromptFilter;
+use crate::git;
+use crate::risk::RiskLabel;
+use anyhow::{anyhow, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+lazy_static! {
+    // Conventional commit subject: type(scope)!: description
+    static ref CONVENTIONAL_SUBJECT: Regex =
+        Regex::new(r"^(?P<type>[a-zA-Z]+)(\([^)]*\))?(?P<breaking>!)?:\s").unwrap();
+    static ref BREAKING_FOOTER: Regex = Regex::new(r"(?m)^BREAKING[ -]CHANGE:").unwrap();
+    // Subjects of non-conventional commits that introduce new functionality
+    static ref FEATURE_SUBJECT: Regex =
+        Regex::new(r"(?i)^(add|adds|added|introduce|introduces|implement|implements|support)\b").unwrap();
+}
+
+/// The kind of version bump a release needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bump::Patch => write!(f, "patch"),
+            Bump::Minor => write!(f, "minor"),
+            Bump::Major => write!(f, "major"),
+        }
+    }
+}
+
+impl FromStr for Bump {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "patch" => Ok(Bump::Patch),
+            "minor" => Ok(Bump::Minor),
+            "major" => Ok(Bump::Major),
+            _ => Err(anyhow!("Invalid bump: {}. Use major, minor, or patch", s)),
+        }
+    }
+}
+
+/// A suggested version bump with the reasons behind it
+#[derive(Debug, Clone, Serialize)]
+pub struct SemverSuggestion {
+    pub bump: Bump,
+    pub current_version: Option<String>,
+    pub next_version: Option<String>,
+    pub commits: usize,
+    pub justification: Vec<String>,
+}
+
+/// Suggest the next version from the commits since the latest tag reachable from `to`
+pub fn suggest_next_version(
+    repo_path: &Path,
+    to: &str,
+    config: &Config,
+) -> Result<SemverSuggestion> {
+    let latest_tag = git::get_latest_tag(repo_path, to)?;
+    let filter = PromptFilter::from_config(config);
+    let changes = match &latest_tag {
+        Some(tag) => git::get_commits_between(repo_path, tag, to, &filter)?,
+        None => git::get_all_commits(repo_path, to, &filter)?,
+    };
+    Ok(suggest_bump(latest_tag.as_deref(), &changes))
+}
+
+/// Classify the changes and derive the bump and next version from them
+pub fn suggest_bump(current_tag: Option<&str>, changes: &[AnalyzedChange]) -> SemverSuggestion {
+    let mut bump = Bump::Patch;
+    let mut breaking = Vec::new();
+    let mut features = Vec::new();
+    let mut fixes = 0;
+    let mut risks: Vec<RiskLabel> = Vec::new();
+
+    for change in changes {
+        let subject = change.commit_message.lines().next().unwrap_or("").trim();
+        let short_hash = &change.commit_hash[..7.min(change.commit_hash.len())];
+
+        match classify_commit(&change.commit_message) {
+            Bump::Major => breaking.push(format!("{} {}", short_hash, subject)),
+            Bump::Minor => features.push(format!("{} {}", short_hash, subject)),
+            Bump::Patch => fixes += 1,
+        }
+
+        for label in change.file_changes.iter().flat_map(|fc| &fc.risk_labels) {
+            if !risks.contains(label) {
+                risks.push(*label);
+            }
+        }
+    }
+
+    let mut justification = Vec::new();
+    if !breaking.is_empty() {
+        bump = Bump::Major;
+        justification.push(format!(
+            "{} breaking change(s): {}",
+            breaking.len(),
+            breaking.join("; ")
+        ));
+    }
+    if !features.is_empty() {
+        bump = bump.max(Bump::Minor);
+        justification.push(format!(
+            "{} new feature(s): {}",
+            features.len(),
+            features.join("; ")
+        ));
+    }
+    if fixes > 0 {
+        justification.push(format!("{} fix(es) or other change(s)", fixes));
+    }
+    if changes.is_empty() {
+        justification.push("No commits since the last release".to_string());
+    }
+    for label in &risks {
+        match label {
+            RiskLabel::SchemaMigration | RiskLabel::DependencyMajorBump => justification.push(
+                format!("Includes a {}; check whether it breaks users", label.badge()),
+            ),
+            _ => {}
+        }
+    }
+
+    let current_version = current_tag.and_then(parse_version);
+    let next_version = current_version.map(|version| apply_bump(version, bump));
+    if current_version.is_some_and(|(major, _, _)| major == 0) && bump == Bump::Major {
+        justification.push("Pre-1.0 release: breaking changes bump the minor version".to_string());
+    }
+
+    SemverSuggestion {
+        bump,
+        current_version: current_version.map(format_version),
+        next_version: next_version.map(format_version),
+        commits: changes.len(),
+        justification,
+    }
+}
+
+/// Classify a single commit message by the bump it requires
+pub fn classify_commit(message: &str) -> Bump {
+    let subject = message.lines().next().unwrap_or("").trim();
+
+    if BREAKING_FOOTER.is_match(message) {
+        return Bump::Major;
+    }
+
+    let subject = strip_leading_gitmoji(subject);
+
+    if let Some(captures) = CONVENTIONAL_SUBJECT.captures(subject) {
+        if captures.name("breaking").is_some() {
+            return Bump::Major;
+        }
+        return match captures["type"].to_lowercase().as_str() {
+            "feat" => Bump::Minor,
+            _ => Bump::Patch,
+        };
+    }
+
+    if FEATURE_SUBJECT.is_match(subject) {
+        Bump::Minor
+    } else {
+        Bump::Patch
+    }
+}
+
+/// The conventional commit type of a subject, such as `feat` or `fix`, in lowercase
+pub fn conventional_type(subject: &str) -> Option<String> {
+    CONVENTIONAL_SUBJECT
+        .captures(strip_leading_gitmoji(subject.trim()))
+        .map(|captures| captures["type"].to_lowercase())
+}
+
+/// Strip a leading gitmoji so "✨ feat: ..." is still recognized
+fn strip_leading_gitmoji(subject: &str) -> &str {
+    subject
+        .split_once(' ')
+        .filter(|(first, _)| !first.is_empty() && !first.chars().any(|c| c.is_ascii()))
+        .map(|(_, rest)| rest)
+        .unwrap_or(subject)
+}
+
+/// Parse a tag such as `v1.2.3` or `1.2.3-rc.1` into its numeric components
+pub fn parse_version(tag: &str) -> Option<(u64, u64, u64)> {
+    let version = tag.trim().trim_start_matches(['v', 'V']);
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    Some((major, minor, patch))
+}
+
+/// Apply a bump to a version or tag such as `v1.2.3`, returning the plain next version
+pub fn next_version(current: &str, bump: Bump) -> Option<String> {
+    parse_version(current).map(|version| format_version(apply_bump(version, bump)))
+}
+
+fn apply_bump((major, minor, patch): (u64, u64, u64), bump: Bump) -> (u64, u64, u64) {
+    match bump {
+        // Before 1.0, breaking changes bump the minor version
+        Bump::Major if major == 0 => (0, minor + 1, 0),
+        Bump::Major => (major + 1, 0, 0),
+        Bump::Minor => (major, minor + 1, 0),
+        Bump::Patch => (major, minor, patch + 1),
+    }
+}
+
+fn format_version((major, minor, patch): (u64, u64, u64)) -> String {
+    format!("{}.{}.{}", major, minor, patch)
+}
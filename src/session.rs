@@ -0,0 +1,157 @@
+use crate::log_debug;
+use anyhow::Result;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+const SESSION_PREFIX: &str = "release-notes-";
+const LAST_GENERATION_FILE: &str = "last-generation.json";
+
+/// Intermediate results of a release notes run, saved so an interrupted run can pick up where it stopped
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ReleaseNotesSession {
+    /// Identifies the inputs the saved results were generated from
+    pub key: String,
+    pub readme_summary: Option<String>,
+    pub changelog: Option<String>,
+}
+
+impl ReleaseNotesSession {
+    /// Load the saved session for `key`, or start a new one
+    pub fn load_or_new(repo_path: &Path, key: &str) -> Self {
+        let session = session_path(repo_path, key)
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<ReleaseNotesSession>(&content).ok())
+            .filter(|session| session.key == key);
+
+        match session {
+            Some(session) => {
+                log_debug!("Resuming release notes session: {}", key);
+                session
+            }
+            None => ReleaseNotesSession {
+                key: key.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Whether any intermediate results were restored from a previous run
+    pub fn is_resumed(&self) -> bool {
+        self.readme_summary.is_some() || self.changelog.is_some()
+    }
+
+    /// Whether the repository has saved progress from an interrupted run
+    pub fn has_saved(repo_path: &Path) -> bool {
+        sessions_dir(repo_path)
+            .ok()
+            .and_then(|dir| fs::read_dir(dir).ok())
+            .is_some_and(|mut entries| {
+                entries.any(|entry| {
+                    entry.is_ok_and(|e| e.file_name().to_string_lossy().starts_with(SESSION_PREFIX))
+                })
+            })
+    }
+
+    pub fn save(&self, repo_path: &Path) -> Result<()> {
+        let path = session_path(repo_path, &self.key)?;
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        log_debug!("Release notes session saved: {}", self.key);
+        Ok(())
+    }
+
+    /// Remove the session once the run has completed
+    pub fn clear(&self, repo_path: &Path) -> Result<()> {
+        let path = session_path(repo_path, &self.key)?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Remove every saved release notes session for the repository
+    pub fn discard_all(repo_path: &Path) -> Result<()> {
+        let dir = sessions_dir(repo_path)?;
+        for entry in fs::read_dir(dir)?.filter_map(|e| e.ok()) {
+            if entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(SESSION_PREFIX)
+            {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The most recent commit message generated for a repository, kept so `gen --refine` can revise it
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LastGeneration {
+    pub message: String,
+    /// Feedback the message was refined with, oldest first
+    pub feedback: Vec<String>,
+    /// Other candidates that were shown alongside the message and not chosen
+    #[serde(default)]
+    pub rejected: Vec<String>,
+}
+
+impl LastGeneration {
+    /// Load the last generation for the repository, if there is one
+    pub fn load(repo_path: &Path) -> Option<Self> {
+        last_generation_path(repo_path)
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    pub fn save(&self, repo_path: &Path) -> Result<()> {
+        let path = last_generation_path(repo_path)?;
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        log_debug!("Last generation saved");
+        Ok(())
+    }
+
+    /// Feedback already applied to `message`, when it is the cached generation
+    pub fn feedback_for(repo_path: &Path, message: &str) -> Vec<String> {
+        Self::load(repo_path)
+            .filter(|generation| generation.message == message)
+            .map(|generation| generation.feedback)
+            .unwrap_or_default()
+    }
+
+    /// Candidates passed over in favor of `message`, when it is the cached generation
+    pub fn rejected_for(repo_path: &Path, message: &str) -> Vec<String> {
+        Self::load(repo_path)
+            .filter(|generation| generation.message == message)
+            .map(|generation| generation.rejected)
+            .unwrap_or_default()
+    }
+}
+
+/// Build a session key from everything that affects the generated output
+pub fn session_key(parts: &[&str]) -> String {
+    let mut hasher = DefaultHasher::new();
+    parts.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Sessions live inside the repository's `.git` directory so they never show up as changes
+fn sessions_dir(repo_path: &Path) -> Result<PathBuf> {
+    let repo = Repository::open(repo_path)?;
+    let dir = repo.path().join("git-iris").join("sessions");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn session_path(repo_path: &Path, key: &str) -> Result<PathBuf> {
+    Ok(sessions_dir(repo_path)?.join(format!("{}{}.json", SESSION_PREFIX, key)))
+}
+
+fn last_generation_path(repo_path: &Path) -> Result<PathBuf> {
+    Ok(sessions_dir(repo_path)?.join(LAST_GENERATION_FILE))
+}
@@ -0,0 +1,446 @@
+use crate::commit_message::move_gitmoji_to_body;
+use crate::config::Config;
+use crate::git;
+use crate::llm;
+use crate::llm_providers::{get_provider_metadata, LLMProviderType};
+use crate::prompt;
+use crate::token_optimizer::TokenOptimizer;
+use anyhow::{anyhow, Context, Result};
+use git2::{ApplyLocation, ApplyOptions, Diff, DiffOptions, Patch, Repository};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Title of the group that collects hunks the model did not assign to any group
+pub const REMAINING_GROUP_TITLE: &str = "Remaining changes";
+
+/// A single staged hunk, or a whole file when its change has no hunks (binary files, mode changes)
+#[derive(Debug, Clone)]
+pub struct StagedHunk {
+    /// Number used to refer to the hunk in the prompt and the plan, starting at 1
+    pub id: usize,
+    pub path: String,
+    /// Position of the hunk within its file, or `None` for a whole-file change
+    pub file_hunk_index: Option<usize>,
+    /// First line the hunk covers in the HEAD version of the file
+    pub old_start: usize,
+    pub header: String,
+    pub content: String,
+}
+
+/// A proposed commit: a set of hunks that belong together
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitGroup {
+    pub title: String,
+    pub rationale: String,
+    pub hunk_ids: Vec<usize>,
+}
+
+/// Proposed division of the staged changes into separate commits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitPlan {
+    pub groups: Vec<SplitGroup>,
+}
+
+/// Diff between HEAD and the index, with binary contents so the patch can be reapplied
+fn staged_diff(repo: &Repository) -> Result<Diff<'_>> {
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree()?),
+        Err(_) => None,
+    };
+    let mut diff_options = DiffOptions::new();
+    diff_options.show_binary(true);
+    Ok(repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut diff_options))?)
+}
+
+/// Collect the staged hunks of every file, numbered in diff order
+pub fn collect_staged_hunks(repo_path: &Path) -> Result<Vec<StagedHunk>> {
+    let repo = Repository::open(repo_path)?;
+    let diff = staged_diff(&repo)?;
+
+    let mut hunks = Vec::new();
+    for delta_index in 0..diff.deltas().len() {
+        let Some(patch) = Patch::from_diff(&diff, delta_index)? else {
+            continue;
+        };
+        let delta = patch.delta();
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        if patch.num_hunks() == 0 {
+            hunks.push(StagedHunk {
+                id: hunks.len() + 1,
+                path,
+                file_hunk_index: None,
+                old_start: 0,
+                header: format!("[{:?} file]", delta.status()),
+                content: String::new(),
+            });
+            continue;
+        }
+
+        for hunk_index in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(hunk_index)?;
+            let mut content = String::new();
+            for line_index in 0..line_count {
+                let line = patch.line_in_hunk(hunk_index, line_index)?;
+                if matches!(line.origin(), '+' | '-' | ' ') {
+                    content.push(line.origin());
+                }
+                content.push_str(&String::from_utf8_lossy(line.content()));
+            }
+            hunks.push(StagedHunk {
+                id: hunks.len() + 1,
+                path: path.clone(),
+                file_hunk_index: Some(hunk_index),
+                old_start: hunk.old_start() as usize,
+                header: String::from_utf8_lossy(hunk.header()).trim_end().to_string(),
+                content,
+            });
+        }
+    }
+
+    Ok(hunks)
+}
+
+pub struct SplitPlanner;
+
+impl SplitPlanner {
+    /// Ask the model how to divide the staged changes into logically cohesive commits
+    pub async fn plan(repo_path: &Path, config: &Config) -> Result<(SplitPlan, Vec<StagedHunk>)> {
+        let hunks = collect_staged_hunks(repo_path)?;
+        if hunks.is_empty() {
+            return Err(anyhow!("No staged changes to split"));
+        }
+
+        let mut system_prompt = create_split_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        // Keep large diffs within the provider's context window
+        let token_limit = config
+            .providers
+            .get(&config.default_provider)
+            .and_then(|provider| provider.token_limit)
+            .unwrap_or_else(|| get_provider_metadata(&provider_type).default_token_limit);
+        let optimizer = TokenOptimizer::new(token_limit);
+        let per_hunk_limit = ((token_limit / 2) / hunks.len()).max(1);
+        let truncated: Vec<StagedHunk> = hunks
+            .iter()
+            .cloned()
+            .map(|mut hunk| {
+                hunk.content = optimizer.truncate_string(&hunk.content, per_hunk_limit);
+                hunk
+            })
+            .collect();
+
+        let user_prompt = create_split_user_prompt(&truncated);
+
+        let response =
+            llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+                .await
+                .context("Failed to generate split suggestions")?;
+
+        let plan = parse_split_plan(&response, &hunks)?;
+        Ok((plan, hunks))
+    }
+
+    /// Generate a commit message for the changes currently staged for one group of the plan
+    pub async fn generate_message(
+        repo_path: &Path,
+        group: &SplitGroup,
+        config: &Config,
+    ) -> Result<String> {
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+        let instructions = config.get_effective_instructions();
+        let system_prompt =
+            prompt::create_system_prompt(config.use_gitmoji, &instructions, config.language());
+
+        let mut context = git::get_git_info(repo_path, config)?;
+        let optimizer = TokenOptimizer::new(get_provider_metadata(&provider_type).default_token_limit);
+        optimizer.optimize_context(&mut context);
+
+        let mut user_prompt = prompt::create_user_prompt(&context)?;
+        user_prompt.push_str(&format!(
+            "\n\nThese changes are one of several commits split from a larger set of changes. \
+            It was planned as \"{}\": {}\n\
+            Describe only the changes above.",
+            group.title, group.rationale
+        ));
+
+        let message =
+            llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+                .await
+                .context("Failed to generate a commit message for the group")?;
+        Ok(if config.use_gitmoji && config.gitmoji_in_body {
+            move_gitmoji_to_body(&message)
+        } else {
+            message
+        })
+    }
+}
+
+/// The staged changes as they were before splitting, so groups of hunks can be staged one at a time
+pub struct StagedPatch {
+    patch: Vec<u8>,
+}
+
+impl StagedPatch {
+    /// Capture the difference between HEAD and the index
+    pub fn capture(repo_path: &Path) -> Result<Self> {
+        let repo = Repository::open(repo_path)?;
+        let diff = staged_diff(&repo)?;
+        let mut patch = Vec::new();
+        diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                patch.push(line.origin() as u8);
+            }
+            patch.extend_from_slice(line.content());
+            true
+        })?;
+        Ok(StagedPatch { patch })
+    }
+
+    /// Add the given hunks to the index, on top of whatever it currently holds
+    pub fn stage(&self, repo_path: &Path, hunks: &[StagedHunk], hunk_ids: &[usize]) -> Result<()> {
+        if hunk_ids.is_empty() {
+            return Ok(());
+        }
+        let selected: Vec<&StagedHunk> = hunks
+            .iter()
+            .filter(|hunk| hunk_ids.contains(&hunk.id))
+            .collect();
+
+        let repo = Repository::open(repo_path)?;
+        let diff = Diff::from_buffer(&self.patch)?;
+
+        // Hunk callbacks only see the hunk, so track which file and position they belong to
+        let current: RefCell<(String, usize)> = RefCell::new((String::new(), 0));
+        let mut options = ApplyOptions::new();
+        options.delta_callback(|delta| {
+            let path = delta
+                .and_then(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let wanted = selected.iter().any(|hunk| hunk.path == path);
+            *current.borrow_mut() = (path, 0);
+            wanted
+        });
+        options.hunk_callback(|_hunk| {
+            let mut current = current.borrow_mut();
+            let index = current.1;
+            current.1 += 1;
+            selected
+                .iter()
+                .any(|hunk| hunk.path == current.0 && hunk.file_hunk_index == Some(index))
+        });
+
+        repo.apply(&diff, ApplyLocation::Index, Some(&mut options))
+            .map_err(|e| anyhow!("Failed to stage the hunks: {}", e.message()))?;
+        Ok(())
+    }
+}
+
+/// Reset the index to HEAD, keeping all changes in the working tree, like `git reset`
+pub fn unstage_all(repo_path: &Path) -> Result<()> {
+    let repo = Repository::open(repo_path)?;
+    let mut index = repo.index()?;
+    match repo.head() {
+        Ok(head) => index.read_tree(&head.peel_to_tree()?)?,
+        Err(_) => index.clear()?,
+    }
+    index.write()?;
+    Ok(())
+}
+
+pub fn create_split_system_prompt() -> String {
+    String::from(
+        "You are an AI assistant specialized in organizing Git history. \
+        Your task is to divide the staged changes into a sequence of smaller commits that each do one thing.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Group hunks by concern: a feature, a bug fix, a refactoring, formatting, documentation, or dependency updates.
+        2. Keep hunks that depend on each other in the same group, so every commit builds on its own.
+        3. Order the groups so that each commit only relies on the ones before it.
+        4. Assign every hunk to exactly one group, referring to hunks by their number.
+        5. Give each group a short title in the imperative mood, usable as a commit subject line.
+        6. Explain in one sentence why the hunks in each group belong together.
+        7. If the changes already form a single concern, return a single group.
+        8. NO YAPPING!
+
+        Respond with JSON only, without code fences, in this exact format:
+        {\"groups\": [{\"title\": \"Add retry logic to uploads\", \"rationale\": \"Both hunks implement the retry loop\", \"hunks\": [1, 3]}]}",
+    )
+}
+
+pub fn create_split_user_prompt(hunks: &[StagedHunk]) -> String {
+    let mut prompt = String::from("Based on the following staged hunks, propose how to split them into commits:\n\n");
+
+    for hunk in hunks {
+        prompt.push_str(&format!(
+            "Hunk {}: {} {}\n{}\n",
+            hunk.id, hunk.path, hunk.header, hunk.content
+        ));
+    }
+
+    prompt
+}
+
+/// Parse the model's JSON response into a plan that covers every hunk exactly once
+///
+/// Unknown hunk numbers are dropped, a hunk claimed by several groups stays in the first one,
+/// and hunks left out by the model are collected in a final group.
+pub fn parse_split_plan(response: &str, hunks: &[StagedHunk]) -> Result<SplitPlan> {
+    let json = extract_json_object(response)
+        .ok_or_else(|| anyhow!("The model did not return a split plan"))?;
+    let value: Value =
+        serde_json::from_str(json).context("The model returned an invalid split plan")?;
+    let raw_groups = value
+        .get("groups")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("The split plan has no groups"))?;
+
+    let known: HashSet<usize> = hunks.iter().map(|hunk| hunk.id).collect();
+    let mut assigned = HashSet::new();
+    let mut groups = Vec::new();
+
+    for raw in raw_groups {
+        let text = |key: &str| {
+            raw.get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        let hunk_ids: Vec<usize> = raw
+            .get("hunks")
+            .and_then(Value::as_array)
+            .map(|ids| ids.iter().filter_map(parse_hunk_id).collect())
+            .unwrap_or_default();
+        let hunk_ids: Vec<usize> = hunk_ids
+            .into_iter()
+            .filter(|id| known.contains(id) && assigned.insert(*id))
+            .collect();
+
+        if !hunk_ids.is_empty() {
+            groups.push(SplitGroup {
+                title: text("title"),
+                rationale: text("rationale"),
+                hunk_ids,
+            });
+        }
+    }
+
+    let remaining: Vec<usize> = hunks
+        .iter()
+        .map(|hunk| hunk.id)
+        .filter(|id| !assigned.contains(id))
+        .collect();
+    if !remaining.is_empty() {
+        groups.push(SplitGroup {
+            title: REMAINING_GROUP_TITLE.to_string(),
+            rationale: "These hunks were not assigned to any group".to_string(),
+            hunk_ids: remaining,
+        });
+    }
+
+    Ok(SplitPlan { groups })
+}
+
+/// Accept hunk numbers given as numbers or as strings such as "3" or "H3"
+fn parse_hunk_id(value: &Value) -> Option<usize> {
+    match value {
+        Value::Number(number) => number.as_u64().map(|n| n as usize),
+        Value::String(text) => text
+            .trim()
+            .trim_start_matches(['H', 'h'])
+            .parse()
+            .ok(),
+        _ => None,
+    }
+}
+
+fn extract_json_object(response: &str) -> Option<&str> {
+    let start = response.find('{')?;
+    let end = response.rfind('}')?;
+    (start < end).then(|| &response[start..=end])
+}
+
+/// Format the plan with the `git add` steps that reproduce it from the current index
+pub fn format_split_plan(plan: &SplitPlan, hunks: &[StagedHunk]) -> String {
+    let mut output = String::from(
+        "Unstage everything first, keeping your changes in the working tree:\n    git reset\n",
+    );
+
+    for (index, group) in plan.groups.iter().enumerate() {
+        output.push_str(&format!("\n{}. {}\n", index + 1, group.title));
+        if !group.rationale.is_empty() {
+            output.push_str(&format!("   {}\n", group.rationale));
+        }
+        output.push_str("   Steps:\n");
+        for step in group_steps(group, hunks) {
+            output.push_str(&format!("    {}\n", step));
+        }
+        output.push_str(&format!("    git commit -m \"{}\"\n", group.title.replace('"', "\\\"")));
+    }
+
+    output
+}
+
+/// The `git add` commands that stage exactly the hunks of one group
+pub fn group_steps(group: &SplitGroup, hunks: &[StagedHunk]) -> Vec<String> {
+    let mut paths: Vec<&str> = Vec::new();
+    for hunk in hunks.iter().filter(|hunk| group.hunk_ids.contains(&hunk.id)) {
+        if !paths.contains(&hunk.path.as_str()) {
+            paths.push(&hunk.path);
+        }
+    }
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let file_hunks: Vec<&StagedHunk> =
+                hunks.iter().filter(|hunk| hunk.path == path).collect();
+            let selected: Vec<&StagedHunk> = file_hunks
+                .iter()
+                .copied()
+                .filter(|hunk| group.hunk_ids.contains(&hunk.id))
+                .collect();
+
+            if selected.len() == file_hunks.len() {
+                format!("git add -- {}", path)
+            } else {
+                let positions: Vec<String> = selected
+                    .iter()
+                    .filter_map(|hunk| hunk.file_hunk_index)
+                    .map(|index| (index + 1).to_string())
+                    .collect();
+                format!(
+                    "git add -p -- {}    # stage hunk {} of {}",
+                    path,
+                    positions.join(", "),
+                    file_hunks.len()
+                )
+            }
+        })
+        .collect()
+}
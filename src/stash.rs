@@ -0,0 +1,120 @@
+use crate::config::Config;
+use crate::context::CommitContext;
+use crate::exclusions::PromptFilter;
+use crate::git;
+use crate::llm;
+use crate::llm_providers::LLMProviderType;
+use anyhow::{anyhow, Context, Result};
+use std::path::Path;
+
+/// Longest stash message to keep, so `git stash list` stays on one line
+pub const MAX_STASH_MESSAGE_LENGTH: usize = 72;
+
+pub struct StashMessageGenerator;
+
+impl StashMessageGenerator {
+    /// Generate a stash message for the uncommitted changes in the working tree
+    pub async fn generate(
+        repo_path: &Path,
+        include_untracked: bool,
+        config: &Config,
+    ) -> Result<String> {
+        let context = git::get_working_tree_info(
+            repo_path,
+            include_untracked,
+            &PromptFilter::from_config(config),
+        )?;
+        if context.staged_files.is_empty() {
+            return Err(anyhow!("No local changes to stash"));
+        }
+
+        let mut system_prompt = create_stash_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let user_prompt = create_stash_user_prompt(&context);
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let response =
+            llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+                .await
+                .context("Failed to generate stash message")?;
+
+        let message = parse_stash_message(&response);
+        if message.is_empty() {
+            return Err(anyhow!("The model did not generate a stash message"));
+        }
+        Ok(message)
+    }
+}
+
+pub fn create_stash_system_prompt() -> String {
+    format!(
+        "You are an AI assistant specialized in describing unfinished work in Git repositories. \
+        Your task is to write a stash message that tells the developer, weeks from now, what this stash contains.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Write a single line of at most {} characters.
+        2. Describe what the work in progress does or attempts, not how it is stored.
+        3. Mention the main area or component touched when it helps tell stashes apart.
+        4. Prefer specific wording: 'retry logic for flaky upload requests' beats 'update upload code'.
+        5. Do not start with 'WIP', 'Stash', or the branch name; Git already shows the branch.
+        6. Do not end the line with a period.
+        7. NO YAPPING!
+
+        Generate only the stash message, without any quotes, explanations, or code fences.",
+        MAX_STASH_MESSAGE_LENGTH
+    )
+}
+
+pub fn create_stash_user_prompt(context: &CommitContext) -> String {
+    let mut prompt = String::from("Based on the following uncommitted changes, write a stash message:\n\n");
+
+    prompt.push_str(&format!("Branch: {}\n\n", context.branch));
+
+    prompt.push_str("Changes:\n");
+    for file in &context.staged_files {
+        prompt.push_str(&format!("- {} ({})\n", file.path, file.change_type));
+        for analysis in &file.analysis {
+            prompt.push_str(&format!("  - {}\n", analysis));
+        }
+    }
+
+    prompt.push_str("\nDiffs:\n");
+    for file in &context.staged_files {
+        prompt.push_str(&format!("File: {}\n{}\n", file.path, file.diff));
+    }
+
+    prompt
+}
+
+/// Reduce the model response to a single line usable as a stash message
+pub fn parse_stash_message(response: &str) -> String {
+    let line = response
+        .lines()
+        .map(|line| line.trim().trim_matches(['`', '"', '\'']).trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .trim_end_matches('.');
+
+    if line.chars().count() <= MAX_STASH_MESSAGE_LENGTH {
+        return line.to_string();
+    }
+
+    // Cut at the last word boundary that fits
+    let truncated: String = line.chars().take(MAX_STASH_MESSAGE_LENGTH).collect();
+    match truncated.rfind(' ') {
+        Some(index) if index > 0 => truncated[..index].trim_end().to_string(),
+        _ => truncated,
+    }
+}
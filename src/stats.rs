@@ -0,0 +1,377 @@
+use crate::config::Config;
+use crate::llm;
+use crate::llm_providers::LLMProviderType;
+use crate::semver::conventional_type;
+use crate::summary::repository_area;
+use anyhow::{anyhow, Context, Result};
+use chrono::{Local, TimeZone};
+use git2::{Patch, Repository};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Number of entries shown per section of the report by default
+pub const DEFAULT_TOP: usize = 10;
+
+/// Activity of a single file over the range
+#[derive(Debug, Clone, Serialize)]
+pub struct FileActivity {
+    pub path: String,
+    pub commits: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// Lines changed in one area of the repository, such as `src/llm_providers` or `docs`
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectoryChurn {
+    pub directory: String,
+    pub commits: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Contributor {
+    pub name: String,
+    pub email: String,
+    pub commits: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitTypeCount {
+    pub commit_type: String,
+    pub commits: usize,
+}
+
+/// Statistics over the non-merge commits in a range
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoStats {
+    pub range: String,
+    pub commits: usize,
+    /// Date of the oldest commit, as YYYY-MM-DD
+    pub first_commit: Option<String>,
+    /// Date of the newest commit, as YYYY-MM-DD
+    pub last_commit: Option<String>,
+    pub average_insertions: f64,
+    pub average_deletions: f64,
+    pub average_files: f64,
+    pub hot_files: Vec<FileActivity>,
+    pub churn_by_directory: Vec<DirectoryChurn>,
+    pub contributors: Vec<Contributor>,
+    pub commit_types: Vec<CommitTypeCount>,
+}
+
+/// Compute statistics for the commits reachable from `to`, excluding those reachable from `from`
+///
+/// Merge commits are skipped, so changes are not counted twice. Each section keeps the `top`
+/// largest entries; commit types are always listed in full.
+pub fn compute_stats(
+    repo_path: &Path,
+    from: Option<&str>,
+    to: &str,
+    top: usize,
+) -> Result<RepoStats> {
+    let repo = Repository::open(repo_path)?;
+    let to_commit = repo
+        .revparse_single(to)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|_| anyhow!("Reference '{}' not found", to))?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(to_commit.id())?;
+    if let Some(from) = from {
+        let from_commit = repo
+            .revparse_single(from)
+            .and_then(|obj| obj.peel_to_commit())
+            .map_err(|_| anyhow!("Reference '{}' not found", from))?;
+        revwalk.hide(from_commit.id())?;
+    }
+
+    let mut commits = 0;
+    let mut first_time: Option<i64> = None;
+    let mut last_time: Option<i64> = None;
+    let mut total_insertions = 0;
+    let mut total_deletions = 0;
+    let mut total_files = 0;
+    let mut files: HashMap<String, FileActivity> = HashMap::new();
+    let mut directories: HashMap<String, DirectoryChurn> = HashMap::new();
+    let mut contributors: HashMap<String, Contributor> = HashMap::new();
+    let mut commit_types: HashMap<String, usize> = HashMap::new();
+
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        commits += 1;
+
+        let time = commit.time().seconds();
+        first_time = Some(first_time.map_or(time, |t| t.min(time)));
+        last_time = Some(last_time.map_or(time, |t| t.max(time)));
+
+        let parent_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+
+        let mut commit_insertions = 0;
+        let mut commit_deletions = 0;
+        let mut touched_directories: Vec<String> = Vec::new();
+        for (index, delta) in diff.deltas().enumerate() {
+            let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+                continue;
+            };
+            let path = path.to_string_lossy().to_string();
+            let (insertions, deletions) = match Patch::from_diff(&diff, index)? {
+                Some(patch) => {
+                    let (_, insertions, deletions) = patch.line_stats()?;
+                    (insertions, deletions)
+                }
+                None => (0, 0),
+            };
+            commit_insertions += insertions;
+            commit_deletions += deletions;
+            total_files += 1;
+
+            let file = files.entry(path.clone()).or_insert_with(|| FileActivity {
+                path: path.clone(),
+                commits: 0,
+                insertions: 0,
+                deletions: 0,
+            });
+            file.commits += 1;
+            file.insertions += insertions;
+            file.deletions += deletions;
+
+            let area = repository_area(&path);
+            let directory = directories.entry(area.clone()).or_insert_with(|| DirectoryChurn {
+                directory: area.clone(),
+                commits: 0,
+                insertions: 0,
+                deletions: 0,
+            });
+            if !touched_directories.contains(&area) {
+                directory.commits += 1;
+                touched_directories.push(area);
+            }
+            directory.insertions += insertions;
+            directory.deletions += deletions;
+        }
+        total_insertions += commit_insertions;
+        total_deletions += commit_deletions;
+
+        let author = commit.author();
+        let email = author.email().unwrap_or_default().to_lowercase();
+        let contributor = contributors.entry(email.clone()).or_insert_with(|| Contributor {
+            // The walk starts at the newest commit, so this is the author's latest name
+            name: author.name().unwrap_or_default().to_string(),
+            email,
+            commits: 0,
+            insertions: 0,
+            deletions: 0,
+        });
+        contributor.commits += 1;
+        contributor.insertions += commit_insertions;
+        contributor.deletions += commit_deletions;
+
+        *commit_types
+            .entry(commit_type(commit.message().unwrap_or_default()))
+            .or_default() += 1;
+    }
+
+    let average = |total: usize| {
+        if commits == 0 {
+            0.0
+        } else {
+            total as f64 / commits as f64
+        }
+    };
+    let format_date = |seconds: i64| {
+        Local
+            .timestamp_opt(seconds, 0)
+            .single()
+            .map(|date| date.format("%Y-%m-%d").to_string())
+    };
+
+    let mut hot_files: Vec<FileActivity> = files.into_values().collect();
+    hot_files.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then((b.insertions + b.deletions).cmp(&(a.insertions + a.deletions)))
+            .then(a.path.cmp(&b.path))
+    });
+    hot_files.truncate(top);
+
+    let mut churn_by_directory: Vec<DirectoryChurn> = directories.into_values().collect();
+    churn_by_directory.sort_by(|a, b| {
+        (b.insertions + b.deletions)
+            .cmp(&(a.insertions + a.deletions))
+            .then(a.directory.cmp(&b.directory))
+    });
+    churn_by_directory.truncate(top);
+
+    let mut contributors: Vec<Contributor> = contributors.into_values().collect();
+    contributors.sort_by(|a, b| b.commits.cmp(&a.commits).then(a.name.cmp(&b.name)));
+    contributors.truncate(top);
+
+    let mut commit_types: Vec<CommitTypeCount> = commit_types
+        .into_iter()
+        .map(|(commit_type, commits)| CommitTypeCount {
+            commit_type,
+            commits,
+        })
+        .collect();
+    commit_types.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then(a.commit_type.cmp(&b.commit_type))
+    });
+
+    Ok(RepoStats {
+        range: match from {
+            Some(from) => format!("{}..{}", from, to),
+            None => to.to_string(),
+        },
+        commits,
+        first_commit: first_time.and_then(format_date),
+        last_commit: last_time.and_then(format_date),
+        average_insertions: average(total_insertions),
+        average_deletions: average(total_deletions),
+        average_files: average(total_files),
+        hot_files,
+        churn_by_directory,
+        contributors,
+        commit_types,
+    })
+}
+
+/// The conventional commit type of a message, or a type inferred from the subject's first word
+pub fn commit_type(message: &str) -> String {
+    let subject = message.lines().next().unwrap_or("").trim();
+    if let Some(commit_type) = conventional_type(subject) {
+        return commit_type;
+    }
+
+    let first_word = subject
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+    let inferred = match first_word.trim_end_matches([':', ',']) {
+        "add" | "adds" | "added" | "introduce" | "implement" | "support" => "feat",
+        "fix" | "fixes" | "fixed" | "correct" | "resolve" => "fix",
+        "refactor" | "rename" | "move" | "extract" | "simplify" | "restructure" => "refactor",
+        "doc" | "docs" | "document" | "documentation" => "docs",
+        "test" | "tests" => "test",
+        "remove" | "delete" | "drop" | "bump" | "update" | "upgrade" => "chore",
+        "revert" => "revert",
+        _ => "other",
+    };
+    inferred.to_string()
+}
+
+/// Format the statistics as a plain-text report
+pub fn format_stats_report(stats: &RepoStats) -> String {
+    let mut report = format!("Repository stats for {}\n\n", stats.range);
+
+    report.push_str(&format!("Commits: {}", stats.commits));
+    if let (Some(first), Some(last)) = (&stats.first_commit, &stats.last_commit) {
+        report.push_str(&format!(" ({} to {})", first, last));
+    }
+    report.push('\n');
+    report.push_str(&format!(
+        "Average commit: +{:.1} -{:.1} lines in {:.1} files\n",
+        stats.average_insertions, stats.average_deletions, stats.average_files
+    ));
+
+    report.push_str("\nHot files:\n");
+    for file in &stats.hot_files {
+        report.push_str(&format!(
+            "  {:>4} commits  +{} -{}  {}\n",
+            file.commits, file.insertions, file.deletions, file.path
+        ));
+    }
+
+    report.push_str("\nChurn by directory:\n");
+    for directory in &stats.churn_by_directory {
+        report.push_str(&format!(
+            "  +{} -{} in {} commits  {}\n",
+            directory.insertions, directory.deletions, directory.commits, directory.directory
+        ));
+    }
+
+    report.push_str("\nContributors:\n");
+    for contributor in &stats.contributors {
+        report.push_str(&format!(
+            "  {:>4} commits  +{} -{}  {} <{}>\n",
+            contributor.commits,
+            contributor.insertions,
+            contributor.deletions,
+            contributor.name,
+            contributor.email
+        ));
+    }
+
+    report.push_str("\nCommit types:\n");
+    for count in &stats.commit_types {
+        let share = count.commits as f64 * 100.0 / stats.commits.max(1) as f64;
+        report.push_str(&format!(
+            "  {:>4} commits  {:>3.0}%  {}\n",
+            count.commits, share, count.commit_type
+        ));
+    }
+
+    report
+}
+
+pub struct StatsNarrativeGenerator;
+
+impl StatsNarrativeGenerator {
+    /// Write a short narrative that interprets the statistics
+    pub async fn generate(stats: &RepoStats, config: &Config) -> Result<String> {
+        let mut system_prompt = create_stats_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let user_prompt = format!(
+            "Based on the following repository statistics, write the narrative:\n\n{}",
+            format_stats_report(stats)
+        );
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+            .await
+            .context("Failed to generate stats narrative")
+    }
+}
+
+pub fn create_stats_system_prompt() -> String {
+    String::from(
+        "You are an AI assistant specialized in interpreting Git repository statistics for engineering teams. \
+        Your task is to turn a statistics report into a short narrative about where the work went. \
+        Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Write two or three short paragraphs of plain prose, without headings or bullet points.
+        2. Describe where most of the activity was concentrated, naming the hottest files and directories.
+        3. Describe the balance of work, such as features versus fixes, based on the commit types.
+        4. Describe how the work was spread across contributors, without ranking or judging individuals.
+        5. Point out patterns worth a closer look, such as a file changed in most commits, but clearly mark any interpretation as likely.
+        6. Use numbers from the report; do not invent any.
+        7. Avoid common cliché words (like 'enhance', 'streamline', 'leverage', etc) and phrases.
+        8. NO YAPPING!
+
+        Generate only the narrative, without any introduction or closing remarks.",
+    )
+}
@@ -0,0 +1,236 @@
+use crate::change_analyzer::{AnalyzedChange, ChangeAnalyzer};
+use crate::config::Config;
+use crate::llm;
+use crate::llm_providers::LLMProviderType;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone};
+use git2::Repository;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+lazy_static! {
+    static ref RELATIVE_TIME: Regex =
+        Regex::new(r"^(\d+)\s+(minute|hour|day|week|month|year)s?\s+ago$").unwrap();
+}
+
+/// Directories whose subdirectories are treated as separate areas of the repository
+const SOURCE_ROOTS: &[&str] = &["src", "lib", "app", "apps", "packages", "crates", "services"];
+
+pub struct WorkSummaryGenerator;
+
+impl WorkSummaryGenerator {
+    /// Summarize the commits by `author` since the given time
+    pub async fn generate(
+        repo_path: &Path,
+        author: Option<&str>,
+        since: DateTime<Local>,
+        config: &Config,
+    ) -> Result<String> {
+        let author = match author {
+            Some(author) => author.to_string(),
+            None => current_author(repo_path)?,
+        };
+        let changes = get_author_commits_since(repo_path, &author, since)?;
+        if changes.is_empty() {
+            return Err(anyhow!(
+                "No commits by '{}' since {}",
+                author,
+                since.format("%Y-%m-%d %H:%M")
+            ));
+        }
+
+        let mut system_prompt = create_summary_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let user_prompt = create_summary_user_prompt(&changes, since);
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+            .await
+            .context("Failed to generate work summary")
+    }
+}
+
+pub fn create_summary_system_prompt() -> String {
+    String::from(
+        "You are an AI assistant specialized in summarizing a developer's recent work for standups and status reports. \
+        Your task is to turn the provided commits, grouped by area of the repository, into a short narrative. \
+        Aim for a tone that is professional, approachable, and authoritative, keeping in mind any additional user instructions.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Write in the first person and the past tense, as the developer would say it in a standup.
+        2. Organize the summary by area, using a short bold label for each area followed by one to three sentences.
+        3. Combine related commits into a single statement about what was accomplished.
+        4. Mention user-visible outcomes before internal changes.
+        5. Leave out merge commits and trivial changes such as typo fixes unless they are the only work in an area.
+        6. Keep the whole summary under 150 words.
+        7. Avoid common cliché words (like 'enhance', 'streamline', 'leverage', etc) and phrases.
+        8. Do not speculate about the purpose of a change or add any information not directly supported by the context.
+        9. NO YAPPING!
+
+        Generate only the summary, without any introduction or closing remarks."
+    )
+}
+
+pub fn create_summary_user_prompt(changes: &[AnalyzedChange], since: DateTime<Local>) -> String {
+    let mut prompt = format!(
+        "Based on the following {} commits since {}, summarize the work:\n\n",
+        changes.len(),
+        since.format("%Y-%m-%d %H:%M")
+    );
+
+    for (area, commits) in group_by_area(changes) {
+        prompt.push_str(&format!("Area: {}\n", area));
+        for change in commits {
+            prompt.push_str(&format!(
+                "- {}\n",
+                change.commit_message.lines().next().unwrap_or("").trim()
+            ));
+        }
+        prompt.push('\n');
+    }
+
+    prompt
+}
+
+/// Group commits by the repository area they touch most; each commit appears once
+pub fn group_by_area(changes: &[AnalyzedChange]) -> BTreeMap<String, Vec<&AnalyzedChange>> {
+    let mut groups: BTreeMap<String, Vec<&AnalyzedChange>> = BTreeMap::new();
+    for change in changes {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for file_change in &change.file_changes {
+            *counts.entry(repository_area(&file_change.new_path)).or_default() += 1;
+        }
+        // Ties go to the alphabetically first area, so grouping is stable
+        let area = counts
+            .into_iter()
+            .fold(None, |best: Option<(String, usize)>, (area, count)| match best {
+                Some((_, best_count)) if best_count >= count => best,
+                _ => Some((area, count)),
+            })
+            .map(|(area, _)| area)
+            .unwrap_or_else(|| "(root)".to_string());
+        groups.entry(area).or_default().push(change);
+    }
+    groups
+}
+
+/// The area of the repository a path belongs to, e.g. `src/llm_providers` or `docs`
+pub fn repository_area(path: &str) -> String {
+    let parts: Vec<&str> = path.split('/').collect();
+    match parts.as_slice() {
+        [_file] => "(root)".to_string(),
+        [root, dir, _, ..] if SOURCE_ROOTS.contains(root) => format!("{}/{}", root, dir),
+        [dir, ..] => dir.to_string(),
+        [] => "(root)".to_string(),
+    }
+}
+
+/// Parse a `--since` value such as `yesterday`, `3 days ago`, `1 week ago`, or `2024-05-01`
+pub fn parse_since(input: &str, now: DateTime<Local>) -> Result<DateTime<Local>> {
+    let input = input.trim().to_lowercase();
+    let start_of = |date: NaiveDate| {
+        Local
+            .from_local_datetime(&date.and_time(NaiveTime::default()))
+            .earliest()
+            .ok_or_else(|| anyhow!("Invalid local time for {}", date))
+    };
+
+    match input.as_str() {
+        "today" => return start_of(now.date_naive()),
+        "yesterday" => return start_of(now.date_naive() - Duration::days(1)),
+        _ => {}
+    }
+
+    if let Some(captures) = RELATIVE_TIME.captures(&input) {
+        let amount: i64 = captures[1].parse()?;
+        let duration = match &captures[2] {
+            "minute" => Duration::minutes(amount),
+            "hour" => Duration::hours(amount),
+            "day" => Duration::days(amount),
+            "week" => Duration::weeks(amount),
+            "month" => Duration::days(amount * 30),
+            _ => Duration::days(amount * 365),
+        };
+        return Ok(now - duration);
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return start_of(date);
+    }
+
+    Err(anyhow!(
+        "Unrecognized time '{}'. Use 'today', 'yesterday', 'N days ago', or a date like 2024-05-01",
+        input
+    ))
+}
+
+/// The email of the configured Git user
+fn current_author(repo_path: &Path) -> Result<String> {
+    let repo = Repository::open(repo_path)?;
+    let signature = repo
+        .signature()
+        .map_err(|_| anyhow!("Git user is not configured. Set user.email or use --author"))?;
+    signature
+        .email()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("Git user email is not valid UTF-8"))
+}
+
+/// Analyze the commits on HEAD by the given author (name or email) made since the given time
+pub fn get_author_commits_since(
+    repo_path: &Path,
+    author: &str,
+    since: DateTime<Local>,
+) -> Result<Vec<AnalyzedChange>> {
+    get_commits_since(repo_path, Some(author), since)
+}
+
+/// Analyze the non-merge commits on HEAD made since the given time, optionally by one author
+pub fn get_commits_since(
+    repo_path: &Path,
+    author: Option<&str>,
+    since: DateTime<Local>,
+) -> Result<Vec<AnalyzedChange>> {
+    let repo = Repository::open(repo_path)?;
+    let analyzer = ChangeAnalyzer::new(&repo);
+    let since = since.timestamp();
+    let author = author.map(str::to_lowercase);
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.set_sorting(git2::Sort::TIME)?;
+
+    let mut changes = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.time().seconds() < since {
+            break;
+        }
+        let signature = commit.author();
+        let matches_author = author.as_ref().is_none_or(|author| {
+            [signature.name(), signature.email()]
+                .iter()
+                .flatten()
+                .any(|value| value.to_lowercase() == *author)
+        });
+        if matches_author && commit.parent_count() <= 1 {
+            changes.push(analyzer.analyze_commit(&commit)?);
+        }
+    }
+
+    Ok(changes)
+}
@@ -0,0 +1,267 @@
+use crate::config::Config;
+use crate::log_debug;
+use anyhow::{anyhow, Result};
+use colored::*;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+
+/// Directory next to the configuration file that holds template overrides
+pub const TEMPLATES_DIR: &str = "templates";
+
+pub const COMMIT_SYSTEM: &str = "commit_system";
+pub const COMMIT_USER: &str = "commit_user";
+pub const CHANGELOG_SYSTEM: &str = "changelog_system";
+pub const CHANGELOG_USER: &str = "changelog_user";
+pub const RELEASE_NOTES_SYSTEM: &str = "release_notes_system";
+pub const RELEASE_NOTES_USER: &str = "release_notes_user";
+
+/// Every prompt that can be overridden, in the order they are documented
+pub const TEMPLATE_NAMES: &[&str] = &[
+    COMMIT_SYSTEM,
+    COMMIT_USER,
+    CHANGELOG_SYSTEM,
+    CHANGELOG_USER,
+    RELEASE_NOTES_SYSTEM,
+    RELEASE_NOTES_USER,
+];
+
+/// Where template overrides are read from, e.g. `~/.config/git-iris/templates`
+pub fn templates_dir() -> Result<PathBuf> {
+    let config_path = Config::get_config_path()?;
+    let dir = config_path
+        .parent()
+        .ok_or_else(|| anyhow!("Unable to determine the templates directory"))?;
+    Ok(dir.join(TEMPLATES_DIR))
+}
+
+/// Path of the file that overrides the named prompt
+pub fn template_path(name: &str) -> Result<PathBuf> {
+    Ok(templates_dir()?.join(format!("{}.tmpl", name)))
+}
+
+/// Render the user's override for the named prompt, or `None` if there is no override
+pub fn render_override(name: &str, variables: &Value) -> Result<Option<String>> {
+    let Some(path) = template_path(name).ok().filter(|path| path.is_file()) else {
+        return Ok(None);
+    };
+    let template = fs::read_to_string(&path)?;
+    log_debug!("Using prompt template {}", path.display());
+    let rendered = render(&template, variables)
+        .map_err(|e| anyhow!("Invalid prompt template {}: {}", path.display(), e))?;
+    Ok(Some(rendered))
+}
+
+/// Like `render_override`, for prompts that can't fail: a broken template is reported and
+/// the built-in prompt is used instead
+pub fn render_override_or_warn(name: &str, variables: &Value) -> Option<String> {
+    match render_override(name, variables) {
+        Ok(rendered) => rendered,
+        Err(e) => {
+            eprintln!("{}", format!("{}; using the built-in prompt", e).yellow());
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Variable(String),
+    If {
+        name: String,
+        negate: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Each {
+        name: String,
+        body: Vec<Node>,
+    },
+}
+
+/// Render a template against a JSON object of variables
+///
+/// The syntax is a small subset of Handlebars:
+/// - `{{name}}` inserts a variable; `{{file.path}}` looks up a field
+/// - `{{#if name}}…{{else}}…{{/if}}` and `{{#unless name}}…{{/unless}}` test whether a
+///   variable is set: `false`, `null`, `0`, empty strings and empty lists are not
+/// - `{{#each list}}…{{/each}}` repeats its body for every item; inside it, `{{this}}` is the
+///   item, `{{@index}}` its position starting at 0, and fields of the item can be used by name
+///
+/// Unknown variables render as nothing.
+pub fn render(template: &str, variables: &Value) -> Result<String> {
+    let tags = split_tags(template)?;
+    let mut position = 0;
+    let (nodes, end) = parse_nodes(&tags, &mut position)?;
+    if let Some(tag) = end {
+        return Err(anyhow!("Unexpected {{{{{}}}}}", tag));
+    }
+    let mut output = String::new();
+    render_nodes(&nodes, &mut vec![(variables, None)], &mut output);
+    Ok(output)
+}
+
+#[derive(Debug)]
+enum Piece {
+    Text(String),
+    Tag(String),
+}
+
+fn split_tags(template: &str) -> Result<Vec<Piece>> {
+    let mut pieces = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            pieces.push(Piece::Text(rest[..start].to_string()));
+        }
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| anyhow!("Unclosed {{{{ tag"))?;
+        pieces.push(Piece::Tag(rest[start + 2..start + end].trim().to_string()));
+        rest = &rest[start + end + 2..];
+    }
+    if !rest.is_empty() {
+        pieces.push(Piece::Text(rest.to_string()));
+    }
+    Ok(pieces)
+}
+
+/// Parse nodes until the end of the template or a closing tag, which is returned with them
+fn parse_nodes(pieces: &[Piece], position: &mut usize) -> Result<(Vec<Node>, Option<String>)> {
+    let mut nodes = Vec::new();
+    while let Some(piece) = pieces.get(*position) {
+        *position += 1;
+        let tag = match piece {
+            Piece::Text(text) => {
+                nodes.push(Node::Text(text.clone()));
+                continue;
+            }
+            Piece::Tag(tag) => tag,
+        };
+        if tag == "else" || tag.starts_with('/') {
+            return Ok((nodes, Some(tag.clone())));
+        }
+        let Some(block) = tag.strip_prefix('#') else {
+            nodes.push(Node::Variable(tag.clone()));
+            continue;
+        };
+        let (kind, name) = block
+            .split_once(char::is_whitespace)
+            .map(|(kind, name)| (kind, name.trim().to_string()))
+            .ok_or_else(|| anyhow!("{{{{#{}}}}} needs a variable name", block))?;
+        let (body, end) = parse_nodes(pieces, position)?;
+        match kind {
+            "if" | "unless" => {
+                let otherwise = if end.as_deref() == Some("else") {
+                    let (otherwise, end) = parse_nodes(pieces, position)?;
+                    expect_close(end, kind)?;
+                    otherwise
+                } else {
+                    expect_close(end, kind)?;
+                    Vec::new()
+                };
+                nodes.push(Node::If {
+                    name,
+                    negate: kind == "unless",
+                    then: body,
+                    otherwise,
+                });
+            }
+            "each" => {
+                expect_close(end, kind)?;
+                nodes.push(Node::Each { name, body });
+            }
+            _ => return Err(anyhow!("Unknown block {{{{#{}}}}}", kind)),
+        }
+    }
+    Ok((nodes, None))
+}
+
+fn expect_close(end: Option<String>, kind: &str) -> Result<()> {
+    match end {
+        Some(tag) if tag == format!("/{}", kind) => Ok(()),
+        Some(tag) => Err(anyhow!("Expected {{{{/{}}}}} but found {{{{{}}}}}", kind, tag)),
+        None => Err(anyhow!("Missing {{{{/{}}}}}", kind)),
+    }
+}
+
+/// Variables in scope, innermost last, each with its position when inside `each`
+type Scopes<'a> = Vec<(&'a Value, Option<usize>)>;
+
+fn render_nodes<'a>(nodes: &[Node], scopes: &mut Scopes<'a>, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Variable(name) => {
+                if let Some(value) = lookup(scopes, name) {
+                    output.push_str(&display(&value));
+                }
+            }
+            Node::If {
+                name,
+                negate,
+                then,
+                otherwise,
+            } => {
+                let set = lookup(scopes, name).is_some_and(|value| is_truthy(&value));
+                let branch = if set != *negate { then } else { otherwise };
+                render_nodes(branch, scopes, output);
+            }
+            Node::Each { name, body } => {
+                let Some(Value::Array(items)) = lookup_ref(scopes, name) else {
+                    continue;
+                };
+                for (index, item) in items.iter().enumerate() {
+                    scopes.push((item, Some(index)));
+                    render_nodes(body, scopes, output);
+                    scopes.pop();
+                }
+            }
+        }
+    }
+}
+
+fn lookup(scopes: &Scopes, name: &str) -> Option<Value> {
+    if name == "@index" {
+        let index = scopes.iter().rev().find_map(|(_, index)| *index)?;
+        return Some(Value::from(index));
+    }
+    lookup_ref(scopes, name).cloned()
+}
+
+fn lookup_ref<'a>(scopes: &Scopes<'a>, name: &str) -> Option<&'a Value> {
+    if name == "this" {
+        return scopes.last().map(|(value, _)| *value);
+    }
+    let mut segments = name.split('.');
+    let first = segments.next()?;
+    let mut value = scopes
+        .iter()
+        .rev()
+        .find_map(|(scope, _)| scope.get(first))?;
+    for segment in segments {
+        value = value.get(segment)?;
+    }
+    Some(value)
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(set) => *set,
+        Value::Number(number) => number.as_f64().is_some_and(|number| number != 0.0),
+        Value::String(text) => !text.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(display).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,137 @@
+use crate::context::StagedFile;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use std::fmt;
+
+lazy_static! {
+    // A marker word, an optional owner like TODO(alice), and the rest of the comment
+    static ref TODO_PATTERN: Regex =
+        Regex::new(r"\b(?P<kind>TODO|FIXME|HACK)\b(?:\([^)]*\))?:?\s*(?P<text>.*)").unwrap();
+    static ref HUNK_HEADER: Regex = Regex::new(r"^@@ -\d+(?:,\d+)? \+(?P<start>\d+)").unwrap();
+}
+
+/// Markers that flag unfinished or questionable code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TodoKind {
+    Todo,
+    Fixme,
+    Hack,
+}
+
+impl fmt::Display for TodoKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TodoKind::Todo => write!(f, "TODO"),
+            TodoKind::Fixme => write!(f, "FIXME"),
+            TodoKind::Hack => write!(f, "HACK"),
+        }
+    }
+}
+
+/// A TODO, FIXME, or HACK comment added by a change
+#[derive(Debug, Clone, Serialize)]
+pub struct TodoItem {
+    pub kind: TodoKind,
+    pub path: String,
+    /// Line number in the new version of the file
+    pub line: usize,
+    pub text: String,
+}
+
+impl fmt::Display for TodoItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{} {}", self.path, self.line, self.kind)?;
+        if !self.text.is_empty() {
+            write!(f, ": {}", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Find the TODO, FIXME, and HACK comments on lines the changes add
+pub fn find_added_todos(files: &[StagedFile]) -> Vec<TodoItem> {
+    files
+        .iter()
+        .flat_map(|file| find_added_todos_in_diff(&file.path, &file.diff))
+        .collect()
+}
+
+/// Find the TODO, FIXME, and HACK comments on added lines of a single file's diff
+pub fn find_added_todos_in_diff(path: &str, diff: &str) -> Vec<TodoItem> {
+    let mut items = Vec::new();
+    // Line number in the new file of the next added or context line, once inside a hunk
+    let mut next_line: Option<usize> = None;
+
+    for line in diff.lines() {
+        // Hunk headers may carry a leading space when the diff was printed without origins
+        let header = line.strip_prefix(' ').unwrap_or(line);
+        if let Some(captures) = HUNK_HEADER.captures(header) {
+            next_line = captures["start"].parse().ok();
+            continue;
+        }
+        let Some(line_number) = next_line else {
+            continue;
+        };
+
+        match line.chars().next() {
+            Some('+') => {
+                if let Some(item) = parse_todo(path, line_number, &line[1..]) {
+                    items.push(item);
+                }
+                next_line = Some(line_number + 1);
+            }
+            Some('-') | Some('\\') => {}
+            _ => next_line = Some(line_number + 1),
+        }
+    }
+
+    items
+}
+
+fn parse_todo(path: &str, line: usize, content: &str) -> Option<TodoItem> {
+    let captures = TODO_PATTERN.captures(content)?;
+    let kind = match &captures["kind"] {
+        "TODO" => TodoKind::Todo,
+        "FIXME" => TodoKind::Fixme,
+        _ => TodoKind::Hack,
+    };
+    // Drop the closing part of block comments
+    let text = captures["text"]
+        .trim()
+        .trim_end_matches("*/")
+        .trim_end_matches("-->")
+        .trim()
+        .to_string();
+
+    Some(TodoItem {
+        kind,
+        path: path.to_string(),
+        line,
+        text,
+    })
+}
+
+/// Format the items as a commit message section
+pub fn format_todo_section(items: &[TodoItem]) -> String {
+    let mut section = String::from("TODOs added:");
+    for item in items {
+        section.push_str(&format!("\n- {}", item));
+    }
+    section
+}
+
+/// Append a section listing the items to a commit message, if there are any
+///
+/// A section already in the message, such as one kept by a refined message, is replaced.
+pub fn append_todo_section(message: &str, items: &[TodoItem]) -> String {
+    if items.is_empty() {
+        return message.to_string();
+    }
+    let message = message
+        .split("\n\nTODOs added:")
+        .next()
+        .unwrap_or(message);
+    format!("{}\n\n{}", message.trim_end(), format_todo_section(items))
+}
@@ -0,0 +1,668 @@
+use crate::config::LintConfig;
+use crate::git;
+use crate::highlight::{tokenize, Language, TokenKind};
+use crate::lint::lint_message;
+use crate::log_debug;
+use crate::ui;
+use anyhow::Result;
+use colored::*;
+use console::{Key, Term};
+use std::io::Write;
+use std::process::Command;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Terminal width from which the diff and the message are shown side by side
+const SIDE_BY_SIDE_WIDTH: usize = 100;
+
+/// Lines scrolled by Page Up and Page Down
+const PAGE_LINES: usize = 10;
+
+/// Escape sequences to switch to the alternate screen and back
+const ENTER_ALTERNATE_SCREEN: &str = "\x1b[?1049h";
+const LEAVE_ALTERNATE_SCREEN: &str = "\x1b[?1049l";
+
+/// What a message is generated with; changed from the TUI with `g` and `p`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerationSettings {
+    pub use_gitmoji: bool,
+    pub preset: String,
+}
+
+/// What the TUI should do after a key press
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuiAction {
+    /// Redraw the screen
+    Redraw,
+    /// Generate a new message with the current settings
+    Regenerate,
+    /// Open the message in the editor
+    Edit,
+    Commit,
+    Quit,
+}
+
+/// A line of the staged diff, classified for highlighting
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DiffLine {
+    /// A file header, or anything else outside a hunk
+    Meta(String),
+    Hunk(String),
+    /// A removed, added, or context line, without its marker
+    Code {
+        marker: char,
+        code: String,
+        language: Language,
+    },
+}
+
+/// A row of the diff pane
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DiffRow {
+    Full(DiffLine),
+    /// Removed lines on the left, added lines on the right, and context lines on both sides
+    Split(Option<DiffLine>, Option<DiffLine>),
+}
+
+/// A full-screen view of the staged diff and the generated message
+pub struct TuiCommit {
+    diff_lines: Vec<DiffLine>,
+    side_by_side: bool,
+    message: String,
+    settings: GenerationSettings,
+    presets: Vec<String>,
+    diff_scroll: usize,
+    status: String,
+    program_name: String,
+    program_version: String,
+    commit_all: bool,
+    lint: LintConfig,
+}
+
+impl TuiCommit {
+    pub fn new(
+        diff: &str,
+        initial_message: String,
+        settings: GenerationSettings,
+        mut presets: Vec<String>,
+        program_name: String,
+        program_version: String,
+    ) -> Self {
+        presets.sort();
+        if !presets.contains(&settings.preset) {
+            presets.insert(0, settings.preset.clone());
+        }
+        TuiCommit {
+            diff_lines: parse_diff(diff),
+            side_by_side: false,
+            message: initial_message,
+            settings,
+            presets,
+            diff_scroll: 0,
+            status: String::new(),
+            program_name,
+            program_version,
+            commit_all: false,
+            lint: LintConfig::default(),
+        }
+    }
+
+    /// Stage every change to tracked files before committing, like `git commit -a`
+    pub fn set_commit_all(&mut self, commit_all: bool) {
+        self.commit_all = commit_all;
+    }
+
+    /// Rules the message is checked against while it is shown
+    pub fn set_lint_config(&mut self, lint: LintConfig) {
+        self.lint = lint;
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn settings(&self) -> &GenerationSettings {
+        &self.settings
+    }
+
+    /// Run the TUI until the user commits or quits. Returns whether a commit was made.
+    pub async fn run<F, Fut>(&mut self, generate_message: F) -> Result<bool>
+    where
+        F: Fn(GenerationSettings) -> Fut,
+        Fut: std::future::Future<Output = Result<String>>,
+    {
+        let mut term = Term::stdout();
+        let mut screen = AlternateScreen::enter(&term)?;
+
+        loop {
+            self.draw(&mut term)?;
+            match self.handle_key(term.read_key()?) {
+                TuiAction::Redraw => {}
+                TuiAction::Regenerate => {
+                    self.status = "🔮 Generating a new message...".to_string();
+                    self.draw(&mut term)?;
+                    match generate_message(self.settings.clone()).await {
+                        Ok(message) => {
+                            self.message = message;
+                            self.status.clear();
+                        }
+                        Err(e) => self.status = format!("Generation failed: {}", e),
+                    }
+                }
+                TuiAction::Edit => {
+                    screen.leave()?;
+                    let edited = edit_in_editor(&self.message);
+                    screen = AlternateScreen::enter(&term)?;
+                    match edited {
+                        Ok(Some(message)) => self.message = message,
+                        Ok(None) => self.status = "Editing cancelled".to_string(),
+                        Err(e) => self.status = format!("Could not open the editor: {}", e),
+                    }
+                }
+                TuiAction::Commit => {
+                    screen.leave()?;
+                    return self.perform_commit();
+                }
+                TuiAction::Quit => {
+                    screen.leave()?;
+                    ui::print_info("🌠 Commit cancelled.");
+                    return Ok(false);
+                }
+            }
+        }
+    }
+
+    /// Update the state for a key press and decide what happens next
+    pub fn handle_key(&mut self, key: Key) -> TuiAction {
+        self.status.clear();
+        match key {
+            Key::ArrowDown | Key::Char('j') => self.scroll_diff(1),
+            Key::ArrowUp | Key::Char('k') => self.scroll_diff(-1),
+            Key::PageDown | Key::Char(' ') => self.scroll_diff(PAGE_LINES as isize),
+            Key::PageUp => self.scroll_diff(-(PAGE_LINES as isize)),
+            Key::Tab | Key::Char(']') => self.jump_to_file(1),
+            Key::BackTab | Key::Char('[') => self.jump_to_file(-1),
+            Key::Char('s') | Key::Char('S') => self.toggle_side_by_side(),
+            Key::Char('r') | Key::Char('R') => TuiAction::Regenerate,
+            Key::Char('e') | Key::Char('E') => TuiAction::Edit,
+            Key::Char('g') | Key::Char('G') => {
+                self.settings.use_gitmoji = !self.settings.use_gitmoji;
+                TuiAction::Regenerate
+            }
+            Key::Char('p') => self.switch_preset(1),
+            Key::Char('P') => self.switch_preset(-1),
+            Key::Enter | Key::Char('c') | Key::Char('C') => TuiAction::Commit,
+            Key::Escape | Key::Char('q') | Key::Char('Q') => TuiAction::Quit,
+            _ => TuiAction::Redraw,
+        }
+    }
+
+    fn scroll_diff(&mut self, lines: isize) -> TuiAction {
+        let max_scroll = self.diff_rows().len().saturating_sub(1);
+        self.diff_scroll = self
+            .diff_scroll
+            .saturating_add_signed(lines)
+            .min(max_scroll);
+        TuiAction::Redraw
+    }
+
+    /// Scroll to the start of the next or previous file
+    fn jump_to_file(&mut self, step: isize) -> TuiAction {
+        let starts = file_starts(&self.diff_rows());
+        let target = if step > 0 {
+            starts.iter().find(|start| **start > self.diff_scroll)
+        } else {
+            starts.iter().rev().find(|start| **start < self.diff_scroll)
+        };
+        if let Some(target) = target {
+            self.diff_scroll = *target;
+        }
+        TuiAction::Redraw
+    }
+
+    /// Switch between the unified and the side-by-side diff, staying in the same file
+    fn toggle_side_by_side(&mut self) -> TuiAction {
+        let file = current_file(&file_starts(&self.diff_rows()), self.diff_scroll);
+        self.side_by_side = !self.side_by_side;
+        self.diff_scroll = file_starts(&self.diff_rows())
+            .get(file.unwrap_or(0))
+            .copied()
+            .unwrap_or(0);
+        TuiAction::Redraw
+    }
+
+    fn diff_rows(&self) -> Vec<DiffRow> {
+        if self.side_by_side {
+            side_by_side_rows(&self.diff_lines)
+        } else {
+            self.diff_lines.iter().cloned().map(DiffRow::Full).collect()
+        }
+    }
+
+    fn switch_preset(&mut self, step: isize) -> TuiAction {
+        if self.presets.len() < 2 {
+            return TuiAction::Redraw;
+        }
+        let current = self
+            .presets
+            .iter()
+            .position(|preset| *preset == self.settings.preset)
+            .unwrap_or(0);
+        let count = self.presets.len() as isize;
+        let next = (current as isize + step).rem_euclid(count) as usize;
+        self.settings.preset = self.presets[next].clone();
+        TuiAction::Regenerate
+    }
+
+    fn draw(&self, term: &mut Term) -> Result<()> {
+        let (height, width) = term.size();
+        let frame = self.render_frame(width as usize, height as usize);
+        term.move_cursor_to(0, 0)?;
+        write!(term, "{}", frame.join("\r\n"))?;
+        term.flush()?;
+        Ok(())
+    }
+
+    /// Render the screen as lines of exactly `width` columns, one per terminal row
+    pub fn render_frame(&self, width: usize, height: usize) -> Vec<String> {
+        let width = width.max(20);
+        let height = height.max(8);
+        let body_height = height - 2;
+
+        let mut message_lines: Vec<String> = Vec::new();
+        let inner_message_width = if width >= SIDE_BY_SIDE_WIDTH {
+            width - width * 3 / 5 - 2
+        } else {
+            width - 2
+        };
+        for line in textwrap::wrap(self.message.trim(), inner_message_width.max(1)) {
+            message_lines.push(line.truecolor(173, 216, 230).bold().to_string());
+        }
+        let violations = lint_message(&self.message, &self.lint);
+        if !violations.is_empty() {
+            message_lines.push(String::new());
+            for violation in violations {
+                message_lines.push(format!("⚠️  {}", violation.message).yellow().to_string());
+            }
+        }
+
+        let diff_width = if width >= SIDE_BY_SIDE_WIDTH {
+            width * 3 / 5
+        } else {
+            width
+        };
+        let diff_rows = self.diff_rows();
+        let diff_lines: Vec<String> = diff_rows
+            .iter()
+            .skip(self.diff_scroll)
+            .map(|row| render_diff_row(row, diff_width.saturating_sub(2)))
+            .collect();
+        let mut diff_title = format!(
+            "Staged Changes ({}/{})",
+            (self.diff_scroll + 1).min(diff_rows.len()),
+            diff_rows.len()
+        );
+        let starts = file_starts(&diff_rows);
+        if let Some(file) = current_file(&starts, self.diff_scroll) {
+            if let DiffRow::Full(DiffLine::Meta(header)) = &diff_rows[starts[file]] {
+                diff_title.push_str(&format!(
+                    " · file {}/{}: {}",
+                    file + 1,
+                    starts.len(),
+                    header.trim_start_matches("File: ")
+                ));
+            }
+        }
+
+        let mut rows = vec![self.render_header(width)];
+        if width >= SIDE_BY_SIDE_WIDTH {
+            let diff_pane = render_pane(&diff_title, &diff_lines, diff_width, body_height);
+            let message_pane =
+                render_pane("Commit Message", &message_lines, width - diff_width, body_height);
+            rows.extend(
+                diff_pane
+                    .into_iter()
+                    .zip(message_pane)
+                    .map(|(left, right)| format!("{}{}", left, right)),
+            );
+        } else {
+            let message_height = (message_lines.len() + 2).clamp(4, body_height / 2);
+            rows.extend(render_pane(
+                &diff_title,
+                &diff_lines,
+                width,
+                body_height - message_height,
+            ));
+            rows.extend(render_pane("Commit Message", &message_lines, width, message_height));
+        }
+        rows.push(self.render_footer(width));
+        rows
+    }
+
+    fn render_header(&self, width: usize) -> String {
+        let title = format!("🔮 {} v{}", self.program_name, self.program_version);
+        let settings = format!(
+            "gitmoji: {}  preset: {}",
+            if self.settings.use_gitmoji { "on" } else { "off" },
+            self.settings.preset
+        );
+        let gap = width.saturating_sub(title.width() + settings.width() + 2);
+        fit(
+            &format!(
+                " {}{}{} ",
+                ui::create_gradient_text(&title),
+                " ".repeat(gap),
+                settings.truecolor(147, 112, 219)
+            ),
+            width,
+        )
+    }
+
+    fn render_footer(&self, width: usize) -> String {
+        if !self.status.is_empty() {
+            return fit(&format!(" {}", self.status.yellow().bold()), width);
+        }
+        let hints = [
+            ("↑↓", "Scroll"),
+            ("Tab", "Next file"),
+            ("s", "Side by side"),
+            ("r", "Regenerate"),
+            ("e", "Edit"),
+            ("g", "Gitmoji"),
+            ("p/P", "Preset"),
+            ("Enter", "Commit"),
+            ("q", "Quit"),
+        ];
+        let line = hints
+            .iter()
+            .map(|(key, action)| {
+                format!("{} {}", key.bold(), action.truecolor(147, 112, 219))
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        fit(&format!(" {}", line), width)
+    }
+
+    fn perform_commit(&self) -> Result<bool> {
+        let spinner = ui::create_spinner("💫 Committing changes...");
+        let repo_path = std::env::current_dir()?;
+        let result = if self.commit_all {
+            git::commit_all(&repo_path, &self.message)
+        } else {
+            git::commit(&repo_path, &self.message)
+        };
+        spinner.finish_and_clear();
+
+        match result {
+            Ok(()) => {
+                ui::print_success("✨ Commit successful!");
+                Ok(true)
+            }
+            Err(e) => {
+                ui::print_error(&format!("Failed to commit: {}", e));
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// Switches the terminal to the alternate screen, restoring it when left or dropped
+struct AlternateScreen {
+    term: Term,
+    active: bool,
+}
+
+impl AlternateScreen {
+    fn enter(term: &Term) -> Result<Self> {
+        let mut term = term.clone();
+        write!(term, "{}", ENTER_ALTERNATE_SCREEN)?;
+        term.hide_cursor()?;
+        term.clear_screen()?;
+        Ok(AlternateScreen { term, active: true })
+    }
+
+    fn leave(&mut self) -> Result<()> {
+        if self.active {
+            self.active = false;
+            self.term.show_cursor()?;
+            write!(self.term, "{}", LEAVE_ALTERNATE_SCREEN)?;
+            self.term.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for AlternateScreen {
+    fn drop(&mut self) {
+        let _ = self.leave();
+    }
+}
+
+/// Draw a bordered pane with a title, `width` columns wide and `height` rows high
+fn render_pane(title: &str, lines: &[String], width: usize, height: usize) -> Vec<String> {
+    let border = |text: &str| text.truecolor(147, 112, 219).to_string();
+    let inner_width = width.saturating_sub(2);
+    let title = fit(&format!(" {} ", title), inner_width.saturating_sub(2));
+    let title_width = console::strip_ansi_codes(&title).width();
+
+    let mut rows = vec![format!(
+        "{}{}{}{}",
+        border("┏━"),
+        ui::create_secondary_gradient_text(&title),
+        border(&"━".repeat(inner_width.saturating_sub(title_width + 1))),
+        border("┓")
+    )];
+    for index in 0..height.saturating_sub(2) {
+        let line = lines.get(index).map(String::as_str).unwrap_or("");
+        rows.push(format!("{}{}{}", border("┃"), fit(line, inner_width), border("┃")));
+    }
+    rows.push(format!(
+        "{}{}{}",
+        border("┗"),
+        border(&"━".repeat(inner_width)),
+        border("┛")
+    ));
+    rows
+}
+
+/// Classify the lines of a diff made of `File: <path>` headers, each followed by its patch
+fn parse_diff(diff: &str) -> Vec<DiffLine> {
+    let mut language = Language::Plain;
+    let mut in_hunk = false;
+    diff.lines()
+        .map(|line| {
+            // Tabs have no display width of their own, so they would break the pane borders
+            let line = line.replace('\t', "    ");
+            if let Some(path) = line.strip_prefix("File: ") {
+                language = Language::from_path(path);
+                in_hunk = false;
+            } else if line.starts_with("diff --git") {
+                in_hunk = false;
+            } else if line.starts_with("@@") {
+                in_hunk = true;
+                return DiffLine::Hunk(line);
+            } else if let Some(marker) = line.chars().next().filter(|c| "+- ".contains(*c)) {
+                if in_hunk {
+                    return DiffLine::Code {
+                        marker,
+                        code: line[1..].to_string(),
+                        language,
+                    };
+                }
+            }
+            DiffLine::Meta(line)
+        })
+        .collect()
+}
+
+/// Pair each run of removed lines with the added lines that follow it
+fn side_by_side_rows(lines: &[DiffLine]) -> Vec<DiffRow> {
+    let mut rows = Vec::new();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    let flush = |rows: &mut Vec<DiffRow>,
+                 removed: &mut Vec<DiffLine>,
+                 added: &mut Vec<DiffLine>| {
+        let count = removed.len().max(added.len());
+        let mut removed = removed.drain(..);
+        let mut added = added.drain(..);
+        for _ in 0..count {
+            rows.push(DiffRow::Split(removed.next(), added.next()));
+        }
+    };
+    for line in lines {
+        match line {
+            DiffLine::Code { marker: '-', .. } => {
+                // A removal after additions starts a new change
+                if !added.is_empty() {
+                    flush(&mut rows, &mut removed, &mut added);
+                }
+                removed.push(line.clone());
+            }
+            DiffLine::Code { marker: '+', .. } => added.push(line.clone()),
+            DiffLine::Code { .. } => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push(DiffRow::Split(Some(line.clone()), Some(line.clone())));
+            }
+            _ => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push(DiffRow::Full(line.clone()));
+            }
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+/// Row indexes where each file's diff starts
+fn file_starts(rows: &[DiffRow]) -> Vec<usize> {
+    rows.iter()
+        .enumerate()
+        .filter(|(_, row)| {
+            matches!(row, DiffRow::Full(DiffLine::Meta(line)) if line.starts_with("File: "))
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// The position among `starts` of the file shown at the given row
+fn current_file(starts: &[usize], row: usize) -> Option<usize> {
+    starts.iter().rposition(|start| *start <= row)
+}
+
+/// Render a diff row as exactly `width` columns
+fn render_diff_row(row: &DiffRow, width: usize) -> String {
+    match row {
+        DiffRow::Full(line) => render_diff_line(line, width),
+        DiffRow::Split(left, right) => {
+            let left_width = width.saturating_sub(1) / 2;
+            let right_width = width.saturating_sub(left_width + 1);
+            let side = |line: &Option<DiffLine>, width: usize| match line {
+                Some(line) => render_diff_line(line, width),
+                None => " ".repeat(width),
+            };
+            format!(
+                "{}{}{}",
+                side(left, left_width),
+                "│".truecolor(147, 112, 219),
+                side(right, right_width)
+            )
+        }
+    }
+}
+
+/// Render a diff line as exactly `width` columns, highlighting the syntax of code
+fn render_diff_line(line: &DiffLine, width: usize) -> String {
+    let (marker, code, language) = match line {
+        DiffLine::Meta(text) if text.starts_with("File: ") => {
+            return fit(&text.bold().to_string(), width)
+        }
+        DiffLine::Meta(text) => return fit(&text.dimmed().to_string(), width),
+        DiffLine::Hunk(text) => return fit(&text.cyan().to_string(), width),
+        DiffLine::Code {
+            marker,
+            code,
+            language,
+        } => (*marker, code, *language),
+    };
+    if width == 0 {
+        return String::new();
+    }
+
+    let background = |text: ColoredString| match marker {
+        '+' => text.on_truecolor(20, 60, 30),
+        '-' => text.on_truecolor(70, 25, 30),
+        _ => text,
+    };
+    let mut rendered = match marker {
+        '+' => background(marker.to_string().green().bold()).to_string(),
+        '-' => background(marker.to_string().red().bold()).to_string(),
+        _ => marker.to_string(),
+    };
+    let mut used = 1;
+    for (kind, text) in tokenize(code, language) {
+        // Cut the code at the pane edge without breaking the styling of the last token
+        let mut visible = String::new();
+        let mut truncated = false;
+        for c in text.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if used + char_width > width {
+                truncated = true;
+                break;
+            }
+            visible.push(c);
+            used += char_width;
+        }
+        let styled = match kind {
+            TokenKind::Keyword => visible.truecolor(198, 120, 221).bold(),
+            TokenKind::String => visible.truecolor(229, 192, 123),
+            TokenKind::Comment => visible.truecolor(128, 128, 128).italic(),
+            TokenKind::Number => visible.truecolor(209, 154, 102),
+            TokenKind::Plain => visible.normal(),
+        };
+        rendered.push_str(&background(styled).to_string());
+        if truncated {
+            break;
+        }
+    }
+    let padding = background(" ".repeat(width - used).normal());
+    format!("{}{}", rendered, padding)
+}
+
+/// Cut or pad a line, which may contain ANSI codes, to exactly `width` columns
+fn fit(line: &str, width: usize) -> String {
+    let plain = console::strip_ansi_codes(line);
+    let plain_width = plain.width();
+    if plain_width <= width {
+        return format!("{}{}", line, " ".repeat(width - plain_width));
+    }
+
+    // Too wide: styling is dropped, since cutting through escape codes would garble it
+    let mut fitted = String::new();
+    let mut used = 0;
+    for c in plain.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if used + char_width > width {
+            break;
+        }
+        fitted.push(c);
+        used += char_width;
+    }
+    format!("{}{}", fitted, " ".repeat(width - used))
+}
+
+fn edit_in_editor(message: &str) -> Result<Option<String>> {
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(message.as_bytes())?;
+
+    let path = file.into_temp_path();
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vim".to_string());
+    let status = Command::new(editor).arg(&path).status()?;
+
+    if status.success() {
+        let edited = std::fs::read_to_string(&path)?;
+        log_debug!("✏️ Message edited in the TUI: {}", edited);
+        Ok(Some(edited))
+    } else {
+        Ok(None)
+    }
+}
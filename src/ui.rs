@@ -4,11 +4,16 @@ use indicatif::{ProgressBar, ProgressStyle};
 use std::time::Duration;
 
 pub fn create_spinner(message: &str) -> ProgressBar {
+    // Keep dry-run and CI output free of spinner frames
+    if crate::llm::is_dry_run() || crate::ci::is_ci() {
+        return ProgressBar::hidden();
+    }
+
     let pb = ProgressBar::new_spinner();
     pb.set_style(
         ProgressStyle::default_spinner()
             .tick_chars("✦✧✶✷✸✹✺✻✼✽")
-            .template("{spinner} {msg}")
+            .template("{spinner} {msg} {elapsed:.dim}")
             .unwrap(),
     );
     pb.set_message(message.to_string());
@@ -16,6 +21,25 @@ pub fn create_spinner(message: &str) -> ProgressBar {
     pb
 }
 
+/// Progress bar for work with a known number of steps, such as analyzing each commit of a range
+pub fn create_progress_bar(len: u64, message: &str) -> ProgressBar {
+    if crate::llm::is_dry_run() || crate::ci::is_ci() {
+        return ProgressBar::hidden();
+    }
+
+    let pb = ProgressBar::new(len);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .tick_chars("✦✧✶✷✸✹✺✻✼✽")
+            .template("{spinner} {msg} {bar:30.magenta/blue} {pos}/{len} {elapsed:.dim}")
+            .unwrap()
+            .progress_chars("━╸─"),
+    );
+    pb.set_message(message.to_string());
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb
+}
+
 pub fn print_info(message: &str) {
     println!("{}", message.cyan().bold());
 }
@@ -0,0 +1,109 @@
+use crate::git;
+use crate::rewrite::check_not_pushed;
+use anyhow::{anyhow, Result};
+use git2::{Oid, Repository, RepositoryState, ResetType};
+use std::path::Path;
+
+/// Notes reference marking the commits `gen --auto-commit` created
+pub const AUTO_COMMIT_NOTES_REF: &str = "refs/notes/iris-auto-commit";
+
+const MARKER_HEADER: &str = "Created by git-iris gen --auto-commit";
+const INDEX_PREFIX: &str = "Index: ";
+
+/// A commit that `undo` removed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoneCommit {
+    pub commit_id: String,
+    pub summary: String,
+}
+
+/// Mark a commit as created by `gen --auto-commit`
+///
+/// `index_tree` is the tree the index held before the commit, which differs from the commit's
+/// own tree when `--all` staged changes for it.
+pub fn record_auto_commit(repo_path: &Path, commit: &str, index_tree: &str) -> Result<()> {
+    let repo = Repository::open(repo_path)?;
+    let oid = repo.revparse_single(commit)?.peel_to_commit()?.id();
+    let signature = repo.signature()?;
+    repo.note(
+        &signature,
+        &signature,
+        Some(AUTO_COMMIT_NOTES_REF),
+        oid,
+        &format!("{}\n{}{}\n", MARKER_HEADER, INDEX_PREFIX, index_tree),
+        true,
+    )?;
+    Ok(())
+}
+
+/// The index tree recorded for a commit created by `gen --auto-commit`, or `None` if another
+/// tool or person created the commit
+pub fn auto_commit_index_tree(repo_path: &Path, commit: &str) -> Result<Option<String>> {
+    let repo = Repository::open(repo_path)?;
+    let oid = repo.revparse_single(commit)?.peel_to_commit()?.id();
+    let note = match repo.find_note(Some(AUTO_COMMIT_NOTES_REF), oid) {
+        Ok(note) => note.message().unwrap_or_default().to_string(),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    if !note.starts_with(MARKER_HEADER) {
+        return Ok(None);
+    }
+    Ok(note
+        .lines()
+        .find_map(|line| line.strip_prefix(INDEX_PREFIX))
+        .map(|tree| tree.trim().to_string()))
+}
+
+/// Soft-reset the last commit if `gen --auto-commit` created it, restoring the index to what it
+/// held before the commit
+///
+/// Commits already on a remote-tracking branch are refused unless `force` is set.
+pub fn undo_auto_commit(repo_path: &Path, force: bool) -> Result<UndoneCommit> {
+    let repo = Repository::open(repo_path)?;
+    if repo.state() != RepositoryState::Clean {
+        return Err(anyhow!(
+            "Finish or abort the operation in progress before undoing a commit"
+        ));
+    }
+
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|_| anyhow!("There is no commit to undo"))?;
+    let commit_id = head.id().to_string();
+    let Some(index_tree) = auto_commit_index_tree(repo_path, &commit_id)? else {
+        return Err(anyhow!(
+            "The last commit ({}) was not created by 'git-iris gen --auto-commit'; nothing to undo",
+            &commit_id[..7]
+        ));
+    };
+    let parent = head
+        .parents()
+        .next()
+        .ok_or_else(|| anyhow!("The last commit has no parent to reset to"))?;
+
+    if git::has_staged_changes(repo_path)? {
+        return Err(anyhow!(
+            "Changes were staged after the commit. Commit or unstage them before undoing"
+        ));
+    }
+    if !force {
+        check_not_pushed(repo_path, std::slice::from_ref(&commit_id))?;
+    }
+
+    let index_tree = repo.find_tree(Oid::from_str(&index_tree)?)?;
+    repo.reset(parent.as_object(), ResetType::Soft, None)?;
+    let mut index = repo.index()?;
+    index.read_tree(&index_tree)?;
+    index.write()?;
+
+    // The commit is no longer on the branch, so its marker goes too
+    let signature = repo.signature()?;
+    repo.note_delete(head.id(), Some(AUTO_COMMIT_NOTES_REF), &signature, &signature)?;
+
+    Ok(UndoneCommit {
+        summary: head.summary().unwrap_or_default().to_string(),
+        commit_id,
+    })
+}
@@ -0,0 +1,139 @@
+use crate::config::Config;
+use crate::context::CommitContext;
+use crate::exclusions::PromptFilter;
+use crate::git;
+use crate::llm;
+use crate::llm_providers::LLMProviderType;
+use anyhow::{anyhow, Context, Result};
+use std::path::Path;
+
+/// Prefix of the subject line of work-in-progress commits
+pub const WIP_PREFIX: &str = "wip: ";
+
+/// Summary of the uncommitted work in a repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WipSummary {
+    /// One-line description of the work, without the `wip:` prefix
+    pub subject: String,
+    /// Short narrative of what was done and what looks unfinished
+    pub narrative: String,
+}
+
+impl WipSummary {
+    /// Format the summary as a `wip:`-prefixed commit message
+    pub fn to_commit_message(&self) -> String {
+        if self.narrative.is_empty() {
+            format!("{}{}", WIP_PREFIX, self.subject)
+        } else {
+            format!("{}{}\n\n{}", WIP_PREFIX, self.subject, self.narrative)
+        }
+    }
+}
+
+pub struct WipSummaryGenerator;
+
+impl WipSummaryGenerator {
+    /// Summarize every uncommitted change, staged, unstaged, or untracked
+    pub async fn generate(repo_path: &Path, config: &Config) -> Result<WipSummary> {
+        let filter = PromptFilter::from_config(config);
+        let context = git::get_working_tree_info(repo_path, true, &filter)?;
+        if context.staged_files.is_empty() {
+            return Err(anyhow!("No work in progress: the working tree is clean"));
+        }
+
+        let mut system_prompt = create_wip_system_prompt();
+        let effective_instructions = config.get_effective_instructions();
+        if !effective_instructions.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nAdditional instructions:\n{}",
+                effective_instructions
+            ));
+        }
+
+        let user_prompt = create_wip_user_prompt(&context);
+
+        let provider_type: LLMProviderType = config
+            .default_provider
+            .parse()
+            .context("Failed to parse default provider")?;
+
+        let response =
+            llm::get_refined_message(config, &provider_type, &system_prompt, &user_prompt, None)
+                .await
+                .context("Failed to generate work-in-progress summary")?;
+
+        let summary = parse_wip_summary(&response);
+        if summary.subject.is_empty() {
+            return Err(anyhow!("The model did not generate a summary"));
+        }
+        Ok(summary)
+    }
+}
+
+pub fn create_wip_system_prompt() -> String {
+    String::from(
+        "You are an AI assistant specialized in describing unfinished work in Git repositories. \
+        Your task is to summarize the uncommitted changes so the developer, or a teammate taking over, can pick the work up later.
+
+        Work step-by-step and follow these guidelines exactly:
+
+        1. Start with a single summary line of at most 60 characters describing what the work is about.
+        2. Do not start the summary line with 'WIP' or 'wip:'; the prefix is added for you.
+        3. After a blank line, write a short narrative of two to five sentences.
+        4. In the narrative, describe what has been done so far, grouped by area when the changes touch several.
+        5. Point out anything that looks unfinished, such as new TODO comments, stubs, or code without tests.
+        6. Mention new, untracked files explicitly, since they are easy to forget.
+        7. Use plain prose in the first person plural or the imperative; do not use Markdown headings.
+        8. Only describe what the changes show; do not speculate about intent.
+        9. NO YAPPING!
+
+        Generate only the summary line and the narrative, without any quotes, explanations, or code fences.",
+    )
+}
+
+pub fn create_wip_user_prompt(context: &CommitContext) -> String {
+    let mut prompt = String::from(
+        "Based on the following uncommitted changes, summarize the work in progress:\n\n",
+    );
+
+    prompt.push_str(&format!("Branch: {}\n\n", context.branch));
+
+    prompt.push_str("Changes:\n");
+    for file in &context.staged_files {
+        prompt.push_str(&format!("- {} ({})\n", file.path, file.change_type));
+        for analysis in &file.analysis {
+            prompt.push_str(&format!("  - {}\n", analysis));
+        }
+    }
+
+    prompt.push_str("\nDiffs:\n");
+    for file in &context.staged_files {
+        prompt.push_str(&format!("File: {}\n{}\n", file.path, file.diff));
+    }
+
+    prompt
+}
+
+/// Split the model response into the summary line and the narrative
+pub fn parse_wip_summary(response: &str) -> WipSummary {
+    let response = response.trim().trim_matches('`').trim();
+    let (first, rest) = response.split_once('\n').unwrap_or((response, ""));
+
+    let first = first.trim().trim_matches(['"', '\'']).trim();
+    let subject = strip_wip_prefix(first).trim_end_matches('.').to_string();
+
+    WipSummary {
+        subject,
+        narrative: rest.trim().to_string(),
+    }
+}
+
+fn strip_wip_prefix(line: &str) -> &str {
+    let lower = line.to_lowercase();
+    for prefix in ["wip:", "wip -", "wip "] {
+        if lower.starts_with(prefix) {
+            return line[prefix.len()..].trim_start();
+        }
+    }
+    line
+}
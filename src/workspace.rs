@@ -0,0 +1,242 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+
+/// A crate or package that belongs to a workspace
+#[derive(Debug, Clone)]
+pub struct WorkspaceMember {
+    pub name: String,
+    /// Directory of the member relative to the repository root, using `/` separators
+    pub dir: String,
+    /// Names of other workspace members this member depends on
+    pub dependencies: Vec<String>,
+}
+
+/// The members of a Cargo or npm workspace and the dependencies between them
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceGraph {
+    pub members: Vec<WorkspaceMember>,
+}
+
+/// A changed workspace member and the members that depend on it, directly or transitively
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct WorkspaceImpact {
+    pub member: String,
+    pub dependents: Vec<String>,
+}
+
+impl WorkspaceGraph {
+    /// Load the workspace rooted at `repo_path`, if there is one
+    pub fn load(repo_path: &Path) -> Option<WorkspaceGraph> {
+        load_cargo_workspace(repo_path)
+            .or_else(|| load_npm_workspace(repo_path))
+            .filter(|graph| graph.members.len() > 1)
+    }
+
+    /// Find the member that owns the given repository-relative path
+    pub fn member_for_path(&self, path: &str) -> Option<&WorkspaceMember> {
+        self.members
+            .iter()
+            .filter(|member| {
+                member.dir.is_empty()
+                    || path == member.dir
+                    || path.starts_with(&format!("{}/", member.dir))
+            })
+            .max_by_key(|member| member.dir.len())
+    }
+
+    /// Walk the reverse dependency graph from every member touched by `changed_paths`
+    pub fn impact_of<'a, I>(&self, changed_paths: I) -> Vec<WorkspaceImpact>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+        for member in &self.members {
+            for dependency in &member.dependencies {
+                dependents
+                    .entry(dependency.as_str())
+                    .or_default()
+                    .push(member.name.as_str());
+            }
+        }
+
+        let mut changed_members: Vec<&str> = changed_paths
+            .into_iter()
+            .filter_map(|path| self.member_for_path(path))
+            .map(|member| member.name.as_str())
+            .collect();
+        changed_members.sort();
+        changed_members.dedup();
+
+        changed_members
+            .into_iter()
+            .map(|member| {
+                let mut seen = HashSet::new();
+                let mut queue = VecDeque::from([member]);
+                while let Some(current) = queue.pop_front() {
+                    for &dependent in dependents.get(current).into_iter().flatten() {
+                        if dependent != member && seen.insert(dependent) {
+                            queue.push_back(dependent);
+                        }
+                    }
+                }
+
+                let mut affected: Vec<String> = seen.into_iter().map(str::to_string).collect();
+                affected.sort();
+                WorkspaceImpact {
+                    member: member.to_string(),
+                    dependents: affected,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Format workspace impact for inclusion in a prompt
+pub fn format_workspace_impact(impacts: &[WorkspaceImpact]) -> String {
+    impacts
+        .iter()
+        .map(|impact| {
+            if impact.dependents.is_empty() {
+                format!("{} (no dependent workspace members)", impact.member)
+            } else {
+                format!(
+                    "{} -> affects {}",
+                    impact.member,
+                    impact.dependents.join(", ")
+                )
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn load_cargo_workspace(repo_path: &Path) -> Option<WorkspaceGraph> {
+    let root: toml::Value = fs::read_to_string(repo_path.join("Cargo.toml"))
+        .ok()?
+        .parse()
+        .ok()?;
+    let patterns: Vec<String> = root
+        .get("workspace")?
+        .get("members")?
+        .as_array()?
+        .iter()
+        .filter_map(|m| m.as_str().map(str::to_string))
+        .collect();
+
+    let mut manifests = Vec::new();
+    for dir in expand_member_patterns(repo_path, &patterns) {
+        let manifest_path = repo_path.join(&dir).join("Cargo.toml");
+        let Some(manifest) = fs::read_to_string(manifest_path)
+            .ok()
+            .and_then(|content| content.parse::<toml::Value>().ok())
+        else {
+            continue;
+        };
+        let Some(name) = manifest
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+        else {
+            continue;
+        };
+        manifests.push((name.to_string(), dir, manifest));
+    }
+
+    let names: HashSet<String> = manifests.iter().map(|(name, _, _)| name.clone()).collect();
+    let members = manifests
+        .into_iter()
+        .map(|(name, dir, manifest)| {
+            let mut dependencies: Vec<String> =
+                ["dependencies", "dev-dependencies", "build-dependencies"]
+                    .iter()
+                    .filter_map(|section| manifest.get(section).and_then(|s| s.as_table()))
+                    .flat_map(|table| table.keys().cloned())
+                    .filter(|dep| names.contains(dep))
+                    .collect();
+            dependencies.sort();
+            dependencies.dedup();
+            WorkspaceMember {
+                name,
+                dir,
+                dependencies,
+            }
+        })
+        .collect();
+
+    Some(WorkspaceGraph { members })
+}
+
+fn load_npm_workspace(repo_path: &Path) -> Option<WorkspaceGraph> {
+    let root: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(repo_path.join("package.json")).ok()?).ok()?;
+    // Both `"workspaces": [...]` and `"workspaces": { "packages": [...] }` are valid
+    let workspaces = root
+        .get("workspaces")
+        .and_then(|w| w.as_array().or_else(|| w.get("packages")?.as_array()))?;
+    let patterns: Vec<String> = workspaces
+        .iter()
+        .filter_map(|w| w.as_str().map(str::to_string))
+        .collect();
+
+    let mut packages = Vec::new();
+    for dir in expand_member_patterns(repo_path, &patterns) {
+        let Some(package) = fs::read_to_string(repo_path.join(&dir).join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        else {
+            continue;
+        };
+        let Some(name) = package["name"].as_str() else {
+            continue;
+        };
+        packages.push((name.to_string(), dir, package));
+    }
+
+    let names: HashSet<String> = packages.iter().map(|(name, _, _)| name.clone()).collect();
+    let members = packages
+        .into_iter()
+        .map(|(name, dir, package)| {
+            let mut dependencies: Vec<String> =
+                ["dependencies", "devDependencies", "peerDependencies"]
+                    .iter()
+                    .filter_map(|section| package[section].as_object())
+                    .flat_map(|deps| deps.keys().cloned())
+                    .filter(|dep| names.contains(dep))
+                    .collect();
+            dependencies.sort();
+            dependencies.dedup();
+            WorkspaceMember {
+                name,
+                dir,
+                dependencies,
+            }
+        })
+        .collect();
+
+    Some(WorkspaceGraph { members })
+}
+
+/// Expand workspace member patterns; only trailing `*` globs (e.g. `crates/*`) are supported
+fn expand_member_patterns(repo_path: &Path, patterns: &[String]) -> Vec<String> {
+    let mut dirs = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim_end_matches('/');
+        if let Some(parent) = pattern.strip_suffix("/*") {
+            if let Ok(entries) = fs::read_dir(repo_path.join(parent)) {
+                for entry in entries.filter_map(|e| e.ok()) {
+                    if entry.path().is_dir() {
+                        dirs.push(format!("{}/{}", parent, entry.file_name().to_string_lossy()));
+                    }
+                }
+            }
+        } else if pattern == "." {
+            dirs.push(String::new());
+        } else {
+            dirs.push(pattern.to_string());
+        }
+    }
+    dirs.sort();
+    dirs
+}
@@ -0,0 +1,133 @@
+use git2::Repository;
+use git_iris::blame::{
+    create_blame_user_prompt, get_line_history, parse_line_target, read_lines_at_head,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn commit_file(repo: &Repository, path: &str, content: &str, message: &str) {
+    let workdir = repo.workdir().unwrap();
+    fs::write(workdir.join(path), content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(path)).unwrap();
+    index.write().unwrap();
+    commit_index(repo, message);
+}
+
+fn commit_index(repo: &Repository, message: &str) {
+    let mut index = repo.index().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .unwrap();
+}
+
+fn setup_repo() -> (TempDir, Repository) {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+
+    commit_file(&repo, "lib.rs", "a\nb\nc\nd\ne\n", "Add lib");
+    commit_file(&repo, "lib.rs", "a\nb\nC\nd\ne\n", "Change c");
+    commit_file(&repo, "lib.rs", "x\ny\na\nb\nC\nd\ne\n", "Add header");
+    commit_file(&repo, "lib.rs", "x\ny\na\nb\nC\nd\nE\n", "Change e");
+
+    (temp_dir, repo)
+}
+
+fn messages(repo_path: &Path, path: &str, start: usize, end: usize) -> Vec<String> {
+    get_line_history(repo_path, path, start, end, 10)
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.change.commit_message.trim().to_string())
+        .collect()
+}
+
+#[test]
+fn test_parse_line_target() {
+    assert_eq!(
+        parse_line_target("src/main.rs:42").unwrap(),
+        ("src/main.rs".to_string(), 42, 42)
+    );
+    assert_eq!(
+        parse_line_target("./src/main.rs:10-20").unwrap(),
+        ("src/main.rs".to_string(), 10, 20)
+    );
+    assert!(parse_line_target("src/main.rs").is_err());
+    assert!(parse_line_target("src/main.rs:0").is_err());
+    assert!(parse_line_target("src/main.rs:20-10").is_err());
+    assert!(parse_line_target(":5").is_err());
+}
+
+#[test]
+fn test_read_lines_at_head() {
+    let (temp_dir, _repo) = setup_repo();
+
+    assert_eq!(read_lines_at_head(temp_dir.path(), "lib.rs", 5, 5).unwrap(), "C");
+    assert_eq!(
+        read_lines_at_head(temp_dir.path(), "lib.rs", 1, 3).unwrap(),
+        "x\ny\na"
+    );
+    assert!(read_lines_at_head(temp_dir.path(), "lib.rs", 7, 8).is_err());
+    assert!(read_lines_at_head(temp_dir.path(), "missing.rs", 1, 1).is_err());
+}
+
+#[test]
+fn test_line_history_follows_shifted_lines() {
+    let (temp_dir, _repo) = setup_repo();
+
+    // Line 5 is "C", which moved down two lines when the header was added
+    assert_eq!(
+        messages(temp_dir.path(), "lib.rs", 5, 5),
+        vec!["Change c", "Add lib"]
+    );
+}
+
+#[test]
+fn test_line_history_stops_where_lines_were_added() {
+    let (temp_dir, _repo) = setup_repo();
+
+    assert_eq!(messages(temp_dir.path(), "lib.rs", 1, 2), vec!["Add header"]);
+    assert_eq!(
+        messages(temp_dir.path(), "lib.rs", 7, 7),
+        vec!["Change e", "Add lib"]
+    );
+}
+
+#[test]
+fn test_line_history_respects_max_commits() {
+    let (temp_dir, _repo) = setup_repo();
+
+    let history = get_line_history(temp_dir.path(), "lib.rs", 1, 7, 2).unwrap();
+    assert_eq!(history.len(), 2);
+}
+
+#[test]
+fn test_line_history_follows_renames() {
+    let (temp_dir, repo) = setup_repo();
+
+    fs::rename(temp_dir.path().join("lib.rs"), temp_dir.path().join("core.rs")).unwrap();
+    let mut index = repo.index().unwrap();
+    index.remove_path(Path::new("lib.rs")).unwrap();
+    index.add_path(Path::new("core.rs")).unwrap();
+    index.write().unwrap();
+    commit_index(&repo, "Rename lib");
+
+    let history = get_line_history(temp_dir.path(), "core.rs", 5, 5, 10).unwrap();
+    let paths: Vec<&str> = history.iter().map(|entry| entry.path.as_str()).collect();
+    assert_eq!(paths, vec!["lib.rs", "lib.rs"]);
+    assert!(history[0].hunks.contains("+C"));
+}
+
+#[test]
+fn test_blame_user_prompt_lists_commits_oldest_first() {
+    let (temp_dir, _repo) = setup_repo();
+
+    let history = get_line_history(temp_dir.path(), "lib.rs", 5, 5, 10).unwrap();
+    let prompt = create_blame_user_prompt("lib.rs", 5, 5, "C", &history);
+
+    assert!(prompt.contains("lines 5-5 of lib.rs"));
+    assert!(prompt.find("Message: Add lib").unwrap() < prompt.find("Message: Change c").unwrap());
+}
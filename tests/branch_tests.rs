@@ -0,0 +1,63 @@
+use git2::Repository;
+use git_iris::branch::{create_branch_name_system_prompt, parse_branch_names};
+use git_iris::git::{create_and_checkout_branch, get_current_branch_name};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+#[test]
+fn test_parse_branch_names() {
+    let response = "```\n1. feat/Add OAuth Login\n2) fix/handle_empty_diff\n- `chore/bump-deps`\nfeat/add-oauth-login\n42-add-login\n```";
+    assert_eq!(
+        parse_branch_names(response),
+        vec![
+            "feat/add-oauth-login".to_string(),
+            "fix/handle-empty-diff".to_string(),
+            "chore/bump-deps".to_string(),
+        ]
+    );
+
+    // Names that start with an issue number are kept intact
+    assert_eq!(
+        parse_branch_names("42-add-login\nfeat//--trailing--"),
+        vec!["42-add-login".to_string(), "feat/trailing".to_string()]
+    );
+    assert!(parse_branch_names("").is_empty());
+}
+
+#[test]
+fn test_branch_name_system_prompt_uses_pattern() {
+    let prompt = create_branch_name_system_prompt("{type}/{issue}-{description}");
+    assert!(prompt.contains("Follow this pattern exactly: {type}/{issue}-{description}"));
+    assert!(prompt.contains("Suggest exactly 3 branch names"));
+}
+
+#[test]
+fn test_create_and_checkout_branch() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+
+    fs::write(temp_dir.path().join("file.txt"), "content").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("file.txt")).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+        .unwrap();
+
+    // Uncommitted changes survive the switch
+    fs::write(temp_dir.path().join("file.txt"), "changed").unwrap();
+
+    create_and_checkout_branch(temp_dir.path(), "feat/new-thing").unwrap();
+    assert_eq!(
+        get_current_branch_name(temp_dir.path()).unwrap(),
+        "feat/new-thing"
+    );
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("file.txt")).unwrap(),
+        "changed"
+    );
+
+    assert!(create_and_checkout_branch(temp_dir.path(), "feat/new-thing").is_err());
+}
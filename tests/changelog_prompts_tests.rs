@@ -24,6 +24,7 @@ fn create_mock_analyzed_change() -> AnalyzedChange {
             new_path: "src/new.rs".to_string(),
             change_type: ChangeType::Modified,
             analysis: vec!["Modified function: process_data".to_string()],
+            risk_labels: vec![],
         }],
         metrics: ChangeMetrics {
             files_changed: 1,
@@ -58,7 +59,8 @@ fn test_create_changelog_user_prompt() {
         "v1.0.0",
         "v1.1.0",
         readme_summary,
-    );
+    )
+    .unwrap();
     assert!(minimal_prompt.contains("Based on the following changes from v1.0.0 to v1.1.0"));
     assert!(minimal_prompt.contains("Overall Changes:"));
     assert!(minimal_prompt.contains("Total commits: 1"));
@@ -80,7 +82,8 @@ fn test_create_changelog_user_prompt() {
         "v1.0.0",
         "v1.1.0",
         readme_summary,
-    );
+    )
+    .unwrap();
     assert!(standard_prompt.contains("File changes summary:"));
     assert!(standard_prompt.contains("src/new.rs (Modified)"));
     assert!(standard_prompt.contains("generate a comprehensive changelog"));
@@ -92,7 +95,8 @@ fn test_create_changelog_user_prompt() {
         "v1.0.0",
         "v1.1.0",
         readme_summary,
-    );
+    )
+    .unwrap();
     assert!(detailed_prompt.contains("Detailed file changes:"));
     assert!(detailed_prompt.contains("Modified function: process_data"));
     assert!(detailed_prompt.contains("generate a highly detailed changelog"));
@@ -122,7 +126,8 @@ fn test_create_release_notes_user_prompt() {
         "v1.0.0",
         "v1.1.0",
         readme_summary,
-    );
+    )
+    .unwrap();
     assert!(minimal_prompt
         .contains("Based on the following changelog for changes from v1.0.0 to v1.1.0"));
     assert!(minimal_prompt.contains("generate concise release notes"));
@@ -137,7 +142,8 @@ fn test_create_release_notes_user_prompt() {
         "v1.0.0",
         "v1.1.0",
         readme_summary,
-    );
+    )
+    .unwrap();
     assert!(standard_prompt.contains("generate comprehensive release notes"));
     assert!(standard_prompt.contains("Provide a balanced overview"));
 
@@ -148,7 +154,8 @@ fn test_create_release_notes_user_prompt() {
         "v1.0.0",
         "v1.1.0",
         readme_summary,
-    );
+    )
+    .unwrap();
     assert!(detailed_prompt.contains("generate highly detailed release notes"));
     assert!(detailed_prompt.contains("Include detailed explanations"));
 }
@@ -157,7 +164,8 @@ fn test_create_release_notes_user_prompt() {
 fn test_changelog_user_prompt_without_readme() {
     let changes = vec![create_mock_analyzed_change()];
     let prompt =
-        create_changelog_user_prompt(&changes, DetailLevel::Standard, "v1.0.0", "v1.1.0", None);
+        create_changelog_user_prompt(&changes, DetailLevel::Standard, "v1.0.0", "v1.1.0", None)
+            .unwrap();
 
     assert!(!prompt.contains("Project README Summary:"));
     assert!(prompt.contains("Based on the following changes from v1.0.0 to v1.1.0"));
@@ -174,7 +182,8 @@ fn test_release_notes_user_prompt_without_readme() {
         "v1.0.0",
         "v1.1.0",
         None,
-    );
+    )
+    .unwrap();
 
     assert!(!prompt.contains("Project README Summary:"));
     assert!(prompt.contains("Based on the following changelog for changes from v1.0.0 to v1.1.0"));
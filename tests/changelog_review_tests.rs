@@ -0,0 +1,60 @@
+use git_iris::changelog_review::ChangelogDocument;
+
+const CHANGELOG: &str = "\
+Total commits: 4, files changed: 9
+
+## Features
+
+- Add the parser (abc1234)
+- Add streaming output (def5678)
+  Large responses are printed as they arrive
+
+## Bug Fixes
+
+- Fix the crash on empty input (0123abc)
+
+## Risk Notes
+
+Review these before upgrading.
+
+- Schema migration in the cache (4567def)
+";
+
+#[test]
+fn test_parse_and_render_round_trip() {
+    let document = ChangelogDocument::parse(CHANGELOG);
+    assert_eq!(document.intro, vec!["Total commits: 4, files changed: 9"]);
+    assert_eq!(document.sections.len(), 3);
+    assert_eq!(document.sections[0].heading, "## Features");
+    assert_eq!(
+        document.sections[0].entries[1],
+        "- Add streaming output (def5678)\n  Large responses are printed as they arrive"
+    );
+    assert_eq!(document.sections[2].body, vec!["Review these before upgrading."]);
+    assert_eq!(document.render(), CHANGELOG.trim());
+}
+
+#[test]
+fn test_edit_reorder_and_drop_entries() {
+    let mut document = ChangelogDocument::parse(CHANGELOG);
+
+    assert_eq!(document.move_entry(0, 1, true), 0);
+    assert!(document.sections[0].entries[0].starts_with("- Add streaming output"));
+    // Moving past either end leaves the entry in place
+    assert_eq!(document.move_entry(0, 0, true), 0);
+    assert_eq!(document.move_entry(0, 1, false), 1);
+
+    document.replace_entry(0, 1, "- Add the parser for TOML files (abc1234)\n");
+    assert_eq!(document.sections[0].entries[1], "- Add the parser for TOML files (abc1234)");
+
+    // Dropping the last entry of a section removes its heading too
+    document.drop_entry(1, 0);
+    assert_eq!(document.sections.len(), 2);
+    assert!(!document.render().contains("Bug Fixes"));
+
+    // Sections with their own text stay, and empty edits drop the entry
+    document.replace_entry(1, 0, "  \n");
+    assert_eq!(document.sections.len(), 2);
+    assert!(document.sections[1].entries.is_empty());
+    assert!(document.render().ends_with("Review these before upgrading."));
+}
@@ -1,8 +1,10 @@
 use anyhow::Result;
 use git2::Repository;
-use git_iris::changelog::{ChangelogGenerator, DetailLevel, ReleaseNotesGenerator};
+use git_iris::changelog::{ChangelogGenerator, DetailLevel, OutputFormat, ReleaseNotesGenerator};
 use git_iris::config::Config;
+use git_iris::config_validation::validate_config;
 use std::path::Path;
+use std::str::FromStr;
 use tempfile::TempDir;
 
 fn setup_test_repo() -> Result<(TempDir, Repository)> {
@@ -137,3 +139,61 @@ fn test_detail_level_from_str() {
     );
     assert!(DetailLevel::from_str("invalid").is_err());
 }
+
+#[test]
+fn test_output_format_render() {
+    assert_eq!(OutputFormat::from_str("md").unwrap(), OutputFormat::Markdown);
+    assert!(OutputFormat::from_str("html").is_err());
+
+    let markdown = OutputFormat::Markdown
+        .render("## Added\n\n- Export\n\n", "v1.0.0", "HEAD")
+        .unwrap();
+    assert_eq!(markdown, "## Added\n\n- Export\n");
+
+    let json = OutputFormat::Json
+        .render("## Added", "v1.0.0", "HEAD")
+        .unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["from"], "v1.0.0");
+    assert_eq!(value["to"], "HEAD");
+    assert_eq!(value["content"], "## Added");
+}
+
+#[test]
+fn test_release_text_defaults_are_read_from_config_file() {
+    let config: Config = toml::from_str(
+        r#"
+        default_provider = "openai"
+        providers = {}
+
+        [changelog]
+        detail_level = "detailed"
+        output = "CHANGELOG-draft.md"
+
+        [release_notes]
+        format = "json"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.changelog.detail_level, "detailed");
+    assert_eq!(config.changelog.format, "markdown");
+    assert_eq!(config.changelog.output.as_deref(), Some("CHANGELOG-draft.md"));
+    assert_eq!(config.release_notes.detail_level, "standard");
+    assert_eq!(config.release_notes.format, "json");
+    assert!(config.release_notes.output.is_none());
+}
+
+#[test]
+fn test_validation_flags_unknown_release_text_defaults() {
+    let mut config = Config::default();
+    config.changelog.detail_level = "verbose".to_string();
+    config.release_notes.format = "html".to_string();
+
+    let keys: Vec<String> = validate_config(&config)
+        .into_iter()
+        .map(|problem| problem.key)
+        .collect();
+    assert!(keys.contains(&"changelog.detail_level".to_string()));
+    assert!(keys.contains(&"release_notes.format".to_string()));
+}
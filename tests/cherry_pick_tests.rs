@@ -0,0 +1,147 @@
+use git2::Repository;
+use git_iris::cherry_pick::{
+    create_cherry_pick_user_prompt, ensure_cherry_pick_trailer, is_cherry_pick_in_progress,
+    CherryPickContext,
+};
+use git_iris::exclusions::PromptFilter;
+use git_iris::hooks::{hook_action_in_repo, HookAction};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn commit_file(repo: &Repository, repo_path: &Path, content: &str, message: &str) -> git2::Oid {
+    fs::write(repo_path.join("app.txt"), content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("app.txt")).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = repo.signature().unwrap();
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => Vec::new(),
+    };
+    let parents: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .unwrap()
+}
+
+fn checkout(repo: &Repository, branch: &str) {
+    repo.set_head(&format!("refs/heads/{}", branch)).unwrap();
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .unwrap();
+}
+
+/// A repository where 'feature' fixes app.txt and the release branch changed the same line
+fn setup_repo() -> (TempDir, Repository, String) {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+    let path = temp_dir.path();
+
+    let base = commit_file(&repo, path, "version 1\n", "Initial commit");
+    for branch in ["feature", "release"] {
+        repo.branch(branch, &repo.find_commit(base).unwrap(), false)
+            .unwrap();
+    }
+
+    checkout(&repo, "feature");
+    let fix = commit_file(
+        &repo,
+        path,
+        "version 1 without the crash\n",
+        "Fix crash on empty input\n\nFixes #12",
+    );
+
+    checkout(&repo, "release");
+    commit_file(&repo, path, "version 1.0\n", "Prepare release 1.0");
+
+    (temp_dir, repo, fix.to_string())
+}
+
+#[test]
+fn test_ensure_cherry_pick_trailer() {
+    let id = "0123456789abcdef0123456789abcdef01234567";
+    let expected = format!(
+        "Fix crash on empty input\n\nBackport to release.\n\n(cherry picked from commit {})",
+        id
+    );
+
+    assert_eq!(
+        ensure_cherry_pick_trailer("Fix crash on empty input\n\nBackport to release.\n", id),
+        expected
+    );
+    assert_eq!(
+        ensure_cherry_pick_trailer(
+            "Fix crash on empty input\n\nBackport to release.\n\n(cherry picked from commit 0123456)",
+            id
+        ),
+        expected
+    );
+}
+
+#[test]
+fn test_load_context_for_commit() {
+    let (temp_dir, _repo, fix) = setup_repo();
+
+    let context =
+        CherryPickContext::load(temp_dir.path(), Some("feature"), &PromptFilter::default()).unwrap();
+    assert_eq!(context.commit_id, fix);
+    assert_eq!(context.target, "release");
+    assert_eq!(context.source_branches, vec!["feature".to_string()]);
+    assert_eq!(
+        context.target_history,
+        vec!["Prepare release 1.0".to_string(), "Initial commit".to_string()]
+    );
+
+    let prompt = create_cherry_pick_user_prompt(&context, &context.changes);
+    assert!(prompt.contains("Source branch: feature\nTarget branch: release"));
+    assert!(prompt.contains("Original message:\nFix crash on empty input\n\nFixes #12"));
+    assert!(prompt.contains("- Prepare release 1.0"));
+    assert!(prompt.contains("- app.txt (Modified)"));
+}
+
+#[test]
+fn test_hook_adapts_default_cherry_pick_message() {
+    let (temp_dir, repo, fix) = setup_repo();
+    let path = temp_dir.path();
+    assert!(!is_cherry_pick_in_progress(path));
+    assert!(CherryPickContext::load(path, None, &PromptFilter::default()).is_err());
+
+    let commit = repo.find_commit(git2::Oid::from_str(&fix).unwrap()).unwrap();
+    repo.cherrypick(&commit, None).unwrap();
+    assert!(is_cherry_pick_in_progress(path));
+    assert_eq!(
+        CherryPickContext::load(path, None, &PromptFilter::default())
+            .unwrap()
+            .commit_id,
+        fix
+    );
+
+    let message_file = path.join("COMMIT_EDITMSG");
+    fs::write(
+        &message_file,
+        "Fix crash on empty input\n\nFixes #12\n\n# Conflicts:\n#\tapp.txt\n",
+    )
+    .unwrap();
+    assert_eq!(
+        hook_action_in_repo(path, &message_file, Some("merge")),
+        HookAction::CherryPickMessage
+    );
+    assert_eq!(
+        hook_action_in_repo(path, &message_file, Some("message")),
+        HookAction::CherryPickMessage
+    );
+    assert_eq!(
+        hook_action_in_repo(path, &message_file, Some("commit")),
+        HookAction::Skip
+    );
+
+    // A message written with `git commit -m` is kept
+    fs::write(&message_file, "Backport the empty input fix\n").unwrap();
+    assert_eq!(
+        hook_action_in_repo(path, &message_file, Some("message")),
+        HookAction::Skip
+    );
+}
@@ -0,0 +1,101 @@
+use anyhow::{anyhow, Context};
+use git_iris::ci::{
+    detect_ci_env, exit_code, failure, failure_kind, format_error_json, FailureKind, EXIT_ERROR,
+};
+use git_iris::config::{Config, ProviderConfig};
+use git_iris::llm::{get_refined_message, validate_provider_config};
+use git_iris::llm_providers::LLMProviderType;
+
+#[test]
+fn test_failure_kinds_have_distinct_exit_codes() {
+    let codes = [
+        EXIT_ERROR,
+        FailureKind::NoStagedChanges.exit_code(),
+        FailureKind::ConfigMissing.exit_code(),
+        FailureKind::ProviderFailure.exit_code(),
+    ];
+    for (i, code) in codes.iter().enumerate() {
+        assert!(*code != 0);
+        assert!(!codes[i + 1..].contains(code));
+    }
+}
+
+#[test]
+fn test_failure_kind_survives_added_context() {
+    let error = failure(FailureKind::ProviderFailure, anyhow!("Request timed out"));
+    assert_eq!(error.to_string(), "Request timed out");
+
+    let error: anyhow::Error = Err::<(), _>(error)
+        .context("Failed to generate changelog")
+        .unwrap_err();
+    assert_eq!(failure_kind(&error), Some(FailureKind::ProviderFailure));
+    assert_eq!(exit_code(&error), FailureKind::ProviderFailure.exit_code());
+}
+
+#[test]
+fn test_untagged_errors_use_the_generic_exit_code() {
+    let error = anyhow!("Something else went wrong");
+    assert_eq!(failure_kind(&error), None);
+    assert_eq!(exit_code(&error), EXIT_ERROR);
+}
+
+#[test]
+fn test_format_error_json() {
+    let error = failure(FailureKind::NoStagedChanges, anyhow!("No staged changes"));
+    let value: serde_json::Value = serde_json::from_str(&format_error_json(&error)).unwrap();
+
+    assert_eq!(value["error"]["kind"], "no_staged_changes");
+    assert_eq!(value["error"]["message"], "No staged changes");
+    assert_eq!(
+        value["error"]["exit_code"],
+        FailureKind::NoStagedChanges.exit_code()
+    );
+
+    let value: serde_json::Value =
+        serde_json::from_str(&format_error_json(&anyhow!("Boom"))).unwrap();
+    assert_eq!(value["error"]["kind"], "error");
+    assert_eq!(value["error"]["exit_code"], EXIT_ERROR);
+}
+
+#[test]
+fn test_missing_api_key_is_a_config_failure() {
+    let mut config = Config {
+        default_provider: "claude".to_string(),
+        ..Default::default()
+    };
+    config
+        .providers
+        .insert("claude".to_string(), ProviderConfig::default_for("claude"));
+
+    let error = validate_provider_config(&config, &LLMProviderType::Claude).unwrap_err();
+    assert_eq!(failure_kind(&error), Some(FailureKind::ConfigMissing));
+}
+
+#[tokio::test]
+async fn test_generation_without_api_key_fails_before_any_request() {
+    let mut config = Config::default();
+    config.providers.clear();
+
+    let error = get_refined_message(
+        &config,
+        &LLMProviderType::OpenAI,
+        "System prompt",
+        "User prompt",
+        None,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(failure_kind(&error), Some(FailureKind::ConfigMissing));
+}
+
+#[test]
+fn test_detect_ci_env() {
+    std::env::set_var("CI", "true");
+    assert!(detect_ci_env());
+    std::env::set_var("CI", "1");
+    assert!(detect_ci_env());
+    std::env::set_var("CI", "false");
+    assert!(!detect_ci_env());
+    std::env::remove_var("CI");
+    assert!(!detect_ci_env());
+}
@@ -0,0 +1,97 @@
+use git_iris::classify::{
+    classify_file, count_added_tests, is_test_file, test_coverage_signals, FileRole,
+};
+
+#[test]
+fn test_classify_file_roles() {
+    assert_eq!(classify_file("src/parser.rs", ""), FileRole::Production);
+    assert_eq!(classify_file("tests/parser_tests.rs", ""), FileRole::Test);
+    assert_eq!(classify_file("web/Button.test.tsx", ""), FileRole::Test);
+    assert_eq!(classify_file("pkg/server_test.go", ""), FileRole::Test);
+    assert_eq!(classify_file("app/test_models.py", ""), FileRole::Test);
+    assert_eq!(
+        classify_file("src/main/java/UserServiceTest.java", ""),
+        FileRole::Test
+    );
+    assert_eq!(classify_file("README.md", ""), FileRole::Docs);
+    assert_eq!(classify_file("docs/setup.html", ""), FileRole::Docs);
+    assert_eq!(classify_file("Cargo.toml", ""), FileRole::Config);
+    assert_eq!(
+        classify_file(".github/workflows/ci.yml", ""),
+        FileRole::Config
+    );
+    assert_eq!(classify_file("vite.config.ts", ""), FileRole::Config);
+    assert_eq!(classify_file("Cargo.lock", ""), FileRole::Generated);
+    assert_eq!(classify_file("api/service.pb.go", ""), FileRole::Generated);
+    assert_eq!(
+        classify_file(
+            "src/schema.rs",
+            "+// @generated by diesel CLI\n+table! {}\n"
+        ),
+        FileRole::Generated
+    );
+
+    assert!(!is_test_file("src/contest.rs"));
+    assert!(!is_test_file("src/Latest.md"));
+    assert_eq!(FileRole::Generated.to_string(), "generated");
+}
+
+#[test]
+fn test_count_added_tests() {
+    let rust = "+#[test]\n+fn parses() {}\n+#[tokio::test]\n+async fn fetches() {}\n-#[test]\n";
+    assert_eq!(count_added_tests("src/lib.rs", rust), 2);
+    assert_eq!(
+        count_added_tests("test_app.py", "+def test_login():\n+def helper():\n"),
+        1
+    );
+    assert_eq!(
+        count_added_tests("a_test.go", "+func TestServe(t *testing.T) {\n"),
+        1
+    );
+    assert_eq!(
+        count_added_tests(
+            "a.test.ts",
+            "+  it('renders', () => {\n+  test.skip('x', f)\n"
+        ),
+        2
+    );
+    // Calls named `test` in application code aren't tests
+    assert_eq!(count_added_tests("src/app.ts", "+  test(value)\n"), 0);
+}
+
+#[test]
+fn test_coverage_signals_pair_tests_with_code() {
+    let files = [
+        ("src/parser.rs", "+fn parse() {}\n"),
+        ("src/lexer/mod.rs", "+fn lex() {}\n"),
+        ("src/render.rs", "+fn render() {}\n"),
+        (
+            "tests/parser_tests.rs",
+            "+#[test]\n+fn a() {}\n+#[test]\n+fn b() {}\n+#[test]\n+fn c() {}\n",
+        ),
+        (
+            "tests/lexer_tests.rs",
+            "-    assert!(old);\n+    assert!(new);\n",
+        ),
+        ("README.md", "+More docs\n"),
+    ];
+    assert_eq!(
+        test_coverage_signals(&files),
+        vec![
+            "Adds 3 tests for parser in tests/parser_tests.rs",
+            "Modifies tests in tests/lexer_tests.rs",
+            "Modifies code in src/render.rs without matching test changes",
+        ]
+    );
+
+    assert_eq!(
+        test_coverage_signals(&[("src/a.rs", "+x\n"), ("docs/guide.md", "+y\n")]),
+        vec!["Modifies code in src/a.rs without any test changes"]
+    );
+    // Inline tests cover the file they are in
+    assert_eq!(
+        test_coverage_signals(&[("src/a.rs", "+#[test]\n+fn works() {}\n")]),
+        vec!["Adds 1 test for a in src/a.rs"]
+    );
+    assert!(test_coverage_signals(&[("Cargo.toml", "+[features]\n")]).is_empty());
+}
@@ -0,0 +1,166 @@
+use git_iris::commit_message::{
+    candidate_diff, enforce_format, is_subject_too_long, move_gitmoji_to_body, replace_gitmoji,
+    shorten_subject, split_leading_emoji, split_subject, subject_line, subject_width,
+};
+use git_iris::config::MessageFormatConfig;
+
+#[test]
+fn test_subject_width_counts_emoji_as_wide() {
+    assert_eq!(subject_width("feat: add parser\n\nBody text"), 16);
+    // ✨ occupies two terminal columns
+    assert_eq!(subject_width("✨ feat: add parser"), 19);
+    // CJK characters are double width as well
+    assert_eq!(subject_width("修正"), 4);
+}
+
+#[test]
+fn test_is_subject_too_long() {
+    let ascii = "a".repeat(72);
+    assert!(!is_subject_too_long(&ascii, 72));
+
+    let with_emoji = format!("✨ {}", "a".repeat(70));
+    assert_eq!(with_emoji.chars().count(), 72);
+    assert!(is_subject_too_long(&with_emoji, 72));
+}
+
+#[test]
+fn test_subject_line() {
+    assert_eq!(subject_line("\n  Fix bug  \n\nDetails"), "Fix bug");
+    assert_eq!(subject_line(""), "");
+}
+
+#[test]
+fn test_split_leading_emoji() {
+    assert_eq!(split_leading_emoji("✨ Add parser"), (Some("✨"), "Add parser"));
+    assert_eq!(split_leading_emoji("♻️ Refactor code"), (Some("♻️"), "Refactor code"));
+    assert_eq!(split_leading_emoji("Add parser"), (None, "Add parser"));
+    assert_eq!(split_leading_emoji("日本語 を追加"), (None, "日本語 を追加"));
+}
+
+#[test]
+fn test_move_gitmoji_to_body() {
+    assert_eq!(
+        move_gitmoji_to_body("✨ Add parser\n\nSupport nested blocks"),
+        "Add parser\n\n✨ Support nested blocks"
+    );
+    assert_eq!(move_gitmoji_to_body("🐛 Fix crash"), "Fix crash\n\n🐛");
+    assert_eq!(move_gitmoji_to_body("Fix crash"), "Fix crash");
+}
+
+#[test]
+fn test_candidate_diff() {
+    let first = "Add retries to the client\n\nRetry once on timeouts.\nLog each retry.";
+    let second = "Retry failed requests\n\nRetry once on timeouts.\nLog each retry.";
+
+    assert_eq!(
+        candidate_diff(first, second, 6),
+        vec!["- Add retries to the client", "+ Retry failed requests"]
+    );
+    assert!(candidate_diff(first, first, 6).is_empty());
+
+    let limited = candidate_diff("a\nb\nc", "x\ny\nz", 2);
+    assert_eq!(limited.len(), 3);
+    assert_eq!(limited[2], "… 4 more changed line(s)");
+}
+
+#[test]
+fn test_replace_gitmoji() {
+    assert_eq!(
+        replace_gitmoji("✨ Add parser\n\nSupport nested blocks", "🎨"),
+        "🎨 Add parser\n\nSupport nested blocks"
+    );
+    assert_eq!(replace_gitmoji("Fix crash", "🐛"), "🐛 Fix crash");
+    // A gitmoji moved to the body stays there
+    assert_eq!(
+        replace_gitmoji("Add parser\n\n✨ Support nested blocks", "🎉"),
+        "Add parser\n\n🎉 Support nested blocks"
+    );
+    assert_eq!(replace_gitmoji("Fix crash\n\n🐛", "🚑"), "Fix crash\n\n🚑");
+}
+
+#[test]
+fn test_shorten_subject() {
+    assert_eq!(shorten_subject("Add retries", 72), "Add retries");
+    assert_eq!(shorten_subject("Add retries", 0), "Add retries");
+    // Cut at a word boundary, without a dangling connector or punctuation
+    assert_eq!(
+        shorten_subject("Add retries to the HTTP client, and log failed attempts", 34),
+        "Add retries to the HTTP client"
+    );
+    assert_eq!(shorten_subject("Add retries for the client", 19), "Add retries");
+    assert_eq!(shorten_subject("✨ Add retries", 12), "✨ Add");
+    assert_eq!(shorten_subject("Supercalifragilistic", 5), "Super");
+}
+
+#[test]
+fn test_split_subject() {
+    assert_eq!(split_subject("Add retries", 72), ("Add retries".to_string(), None));
+    assert_eq!(split_subject("Add retries", 0), ("Add retries".to_string(), None));
+
+    let subject = "Add retries to the HTTP client, and log failed attempts";
+    let (shortened, rest) = split_subject(subject, 34);
+    assert_eq!(shortened, "Add retries to the HTTP client…");
+    assert!(subject_width(&shortened) <= 34);
+    let rest = rest.unwrap();
+    assert_eq!(rest, "…and log failed attempts");
+
+    // Every word of the subject is kept, in the subject or the body
+    let kept = format!("{} {}", shortened, rest).replace('…', "");
+    let kept: Vec<&str> = kept.split_whitespace().collect();
+    let original: Vec<String> = subject
+        .split_whitespace()
+        .map(|word| word.replace(',', ""))
+        .collect();
+    assert_eq!(kept, original);
+
+    assert_eq!(
+        split_subject("Supercalifragilistic", 5),
+        ("Supe…".to_string(), Some("…rcalifragilistic".to_string()))
+    );
+}
+
+#[test]
+fn test_enforce_format() {
+    let format = MessageFormatConfig::default();
+    let long_line = "word ".repeat(20);
+
+    let message = format!("Add retries\n{}\nmore words", long_line.trim());
+    let formatted = enforce_format(&message, &format);
+    let lines: Vec<&str> = formatted.lines().collect();
+    assert_eq!(lines[0], "Add retries");
+    assert_eq!(lines[1], "");
+    assert!(lines[2..].iter().all(|line| line.len() <= 72));
+    assert!(formatted.ends_with("word more words"));
+
+    // List items wrap with a hanging indent; code, URLs, and trailers keep their layout
+    let message = format!(
+        "Add retries\n\n- {}\n\n    let attempts = {};\n\nSee https://example.com/{}\n\nSigned-off-by: A <a@example.com>",
+        long_line.trim(),
+        "1 + ".repeat(20),
+        "x".repeat(80)
+    );
+    let formatted = enforce_format(&message, &format);
+    let lines: Vec<&str> = formatted.lines().collect();
+    assert!(lines[2].starts_with("- word"));
+    assert!(lines[3].starts_with("  word"));
+    assert!(formatted.contains(&format!("    let attempts = {};", "1 + ".repeat(20))));
+    assert!(formatted.contains(&format!("https://example.com/{}", "x".repeat(80))));
+    assert!(formatted.ends_with("\n\nSigned-off-by: A <a@example.com>"));
+
+    let format = MessageFormatConfig {
+        subject_length: 50,
+        max_subject_length: 20,
+        wrap_body_at: 0,
+        blank_line_after_subject: false,
+    };
+    let message = format!("Add retries to the HTTP client\n{}", long_line.trim());
+    assert_eq!(
+        enforce_format(&message, &format),
+        format!("Add retries…\n…to the HTTP client\n\n{}", long_line.trim())
+    );
+    assert_eq!(
+        enforce_format("Add retries to the HTTP client", &format),
+        "Add retries…\n…to the HTTP client"
+    );
+    assert_eq!(enforce_format("  Add retries\n\n\n", &format), "Add retries");
+}
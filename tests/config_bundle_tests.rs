@@ -0,0 +1,118 @@
+use git_iris::config::Config;
+use git_iris::config_bundle::{BundleFormat, ConfigBundle, IMPORTED_GITMOJI_FILE};
+use std::fs;
+use tempfile::TempDir;
+
+fn settings() -> toml::Value {
+    toml::from_str(
+        r#"
+version = 1
+default_provider = "openai"
+instructions = "Be brief"
+
+[providers.openai]
+api_key = "sk-abcdefghijklmnop1234"
+model = "gpt-4o"
+
+[providers.openai.additional_params]
+temperature = "0.2"
+"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_export_bundles_templates_and_gitmoji_file() {
+    let dir = TempDir::new().unwrap();
+    let templates = dir.path().join("templates");
+    fs::create_dir(&templates).unwrap();
+    fs::write(templates.join("commit_system.tmpl"), "Write a commit message").unwrap();
+    fs::write(templates.join("unrelated.txt"), "Not a template").unwrap();
+    let gitmoji_file = dir.path().join("emoji.toml");
+    fs::write(&gitmoji_file, "[types.feat]\nemoji = \":rocket:\"\n").unwrap();
+
+    let bundle =
+        ConfigBundle::from_parts(settings(), &templates, Some(&gitmoji_file), true).unwrap();
+    assert_eq!(bundle.templates.len(), 1);
+    assert_eq!(bundle.templates["commit_system"], "Write a commit message");
+    assert!(bundle.gitmoji_mapping.as_deref().unwrap().contains(":rocket:"));
+
+    for format in [BundleFormat::Toml, BundleFormat::Json] {
+        let rendered = bundle.render(format).unwrap();
+        assert_eq!(ConfigBundle::parse(&rendered).unwrap(), bundle);
+    }
+}
+
+#[test]
+fn test_export_without_secrets() {
+    let dir = TempDir::new().unwrap();
+    let bundle = ConfigBundle::from_parts(settings(), dir.path(), None, false).unwrap();
+    let rendered = bundle.render(BundleFormat::Toml).unwrap();
+    assert!(!rendered.contains("sk-abcdefghijklmnop1234"));
+    assert!(rendered.contains("gpt-4o"));
+    assert!(rendered.contains("temperature"));
+}
+
+#[test]
+fn test_parse_rejects_bad_bundles() {
+    assert!(ConfigBundle::parse("version = 99\n[config]\n").is_err());
+    assert!(ConfigBundle::parse("version = 1\nconfig = 3\n").is_err());
+    assert!(ConfigBundle::parse("version = 1\n[config]\n[templates]\nprompt = \"x\"\n").is_err());
+    assert!(ConfigBundle::parse("{ not json").is_err());
+}
+
+#[test]
+fn test_apply_merges_and_keeps_api_keys() {
+    let dir = TempDir::new().unwrap();
+    let mut shared: toml::Value = toml::from_str(
+        r#"
+instructions = "Mention the ticket"
+
+[providers.openai]
+model = "gpt-4o-mini"
+"#,
+    )
+    .unwrap();
+    shared
+        .as_table_mut()
+        .unwrap()
+        .insert("default_provider".to_string(), "openai".into());
+    let bundle = ConfigBundle::from_parts(shared, dir.path(), None, false).unwrap();
+
+    let merged = bundle.apply(&settings(), false);
+    let config: Config = merged.try_into().unwrap();
+    assert_eq!(config.instructions, "Mention the ticket");
+    assert_eq!(config.providers["openai"].model, "gpt-4o-mini");
+    assert_eq!(config.providers["openai"].api_key, "sk-abcdefghijklmnop1234");
+    assert_eq!(config.providers["openai"].additional_params["temperature"], "0.2");
+
+    let replaced = bundle.apply(&settings(), true);
+    let config: Config = replaced.try_into().unwrap();
+    assert_eq!(config.providers["openai"].api_key, "sk-abcdefghijklmnop1234");
+    assert!(config.providers["openai"].additional_params.is_empty());
+}
+
+#[test]
+fn test_import_writes_files() {
+    let dir = TempDir::new().unwrap();
+    let source = TempDir::new().unwrap();
+    let templates = source.path().join("templates");
+    fs::create_dir(&templates).unwrap();
+    fs::write(templates.join("changelog_user.tmpl"), "{{commits}}").unwrap();
+    let gitmoji_file = source.path().join("emoji.toml");
+    fs::write(&gitmoji_file, "[types.feat]\nemoji = \":rocket:\"\n").unwrap();
+    let bundle =
+        ConfigBundle::from_parts(settings(), &templates, Some(&gitmoji_file), false).unwrap();
+
+    bundle.write_files(dir.path()).unwrap();
+    assert_eq!(
+        fs::read_to_string(dir.path().join("templates/changelog_user.tmpl")).unwrap(),
+        "{{commits}}"
+    );
+    assert!(dir.path().join(IMPORTED_GITMOJI_FILE).is_file());
+    let applied = bundle.apply(&settings(), false);
+    assert_eq!(
+        applied.get("gitmoji_file").and_then(|file| file.as_str()),
+        Some(IMPORTED_GITMOJI_FILE)
+    );
+}
@@ -0,0 +1,88 @@
+use git_iris::config::Config;
+use git_iris::config_bundle::ConfigBundle;
+use git_iris::config_migration::{migrate, version_of, CONFIG_VERSION};
+use git_iris::config_validation::validate_config;
+
+fn old_layout() -> toml::Value {
+    toml::from_str(
+        r#"
+default_provider = "claude"
+custom_instructions = "Mention the ticket"
+api_key = "sk-ant-abcdefghijklmnop"
+model = "claude-3-5-sonnet-latest"
+
+[providers.claude]
+api_key = ""
+model = "claude-3-opus-latest"
+"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_migrate_renames_and_moves_settings() {
+    let mut value = old_layout();
+    assert_eq!(version_of(&value), 0);
+
+    let changes = migrate(&mut value).unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(version_of(&value), CONFIG_VERSION);
+    assert!(value.get("custom_instructions").is_none());
+    assert!(value.get("api_key").is_none());
+
+    let config: Config = value.try_into().unwrap();
+    assert_eq!(config.instructions, "Mention the ticket");
+    let claude = &config.providers["claude"];
+    // An empty key is filled in, while a model already in the provider's table is kept
+    assert_eq!(claude.api_key, "sk-ant-abcdefghijklmnop");
+    assert_eq!(claude.model, "claude-3-opus-latest");
+}
+
+#[test]
+fn test_migrate_leaves_current_and_newer_layouts_alone() {
+    let mut current = toml::Value::try_from(Config::default()).unwrap();
+    let before = current.clone();
+    assert!(migrate(&mut current).unwrap().is_empty());
+    assert_eq!(current, before);
+
+    // A file without anything to change still gets its version recorded
+    let mut unversioned: toml::Value = toml::from_str("use_gitmoji = false\n").unwrap();
+    assert!(migrate(&mut unversioned).unwrap().is_empty());
+    assert_eq!(version_of(&unversioned), CONFIG_VERSION);
+
+    let mut newer: toml::Value =
+        toml::from_str("version = 99\ncustom_instructions = \"x\"\n").unwrap();
+    assert!(migrate(&mut newer).unwrap().is_empty());
+    assert!(newer.get("custom_instructions").is_some());
+}
+
+#[test]
+fn test_validation_flags_a_newer_layout() {
+    let config = Config {
+        version: CONFIG_VERSION + 1,
+        ..Default::default()
+    };
+    assert!(validate_config(&config)
+        .iter()
+        .any(|problem| problem.key == "version"));
+    assert!(!validate_config(&Config::default())
+        .iter()
+        .any(|problem| problem.key == "version"));
+}
+
+#[test]
+fn test_bundles_in_an_old_layout_are_upgraded() {
+    let bundle = ConfigBundle::parse(
+        r#"
+version = 1
+
+[config]
+default_provider = "openai"
+custom_instructions = "Be brief"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(version_of(&bundle.config), CONFIG_VERSION);
+    assert_eq!(bundle.config["instructions"].as_str(), Some("Be brief"));
+}
@@ -0,0 +1,483 @@
+use git2::Repository;
+use git_iris::config::{
+    env_layer, merge_toml, migrate_legacy_config, read_repo_layer, Config, PolicyConfig,
+    SHARED_CONFIG_FILE,
+};
+use std::fs;
+use tempfile::TempDir;
+
+fn config_with_key() -> Config {
+    let mut config = Config::default();
+    let provider = config.default_provider.clone();
+    let provider_config = config.providers.get_mut(&provider).unwrap();
+    provider_config.api_key = "sk-abcdefghijklmnop1234".to_string();
+    provider_config
+        .additional_params
+        .insert("temperature".to_string(), "0.2".to_string());
+    config.instructions = "Be brief".to_string();
+    config
+}
+
+#[test]
+fn test_get_value_by_dotted_key() {
+    let config = config_with_key();
+    let provider = config.default_provider.clone();
+
+    assert_eq!(config.get_value("use_gitmoji").unwrap(), toml::Value::Boolean(true));
+    assert_eq!(
+        config
+            .get_value(&format!("providers.{}.api_key", provider))
+            .unwrap()
+            .as_str(),
+        Some("sk-abcdefghijklmnop1234")
+    );
+    assert!(config
+        .get_value(&format!("providers.{}", provider))
+        .unwrap()
+        .is_table());
+    assert!(config.get_value("no_such_key").is_err());
+    assert!(config.get_value("use_gitmoji.nested").is_err());
+}
+
+#[test]
+fn test_unset_value_restores_defaults() {
+    let mut config = config_with_key();
+    let provider = config.default_provider.clone();
+
+    config.unset_value("instructions").unwrap();
+    assert!(config.instructions.is_empty());
+
+    config.use_gitmoji = false;
+    config.unset_value("use_gitmoji").unwrap();
+    assert!(config.use_gitmoji);
+
+    config
+        .unset_value(&format!("providers.{}.additional_params.temperature", provider))
+        .unwrap();
+    assert!(config.providers[&provider].additional_params.is_empty());
+
+    // Required settings fall back to their default value
+    config
+        .unset_value(&format!("providers.{}.api_key", provider))
+        .unwrap();
+    assert!(config.providers[&provider].api_key.is_empty());
+
+    assert!(config.unset_value("no_such_key").is_err());
+    assert!(config
+        .unset_value(&format!("providers.{}", provider))
+        .is_err());
+}
+
+#[test]
+fn test_masked_toml_hides_secrets() {
+    let config = config_with_key();
+    let shown = config.to_masked_toml().unwrap();
+
+    assert!(!shown.contains("sk-abcdefghijklmnop1234"));
+    assert!(shown.contains("********1234"));
+    assert!(shown.contains("Be brief"));
+    assert!(shown.contains("token_limit"));
+}
+
+#[test]
+fn test_custom_presets() {
+    use git_iris::instruction_presets::InstructionPreset;
+
+    let mut config = Config::default();
+    let preset = InstructionPreset {
+        name: "team".to_string(),
+        description: "Team style".to_string(),
+        instructions: "Reference the JIRA ticket".to_string(),
+    };
+
+    assert!(config.set_custom_preset("default", preset.clone()).is_err());
+    assert!(config.set_custom_preset("has space", preset.clone()).is_err());
+    config.set_custom_preset("team", preset).unwrap();
+
+    // Custom presets sit alongside the built-ins and work with --preset
+    let library = config.preset_library();
+    assert!(library.get_preset("team").is_some());
+    assert!(library.get_preset("default").is_some());
+    config.set_temp_preset(Some("team".to_string()));
+    assert!(config
+        .get_effective_instructions()
+        .contains("Reference the JIRA ticket"));
+
+    // Custom presets survive a round trip through the config file format
+    let saved: Config = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+    assert_eq!(
+        saved.custom_presets["team"].instructions,
+        "Reference the JIRA ticket"
+    );
+
+    config.instruction_preset = "team".to_string();
+    config.remove_custom_preset("team").unwrap();
+    assert_eq!(config.instruction_preset, "default");
+    assert!(config.preset_library().get_preset("team").is_none());
+    assert!(config.remove_custom_preset("team").is_err());
+    assert!(config.remove_custom_preset("default").is_err());
+}
+
+#[test]
+fn test_repository_presets() {
+    use git_iris::config_validation::validate_config;
+    use git_iris::instruction_presets::{InstructionPreset, REPO_PRESETS_DIR};
+
+    let temp_dir = TempDir::new().unwrap();
+    Repository::init(temp_dir.path()).unwrap();
+    let dir = temp_dir.path().join(REPO_PRESETS_DIR);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("release-hotfix.toml"),
+        r#"
+name = "Release hotfix"
+description = "Hotfixes on a release branch"
+instructions = "Name the release branch in the subject"
+"#,
+    )
+    .unwrap();
+    fs::write(dir.join("docs.md"), "Start the subject with 'docs:'\n").unwrap();
+    fs::write(dir.join("default.md"), "Shadow the built-in preset").unwrap();
+    fs::write(dir.join("broken.toml"), "description = \"No instructions\"\n").unwrap();
+    fs::write(dir.join("README"), "Not a preset").unwrap();
+
+    let mut config = Config::default();
+    config
+        .set_custom_preset(
+            "docs",
+            InstructionPreset {
+                name: "docs".to_string(),
+                description: "Personal docs style".to_string(),
+                instructions: "Be brief".to_string(),
+            },
+        )
+        .unwrap();
+    let nested = temp_dir.path().join("src");
+    fs::create_dir_all(&nested).unwrap();
+    config.load_repo_presets(&nested);
+
+    let mut keys: Vec<&String> = config.repo_presets.keys().collect();
+    keys.sort();
+    assert_eq!(keys, ["docs", "release-hotfix"]);
+    let library = config.preset_library();
+    assert_eq!(
+        library.get_preset("release-hotfix").unwrap().name,
+        "Release hotfix"
+    );
+    // The repository's preset replaces the personal one of the same name
+    assert_eq!(
+        library.get_preset("docs").unwrap().instructions,
+        "Start the subject with 'docs:'"
+    );
+    assert_ne!(
+        library.get_preset("default").unwrap().instructions,
+        "Shadow the built-in preset"
+    );
+    config.set_temp_preset(Some("release-hotfix".to_string()));
+    assert!(config
+        .get_effective_instructions()
+        .contains("Name the release branch in the subject"));
+
+    let problems: Vec<String> = validate_config(&config)
+        .into_iter()
+        .filter(|problem| problem.key == REPO_PRESETS_DIR)
+        .map(|problem| problem.message)
+        .collect();
+    assert_eq!(problems.len(), 2);
+    assert!(problems[0].contains("broken.toml"));
+    assert!(problems[1].contains("built-in preset"));
+}
+
+#[test]
+fn test_keyring_keys_stay_out_of_the_file() {
+    let mut config = config_with_key();
+    let provider = config.default_provider.clone();
+    let stored = config.without_keyring_keys();
+    assert_eq!(stored.providers[&provider].api_key, "sk-abcdefghijklmnop1234");
+
+    config.providers.get_mut(&provider).unwrap().keyring = true;
+    let stored = config.without_keyring_keys();
+    assert!(stored.providers[&provider].api_key.is_empty());
+    assert!(stored.providers[&provider].keyring);
+    // The configuration in use keeps the key
+    assert_eq!(config.providers[&provider].api_key, "sk-abcdefghijklmnop1234");
+
+    // Configurations written before the keyring option default to the file
+    let toml = toml::to_string(&stored).unwrap().replace("keyring = true", "");
+    let parsed: Config = toml::from_str(&toml).unwrap();
+    assert!(!parsed.providers[&provider].keyring);
+}
+
+fn repo_with_config_files() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join(SHARED_CONFIG_FILE),
+        r#"
+instructions = "Mention the ticket number"
+prompt_exclude = ["*.snap"]
+
+[providers.openai]
+api_key = "sk-committed-by-mistake"
+model = "gpt-4o-mini"
+"#,
+    )
+    .unwrap();
+    fs::create_dir_all(temp_dir.path().join(".git/iris")).unwrap();
+    fs::write(
+        temp_dir.path().join(".git/iris/config.toml"),
+        "use_gitmoji = false\n",
+    )
+    .unwrap();
+    temp_dir
+}
+
+#[test]
+fn test_repository_config_layers() {
+    let temp_dir = repo_with_config_files();
+    let nested = temp_dir.path().join("src");
+    fs::create_dir_all(&nested).unwrap();
+
+    let global = config_with_key();
+    let layer = read_repo_layer(&nested).unwrap();
+    let config =
+        Config::from_layers(toml::Value::try_from(&global).unwrap(), layer, None).unwrap();
+
+    assert_eq!(config.instructions, "Mention the ticket number");
+    assert_eq!(config.prompt_exclude, vec!["*.snap"]);
+    assert!(!config.use_gitmoji);
+    let openai = &config.providers["openai"];
+    assert_eq!(openai.model, "gpt-4o-mini");
+    // API keys only come from the global configuration
+    assert_eq!(openai.api_key, global.providers["openai"].api_key);
+    // Settings the repository doesn't mention keep their global values
+    assert_eq!(
+        openai.additional_params,
+        global.providers["openai"].additional_params
+    );
+
+    assert!(read_repo_layer(TempDir::new().unwrap().path()).unwrap().is_none());
+}
+
+#[test]
+fn test_saving_keeps_repository_settings_out_of_the_global_file() {
+    let temp_dir = repo_with_config_files();
+    let global = toml::Value::try_from(config_with_key()).unwrap();
+    let layer = read_repo_layer(temp_dir.path()).unwrap();
+    let mut config = Config::from_layers(global.clone(), layer, None).unwrap();
+
+    config.use_gitmoji = false;
+    config.gitmoji_in_body = true;
+    config.providers.get_mut("openai").unwrap().model = "gpt-4o".to_string();
+
+    let saved: Config = config.global_value(global).unwrap().try_into().unwrap();
+    assert_eq!(saved.instructions, "Be brief");
+    assert!(saved.prompt_exclude.is_empty());
+    // The repository's values revert to the global ones, while real changes are kept
+    assert!(saved.use_gitmoji);
+    assert!(saved.gitmoji_in_body);
+    assert_eq!(saved.providers["openai"].model, "gpt-4o");
+}
+
+#[test]
+fn test_profiles() {
+    let global: toml::Value = toml::from_str(
+        r#"
+default_provider = "openai"
+instructions = "Be brief"
+
+[providers.openai]
+api_key = "sk-personal"
+model = "gpt-4o"
+
+[providers.claude]
+api_key = "sk-work"
+model = "claude-3-5-sonnet-20240620"
+
+[profiles.work]
+default_provider = "claude"
+instructions = "Reference the Jira ticket"
+
+[profiles.client]
+providers = { openai = { model = "gpt-4o-mini" } }
+"#,
+    )
+    .unwrap();
+
+    let config = Config::from_layers(global.clone(), None, None).unwrap();
+    assert_eq!(config.default_provider, "openai");
+    assert!(config.active_profile.is_none());
+
+    let config = Config::from_layers(global.clone(), None, Some("work")).unwrap();
+    assert_eq!(config.default_provider, "claude");
+    assert_eq!(config.instructions, "Reference the Jira ticket");
+    assert_eq!(config.active_profile.as_deref(), Some("work"));
+
+    // Provider settings are merged, so a profile can change just the model
+    let config = Config::from_layers(global.clone(), None, Some("client")).unwrap();
+    assert_eq!(config.providers["openai"].model, "gpt-4o-mini");
+    assert_eq!(config.providers["openai"].api_key, "sk-personal");
+
+    // A repository can pick a default profile, and its own settings still win
+    let repo_layer: toml::Value =
+        toml::from_str("profile = \"work\"\ninstructions = \"Mention the PR\"").unwrap();
+    let config = Config::from_layers(global.clone(), Some(repo_layer.clone()), None).unwrap();
+    assert_eq!(config.default_provider, "claude");
+    assert_eq!(config.instructions, "Mention the PR");
+    let config = Config::from_layers(global.clone(), Some(repo_layer), Some("client")).unwrap();
+    assert_eq!(config.default_provider, "openai");
+
+    // Profile settings stay out of the global settings when saving
+    let mut config = Config::from_layers(global.clone(), None, Some("work")).unwrap();
+    config.use_gitmoji = false;
+    let saved: Config = config.global_value(global.clone()).unwrap().try_into().unwrap();
+    assert_eq!(saved.default_provider, "openai");
+    assert_eq!(saved.instructions, "Be brief");
+    assert!(!saved.use_gitmoji);
+    assert_eq!(saved.profiles.len(), 2);
+
+    let error = Config::from_layers(global, None, Some("home")).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Unknown profile 'home'. Available profiles: client, work"
+    );
+}
+
+#[test]
+fn test_migrate_legacy_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let legacy = temp_dir.path().join(".git-iris");
+    let path = temp_dir.path().join("git-iris").join("config.toml");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+    // Nothing to migrate
+    assert!(!migrate_legacy_config(&legacy, &path).unwrap());
+    assert!(!path.exists());
+
+    fs::write(&legacy, "default_provider = \"claude\"\n").unwrap();
+    assert!(migrate_legacy_config(&legacy, &path).unwrap());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "default_provider = \"claude\"\n");
+    assert!(legacy.exists());
+
+    // The migration happens once: an existing configuration is never overwritten
+    fs::write(&legacy, "default_provider = \"ollama\"\n").unwrap();
+    assert!(!migrate_legacy_config(&legacy, &path).unwrap());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "default_provider = \"claude\"\n");
+
+    // A broken legacy file is reported rather than copied
+    fs::remove_file(&path).unwrap();
+    fs::write(&legacy, "default_provider = ").unwrap();
+    assert!(migrate_legacy_config(&legacy, &path).is_err());
+    assert!(!path.exists());
+}
+
+fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    vars.iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_env_layer() {
+    let layer = env_layer(env(&[
+        ("GIT_IRIS_PROVIDER", "claude"),
+        ("GIT_IRIS_GITMOJI", "0"),
+        ("GIT_IRIS_CLAUDE_API_KEY", "sk-ant-from-env"),
+        ("GIT_IRIS_UNKNOWN", "ignored"),
+        ("HOME", "/home/user"),
+    ]))
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(layer["default_provider"].as_str(), Some("claude"));
+    assert_eq!(layer["use_gitmoji"].as_bool(), Some(false));
+    assert_eq!(
+        layer["providers"]["claude"]["api_key"].as_str(),
+        Some("sk-ant-from-env")
+    );
+    assert!(layer.get("GIT_IRIS_UNKNOWN").is_none());
+
+    assert!(env_layer(env(&[("HOME", "/home/user")])).unwrap().is_none());
+    assert!(env_layer(env(&[("GIT_IRIS_GITMOJI", "maybe")])).is_err());
+}
+
+#[test]
+fn test_environment_overrides_repository_settings() {
+    let temp_dir = repo_with_config_files();
+    let global = toml::Value::try_from(config_with_key()).unwrap();
+    let mut layer = read_repo_layer(temp_dir.path()).unwrap().unwrap();
+    let env = env_layer(env(&[
+        ("GIT_IRIS_INSTRUCTIONS", "Be thorough"),
+        ("GIT_IRIS_CLAUDE_API_KEY", "sk-ant-from-env"),
+    ]))
+    .unwrap()
+    .unwrap();
+    merge_toml(&mut layer, &env);
+    let config = Config::from_layers(global.clone(), Some(layer), None).unwrap();
+
+    assert_eq!(config.instructions, "Be thorough");
+    // The local file still wins over the shared one and the global settings
+    assert!(!config.use_gitmoji);
+    // A provider that only has a key in the environment gets its default model
+    let claude = &config.providers["claude"];
+    assert_eq!(claude.api_key, "sk-ant-from-env");
+    assert!(!claude.model.is_empty());
+
+    // Keys from the environment are never written to the global file
+    let saved: Config = config.global_value(global).unwrap().try_into().unwrap();
+    assert_eq!(saved.providers["claude"].api_key, "");
+}
+
+#[test]
+fn test_policy_comes_only_from_shared_settings() {
+    let temp_dir = repo_with_config_files();
+    fs::write(
+        temp_dir.path().join(SHARED_CONFIG_FILE),
+        r#"
+[policy]
+conventional = true
+forbidden_providers = ["openai"]
+"#,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join(".git/iris/config.toml"),
+        r#"
+[conventional]
+enabled = false
+
+[policy]
+conventional = false
+forbidden_providers = []
+"#,
+    )
+    .unwrap();
+
+    let mut global = toml::Value::try_from(config_with_key()).unwrap();
+    let layer = read_repo_layer(temp_dir.path()).unwrap();
+    let config = Config::from_layers(global.clone(), layer, None).unwrap();
+
+    assert!(config.policy.conventional);
+    assert!(config.conventional.enabled);
+    assert!(config.policy.check_provider("OpenAI").is_err());
+    assert!(config.policy.check_provider("claude").is_ok());
+
+    // The forced settings stay out of the global file
+    let saved: Config = config.global_value(global.clone()).unwrap().try_into().unwrap();
+    assert!(!saved.conventional.enabled);
+    assert!(saved.policy.is_empty());
+
+    // A policy in the global settings is ignored
+    let policy = toml::Value::try_from(PolicyConfig {
+        conventional: true,
+        forbidden_providers: vec!["openai".to_string()],
+    })
+    .unwrap();
+    global
+        .as_table_mut()
+        .unwrap()
+        .insert("policy".to_string(), policy);
+    let config = Config::from_layers(global, None, None).unwrap();
+    assert!(config.policy.is_empty());
+    assert!(!config.conventional.enabled);
+}
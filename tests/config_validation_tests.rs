@@ -0,0 +1,105 @@
+use git_iris::config::Config;
+use git_iris::config_validation::{check_templates, validate_config};
+use std::fs;
+use tempfile::TempDir;
+
+fn keys(config: &Config) -> Vec<String> {
+    validate_config(config).into_iter().map(|problem| problem.key).collect()
+}
+
+#[test]
+fn test_default_config_is_valid() {
+    assert!(validate_config(&Config::default()).is_empty());
+}
+
+#[test]
+fn test_reports_every_problem_with_suggestions() {
+    let mut config = Config::default();
+    let provider = config.default_provider.clone();
+    config.default_provider = "opena1".to_string();
+    let openai = config.providers.get_mut(&provider).unwrap();
+    openai.model = String::new();
+    openai.token_limit = Some(0);
+    openai
+        .additional_params
+        .insert("temperature".to_string(), "warm".to_string());
+    let mut claud = openai.clone();
+    claud.model = "claude".to_string();
+    claud.token_limit = None;
+    claud.additional_params.clear();
+    config.providers.insert("claud".to_string(), claud);
+    config.instruction_preset = "technicl".to_string();
+    config.risk_labels = vec!["auth-changes".to_string()];
+    config.profile = Some("work".to_string());
+    config.format.subject_length = 80;
+    config.gitmoji_file = Some("/no/such/gitmoji.toml".to_string());
+
+    let problems = validate_config(&config);
+    let find = |key: &str| problems.iter().find(|problem| problem.key == key).unwrap();
+    assert!(find("default_provider").suggestion.contains("Did you mean 'openai'?"));
+    assert!(find("providers.claud").suggestion.contains("'claude'"));
+    assert!(find(&format!("providers.{}.model", provider))
+        .suggestion
+        .contains("--model"));
+    assert!(find(&format!("providers.{}.additional_params.temperature", provider))
+        .message
+        .contains("should be a number"));
+    assert!(find("instruction_preset").suggestion.contains("'technical'"));
+    assert!(find("risk_labels").suggestion.contains("'auth-change'"));
+    assert_eq!(
+        keys(&config),
+        vec![
+            "default_provider".to_string(),
+            "providers.claud".to_string(),
+            format!("providers.{}.model", provider),
+            format!("providers.{}.token_limit", provider),
+            format!("providers.{}.additional_params.temperature", provider),
+            "instruction_preset".to_string(),
+            "risk_labels".to_string(),
+            "profile".to_string(),
+            "format.subject_length".to_string(),
+            "gitmoji_file".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_recent_commit_override_for_unknown_command() {
+    let mut config = Config::default();
+    config
+        .recent_commits
+        .commands
+        .insert("reveiw".to_string(), Default::default());
+
+    let problems = validate_config(&config);
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].key, "recent_commits.commands.reveiw");
+    assert!(problems[0].suggestion.contains("'review'"));
+}
+
+#[test]
+fn test_missing_provider_settings() {
+    let mut config = Config::default();
+    config.providers.remove(&config.default_provider.clone());
+    let problems = validate_config(&config);
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].suggestion.contains("git-iris config --provider"));
+}
+
+#[test]
+fn test_check_templates() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("commit_system.tmpl"), "{{#if use_gitmoji}}emoji").unwrap();
+    fs::write(dir.path().join("commit_user.tmpl"), "On {{branch}}").unwrap();
+    fs::write(dir.path().join("changelog_usr.tmpl"), "Changes").unwrap();
+    fs::write(dir.path().join("notes.txt"), "Not a template").unwrap();
+
+    let problems = check_templates(dir.path());
+    assert_eq!(problems.len(), 2);
+    assert_eq!(problems[0].key, "templates/changelog_usr.tmpl");
+    assert!(problems[0].suggestion.contains("'changelog_user.tmpl'"));
+    assert_eq!(problems[1].key, "templates/commit_system.tmpl");
+    assert!(problems[1].message.contains("{{/if}}"));
+
+    assert!(check_templates(&dir.path().join("missing")).is_empty());
+}
@@ -0,0 +1,111 @@
+use git2::Repository;
+use git_iris::conflicts::{
+    create_conflicts_user_prompt, parse_conflict_regions, ConflictContext, ConflictOperation,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn commit_file(repo: &Repository, repo_path: &Path, name: &str, content: &str, message: &str) {
+    fs::write(repo_path.join(name), content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(name)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = repo.signature().unwrap();
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => Vec::new(),
+    };
+    let parents: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .unwrap();
+}
+
+fn checkout(repo: &Repository, branch: &str) {
+    repo.set_head(&format!("refs/heads/{}", branch)).unwrap();
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .unwrap();
+}
+
+#[test]
+fn test_parse_conflict_regions() {
+    let content = "fn main() {\n\
+        <<<<<<< HEAD\n    run(3);\n=======\n    run_with_retries(5);\n>>>>>>> feature\n\
+        }\n\
+        <<<<<<< HEAD\nours\n||||||| base\nbase\n=======\ntheirs\n>>>>>>> feature\n\
+        <<<<<<< HEAD\nunterminated\n";
+
+    let regions = parse_conflict_regions(content);
+    assert_eq!(regions.len(), 2);
+    assert_eq!(regions[0].start_line, 2);
+    assert_eq!(regions[0].ours, "    run(3);\n");
+    assert_eq!(regions[0].base, None);
+    assert_eq!(regions[0].theirs, "    run_with_retries(5);\n");
+    assert_eq!(regions[1].start_line, 8);
+    assert_eq!(regions[1].ours, "ours\n");
+    assert_eq!(regions[1].base.as_deref(), Some("base\n"));
+    assert_eq!(regions[1].theirs, "theirs\n");
+
+    assert!(parse_conflict_regions("no conflicts here\n").is_empty());
+}
+
+#[test]
+fn test_load_merge_conflicts() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    let repo = Repository::init(path).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+
+    commit_file(&repo, path, "retry.rs", "const RETRIES: u32 = 3;\n", "Add retries");
+    commit_file(&repo, path, "notes.txt", "notes\n", "Add notes");
+    let base = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.branch("feature", &base, false).unwrap();
+    repo.branch("main-line", &base, false).unwrap();
+
+    checkout(&repo, "feature");
+    commit_file(
+        &repo,
+        path,
+        "retry.rs",
+        "const RETRIES: u32 = 5;\n",
+        "Retry more often on flaky networks",
+    );
+
+    checkout(&repo, "main-line");
+    commit_file(&repo, path, "notes.txt", "more notes\n", "Update notes");
+    commit_file(&repo, path, "retry.rs", "const RETRIES: u32 = 1;\n", "Fail fast in CI");
+
+    let feature = repo
+        .find_annotated_commit(repo.revparse_single("feature").unwrap().id())
+        .unwrap();
+    repo.merge(&[&feature], None, None).unwrap();
+
+    let context = ConflictContext::load(path).unwrap();
+    assert_eq!(context.operation, ConflictOperation::Merge);
+    assert_eq!(context.files.len(), 1);
+
+    let file = &context.files[0];
+    assert_eq!(file.path, "retry.rs");
+    assert_eq!(file.regions.len(), 1);
+    assert_eq!(file.regions[0].ours, "const RETRIES: u32 = 1;\n");
+    assert_eq!(file.regions[0].theirs, "const RETRIES: u32 = 5;\n");
+
+    // Only commits on each side since the merge base that changed the file
+    let subjects = |commits: &[git_iris::change_analyzer::AnalyzedChange]| -> Vec<String> {
+        commits.iter().map(|c| c.commit_message.trim().to_string()).collect()
+    };
+    assert_eq!(subjects(&file.ours_commits), vec!["Fail fast in CI"]);
+    assert_eq!(
+        subjects(&file.theirs_commits),
+        vec!["Retry more often on flaky networks"]
+    );
+
+    let prompt = create_conflicts_user_prompt(&context);
+    assert!(prompt.contains("Operation: merge"));
+    assert!(prompt.contains("File: retry.rs"));
+    assert!(prompt.contains("Fail fast in CI"));
+    assert!(prompt.contains("Theirs:\nconst RETRIES: u32 = 5;"));
+}
@@ -0,0 +1,112 @@
+use git_iris::config::{Config, ConventionalConfig};
+use git_iris::context::{ChangeType, StageStatus, StagedFile};
+use git_iris::conventional::{infer_scope, scope_for_path, ConventionalMode};
+use std::collections::HashMap;
+
+fn staged(path: &str) -> StagedFile {
+    StagedFile {
+        path: path.to_string(),
+        change_type: ChangeType::Modified,
+        diff: String::new(),
+        analysis: vec![],
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    }
+}
+
+fn paths(paths: &[&str]) -> Vec<String> {
+    paths.iter().map(|path| path.to_string()).collect()
+}
+
+#[test]
+fn test_scope_for_path() {
+    let none = HashMap::new();
+    assert_eq!(scope_for_path("src/commands.rs", &none).as_deref(), Some("commands"));
+    assert_eq!(
+        scope_for_path("src/llm_providers/openai.rs", &none).as_deref(),
+        Some("llm_providers")
+    );
+    assert_eq!(scope_for_path("crates/core/src/lib.rs", &none).as_deref(), Some("core"));
+    assert_eq!(scope_for_path("docs/usage.md", &none).as_deref(), Some("docs"));
+    assert_eq!(scope_for_path(".github/workflows/ci.yml", &none).as_deref(), Some("github"));
+    assert_eq!(scope_for_path("src/lib.rs", &none), None);
+    assert_eq!(scope_for_path("Cargo.toml", &none), None);
+
+    let scopes = HashMap::from([
+        ("src/llm_providers/**".to_string(), "providers".to_string()),
+        ("src/llm_providers/openai.rs".to_string(), "openai".to_string()),
+        ("*.md".to_string(), "docs".to_string()),
+    ]);
+    assert_eq!(
+        scope_for_path("src/llm_providers/claude.rs", &scopes).as_deref(),
+        Some("providers")
+    );
+    // The most specific pattern wins
+    assert_eq!(scope_for_path("src/llm_providers/openai.rs", &scopes).as_deref(), Some("openai"));
+    assert_eq!(scope_for_path("README.md", &scopes).as_deref(), Some("docs"));
+}
+
+#[test]
+fn test_infer_scope() {
+    let none = HashMap::new();
+    assert_eq!(
+        infer_scope(&paths(&["src/git.rs", "Cargo.toml", "Cargo.lock"]), &none).as_deref(),
+        Some("git")
+    );
+    assert_eq!(infer_scope(&paths(&["src/git.rs", "src/cli.rs"]), &none), None);
+    assert_eq!(infer_scope(&paths(&["Cargo.toml"]), &none), None);
+
+    let config = ConventionalConfig {
+        enabled: true,
+        scopes: HashMap::from([("docs/**".to_string(), "docs".to_string())]),
+    };
+    let mode = ConventionalMode::for_changes(&[staged("tests/git_tests.rs")], &config);
+    assert_eq!(mode.scope.as_deref(), Some("tests"));
+    assert!(mode.instructions().contains("Use 'tests' as the scope."));
+}
+
+#[test]
+fn test_check_and_apply_scope() {
+    let mode = ConventionalMode {
+        scope: Some("git".to_string()),
+    };
+    assert_eq!(mode.check("feat(cli): add a flag\n\nBody."), None);
+    assert_eq!(mode.check("✨ feat: add a flag"), None);
+    assert!(mode.check("Add a flag").is_some());
+    assert!(mode.check("feature: add a flag").unwrap().contains("Unknown commit type"));
+    assert!(mode.check("").is_some());
+
+    assert_eq!(
+        mode.apply_scope("feat(cli)!: drop a flag\n\nBREAKING CHANGE: gone."),
+        "feat(git)!: drop a flag\n\nBREAKING CHANGE: gone."
+    );
+    assert_eq!(
+        mode.apply_scope("✨ fix: handle detached HEAD"),
+        "✨ fix(git): handle detached HEAD"
+    );
+    assert_eq!(mode.apply_scope("Add a flag"), "Add a flag");
+
+    let unscoped = ConventionalMode { scope: None };
+    assert_eq!(unscoped.apply_scope("fix(cli): typo"), "fix(cli): typo");
+}
+
+#[test]
+fn test_conventional_config() {
+    let config: Config = toml::from_str(
+        r#"
+        default_provider = "openai"
+
+        [providers]
+
+        [conventional]
+        enabled = true
+
+        [conventional.scopes]
+        "src/llm_providers/**" = "providers"
+        "#,
+    )
+    .unwrap();
+    assert!(config.conventional.enabled);
+    assert_eq!(config.conventional.scopes["src/llm_providers/**"], "providers");
+    assert_eq!(Config::default().conventional, ConventionalConfig::default());
+}
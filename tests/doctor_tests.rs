@@ -0,0 +1,42 @@
+use git2::Repository;
+use git_iris::doctor::{run_setup_checks, CheckResult, CheckStatus};
+use git_iris::hooks::{install_hook, HOOK_NAME};
+use std::fs;
+use tempfile::TempDir;
+
+fn find<'a>(results: &'a [CheckResult], name: &str) -> &'a CheckResult {
+    results
+        .iter()
+        .find(|result| result.name == name)
+        .unwrap_or_else(|| panic!("missing check: {}", name))
+}
+
+#[test]
+fn test_repository_and_hook_checks() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Outside a repository
+    let results = run_setup_checks(temp_dir.path());
+    let repository = find(&results, "Repository");
+    assert_eq!(repository.status, CheckStatus::Fail);
+    assert!(repository.fix.as_deref().unwrap().contains("git init"));
+
+    // A fresh repository has no commits yet
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let results = run_setup_checks(temp_dir.path());
+    assert_eq!(find(&results, "Repository").status, CheckStatus::Warn);
+    assert_eq!(find(&results, "Hook").status, CheckStatus::Pass);
+
+    // A hook written by something else is flagged, ours passes
+    let hook_path = repo.path().join("hooks").join(HOOK_NAME);
+    fs::create_dir_all(hook_path.parent().unwrap()).unwrap();
+    fs::write(&hook_path, "#!/bin/sh\nexit 0\n").unwrap();
+    let results = run_setup_checks(temp_dir.path());
+    assert_eq!(find(&results, "Hook").status, CheckStatus::Warn);
+
+    install_hook(temp_dir.path(), true).unwrap();
+    let results = run_setup_checks(temp_dir.path());
+    let hook = find(&results, "Hook");
+    assert_eq!(hook.status, CheckStatus::Pass);
+    assert!(hook.detail.ends_with("hook installed"));
+}
@@ -0,0 +1,40 @@
+// Dry-run mode is process-wide, so these tests live in their own test binary
+use git_iris::config::Config;
+use git_iris::llm::{
+    enable_dry_run, format_dry_run_report, get_refined_message, is_dry_run, DryRunComplete,
+};
+use git_iris::llm_providers::LLMProviderType;
+
+#[test]
+fn test_format_dry_run_report() {
+    let report = format_dry_run_report(&LLMProviderType::Test, "System prompt", "User prompt");
+
+    assert!(report.contains("Provider: test"));
+    assert!(report.contains("Estimated tokens:"));
+    assert!(report.contains("=== System prompt ===\nSystem prompt"));
+    assert!(report.contains("=== User prompt ===\nUser prompt"));
+    assert!(report.find("System prompt ===").unwrap() < report.find("User prompt ===").unwrap());
+}
+
+#[tokio::test]
+async fn test_dry_run_skips_the_provider() {
+    let config = Config {
+        default_provider: "test".to_string(),
+        ..Default::default()
+    };
+
+    enable_dry_run();
+    assert!(is_dry_run());
+
+    let result = get_refined_message(
+        &config,
+        &LLMProviderType::Test,
+        "System prompt",
+        "User prompt",
+        Some("Be brief"),
+    )
+    .await;
+
+    let error = result.unwrap_err();
+    assert!(error.is::<DryRunComplete>());
+}
@@ -0,0 +1,172 @@
+use git2::Repository;
+use git_iris::config::Config;
+use git_iris::exclusions::{
+    exclude_files, matches_pattern, ExclusionHistory, PromptFilter, SUGGEST_AFTER,
+};
+use git_iris::git::get_git_info;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn setup_repo() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+    temp_dir
+}
+
+fn stage(temp_dir: &TempDir, path: &str, content: &str) {
+    let repo = Repository::open(temp_dir.path()).unwrap();
+    let full_path = temp_dir.path().join(path);
+    fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+    fs::write(full_path, content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(path)).unwrap();
+    index.write().unwrap();
+}
+
+fn commit_initial(temp_dir: &TempDir) {
+    stage(temp_dir, "README.md", "# Project\n");
+    let repo = Repository::open(temp_dir.path()).unwrap();
+    let tree = repo
+        .find_tree(repo.index().unwrap().write_tree().unwrap())
+        .unwrap();
+    let signature = repo.signature().unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[])
+        .unwrap();
+}
+
+#[test]
+fn test_matches_pattern() {
+    assert!(matches_pattern("*.snap", "tests/snapshots/ui.snap"));
+    assert!(matches_pattern("*.snap", "ui.snap"));
+    assert!(!matches_pattern("*.snap", "ui.snap.rs"));
+    assert!(matches_pattern("src/generated/**", "src/generated/api/client.rs"));
+    assert!(matches_pattern("src/generated", "src/generated/client.rs"));
+    assert!(matches_pattern("**/fixtures/*.json", "fixtures/data.json"));
+    assert!(matches_pattern("**/fixtures/*.json", "tests/fixtures/data.json"));
+    assert!(!matches_pattern("src/*.rs", "src/nested/lib.rs"));
+    assert!(matches_pattern("Cargo.lo?k", "Cargo.lock"));
+    assert!(!matches_pattern("", "Cargo.lock"));
+}
+
+#[test]
+fn test_excluded_files_stay_listed_without_contents() {
+    let temp_dir = setup_repo();
+    commit_initial(&temp_dir);
+    stage(&temp_dir, "src/main.rs", "fn main() {}\n");
+    stage(&temp_dir, "tests/snapshots/main.snap", "snapshot output\n");
+    stage(&temp_dir, "schema.json", "{}\n");
+
+    let config = Config {
+        prompt_exclude: vec!["*.snap".to_string()],
+        ..Config::default()
+    };
+    let mut context = get_git_info(temp_dir.path(), &config).unwrap();
+    assert_eq!(context.staged_files.len(), 3);
+
+    let snapshot = context
+        .staged_files
+        .iter()
+        .find(|file| file.path.ends_with(".snap"))
+        .unwrap();
+    assert!(snapshot.content_excluded);
+    assert!(!snapshot.diff.contains("snapshot output"));
+
+    exclude_files(&mut context, &["schema.json".to_string()]);
+    for file in &context.staged_files {
+        assert_eq!(file.content_excluded, file.path != "src/main.rs", "{}", file.path);
+    }
+}
+
+#[test]
+fn test_prompt_filter() {
+    let filter = PromptFilter::default();
+    assert!(filter.is_excluded("Cargo.lock"));
+    assert!(filter.is_excluded("web/node_modules/react/index.js"));
+    assert!(filter.is_excluded("target/debug/build.log"));
+    assert!(filter.is_excluded("static/app.min.js"));
+    // Names that merely contain an excluded word are kept
+    assert!(!filter.is_excluded(".gitignore"));
+    assert!(!filter.is_excluded("src/build_script.rs"));
+    assert!(!filter.is_excluded("docs/distribution.md"));
+
+    let config = Config {
+        prompt_exclude: vec!["src/generated/**".to_string()],
+        prompt_include: vec!["Cargo.lock".to_string(), "src/generated/schema.rs".to_string()],
+        ..Config::default()
+    };
+    let filter = PromptFilter::from_config(&config);
+    assert!(filter.is_excluded("src/generated/api.rs"));
+    // Include patterns win over the built-in and the configured exclusions
+    assert!(!filter.is_excluded("Cargo.lock"));
+    assert!(!filter.is_excluded("src/generated/schema.rs"));
+    assert!(filter.is_excluded("yarn.lock"));
+}
+
+#[test]
+fn test_included_files_keep_their_contents() {
+    let temp_dir = setup_repo();
+    commit_initial(&temp_dir);
+    stage(&temp_dir, "Cargo.lock", "version = 3\n");
+    stage(&temp_dir, "yarn.lock", "# yarn lockfile v1\n");
+
+    let config = Config {
+        prompt_include: vec!["Cargo.lock".to_string()],
+        ..Config::default()
+    };
+    let context = get_git_info(temp_dir.path(), &config).unwrap();
+    let file = |path: &str| {
+        context
+            .staged_files
+            .iter()
+            .find(|file| file.path == path)
+            .unwrap()
+    };
+    assert!(!file("Cargo.lock").content_excluded);
+    assert!(file("Cargo.lock").diff.contains("version = 3"));
+    assert!(file("yarn.lock").content_excluded);
+}
+
+#[test]
+fn test_excluded_lockfiles_are_summarized() {
+    let temp_dir = setup_repo();
+    commit_initial(&temp_dir);
+    stage(
+        &temp_dir,
+        "yarn.lock",
+        "# yarn lockfile v1\n\nreact@^18.2.0:\n  version \"18.2.0\"\n",
+    );
+
+    let context = get_git_info(temp_dir.path(), &Config::default()).unwrap();
+    let lockfile = context
+        .staged_files
+        .iter()
+        .find(|file| file.path == "yarn.lock")
+        .unwrap();
+    assert!(lockfile.content_excluded);
+    assert_eq!(lockfile.diff, "[Content excluded]");
+    assert_eq!(lockfile.analysis, vec!["Added dependencies: react 18.2.0"]);
+}
+
+#[test]
+fn test_exclusion_history_suggestions() {
+    let temp_dir = setup_repo();
+    let often = vec!["Cargo.lock".to_string(), "ui.snap".to_string()];
+
+    for _ in 0..SUGGEST_AFTER {
+        let mut history = ExclusionHistory::load(temp_dir.path());
+        history.record(&often);
+        history.save(temp_dir.path()).unwrap();
+    }
+    let mut history = ExclusionHistory::load(temp_dir.path());
+    history.record(&["README.md".to_string()]);
+
+    assert_eq!(history.suggestions(&[]), often);
+    // Files already covered by a configured pattern aren't suggested again
+    assert_eq!(history.suggestions(&["*.snap".to_string()]), vec!["Cargo.lock"]);
+    // The history is kept out of the working tree
+    assert!(!temp_dir.path().join("exclusions.json").exists());
+}
@@ -0,0 +1,176 @@
+use git2::Repository;
+use git_iris::context::ChangeType;
+use git_iris::exclusions::PromptFilter;
+use git_iris::explain::{analyze_unified_diff, parse_unified_diff};
+use git_iris::explain_prompts::{
+    create_explain_commit_user_prompt, create_explain_diff_user_prompt,
+    create_explain_system_prompt,
+};
+use git_iris::git::{analyze_commit, get_commit_patch};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn commit_file(repo: &Repository, dir: &Path, name: &str, content: &str, message: &str) {
+    fs::write(dir.join(name), content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(name)).unwrap();
+    index.write().unwrap();
+    let tree_id = index.write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => vec![],
+    };
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parent_refs,
+    )
+    .unwrap();
+}
+
+fn setup_repo() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    commit_file(&repo, temp_dir.path(), "lib.rs", "fn one() {}\n", "Add one");
+    commit_file(
+        &repo,
+        temp_dir.path(),
+        "lib.rs",
+        "fn one() {}\nfn two() {}\n",
+        "Add two",
+    );
+    temp_dir
+}
+
+#[test]
+fn test_analyze_commit() {
+    let temp_dir = setup_repo();
+
+    let change = analyze_commit(temp_dir.path(), "HEAD", &PromptFilter::default()).unwrap();
+    assert_eq!(change.commit_message, "Add two");
+    assert_eq!(change.author, "Test User");
+    assert_eq!(change.file_changes.len(), 1);
+    assert_eq!(change.file_changes[0].new_path, "lib.rs");
+
+    let first = analyze_commit(temp_dir.path(), "HEAD~1", &PromptFilter::default()).unwrap();
+    assert_eq!(first.commit_message, "Add one");
+
+    assert!(analyze_commit(temp_dir.path(), "missing", &PromptFilter::default()).is_err());
+}
+
+#[test]
+fn test_get_commit_patch() {
+    let temp_dir = setup_repo();
+
+    let patch = get_commit_patch(temp_dir.path(), "HEAD", &PromptFilter::default()).unwrap();
+    assert!(patch.contains("+fn two() {}"));
+    assert!(!patch.contains("+fn one() {}"));
+
+    // The root commit is diffed against an empty tree
+    let root_patch = get_commit_patch(temp_dir.path(), "HEAD~1", &PromptFilter::default()).unwrap();
+    assert!(root_patch.contains("+fn one() {}"));
+}
+
+#[test]
+fn test_explain_prompts() {
+    let temp_dir = setup_repo();
+    let change = analyze_commit(temp_dir.path(), "HEAD", &PromptFilter::default()).unwrap();
+    let patch = get_commit_patch(temp_dir.path(), "HEAD", &PromptFilter::default()).unwrap();
+
+    let system_prompt = create_explain_system_prompt();
+    assert!(system_prompt.contains("## What it does"));
+    assert!(system_prompt.contains("## Why it matters"));
+    assert!(system_prompt.contains("## Areas touched"));
+
+    let user_prompt = create_explain_commit_user_prompt(&change, &patch);
+    assert!(user_prompt.contains(&format!("Commit: {}", change.commit_hash)));
+    assert!(user_prompt.contains("Message: Add two"));
+    assert!(user_prompt.contains("- lib.rs (Modified)"));
+    assert!(user_prompt.contains("+fn two() {}"));
+}
+
+const GIT_DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1,3 @@
+ fn one() {}
+--- old comment
++fn two() {}
++// new comment
+diff --git a/src/new.rs b/src/new.rs
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1 @@
++pub fn added() {}
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+index 4444444..0000000
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+";
+
+#[test]
+fn test_parse_git_diff() {
+    let files = parse_unified_diff(GIT_DIFF);
+    assert_eq!(files.len(), 3);
+
+    assert_eq!(files[0].path, "src/lib.rs");
+    assert!(matches!(files[0].change_type, ChangeType::Modified));
+    // A removed line starting with `--` stays part of the hunk
+    assert!(files[0].diff.contains("--- old comment"));
+    assert!(files[0].diff.contains("+fn two() {}"));
+
+    assert_eq!(files[1].path, "src/new.rs");
+    assert!(matches!(files[1].change_type, ChangeType::Added));
+    assert!(files[1].diff.contains("+pub fn added() {}"));
+
+    assert_eq!(files[2].path, "old.txt");
+    assert!(matches!(files[2].change_type, ChangeType::Deleted));
+}
+
+#[test]
+fn test_parse_plain_unified_diff() {
+    let diff = "\
+--- a.py\t2024-01-01 00:00:00
++++ a.py\t2024-01-02 00:00:00
+@@ -1 +1 @@
+-x = 1
++x = 2
+--- b.py
++++ b.py
+@@ -1 +1,2 @@
+ y = 1
++z = 2
+";
+    let files = parse_unified_diff(diff);
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].path, "a.py");
+    assert_eq!(files[1].path, "b.py");
+    assert!(files[1].diff.contains("+z = 2"));
+
+    assert!(parse_unified_diff("not a diff").is_empty());
+}
+
+#[test]
+fn test_explain_diff_prompt() {
+    let files = analyze_unified_diff(GIT_DIFF);
+    let prompt = create_explain_diff_user_prompt(&files);
+    assert!(prompt.contains("Based on the following diff"));
+    assert!(prompt.contains("File: src/lib.rs (Modified)"));
+    assert!(prompt.contains("File: src/new.rs (Added)"));
+    assert!(prompt.contains("File: old.txt (Deleted)"));
+    assert!(prompt.contains("+pub fn added() {}"));
+}
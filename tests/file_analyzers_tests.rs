@@ -1,5 +1,5 @@
-use git_iris::context::{ChangeType, StagedFile};
-use git_iris::file_analyzers::get_analyzer;
+use git_iris::context::{ChangeType, StageStatus, StagedFile};
+use git_iris::file_analyzers::{analyze_binary, analyze_contents, analyzes_contents, get_analyzer};
 
 #[test]
 fn test_rust_analyzer() {
@@ -21,6 +21,7 @@ fn test_rust_analyzer() {
         .to_string(),
         analysis: Vec::new(),
         content_excluded: false,
+        stage_status: StageStatus::Staged,
     };
 
     let analysis = analyzer.analyze("test.rs", &change);
@@ -55,6 +56,7 @@ fn test_javascript_analyzer() {
         .to_string(),
         analysis: Vec::new(),
         content_excluded: false,
+        stage_status: StageStatus::Staged,
     };
 
     let analysis = analyzer.analyze("test.js", &change);
@@ -71,6 +73,50 @@ fn test_javascript_analyzer() {
     assert!(react_components.contains("FunctionalComponent"));
 }
 
+#[test]
+fn test_typescript_structural_analysis() {
+    let analyzer = get_analyzer("src/orders/OrderList.tsx");
+    let change = StagedFile {
+        path: "src/orders/OrderList.tsx".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-import { fetchOrders } from '../api/legacy';
++import { fetchOrders } from '../api/orders';
+ import React from 'react';
++export interface OrderListProps {
+-export type OrderFilter = 'all' | 'open';
++export type OrderFilter = 'all' | 'open' | 'closed';
++export function OrderList({ orders }: OrderListProps) {
++  const [filter, setFilter] = useState<OrderFilter>('all');
++  useEffect(() => refresh(), [filter]);
+-export const formatTotal = (total: number) => `${total}`;
++export const formatTotal = (total: number, currency: string) => `${total} ${currency}`;
+-export function legacyTotal(order) {
++export function useOrders(filter: OrderFilter) {
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("src/orders/OrderList.tsx", &change);
+    assert!(analysis.contains(&"Added exported functions: OrderList, useOrders".to_string()));
+    assert!(analysis.contains(&"Removed exported functions: legacyTotal".to_string()));
+    assert!(analysis.contains(&"Changed exported functions: formatTotal".to_string()));
+    assert!(analysis.contains(
+        &"Modified types and interfaces: OrderListProps, OrderFilter".to_string()
+    ));
+    assert!(analysis.contains(&"Added imports from: ../api/orders".to_string()));
+    assert!(analysis.contains(&"Removed imports from: ../api/legacy".to_string()));
+    assert!(analysis.contains(&"Modified React components: OrderList".to_string()));
+    assert!(analysis.contains(&"Modified custom hooks: useOrders".to_string()));
+    assert!(analysis.contains(&"Modified hook calls: useState, useEffect".to_string()));
+
+    let metadata = analyzer.extract_metadata("OrderList.tsx", "import React from 'react';");
+    assert_eq!(metadata.language, Some("TypeScript".to_string()));
+}
+
 #[test]
 fn test_python_analyzer() {
     let analyzer = get_analyzer("test.py");
@@ -93,6 +139,7 @@ fn test_python_analyzer() {
         .to_string(),
         analysis: Vec::new(),
         content_excluded: false,
+        stage_status: StageStatus::Staged,
     };
 
     let analysis = analyzer.analyze("test.py", &change);
@@ -121,6 +168,7 @@ fn test_yaml_analyzer() {
         .to_string(),
         analysis: Vec::new(),
         content_excluded: false,
+        stage_status: StageStatus::Staged,
     };
 
     let analysis = analyzer.analyze("test.yaml", &change);
@@ -138,6 +186,257 @@ fn test_yaml_analyzer() {
     assert!(analysis.contains(&"Nested structures have been modified".to_string()));
 }
 
+#[test]
+fn test_docker_compose_analyzer() {
+    let analyzer = get_analyzer("deploy/docker-compose.yml");
+    let change = StagedFile {
+        path: "deploy/docker-compose.yml".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+   web:
+-    image: nginx:1.25
++    image: nginx:1.27
+     ports:
+       - "80:80"
++      - "443:443"
+     environment:
++      - LOG_LEVEL=debug
+       - TZ=UTC
++  redis:
++    image: redis:7
++    ports:
++      - "6379:6379"
+ volumes:
+   pgdata:
++  redisdata:
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("deploy/docker-compose.yml", &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Services added: redis".to_string(),
+            "Image changes: web (nginx:1.25 → nginx:1.27)".to_string(),
+            "New ports: web 443:443".to_string(),
+            "New environment variables: web LOG_LEVEL".to_string(),
+            "Named volumes added: redisdata".to_string(),
+        ]
+    );
+
+    let content = "services:\n  web:\n    image: nginx:1.27\n  db:\n    image: \"postgres:16\"\n";
+    let metadata = analyzer.extract_metadata("docker-compose.yml", content);
+    assert_eq!(metadata.build_system, Some("Docker Compose".to_string()));
+    assert_eq!(metadata.dependencies, vec!["nginx:1.27", "postgres:16"]);
+}
+
+#[test]
+fn test_github_workflow_analyzer() {
+    let file = ".github/workflows/release.yml";
+    let analyzer = get_analyzer(file);
+    let change = StagedFile {
+        path: file.to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ on:
+   push:
+     tags: ["v*"]
++  workflow_dispatch:
+ permissions:
+-  contents: read
++  contents: write
+ jobs:
+   build:
+     runs-on: ubuntu-latest
+     steps:
+-      - uses: actions/checkout@v3
++      - uses: actions/checkout@v4
++  publish:
++    needs: build
++    steps:
++      - run: npm publish
++        env:
++          NODE_AUTH_TOKEN: ${{ secrets.NPM_TOKEN }}
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze(file, &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Jobs added: publish".to_string(),
+            "Action and image version changes: actions/checkout (v3 → v4)".to_string(),
+            "New triggers: workflow_dispatch".to_string(),
+            "Permissions changed: contents: write".to_string(),
+            "New secrets used: NPM_TOKEN".to_string(),
+        ]
+    );
+
+    let metadata = analyzer.extract_metadata(
+        file,
+        "jobs:\n  test:\n    steps:\n      - uses: actions/setup-node@v4\n",
+    );
+    assert_eq!(metadata.build_system, Some("GitHub Actions".to_string()));
+    assert_eq!(metadata.dependencies, vec!["actions/setup-node@v4"]);
+}
+
+#[test]
+fn test_gitlab_ci_analyzer() {
+    let analyzer = get_analyzer(".gitlab-ci.yml");
+    let change = StagedFile {
+        path: ".gitlab-ci.yml".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ test:
+-  image: python:3.11
++  image: python:3.12
+   script:
+     - pytest
+-lint:
+-  script:
+-    - ruff check .
++deploy:
++  script:
++    - ./deploy.sh --token "$DEPLOY_TOKEN" --ref "$CI_COMMIT_SHA"
++  rules:
++    - if: $CI_COMMIT_BRANCH == "main"
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze(".gitlab-ci.yml", &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Jobs added: deploy".to_string(),
+            "Jobs removed: lint".to_string(),
+            "Image version changes: python (3.11 → 3.12)".to_string(),
+            "Job rules or triggers have been modified".to_string(),
+            "New variables used: DEPLOY_TOKEN".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_kubernetes_manifest_analyzer() {
+    let analyzer = get_analyzer("deploy/web.yaml");
+    let change = StagedFile {
+        path: "deploy/web.yaml".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ apiVersion: apps/v1
+ kind: Deployment
+ metadata:
+   name: web
+ spec:
+-  replicas: 2
++  replicas: 4
+   template:
+     spec:
+       containers:
+         - name: web
+-          image: ghcr.io/acme/web:1.2.0
++          image: ghcr.io/acme/web:1.3.0
+           resources:
+             limits:
+-              memory: 256Mi
++              memory: 512Mi
++---
++apiVersion: rbac.authorization.k8s.io/v1
++kind: Role
++metadata:
++  name: web-reader
++rules:
++- apiGroups: [""]
++  resources: ["secrets"]
++  verbs: ["get", "list"]
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("deploy/web.yaml", &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Resources added: Role/web-reader".to_string(),
+            "Image changes: ghcr.io/acme/web (1.2.0 → 1.3.0)".to_string(),
+            "Replica count changed: 2 → 4".to_string(),
+            "Resource limits changed: limits.memory 256Mi → 512Mi".to_string(),
+            "New RBAC rules: resources secrets, verbs get, list".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_helm_chart_analyzer() {
+    let analyzer = get_analyzer("charts/web/Chart.yaml");
+    let change = StagedFile {
+        path: "charts/web/Chart.yaml".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-version: 0.4.0
++version: 0.5.0
+ appVersion: "1.3.0"
+ dependencies:
++  - name: redis
++    version: 19.0.0
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+    let analysis = analyzer.analyze("charts/web/Chart.yaml", &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Chart version changed: 0.4.0 → 0.5.0".to_string(),
+            "Chart dependencies modified: redis".to_string()
+        ]
+    );
+
+    let values_change = StagedFile {
+        path: "charts/web/values.yaml".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ image:
+   repository: ghcr.io/acme/web
+-  tag: 1.2.0
++  tag: 1.3.0
++replicaCount: 3
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+    let analysis =
+        get_analyzer("charts/web/values.yaml").analyze("charts/web/values.yaml", &values_change);
+    assert_eq!(analysis, vec!["Modified values: image.tag, replicaCount".to_string()]);
+
+    let metadata = analyzer.extract_metadata(
+        "Chart.yaml",
+        "name: web\nversion: 0.5.0\ndependencies:\n  - name: redis\n    version: 19.0.0\n",
+    );
+    assert_eq!(metadata.build_system, Some("Helm".to_string()));
+    assert_eq!(metadata.version, Some("0.5.0".to_string()));
+    assert_eq!(metadata.dependencies, vec!["redis"]);
+}
+
 #[test]
 fn test_json_analyzer() {
     let analyzer = get_analyzer("test.json");
@@ -158,6 +457,7 @@ fn test_json_analyzer() {
         .to_string(),
         analysis: Vec::new(),
         content_excluded: false,
+        stage_status: StageStatus::Staged,
     };
 
     let analysis = analyzer.analyze("test.json", &change);
@@ -198,6 +498,7 @@ fn test_markdown_analyzer() {
         .to_string(),
         analysis: Vec::new(),
         content_excluded: false,
+        stage_status: StageStatus::Staged,
     };
 
     let analysis = analyzer.analyze("test.md", &change);
@@ -217,6 +518,7 @@ fn test_default_analyzer() {
         diff: "Some changes".to_string(),
         analysis: Vec::new(),
         content_excluded: false,
+        stage_status: StageStatus::Staged,
     };
 
     let analysis = analyzer.analyze("unknown.xyz", &change);
@@ -250,6 +552,7 @@ fn test_java_analyzer() {
         .to_string(),
         analysis: Vec::new(),
         content_excluded: false,
+        stage_status: StageStatus::Staged,
     };
 
     let analysis = analyzer.analyze("test.java", &change);
@@ -285,6 +588,107 @@ fn test_java_analyzer() {
     );
 }
 
+#[test]
+fn test_java_annotations_and_visibility() {
+    let analyzer = get_analyzer("Service.java");
+    let change = StagedFile {
+        path: "Service.java".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-    private String render(Order order) {
++    @Deprecated
++    public String render(Order order) {
+-    @Override
++    @Override
+     public void close() {
+         return render(current);
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("Service.java", &change);
+    assert!(analysis.contains(&"Modified annotations: @Deprecated, @Override".to_string()));
+    assert!(analysis.contains(&"Visibility changed: render (private → public)".to_string()));
+}
+
+#[test]
+fn test_maven_pom_analyzer() {
+    let analyzer = get_analyzer("service/pom.xml");
+    let change = StagedFile {
+        path: "service/pom.xml".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+     <dependency>
+       <groupId>com.google.guava</groupId>
+       <artifactId>guava</artifactId>
+-      <version>32.1.0-jre</version>
++      <version>33.0.0-jre</version>
+     </dependency>
+     <dependency>
+       <groupId>org.slf4j</groupId>
+       <artifactId>slf4j-api</artifactId>
+     </dependency>
++    <plugin>
++      <groupId>org.apache.maven.plugins</groupId>
++      <artifactId>maven-surefire-plugin</artifactId>
++    </plugin>
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("service/pom.xml", &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Modified dependencies: guava".to_string(),
+            "Modified plugins: maven-surefire-plugin".to_string()
+        ]
+    );
+
+    let content = r#"
+<project>
+  <version>1.4.0</version>
+  <dependencies>
+    <dependency>
+      <groupId>org.springframework.boot</groupId>
+      <artifactId>spring-boot-starter-web</artifactId>
+    </dependency>
+    <dependency>
+      <groupId>org.junit.jupiter</groupId>
+      <artifactId>junit-jupiter</artifactId>
+    </dependency>
+  </dependencies>
+  <build>
+    <plugins>
+      <plugin>
+        <groupId>org.apache.maven.plugins</groupId>
+        <artifactId>maven-compiler-plugin</artifactId>
+      </plugin>
+    </plugins>
+  </build>
+</project>
+"#;
+    let metadata = analyzer.extract_metadata("pom.xml", content);
+    assert_eq!(metadata.build_system, Some("Maven".to_string()));
+    assert_eq!(metadata.version, Some("1.4.0".to_string()));
+    assert_eq!(metadata.framework, Some("Spring".to_string()));
+    assert_eq!(metadata.test_framework, Some("JUnit".to_string()));
+    assert_eq!(
+        metadata.dependencies,
+        vec![
+            "org.springframework.boot:spring-boot-starter-web",
+            "org.junit.jupiter:junit-jupiter"
+        ]
+    );
+    assert_eq!(metadata.plugins, vec!["maven-compiler-plugin"]);
+}
+
 #[test]
 fn test_kotlin_analyzer() {
     let analyzer = get_analyzer("test.kt");
@@ -308,6 +712,7 @@ fn test_kotlin_analyzer() {
         .to_string(),
         analysis: Vec::new(),
         content_excluded: false,
+        stage_status: StageStatus::Staged,
     };
 
     let analysis = analyzer.analyze("test.kt", &change);
@@ -332,6 +737,108 @@ fn test_kotlin_analyzer() {
     assert!(analysis.contains(&"Import statements have been modified".to_string()));
 }
 
+#[test]
+fn test_kotlin_composables_and_coroutines() {
+    let analyzer = get_analyzer("ui/OrderScreen.kt");
+    let change = StagedFile {
+        path: "ui/OrderScreen.kt".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
++data class OrderState(val items: List<Item>)
+ @Composable
+-fun OrderList(orders: List<Order>) {
++fun OrderList(orders: List<Order>, onSelect: (Order) -> Unit) {
+ }
++@Composable
++private fun EmptyOrders() {
++}
+ fun helper() {
+-    GlobalScope.launch { refresh() }
++    viewModelScope.launch(Dispatchers.IO) { refresh() }
+ }
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("ui/OrderScreen.kt", &change);
+    assert!(analysis.contains(&"Modified classes: OrderState".to_string()));
+    assert!(analysis.contains(&"Modified composables: OrderList, EmptyOrders".to_string()));
+    assert!(analysis.contains(&"Coroutine usage has been modified".to_string()));
+
+    let metadata = analyzer.extract_metadata(
+        "OrderScreen.kt",
+        "import androidx.compose.runtime.Composable\nimport io.kotest.core.spec.style.StringSpec\n",
+    );
+    assert_eq!(metadata.language, Some("Kotlin".to_string()));
+    assert_eq!(metadata.framework, Some("Jetpack Compose".to_string()));
+    assert_eq!(metadata.test_framework, Some("Kotest".to_string()));
+}
+
+#[test]
+fn test_kotlin_gradle_script_analyzer() {
+    let analyzer = get_analyzer("app/build.gradle.kts");
+    let change = StagedFile {
+        path: "app/build.gradle.kts".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ plugins {
++    kotlin("plugin.serialization")
+ }
+ dependencies {
+-    implementation("io.ktor:ktor-server-core:2.3.0")
++    implementation("io.ktor:ktor-server-core:2.3.12")
++    testImplementation(libs.kotest.runner)
+ }
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("app/build.gradle.kts", &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Modified dependencies: io.ktor:ktor-server-core, libs.kotest.runner".to_string(),
+            "Modified plugins: org.jetbrains.kotlin.plugin.serialization".to_string()
+        ]
+    );
+
+    let content = r#"
+plugins {
+    kotlin("jvm") version "2.0.0"
+    id("io.ktor.plugin") version "2.3.12"
+}
+
+version = "0.3.0"
+
+dependencies {
+    implementation("io.ktor:ktor-server-core:2.3.12")
+    testImplementation("org.junit.jupiter:junit-jupiter:5.10.2")
+}
+"#;
+    let metadata = analyzer.extract_metadata("build.gradle.kts", content);
+    assert_eq!(metadata.build_system, Some("Gradle".to_string()));
+    assert_eq!(metadata.version, Some("0.3.0".to_string()));
+    assert_eq!(metadata.framework, Some("Ktor".to_string()));
+    assert_eq!(metadata.test_framework, Some("JUnit".to_string()));
+    assert_eq!(
+        metadata.dependencies,
+        vec![
+            "io.ktor:ktor-server-core:2.3.12",
+            "org.junit.jupiter:junit-jupiter:5.10.2"
+        ]
+    );
+    assert_eq!(
+        metadata.plugins,
+        vec!["org.jetbrains.kotlin.jvm", "io.ktor.plugin"]
+    );
+}
+
 #[test]
 fn test_gradle_analyzer() {
     let analyzer = get_analyzer("build.gradle");
@@ -354,6 +861,7 @@ fn test_gradle_analyzer() {
         .to_string(),
         analysis: Vec::new(),
         content_excluded: false,
+        stage_status: StageStatus::Staged,
     };
 
     let analysis = analyzer.analyze("build.gradle", &change);
@@ -384,6 +892,7 @@ fn test_c_analyzer() {
         .to_string(),
         analysis: Vec::new(),
         content_excluded: false,
+        stage_status: StageStatus::Staged,
     };
 
     let analysis = analyzer.analyze("test.c", &change);
@@ -411,49 +920,1588 @@ LIBS += -lm
 }
 
 #[test]
-fn test_cpp_analyzer() {
-    let analyzer = get_analyzer("test.cpp");
+fn test_c_header_signatures_macros_and_includes() {
+    let analyzer = get_analyzer("include/buffer.h");
     let change = StagedFile {
-        path: "test.cpp".to_string(),
+        path: "include/buffer.h".to_string(),
         change_type: ChangeType::Modified,
         diff: r#"
-+void newFunction() {
-+    std::cout << "Hello, world!" << std::endl;
-+}
--class OldClass {
--    OldClass() {}
--}
-+class NewClass {
-+    NewClass() {}
-+}
-+#include <iostream>
--#include <vector>
+-#include <stdlib.h>
++#include <stdint.h>
+ #include "config.h"
+-#define BUFFER_SIZE 1024
++#define BUFFER_SIZE 4096
++#define BUFFER_ALIGN 16
+-int buffer_write(char *data, int len);
++int buffer_write(const uint8_t *data, size_t len);
++char *buffer_name(void);
         "#
         .to_string(),
         analysis: Vec::new(),
         content_excluded: false,
+        stage_status: StageStatus::Staged,
     };
 
-    let analysis = analyzer.analyze("test.cpp", &change);
-    println!("C++ Test Debug: Analysis results: {:?}", analysis);
-    assert!(analysis.contains(&"Modified functions: newFunction".to_string()));
+    let analysis = analyzer.analyze("include/buffer.h", &change);
+    assert!(analysis.contains(&"Changed function signatures: buffer_write".to_string()));
     assert!(analysis
         .iter()
-        .any(|s| s.contains("OldClass") && s.contains("NewClass")));
-    assert!(analysis.contains(&"Include statements have been modified".to_string()));
+        .any(|s| s.starts_with("Modified functions:") && s.contains("buffer_name")));
+    assert!(analysis.contains(&"Modified macros: BUFFER_SIZE, BUFFER_ALIGN".to_string()));
+    assert!(analysis.contains(&"Added includes: <stdint.h>".to_string()));
+    assert!(analysis.contains(&"Removed includes: <stdlib.h>".to_string()));
 }
 
 #[test]
-fn test_cpp_analyzer_metadata() {
-    let analyzer = get_analyzer("CMakeLists.txt");
+fn test_csharp_analyzer() {
+    let analyzer = get_analyzer("Api/OrdersController.cs");
+    let change = StagedFile {
+        path: "Api/OrdersController.cs".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
++using Microsoft.Extensions.Logging;
+-namespace Shop.Controllers
++namespace Shop.Api.Controllers
+ {
+-    public class OrdersController : ControllerBase
++    public sealed class OrdersController : ControllerBase
+     {
++        public async Task<IActionResult> Cancel(int id)
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("Api/OrdersController.cs", &change);
+    assert!(analysis.contains(
+        &"Modified namespaces: Shop.Controllers, Shop.Api.Controllers".to_string()
+    ));
+    assert!(analysis.contains(&"Modified classes: OrdersController".to_string()));
+    assert!(analysis.contains(&"Modified methods: Cancel".to_string()));
+    assert!(analysis.contains(&"Using directives have been modified".to_string()));
+}
+
+#[test]
+fn test_csproj_analyzer() {
+    let analyzer = get_analyzer("Api/Api.csproj");
+    let change = StagedFile {
+        path: "Api/Api.csproj".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-    <TargetFramework>net6.0</TargetFramework>
++    <TargetFramework>net8.0</TargetFramework>
+-    <PackageReference Include="Serilog" Version="2.12.0" />
++    <PackageReference Include="Serilog" Version="3.1.1" />
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("Api/Api.csproj", &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Modified package references: Serilog".to_string(),
+            "Target framework has been modified".to_string()
+        ]
+    );
+
     let content = r#"
-project(MyProject VERSION 1.0)
-find_package(Boost REQUIRED)
-    "#;
+<Project Sdk="Microsoft.NET.Sdk.Web">
+  <PropertyGroup>
+    <TargetFramework>net8.0</TargetFramework>
+    <Version>1.2.0</Version>
+  </PropertyGroup>
+  <ItemGroup>
+    <PackageReference Include="Serilog" Version="3.1.1" />
+    <PackageReference Include="xunit" Version="2.7.0" />
+  </ItemGroup>
+</Project>
+"#;
+    let metadata = analyzer.extract_metadata("Api.csproj", content);
+    assert_eq!(metadata.language, Some("C#".to_string()));
+    assert_eq!(metadata.build_system, Some("MSBuild".to_string()));
+    assert_eq!(metadata.version, Some("1.2.0".to_string()));
+    assert_eq!(metadata.framework, Some("ASP.NET Core (net8.0)".to_string()));
+    assert_eq!(metadata.test_framework, Some("xUnit".to_string()));
+    assert_eq!(metadata.dependencies, vec!["Serilog", "xunit"]);
+}
 
-    let metadata = analyzer.extract_metadata("CMakeLists.txt", content);
-    assert_eq!(metadata.language, Some("C++".to_string()));
-    assert_eq!(metadata.build_system, Some("CMake".to_string()));
-    assert_eq!(metadata.version, Some("1.0".to_string()));
-    assert!(metadata.dependencies.contains(&"Boost".to_string()));
+#[test]
+fn test_ruby_migration_and_models() {
+    let analyzer = get_analyzer("db/migrate/20240101000000_create_orders.rb");
+    let change = StagedFile {
+        path: "db/migrate/20240101000000_create_orders.rb".to_string(),
+        change_type: ChangeType::Added,
+        diff: r#"
++class CreateOrders < ActiveRecord::Migration[7.1]
++  def change
++    create_table :orders do |t|
++      t.references :user
++    end
++    add_index :orders, :created_at
++    remove_column :users, :legacy_orders
++  end
++end
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("db/migrate/20240101000000_create_orders.rb", &change);
+    assert_eq!(
+        analysis[0],
+        "Destructive operations (data may be lost): remove_column users"
+    );
+    assert!(analysis.contains(&"Rails database migration has been modified".to_string()));
+    assert!(analysis.contains(
+        &"Schema changes: create_table orders, add_index orders, remove_column users".to_string()
+    ));
+    assert!(analysis.contains(&"Modified classes: CreateOrders".to_string()));
+    assert!(analysis.contains(&"Modified methods: change".to_string()));
+
+    let metadata =
+        analyzer.extract_metadata("order.rb", "class Order < ApplicationRecord\nend\n");
+    assert_eq!(metadata.language, Some("Ruby".to_string()));
+    assert_eq!(metadata.framework, Some("Rails".to_string()));
+}
+
+#[test]
+fn test_ruby_specs() {
+    let analyzer = get_analyzer("spec/models/order_spec.rb");
+    let change = StagedFile {
+        path: "spec/models/order_spec.rb".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ RSpec.describe Order do
++  context "when cancelled" do
++    it "refunds the payment" do
+-  it 'is valid' do
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("spec/models/order_spec.rb", &change);
+    assert!(analysis.contains(
+        &"Modified specs: when cancelled, refunds the payment, is valid".to_string()
+    ));
+}
+
+#[test]
+fn test_gemfile_analyzer() {
+    let analyzer = get_analyzer("Gemfile");
+    let change = StagedFile {
+        path: "Gemfile".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-gem "rails", "~> 7.0"
++gem "rails", "~> 7.1"
++gem 'sidekiq'
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+    let analysis = analyzer.analyze("Gemfile", &change);
+    assert_eq!(analysis, vec!["Modified gems: rails, sidekiq".to_string()]);
+
+    let lock_change = StagedFile {
+        path: "Gemfile.lock".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-    rack (3.0.8)
++    rack (3.0.9)
+-      rack (>= 2.2.4)
++      rack (>= 3.0)
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+    let analysis = get_analyzer("Gemfile.lock").analyze("Gemfile.lock", &lock_change);
+    assert_eq!(analysis, vec!["Modified locked gems: rack".to_string()]);
+
+    let content = r#"
+source "https://rubygems.org"
+gem "rails", "~> 7.1"
+group :test do
+  gem "rspec-rails"
+end
+"#;
+    let metadata = analyzer.extract_metadata("Gemfile", content);
+    assert_eq!(metadata.build_system, Some("Bundler".to_string()));
+    assert_eq!(metadata.framework, Some("Rails".to_string()));
+    assert_eq!(metadata.test_framework, Some("RSpec".to_string()));
+    assert_eq!(metadata.dependencies, vec!["rails", "rspec-rails"]);
+}
+
+#[test]
+fn test_php_analyzer() {
+    let analyzer = get_analyzer("app/Http/Controllers/OrderController.php");
+    let change = StagedFile {
+        path: "app/Http/Controllers/OrderController.php".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-namespace App\Controllers;
++namespace App\Http\Controllers;
++use Illuminate\Http\Request;
+ final class OrderController extends Controller
+ {
++    public function store(Request $request)
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("app/Http/Controllers/OrderController.php", &change);
+    assert!(analysis.contains(
+        &"Namespace moved: App\\Controllers → App\\Http\\Controllers".to_string()
+    ));
+    assert!(analysis.contains(&"Modified functions: store".to_string()));
+    assert!(analysis.contains(&"Use statements have been modified".to_string()));
+    assert!(!analysis.iter().any(|s| s.starts_with("Modified classes:")));
+}
+
+#[test]
+fn test_composer_analyzer() {
+    let analyzer = get_analyzer("composer.json");
+    let change = StagedFile {
+        path: "composer.json".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-        "php": "^8.1",
++        "php": "^8.2",
+-        "laravel/framework": "^10.0",
++        "laravel/framework": "^11.0",
+         "guzzlehttp/guzzle": "^7.2"
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("composer.json", &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Modified dependencies: laravel/framework".to_string(),
+            "PHP version requirement has been modified".to_string()
+        ]
+    );
+
+    let content = r#"{
+    "require": {"php": "^8.2", "ext-json": "*", "laravel/framework": "^11.0"},
+    "require-dev": {"phpunit/phpunit": "^10.5"}
+}"#;
+    let metadata = analyzer.extract_metadata("composer.json", content);
+    assert_eq!(metadata.language, Some("PHP".to_string()));
+    assert_eq!(metadata.build_system, Some("Composer".to_string()));
+    assert_eq!(metadata.framework, Some("Laravel".to_string()));
+    assert_eq!(metadata.test_framework, Some("PHPUnit".to_string()));
+    assert_eq!(
+        metadata.dependencies,
+        vec!["laravel/framework", "phpunit/phpunit"]
+    );
+}
+
+#[test]
+fn test_swift_analyzer() {
+    let analyzer = get_analyzer("Sources/App/OrderView.swift");
+    let change = StagedFile {
+        path: "Sources/App/OrderView.swift".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
++import Combine
+-struct OrderView: View {
++struct OrderView: View, Equatable {
+     var body: some View {
++extension Order: Codable {}
++    class func make() -> Order {
++    private func reload() async {
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("Sources/App/OrderView.swift", &change);
+    assert!(analysis.contains(&"Modified types: OrderView".to_string()));
+    assert!(analysis.contains(
+        &"Modified protocol conformances: OrderView (View), OrderView (View, Equatable), Order (Codable)"
+            .to_string()
+    ));
+    assert!(analysis.contains(&"Modified SwiftUI views: OrderView".to_string()));
+    assert!(analysis.contains(&"Modified functions: make, reload".to_string()));
+    assert!(analysis.contains(&"Import statements have been modified".to_string()));
+}
+
+#[test]
+fn test_swift_package_manifest() {
+    let analyzer = get_analyzer("Package.swift");
+    let change = StagedFile {
+        path: "Package.swift".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-// swift-tools-version:5.8
++// swift-tools-version:5.9
++        .package(url: "https://github.com/apple/swift-argument-parser.git", from: "1.3.0"),
++        .executableTarget(name: "orders-cli", dependencies: []),
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("Package.swift", &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Modified package dependencies: swift-argument-parser".to_string(),
+            "Modified targets: orders-cli".to_string(),
+            "Swift tools version has been modified".to_string()
+        ]
+    );
+
+    let content = r#"
+let package = Package(
+    dependencies: [
+        .package(url: "https://github.com/vapor/vapor.git", from: "4.0.0"),
+    ],
+    targets: [
+        .testTarget(name: "AppTests", dependencies: ["App"]),
+    ]
+)
+"#;
+    let metadata = analyzer.extract_metadata("Package.swift", content);
+    assert_eq!(metadata.language, Some("Swift".to_string()));
+    assert_eq!(metadata.build_system, Some("Swift Package Manager".to_string()));
+    assert_eq!(metadata.dependencies, vec!["vapor"]);
+    assert_eq!(metadata.framework, Some("Vapor".to_string()));
+    assert_eq!(metadata.test_framework, Some("XCTest".to_string()));
+
+    let project_change = StagedFile {
+        path: "App.xcodeproj/project.pbxproj".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-				SWIFT_VERSION = 5.0;
++				SWIFT_VERSION = 6.0;
++		A1 /* XCRemoteSwiftPackageReference "swift-collections" */ = {
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+    let analysis = get_analyzer("App.xcodeproj/project.pbxproj")
+        .analyze("App.xcodeproj/project.pbxproj", &project_change);
+    assert!(analysis.contains(&"Modified package dependencies: swift-collections".to_string()));
+    assert!(analysis.contains(&"Modified build settings: SWIFT_VERSION".to_string()));
+}
+
+#[test]
+fn test_sql_migration_analyzer() {
+    let file = "db/migration/V3__split_customers.sql";
+    let analyzer = get_analyzer(file);
+    let change = StagedFile {
+        path: file.to_string(),
+        change_type: ChangeType::Added,
+        diff: r#"
++CREATE TABLE IF NOT EXISTS "addresses" (
++    id SERIAL PRIMARY KEY,
++    line1 TEXT NOT NULL
++);
++ALTER TABLE customers
++    ADD COLUMN address_id INTEGER,
++    ADD CONSTRAINT fk_address FOREIGN KEY (address_id) REFERENCES addresses (id),
++    ALTER COLUMN email DROP NOT NULL,
++    DROP COLUMN street;
++CREATE UNIQUE INDEX idx_addresses_line1 ON addresses (line1);
++DROP INDEX IF EXISTS idx_customers_street;
++drop table legacy_addresses;
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze(file, &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Destructive operations (data may be lost): DROP TABLE legacy_addresses, DROP COLUMN customers.street".to_string(),
+            "Database migration (Flyway)".to_string(),
+            "Tables added: addresses".to_string(),
+            "Tables dropped: legacy_addresses".to_string(),
+            "Columns added: customers.address_id".to_string(),
+            "Columns dropped: customers.street".to_string(),
+            "Columns altered: customers.email".to_string(),
+            "Indexes added: idx_addresses_line1 on addresses".to_string(),
+            "Indexes dropped: idx_customers_street".to_string(),
+        ]
+    );
+
+    let metadata = analyzer.extract_metadata(file, "");
+    assert_eq!(metadata.language, Some("SQL".to_string()));
+    assert_eq!(metadata.build_system, Some("Flyway".to_string()));
+
+    let diesel = get_analyzer("migrations/2024-01-01-000000_create_posts/up.sql");
+    let metadata = diesel.extract_metadata("migrations/2024-01-01-000000_create_posts/up.sql", "");
+    assert_eq!(metadata.build_system, Some("Diesel".to_string()));
+    let metadata = diesel.extract_metadata("migrations/20240101000000_create_posts.sql", "");
+    assert_eq!(metadata.build_system, Some("sqlx".to_string()));
+}
+
+#[test]
+fn test_django_migration_analyzer() {
+    let file = "shop/migrations/0007_order_total.py";
+    let analyzer = get_analyzer(file);
+    let change = StagedFile {
+        path: file.to_string(),
+        change_type: ChangeType::Added,
+        diff: r#"
++    operations = [
++        migrations.AddField(
++            model_name='order',
++            name='total',
++            field=models.DecimalField(max_digits=10, decimal_places=2),
++        ),
++        migrations.RemoveField(model_name='order', name='legacy_total'),
++        migrations.DeleteModel(name='Coupon'),
++    ]
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze(file, &change);
+    assert_eq!(
+        analysis[0],
+        "Destructive operations (data may be lost): RemoveField order.legacy_total, DeleteModel Coupon"
+    );
+    assert!(analysis.contains(&"Database migration (Django)".to_string()));
+    assert!(analysis.contains(&"Columns added: order.total".to_string()));
+    assert!(analysis.contains(&"Tables dropped: Coupon".to_string()));
+}
+
+#[test]
+fn test_dockerfile_analyzer() {
+    let analyzer = get_analyzer("services/api/Dockerfile");
+    let change = StagedFile {
+        path: "services/api/Dockerfile".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-FROM node:18-alpine AS build
++FROM node:20-alpine AS build
+ RUN apk add --no-cache \
+-    python3
++    python3 \
++    git
++FROM nginx:1.27 AS runtime
++COPY --from=build /app/dist /usr/share/nginx/html
++ADD https://example.com/config.tar.gz /etc/app/
+-EXPOSE 3000
++EXPOSE 8080
+-USER node
++USER root
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("services/api/Dockerfile", &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Security-relevant changes: switches to the root user, ADD of remote URL https://example.com/config.tar.gz".to_string(),
+            "Base image changes: node:18-alpine → node:20-alpine".to_string(),
+            "Build stages added: runtime".to_string(),
+            "Multi-stage build copies have been modified".to_string(),
+            "Newly exposed ports: 8080".to_string(),
+            "Ports no longer exposed: 3000".to_string(),
+            "Added packages: git".to_string(),
+            "User changed: node → root".to_string(),
+        ]
+    );
+
+    let metadata = analyzer.extract_metadata(
+        "Dockerfile",
+        "FROM rust:1.80 AS builder\nFROM debian:bookworm-slim\n",
+    );
+    assert_eq!(metadata.build_system, Some("Docker".to_string()));
+    assert_eq!(metadata.dependencies, vec!["rust:1.80", "debian:bookworm-slim"]);
+}
+
+#[test]
+fn test_cpp_analyzer() {
+    let analyzer = get_analyzer("test.cpp");
+    let change = StagedFile {
+        path: "test.cpp".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
++void newFunction() {
++    std::cout << "Hello, world!" << std::endl;
++}
+-class OldClass {
+-    OldClass() {}
+-}
++class NewClass {
++    NewClass() {}
++}
++#include <iostream>
+-#include <vector>
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("test.cpp", &change);
+    println!("C++ Test Debug: Analysis results: {:?}", analysis);
+    assert!(analysis.contains(&"Modified functions: newFunction".to_string()));
+    assert!(analysis
+        .iter()
+        .any(|s| s.contains("OldClass") && s.contains("NewClass")));
+    assert!(analysis.contains(&"Include statements have been modified".to_string()));
+}
+
+#[test]
+fn test_cpp_signature_changes() {
+    let analyzer = get_analyzer("src/parser.hpp");
+    let change = StagedFile {
+        path: "src/parser.hpp".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-    std::string Parser::parse(const std::string& input) const;
++    std::string Parser::parse(std::string_view input) const;
++    #include "tokens.hpp"
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("src/parser.hpp", &change);
+    assert!(analysis.contains(&"Changed function signatures: Parser::parse".to_string()));
+    assert!(analysis.contains(&"Added includes: \"tokens.hpp\"".to_string()));
+}
+
+#[test]
+fn test_cmake_targets_and_dependencies() {
+    let analyzer = get_analyzer("engine/CMakeLists.txt");
+    let change = StagedFile {
+        path: "engine/CMakeLists.txt".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
++find_package(fmt REQUIRED)
+ add_library(engine src/engine.cpp)
+-target_link_libraries(engine PRIVATE Boost::system)
++target_link_libraries(engine PRIVATE Boost::system fmt::fmt)
++add_executable(engine_tests tests/main.cpp)
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("engine/CMakeLists.txt", &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Modified targets: engine_tests".to_string(),
+            "Modified dependencies: fmt, fmt::fmt".to_string()
+        ]
+    );
+
+    // Reordering the linked libraries changes none of them; dropping one does
+    let diff = "-target_link_libraries(engine PRIVATE Boost::system fmt::fmt)\n\
+                +target_link_libraries(engine PRIVATE fmt::fmt Boost::system)\n\
+                -target_link_libraries(engine_tests PRIVATE engine GTest::gtest)\n\
+                +target_link_libraries(engine_tests PRIVATE engine)\n";
+    let change = StagedFile {
+        diff: diff.to_string(),
+        ..change
+    };
+    let analysis = analyzer.analyze("engine/CMakeLists.txt", &change);
+    assert_eq!(analysis, vec!["Modified dependencies: GTest::gtest".to_string()]);
+
+    let content = r#"
+project(Engine VERSION 2.1)
+FetchContent_Declare(googletest URL https://example.com/googletest.zip)
+find_package(GTest REQUIRED)
+"#;
+    let metadata = analyzer.extract_metadata("CMakeLists.txt", content);
+    assert_eq!(metadata.version, Some("2.1".to_string()));
+    assert_eq!(metadata.dependencies, vec!["GTest", "googletest"]);
+    assert_eq!(metadata.test_framework, Some("GoogleTest".to_string()));
+}
+
+#[test]
+fn test_cpp_analyzer_metadata() {
+    let analyzer = get_analyzer("CMakeLists.txt");
+    let content = r#"
+project(MyProject VERSION 1.0)
+find_package(Boost REQUIRED)
+    "#;
+
+    let metadata = analyzer.extract_metadata("CMakeLists.txt", content);
+    assert_eq!(metadata.language, Some("C++".to_string()));
+    assert_eq!(metadata.build_system, Some("CMake".to_string()));
+    assert_eq!(metadata.version, Some("1.0".to_string()));
+    assert!(metadata.dependencies.contains(&"Boost".to_string()));
+}
+
+#[test]
+fn test_go_analyzer() {
+    let analyzer = get_analyzer("pkg/server_test.go");
+    let change = StagedFile {
+        path: "pkg/server_test.go".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
++//go:build integration
++func (s *Server) Start(ctx context.Context) error {
++func helper() {}
++type Handler interface {
+-type Options struct {
++	"net/http"
+ 	tests := []struct {
++		{name: "empty body", input: ""},
+-		{"nil request", nil},
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("pkg/server_test.go", &change);
+    assert!(analysis.contains(&"Modified exported functions: Start".to_string()));
+    assert!(analysis.contains(&"Modified exported structs: Options".to_string()));
+    assert!(analysis.contains(&"Modified exported interfaces: Handler".to_string()));
+    assert!(analysis.contains(&"Build constraints have been modified".to_string()));
+    assert!(analysis.contains(
+        &"Modified table-driven test cases: empty body, nil request".to_string()
+    ));
+    assert!(analysis.contains(&"Import statements have been modified".to_string()));
+}
+
+#[test]
+fn test_go_mod_analyzer() {
+    let analyzer = get_analyzer("go.mod");
+    let change = StagedFile {
+        path: "go.mod".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-go 1.21
++go 1.22
+-	github.com/gin-gonic/gin v1.9.0
++	github.com/gin-gonic/gin v1.9.1
++require golang.org/x/sync v0.7.0
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("go.mod", &change);
+    assert!(analysis.contains(
+        &"Modified dependencies: github.com/gin-gonic/gin, golang.org/x/sync".to_string()
+    ));
+    assert!(analysis.contains(&"Go version directive has been modified".to_string()));
+
+    let content = r#"
+module example.com/app
+
+go 1.22
+
+require (
+	github.com/gin-gonic/gin v1.9.1
+	github.com/stretchr/testify v1.9.0 // indirect
+)
+"#;
+    let metadata = analyzer.extract_metadata("go.mod", content);
+    assert_eq!(metadata.language, Some("Go".to_string()));
+    assert_eq!(metadata.build_system, Some("Go modules".to_string()));
+    assert_eq!(metadata.framework, Some("Gin".to_string()));
+    assert_eq!(metadata.test_framework, Some("testify".to_string()));
+    assert_eq!(
+        metadata.dependencies,
+        vec!["github.com/gin-gonic/gin", "github.com/stretchr/testify"]
+    );
+}
+
+#[test]
+fn test_protobuf_analyzer() {
+    let analyzer = get_analyzer("proto/orders.proto");
+    let change = StagedFile {
+        path: "proto/orders.proto".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ @@ -5,14 +5,16 @@ message Order {
+   string id = 1;
+-  int32 quantity = 2;
+-  double total = 3;
+-  string coupon = 4;
++  int64 quantity = 2;
++  double amount = 3;
++  repeated string tags = 5;
+ }
++
++message Refund {
++  string order_id = 1;
++}
+ 
+ service OrderService {
+   rpc GetOrder(GetOrderRequest) returns (Order);
+-  rpc ListOrders(ListOrdersRequest) returns (ListOrdersResponse);
+-  rpc DeleteOrder(DeleteOrderRequest) returns (Empty);
++  rpc ListOrders(ListOrdersRequest) returns (stream Order);
++  rpc RefundOrder(RefundOrderRequest) returns (Refund);
+ }
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("proto/orders.proto", &change);
+    assert_eq!(
+        analysis[0],
+        "Wire-compatibility risks: Order.quantity type changed (int32 → int64), \
+         Order field number 3 reassigned (total → amount), \
+         Order.coupon removed without reserving field number 4, \
+         OrderService.ListOrders signature changed \
+         ((ListOrdersRequest) returns (ListOrdersResponse) → (ListOrdersRequest) returns (stream Order)), \
+         OrderService.DeleteOrder removed"
+    );
+    assert!(analysis.contains(&"Messages added: Refund".to_string()));
+    assert!(analysis.contains(
+        &"Fields added: Order.amount, Order.tags, Refund.order_id".to_string()
+    ));
+    assert!(analysis.contains(&"Fields removed: Order.total, Order.coupon".to_string()));
+    assert!(analysis.contains(&"RPCs added: OrderService.RefundOrder".to_string()));
+    assert!(analysis.contains(&"RPCs removed: OrderService.DeleteOrder".to_string()));
+
+    let content = r#"
+syntax = "proto3";
+import "google/protobuf/timestamp.proto";
+
+service OrderService {
+  rpc GetOrder(GetOrderRequest) returns (Order);
+}
+"#;
+    let metadata = analyzer.extract_metadata("proto/orders.proto", content);
+    assert_eq!(metadata.language, Some("Protocol Buffers".to_string()));
+    assert_eq!(metadata.framework, Some("gRPC".to_string()));
+    assert_eq!(metadata.dependencies, vec!["google/protobuf/timestamp.proto"]);
+}
+
+#[test]
+fn test_graphql_analyzer() {
+    let analyzer = get_analyzer("schema/orders.graphql");
+    let change = StagedFile {
+        path: "schema/orders.graphql".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ @@ -1,20 +1,22 @@
+ type Order {
+   id: ID!
+-  total: Float!
+-  customer: Customer!
+-  note: String
++  total: Float
++  customer: Customer! @deprecated(reason: "Use buyer")
++  buyer: Customer!
++  note: Int
+ }
+ 
+ input OrderFilter {
+   status: OrderStatus
++  region: String!
+ }
+ 
+ enum OrderStatus {
+   PENDING
+-  CANCELLED
++  SHIPPED
+ }
+-
+-type Coupon {
+-  code: String!
+-}
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("schema/orders.graphql", &change);
+    assert_eq!(
+        analysis[0],
+        "Breaking changes: type Coupon removed, \
+         Order.total nullability changed (Float! → Float), \
+         Order.note type changed (String → Int), \
+         OrderStatus.CANCELLED removed, \
+         required input OrderFilter.region added"
+    );
+    assert!(analysis.contains(&"Types removed: Coupon".to_string()));
+    assert!(analysis.contains(
+        &"Fields added: Order.buyer, OrderFilter.region, OrderStatus.SHIPPED".to_string()
+    ));
+    assert!(analysis.contains(
+        &"Fields removed: OrderStatus.CANCELLED, Coupon.code".to_string()
+    ));
+    assert!(analysis.contains(&"Deprecated: Order.customer (Use buyer)".to_string()));
+
+    let metadata = analyzer.extract_metadata(
+        "schema/orders.graphql",
+        "type Order @key(fields: \"id\") {\n  id: ID!\n}\n",
+    );
+    assert_eq!(metadata.language, Some("GraphQL".to_string()));
+    assert_eq!(metadata.framework, Some("Apollo Federation".to_string()));
+}
+
+#[test]
+fn test_css_analyzer() {
+    let analyzer = get_analyzer("styles/app.scss");
+    let change = StagedFile {
+        path: "styles/app.scss".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ @@ -1,12 +1,20 @@
+ :root {
+-  --color-primary: #2563eb;
++  --color-primary: #7c3aed;
+ }
+ 
+ .header {
+-  display: block;
++  display: flex;
++  gap: 1rem;
+ }
+-
+-.banner {
+-  color: red;
+-}
++
++.card {
++  border-radius: 8px;
++  &__title {
++    font-weight: 600;
++  }
++}
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("styles/app.scss", &change);
+    assert!(analysis.contains(&"New selectors: .card, &__title".to_string()));
+    assert!(analysis.contains(&"Removed selectors: .banner".to_string()));
+    assert!(analysis.contains(&"Modified rules: :root, .header".to_string()));
+    assert!(analysis.contains(&"Modified design tokens: --color-primary".to_string()));
+    assert!(analysis.contains(
+        &"Style changes: layout, theming, new component styles".to_string()
+    ));
+
+    let metadata = analyzer.extract_metadata("styles/app.scss", "@tailwind base;\n");
+    assert_eq!(metadata.language, Some("SCSS".to_string()));
+    assert_eq!(metadata.framework, Some("Tailwind CSS".to_string()));
+}
+
+#[test]
+fn test_tailwind_config_analyzer() {
+    let analyzer = get_analyzer("tailwind.config.js");
+    let change = StagedFile {
+        path: "tailwind.config.js".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ @@ -1,16 +1,18 @@
+ module.exports = {
+-  content: ['./src/**/*.{js,jsx}'],
++  content: ['./src/**/*.{js,jsx,ts,tsx}'],
+-  darkMode: 'media',
++  darkMode: 'class',
+   theme: {
+     extend: {
+       colors: {
+-        brand: '#2563eb',
++        brand: '#7c3aed',
+       },
++      spacing: {
++        18: '4.5rem',
++      },
+     },
+   },
+   plugins: [
++    require('@tailwindcss/forms'),
+   ],
+ }
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("tailwind.config.js", &change);
+    assert!(analysis.contains(
+        &"Modified Tailwind theme values: colors.brand, spacing.18".to_string()
+    ));
+    assert!(analysis.contains(&"Modified Tailwind settings: darkMode".to_string()));
+    assert!(analysis.contains(&"Modified Tailwind plugins: @tailwindcss/forms".to_string()));
+    assert!(analysis.contains(&"Tailwind content paths have been modified".to_string()));
+
+    let metadata = analyzer.extract_metadata(
+        "tailwind.config.js",
+        "module.exports = {\n  plugins: [require('@tailwindcss/forms')],\n}\n",
+    );
+    assert_eq!(metadata.framework, Some("Tailwind CSS".to_string()));
+    assert_eq!(metadata.plugins, vec!["@tailwindcss/forms"]);
+}
+
+#[test]
+fn test_html_template_analyzer() {
+    let analyzer = get_analyzer("templates/login.html");
+    let change = StagedFile {
+        path: "templates/login.html".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+-{% extends "base.html" %}
++{% extends "layouts/auth.html" %}
+ {% block content %}
+-{% include "partials/flash.html" %}
++{% include "partials/alerts.html" %}
+-<form action="/login" method="post">
++<form action="{{ url_for('auth.login') }}" method="post">
++  <a href="/password/reset">Forgot your password?</a>
+ </form>
++<script src="/static/js/login.js"></script>
+ {% endblock %}
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("templates/login.html", &change);
+    assert!(analysis.contains(
+        &"Modified parent templates: base.html, layouts/auth.html".to_string()
+    ));
+    assert!(analysis.contains(
+        &"Modified includes and partials: partials/flash.html, partials/alerts.html".to_string()
+    ));
+    assert!(analysis.contains(
+        &"Modified forms: POST /login, POST {{ url_for('auth.login') }}".to_string()
+    ));
+    assert!(analysis.contains(
+        &"Modified route references: /login, auth.login, /password/reset".to_string()
+    ));
+    assert!(analysis.contains(&"Modified scripts and stylesheets: /static/js/login.js".to_string()));
+
+    let change = StagedFile {
+        path: "app/views/posts/show.html.erb".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
++<% content_for :sidebar do %>
++  <%= render "posts/related", post: @post %>
++  <%= link_to "Edit", edit_post_path(@post) %>
++<% end %>
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+    let analyzer = get_analyzer("app/views/posts/show.html.erb");
+    let analysis = analyzer.analyze("app/views/posts/show.html.erb", &change);
+    assert!(analysis.contains(&"Modified blocks: sidebar".to_string()));
+    assert!(analysis.contains(&"Modified includes and partials: posts/related".to_string()));
+    assert!(analysis.contains(&"Modified route references: edit_post_path".to_string()));
+
+    let metadata = analyzer.extract_metadata("app/views/posts/show.html.erb", "");
+    assert_eq!(metadata.language, Some("HTML".to_string()));
+    assert_eq!(metadata.framework, Some("ERB".to_string()));
+}
+
+#[test]
+fn test_vue_component_analyzer() {
+    let analyzer = get_analyzer("src/components/CartItem.vue");
+    let change = StagedFile {
+        path: "src/components/CartItem.vue".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ <script setup lang="ts">
++import { useCartStore } from '@/stores/cart'
++
+ const props = defineProps<{
+   item: CartItem
+-  editable: boolean
++  readonly?: boolean
++  quantity: number
+ }>()
+-const emit = defineEmits(['remove'])
++const emit = defineEmits(['remove', 'update:quantity'])
++const cart = useCartStore()
+ </script>
+ 
+ <template>
+   <div class="cart-item">
+-    <span>{{ item.name }}</span>
++    <ItemTitle :item="item" />
++    <QuantityPicker v-model="quantity" @change="emit('update:quantity', $event)" />
+   </div>
+ </template>
+ 
+ <style scoped>
+ .cart-item {
+-  padding: 4px;
++  padding: 8px;
+ }
+ </style>
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("src/components/CartItem.vue", &change);
+    assert!(analysis.contains(&"Modified blocks: script, template, style".to_string()));
+    assert!(analysis.contains(&"Added props: readonly, quantity".to_string()));
+    assert!(analysis.contains(&"Removed props: editable".to_string()));
+    assert!(analysis.contains(&"Added emitted events: update:quantity".to_string()));
+    assert!(analysis.contains(&"Modified store usage: useCartStore".to_string()));
+    assert!(analysis.contains(
+        &"Modified child components: ItemTitle, QuantityPicker".to_string()
+    ));
+
+    let metadata = analyzer.extract_metadata(
+        "src/components/CartItem.vue",
+        "<script setup lang=\"ts\">\n</script>\n",
+    );
+    assert_eq!(metadata.language, Some("TypeScript".to_string()));
+    assert_eq!(metadata.framework, Some("Vue".to_string()));
+}
+
+#[test]
+fn test_svelte_component_analyzer() {
+    let analyzer = get_analyzer("src/lib/Counter.svelte");
+    let change = StagedFile {
+        path: "src/lib/Counter.svelte".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ @@ -3,9 +3,10 @@
+   export let count = 0;
++  export let step = 1;
+   const dispatch = createEventDispatcher();
+ </script>
+ 
+-<button on:click={() => dispatch('increment')}>
++<button on:click={() => dispatch('change', count + step)}>
+-  Clicked {count} times
++  Clicked {count} times by {$user.name}
+ </button>
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("src/lib/Counter.svelte", &change);
+    assert!(analysis.contains(&"Modified blocks: script, markup".to_string()));
+    assert!(analysis.contains(&"Added props: step".to_string()));
+    assert!(analysis.contains(&"Added emitted events: change".to_string()));
+    assert!(analysis.contains(&"Removed emitted events: increment".to_string()));
+    assert!(analysis.contains(&"Modified store usage: $user".to_string()));
+}
+
+#[test]
+fn test_package_json_analyzer() {
+    let analyzer = get_analyzer("web/package.json");
+    let change = StagedFile {
+        path: "web/package.json".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ @@ -2,14 +2,14 @@
+   "version": "1.4.0",
+   "scripts": {
+-    "build": "vite build"
++    "build": "vite build --mode production"
+   },
+   "dependencies": {
+-    "moment": "^2.29.4",
+-    "react": "^17.0.2",
++    "dayjs": "^1.11.10",
++    "react": "^18.2.0",
+-    "zod": "^3.22.2"
++    "zod": "^3.22.4"
+   },
+   "devDependencies": {
+-    "vitest": "^1.6.0"
++    "vitest": "^1.2.0"
+   }
+        "#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let analysis = analyzer.analyze("web/package.json", &change);
+    assert_eq!(
+        analysis,
+        vec![
+            "Major version bumps (potentially breaking): react ^17.0.2 → ^18.2.0",
+            "Added dependencies: dayjs ^1.11.10",
+            "Removed dependencies: moment ^2.29.4",
+            "Upgraded dependencies: react ^17.0.2 → ^18.2.0, zod ^3.22.2 → ^3.22.4",
+            "Downgraded dependencies: vitest (dev) ^1.6.0 → ^1.2.0",
+        ]
+    );
+
+    let metadata = analyzer.extract_metadata(
+        "package.json",
+        r#"{"version": "1.4.0", "dependencies": {"react": "^18.2.0"}}"#,
+    );
+    assert_eq!(metadata.framework, Some("React".to_string()));
+    assert_eq!(metadata.build_system, Some("npm".to_string()));
+}
+
+#[test]
+fn test_lockfile_analyzers() {
+    let lockfile = |path: &str, diff: &str| StagedFile {
+        path: path.to_string(),
+        change_type: ChangeType::Modified,
+        diff: diff.to_string(),
+        analysis: Vec::new(),
+        content_excluded: true,
+        stage_status: StageStatus::Staged,
+    };
+
+    let npm = lockfile(
+        "package-lock.json",
+        r#"
+     "node_modules/react": {
+-      "version": "17.0.2",
++      "version": "18.2.0",
+       "resolved": "https://registry.npmjs.org/react/-/react-18.2.0.tgz",
+     },
++    "node_modules/dayjs": {
++      "version": "1.11.10",
++    },
+"#,
+    );
+    assert_eq!(
+        get_analyzer("package-lock.json").analyze("package-lock.json", &npm),
+        vec![
+            "Major version bumps (potentially breaking): react 17.0.2 → 18.2.0",
+            "Added dependencies: dayjs 1.11.10",
+            "Upgraded dependencies: react 17.0.2 → 18.2.0",
+        ]
+    );
+
+    let yarn = lockfile(
+        "yarn.lock",
+        r#"
+ @@ -10,7 +10,7 @@ "@babel/core@^7.22.0":
+-  version "7.22.5"
++  version "7.24.0"
+ 
+-moment@^2.29.4:
+-  version "2.29.4"
+"#,
+    );
+    assert_eq!(
+        get_analyzer("yarn.lock").analyze("yarn.lock", &yarn),
+        vec![
+            "Removed dependencies: moment 2.29.4",
+            "Upgraded dependencies: @babel/core 7.22.5 → 7.24.0",
+        ]
+    );
+
+    let pnpm = lockfile(
+        "pnpm-lock.yaml",
+        r#"
+-  /lodash@4.17.20:
++  /lodash@4.17.21:
+     resolution: {integrity: sha512-abc}
++  /@vitejs/plugin-react@4.2.1(vite@5.0.0):
+"#,
+    );
+    assert_eq!(
+        get_analyzer("pnpm-lock.yaml").analyze("pnpm-lock.yaml", &pnpm),
+        vec![
+            "Added dependencies: @vitejs/plugin-react 4.2.1",
+            "Upgraded dependencies: lodash 4.17.20 → 4.17.21",
+        ]
+    );
+}
+
+#[test]
+fn test_cargo_manifest_contents() {
+    let old = r#"
+[package]
+name = "demo"
+version = "0.3.0"
+edition = "2021"
+rust-version = "1.70"
+
+[dependencies]
+anyhow = "1.0"
+clap = { version = "3.2", features = ["derive"] }
+reqwest = { version = "0.11", default-features = false, features = ["json"] }
+local = { path = "../local" }
+
+[features]
+default = ["cli"]
+cli = []
+legacy = []
+"#;
+    let new = r#"
+[package]
+name = "demo"
+version = "0.4.0"
+edition = "2021"
+rust-version = "1.74"
+
+[dependencies]
+anyhow = "1.0"
+clap = { version = "4.5", features = ["derive", "env"] }
+reqwest = { version = "0.12", features = ["json"] }
+serde = { version = "1.0", features = ["derive"] }
+
+[dev-dependencies]
+tempfile = "3.10"
+
+[features]
+default = ["cli", "tls"]
+cli = []
+tls = ["reqwest/rustls-tls"]
+"#;
+    let change = StagedFile {
+        path: "Cargo.toml".to_string(),
+        change_type: ChangeType::Modified,
+        diff: String::new(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    assert!(analyzes_contents("crates/demo/Cargo.toml"));
+    assert_eq!(
+        analyze_contents(&change, Some(old), Some(new)),
+        vec![
+            "Package version changed: 0.3.0 → 0.4.0",
+            "Minimum supported Rust version changed: 1.70 → 1.74",
+            "Major version bumps (potentially breaking): clap 3.2 → 4.5, reqwest 0.11 → 0.12",
+            "Added dependencies: serde 1.0, tempfile (dev) 3.10",
+            "Removed dependencies: local path ../local",
+            "Upgraded dependencies: clap 3.2 → 4.5, reqwest 0.11 → 0.12",
+            "Dependency features changed: clap (+env), reqwest (default features on)",
+            "Added features: tls",
+            "Removed features: legacy",
+            "Modified features: default",
+        ]
+    );
+}
+
+#[test]
+fn test_cargo_lock_contents_and_diff() {
+    let old = "version = 3\n\n[[package]]\nname = \"libc\"\nversion = \"0.2.153\"\n\n[[package]]\nname = \"syn\"\nversion = \"1.0.109\"\n";
+    let new = "version = 3\n\n[[package]]\nname = \"libc\"\nversion = \"0.2.155\"\n\n[[package]]\nname = \"syn\"\nversion = \"1.0.109\"\n\n[[package]]\nname = \"syn\"\nversion = \"2.0.66\"\n";
+    let change = StagedFile {
+        path: "Cargo.lock".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ [[package]]
+ name = "libc"
+-version = "0.2.153"
++version = "0.2.155"
+"#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: true,
+        stage_status: StageStatus::Staged,
+    };
+
+    assert_eq!(
+        analyze_contents(&change, Some(old), Some(new)),
+        vec![
+            "Upgraded dependencies: libc 0.2.153 → 0.2.155, syn 1.0.109 → 1.0.109, 2.0.66",
+        ]
+    );
+    // Without the contents, the diff shows what it can
+    assert_eq!(
+        get_analyzer("Cargo.lock").analyze("Cargo.lock", &change),
+        vec!["Upgraded dependencies: libc 0.2.153 → 0.2.155"]
+    );
+    // Contents that aren't valid TOML fall back to the diff
+    assert_eq!(
+        analyze_contents(&change, Some(old), Some("[[package")),
+        vec!["Upgraded dependencies: libc 0.2.153 → 0.2.155"]
+    );
+}
+
+#[test]
+fn test_cargo_manifest_diff() {
+    let analyzer = get_analyzer("Cargo.toml");
+    let change = StagedFile {
+        path: "Cargo.toml".to_string(),
+        change_type: ChangeType::Modified,
+        diff: r#"
+ [dependencies]
+-tokio = { version = "1.37", features = ["full"] }
++tokio = { version = "1.38", features = ["full"] }
++serde = "1.0"
+ 
+ [dev-dependencies]
+-mockall = "0.11"
+"#
+        .to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    assert_eq!(
+        analyzer.analyze("Cargo.toml", &change),
+        vec![
+            "Added dependencies: serde 1.0",
+            "Removed dependencies: mockall (dev) 0.11",
+            "Upgraded dependencies: tokio 1.37 → 1.38",
+        ]
+    );
+
+    let metadata = analyzer.extract_metadata(
+        "Cargo.toml",
+        "[package]\nname = \"demo\"\nversion = \"0.4.0\"\n\n[dependencies]\naxum = \"0.7\"\n",
+    );
+    assert_eq!(metadata.version, Some("0.4.0".to_string()));
+    assert_eq!(metadata.framework, Some("axum".to_string()));
+    assert_eq!(metadata.build_system, Some("Cargo".to_string()));
+}
+
+#[cfg(not(feature = "tree-sitter"))]
+#[test]
+fn test_source_files_are_analyzed_from_diffs_by_default() {
+    assert!(!analyzes_contents("src/lib.rs"));
+    assert!(!analyzes_contents("app/models.py"));
+}
+
+#[cfg(feature = "tree-sitter")]
+#[test]
+fn test_rust_symbols_are_compared_with_tree_sitter() {
+    let old = r#"
+pub struct Config {
+    name: String,
+}
+
+impl Config {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string() }
+    }
+
+    pub fn name(&self) -> &str { &self.name }
+}
+
+fn helper() {}
+"#;
+    let new = r#"
+pub struct Config {
+    name: String,
+    verbose: bool,
+}
+
+impl Config {
+    pub fn new(name: &str, verbose: bool) -> Self {
+        Self { name: name.to_string(), verbose }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new("default", false)
+    }
+}
+"#;
+    let change = StagedFile {
+        path: "src/config.rs".to_string(),
+        change_type: ChangeType::Modified,
+        diff: String::new(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    assert!(analyzes_contents("src/config.rs"));
+    assert_eq!(
+        analyze_contents(&change, Some(old), Some(new)),
+        vec![
+            "Functions added: <Config as Default>::default",
+            "Functions removed: helper",
+            "Signatures changed: pub fn new(name: &str) -> Self → pub fn new(name: &str, verbose: bool) -> Self",
+            "Types modified: Config",
+        ]
+    );
+}
+
+#[cfg(feature = "tree-sitter")]
+#[test]
+fn test_python_and_typescript_symbols_are_compared_with_tree_sitter() {
+    let staged = |path: &str| StagedFile {
+        path: path.to_string(),
+        change_type: ChangeType::Added,
+        diff: String::new(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+
+    let python = "class Store:\n    def get(self, key):\n        return self.items[key]\n";
+    assert_eq!(
+        analyze_contents(&staged("app/store.py"), None, Some(python)),
+        vec!["Functions added: Store.get", "Types added: Store"]
+    );
+
+    let old = "export const load = async (id: string) => fetch(id);\n";
+    let new = "export const load = async (id: string, cache = true) => fetch(id);\ninterface Options { cache: boolean }\n";
+    assert_eq!(
+        analyze_contents(&staged("src/load.ts"), Some(old), Some(new)),
+        vec![
+            "Signatures changed: load = async (id: string) → load = async (id: string, cache = true)",
+            "Types added: Options",
+        ]
+    );
+
+    // A file that doesn't parse is analyzed from its diff instead
+    assert!(analyze_contents(&staged("src/broken.ts"), None, Some("function (")).is_empty());
+}
+
+fn png(width: u32, height: u32, padding: usize) -> Vec<u8> {
+    let mut bytes = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+    bytes.extend(width.to_be_bytes());
+    bytes.extend(height.to_be_bytes());
+    bytes.extend(vec![0; padding]);
+    bytes
+}
+
+#[test]
+fn test_binary_images_report_dimensions_and_size() {
+    let old = png(640, 480, 2000);
+    let new = png(1280, 960, 3000);
+    assert_eq!(
+        analyze_binary(Some(&old), Some(&new)),
+        vec![
+            "Image dimensions changed: 640×480 → 1280×960",
+            "Size changed: 2.0 KB → 3.0 KB (+1000 B)",
+        ]
+    );
+    assert_eq!(
+        analyze_binary(None, Some(&png(16, 16, 0))),
+        vec!["Added PNG image, 16×16, 24 B"]
+    );
+
+    // A JPEG keeps its dimensions in the first start-of-frame segment, after APP0
+    let mut jpeg = b"\xff\xd8\xff\xe0\x00\x10JFIF\x00\x01\x01\x00\x00\x01\x00\x01\x00\x00".to_vec();
+    jpeg.extend(b"\xff\xc0\x00\x11\x08\x02\x58\x03\x20\x03");
+    assert_eq!(
+        analyze_binary(Some(&jpeg), None),
+        vec!["Removed JPEG image, 800×600, 30 B"]
+    );
+}
+
+#[test]
+fn test_binary_fonts_and_archives() {
+    let name: Vec<u8> = "Inter Bold"
+        .encode_utf16()
+        .flat_map(u16::to_be_bytes)
+        .collect();
+    let mut font = b"\x00\x01\x00\x00\x00\x01\x00\x00\x00\x00\x00\x00".to_vec();
+    font.extend(b"name\x00\x00\x00\x00\x00\x00\x00\x1c");
+    font.extend((30 + name.len() as u32).to_be_bytes());
+    font.extend([0, 0, 0, 1, 0, 18]);
+    font.extend([0, 3, 0, 1, 4, 9, 0, 4]);
+    font.extend((name.len() as u16).to_be_bytes());
+    font.extend([0, 0]);
+    font.extend(&name);
+    assert_eq!(
+        analyze_binary(None, Some(&font)),
+        vec!["Added font Inter Bold (TrueType), 66 B"]
+    );
+
+    let zip = |entries: u16| {
+        let mut bytes = b"PK\x03\x04".to_vec();
+        bytes.extend(vec![0; 26]);
+        bytes.extend(b"PK\x05\x06\x00\x00\x00\x00");
+        bytes.extend(entries.to_le_bytes());
+        bytes.extend(entries.to_le_bytes());
+        bytes.extend(vec![0; 10]);
+        bytes
+    };
+    assert_eq!(
+        analyze_binary(Some(&zip(3)), Some(&zip(5))),
+        vec!["Archive entries changed: 3 → 5", "Size unchanged: 52 B"]
+    );
+    assert_eq!(
+        analyze_binary(None, Some(b"\x00\x01\x02")),
+        vec!["Added binary file, 3 B"]
+    );
+}
+
+#[test]
+fn test_svg_dimensions_are_compared() {
+    let change = StagedFile {
+        path: "assets/logo.svg".to_string(),
+        change_type: ChangeType::Modified,
+        diff: String::new(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    };
+    let old = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" stroke-width="2"><path d="M0 0"/></svg>"#;
+    let new = r#"<svg xmlns="http://www.w3.org/2000/svg" width="48" height="48"><path d="M0 0h48"/></svg>"#;
+
+    assert!(analyzes_contents("assets/logo.svg"));
+    assert_eq!(
+        analyze_contents(&change, Some(old), Some(new)),
+        vec![
+            "Image dimensions changed: 24×24 → 48×48",
+            "Size changed: 99 B → 88 B (-11 B)",
+        ]
+    );
 }
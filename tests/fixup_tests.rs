@@ -0,0 +1,88 @@
+use git2::Repository;
+use git_iris::fixup::{suggest_fixup_targets, DEFAULT_MAX_COMMITS};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn numbered_lines(prefix: &str, count: usize) -> String {
+    (1..=count).map(|n| format!("{} {}\n", prefix, n)).collect()
+}
+
+fn commit_file(repo: &Repository, repo_path: &Path, name: &str, content: &str, message: &str) {
+    fs::write(repo_path.join(name), content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(name)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => Vec::new(),
+    };
+    let parents: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .unwrap();
+}
+
+fn stage_file(repo: &Repository, repo_path: &Path, name: &str, content: &str) {
+    fs::write(repo_path.join(name), content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(name)).unwrap();
+    index.write().unwrap();
+}
+
+fn setup_repo() -> (TempDir, Repository) {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let path = temp_dir.path();
+
+    commit_file(&repo, path, "parser.rs", &numbered_lines("parse", 20), "Add parser");
+    commit_file(&repo, path, "lexer.rs", &numbered_lines("lex", 20), "Add lexer");
+    let parser = numbered_lines("parse", 20).replace("parse 18\n", "parse eighteen\n");
+    commit_file(&repo, path, "parser.rs", &parser, "Tweak parser ending");
+
+    (temp_dir, repo)
+}
+
+#[test]
+fn test_modified_lines_point_to_the_commit_that_wrote_them() {
+    let (temp_dir, repo) = setup_repo();
+    let parser = fs::read_to_string(temp_dir.path().join("parser.rs")).unwrap();
+    stage_file(&repo, temp_dir.path(), "parser.rs", &parser.replace("parse 5\n", "parse five\n"));
+
+    let candidates = suggest_fixup_targets(temp_dir.path(), DEFAULT_MAX_COMMITS).unwrap();
+    assert_eq!(candidates[0].subject, "Add parser");
+    assert_eq!(candidates[0].blamed_lines, 1);
+    assert_eq!(candidates[0].shared_files, vec!["parser.rs".to_string()]);
+    assert_eq!(candidates[0].fixup_message(), "fixup! Add parser");
+
+    // The later parser commit shares the file but wrote none of the lines
+    assert_eq!(candidates[1].subject, "Tweak parser ending");
+    assert_eq!(candidates[1].blamed_lines, 0);
+    assert!(candidates.iter().all(|c| c.subject != "Add lexer"));
+}
+
+#[test]
+fn test_pure_additions_blame_the_surrounding_lines() {
+    let (temp_dir, repo) = setup_repo();
+    let parser = fs::read_to_string(temp_dir.path().join("parser.rs")).unwrap();
+    stage_file(
+        &repo,
+        temp_dir.path(),
+        "parser.rs",
+        &parser.replace("parse eighteen\n", "parse eighteen\nparse eighteen and a half\n"),
+    );
+
+    let candidates = suggest_fixup_targets(temp_dir.path(), DEFAULT_MAX_COMMITS).unwrap();
+    assert_eq!(candidates[0].subject, "Tweak parser ending");
+}
+
+#[test]
+fn test_new_files_have_no_fixup_target() {
+    let (temp_dir, repo) = setup_repo();
+    stage_file(&repo, temp_dir.path(), "emitter.rs", "emit\n");
+
+    assert!(suggest_fixup_targets(temp_dir.path(), DEFAULT_MAX_COMMITS)
+        .unwrap()
+        .is_empty());
+}
@@ -1,7 +1,12 @@
 use git2::Repository;
-use git_iris::config::Config;
-use git_iris::context::ChangeType;
-use git_iris::git::{commit, get_git_info};
+use git_iris::config::{Config, RecentCommitsConfig};
+use git_iris::context::{ChangeScope, ChangeType, StageStatus};
+use git_iris::exclusions::PromptFilter;
+use git_iris::git::{
+    amend_head_message, commit, commit_all, get_comment_char, get_commit_info, get_commit_message,
+    get_commit_patch, get_commits_between_with_progress, get_git_info, get_git_info_with_scope,
+    stage_all,
+};
 use git_iris::prompt::create_prompt;
 use git_iris::token_optimizer::TokenOptimizer;
 use std::fs;
@@ -124,6 +129,147 @@ fn test_commit() {
         .contains("Test commit message"));
 }
 
+#[test]
+fn test_recent_commit_settings() {
+    let temp_dir = setup_git_repo();
+    for i in 1..=3 {
+        fs::write(temp_dir.path().join("initial.txt"), format!("Change {}", i)).unwrap();
+        let repo = Repository::open(temp_dir.path()).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("initial.txt")).unwrap();
+        index.write().unwrap();
+        commit(temp_dir.path(), &format!("Change {}\n\nBody of change {}", i, i)).unwrap();
+    }
+
+    let mut config = Config::default();
+    let context = get_git_info(temp_dir.path(), &config).unwrap();
+    assert_eq!(context.recent_commits.len(), 4);
+    assert!(context.recent_commits[0].message.contains("Body of change 3"));
+
+    config.recent_commits.count = 2;
+    config.recent_commits.bodies = false;
+    let context = get_git_info(temp_dir.path(), &config).unwrap();
+    assert_eq!(context.recent_commits.len(), 2);
+    assert_eq!(context.recent_commits[0].message, "Change 3");
+
+    config.recent_commits.count = 0;
+    let context = get_git_info(temp_dir.path(), &config).unwrap();
+    assert!(context.recent_commits.is_empty());
+}
+
+#[test]
+fn test_recent_commit_overrides_per_command() {
+    let config: Config = toml::from_str(
+        r#"
+        default_provider = "openai"
+        providers = {}
+
+        [recent_commits]
+        count = 10
+        bodies = false
+
+        [recent_commits.commands.review]
+        count = 0
+
+        [recent_commits.commands.gen]
+        bodies = true
+        "#,
+    )
+    .unwrap();
+
+    let review = config.recent_commits.for_command("review");
+    assert_eq!((review.count, review.bodies), (0, false));
+    let gen = config.recent_commits.for_command("gen");
+    assert_eq!((gen.count, gen.bodies), (10, true));
+    let amend = config.recent_commits.for_command("amend");
+    assert_eq!((amend.count, amend.bodies), (10, false));
+}
+
+#[test]
+fn test_get_commit_info_and_amend() {
+    let temp_dir = setup_git_repo();
+
+    let new_file_path = temp_dir.path().join("amend_test.txt");
+    fs::write(&new_file_path, "Amend test content").unwrap();
+    let repo = Repository::open(temp_dir.path()).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("amend_test.txt")).unwrap();
+    index.write().unwrap();
+    commit(temp_dir.path(), "wip").unwrap();
+    let tree_before = repo.head().unwrap().peel_to_tree().unwrap().id();
+
+    // The committed changes become the staged files of the context
+    let context = get_commit_info(
+        temp_dir.path(),
+        "HEAD",
+        &RecentCommitsConfig::default(),
+        &PromptFilter::default(),
+    )
+    .unwrap();
+    assert_eq!(context.staged_files.len(), 1);
+    assert_eq!(context.staged_files[0].path, "amend_test.txt");
+    assert!(matches!(
+        context.staged_files[0].change_type,
+        ChangeType::Added
+    ));
+    assert!(context.staged_files[0].diff.contains("+Amend test content"));
+    assert!(context.unstaged_files.is_empty());
+    // Recent commits start at the parent of the commit
+    assert_eq!(context.recent_commits.len(), 1);
+    assert!(context.recent_commits[0].message.contains("Initial commit"));
+
+    amend_head_message(temp_dir.path(), "Add amend test file").unwrap();
+    assert_eq!(
+        get_commit_message(temp_dir.path(), "HEAD").unwrap(),
+        "Add amend test file"
+    );
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.tree_id(), tree_before);
+    assert_eq!(head.parent_count(), 1);
+}
+
+#[test]
+fn test_get_commit_info_applies_prompt_exclusions() {
+    let temp_dir = setup_git_repo();
+    fs::create_dir(temp_dir.path().join("fixtures")).unwrap();
+    fs::write(temp_dir.path().join("fixtures/data.txt"), "Fixture data").unwrap();
+    fs::write(temp_dir.path().join("notes.txt"), "Notes").unwrap();
+    stage_all(temp_dir.path(), true).unwrap();
+    commit(temp_dir.path(), "Add fixtures").unwrap();
+
+    let config = Config {
+        prompt_exclude: vec!["fixtures/**".to_string()],
+        ..Default::default()
+    };
+    let filter = PromptFilter::from_config(&config);
+    let context = get_commit_info(
+        temp_dir.path(),
+        "HEAD",
+        &RecentCommitsConfig::default(),
+        &filter,
+    )
+    .unwrap();
+    let fixture = context
+        .staged_files
+        .iter()
+        .find(|file| file.path == "fixtures/data.txt")
+        .unwrap();
+    assert!(fixture.content_excluded);
+    assert!(!fixture.diff.contains("Fixture data"));
+    let notes = context
+        .staged_files
+        .iter()
+        .find(|file| file.path == "notes.txt")
+        .unwrap();
+    assert!(!notes.content_excluded);
+    assert!(notes.diff.contains("+Notes"));
+
+    let patch = get_commit_patch(temp_dir.path(), "HEAD", &filter).unwrap();
+    assert!(patch.contains("fixtures/data.txt"));
+    assert!(!patch.contains("Fixture data"));
+    assert!(patch.contains("+Notes"));
+}
+
 #[test]
 fn test_multiple_staged_files() {
     let temp_dir = setup_git_repo();
@@ -233,6 +379,7 @@ fn test_binary_file() {
         .find(|file| file.path == "image.png")
         .unwrap();
     assert_eq!(binary_file.diff, "[Binary file changed]");
+    assert_eq!(binary_file.analysis, vec!["Added PNG image, 1×1, 67 B"]);
 
     // Check if the status is correct
     assert!(matches!(binary_file.change_type, ChangeType::Added));
@@ -470,3 +617,162 @@ fn test_token_optimization_integration() {
         "Large prompt should not end with truncation indicator"
     );
 }
+
+#[test]
+fn test_get_comment_char() {
+    let temp_dir = setup_git_repo();
+    let repo = Repository::open(temp_dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+
+    config.remove("core.commentChar").ok();
+    assert_eq!(get_comment_char(temp_dir.path()), '#');
+
+    config.set_str("core.commentChar", ";").unwrap();
+    assert_eq!(get_comment_char(temp_dir.path()), ';');
+
+    config.set_str("core.commentChar", "auto").unwrap();
+    assert_eq!(get_comment_char(temp_dir.path()), '#');
+}
+
+#[test]
+fn test_get_git_info_with_scope() {
+    let temp_dir = setup_git_repo();
+    let config = Config::default();
+    let repo = Repository::open(temp_dir.path()).unwrap();
+
+    // A staged new file, a tracked file modified after staging, and an untracked file
+    fs::write(temp_dir.path().join("staged.txt"), "Staged content").unwrap();
+    fs::write(temp_dir.path().join("initial.txt"), "Staged change").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("staged.txt")).unwrap();
+    index.add_path(Path::new("initial.txt")).unwrap();
+    index.write().unwrap();
+    fs::write(temp_dir.path().join("initial.txt"), "Unstaged change").unwrap();
+    fs::write(temp_dir.path().join("untracked.txt"), "Untracked").unwrap();
+
+    let all = get_git_info_with_scope(temp_dir.path(), &config, ChangeScope::All).unwrap();
+    assert_eq!(all.staged_files.len(), 2);
+    let status_of = |path: &str| {
+        all.staged_files
+            .iter()
+            .find(|file| file.path == path)
+            .map(|file| file.stage_status)
+    };
+    assert_eq!(status_of("staged.txt"), Some(StageStatus::Staged));
+    assert_eq!(status_of("initial.txt"), Some(StageStatus::PartiallyStaged));
+    assert_eq!(all.unstaged_files, vec!["untracked.txt".to_string()]);
+
+    let unstaged =
+        get_git_info_with_scope(temp_dir.path(), &config, ChangeScope::Unstaged).unwrap();
+    assert_eq!(unstaged.staged_files.len(), 1);
+    assert_eq!(unstaged.staged_files[0].path, "initial.txt");
+    assert!(unstaged.staged_files[0].diff.contains("+Unstaged change"));
+    assert!(!unstaged.staged_files[0].diff.contains("Initial content"));
+}
+
+#[test]
+fn test_commit_all() {
+    let temp_dir = setup_git_repo();
+    let config = Config::default();
+
+    fs::write(temp_dir.path().join("initial.txt"), "Modified content").unwrap();
+    fs::write(temp_dir.path().join("untracked.txt"), "Untracked").unwrap();
+
+    let context = get_git_info_with_scope(temp_dir.path(), &config, ChangeScope::All).unwrap();
+    assert_eq!(context.staged_files.len(), 1);
+    assert_eq!(context.staged_files[0].stage_status, StageStatus::Unstaged);
+
+    commit_all(temp_dir.path(), "Commit all tracked changes").unwrap();
+
+    let context = get_git_info_with_scope(temp_dir.path(), &config, ChangeScope::All).unwrap();
+    assert!(context.staged_files.is_empty());
+    assert_eq!(context.unstaged_files, vec!["untracked.txt".to_string()]);
+}
+
+#[test]
+fn test_staged_diff_excludes_later_edits() {
+    let temp_dir = setup_git_repo();
+    let config = Config::default();
+
+    fs::write(temp_dir.path().join("initial.txt"), "Staged change").unwrap();
+    let repo = Repository::open(temp_dir.path()).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("initial.txt")).unwrap();
+    index.write().unwrap();
+    fs::write(temp_dir.path().join("initial.txt"), "Edited after staging").unwrap();
+
+    let context = get_git_info(temp_dir.path(), &config).unwrap();
+    assert_eq!(context.staged_files.len(), 1);
+    assert!(context.staged_files[0].diff.contains("+Staged change"));
+    assert!(!context.staged_files[0].diff.contains("Edited after staging"));
+}
+
+#[test]
+fn test_get_commits_between_reports_progress() {
+    let temp_dir = setup_git_repo();
+    for n in 1..=3 {
+        fs::write(temp_dir.path().join("initial.txt"), format!("Version {}", n)).unwrap();
+        commit_all(temp_dir.path(), &format!("Change {}", n)).unwrap();
+    }
+
+    let mut progress = Vec::new();
+    let changes = get_commits_between_with_progress(
+        temp_dir.path(),
+        "HEAD~3",
+        "HEAD",
+        &PromptFilter::default(),
+        |done, total| {
+            progress.push((done, total));
+        },
+    )
+    .unwrap();
+    assert_eq!(changes.len(), 3);
+    assert_eq!(progress, vec![(1, 3), (2, 3), (3, 3)]);
+}
+
+#[test]
+fn test_cargo_manifest_is_compared_in_full() {
+    let temp_dir = setup_git_repo();
+    let repo = Repository::open(temp_dir.path()).unwrap();
+    let manifest = temp_dir.path().join("Cargo.toml");
+    fs::write(
+        &manifest,
+        "[package]\nname = \"demo\"\nedition = \"2018\"\n\n[dependencies]\nserde = \"1.0\"\ntokio = { version = \"1.37\", features = [\"rt\"] }\n",
+    )
+    .unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("Cargo.toml")).unwrap();
+    index.write().unwrap();
+    commit(temp_dir.path(), "Add manifest").unwrap();
+
+    fs::write(
+        &manifest,
+        "[package]\nname = \"demo\"\nedition = \"2021\"\n\n[dependencies]\nserde = \"1.0\"\ntokio = { version = \"1.38\", features = [\"rt\", \"macros\"] }\n",
+    )
+    .unwrap();
+    let expected = vec![
+        "Edition changed: 2018 → 2021",
+        "Upgraded dependencies: tokio 1.37 → 1.38",
+        "Dependency features changed: tokio (+macros)",
+    ];
+
+    // Unstaged changes are read from the working tree
+    let context =
+        get_git_info_with_scope(temp_dir.path(), &Config::default(), ChangeScope::All).unwrap();
+    let file = context
+        .staged_files
+        .iter()
+        .find(|file| file.path == "Cargo.toml")
+        .unwrap();
+    assert_eq!(file.analysis, expected);
+
+    index.add_path(Path::new("Cargo.toml")).unwrap();
+    index.write().unwrap();
+    let context = get_git_info(temp_dir.path(), &Config::default()).unwrap();
+    let file = context
+        .staged_files
+        .iter()
+        .find(|file| file.path == "Cargo.toml")
+        .unwrap();
+    assert_eq!(file.analysis, expected);
+}
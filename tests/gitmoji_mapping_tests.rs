@@ -0,0 +1,46 @@
+use git_iris::commit_message::split_leading_emoji;
+use git_iris::gitmoji::{
+    apply_gitmoji, built_in_gitmojis, get_gitmoji, get_gitmoji_list, parse_mapping,
+    render_gitmoji, search_gitmojis, set_gitmojis, GitmojiStyle,
+};
+
+// The active gitmojis are shared by the whole process, so everything that changes them runs in
+// one test
+#[test]
+fn test_custom_gitmojis_and_shortcode_style() {
+    let gitmojis = parse_mapping(
+        r#"
+[types.feat]
+emoji = "🚀"
+
+[types.deps]
+emoji = ":package:"
+description = "Update dependencies"
+"#,
+    )
+    .unwrap();
+    set_gitmojis(gitmojis.clone(), GitmojiStyle::Unicode);
+    assert_eq!(get_gitmoji("feat").as_deref(), Some("🚀"));
+    assert_eq!(apply_gitmoji("deps: bump serde"), "📦️ deps: bump serde");
+    assert!(get_gitmoji_list().contains("🚀 - :feat: - Introduce new features"));
+    assert_eq!(search_gitmojis("deps")[0].name, "deps");
+    // Shortcodes from the model are written as emoji
+    assert_eq!(render_gitmoji(":rocket: Add retries"), "🚀 Add retries");
+    assert_eq!(render_gitmoji("✨ Add retries"), "✨ Add retries");
+
+    set_gitmojis(gitmojis, GitmojiStyle::Shortcode);
+    assert_eq!(get_gitmoji("feat").as_deref(), Some(":rocket:"));
+    assert!(get_gitmoji_list().contains(":package: - :deps: - Update dependencies"));
+    assert_eq!(render_gitmoji("🚀 Add retries\n\nBody"), ":rocket: Add retries\n\nBody");
+    // A missing variation selector still matches
+    assert_eq!(render_gitmoji("📦 Bump serde"), ":package: Bump serde");
+    assert_eq!(render_gitmoji("Add retries"), "Add retries");
+    assert_eq!(
+        split_leading_emoji(":rocket: Add retries"),
+        (Some(":rocket:"), "Add retries")
+    );
+    assert_eq!(split_leading_emoji(":unknown: Add retries"), (None, ":unknown: Add retries"));
+
+    set_gitmojis(built_in_gitmojis(), GitmojiStyle::Unicode);
+    assert_eq!(get_gitmoji("feat").as_deref(), Some("✨"));
+}
@@ -1,4 +1,8 @@
-use git_iris::gitmoji::{apply_gitmoji, get_gitmoji, get_gitmoji_list};
+use git_iris::gitmoji::{
+    all_gitmojis, apply_gitmoji, built_in_gitmojis, get_gitmoji, get_gitmoji_list, parse_mapping,
+    search_gitmojis, GitmojiStyle,
+};
+use std::str::FromStr;
 
 #[cfg(test)]
 mod tests {
@@ -51,13 +55,94 @@ mod tests {
 
     #[test]
     fn test_get_gitmoji() {
-        assert_eq!(get_gitmoji("feat"), Some("✨"));
-        assert_eq!(get_gitmoji("fix"), Some("🐛"));
-        assert_eq!(get_gitmoji("docs"), Some("📝"));
-        assert_eq!(get_gitmoji("style"), Some("💄"));
-        assert_eq!(get_gitmoji("refactor"), Some("♻️"));
-        assert_eq!(get_gitmoji("test"), Some("✅"));
-        assert_eq!(get_gitmoji("chore"), Some("🔨"));
+        assert_eq!(get_gitmoji("feat").as_deref(), Some("✨"));
+        assert_eq!(get_gitmoji("fix").as_deref(), Some("🐛"));
+        assert_eq!(get_gitmoji("docs").as_deref(), Some("📝"));
+        assert_eq!(get_gitmoji("style").as_deref(), Some("💄"));
+        assert_eq!(get_gitmoji("refactor").as_deref(), Some("♻️"));
+        assert_eq!(get_gitmoji("test").as_deref(), Some("✅"));
+        assert_eq!(get_gitmoji("chore").as_deref(), Some("🔨"));
         assert_eq!(get_gitmoji("unknown"), None);
     }
+
+    #[test]
+    fn test_search_gitmojis() {
+        let gitmojis = all_gitmojis();
+        let names: Vec<&str> = gitmojis.iter().map(|gitmoji| gitmoji.name.as_str()).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+        assert_eq!(search_gitmojis("").len(), names.len());
+
+        // Name prefixes rank above description matches
+        let fixes: Vec<String> =
+            search_gitmojis("fix").into_iter().map(|gitmoji| gitmoji.name).collect();
+        assert_eq!(fixes[0], "fix");
+        assert!(fixes.contains(&"hotfix".to_string()));
+        assert!(fixes.contains(&"security".to_string()));
+
+        assert_eq!(search_gitmojis("rfctr")[0].name, "refactor");
+        assert_eq!(search_gitmojis("Performance")[0].emoji, "⚡️");
+        assert!(search_gitmojis("zzz").is_empty());
+    }
+
+    #[test]
+    fn test_parse_mapping_adds_to_built_in_gitmojis() {
+        let gitmojis = parse_mapping(
+            r#"
+[types.feat]
+emoji = ":rocket:"
+description = "Ship a feature"
+
+[types.deps]
+emoji = "📦️"
+description = "Update dependencies"
+
+[types.fix]
+emoji = "🩹"
+"#,
+        )
+        .unwrap();
+        assert_eq!(gitmojis.len(), built_in_gitmojis().len() + 1);
+
+        let find = |name: &str| gitmojis.iter().find(|gitmoji| gitmoji.name == name).unwrap();
+        assert_eq!(find("feat").emoji, "🚀");
+        assert_eq!(find("feat").code.as_deref(), Some("rocket"));
+        assert_eq!(find("feat").description, "Ship a feature");
+        assert_eq!(find("deps").code.as_deref(), Some("package"));
+        // Built-in types keep their description when none is given
+        assert_eq!(find("fix").description, "Fix a bug");
+        assert_eq!(find("fix").code.as_deref(), Some("adhesive_bandage"));
+        assert_eq!(find("docs").emoji, "📝");
+    }
+
+    #[test]
+    fn test_parse_mapping_replace() {
+        let gitmojis = parse_mapping(
+            r#"
+replace = true
+
+[types.feature]
+emoji = ":party_parrot:"
+description = "Add a feature"
+"#,
+        )
+        .unwrap();
+        assert_eq!(gitmojis.len(), 1);
+        // Unknown shortcodes are kept as they are
+        assert_eq!(gitmojis[0].emoji, ":party_parrot:");
+        assert_eq!(gitmojis[0].code.as_deref(), Some("party_parrot"));
+
+        assert!(parse_mapping("replace = true").is_err());
+        assert!(parse_mapping("[types.custom]\nemoji = \"🦀\"").is_err());
+        assert!(parse_mapping("[types.feat]\nemoji = \"\"").is_err());
+        assert!(parse_mapping("[types.feat").is_err());
+    }
+
+    #[test]
+    fn test_gitmoji_style_from_str() {
+        assert_eq!(GitmojiStyle::from_str("unicode").unwrap(), GitmojiStyle::Unicode);
+        assert_eq!(GitmojiStyle::from_str("Shortcode").unwrap(), GitmojiStyle::Shortcode);
+        assert!(GitmojiStyle::from_str("ascii").is_err());
+    }
 }
@@ -0,0 +1,98 @@
+use git_iris::config::Config;
+use git_iris::config_validation::validate_config;
+use git_iris::glossary::{glossary_instructions, parse_entry};
+use std::collections::HashMap;
+
+fn glossary(entries: &[(&str, &str)]) -> HashMap<String, String> {
+    entries
+        .iter()
+        .map(|(term, meaning)| (term.to_string(), meaning.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_glossary_instructions_list_terms_in_order() {
+    let instructions = glossary_instructions(&glossary(&[
+        ("the scheduler", "src/sched/"),
+        ("ACL", "access control list"),
+    ]));
+
+    assert!(instructions.starts_with("Use the project's established terminology"));
+    let acl = instructions.find("- \"ACL\": access control list").unwrap();
+    let scheduler = instructions
+        .find("- \"the scheduler\": the code in src/sched/; call changes there by this name")
+        .unwrap();
+    assert!(acl < scheduler);
+}
+
+#[test]
+fn test_empty_glossary_adds_nothing() {
+    assert_eq!(glossary_instructions(&HashMap::new()), "");
+    assert_eq!(glossary_instructions(&glossary(&[("ACL", " ")])), "");
+
+    let config = Config::default();
+    assert!(!config.get_effective_instructions().contains("terminology"));
+}
+
+#[test]
+fn test_glossary_reaches_every_prompt_through_instructions() {
+    let mut config = Config {
+        instructions: "Mention the ticket".to_string(),
+        glossary: glossary(&[("ACL", "access control list")]),
+        ..Default::default()
+    };
+
+    let instructions = config.get_effective_instructions();
+    assert!(instructions.contains("Mention the ticket\n\nUse the project's established"));
+    assert!(instructions.contains("\"ACL\": access control list"));
+
+    // Temporary instructions replace the custom ones but keep the glossary
+    config.set_temp_instructions(Some("Be brief".to_string()));
+    let instructions = config.get_effective_instructions();
+    assert!(!instructions.contains("Mention the ticket"));
+    assert!(instructions.contains("\"ACL\": access control list"));
+}
+
+#[test]
+fn test_glossary_is_read_from_config_file() {
+    let config: Config = toml::from_str(
+        r#"
+        default_provider = "openai"
+        providers = {}
+
+        [glossary]
+        ACL = "access control list"
+        "the scheduler" = "src/sched/"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.glossary["ACL"], "access control list");
+    assert_eq!(config.glossary["the scheduler"], "src/sched/");
+}
+
+#[test]
+fn test_parse_entry() {
+    assert_eq!(
+        parse_entry("the scheduler = src/sched/").unwrap(),
+        ("the scheduler".to_string(), "src/sched/".to_string())
+    );
+    assert_eq!(
+        parse_entry("ACL=").unwrap(),
+        ("ACL".to_string(), String::new())
+    );
+    assert!(parse_entry("ACL").is_err());
+    assert!(parse_entry("=access control list").is_err());
+}
+
+#[test]
+fn test_validation_flags_incomplete_entries() {
+    let config = Config {
+        glossary: glossary(&[("ACL", "")]),
+        ..Default::default()
+    };
+
+    assert!(validate_config(&config)
+        .iter()
+        .any(|problem| problem.key == "glossary"));
+}
@@ -0,0 +1,52 @@
+use git_iris::highlight::{tokenize, Language, TokenKind};
+
+fn tokens_of(kind: TokenKind, code: &str, language: Language) -> Vec<String> {
+    tokenize(code, language)
+        .into_iter()
+        .filter(|(token_kind, _)| *token_kind == kind)
+        .map(|(_, text)| text)
+        .collect()
+}
+
+#[test]
+fn test_language_from_path() {
+    assert_eq!(Language::from_path("src/main.rs"), Language::Rust);
+    assert_eq!(Language::from_path("web/app.tsx"), Language::JavaScript);
+    assert_eq!(Language::from_path("lib/Util.java"), Language::CLike);
+    assert_eq!(Language::from_path("Cargo.toml"), Language::Config);
+    assert_eq!(Language::from_path("Makefile"), Language::Shell);
+    assert_eq!(Language::from_path("README"), Language::Plain);
+}
+
+#[test]
+fn test_tokenize() {
+    let code = r#"let name = "a \"quoted\" // word"; // 42 comment"#;
+    let tokens = tokenize(code, Language::Rust);
+    // Tokens cover the whole line
+    let joined: String = tokens.iter().map(|(_, text)| text.as_str()).collect();
+    assert_eq!(joined, code);
+
+    assert_eq!(tokens_of(TokenKind::Keyword, code, Language::Rust), vec!["let"]);
+    assert_eq!(
+        tokens_of(TokenKind::String, code, Language::Rust),
+        vec![r#""a \"quoted\" // word""#]
+    );
+    assert_eq!(
+        tokens_of(TokenKind::Comment, code, Language::Rust),
+        vec!["// 42 comment"]
+    );
+
+    let code = "def area(r): return 3.14 * r * r  # circle";
+    assert_eq!(
+        tokens_of(TokenKind::Keyword, code, Language::Python),
+        vec!["def", "return"]
+    );
+    assert_eq!(tokens_of(TokenKind::Number, code, Language::Python), vec!["3", "14"]);
+    assert_eq!(tokens_of(TokenKind::Comment, code, Language::Python), vec!["# circle"]);
+
+    // Keywords only count as whole words
+    assert!(tokens_of(TokenKind::Keyword, "letter", Language::Rust).is_empty());
+    assert!(tokenize("fn main", Language::Plain)
+        .iter()
+        .all(|(kind, _)| *kind == TokenKind::Plain));
+}
@@ -0,0 +1,68 @@
+use chrono::{Local, TimeZone};
+use git2::Repository;
+use git_iris::context::{ChangeType, StageStatus, StagedFile};
+use git_iris::history::{changes_hash, GenerationHistory, MAX_HISTORY_ENTRIES};
+use tempfile::TempDir;
+
+fn staged_file(path: &str, diff: &str) -> StagedFile {
+    StagedFile {
+        path: path.to_string(),
+        change_type: ChangeType::Modified,
+        diff: diff.to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    }
+}
+
+#[test]
+fn test_history_numbers_newest_first() {
+    let time = Local.with_ymd_and_hms(2024, 5, 1, 9, 30, 0).unwrap();
+    let mut history = GenerationHistory::default();
+    history.push("Add parser\n", "aaa", time);
+    history.push("Add parser", "aaa", time);
+    history.push("Add the parser module", "aaa", time);
+    history.push("Add parser", "aaa", time);
+
+    // Only an exact repeat of the newest message is skipped
+    assert_eq!(history.entries.len(), 3);
+    assert_eq!(history.get(1).unwrap().message, "Add parser");
+    assert_eq!(history.get(2).unwrap().message, "Add the parser module");
+    assert_eq!(history.get(3).unwrap().local_time(), "2024-05-01 09:30");
+    assert!(history.get(0).is_err());
+    assert!(history.get(4).unwrap_err().to_string().contains("3 saved"));
+
+    let numbers: Vec<usize> = history.newest_first().map(|(number, _)| number).collect();
+    assert_eq!(numbers, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_history_keeps_newest_entries() {
+    let temp_dir = TempDir::new().unwrap();
+    Repository::init(temp_dir.path()).unwrap();
+
+    let mut history = GenerationHistory::default();
+    for n in 0..MAX_HISTORY_ENTRIES + 5 {
+        history.push(&format!("Message {}", n), "hash", Local::now());
+    }
+    history.save(temp_dir.path()).unwrap();
+
+    let loaded = GenerationHistory::load(temp_dir.path());
+    assert_eq!(loaded.entries.len(), MAX_HISTORY_ENTRIES);
+    assert_eq!(loaded.get(1).unwrap().message, format!("Message {}", MAX_HISTORY_ENTRIES + 4));
+    assert_eq!(loaded.get(MAX_HISTORY_ENTRIES).unwrap().message, "Message 5");
+}
+
+#[test]
+fn test_changes_hash() {
+    let files = vec![staged_file("src/lib.rs", "+fn a() {}")];
+    assert_eq!(changes_hash(&files), changes_hash(&files.clone()));
+    assert_ne!(
+        changes_hash(&files),
+        changes_hash(&[staged_file("src/lib.rs", "+fn b() {}")])
+    );
+    assert_ne!(
+        changes_hash(&files),
+        changes_hash(&[staged_file("src/main.rs", "+fn a() {}")])
+    );
+}
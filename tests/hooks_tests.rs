@@ -0,0 +1,79 @@
+use git2::Repository;
+use git_iris::hooks::{
+    hook_action, hook_script, install_hook, install_lint_hook, is_hook_installed,
+    lint_hook_script, uninstall_hook, uninstall_lint_hook, HookAction,
+};
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_hook_action() {
+    assert_eq!(hook_action(None), HookAction::CommitMessage);
+    assert_eq!(hook_action(Some("")), HookAction::CommitMessage);
+    assert_eq!(hook_action(Some("merge")), HookAction::MergeMessage);
+    assert_eq!(hook_action(Some("message")), HookAction::Skip);
+    assert_eq!(hook_action(Some("template")), HookAction::Skip);
+    assert_eq!(hook_action(Some("squash")), HookAction::Skip);
+    assert_eq!(hook_action(Some("commit")), HookAction::Skip);
+}
+
+#[test]
+fn test_install_and_uninstall_hook() {
+    let temp_dir = TempDir::new().unwrap();
+    Repository::init(temp_dir.path()).unwrap();
+
+    let hook_path = install_hook(temp_dir.path(), false).unwrap();
+    assert!(hook_path.ends_with("hooks/prepare-commit-msg"));
+    assert_eq!(fs::read_to_string(&hook_path).unwrap(), hook_script());
+    assert!(is_hook_installed(temp_dir.path()));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&hook_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o111, 0o111);
+    }
+
+    // Reinstalling over our own hook is fine
+    install_hook(temp_dir.path(), false).unwrap();
+
+    assert!(uninstall_hook(temp_dir.path()).unwrap());
+    assert!(!hook_path.exists());
+    assert!(!uninstall_hook(temp_dir.path()).unwrap());
+}
+
+#[test]
+fn test_install_and_uninstall_lint_hook() {
+    let temp_dir = TempDir::new().unwrap();
+    Repository::init(temp_dir.path()).unwrap();
+
+    let hook_path = install_lint_hook(temp_dir.path(), false).unwrap();
+    assert!(hook_path.ends_with("hooks/commit-msg"));
+    assert_eq!(fs::read_to_string(&hook_path).unwrap(), lint_hook_script());
+    assert!(lint_hook_script().contains("git-iris lint --message-file \"$1\""));
+
+    assert!(uninstall_lint_hook(temp_dir.path()).unwrap());
+    assert!(!hook_path.exists());
+}
+
+#[test]
+fn test_existing_hook_is_preserved() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let hook_path = repo.path().join("hooks").join("prepare-commit-msg");
+    fs::create_dir_all(hook_path.parent().unwrap()).unwrap();
+    fs::write(&hook_path, "#!/bin/sh\necho custom\n").unwrap();
+
+    assert!(install_hook(temp_dir.path(), false).is_err());
+    assert!(uninstall_hook(temp_dir.path()).is_err());
+    assert!(!is_hook_installed(temp_dir.path()));
+
+    // Forcing keeps a backup that is restored on uninstall
+    install_hook(temp_dir.path(), true).unwrap();
+    assert!(is_hook_installed(temp_dir.path()));
+    uninstall_hook(temp_dir.path()).unwrap();
+    assert_eq!(
+        fs::read_to_string(&hook_path).unwrap(),
+        "#!/bin/sh\necho custom\n"
+    );
+}
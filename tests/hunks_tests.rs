@@ -0,0 +1,88 @@
+use git2::Repository;
+use git_iris::hunks::{list_hunks, toggle_hunk, HunkSide};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn setup_repo() -> (TempDir, Repository) {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+    (temp_dir, repo)
+}
+
+fn commit_file(repo: &Repository, path: &str, content: &str) {
+    fs::write(repo.workdir().unwrap().join(path), content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(path)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = repo.signature().unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[])
+        .unwrap();
+}
+
+fn numbered_lines(changed: &[usize]) -> String {
+    (1..=20)
+        .map(|n| {
+            if changed.contains(&n) {
+                format!("line {} changed\n", n)
+            } else {
+                format!("line {}\n", n)
+            }
+        })
+        .collect()
+}
+
+fn staged_content(repo: &Repository, path: &str) -> String {
+    let mut index = repo.index().unwrap();
+    index.read(true).unwrap();
+    let entry = index.get_path(Path::new(path), 0).unwrap();
+    let blob = repo.find_blob(entry.id).unwrap();
+    String::from_utf8(blob.content().to_vec()).unwrap()
+}
+
+#[test]
+fn test_stage_and_unstage_single_hunk() {
+    let (temp_dir, repo) = setup_repo();
+    commit_file(&repo, "notes.txt", &numbered_lines(&[]));
+    fs::write(temp_dir.path().join("notes.txt"), numbered_lines(&[2, 18])).unwrap();
+
+    let hunks = list_hunks(temp_dir.path()).unwrap();
+    assert_eq!(hunks.len(), 2);
+    assert!(hunks.iter().all(|hunk| hunk.side == HunkSide::Unstaged));
+    assert!(hunks[1].lines.contains(&"+line 18 changed".to_string()));
+
+    toggle_hunk(temp_dir.path(), &hunks[1]).unwrap();
+    assert_eq!(staged_content(&repo, "notes.txt"), numbered_lines(&[18]));
+    // The working tree keeps both changes
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("notes.txt")).unwrap(),
+        numbered_lines(&[2, 18])
+    );
+
+    let hunks = list_hunks(temp_dir.path()).unwrap();
+    assert_eq!(hunks.len(), 2);
+    assert_eq!(hunks[0].side, HunkSide::Staged);
+    assert!(hunks[0].lines.contains(&"+line 18 changed".to_string()));
+    assert_eq!(hunks[1].side, HunkSide::Unstaged);
+
+    toggle_hunk(temp_dir.path(), &hunks[0]).unwrap();
+    assert_eq!(staged_content(&repo, "notes.txt"), numbered_lines(&[]));
+    let hunks = list_hunks(temp_dir.path()).unwrap();
+    assert!(hunks.iter().all(|hunk| hunk.side == HunkSide::Unstaged));
+}
+
+#[test]
+fn test_toggle_stale_hunk_fails() {
+    let (temp_dir, repo) = setup_repo();
+    commit_file(&repo, "notes.txt", &numbered_lines(&[]));
+    fs::write(temp_dir.path().join("notes.txt"), numbered_lines(&[2])).unwrap();
+
+    let mut hunk = list_hunks(temp_dir.path()).unwrap().remove(0);
+    hunk.index = 3;
+    assert!(toggle_hunk(temp_dir.path(), &hunk).is_err());
+    assert_eq!(staged_content(&repo, "notes.txt"), numbered_lines(&[]));
+}
@@ -0,0 +1,18 @@
+use git_iris::interactive::InteractiveCommit;
+
+#[test]
+fn test_candidates_not_selected_are_rejected() {
+    let mut interactive = InteractiveCommit::new(
+        "Add retries".to_string(),
+        String::new(),
+        "git-iris".to_string(),
+        "1.0.0".to_string(),
+    );
+    assert!(interactive.rejected_candidates().is_empty());
+
+    interactive.add_candidate("Retry failed requests".to_string());
+    interactive.add_candidate("Add retries\n".to_string());
+
+    assert_eq!(interactive.selected_message(), "Add retries");
+    assert_eq!(interactive.rejected_candidates(), vec!["Retry failed requests"]);
+}
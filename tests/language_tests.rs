@@ -0,0 +1,72 @@
+use git_iris::changelog_prompts::create_changelog_system_prompt;
+use git_iris::config::Config;
+use git_iris::config_validation::validate_config;
+use git_iris::language::Language;
+use git_iris::prompt::create_system_prompt;
+use std::str::FromStr;
+
+#[test]
+fn test_language_from_code_name_and_region() {
+    assert_eq!(Language::from_str("de").unwrap().name, "German");
+    assert_eq!(Language::from_str("French").unwrap().code, "fr");
+    assert_eq!(Language::from_str("pt-BR").unwrap().code, "pt");
+    assert_eq!(Language::from_str(" ES ").unwrap().code, "es");
+    assert!(Language::from_str("klingon").is_err());
+    assert!(Language::default().is_english());
+}
+
+#[test]
+fn test_english_adds_no_instructions() {
+    assert_eq!(Language::default().instructions(), "");
+    assert!(!Config::default()
+        .get_effective_instructions()
+        .contains("Write all generated text"));
+}
+
+#[test]
+fn test_configured_language_reaches_every_prompt() {
+    let config = Config {
+        language: "de".to_string(),
+        ..Default::default()
+    };
+
+    let instructions = config.get_effective_instructions();
+    assert!(instructions.contains("Write all generated text in German."));
+    assert!(instructions.contains("Füge Export hinzu"));
+}
+
+#[test]
+fn test_commit_prompt_adapts_the_mood_rule() {
+    let english = create_system_prompt(false, "", Language::default());
+    assert!(english.contains("1. Use the imperative mood (e.g., 'Add feature'"));
+
+    let french = create_system_prompt(false, "", Language::from_str("fr").unwrap());
+    assert!(french.contains("1. Use the infinitive (e.g., 'Ajouter l'export'"));
+    assert!(!french.contains("'Add feature'"));
+}
+
+#[test]
+fn test_changelog_prompt_adapts_the_mood_rule() {
+    let config = Config {
+        language: "es".to_string(),
+        ..Default::default()
+    };
+
+    let prompt = create_changelog_system_prompt(&config);
+    assert!(prompt.contains("2. Use the present tense and the infinitive"));
+}
+
+#[test]
+fn test_unsupported_language_falls_back_to_english() {
+    let config = Config {
+        language: "frnch".to_string(),
+        ..Default::default()
+    };
+
+    assert!(config.language().is_english());
+    let problem = validate_config(&config)
+        .into_iter()
+        .find(|problem| problem.key == "language")
+        .unwrap();
+    assert!(problem.suggestion.contains("git-iris config --language"));
+}
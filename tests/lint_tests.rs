@@ -0,0 +1,70 @@
+use git_iris::config::LintConfig;
+use git_iris::lint::{lint_message, strip_comments, LintRule};
+
+fn rules(message: &str, config: &LintConfig) -> Vec<LintRule> {
+    lint_message(message, config)
+        .into_iter()
+        .map(|violation| violation.rule)
+        .collect()
+}
+
+#[test]
+fn test_default_rules() {
+    let config = LintConfig::default();
+
+    assert!(rules("Add retry to the HTTP client", &config).is_empty());
+    assert!(rules("✨ Add retry to the HTTP client\n\nRetry once on timeouts.", &config).is_empty());
+    assert_eq!(rules("", &config), vec![LintRule::Empty]);
+    assert_eq!(rules(&"a".repeat(73), &config), vec![LintRule::SubjectLength]);
+    assert_eq!(rules("Added retries", &config), vec![LintRule::ImperativeMood]);
+    assert_eq!(rules("Fixes the build", &config), vec![LintRule::ImperativeMood]);
+    assert_eq!(rules("fix: adding retries", &config), vec![LintRule::ImperativeMood]);
+    assert!(rules("Embed the font", &config).is_empty());
+    assert_eq!(
+        rules("Add retries\nRetry once on timeouts.", &config),
+        vec![LintRule::BodySeparator]
+    );
+
+    let long_line = "word ".repeat(16);
+    let violations = lint_message(&format!("Add retries\n\n{}", long_line.trim()), &config);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].rule, LintRule::BodyWrap);
+    assert_eq!(violations[0].line, 3);
+
+    // URLs and Git's own subjects are left alone
+    let url = format!("Add retries\n\nSee https://example.com/{}", "a".repeat(80));
+    assert!(rules(&url, &config).is_empty());
+    assert!(rules("fixup! Added retries", &config).is_empty());
+    assert!(rules("Merge branch 'main' into feature", &config).is_empty());
+}
+
+#[test]
+fn test_conventional_rule() {
+    let config = LintConfig {
+        conventional: true,
+        ..LintConfig::default()
+    };
+
+    assert!(rules("feat(http): add retries", &config).is_empty());
+    assert!(rules("fix!: drop the legacy endpoint", &config).is_empty());
+    assert!(rules("🐛 fix: handle empty responses", &config).is_empty());
+    assert_eq!(rules("Add retries", &config), vec![LintRule::Conventional]);
+    assert_eq!(rules("feature: add retries", &config), vec![LintRule::Conventional]);
+    assert_eq!(rules("feat:add retries", &config), vec![LintRule::Conventional]);
+
+    let relaxed = LintConfig {
+        max_subject_length: 0,
+        imperative_mood: false,
+        max_body_line_length: 0,
+        ..LintConfig::default()
+    };
+    assert!(rules(&format!("Added {}", "a".repeat(100)), &relaxed).is_empty());
+}
+
+#[test]
+fn test_strip_comments() {
+    let content = "Add retries\n# Please enter the commit message\n\nBody\n\
+                   # ------------------------ >8 ------------------------\ndiff --git a b\n";
+    assert_eq!(strip_comments(content, '#'), "Add retries\n\nBody");
+    assert_eq!(strip_comments("Subject\n; comment", ';'), "Subject");
+}
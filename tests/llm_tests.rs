@@ -2,7 +2,7 @@ use anyhow::Result;
 use git_iris::config::Config;
 use git_iris::llm::{
     get_available_provider_names, get_default_model_for_provider,
-    get_default_token_limit_for_provider, get_refined_message,
+    get_default_token_limit_for_provider, get_refined_message, ping_provider,
 };
 use git_iris::llm_providers::LLMProviderType;
 use std::str::FromStr;
@@ -48,6 +48,21 @@ async fn test_get_refined_message_with_custom_instructions() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_ping_provider() -> Result<()> {
+    let config = Config::default();
+
+    // The test provider is always reachable
+    assert!(ping_provider(&config, &LLMProviderType::Test).await.is_ok());
+
+    // Providers that require a key fail before any request is made
+    let err = ping_provider(&config, &LLMProviderType::OpenAI)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("API key required"));
+    Ok(())
+}
+
 #[test]
 fn test_get_available_providers() {
     let providers = get_available_provider_names();
@@ -0,0 +1,108 @@
+use git_iris::mcp::{tool_definitions, McpServer, PROTOCOL_VERSION};
+use serde_json::{json, Value};
+use tempfile::TempDir;
+
+async fn request(server: &McpServer, message: Value) -> Value {
+    server
+        .handle_message(&message.to_string())
+        .await
+        .expect("expected a response")
+}
+
+#[tokio::test]
+async fn test_initialize_and_list_tools() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = McpServer::new(temp_dir.path().to_path_buf());
+
+    let response = request(
+        &server,
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+    )
+    .await;
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["result"]["protocolVersion"], PROTOCOL_VERSION);
+    assert_eq!(response["result"]["serverInfo"]["name"], "git-iris");
+
+    // Notifications get no response
+    assert!(server
+        .handle_message(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }).to_string())
+        .await
+        .is_none());
+
+    let response = request(
+        &server,
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+    )
+    .await;
+    let names: Vec<&str> = response["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "generate_commit_message",
+            "generate_changelog",
+            "generate_release_notes",
+            "review_changes"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_protocol_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = McpServer::new(temp_dir.path().to_path_buf());
+
+    let response = server.handle_message("{not json").await.unwrap();
+    assert_eq!(response["error"]["code"], -32700);
+
+    let response = request(
+        &server,
+        json!({ "jsonrpc": "2.0", "id": "a", "method": "resources/list" }),
+    )
+    .await;
+    assert_eq!(response["id"], "a");
+    assert_eq!(response["error"]["code"], -32601);
+
+    let response = request(
+        &server,
+        json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": { "name": "deploy" } }),
+    )
+    .await;
+    assert_eq!(response["error"]["code"], -32602);
+}
+
+#[tokio::test]
+async fn test_tool_failures_are_results() {
+    let temp_dir = TempDir::new().unwrap();
+    let server = McpServer::new(temp_dir.path().to_path_buf());
+
+    let response = request(
+        &server,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "tools/call",
+            "params": { "name": "generate_changelog", "arguments": {} }
+        }),
+    )
+    .await;
+    assert_eq!(response["result"]["isError"], true);
+    assert!(response["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("from"));
+}
+
+#[test]
+fn test_tool_schemas() {
+    for tool in tool_definitions() {
+        let schema = &tool["inputSchema"];
+        assert_eq!(schema["type"], "object");
+        assert!(schema["properties"]["repository"].is_object());
+        assert!(tool["description"].as_str().is_some_and(|d| !d.is_empty()));
+    }
+}
@@ -0,0 +1,147 @@
+use git2::Repository;
+use git_iris::exclusions::PromptFilter;
+use git_iris::merge::{
+    conflicts_from_message, format_message_file, merge_source_from_message, write_message_file,
+    MergeContext,
+};
+use git_iris::merge_prompts::create_merge_user_prompt;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn commit_file(repo: &Repository, dir: &Path, name: &str, message: &str) {
+    fs::write(dir.join(name), message).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(name)).unwrap();
+    index.write().unwrap();
+    let tree_id = index.write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => vec![],
+    };
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parent_refs,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_merge_context_for_branch() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+
+    commit_file(&repo, temp_dir.path(), "base.txt", "Initial commit");
+    let base = repo.head().unwrap().peel_to_commit().unwrap();
+    let target = repo.head().unwrap().shorthand().unwrap().to_string();
+
+    // Add two commits on a feature branch, then return to the original branch
+    repo.branch("feature", &base, false).unwrap();
+    repo.set_head("refs/heads/feature").unwrap();
+    commit_file(&repo, temp_dir.path(), "one.txt", "Add one");
+    commit_file(&repo, temp_dir.path(), "two.txt", "Add two");
+    repo.set_head(&format!("refs/heads/{}", target)).unwrap();
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .unwrap();
+
+    let context =
+        MergeContext::load(temp_dir.path(), Some("feature"), &PromptFilter::default()).unwrap();
+    assert_eq!(context.source, "feature");
+    assert_eq!(context.target, target);
+    assert_eq!(context.merge_base, base.id().to_string());
+    assert_eq!(context.incoming.len(), 2);
+    assert!(context.conflicts.is_empty());
+
+    let prompt = create_merge_user_prompt(&context);
+    assert!(prompt.contains("Source: feature"));
+    assert!(prompt.contains("Incoming commits: 2"));
+    assert!(prompt.contains("Message: Add one"));
+    assert!(prompt.contains("Conflicts: none"));
+
+    // Without a branch, a merge must be in progress
+    assert!(MergeContext::load(temp_dir.path(), None, &PromptFilter::default()).is_err());
+}
+
+#[test]
+fn test_merge_source_from_message() {
+    assert_eq!(
+        merge_source_from_message("Merge branch 'feature/login' into main\n"),
+        Some("feature/login".to_string())
+    );
+    assert_eq!(
+        merge_source_from_message("Merge remote-tracking branch 'origin/dev'"),
+        Some("origin/dev".to_string())
+    );
+    assert_eq!(merge_source_from_message("Merge commit 'abc1234'"), None);
+}
+
+#[test]
+fn test_conflicts_from_message() {
+    let message = "Merge branch 'feature'\n\n# Conflicts:\n#\tsrc/lib.rs\n#\tREADME.md\n#\n# It looks like you may be committing a merge.\n";
+    assert_eq!(
+        conflicts_from_message(message),
+        vec!["src/lib.rs".to_string(), "README.md".to_string()]
+    );
+    assert!(conflicts_from_message("Merge branch 'feature'\n").is_empty());
+}
+
+#[test]
+fn test_write_message_file_keeps_comments() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("COMMIT_EDITMSG");
+    fs::write(
+        &path,
+        "Merge branch 'feature'\n\n# Conflicts:\n#\tsrc/lib.rs\n",
+    )
+    .unwrap();
+
+    write_message_file(
+        &path,
+        "Merge feature into main: add login\n\n- Add login form",
+        '#',
+    )
+    .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "Merge feature into main: add login\n\n- Add login form\n\n# Conflicts:\n#\tsrc/lib.rs\n"
+    );
+}
+
+#[test]
+fn test_format_message_file_normalizes_newlines() {
+    assert_eq!(
+        format_message_file("", "\n\nAdd login\n\n- Add form\n\n\n", '#'),
+        "Add login\n\n- Add form\n"
+    );
+}
+
+#[test]
+fn test_format_message_file_keeps_verbose_diff() {
+    let existing = "\n# Please enter the commit message for your changes.\n#\n\
+        # ------------------------ >8 ------------------------\n\
+        # Do not modify or remove the line above.\n\
+        diff --git a/src/lib.rs b/src/lib.rs\n\
+        +fn login() {}";
+
+    let content = format_message_file(existing, "Add login", '#');
+    assert!(content.starts_with("Add login\n\n# Please enter the commit message"));
+    assert!(content.ends_with("diff --git a/src/lib.rs b/src/lib.rs\n+fn login() {}\n"));
+}
+
+#[test]
+fn test_format_message_file_uses_comment_char() {
+    let existing = "old message\n# not a comment here\n; Please enter the commit message\n";
+
+    assert_eq!(
+        format_message_file(existing, "Add login", ';'),
+        "Add login\n\n; Please enter the commit message\n"
+    );
+}
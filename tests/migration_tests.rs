@@ -0,0 +1,129 @@
+use git2::{Oid, Repository};
+use git_iris::config::Config;
+use git_iris::context::{ChangeType, StageStatus, StagedFile};
+use git_iris::exclusions::PromptFilter;
+use git_iris::migration::{
+    detect_api_changes, is_config_file, ApiChangeKind, MigrationContext, MigrationGuideGenerator,
+};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn staged_file(path: &str, diff: &str) -> StagedFile {
+    StagedFile {
+        path: path.to_string(),
+        change_type: ChangeType::Modified,
+        diff: diff.to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    }
+}
+
+fn commit_file(repo: &Repository, path: &str, content: &str, message: &str) -> Oid {
+    let root = repo.workdir().unwrap();
+    std::fs::write(root.join(path), content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(path)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let parents: Vec<git2::Commit> = repo
+        .head()
+        .ok()
+        .and_then(|head| head.peel_to_commit().ok())
+        .into_iter()
+        .collect();
+    let parents: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .unwrap()
+}
+
+#[test]
+fn test_detect_api_changes() {
+    let files = vec![
+        staged_file(
+            "src/lib.rs",
+            "-pub fn connect(url: &str) -> Client {\n\
+             +pub fn connect(url: &str, timeout: u64) -> Client {\n\
+             -pub struct Pool;\n\
+             -pub(crate) fn helper() {}\n\
+             +pub fn open() {}\n\
+             -pub fn unchanged() {}\n\
+             +pub fn unchanged() {}\n",
+        ),
+        staged_file("src/net.rs", "+pub struct Pool;\n"),
+        staged_file("app.py", "-def load(path):\n-def _private():\n-    def nested():\n"),
+    ];
+
+    let changes = detect_api_changes(&files);
+    let find = |name: &str| changes.iter().find(|change| change.name == name);
+
+    let connect = find("connect").unwrap();
+    assert_eq!(connect.kind, ApiChangeKind::Changed);
+    assert_eq!(
+        connect.new_declaration.as_deref(),
+        Some("pub fn connect(url: &str, timeout: u64) -> Client {")
+    );
+
+    let pool = find("Pool").unwrap();
+    assert_eq!(pool.kind, ApiChangeKind::Moved);
+    assert_eq!(pool.path, "src/net.rs");
+    assert_eq!(pool.previous_path.as_deref(), Some("src/lib.rs"));
+
+    assert_eq!(find("open").unwrap().kind, ApiChangeKind::Added);
+    assert_eq!(find("load").unwrap().kind, ApiChangeKind::Removed);
+    assert!(find("helper").is_none());
+    assert!(find("unchanged").is_none());
+    assert!(find("_private").is_none());
+    assert!(find("nested").is_none());
+}
+
+#[test]
+fn test_is_config_file() {
+    assert!(is_config_file("config/settings.yaml"));
+    assert!(is_config_file("app.toml"));
+    assert!(is_config_file(".env.example"));
+    assert!(!is_config_file("Cargo.lock"));
+    assert!(!is_config_file("package-lock.json"));
+    assert!(!is_config_file("src/main.rs"));
+}
+
+#[tokio::test]
+async fn test_generate_migration_guide() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let first = commit_file(&repo, "lib.rs", "pub fn connect(url: &str) {}\n", "Initial release");
+    commit_file(&repo, "app.toml", "timeout = 30\n", "Add config file");
+    commit_file(
+        &repo,
+        "lib.rs",
+        "pub fn open(url: &str) {}\n",
+        "refactor!: rename connect to open",
+    );
+
+    let context = MigrationContext::load(
+        temp_dir.path(),
+        &first.to_string(),
+        "HEAD",
+        &PromptFilter::default(),
+    )
+    .unwrap();
+    assert_eq!(context.commit_subjects.len(), 2);
+    assert_eq!(context.breaking_commits, vec!["refactor!: rename connect to open"]);
+    assert_eq!(context.config_changes.len(), 1);
+    assert_eq!(context.config_changes[0].path, "app.toml");
+
+    let config = Config {
+        default_provider: "test".to_string(),
+        ..Default::default()
+    };
+
+    let guide = MigrationGuideGenerator::generate(&context, &config).await.unwrap();
+    assert!(guide.contains("- Removed fn connect (lib.rs)"));
+    assert!(guide.contains("- Added fn open (lib.rs)"));
+    assert!(guide.contains("File: app.toml (Added)"));
+
+    let empty =
+        MigrationContext::load(temp_dir.path(), "HEAD", "HEAD", &PromptFilter::default()).unwrap();
+    assert!(MigrationGuideGenerator::generate(&empty, &config).await.is_err());
+}
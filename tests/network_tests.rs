@@ -0,0 +1,119 @@
+use git_iris::config::{Config, ProviderConfig};
+use git_iris::config_validation::validate_config;
+use git_iris::llm_providers::{is_retryable_status, NetworkSettings};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Serve one response per connection from `statuses`, counting the requests received
+fn serve(statuses: &'static [u16]) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&requests);
+    thread::spawn(move || {
+        for (stream, status) in listener.incoming().zip(statuses) {
+            let mut stream = stream.unwrap();
+            let mut buffer = [0; 4096];
+            let _ = stream.read(&mut buffer);
+            counter.fetch_add(1, Ordering::SeqCst);
+            let response = format!(
+                "HTTP/1.1 {} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (url, requests)
+}
+
+#[test]
+fn test_network_settings_from_provider_config() {
+    let config: Config = toml::from_str(
+        r#"
+        default_provider = "openai"
+
+        [providers.openai]
+        api_key = ""
+        model = "gpt-4o"
+        timeout = 30
+        max_retries = 3
+        proxy = "http://proxy.example.com:8080"
+
+        [providers.openai.headers]
+        X-Gateway-Auth = "secret"
+        "#,
+    )
+    .unwrap();
+
+    let network = config.providers["openai"].to_llm_provider_config().network;
+    assert_eq!(network.timeout, Some(Duration::from_secs(30)));
+    assert_eq!(network.max_retries, 3);
+    assert_eq!(network.proxy.as_deref(), Some("http://proxy.example.com:8080"));
+    assert_eq!(network.header_map().unwrap()["x-gateway-auth"], "secret");
+    assert!(network.client().is_ok());
+
+    // Without settings, requests are sent once and wait indefinitely
+    let network = ProviderConfig::default().network_settings();
+    assert_eq!(network, NetworkSettings::default());
+}
+
+#[test]
+fn test_invalid_network_settings_are_reported() {
+    let mut config = Config::default();
+    let provider = config.default_provider.clone();
+    let provider_config = config.providers.get_mut(&provider).unwrap();
+    provider_config.headers = HashMap::from([("Bad Header".to_string(), "x".to_string())]);
+
+    let problems = validate_config(&config);
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].key, format!("providers.{}.headers", provider));
+
+    let provider_config = config.providers.get_mut(&provider).unwrap();
+    provider_config.headers.clear();
+    provider_config.proxy = Some("not a url".to_string());
+    let problems = validate_config(&config);
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].key, format!("providers.{}.proxy", provider));
+}
+
+#[test]
+fn test_retryable_statuses() {
+    assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+    assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+    assert!(!is_retryable_status(StatusCode::UNAUTHORIZED));
+    assert!(!is_retryable_status(StatusCode::OK));
+}
+
+#[tokio::test]
+async fn test_send_retries_temporary_failures() {
+    let (url, requests) = serve(&[503, 429, 200]);
+    let network = NetworkSettings {
+        max_retries: 2,
+        ..Default::default()
+    };
+    let client = network.client().unwrap();
+
+    let response = network.send(client.get(&url)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_send_stops_after_max_retries() {
+    let (url, requests) = serve(&[500, 500, 200]);
+    let network = NetworkSettings {
+        max_retries: 1,
+        ..Default::default()
+    };
+    let client = network.client().unwrap();
+
+    let response = network.send(client.get(&url)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,101 @@
+use git2::Repository;
+use git_iris::context::{ChangeType, CommitContext, ProjectMetadata, StageStatus, StagedFile};
+use git_iris::notes::{commits_in_range, format_context_note, read_note, write_note, NOTES_REF};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn commit_file(repo: &Repository, dir: &Path, name: &str, message: &str) -> String {
+    fs::write(dir.join(name), message).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(name)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => vec![],
+    };
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parent_refs)
+        .unwrap()
+        .to_string()
+}
+
+fn setup_repo() -> (TempDir, Repository, Vec<String>) {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+
+    let commits = ["first.txt", "second.txt", "third.txt"]
+        .iter()
+        .map(|name| commit_file(&repo, temp_dir.path(), name, &format!("Add {}", name)))
+        .collect();
+    (temp_dir, repo, commits)
+}
+
+#[test]
+fn test_commits_in_range() {
+    let (temp_dir, _repo, commits) = setup_repo();
+
+    assert_eq!(
+        commits_in_range(temp_dir.path(), "HEAD~2..HEAD").unwrap(),
+        commits[1..].to_vec()
+    );
+    assert_eq!(
+        commits_in_range(temp_dir.path(), "HEAD~1").unwrap(),
+        vec![commits[1].clone()]
+    );
+    assert!(commits_in_range(temp_dir.path(), "HEAD..HEAD").is_err());
+    assert!(commits_in_range(temp_dir.path(), "missing").is_err());
+}
+
+#[test]
+fn test_write_and_read_notes() {
+    let (temp_dir, repo, commits) = setup_repo();
+
+    assert_eq!(read_note(temp_dir.path(), &commits[0]).unwrap(), None);
+
+    write_note(temp_dir.path(), &commits[0], "Adds the first file").unwrap();
+    write_note(temp_dir.path(), &commits[0], "Adds the first file, explained again").unwrap();
+    assert_eq!(
+        read_note(temp_dir.path(), &commits[0]).unwrap().as_deref(),
+        Some("Adds the first file, explained again\n")
+    );
+
+    // Notes live under their own ref, leaving the default notes untouched
+    assert!(repo.find_reference(NOTES_REF).is_ok());
+    assert!(repo.find_reference("refs/notes/commits").is_err());
+}
+
+#[test]
+fn test_format_context_note() {
+    let context = CommitContext::new(
+        "main".to_string(),
+        vec![],
+        vec![StagedFile {
+            path: "src/lib.rs".to_string(),
+            change_type: ChangeType::Modified,
+            diff: "+fn new() {}".to_string(),
+            analysis: vec!["Added function: new".to_string()],
+            content_excluded: false,
+            stage_status: StageStatus::Staged,
+        }],
+        vec![],
+        ProjectMetadata {
+            language: Some("Rust".to_string()),
+            ..Default::default()
+        },
+        vec![],
+    );
+
+    let note = format_context_note(&context, "openai", "Mention the ticket");
+    assert!(note.contains("Branch: main"));
+    assert!(note.contains("Provider: openai"));
+    assert!(note.contains("Language: Rust"));
+    assert!(note.contains("- src/lib.rs (Modified)\n  - Added function: new"));
+    assert!(note.contains("Instructions:\nMention the ticket"));
+    assert!(!note.contains("Workspace impact"));
+}
@@ -0,0 +1,125 @@
+use git2::Repository;
+use git_iris::exclusions::PromptFilter;
+use git_iris::git::{get_branch_commits, get_commits_between, write_squash_msg};
+use git_iris::pr::{parse_remote_url, parse_squash_message, Forge, SquashMessage};
+use git_iris::pr_prompts::create_squash_range_user_prompt;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn commit_file(repo: &Repository, dir: &Path, name: &str, message: &str) {
+    fs::write(dir.join(name), message).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(name)).unwrap();
+    index.write().unwrap();
+    let tree_id = index.write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => vec![],
+    };
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parent_refs,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_get_branch_commits() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+
+    commit_file(&repo, temp_dir.path(), "base.txt", "Initial commit");
+    let base = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.branch("base", &base, false).unwrap();
+
+    commit_file(&repo, temp_dir.path(), "one.txt", "Add one");
+    commit_file(&repo, temp_dir.path(), "two.txt", "Add two");
+
+    let commits = get_branch_commits(temp_dir.path(), "base", &PromptFilter::default()).unwrap();
+    assert_eq!(commits.len(), 2);
+    assert!(commits.iter().any(|c| c.commit_message == "Add one"));
+    assert!(commits.iter().any(|c| c.commit_message == "Add two"));
+
+    assert!(get_branch_commits(temp_dir.path(), "missing", &PromptFilter::default()).is_err());
+}
+
+#[test]
+fn test_squash_range_prompt_and_write() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+
+    commit_file(&repo, temp_dir.path(), "base.txt", "Initial commit");
+    commit_file(&repo, temp_dir.path(), "one.txt", "Add one");
+    commit_file(&repo, temp_dir.path(), "two.txt", "Add two");
+
+    let changes =
+        get_commits_between(temp_dir.path(), "HEAD~2", "HEAD", &PromptFilter::default()).unwrap();
+    assert_eq!(changes.len(), 2);
+
+    let prompt = create_squash_range_user_prompt(&changes, "HEAD~2", "HEAD");
+    assert!(prompt.contains("from HEAD~2 to HEAD"));
+    assert!(prompt.contains("Message: Add one"));
+    assert!(prompt.contains("Message: Add two"));
+    assert!(!prompt.contains("Message: Initial commit"));
+
+    let message = SquashMessage {
+        title: "feat: add one and two".to_string(),
+        body: Some("- Add one\n- Add two".to_string()),
+    };
+    let path = write_squash_msg(temp_dir.path(), &message.to_commit_message()).unwrap();
+    assert!(path.ends_with("SQUASH_MSG"));
+    assert_eq!(
+        fs::read_to_string(path).unwrap(),
+        "feat: add one and two\n\n- Add one\n- Add two\n"
+    );
+}
+
+#[test]
+fn test_parse_squash_message_title_only() {
+    let message = parse_squash_message("```\n\"feat(cli): add pr-title command\"\n```", false);
+    assert_eq!(
+        message,
+        SquashMessage {
+            title: "feat(cli): add pr-title command".to_string(),
+            body: None,
+        }
+    );
+}
+
+#[test]
+fn test_parse_squash_message_with_body() {
+    let response = "fix: handle empty diffs\n\n- Skip files without hunks\n- Add regression test\n";
+    let message = parse_squash_message(response, true);
+    assert_eq!(message.title, "fix: handle empty diffs");
+    assert_eq!(
+        message.body.as_deref(),
+        Some("- Skip files without hunks\n- Add regression test")
+    );
+}
+
+#[test]
+fn test_parse_remote_url() {
+    let github = parse_remote_url("git@github.com:hyperb1iss/git-iris.git").unwrap();
+    assert_eq!(github.forge, Forge::GitHub);
+    assert_eq!(github.host, "github.com");
+    assert_eq!(github.path, "hyperb1iss/git-iris");
+
+    let https = parse_remote_url("https://github.com/hyperb1iss/git-iris").unwrap();
+    assert_eq!(https.path, "hyperb1iss/git-iris");
+
+    let gitlab = parse_remote_url("ssh://git@gitlab.example.com:2222/group/sub/project.git").unwrap();
+    assert_eq!(gitlab.forge, Forge::GitLab);
+    assert_eq!(gitlab.host, "gitlab.example.com");
+    assert_eq!(gitlab.path, "group/sub/project");
+
+    assert!(parse_remote_url("https://bitbucket.org/owner/repo.git").is_none());
+    assert!(parse_remote_url("/local/path/repo.git").is_none());
+}
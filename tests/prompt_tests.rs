@@ -1,6 +1,11 @@
-use git_iris::config::Config;
-use git_iris::context::{ChangeType, CommitContext, ProjectMetadata, RecentCommit, StagedFile};
-use git_iris::prompt::{create_prompt, create_user_prompt};
+use git_iris::config::{Config, MessageFormatConfig};
+use git_iris::context::{
+    ChangeType, CommitContext, ProjectMetadata, RecentCommit, StageStatus, StagedFile,
+};
+use git_iris::prompt::{
+    append_rejected_candidates, create_prompt, create_refine_user_prompt, create_review_system_prompt,
+    create_review_user_prompt, create_user_prompt, format_instructions,
+};
 
 fn create_mock_commit_context() -> CommitContext {
     CommitContext {
@@ -17,6 +22,7 @@ fn create_mock_commit_context() -> CommitContext {
             diff: "- old line\n+ new line".to_string(),
             analysis: vec!["Modified function: main".to_string()],
             content_excluded: false,
+            stage_status: StageStatus::Staged,
         }],
         unstaged_files: vec!["unstaged_file.txt".to_string()],
         project_metadata: ProjectMetadata {
@@ -28,6 +34,7 @@ fn create_mock_commit_context() -> CommitContext {
             test_framework: None,
             plugins: vec![],
         },
+        workspace_impact: vec![],
     }
 }
 
@@ -109,6 +116,31 @@ fn test_create_user_prompt() {
     assert!(prompt.contains("unstaged_file.txt"));
 }
 
+#[test]
+fn test_create_user_prompt_reports_file_roles_and_test_coverage() {
+    let mut commit_context = create_mock_commit_context();
+    commit_context.staged_files.push(StagedFile {
+        path: "tests/file1_tests.rs".to_string(),
+        change_type: ChangeType::Added,
+        diff: "+#[test]\n+fn works() {}\n".to_string(),
+        analysis: Vec::new(),
+        content_excluded: false,
+        stage_status: StageStatus::Staged,
+    });
+
+    let prompt = create_user_prompt(&commit_context).unwrap();
+    assert!(prompt.contains("- Modified [production]"));
+    assert!(prompt.contains("- Added [test]"));
+    assert!(prompt.contains(
+        "Test coverage:\n- Adds 1 test for file1 in tests/file1_tests.rs\nOnly say the change"
+    ));
+    assert!(!prompt.contains("without matching test changes"));
+
+    commit_context.staged_files.pop();
+    let prompt = create_review_user_prompt(&commit_context).unwrap();
+    assert!(prompt.contains("- Modifies code in file1.rs without any test changes"));
+}
+
 #[test]
 fn test_create_prompt_with_multiple_staged_files() {
     let mut commit_context = create_mock_commit_context();
@@ -118,6 +150,7 @@ fn test_create_prompt_with_multiple_staged_files() {
         diff: "+ new file content".to_string(),
         analysis: vec!["New function: helper".to_string()],
         content_excluded: false,
+        stage_status: StageStatus::Staged,
     });
 
     let config = Config::default();
@@ -132,6 +165,19 @@ fn test_create_prompt_with_multiple_staged_files() {
     assert!(prompt.contains("+ new file content"));
 }
 
+#[test]
+fn test_create_user_prompt_labels_unstaged_changes() {
+    let mut commit_context = create_mock_commit_context();
+    let prompt = create_user_prompt(&commit_context).unwrap();
+    assert!(!prompt.contains("(unstaged)"));
+
+    commit_context.staged_files[0].stage_status = StageStatus::Unstaged;
+    let prompt = create_user_prompt(&commit_context).unwrap();
+    assert!(prompt.contains("- Modified (unstaged)"));
+    assert!(prompt.contains("Change Type: Modified (unstaged)"));
+    assert!(prompt.contains("git commit -a"));
+}
+
 #[test]
 fn test_create_prompt_with_project_metadata() {
     let mut commit_context = create_mock_commit_context();
@@ -170,3 +216,73 @@ fn test_create_prompt_with_file_analysis() {
     assert!(prompt.contains("Added new struct: User"));
 }
 
+#[test]
+fn test_create_review_prompts() {
+    let commit_context = create_mock_commit_context();
+
+    let system_prompt = create_review_system_prompt("Focus on error handling");
+    assert!(system_prompt.contains("Bugs, Security, Missing Tests, Style"));
+    assert!(system_prompt.contains("`path:line`"));
+    assert!(system_prompt.contains("Focus on error handling"));
+
+    let user_prompt = create_review_user_prompt(&commit_context).unwrap();
+    assert!(user_prompt.contains("review the staged changes"));
+    assert!(user_prompt.contains("file1.rs"));
+    assert!(user_prompt.contains("- old line\n+ new line"));
+    assert!(!user_prompt.contains("unstaged_file.txt"));
+}
+
+#[test]
+fn test_create_refine_user_prompt() {
+    let earlier = vec!["Use the imperative mood".to_string()];
+    let prompt = create_refine_user_prompt(
+        "Staged changes:\n- src/api.rs",
+        "Fixed the handler\n",
+        &earlier,
+        " make it mention the API change ",
+    );
+
+    assert!(prompt.starts_with("Staged changes:\n- src/api.rs"));
+    assert!(prompt.contains("You previously wrote this commit message"));
+    assert!(prompt.contains("Fixed the handler\n"));
+    assert!(prompt.contains("- Use the imperative mood"));
+    assert!(prompt.ends_with("make it mention the API change"));
+
+    let prompt = create_refine_user_prompt("Changes", "Fixed the handler", &[], "Shorter");
+    assert!(!prompt.contains("earlier feedback"));
+}
+
+#[test]
+fn test_append_rejected_candidates() {
+    let mut prompt = "Revise the message".to_string();
+    append_rejected_candidates(&mut prompt, &[]);
+    assert_eq!(prompt, "Revise the message");
+
+    append_rejected_candidates(&mut prompt, &["Implement retries\n".to_string()]);
+    assert!(prompt.contains("chose the previous message over these alternatives"));
+    assert!(prompt.ends_with("---\nImplement retries\n---"));
+}
+
+#[test]
+fn test_format_instructions() {
+    let format = MessageFormatConfig {
+        subject_length: 40,
+        max_subject_length: 60,
+        wrap_body_at: 80,
+        blank_line_after_subject: false,
+    };
+    let instructions = format_instructions(&format);
+    assert!(instructions.contains("Limit the subject line to 40 characters if possible"));
+    assert!(instructions.contains("never exceed 60 characters"));
+    assert!(instructions.contains("Wrap the body at 80 characters."));
+    assert!(instructions.contains("may follow the subject line directly"));
+
+    let unlimited = MessageFormatConfig {
+        max_subject_length: 0,
+        wrap_body_at: 0,
+        ..MessageFormatConfig::default()
+    };
+    let instructions = format_instructions(&unlimited);
+    assert!(!instructions.contains("never exceed"));
+    assert!(!instructions.contains("Wrap the body"));
+}
@@ -0,0 +1,156 @@
+use clap::Parser;
+use git2::Repository;
+use git_iris::cli::{Cli, Commands};
+use git_iris::git::{commit_paths, has_staged_changes, tag_exists};
+use git_iris::release::{insert_changelog_entry, release_tag_name, update_changelog_file};
+use git_iris::semver::{next_version, Bump};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use tempfile::TempDir;
+
+fn setup_repo() -> (TempDir, Repository) {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+
+    fs::write(temp_dir.path().join("README.md"), "# Project\n").unwrap();
+    {
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+            .unwrap();
+    }
+
+    (temp_dir, repo)
+}
+
+#[test]
+fn test_release_tag_name_follows_previous_prefix() {
+    assert_eq!(release_tag_name(Some("v1.2.3"), "1.3.0"), "v1.3.0");
+    assert_eq!(release_tag_name(Some("1.2.3"), "1.3.0"), "1.3.0");
+    assert_eq!(release_tag_name(Some("1.2.3"), "v1.3.0"), "1.3.0");
+    assert_eq!(release_tag_name(None, "0.1.0"), "v0.1.0");
+}
+
+#[test]
+fn test_insert_changelog_entry_into_empty_file() {
+    let result = insert_changelog_entry("", "1.0.0", "2024-01-01", "### Added\n\n- Feature");
+    assert_eq!(
+        result,
+        "# Changelog\n\n## [1.0.0] - 2024-01-01\n\n### Added\n\n- Feature\n"
+    );
+}
+
+#[test]
+fn test_insert_changelog_entry_before_previous_release() {
+    let existing = "# Changelog\n\nAll notable changes.\n\n## [1.0.0] - 2024-01-01\n\n- Old\n";
+    let result = insert_changelog_entry(existing, "1.1.0", "2024-02-01", "- New");
+
+    let new_pos = result.find("## [1.1.0] - 2024-02-01").unwrap();
+    let old_pos = result.find("## [1.0.0] - 2024-01-01").unwrap();
+    assert!(new_pos < old_pos);
+    assert!(result.starts_with("# Changelog\n\nAll notable changes.\n\n"));
+    assert!(result.ends_with("- Old\n"));
+}
+
+#[test]
+fn test_insert_changelog_entry_nests_generated_headings() {
+    let changelog = "# Changelog\n\n## Added\n\n- Feature\n\n## Fixed\n\n- Bug";
+    let result = insert_changelog_entry("", "2.0.0", "2024-03-01", changelog);
+
+    assert_eq!(result.matches("# Changelog").count(), 1);
+    assert!(result.contains("### Added"));
+    assert!(result.contains("### Fixed"));
+    assert!(!result.contains("\n## Added"));
+}
+
+#[test]
+fn test_update_changelog_file_creates_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("CHANGELOG.md");
+
+    update_changelog_file(&path, "0.2.0", "2024-04-01", "- First").unwrap();
+    update_changelog_file(&path, "0.3.0", "2024-05-01", "- Second").unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.find("## [0.3.0]").unwrap() < content.find("## [0.2.0]").unwrap());
+}
+
+#[test]
+fn test_next_version_applies_bump() {
+    assert_eq!(next_version("v1.2.3", Bump::Patch).as_deref(), Some("1.2.4"));
+    assert_eq!(next_version("1.2.3", Bump::Minor).as_deref(), Some("1.3.0"));
+    assert_eq!(next_version("1.2.3", Bump::Major).as_deref(), Some("2.0.0"));
+    assert_eq!(next_version("0.4.1", Bump::Major).as_deref(), Some("0.5.0"));
+    assert_eq!(next_version("not-a-version", Bump::Patch), None);
+}
+
+#[test]
+fn test_bump_from_str() {
+    assert!(matches!(Bump::from_str("MAJOR"), Ok(Bump::Major)));
+    assert!(matches!(Bump::from_str("minor"), Ok(Bump::Minor)));
+    assert!(matches!(Bump::from_str("patch"), Ok(Bump::Patch)));
+    assert!(Bump::from_str("huge").is_err());
+}
+
+#[test]
+fn test_commit_paths_and_tag_exists() {
+    let (temp_dir, repo) = setup_repo();
+    assert!(!has_staged_changes(temp_dir.path()).unwrap());
+
+    fs::write(temp_dir.path().join("CHANGELOG.md"), "# Changelog\n").unwrap();
+    commit_paths(temp_dir.path(), &[Path::new("CHANGELOG.md")], "Release v1.0.0").unwrap();
+
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.message(), Some("Release v1.0.0"));
+    assert!(head.tree().unwrap().get_name("CHANGELOG.md").is_some());
+    assert!(!has_staged_changes(temp_dir.path()).unwrap());
+
+    assert!(!tag_exists(temp_dir.path(), "v1.0.0").unwrap());
+    repo.tag_lightweight("v1.0.0", head.as_object(), false).unwrap();
+    assert!(tag_exists(temp_dir.path(), "v1.0.0").unwrap());
+}
+
+#[test]
+fn test_has_staged_changes() {
+    let (temp_dir, repo) = setup_repo();
+
+    fs::write(temp_dir.path().join("README.md"), "# Changed\n").unwrap();
+    assert!(!has_staged_changes(temp_dir.path()).unwrap());
+
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("README.md")).unwrap();
+    index.write().unwrap();
+    assert!(has_staged_changes(temp_dir.path()).unwrap());
+}
+
+#[test]
+fn test_parse_release_command() {
+    let cli = Cli::try_parse_from(["git-iris", "release"]).unwrap();
+    assert!(!cli.dry_run);
+    match cli.command {
+        Some(Commands::Release { release_version, bump, .. }) => {
+            assert_eq!(release_version, None);
+            assert_eq!(bump, None);
+        }
+        _ => panic!("expected the release command"),
+    }
+
+    let cli = Cli::try_parse_from(["git-iris", "release", "1.2.3", "--dry-run"]).unwrap();
+    assert!(cli.dry_run);
+    assert!(!cli.version);
+    match cli.command {
+        Some(Commands::Release { release_version, .. }) => {
+            assert_eq!(release_version.as_deref(), Some("1.2.3"));
+        }
+        _ => panic!("expected the release command"),
+    }
+
+    assert!(Cli::try_parse_from(["git-iris", "release", "1.2.3", "--bump", "minor"]).is_err());
+}
@@ -0,0 +1,115 @@
+use chrono::{Duration, Local, TimeZone};
+use git2::Repository;
+use git_iris::config::Config;
+use git_iris::report::{
+    render_report, report_title, ReportFormat, ReportGenerator, ReportMetrics,
+};
+use std::path::Path;
+use std::str::FromStr;
+use tempfile::TempDir;
+
+fn sample_metrics() -> ReportMetrics {
+    ReportMetrics {
+        commits: 4,
+        contributors: 2,
+        files_changed: 7,
+        insertions: 120,
+        deletions: 30,
+        top_areas: vec![("src/api".to_string(), 3), ("docs".to_string(), 1)],
+    }
+}
+
+#[test]
+fn test_report_format_from_str() {
+    assert_eq!(ReportFormat::from_str("md").unwrap(), ReportFormat::Markdown);
+    assert_eq!(ReportFormat::from_str("HTML").unwrap(), ReportFormat::Html);
+    assert!(ReportFormat::from_str("pdf").is_err());
+}
+
+#[test]
+fn test_report_title() {
+    let since = Local.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+    let until = Local.with_ymd_and_hms(2024, 5, 8, 12, 0, 0).unwrap();
+    assert_eq!(
+        report_title(since, until, None),
+        "Progress Report: 2024-05-01 to 2024-05-08"
+    );
+    assert_eq!(
+        report_title(since, until, Some("ada@example.com")),
+        "Progress Report: 2024-05-01 to 2024-05-08 (ada@example.com)"
+    );
+}
+
+#[test]
+fn test_render_markdown_report() {
+    let report = render_report(
+        ReportFormat::Markdown,
+        "Progress Report: 2024-05-01 to 2024-05-08",
+        "## Summary\n\nShipped the API.\n",
+        &sample_metrics(),
+    );
+
+    assert!(report.starts_with("# Progress Report: 2024-05-01 to 2024-05-08\n\n## Summary\n"));
+    assert!(report.contains("## Metrics\n\n| Metric | Value |\n| --- | --- |\n"));
+    assert!(report.contains("| Commits | 4 |\n"));
+    assert!(report.contains("| Lines changed | +120 -30 |\n"));
+    assert!(report.contains("| Most active areas | src/api (3 commits), docs (1 commit) |\n"));
+}
+
+#[test]
+fn test_render_html_report() {
+    let report = render_report(
+        ReportFormat::Html,
+        "Progress Report: 2024-05-01 to 2024-05-08 (Ada <ada@example.com>)",
+        "<h2>Summary</h2>\n<p>Shipped the API.</p>",
+        &sample_metrics(),
+    );
+
+    assert!(report.starts_with("<!DOCTYPE html>\n"));
+    assert!(report.contains(
+        "<title>Progress Report: 2024-05-01 to 2024-05-08 (Ada &lt;ada@example.com&gt;)</title>"
+    ));
+    assert!(report.contains("<h2>Summary</h2>\n<p>Shipped the API.</p>"));
+    assert!(report.contains("<tr><th>Contributors</th><td>2</td></tr>"));
+    assert!(report.trim_end().ends_with("</html>"));
+}
+
+#[tokio::test]
+async fn test_generate_report() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    std::fs::write(temp_dir.path().join("api.rs"), "fn serve() {}\n").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("api.rs")).unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "Add the API server", &tree, &[])
+        .unwrap();
+
+    let config = Config {
+        default_provider: "test".to_string(),
+        ..Default::default()
+    };
+
+    let since = Local::now() - Duration::days(1);
+    let report = ReportGenerator::generate(
+        temp_dir.path(),
+        since,
+        None,
+        ReportFormat::Markdown,
+        &config,
+    )
+    .await
+    .unwrap();
+
+    assert!(report.starts_with("# Progress Report: "));
+    assert!(report.contains("Add the API server"));
+    assert!(report.contains("| Commits | 1 |\n"));
+
+    let later = Local::now() + Duration::days(1);
+    assert!(
+        ReportGenerator::generate(temp_dir.path(), later, None, ReportFormat::Markdown, &config)
+            .await
+            .is_err()
+    );
+}
@@ -0,0 +1,136 @@
+use git2::Repository;
+use git_iris::context::{
+    ChangeType, CommitContext, ProjectMetadata, RecentCommit, StageStatus, StagedFile,
+};
+use git_iris::revert::{
+    create_revert_user_prompt, ensure_revert_trailer, revert_commit, RevertOutcome,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn commit_file(
+    repo: &Repository,
+    repo_path: &Path,
+    name: &str,
+    content: &str,
+    message: &str,
+) -> String {
+    fs::write(repo_path.join(name), content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(name)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => Vec::new(),
+    };
+    let parents: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .unwrap()
+        .to_string()
+}
+
+fn setup_repo() -> (TempDir, Repository, String) {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+    let path = temp_dir.path();
+    commit_file(&repo, path, "config.toml", "retries = 3\n", "Add config");
+    let id = commit_file(&repo, path, "config.toml", "retries = 5\n", "Raise retries to 5");
+    (temp_dir, repo, id)
+}
+
+#[test]
+fn test_ensure_revert_trailer() {
+    let id = "0123456789abcdef0123456789abcdef01234567";
+    let expected = format!(
+        "Revert \"Raise retries\"\n\nGo back to three retries.\n\nThis reverts commit {}.",
+        id
+    );
+
+    assert_eq!(
+        ensure_revert_trailer("Revert \"Raise retries\"\n\nGo back to three retries.", id),
+        expected
+    );
+    // A trailer with an abbreviated or wrong hash is replaced
+    assert_eq!(
+        ensure_revert_trailer(
+            "Revert \"Raise retries\"\n\nGo back to three retries.\n\nThis reverts commit 0123456.\n",
+            id
+        ),
+        expected
+    );
+}
+
+#[test]
+fn test_create_revert_user_prompt() {
+    let context = CommitContext {
+        branch: "main".to_string(),
+        recent_commits: vec![RecentCommit {
+            hash: "abc1234".to_string(),
+            message: "Raise retries to 5".to_string(),
+            author: "Test User".to_string(),
+            timestamp: "1234567890".to_string(),
+        }],
+        staged_files: vec![StagedFile {
+            path: "config.toml".to_string(),
+            change_type: ChangeType::Modified,
+            diff: "-retries = 3\n+retries = 5".to_string(),
+            analysis: vec!["Retry count changed".to_string()],
+            content_excluded: false,
+            stage_status: StageStatus::Staged,
+        }],
+        unstaged_files: vec![],
+        project_metadata: ProjectMetadata::default(),
+        workspace_impact: vec![],
+    };
+
+    let prompt = create_revert_user_prompt(
+        &context,
+        "abc1234",
+        "Raise retries to 5\n\nFlaky network in CI.\n",
+    );
+    assert!(prompt.contains("Commit: abc1234"));
+    assert!(prompt.contains("Original message:\nRaise retries to 5\n\nFlaky network in CI."));
+    assert!(prompt.contains("- config.toml (Modified)"));
+    assert!(prompt.contains("  - Retry count changed"));
+    assert!(prompt.contains("-retries = 3\n+retries = 5"));
+}
+
+#[test]
+fn test_revert_commit_commits_with_message() {
+    let (temp_dir, repo, id) = setup_repo();
+    let message = format!("Revert \"Raise retries to 5\"\n\nThis reverts commit {}.", id);
+
+    let outcome = revert_commit(temp_dir.path(), &id, None, &message).unwrap();
+    let RevertOutcome::Committed(new_id) = outcome else {
+        panic!("expected the revert to be committed");
+    };
+
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.id().to_string(), new_id);
+    assert_eq!(head.message().unwrap().trim_end(), message);
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("config.toml")).unwrap(),
+        "retries = 3\n"
+    );
+    assert_eq!(repo.state(), git2::RepositoryState::Clean);
+}
+
+#[test]
+fn test_revert_commit_reports_conflicts() {
+    let (temp_dir, repo, id) = setup_repo();
+    commit_file(&repo, temp_dir.path(), "config.toml", "retries = 8\n", "Raise retries to 8");
+    let message = format!("Revert \"Raise retries to 5\"\n\nThis reverts commit {}.", id);
+
+    let outcome = revert_commit(temp_dir.path(), &id, None, &message).unwrap();
+    assert_eq!(outcome, RevertOutcome::Conflicts(vec!["config.toml".to_string()]));
+    assert_eq!(
+        fs::read_to_string(repo.path().join("MERGE_MSG")).unwrap(),
+        format!("{}\n", message)
+    );
+}
@@ -0,0 +1,104 @@
+use git2::Repository;
+use git_iris::rewrite::{apply_rewrites, check_not_pushed, commits_to_rewrite, RewriteEntry};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn commit_file(repo: &Repository, dir: &Path, name: &str, message: &str) {
+    fs::write(dir.join(name), message).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(name)).unwrap();
+    index.write().unwrap();
+    let tree_id = index.write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => vec![],
+    };
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parent_refs,
+    )
+    .unwrap();
+}
+
+fn setup_repo() -> (TempDir, Repository) {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    commit_file(&repo, temp_dir.path(), "base.txt", "Initial commit");
+    commit_file(&repo, temp_dir.path(), "one.txt", "wip");
+    commit_file(&repo, temp_dir.path(), "two.txt", "more wip");
+    commit_file(&repo, temp_dir.path(), "three.txt", "Add three");
+    (temp_dir, repo)
+}
+
+fn messages(repo: &Repository) -> Vec<String> {
+    let mut revwalk = repo.revwalk().unwrap();
+    revwalk.push_head().unwrap();
+    revwalk
+        .map(|oid| {
+            repo.find_commit(oid.unwrap())
+                .unwrap()
+                .message()
+                .unwrap()
+                .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn test_apply_rewrites_keeps_trees_and_later_commits() {
+    let (temp_dir, repo) = setup_repo();
+    let tree_before = repo.head().unwrap().peel_to_tree().unwrap().id();
+
+    // Rewrite the two wip commits, leaving the last commit as it is
+    let commits = commits_to_rewrite(temp_dir.path(), "HEAD~3", "HEAD~1").unwrap();
+    assert_eq!(commits.len(), 2);
+
+    let entries = vec![
+        RewriteEntry {
+            commit_id: commits[0].clone(),
+            original: "wip".to_string(),
+            rewritten: "Add one".to_string(),
+        },
+        RewriteEntry {
+            commit_id: commits[1].clone(),
+            original: "more wip".to_string(),
+            rewritten: "Add two".to_string(),
+        },
+    ];
+    let new_head = apply_rewrites(temp_dir.path(), "HEAD~3", &entries).unwrap();
+
+    let head = repo.head().unwrap();
+    assert!(head.is_branch());
+    assert_eq!(head.target().unwrap().to_string(), new_head);
+    assert_eq!(head.peel_to_tree().unwrap().id(), tree_before);
+    assert_eq!(
+        messages(&repo),
+        vec!["Add three", "Add two", "Add one", "Initial commit"]
+    );
+}
+
+#[test]
+fn test_commits_to_rewrite_validation() {
+    let (temp_dir, repo) = setup_repo();
+
+    assert!(commits_to_rewrite(temp_dir.path(), "HEAD", "HEAD").is_err());
+    assert!(commits_to_rewrite(temp_dir.path(), "missing", "HEAD").is_err());
+
+    // A commit on a remote-tracking branch counts as pushed
+    let commits = commits_to_rewrite(temp_dir.path(), "HEAD~3", "HEAD").unwrap();
+    assert!(check_not_pushed(temp_dir.path(), &commits).is_ok());
+
+    let pushed = repo.revparse_single("HEAD~2").unwrap().id();
+    repo.reference("refs/remotes/origin/main", pushed, true, "test")
+        .unwrap();
+    assert!(check_not_pushed(temp_dir.path(), &commits).is_err());
+    assert!(check_not_pushed(temp_dir.path(), &commits[2..]).is_ok());
+}
@@ -0,0 +1,93 @@
+use git2::Repository;
+use git_iris::config::Config;
+use git_iris::risk::{detect_risks, enabled_risk_labels, RiskLabel};
+use std::str::FromStr;
+use tempfile::TempDir;
+
+#[test]
+fn test_detect_schema_migration() {
+    let labels = detect_risks("db/migrations/001_add_users.sql", "+CREATE TABLE users (id INT);", &[]);
+    assert!(labels.contains(&RiskLabel::SchemaMigration));
+
+    let labels = detect_risks("queries/report.sql", "+ALTER TABLE orders ADD COLUMN total INT;", &[]);
+    assert!(labels.contains(&RiskLabel::SchemaMigration));
+
+    let labels = detect_risks("queries/report.sql", "+SELECT * FROM orders;", &[]);
+    assert!(!labels.contains(&RiskLabel::SchemaMigration));
+}
+
+#[test]
+fn test_detect_auth_change() {
+    let labels = detect_risks("src/auth/mod.rs", "+fn check() {}", &[]);
+    assert!(labels.contains(&RiskLabel::AuthChange));
+
+    let analysis = vec!["Modified functions: refresh_session".to_string()];
+    let labels = detect_risks("src/api.rs", "+fn refresh_session() {}", &analysis);
+    assert!(labels.contains(&RiskLabel::AuthChange));
+
+    let labels = detect_risks("src/author.rs", "+fn name() {}", &[]);
+    assert!(!labels.contains(&RiskLabel::AuthChange));
+}
+
+#[test]
+fn test_detect_dependency_major_bump() {
+    let cargo_diff = "-reqwest = \"0.11.4\"\n+reqwest = \"0.12.5\"\n-git2 = { version = \"0.18.0\" }\n+git2 = { version = \"1.0.0\" }";
+    let labels = detect_risks("Cargo.toml", cargo_diff, &[]);
+    assert!(labels.contains(&RiskLabel::DependencyMajorBump));
+
+    let minor_diff = "-    \"react\": \"^18.2.0\",\n+    \"react\": \"^18.3.1\",";
+    let labels = detect_risks("package.json", minor_diff, &[]);
+    assert!(!labels.contains(&RiskLabel::DependencyMajorBump));
+
+    let major_diff = "-    \"react\": \"^17.0.2\",\n+    \"react\": \"^18.3.1\",";
+    let labels = detect_risks("package.json", major_diff, &[]);
+    assert!(labels.contains(&RiskLabel::DependencyMajorBump));
+}
+
+#[test]
+fn test_detect_concurrency_sensitive() {
+    let labels = detect_risks("src/cache.rs", "+    let cache = Arc::new(Mutex::new(HashMap::new()));", &[]);
+    assert!(labels.contains(&RiskLabel::ConcurrencySensitive));
+
+    let labels = detect_risks("README.md", "+Some documentation", &[]);
+    assert!(labels.is_empty());
+}
+
+#[test]
+fn test_risk_label_from_str() {
+    for label in RiskLabel::all() {
+        assert_eq!(RiskLabel::from_str(label.key()).unwrap(), label);
+    }
+    assert!(RiskLabel::from_str("unknown").is_err());
+}
+
+#[test]
+fn test_enabled_risk_labels_repo_override() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut config = Config::default();
+
+    // Falls back to the global configuration
+    assert_eq!(enabled_risk_labels(temp_dir.path(), &config), RiskLabel::all());
+    config.risk_labels = vec!["auth-change".to_string()];
+    assert_eq!(
+        enabled_risk_labels(temp_dir.path(), &config),
+        vec![RiskLabel::AuthChange]
+    );
+
+    // The repository setting takes precedence
+    repo.config()
+        .unwrap()
+        .set_str("iris.riskLabels", "schema-migration, concurrency")
+        .unwrap();
+    assert_eq!(
+        enabled_risk_labels(temp_dir.path(), &config),
+        vec![RiskLabel::SchemaMigration, RiskLabel::ConcurrencySensitive]
+    );
+
+    repo.config()
+        .unwrap()
+        .set_str("iris.riskLabels", "none")
+        .unwrap();
+    assert!(enabled_risk_labels(temp_dir.path(), &config).is_empty());
+}
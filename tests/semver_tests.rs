@@ -0,0 +1,140 @@
+use git2::Repository;
+use git_iris::change_analyzer::{AnalyzedChange, ChangeMetrics, FileChange};
+use git_iris::config::Config;
+use git_iris::context::ChangeType;
+use git_iris::risk::RiskLabel;
+use git_iris::semver::{classify_commit, parse_version, suggest_bump, suggest_next_version, Bump};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn change(hash: &str, message: &str, risk_labels: Vec<RiskLabel>) -> AnalyzedChange {
+    AnalyzedChange {
+        commit_hash: hash.to_string(),
+        commit_message: message.to_string(),
+        author: "Jane Doe".to_string(),
+        file_changes: vec![FileChange {
+            old_path: "src/lib.rs".to_string(),
+            new_path: "src/lib.rs".to_string(),
+            change_type: ChangeType::Modified,
+            analysis: vec![],
+            risk_labels,
+        }],
+        metrics: ChangeMetrics {
+            files_changed: 1,
+            insertions: 1,
+            deletions: 1,
+            total_lines_changed: 2,
+        },
+        impact_score: 0.1,
+    }
+}
+
+#[test]
+fn test_classify_commit() {
+    assert_eq!(classify_commit("fix: handle empty diff"), Bump::Patch);
+    assert_eq!(classify_commit("feat(cli): add semver command"), Bump::Minor);
+    assert_eq!(classify_commit("✨ feat: add semver command"), Bump::Minor);
+    assert_eq!(classify_commit("refactor(api)!: rename config keys"), Bump::Major);
+    assert_eq!(
+        classify_commit("fix: drop old flag\n\nBREAKING CHANGE: --foo is gone"),
+        Bump::Major
+    );
+    assert_eq!(classify_commit("Add support for Go files"), Bump::Minor);
+    assert_eq!(classify_commit("Update README"), Bump::Patch);
+}
+
+#[test]
+fn test_parse_version() {
+    assert_eq!(parse_version("v1.2.3"), Some((1, 2, 3)));
+    assert_eq!(parse_version("0.9.0-rc.1"), Some((0, 9, 0)));
+    assert_eq!(parse_version("2.1"), Some((2, 1, 0)));
+    assert_eq!(parse_version("release-2024"), None);
+}
+
+#[test]
+fn test_suggest_bump() {
+    let changes = vec![
+        change("aaaaaaa1", "fix: handle empty diff", vec![]),
+        change("bbbbbbb2", "feat: add semver command", vec![]),
+    ];
+    let suggestion = suggest_bump(Some("v1.2.3"), &changes);
+    assert_eq!(suggestion.bump, Bump::Minor);
+    assert_eq!(suggestion.current_version.as_deref(), Some("1.2.3"));
+    assert_eq!(suggestion.next_version.as_deref(), Some("1.3.0"));
+    assert_eq!(suggestion.commits, 2);
+    assert!(suggestion.justification[0].contains("bbbbbbb feat: add semver command"));
+
+    let breaking = vec![change(
+        "ccccccc3",
+        "feat!: remove legacy config",
+        vec![RiskLabel::SchemaMigration],
+    )];
+    let suggestion = suggest_bump(Some("v1.2.3"), &breaking);
+    assert_eq!(suggestion.bump, Bump::Major);
+    assert_eq!(suggestion.next_version.as_deref(), Some("2.0.0"));
+    assert!(suggestion
+        .justification
+        .iter()
+        .any(|reason| reason.contains("schema migration")));
+
+    // Before 1.0, breaking changes only bump the minor version
+    let suggestion = suggest_bump(Some("v0.4.1"), &breaking);
+    assert_eq!(suggestion.next_version.as_deref(), Some("0.5.0"));
+
+    let suggestion = suggest_bump(None, &[]);
+    assert_eq!(suggestion.bump, Bump::Patch);
+    assert!(suggestion.next_version.is_none());
+}
+
+#[test]
+fn test_suggestion_json() {
+    let suggestion = suggest_bump(Some("v1.0.0"), &[change("ddddddd4", "fix: typo", vec![])]);
+    let json: serde_json::Value = serde_json::to_value(&suggestion).unwrap();
+    assert_eq!(json["bump"], "patch");
+    assert_eq!(json["next_version"], "1.0.1");
+}
+
+fn commit_file(repo: &Repository, path: &str, content: &str, message: &str) {
+    let workdir = repo.workdir().unwrap();
+    fs::create_dir_all(workdir.join(path).parent().unwrap()).unwrap();
+    fs::write(workdir.join(path), content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(path)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .unwrap();
+}
+
+#[test]
+fn test_suggest_next_version_leaves_out_excluded_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    commit_file(&repo, "README.md", "# Project\n", "Initial");
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.tag_lightweight("v1.0.0", head.as_object(), false).unwrap();
+    let migration = "CREATE TABLE users (id INTEGER);\n";
+    commit_file(&repo, "db/migrations/001_users.sql", migration, "fix: Add the users table");
+
+    let suggestion = suggest_next_version(temp_dir.path(), "HEAD", &Config::default()).unwrap();
+    assert!(suggestion
+        .justification
+        .iter()
+        .any(|reason| reason.contains("schema migration")));
+
+    let config = Config {
+        prompt_exclude: vec!["db/**".to_string()],
+        ..Default::default()
+    };
+    let suggestion = suggest_next_version(temp_dir.path(), "HEAD", &config).unwrap();
+    assert_eq!(suggestion.commits, 1);
+    assert_eq!(suggestion.next_version.as_deref(), Some("1.0.1"));
+    assert!(!suggestion
+        .justification
+        .iter()
+        .any(|reason| reason.contains("schema migration")));
+}
@@ -0,0 +1,74 @@
+use git2::Repository;
+use git_iris::session::{session_key, LastGeneration, ReleaseNotesSession};
+use tempfile::TempDir;
+
+fn setup_repo() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    Repository::init(temp_dir.path()).unwrap();
+    temp_dir
+}
+
+#[test]
+fn test_session_round_trip() {
+    let temp_dir = setup_repo();
+    let key = session_key(&["abc", "def", "Standard"]);
+
+    let mut session = ReleaseNotesSession::load_or_new(temp_dir.path(), &key);
+    assert!(!session.is_resumed());
+
+    session.readme_summary = Some("A summary".to_string());
+    session.changelog = Some("## Features".to_string());
+    session.save(temp_dir.path()).unwrap();
+    assert!(ReleaseNotesSession::has_saved(temp_dir.path()));
+
+    let resumed = ReleaseNotesSession::load_or_new(temp_dir.path(), &key);
+    assert!(resumed.is_resumed());
+    assert_eq!(resumed.readme_summary.as_deref(), Some("A summary"));
+    assert_eq!(resumed.changelog.as_deref(), Some("## Features"));
+
+    resumed.clear(temp_dir.path()).unwrap();
+    assert!(!ReleaseNotesSession::has_saved(temp_dir.path()));
+}
+
+#[test]
+fn test_session_key_changes_with_inputs() {
+    let temp_dir = setup_repo();
+    let key = session_key(&["abc", "def", "Standard"]);
+    let other_key = session_key(&["abc", "def", "Detailed"]);
+    assert_ne!(key, other_key);
+
+    let mut session = ReleaseNotesSession::load_or_new(temp_dir.path(), &key);
+    session.changelog = Some("## Features".to_string());
+    session.save(temp_dir.path()).unwrap();
+
+    let other = ReleaseNotesSession::load_or_new(temp_dir.path(), &other_key);
+    assert!(!other.is_resumed());
+
+    ReleaseNotesSession::discard_all(temp_dir.path()).unwrap();
+    assert!(!ReleaseNotesSession::has_saved(temp_dir.path()));
+}
+
+#[test]
+fn test_last_generation_round_trip() {
+    let temp_dir = setup_repo();
+    assert!(LastGeneration::load(temp_dir.path()).is_none());
+
+    let generation = LastGeneration {
+        message: "Add the user endpoint".to_string(),
+        feedback: vec!["Mention the API change".to_string()],
+        rejected: vec!["Implement user endpoint".to_string()],
+    };
+    generation.save(temp_dir.path()).unwrap();
+    assert_eq!(LastGeneration::load(temp_dir.path()), Some(generation));
+
+    assert_eq!(
+        LastGeneration::feedback_for(temp_dir.path(), "Add the user endpoint"),
+        vec!["Mention the API change".to_string()]
+    );
+    assert!(LastGeneration::feedback_for(temp_dir.path(), "Another message").is_empty());
+    assert_eq!(
+        LastGeneration::rejected_for(temp_dir.path(), "Add the user endpoint"),
+        vec!["Implement user endpoint".to_string()]
+    );
+    assert!(LastGeneration::rejected_for(temp_dir.path(), "Another message").is_empty());
+}
@@ -0,0 +1,148 @@
+use git2::Repository;
+use git_iris::exclusions::PromptFilter;
+use git_iris::git::{commit, get_commit_patch};
+use git_iris::split::{
+    collect_staged_hunks, format_split_plan, group_steps, parse_split_plan, unstage_all,
+    SplitGroup, StagedPatch, REMAINING_GROUP_TITLE,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn numbered_lines(count: usize) -> String {
+    (1..=count).map(|n| format!("line {}\n", n)).collect()
+}
+
+fn setup_repo() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+
+    fs::write(temp_dir.path().join("lib.rs"), numbered_lines(30)).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("lib.rs")).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+        .unwrap();
+
+    // Two distant edits in lib.rs, so they form separate hunks, and a new file
+    let content = numbered_lines(30)
+        .replace("line 2\n", "line two\n")
+        .replace("line 28\n", "line twenty-eight\n");
+    fs::write(temp_dir.path().join("lib.rs"), content).unwrap();
+    fs::write(temp_dir.path().join("README.md"), "# Docs\n").unwrap();
+    index.add_path(Path::new("lib.rs")).unwrap();
+    index.add_path(Path::new("README.md")).unwrap();
+    index.write().unwrap();
+
+    temp_dir
+}
+
+#[test]
+fn test_collect_staged_hunks() {
+    let temp_dir = setup_repo();
+    let hunks = collect_staged_hunks(temp_dir.path()).unwrap();
+
+    assert_eq!(hunks.len(), 3);
+    assert_eq!(
+        hunks.iter().map(|hunk| hunk.id).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(hunks[0].path, "README.md");
+    assert_eq!(hunks[1].path, "lib.rs");
+    assert_eq!(hunks[1].file_hunk_index, Some(0));
+    assert!(hunks[1].header.starts_with("@@"));
+    assert!(hunks[1].content.contains("+line two"));
+    assert!(hunks[2].content.contains("+line twenty-eight"));
+}
+
+#[test]
+fn test_parse_split_plan_covers_every_hunk_once() {
+    let temp_dir = setup_repo();
+    let hunks = collect_staged_hunks(temp_dir.path()).unwrap();
+
+    let response = "```json\n{\"groups\": [\
+        {\"title\": \"Document the library\", \"rationale\": \"Docs only\", \"hunks\": [\"H1\", 7]},\
+        {\"title\": \"Rename line two\", \"rationale\": \"Wording\", \"hunks\": [2, 1]}\
+    ]}\n```";
+    let plan = parse_split_plan(response, &hunks).unwrap();
+
+    assert_eq!(plan.groups.len(), 3);
+    assert_eq!(plan.groups[0].hunk_ids, vec![1]);
+    assert_eq!(plan.groups[1].hunk_ids, vec![2]);
+    assert_eq!(plan.groups[2].title, REMAINING_GROUP_TITLE);
+    assert_eq!(plan.groups[2].hunk_ids, vec![3]);
+
+    assert!(parse_split_plan("No idea", &hunks).is_err());
+}
+
+#[test]
+fn test_group_steps_use_patch_mode_for_partial_files() {
+    let temp_dir = setup_repo();
+    let hunks = collect_staged_hunks(temp_dir.path()).unwrap();
+
+    let group = SplitGroup {
+        title: "Rename line two".to_string(),
+        rationale: String::new(),
+        hunk_ids: vec![1, 2],
+    };
+    assert_eq!(
+        group_steps(&group, &hunks),
+        vec![
+            "git add -- README.md".to_string(),
+            "git add -p -- lib.rs    # stage hunk 1 of 2".to_string(),
+        ]
+    );
+
+    let plan = parse_split_plan(
+        r#"{"groups": [{"title": "Say \"hi\"", "rationale": "Greeting", "hunks": [2, 3]}]}"#,
+        &hunks,
+    )
+    .unwrap();
+    let output = format_split_plan(&plan, &hunks);
+    assert!(output.starts_with("Unstage everything first"));
+    assert!(output.contains("1. Say \"hi\"\n   Greeting\n"));
+    assert!(output.contains("    git add -- lib.rs\n    git commit -m \"Say \\\"hi\\\"\"\n"));
+    assert!(output.contains(&format!("2. {}", REMAINING_GROUP_TITLE)));
+}
+
+#[test]
+fn test_staged_patch_stages_one_group_at_a_time() {
+    let temp_dir = setup_repo();
+    let hunks = collect_staged_hunks(temp_dir.path()).unwrap();
+
+    // An edit made after staging must stay out of every commit
+    let content = fs::read_to_string(temp_dir.path().join("lib.rs")).unwrap();
+    fs::write(
+        temp_dir.path().join("lib.rs"),
+        content.replace("line 15\n", "line fifteen\n"),
+    )
+    .unwrap();
+
+    let staged = StagedPatch::capture(temp_dir.path()).unwrap();
+    unstage_all(temp_dir.path()).unwrap();
+    assert!(collect_staged_hunks(temp_dir.path()).unwrap().is_empty());
+
+    staged.stage(temp_dir.path(), &hunks, &[1, 3]).unwrap();
+    let group = collect_staged_hunks(temp_dir.path()).unwrap();
+    assert_eq!(group.len(), 2);
+    assert_eq!(group[0].path, "README.md");
+    assert!(group[1].content.contains("+line twenty-eight"));
+    commit(temp_dir.path(), "First group").unwrap();
+
+    let patch = get_commit_patch(temp_dir.path(), "HEAD", &PromptFilter::default()).unwrap();
+    assert!(patch.contains("+line twenty-eight"));
+    assert!(!patch.contains("+line two\n"));
+    assert!(!patch.contains("fifteen"));
+
+    // The remaining hunk still applies on top of the new commit
+    staged.stage(temp_dir.path(), &hunks, &[2]).unwrap();
+    let rest = collect_staged_hunks(temp_dir.path()).unwrap();
+    assert_eq!(rest.len(), 1);
+    assert!(rest[0].content.contains("+line two"));
+    assert!(!rest[0].content.contains("fifteen"));
+}
@@ -0,0 +1,98 @@
+use git2::Repository;
+use git_iris::exclusions::PromptFilter;
+use git_iris::git::{get_working_tree_info, stash_push};
+use git_iris::stash::{create_stash_user_prompt, parse_stash_message, MAX_STASH_MESSAGE_LENGTH};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn setup_repo() -> (TempDir, Repository) {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+
+    for name in ["staged.txt", "unstaged.txt"] {
+        fs::write(temp_dir.path().join(name), "original\n").unwrap();
+    }
+    {
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.add_path(Path::new("unstaged.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+            .unwrap();
+    }
+
+    // One staged change, one unstaged change, and one untracked file
+    fs::write(temp_dir.path().join("staged.txt"), "staged change\n").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("staged.txt")).unwrap();
+    index.write().unwrap();
+    fs::write(temp_dir.path().join("unstaged.txt"), "unstaged change\n").unwrap();
+    fs::write(temp_dir.path().join("untracked.txt"), "new file\n").unwrap();
+
+    (temp_dir, repo)
+}
+
+fn paths(repo_path: &Path, include_untracked: bool) -> Vec<String> {
+    get_working_tree_info(repo_path, include_untracked, &PromptFilter::default())
+        .unwrap()
+        .staged_files
+        .into_iter()
+        .map(|file| file.path)
+        .collect()
+}
+
+#[test]
+fn test_working_tree_info_covers_staged_and_unstaged_changes() {
+    let (temp_dir, _repo) = setup_repo();
+
+    assert_eq!(paths(temp_dir.path(), false), vec!["staged.txt", "unstaged.txt"]);
+    assert_eq!(
+        paths(temp_dir.path(), true),
+        vec!["staged.txt", "unstaged.txt", "untracked.txt"]
+    );
+
+    let context = get_working_tree_info(temp_dir.path(), false, &PromptFilter::default()).unwrap();
+    let prompt = create_stash_user_prompt(&context);
+    assert!(prompt.contains("- staged.txt (Modified)"));
+    assert!(prompt.contains("+unstaged change"));
+}
+
+#[test]
+fn test_stash_push_uses_message() {
+    let (temp_dir, mut repo) = setup_repo();
+
+    stash_push(temp_dir.path(), "Retry logic for uploads", false).unwrap();
+
+    let mut messages = Vec::new();
+    repo.stash_foreach(|_, message, _| {
+        messages.push(message.to_string());
+        true
+    })
+    .unwrap();
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].ends_with("Retry logic for uploads"));
+
+    // Tracked changes are stashed, untracked files stay
+    assert!(paths(temp_dir.path(), false).is_empty());
+    assert!(temp_dir.path().join("untracked.txt").exists());
+}
+
+#[test]
+fn test_parse_stash_message() {
+    assert_eq!(
+        parse_stash_message("\n\"Retry logic for flaky uploads.\"\nExtra line"),
+        "Retry logic for flaky uploads"
+    );
+
+    let long = "word ".repeat(30);
+    let parsed = parse_stash_message(&long);
+    assert!(parsed.len() <= MAX_STASH_MESSAGE_LENGTH);
+    assert!(parsed.ends_with("word"));
+    assert_eq!(parse_stash_message(""), "");
+}
@@ -0,0 +1,101 @@
+use git2::{Repository, Signature};
+use git_iris::stats::{commit_type, compute_stats, format_stats_report, DEFAULT_TOP};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn commit_files(
+    repo: &Repository,
+    repo_path: &Path,
+    author: &str,
+    files: &[(&str, &str)],
+    message: &str,
+) {
+    let mut index = repo.index().unwrap();
+    for (name, content) in files {
+        let path = repo_path.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        index.add_path(Path::new(name)).unwrap();
+    }
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let email = format!("{}@example.com", author.to_lowercase());
+    let signature = Signature::now(author, &email).unwrap();
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => Vec::new(),
+    };
+    let parents: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .unwrap();
+}
+
+#[test]
+fn test_commit_type() {
+    assert_eq!(commit_type("feat(cli): add stats command"), "feat");
+    assert_eq!(commit_type("✨ Fix!: drop old flag"), "fix");
+    assert_eq!(commit_type("Add a README"), "feat");
+    assert_eq!(commit_type("Fixed the crash on startup"), "fix");
+    assert_eq!(commit_type("Rename the config module"), "refactor");
+    assert_eq!(commit_type("Bump serde to 1.0.200"), "chore");
+    assert_eq!(commit_type("Initial import"), "other");
+}
+
+#[test]
+fn test_compute_stats() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    let repo = Repository::init(path).unwrap();
+
+    commit_files(&repo, path, "Ada", &[("README.md", "readme\n")], "Initial import");
+    commit_files(
+        &repo,
+        path,
+        "Ada",
+        &[("src/llm/client.rs", "a\nb\n"), ("src/main.rs", "main\n")],
+        "feat: add the client",
+    );
+    commit_files(&repo, path, "Grace", &[("src/llm/client.rs", "a\nc\n")], "fix: correct b");
+    commit_files(&repo, path, "Ada", &[("docs/guide.md", "guide\n")], "docs: add a guide");
+
+    let stats = compute_stats(path, None, "HEAD", DEFAULT_TOP).unwrap();
+    assert_eq!(stats.range, "HEAD");
+    assert_eq!(stats.commits, 4);
+    assert_eq!(stats.average_files, 5.0 / 4.0);
+    assert_eq!(stats.average_insertions, 6.0 / 4.0);
+    assert_eq!(stats.average_deletions, 1.0 / 4.0);
+
+    assert_eq!(stats.hot_files[0].path, "src/llm/client.rs");
+    assert_eq!(stats.hot_files[0].commits, 2);
+    assert_eq!(stats.hot_files[0].insertions, 3);
+    assert_eq!(stats.hot_files[0].deletions, 1);
+
+    assert_eq!(stats.churn_by_directory[0].directory, "src/llm");
+    assert_eq!(stats.churn_by_directory[0].commits, 2);
+
+    assert_eq!(stats.contributors[0].name, "Ada");
+    assert_eq!(stats.contributors[0].commits, 3);
+    assert_eq!(stats.contributors[1].email, "grace@example.com");
+
+    let types: Vec<(&str, usize)> = stats
+        .commit_types
+        .iter()
+        .map(|count| (count.commit_type.as_str(), count.commits))
+        .collect();
+    assert_eq!(types, vec![("docs", 1), ("feat", 1), ("fix", 1), ("other", 1)]);
+
+    // A range only counts the commits after its start
+    let stats = compute_stats(path, Some("HEAD~2"), "HEAD", 1).unwrap();
+    assert_eq!(stats.range, "HEAD~2..HEAD");
+    assert_eq!(stats.commits, 2);
+    assert_eq!(stats.hot_files.len(), 1);
+    assert_eq!(stats.contributors.len(), 1);
+
+    let report = format_stats_report(&stats);
+    assert!(report.starts_with("Repository stats for HEAD~2..HEAD\n"));
+    assert!(report.contains("Commits: 2"));
+    assert!(report.contains("\nHot files:\n"));
+    assert!(report.contains("\nCommit types:\n"));
+    assert!(report.contains("   50%  fix"));
+}
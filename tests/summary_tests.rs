@@ -0,0 +1,82 @@
+use chrono::{Duration, Local, TimeZone, Timelike};
+use git2::Repository;
+use git_iris::summary::{
+    create_summary_user_prompt, get_author_commits_since, group_by_area, parse_since,
+    repository_area,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn commit_as(repo: &Repository, dir: &Path, name: &str, author: &str, email: &str, message: &str) {
+    let path = dir.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, message).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(name)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now(author, email).unwrap();
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => vec![],
+    };
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parent_refs)
+        .unwrap();
+}
+
+#[test]
+fn test_parse_since() {
+    let now = Local.with_ymd_and_hms(2024, 5, 10, 15, 30, 0).unwrap();
+
+    let yesterday = parse_since("yesterday", now).unwrap();
+    assert_eq!(yesterday.date_naive(), now.date_naive() - Duration::days(1));
+    assert_eq!(yesterday.hour(), 0);
+
+    assert_eq!(parse_since("today", now).unwrap().date_naive(), now.date_naive());
+    assert_eq!(parse_since("1 week ago", now).unwrap(), now - Duration::weeks(1));
+    assert_eq!(parse_since("3 Days Ago", now).unwrap(), now - Duration::days(3));
+    assert_eq!(
+        parse_since("2024-05-01", now).unwrap().date_naive(),
+        chrono::NaiveDate::from_ymd_opt(2024, 5, 1).unwrap()
+    );
+    assert!(parse_since("last blue moon", now).is_err());
+}
+
+#[test]
+fn test_repository_area() {
+    assert_eq!(repository_area("README.md"), "(root)");
+    assert_eq!(repository_area("docs/usage.md"), "docs");
+    assert_eq!(repository_area("src/main.rs"), "src");
+    assert_eq!(repository_area("src/llm_providers/openai.rs"), "src/llm_providers");
+    assert_eq!(repository_area("packages/web/src/index.ts"), "packages/web");
+}
+
+#[test]
+fn test_author_commits_grouped_by_area() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+
+    commit_as(&repo, temp_dir.path(), "docs/a.md", "Jane", "jane@example.com", "Document setup");
+    commit_as(&repo, temp_dir.path(), "src/api/b.rs", "Bob", "bob@example.com", "Add endpoint");
+    commit_as(&repo, temp_dir.path(), "src/api/c.rs", "Jane", "jane@example.com", "Fix auth check");
+
+    let since = Local::now() - Duration::hours(1);
+    let changes = get_author_commits_since(temp_dir.path(), "JANE@example.com", since).unwrap();
+    assert_eq!(changes.len(), 2);
+
+    let groups = group_by_area(&changes);
+    assert_eq!(groups.keys().collect::<Vec<_>>(), vec!["docs", "src/api"]);
+
+    let prompt = create_summary_user_prompt(&changes, since);
+    assert!(prompt.contains("Area: src/api\n- Fix auth check"));
+    assert!(prompt.contains("Area: docs\n- Document setup"));
+    assert!(!prompt.contains("Add endpoint"));
+
+    // Commits older than the window are left out
+    let future = Local::now() + Duration::hours(1);
+    assert!(get_author_commits_since(temp_dir.path(), "Jane", future)
+        .unwrap()
+        .is_empty());
+}
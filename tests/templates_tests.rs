@@ -0,0 +1,95 @@
+use git_iris::config::set_config_path;
+use git_iris::context::{ChangeType, CommitContext, ProjectMetadata, StageStatus, StagedFile};
+use git_iris::language::Language;
+use git_iris::prompt::{create_system_prompt, create_user_prompt};
+use git_iris::templates::{render, template_path, COMMIT_SYSTEM, COMMIT_USER};
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_render() {
+    let variables = json!({
+        "branch": "main",
+        "use_gitmoji": false,
+        "instructions": "",
+        "files": [
+            {"path": "src/lib.rs", "analysis": ["Added function: run"]},
+            {"path": "README.md", "analysis": []},
+        ],
+    });
+
+    assert_eq!(render("On {{ branch }}", &variables).unwrap(), "On main");
+    assert_eq!(render("{{missing}}!", &variables).unwrap(), "!");
+    assert_eq!(
+        render("{{#if use_gitmoji}}emoji{{else}}plain{{/if}}", &variables).unwrap(),
+        "plain"
+    );
+    assert_eq!(
+        render("{{#unless instructions}}none{{/unless}}", &variables).unwrap(),
+        "none"
+    );
+    assert_eq!(
+        render(
+            "{{#each files}}{{@index}}: {{path}} [{{analysis}}] on {{branch}}\n{{/each}}",
+            &variables
+        )
+        .unwrap(),
+        "0: src/lib.rs [Added function: run] on main\n1: README.md [] on main\n"
+    );
+
+    assert!(render("{{#if branch}}open", &variables).is_err());
+    assert!(render("{{#each files}}{{/if}}", &variables).is_err());
+    assert!(render("{{/each}}", &variables).is_err());
+    assert!(render("{{branch", &variables).is_err());
+}
+
+#[test]
+fn test_template_overrides() {
+    let config_dir = TempDir::new().unwrap();
+    set_config_path(config_dir.path().join("config.toml"));
+
+    let context = CommitContext {
+        branch: "feature".to_string(),
+        recent_commits: vec![],
+        staged_files: vec![StagedFile {
+            path: "src/main.rs".to_string(),
+            change_type: ChangeType::Added,
+            diff: "+fn main() {}".to_string(),
+            analysis: vec![],
+            content_excluded: false,
+            stage_status: StageStatus::Staged,
+        }],
+        unstaged_files: vec![],
+        project_metadata: ProjectMetadata::default(),
+        workspace_impact: vec![],
+    };
+
+    // Without template files the built-in prompts are used
+    let built_in = create_user_prompt(&context).unwrap();
+    assert!(built_in.contains("Based on the following context"));
+
+    let path = template_path(COMMIT_USER).unwrap();
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(
+        &path,
+        "Describe {{branch}}:\n{{#each files}}- {{path}} ({{change_type}})\n{{diff}}\n{{/each}}",
+    )
+    .unwrap();
+    assert_eq!(
+        create_user_prompt(&context).unwrap(),
+        "Describe feature:\n- src/main.rs (Added)\n+fn main() {}\n"
+    );
+
+    fs::write(template_path(COMMIT_SYSTEM).unwrap(), "Be brief. {{instructions}}").unwrap();
+    assert_eq!(
+        create_system_prompt(false, "Use British spelling", Language::default()),
+        "Be brief. Use British spelling"
+    );
+
+    // A broken system template falls back to the built-in prompt; a broken user template fails
+    fs::write(template_path(COMMIT_SYSTEM).unwrap(), "{{#if use_gitmoji}}").unwrap();
+    assert!(create_system_prompt(false, "", Language::default()).contains("Git commit messages"));
+    fs::write(&path, "{{#each files}}").unwrap();
+    assert!(create_user_prompt(&context).is_err());
+}
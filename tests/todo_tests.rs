@@ -0,0 +1,110 @@
+use git_iris::context::{ChangeType, StageStatus, StagedFile};
+use git_iris::todo::{
+    append_todo_section, find_added_todos, find_added_todos_in_diff, format_todo_section, TodoKind,
+};
+
+const DIFF: &str = "\
+@@ -1,3 +1,6 @@
+ fn main() {
++    // TODO: handle errors
+     run();
+-    // FIXME: old comment
++    // FIXME(alice): remove this workaround
++    /* HACK retry twice */
++    let todo_list = Vec::new();
+ }
+@@ -20,2 +23,3 @@ fn run() {
+     setup();
++    // TODO
+ }
+";
+
+#[test]
+fn test_finds_added_todos_with_line_numbers() {
+    let items = find_added_todos_in_diff("src/main.rs", DIFF);
+
+    let found: Vec<(TodoKind, usize, &str)> = items
+        .iter()
+        .map(|item| (item.kind, item.line, item.text.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (TodoKind::Todo, 2, "handle errors"),
+            (TodoKind::Fixme, 4, "remove this workaround"),
+            (TodoKind::Hack, 5, "retry twice"),
+            (TodoKind::Todo, 24, ""),
+        ]
+    );
+    assert!(items.iter().all(|item| item.path == "src/main.rs"));
+}
+
+#[test]
+fn test_ignores_removed_and_context_lines() {
+    let diff = "\
+@@ -1,2 +1,2 @@
+ // TODO: already there
+-// FIXME: removed
++let x = 1;
+";
+    assert!(find_added_todos_in_diff("lib.rs", diff).is_empty());
+}
+
+#[test]
+fn test_handles_hunk_headers_printed_with_a_leading_space() {
+    let diff = " diff --git a/lib.rs b/lib.rs\n @@ -0,0 +1,2 @@\n+fn a() {}\n+// TODO: b\n";
+    let items = find_added_todos_in_diff("lib.rs", diff);
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].line, 2);
+}
+
+#[test]
+fn test_find_added_todos_across_files() {
+    let files = vec![
+        StagedFile {
+            path: "a.rs".to_string(),
+            change_type: ChangeType::Modified,
+            diff: "@@ -1 +1,2 @@\n x\n+// TODO: a\n".to_string(),
+            analysis: Vec::new(),
+            content_excluded: false,
+            stage_status: StageStatus::Staged,
+        },
+        StagedFile {
+            path: "b.py".to_string(),
+            change_type: ChangeType::Added,
+            diff: "@@ -0,0 +1 @@\n+# FIXME: b\n".to_string(),
+            analysis: Vec::new(),
+            content_excluded: false,
+            stage_status: StageStatus::Staged,
+        },
+    ];
+
+    let items = find_added_todos(&files);
+    let locations: Vec<String> = items
+        .iter()
+        .map(|item| format!("{}:{}", item.path, item.line))
+        .collect();
+    assert_eq!(locations, vec!["a.rs:2", "b.py:1"]);
+}
+
+#[test]
+fn test_format_and_append_todo_section() {
+    let items = find_added_todos_in_diff("src/main.rs", DIFF);
+
+    let section = format_todo_section(&items[..2]);
+    assert_eq!(
+        section,
+        "TODOs added:\n- src/main.rs:2 TODO: handle errors\n- src/main.rs:4 FIXME: remove this workaround"
+    );
+
+    let message = append_todo_section("Add main\n\nBody text\n", &items[..1]);
+    assert_eq!(
+        message,
+        "Add main\n\nBody text\n\nTODOs added:\n- src/main.rs:2 TODO: handle errors"
+    );
+    assert_eq!(append_todo_section("Add main", &[]), "Add main");
+
+    // A section kept by a refined message is replaced rather than repeated
+    let refined = append_todo_section(&message, &items[..1]);
+    assert_eq!(refined, message);
+}
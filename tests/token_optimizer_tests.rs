@@ -1,4 +1,6 @@
-use git_iris::context::{ChangeType, CommitContext, ProjectMetadata, RecentCommit, StagedFile};
+use git_iris::context::{
+    ChangeType, CommitContext, ProjectMetadata, RecentCommit, StageStatus, StagedFile,
+};
 use git_iris::token_optimizer::TokenOptimizer;
 
 fn create_test_context() -> CommitContext {
@@ -25,6 +27,7 @@ fn create_test_context() -> CommitContext {
                 diff: "- Old line\n+ New line".to_string(),
                 analysis: vec!["Modified function: test_function".to_string()],
                 content_excluded: false,
+                stage_status: StageStatus::Staged,
             },
             StagedFile {
                 path: "file2.rs".to_string(),
@@ -32,6 +35,7 @@ fn create_test_context() -> CommitContext {
                 diff: "+ New file content".to_string(),
                 analysis: vec!["Added new struct: TestStruct".to_string()],
                 content_excluded: false,
+                stage_status: StageStatus::Staged,
             },
         ],
         unstaged_files: vec!["unstaged1.rs".to_string(), "unstaged2.rs".to_string()],
@@ -44,6 +48,7 @@ fn create_test_context() -> CommitContext {
             test_framework: None,
             plugins: vec![],
         },
+        workspace_impact: vec![],
     }
 }
 
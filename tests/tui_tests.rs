@@ -0,0 +1,108 @@
+use console::Key;
+use git_iris::tui::{GenerationSettings, TuiAction, TuiCommit};
+use unicode_width::UnicodeWidthStr;
+
+fn sample_tui() -> TuiCommit {
+    let diff = "File: src/lib.rs\n@@ -1,2 +1,2 @@\n-fn old() {}\n+fn new() {}\n\tindented";
+    TuiCommit::new(
+        diff,
+        "Rename old to new\n\nThe old name was misleading.".to_string(),
+        GenerationSettings {
+            use_gitmoji: true,
+            preset: "default".to_string(),
+        },
+        vec!["default".to_string(), "conventional".to_string(), "concise".to_string()],
+        "git-iris".to_string(),
+        "1.0.0".to_string(),
+    )
+}
+
+#[test]
+fn test_tui_key_bindings() {
+    let mut tui = sample_tui();
+
+    assert_eq!(tui.handle_key(Key::Char('r')), TuiAction::Regenerate);
+    assert_eq!(tui.handle_key(Key::Char('e')), TuiAction::Edit);
+    assert_eq!(tui.handle_key(Key::Enter), TuiAction::Commit);
+    assert_eq!(tui.handle_key(Key::Char('q')), TuiAction::Quit);
+    assert_eq!(tui.handle_key(Key::ArrowDown), TuiAction::Redraw);
+
+    assert_eq!(tui.handle_key(Key::Char('g')), TuiAction::Regenerate);
+    assert!(!tui.settings().use_gitmoji);
+
+    // Presets cycle in alphabetical order
+    assert_eq!(tui.handle_key(Key::Char('p')), TuiAction::Regenerate);
+    assert_eq!(tui.settings().preset, "concise");
+    tui.handle_key(Key::Char('p'));
+    assert_eq!(tui.settings().preset, "conventional");
+    tui.handle_key(Key::Char('P'));
+    tui.handle_key(Key::Char('P'));
+    assert_eq!(tui.settings().preset, "default");
+}
+
+#[test]
+fn test_tui_render_frame() {
+    let tui = sample_tui();
+
+    for (width, height) in [(120, 30), (80, 24)] {
+        let frame = tui.render_frame(width, height);
+        assert_eq!(frame.len(), height);
+        for row in &frame {
+            assert_eq!(console::strip_ansi_codes(row).width(), width);
+        }
+
+        let text = console::strip_ansi_codes(&frame.join("\n")).to_string();
+        assert!(text.contains("Staged Changes (1/5)"));
+        assert!(text.contains("+fn new() {}"));
+        assert!(text.contains("Rename old to new"));
+        assert!(text.contains("gitmoji: on  preset: default"));
+        assert!(text.contains("Regenerate"));
+    }
+}
+
+#[test]
+fn test_tui_side_by_side_and_file_navigation() {
+    let diff = "File: src/lib.rs\n@@ -1,3 +1,3 @@\n fn keep() {}\n-fn old() {}\n+fn new() {}\n\
+                File: README.md\n@@ -1 +1 @@\n-Old title\n+New title";
+    let mut tui = TuiCommit::new(
+        diff,
+        "Rename old to new".to_string(),
+        GenerationSettings {
+            use_gitmoji: false,
+            preset: "default".to_string(),
+        },
+        vec!["default".to_string()],
+        "git-iris".to_string(),
+        "1.0.0".to_string(),
+    );
+    let frame_text = |tui: &TuiCommit| {
+        let frame = tui.render_frame(120, 30);
+        for row in &frame {
+            assert_eq!(console::strip_ansi_codes(row).width(), 120);
+        }
+        console::strip_ansi_codes(&frame.join("\n")).to_string()
+    };
+
+    let text = frame_text(&tui);
+    assert!(text.contains("Staged Changes (1/9) · file 1/2: src/lib.rs"));
+
+    assert_eq!(tui.handle_key(Key::Tab), TuiAction::Redraw);
+    let text = frame_text(&tui);
+    assert!(text.contains("Staged Changes (6/9) · file 2/2: README.md"));
+    assert!(!text.contains("fn new() {}"));
+
+    // Side by side, removed and added lines share a row, and the view stays in the same file
+    assert_eq!(tui.handle_key(Key::Char('s')), TuiAction::Redraw);
+    let text = frame_text(&tui);
+    assert!(text.contains("Staged Changes (5/7) · file 2/2: README.md"));
+    assert!(text
+        .lines()
+        .any(|line| line.contains("-Old title") && line.contains("│+New title")));
+
+    tui.handle_key(Key::BackTab);
+    let text = frame_text(&tui);
+    assert!(text.contains("file 1/2: src/lib.rs"));
+    assert!(text
+        .lines()
+        .any(|line| line.contains(" fn keep() {}") && line.contains("│ fn keep() {}")));
+}
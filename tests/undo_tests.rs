@@ -0,0 +1,105 @@
+use git2::{Oid, Repository};
+use git_iris::git;
+use git_iris::undo::{auto_commit_index_tree, record_auto_commit, undo_auto_commit};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn setup_repo() -> (TempDir, Repository) {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+    (temp_dir, repo)
+}
+
+fn commit_file(repo: &Repository, path: &str, content: &str, message: &str) -> Oid {
+    let root = repo.workdir().unwrap();
+    std::fs::write(root.join(path), content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(path)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = repo.signature().unwrap();
+    let parents: Vec<git2::Commit> = repo
+        .head()
+        .ok()
+        .and_then(|head| head.peel_to_commit().ok())
+        .into_iter()
+        .collect();
+    let parents: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .unwrap()
+}
+
+fn stage(repo: &Repository, path: &str, content: &str) {
+    std::fs::write(repo.workdir().unwrap().join(path), content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(path)).unwrap();
+    index.write().unwrap();
+}
+
+#[test]
+fn test_undo_auto_commit_restores_index() {
+    let (temp_dir, repo) = setup_repo();
+    commit_file(&repo, "a.txt", "one\n", "Initial commit");
+    commit_file(&repo, "b.txt", "one\n", "Add b");
+
+    // Only a.txt is staged; --all also commits the change to b.txt
+    stage(&repo, "a.txt", "two\n");
+    std::fs::write(temp_dir.path().join("b.txt"), "two\n").unwrap();
+    let index_tree = git::write_index_tree(temp_dir.path()).unwrap();
+    git::commit_all(temp_dir.path(), "Update a and b").unwrap();
+    record_auto_commit(temp_dir.path(), "HEAD", &index_tree).unwrap();
+    let parent = repo.head().unwrap().peel_to_commit().unwrap().parent_id(0).unwrap();
+    assert_eq!(
+        auto_commit_index_tree(temp_dir.path(), "HEAD").unwrap(),
+        Some(index_tree.clone())
+    );
+
+    let undone = undo_auto_commit(temp_dir.path(), false).unwrap();
+    assert_eq!(undone.summary, "Update a and b");
+    assert_eq!(repo.head().unwrap().peel_to_commit().unwrap().id(), parent);
+
+    let mut index = repo.index().unwrap();
+    index.read(true).unwrap();
+    assert_eq!(index.write_tree().unwrap().to_string(), index_tree);
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("b.txt")).unwrap(),
+        "two\n"
+    );
+}
+
+#[test]
+fn test_undo_refuses_other_commits() {
+    let (temp_dir, repo) = setup_repo();
+    commit_file(&repo, "a.txt", "one\n", "Initial commit");
+    commit_file(&repo, "a.txt", "two\n", "Written by hand");
+
+    let error = undo_auto_commit(temp_dir.path(), false).unwrap_err();
+    assert!(error.to_string().contains("was not created by"));
+}
+
+#[test]
+fn test_undo_refuses_staged_and_pushed_commits() {
+    let (temp_dir, repo) = setup_repo();
+    commit_file(&repo, "a.txt", "one\n", "Initial commit");
+    stage(&repo, "a.txt", "two\n");
+    let index_tree = git::write_index_tree(temp_dir.path()).unwrap();
+    git::commit(temp_dir.path(), "Update a").unwrap();
+    record_auto_commit(temp_dir.path(), "HEAD", &index_tree).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+
+    stage(&repo, "a.txt", "three\n");
+    let error = undo_auto_commit(temp_dir.path(), false).unwrap_err();
+    assert!(error.to_string().contains("staged after the commit"));
+
+    stage(&repo, "a.txt", "two\n");
+    repo.reference("refs/remotes/origin/main", head, true, "push").unwrap();
+    assert!(undo_auto_commit(temp_dir.path(), false).is_err());
+    assert_eq!(repo.head().unwrap().peel_to_commit().unwrap().id(), head);
+
+    undo_auto_commit(temp_dir.path(), true).unwrap();
+    assert_ne!(repo.head().unwrap().peel_to_commit().unwrap().id(), head);
+    assert_eq!(auto_commit_index_tree(temp_dir.path(), &head.to_string()).unwrap(), None);
+}
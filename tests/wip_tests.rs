@@ -0,0 +1,102 @@
+use git2::Repository;
+use git_iris::exclusions::PromptFilter;
+use git_iris::git::{commit, get_commit_message, get_working_tree_info, stage_all};
+use git_iris::wip::{create_wip_user_prompt, parse_wip_summary, WipSummary};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn setup_repo() -> (TempDir, Repository) {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Repository::init(temp_dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+
+    for name in ["staged.txt", "unstaged.txt"] {
+        fs::write(temp_dir.path().join(name), "original\n").unwrap();
+    }
+    {
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.add_path(Path::new("unstaged.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+            .unwrap();
+    }
+
+    // One staged change, one unstaged change, and one untracked file
+    fs::write(temp_dir.path().join("staged.txt"), "staged change\n").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("staged.txt")).unwrap();
+    index.write().unwrap();
+    fs::write(temp_dir.path().join("unstaged.txt"), "unstaged change\n").unwrap();
+    fs::write(temp_dir.path().join("untracked.txt"), "new file\n").unwrap();
+
+    (temp_dir, repo)
+}
+
+#[test]
+fn test_wip_prompt_covers_all_uncommitted_changes() {
+    let (temp_dir, _repo) = setup_repo();
+
+    let context = get_working_tree_info(temp_dir.path(), true, &PromptFilter::default()).unwrap();
+    let prompt = create_wip_user_prompt(&context);
+    assert!(prompt.contains("- staged.txt (Modified)"));
+    assert!(prompt.contains("- unstaged.txt (Modified)"));
+    assert!(prompt.contains("- untracked.txt (Added)"));
+    assert!(prompt.contains("+new file"));
+}
+
+#[test]
+fn test_stage_all_commits_untracked_files() {
+    let (temp_dir, _repo) = setup_repo();
+
+    stage_all(temp_dir.path(), true).unwrap();
+    commit(temp_dir.path(), "wip: checkpoint").unwrap();
+
+    assert!(get_working_tree_info(temp_dir.path(), true, &PromptFilter::default())
+        .unwrap()
+        .staged_files
+        .is_empty());
+    assert_eq!(
+        get_commit_message(temp_dir.path(), "HEAD").unwrap(),
+        "wip: checkpoint"
+    );
+}
+
+#[test]
+fn test_parse_wip_summary() {
+    let summary = parse_wip_summary(
+        "WIP: Retry logic for flaky uploads.\n\nAdded a retry loop to the uploader.\nTests are still missing.",
+    );
+    assert_eq!(summary.subject, "Retry logic for flaky uploads");
+    assert_eq!(
+        summary.narrative,
+        "Added a retry loop to the uploader.\nTests are still missing."
+    );
+
+    // Words that merely start with "wip" are kept
+    assert_eq!(parse_wip_summary("wipe stale caches").subject, "wipe stale caches");
+    assert!(parse_wip_summary("").subject.is_empty());
+}
+
+#[test]
+fn test_wip_commit_message() {
+    let summary = WipSummary {
+        subject: "Retry logic for uploads".to_string(),
+        narrative: "Tests are still missing.".to_string(),
+    };
+    assert_eq!(
+        summary.to_commit_message(),
+        "wip: Retry logic for uploads\n\nTests are still missing."
+    );
+
+    let summary = WipSummary {
+        subject: "Retry logic for uploads".to_string(),
+        narrative: String::new(),
+    };
+    assert_eq!(summary.to_commit_message(), "wip: Retry logic for uploads");
+}
@@ -0,0 +1,95 @@
+use git_iris::workspace::{format_workspace_impact, WorkspaceGraph, WorkspaceImpact};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn write_crate(root: &Path, dir: &str, name: &str, deps: &[&str]) {
+    let crate_dir = root.join(dir);
+    fs::create_dir_all(&crate_dir).unwrap();
+    let mut manifest = format!(
+        "[package]\nname = \"{}\"\nversion = \"0.1.0\"\n\n[dependencies]\n",
+        name
+    );
+    for dep in deps {
+        manifest.push_str(&format!("{} = {{ path = \"../{}\" }}\n", dep, dep));
+    }
+    manifest.push_str("serde = \"1.0\"\n");
+    fs::write(crate_dir.join("Cargo.toml"), manifest).unwrap();
+}
+
+#[test]
+fn test_cargo_workspace_impact() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/*\", \"tools/cli\"]\n",
+    )
+    .unwrap();
+    write_crate(root, "crates/core", "core", &[]);
+    write_crate(root, "crates/db", "db", &["core"]);
+    write_crate(root, "crates/api", "api", &["db"]);
+    write_crate(root, "tools/cli", "cli", &["api"]);
+
+    let graph = WorkspaceGraph::load(root).unwrap();
+    assert_eq!(graph.members.len(), 4);
+    assert_eq!(graph.member_for_path("crates/db/src/lib.rs").unwrap().name, "db");
+    assert!(graph.member_for_path("README.md").is_none());
+
+    let impact = graph.impact_of(["crates/core/src/lib.rs", "tools/cli/src/main.rs"]);
+    assert_eq!(
+        impact,
+        vec![
+            WorkspaceImpact {
+                member: "cli".to_string(),
+                dependents: vec![],
+            },
+            WorkspaceImpact {
+                member: "core".to_string(),
+                dependents: vec!["api".to_string(), "cli".to_string(), "db".to_string()],
+            },
+        ]
+    );
+
+    let formatted = format_workspace_impact(&impact);
+    assert!(formatted.contains("core -> affects api, cli, db"));
+    assert!(formatted.contains("cli (no dependent workspace members)"));
+}
+
+#[test]
+fn test_npm_workspace_impact() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(
+        root.join("package.json"),
+        r#"{ "name": "monorepo", "workspaces": ["packages/*"] }"#,
+    )
+    .unwrap();
+    for (dir, package) in [
+        ("packages/utils", r#"{ "name": "@acme/utils" }"#),
+        (
+            "packages/web",
+            r#"{ "name": "@acme/web", "dependencies": { "@acme/utils": "*", "react": "^18.0.0" } }"#,
+        ),
+    ] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+        fs::write(root.join(dir).join("package.json"), package).unwrap();
+    }
+
+    let graph = WorkspaceGraph::load(root).unwrap();
+    let impact = graph.impact_of(["packages/utils/index.js"]);
+    assert_eq!(impact.len(), 1);
+    assert_eq!(impact[0].member, "@acme/utils");
+    assert_eq!(impact[0].dependents, vec!["@acme/web".to_string()]);
+}
+
+#[test]
+fn test_no_workspace() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Cargo.toml"),
+        "[package]\nname = \"single\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+    assert!(WorkspaceGraph::load(temp_dir.path()).is_none());
+}